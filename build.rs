@@ -5,8 +5,14 @@ fn warn(msg: &str) {
     );
 }
 
+// The app's preset editor uses more of this than the build does
+#[allow(dead_code)]
+#[path = "src/sim/preset_schema.rs"]
+mod preset_schema;
+
 fn main() {
     row_descs::check();
+    declared_ids::build();
     export_keplerian_sim_version();
     presets::build();
 }
@@ -349,6 +355,97 @@ mod row_descs {
     }
 }
 
+mod declared_ids {
+    use std::{
+        env, fs,
+        path::{Path, PathBuf},
+    };
+
+    const SOURCE_PATH: &str = "src";
+    const OUTPUT_NAME: &str = "declared_ids.rs";
+    /// Written by [`super::presets`], and never declares IDs.
+    const GENERATED_PRESETS_PATH: &str = "src/sim/presets.rs";
+
+    /// Lists every `declare_id!` in the source, for `gui::id_registry`
+    /// to check for collisions at runtime.
+    pub(super) fn build() {
+        let mut files = Vec::new();
+        collect_files(Path::new(SOURCE_PATH), &mut files);
+        files.sort();
+
+        let mut entries = String::new();
+        for path in &files {
+            // Each file is watched rather than the whole directory, which the
+            // generated presets would otherwise mark as changed on every build.
+            // New files only come in through a `mod` in a watched one
+            println!("cargo:rerun-if-changed={}", path.display());
+            let Ok(text) = fs::read_to_string(path) else {
+                continue;
+            };
+            let file = path.to_string_lossy().replace('\\', "/");
+            for (index, line) in text.lines().enumerate() {
+                if let Some(entry) = parse_declaration(line, &file, index + 1) {
+                    entries.push_str(&entry);
+                }
+            }
+        }
+
+        let out_dir = env::var("OUT_DIR").expect("OUT_DIR should be set for build scripts");
+        let output = format!("&[\n{entries}]\n");
+        fs::write(Path::new(&out_dir).join(OUTPUT_NAME), output)
+            .expect("failed to write the declared id list");
+    }
+
+    fn collect_files(dir: &Path, files: &mut Vec<PathBuf>) {
+        let Ok(entries) = fs::read_dir(dir) else {
+            return;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                collect_files(&path, files);
+            } else if path.extension().and_then(|s| s.to_str()) == Some("rs")
+                && path != Path::new(GENERATED_PRESETS_PATH)
+            {
+                files.push(path);
+            }
+        }
+    }
+
+    /// Turns a `declare_id!(...)` line into an entry of the generated list.
+    fn parse_declaration(line: &str, file: &str, line_num: usize) -> Option<String> {
+        let args = line.trim().strip_prefix("declare_id!(")?;
+        let args = args.strip_suffix(");")?;
+        let parts: Vec<&str> = args.split(',').map(str::trim).collect();
+        let (salt_only, name, value) = match parts.as_slice() {
+            ["salt_only", name, value] => (true, *name, *value),
+            [name, value] => (false, *name, *value),
+            _ => return None,
+        };
+        if !value.starts_with("b\"") {
+            return None;
+        }
+
+        Some(format!(
+            "    DeclaredId {{ name: {name:?}, module: {module:?}, salt_only: {salt_only}, \
+            bytes: *{value}, file: {file:?}, line: {line_num} }},\n",
+            module = module_path(file),
+        ))
+    }
+
+    /// The module a source file defines, like `gui::celestials` for
+    /// `src/gui/celestials/mod.rs`.
+    fn module_path(file: &str) -> String {
+        let path = file.strip_prefix("src/").unwrap_or(file);
+        let path = path.strip_suffix(".rs").unwrap_or(path);
+        let path = path.strip_suffix("/mod").unwrap_or(path);
+        match path {
+            "main" | "lib" => "crate".to_owned(),
+            path => path.replace('/', "::"),
+        }
+    }
+}
+
 fn export_keplerian_sim_version() {
     // Attempt to read resolved dependency versions via cargo metadata and
     // publish the keplerian_sim version as an environment variable so it can
@@ -375,35 +472,14 @@ fn export_keplerian_sim_version() {
 }
 
 mod presets {
-    use std::{
-        borrow::Cow,
-        fmt::Display,
-        fs::{self, File},
-        io::Write,
-    };
+    use std::{fs, io::Write};
 
-    use toml::{
-        Spanned,
-        de::{DeArray, DeInteger, DeTable, DeValue},
-    };
+    use crate::preset_schema::{self, PresetEntry};
 
     const PRESETS_TOML_PATH: &str = "src/sim/presets.toml";
+    const PRESET_SCHEMA_PATH: &str = "src/sim/preset_schema.rs";
     const OUTPUT_PATH: &str = "src/sim/presets.rs";
 
-    const KEY_NAME: &str = "name";
-    const KEY_DOCNAME: &str = "docname";
-    const KEY_DESC: &str = "description";
-    const KEY_MASS: &str = "mass";
-    const KEY_RADIUS: &str = "radius";
-    const KEY_APOAPSIS: &str = "apoapsis";
-    const KEY_ECCENTRICITY: &str = "eccentricity";
-    const KEY_PERIAPSIS: &str = "periapsis";
-    const KEY_INCLINATION: &str = "inclination";
-    const KEY_ARG_PE: &str = "arg_pe";
-    const KEY_LONG_ASC_NODE: &str = "long_asc_node";
-    const KEY_MEAN_ANOMALY: &str = "mean_anomaly";
-    const KEY_COLOR: &str = "color";
-
     struct BodyCreator<'a> {
         fn_name: &'a str,
         name: &'a str,
@@ -422,106 +498,100 @@ mod presets {
 
     pub(super) fn build() {
         println!("cargo:rerun-if-changed={PRESETS_TOML_PATH}");
-        let mut output_file =
-            File::create(OUTPUT_PATH).expect("failed to initialize output file writer");
-        print_header(&mut output_file);
+        println!("cargo:rerun-if-changed={PRESET_SCHEMA_PATH}");
+        let mut output = Vec::new();
+        print_header(&mut output);
 
         let presets_string =
             fs::read_to_string(PRESETS_TOML_PATH).expect("failed to read from presets file");
-        let table = DeTable::parse(&presets_string).expect("failed to parse presets file as table");
-        for (fn_name, value) in table.get_ref() {
-            process_entry(fn_name, value, &mut output_file);
+        let catalog = match preset_schema::parse_catalog(&presets_string) {
+            Ok(c) => c,
+            Err(e) => panic!("preset builder: {e}"),
+        };
+
+        let mut epochs = Vec::new();
+        for entry in &catalog.entries {
+            if let Some(epoch) = process_entry(entry, catalog.epoch, &mut output) {
+                epochs.push(epoch);
+            }
+        }
+
+        print_epochs(&mut output, &epochs);
+
+        // Left alone when nothing changed, so the file's timestamp
+        // doesn't make cargo rebuild the crate every time
+        if fs::read(OUTPUT_PATH).ok().as_deref() != Some(output.as_slice()) {
+            fs::write(OUTPUT_PATH, output).expect("failed to write to output file");
         }
     }
 
-    fn print_header(file: &mut File) {
+    fn print_header(file: &mut Vec<u8>) {
         file.write_all(
             b"//! Generated by build.rs::presets\n\
             #![allow(clippy::excessive_precision)]\n\
-            use crate::sim::body::Body;\n\
+            use crate::sim::{body::Body, epoch::PresetEpoch};\n\
             use keplerian_sim::Orbit;\n\
             use three_d::Srgba;\n",
         )
         .expect("failed to write to output file");
     }
 
+    /// Writes the entry's function and returns its
+    /// name, epoch and mean anomaly, if it has an epoch.
     fn process_entry(
-        fn_name: &Spanned<Cow<'_, str>>,
-        value: &Spanned<DeValue<'_>>,
-        file: &mut File,
-    ) {
-        let fn_name = fn_name.get_ref();
-        let value = value.get_ref();
-        let map = match value {
-            DeValue::Table(map) => map,
-            v => {
-                panic!("preset builder: parsing {fn_name}: expected table, found {v:?}");
-            }
-        };
-
-        // let Some(name) = table.get(KEY_NAME).and_then(|v| v.get_ref().as_str()) else {
-        //     panic!("preset builder: {fn_name} does not contain {KEY_NAME} of type string");
-        // };
-        let name = get_str_required(map, fn_name, KEY_NAME);
-        let docname = get_str_optional(map, fn_name, KEY_DOCNAME).unwrap_or(name);
-        let desc = get_str_optional(map, fn_name, KEY_DESC);
-        let mass = get_float_required(map, fn_name, KEY_MASS);
-        let radius = get_float_required(map, fn_name, KEY_RADIUS);
-
-        let apoapsis = get_float_optional(map, fn_name, KEY_APOAPSIS);
-        let eccentricity = get_float_optional(map, fn_name, KEY_ECCENTRICITY);
-
-        enum EccentricityDefiner {
-            Eccentricity(f64),
-            Apoapsis(f64),
-        }
+        entry: &PresetEntry,
+        default_epoch: Option<f64>,
+        file: &mut Vec<u8>,
+    ) -> Option<(String, f64, f64)> {
+        let mean_anomaly = entry.mean_anomaly.to_radians();
 
-        let ecc_definer = match (apoapsis, eccentricity) {
-            (None, None) => panic!(
-                "preset builder: {fn_name}: missing either apoapsis or eccentricity (pick one and define)"
-            ),
-            (None, Some(e)) => EccentricityDefiner::Eccentricity(e),
-            (Some(ap), None) => EccentricityDefiner::Apoapsis(ap),
-            (Some(_), Some(_)) => panic!(
-                "preset builder: {fn_name}: defining both apoapsis and eccentricity is disallowed"
-            ),
+        let creator = BodyCreator {
+            fn_name: &entry.key,
+            name: &entry.name,
+            docname: entry.docname.as_deref().unwrap_or(&entry.name),
+            desc: entry.description.as_deref(),
+            mass: entry.mass,
+            radius: entry.radius,
+            eccentricity: entry.eccentricity(),
+            periapsis: entry.periapsis,
+            inclination: entry.inclination.to_radians(),
+            arg_pe: entry.arg_pe.to_radians(),
+            long_asc_node: entry.long_asc_node.to_radians(),
+            mean_anomaly,
+            color: entry.color,
         };
 
-        let periapsis = get_float_required(map, fn_name, KEY_PERIAPSIS);
+        let code = meta_create_body(&creator);
 
-        let eccentricity = match ecc_definer {
-            EccentricityDefiner::Eccentricity(e) => e,
-            EccentricityDefiner::Apoapsis(apoapsis) => {
-                (apoapsis - periapsis) / (apoapsis + periapsis)
-            }
-        };
+        file.write_all(&code.as_bytes())
+            .expect("failed to write to output file");
 
-        let inclination = get_float_required(map, fn_name, KEY_INCLINATION).to_radians();
-        let arg_pe = get_float_required(map, fn_name, KEY_ARG_PE).to_radians();
-        let long_asc_node = get_float_required(map, fn_name, KEY_LONG_ASC_NODE).to_radians();
-        let mean_anomaly = get_float_required(map, fn_name, KEY_MEAN_ANOMALY).to_radians();
+        let epoch = entry.epoch.or(default_epoch);
+        epoch.map(|epoch| (entry.name.clone(), epoch, mean_anomaly))
+    }
 
-        let color = get_srgb_required(map, fn_name, KEY_COLOR);
+    fn print_epochs(file: &mut Vec<u8>, epochs: &[(String, f64, f64)]) {
+        let mut code = String::from(
+            "
 
-        let creator = BodyCreator {
-            fn_name,
-            name,
-            docname,
-            desc,
-            mass,
-            radius,
-            eccentricity,
-            periapsis,
-            inclination,
-            arg_pe,
-            long_asc_node,
-            mean_anomaly,
-            color,
-        };
+/// The epochs the preset bodies' mean anomalies are measured at.
+pub(crate) const PRESET_EPOCHS: &[PresetEpoch] = &[",
+        );
+
+        for (name, epoch, mean_anomaly) in epochs {
+            code.push_str(&format!(
+                "
+    PresetEpoch {{
+        name: \"{name}\",
+        julian_date: {epoch:.20e},
+        mean_anomaly: {mean_anomaly:.20e},
+    }},"
+            ));
+        }
 
-        let code = meta_create_body(&creator);
+        code.push_str("\n];\n");
 
-        file.write_all(&code.as_bytes())
+        file.write_all(code.as_bytes())
             .expect("failed to write to output file");
     }
 
@@ -576,152 +646,4 @@ pub(crate) fn {fn_name}(parent_mu: Option<f64>) -> Body {{
 }}"
         )
     }
-
-    fn expect_exists<'a>(
-        map: &'a DeTable,
-        fn_name: (impl AsRef<str> + Display),
-        key_name: &str,
-    ) -> &'a Spanned<DeValue<'a>> {
-        let Some(val) = map.get(key_name) else {
-            panic!("preset builder: {fn_name}: missing required field {key_name}");
-        };
-        val
-    }
-
-    fn get_str_required<'a>(
-        map: &'a DeTable,
-        fn_name: (impl AsRef<str> + Display),
-        key_name: &str,
-    ) -> &'a str {
-        expect_str(
-            expect_exists(map, fn_name.as_ref(), key_name),
-            fn_name,
-            key_name,
-        )
-    }
-
-    fn get_str_optional<'a>(
-        map: &'a DeTable,
-        fn_name: (impl AsRef<str> + Display),
-        key_name: &str,
-    ) -> Option<&'a str> {
-        let val = map.get(key_name)?;
-        Some(expect_str(val, fn_name, key_name))
-    }
-
-    fn expect_str<'a>(
-        val: &'a Spanned<DeValue<'a>>,
-        fn_name: (impl AsRef<str> + Display),
-        key_name: &str,
-    ) -> &'a str {
-        let Some(val) = val.get_ref().as_str() else {
-            panic!("preset builder: {fn_name}: expected field {key_name} to be string");
-        };
-        val
-    }
-
-    fn get_float_required(
-        map: &DeTable,
-        fn_name: (impl AsRef<str> + Display),
-        key_name: &str,
-    ) -> f64 {
-        expect_float(expect_exists(map, &fn_name, key_name), fn_name, key_name)
-    }
-
-    fn get_float_optional(
-        map: &DeTable,
-        fn_name: (impl AsRef<str> + Display),
-        key_name: &str,
-    ) -> Option<f64> {
-        let val = map.get(key_name)?;
-        Some(expect_float(val, fn_name, key_name))
-    }
-
-    fn expect_float(
-        val: &Spanned<DeValue<'_>>,
-        fn_name: (impl AsRef<str> + Display),
-        key_name: &str,
-    ) -> f64 {
-        let Some(val) = val.get_ref().as_float() else {
-            panic!("preset builder: {fn_name}: expected field {key_name} to be float");
-        };
-        let res = match val.as_str().parse() {
-            Ok(f) => f,
-            Err(e) => {
-                panic!("preset builder: {fn_name}: failed to parse field {key_name} as float: {e}")
-            }
-        };
-        res
-    }
-
-    fn get_srgb_required(
-        map: &DeTable,
-        fn_name: (impl AsRef<str> + Display),
-        key_name: &str,
-    ) -> [u8; 4] {
-        expect_srgb(expect_exists(map, &fn_name, key_name), fn_name, key_name)
-    }
-
-    fn expect_srgb(
-        val: &Spanned<DeValue<'_>>,
-        fn_name: (impl AsRef<str> + Display),
-        key_name: &str,
-    ) -> [u8; 4] {
-        match val.get_ref() {
-            DeValue::Integer(de_integer) => srgb_from_int(de_integer, fn_name, key_name),
-            DeValue::Array(de_array) => srgb_from_array(de_array, fn_name, key_name),
-            _ => {
-                panic!(
-                    "preset builder: {fn_name}: expected field {key_name} \
-                    to be either a 3-element integer array or an integer"
-                );
-            }
-        }
-    }
-
-    fn srgb_from_int(
-        val: &DeInteger<'_>,
-        fn_name: (impl AsRef<str> + Display),
-        key_name: &str,
-    ) -> [u8; 4] {
-        let val = match u32::from_str_radix(val.as_str(), val.radix()) {
-            Ok(v) => v,
-            Err(e) => {
-                panic!(
-                    "preset builder: {fn_name}: expected integer {key_name} to fit in 32 bits, got error {e}"
-                );
-            }
-        };
-
-        val.to_be_bytes()
-    }
-
-    fn srgb_from_array(
-        val: &DeArray<'_>,
-        fn_name: (impl AsRef<str> + Display),
-        key_name: &str,
-    ) -> [u8; 4] {
-        if val.len() != 3 && val.len() != 4 {
-            panic!(
-                "preset builder: {fn_name}: expected array {key_name} to have 3–4 elements, got {}",
-                val.len()
-            );
-        }
-
-        core::array::from_fn(|i| {
-            let val = match val.get(i).and_then(|s| s.get_ref().as_integer()) {
-                Some(v) => v,
-                None if i == 3 => return 255,
-                None => panic!("preset builder: {fn_name}: expected {key_name}[{i}] to be u8"),
-            };
-
-            let val = match u8::from_str_radix(val.as_str(), val.radix()) {
-                Ok(v) => v,
-                Err(e) => panic!(
-                    "preset builder: {fn_name}: expected {key_name}[{i}] to fit in u8, got error {e}"
-                ),
-            };
-            val
-        })
-    }
 }
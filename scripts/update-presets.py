@@ -68,7 +68,12 @@ BODY_MAP: dict[str, tuple[str, str] | None] = {
     "enceladus": ("602", "699"),
     "mimas": ("601", "699"),
     "tethys": ("603", "699"),
+    "dione": ("604", "699"),
+    "rhea": ("605", "699"),
     "iapetus": ("608", "699"),
+    "miranda": ("705", "799"),
+    "ariel": ("701", "799"),
+    "umbriel": ("702", "799"),
     "titania": ("703", "799"),
     "oberon": ("704", "799"),
     "triton": ("801", "899"),
@@ -213,6 +218,8 @@ if __name__ == "__main__":
                         f"Error occurred fetching API for data about {current_body}",
                         exception
                     )
+            if current_body is None and line.startswith("epoch"):
+                modified_lines[line_num] = f"epoch = {TDB_TIMESTAMP}"
             if current_elements is None:
                 continue
             new_line: str | None = None
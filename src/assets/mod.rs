@@ -1,3 +1,5 @@
+pub(crate) mod preload;
+
 /// Arguments:
 /// 1. The name of the `const LazyLock<Image<'static>>` to be available as.
 ///    That name, combined with `_SOURCE`, yields the `ImageSource<'static>` that you can use.
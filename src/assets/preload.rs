@@ -0,0 +1,140 @@
+//! Getting assets ready behind a splash screen, so the first frame
+//! of the simulator doesn't stall building them.
+//!
+//! Meshes are built as a compute job, on their own thread on native builds.
+//! Fonts and icons are loaded into the GUI's context on the main thread,
+//! one step per frame so the splash screen keeps drawing in between.
+
+use std::sync::LazyLock;
+
+use three_d::egui::{Context as EguiContext, Image, ImageSource, Vec2};
+
+use crate::{
+    assets,
+    compute::{self, Job, Task, TaskStatus},
+    gfx::{arrow::ARROW_MESH, object_conversion::SPHERE_MESHES},
+    gui,
+};
+
+/// The size the bottom bar draws its icons at.
+const ICON_SIZE: Vec2 = Vec2::splat(48.0);
+
+/// Something to load on the main thread.
+struct MainThreadStep {
+    /// Shown on the splash screen while it loads.
+    name: &'static str,
+    run: fn(&EguiContext),
+}
+
+const MAIN_THREAD_STEPS: &[MainThreadStep] = &[
+    MainThreadStep {
+        name: "Loading fonts",
+        run: gui::add_fonts,
+    },
+    MainThreadStep {
+        name: "Loading icons",
+        run: load_icons,
+    },
+];
+
+fn load_icons(ctx: &EguiContext) {
+    let sources: [ImageSource<'static>; 9] = [
+        assets::PAUSED_IMAGE_SOURCE,
+        assets::PLAY_IMAGE_SOURCE,
+        assets::TIME_IMAGE_SOURCE,
+        assets::ELLIPSIS_IMAGE_SOURCE,
+        assets::TREE_LIST_IMAGE_SOURCE,
+        assets::ADD_ORBIT_IMAGE_SOURCE,
+        assets::EDIT_ORBIT_IMAGE_SOURCE,
+        assets::OPTIONS_SOURCE,
+        assets::BANNER_SOURCE,
+    ];
+
+    for source in sources {
+        // Tessellates the SVG into the loader's cache
+        let _ = Image::new(source).load_for_size(ctx, ICON_SIZE);
+    }
+}
+
+/// Builds the shared meshes, one per step.
+struct MeshJob {
+    built: usize,
+}
+
+impl Job for MeshJob {
+    type Output = ();
+
+    fn step(&mut self) -> Option<()> {
+        match self.built {
+            0 => {
+                LazyLock::force(&SPHERE_MESHES);
+            }
+            1 => {
+                LazyLock::force(&ARROW_MESH);
+            }
+            _ => return Some(()),
+        }
+        self.built += 1;
+        None
+    }
+}
+
+pub(crate) struct Preloader {
+    meshes: Option<Task<()>>,
+    /// How many of [`MAIN_THREAD_STEPS`] are done.
+    steps_done: usize,
+}
+
+impl Preloader {
+    /// Starts building the meshes.
+    pub(crate) fn start() -> Self {
+        Self {
+            meshes: Some(compute::spawn(MeshJob { built: 0 })),
+            steps_done: 0,
+        }
+    }
+
+    /// Does the next main-thread step, and checks on the meshes.
+    ///
+    /// Returns: Whether everything is loaded.
+    pub(crate) fn step(&mut self, ctx: &EguiContext) -> bool {
+        if let Some(step) = MAIN_THREAD_STEPS.get(self.steps_done) {
+            (step.run)(ctx);
+            self.steps_done += 1;
+        }
+
+        if let Some(meshes) = &self.meshes {
+            match meshes.poll() {
+                TaskStatus::Running => (),
+                TaskStatus::Done(()) => self.meshes = None,
+                TaskStatus::Failed => {
+                    // They're built on first use instead
+                    log::warn!("Couldn't build the meshes ahead of time");
+                    self.meshes = None;
+                }
+            }
+        }
+
+        self.is_done()
+    }
+
+    fn is_done(&self) -> bool {
+        self.steps_done >= MAIN_THREAD_STEPS.len() && self.meshes.is_none()
+    }
+
+    /// How much has been loaded, from 0 to 1.
+    pub(crate) fn progress(&self) -> f32 {
+        let total = MAIN_THREAD_STEPS.len() + 1;
+        let done = self.steps_done + usize::from(self.meshes.is_none());
+        done as f32 / total as f32
+    }
+
+    /// What's being loaded right now.
+    pub(crate) fn current_step(&self) -> &'static str {
+        match MAIN_THREAD_STEPS.get(self.steps_done) {
+            Some(step) => step.name,
+            None if !self.is_done() => "Building meshes",
+            None => "Done",
+        }
+    }
+}
@@ -0,0 +1,293 @@
+//! A benchmark that plays the same heavy scene with the same camera
+//! movements for a set number of frames, then reports how long they took,
+//! so devices and versions can be compared with concrete numbers.
+//!
+//! Started with `--benchmark` on the command line, `?benchmark` in the
+//! page's URL on the web, or from the options menu while holding Shift.
+
+use core::f64::consts::TAU;
+#[cfg(not(target_family = "wasm"))]
+use std::{sync::LazyLock, time::Instant};
+
+use three_d::{Camera, InnerSpace, Vec3};
+
+use crate::{
+    control::CameraControl,
+    sim::{
+        generator::{self, GeneratorSettings, MAX_PLANET_COUNT},
+        universe::Universe,
+    },
+};
+
+/// How many frames are drawn before measuring starts,
+/// so compiling shaders and filling caches doesn't count.
+const WARM_UP_FRAMES: usize = 60;
+
+/// How many frames are measured.
+const MEASURED_FRAMES: usize = 1200;
+
+/// How much time each frame is simulated for, in milliseconds,
+/// so every run simulates the same thing whatever its frame rate.
+pub(crate) const FRAME_STEP_MS: f64 = 1000.0 / 60.0;
+
+/// How fast the scene is simulated: two days per second.
+pub(crate) const SIM_SPEED: f64 = 2.0 * 86_400.0;
+
+/// The seed of the generated scene. Changing it makes old reports incomparable.
+const SCENE_SEED: u64 = u64::from_be_bytes(*b"benchmrk");
+
+/// How close and how far the scripted camera gets to the star, in meters.
+const NEAR_DISTANCE: f64 = 2e10;
+const FAR_DISTANCE: f64 = 2e12;
+
+/// How far the scripted camera tilts up and down, in radians.
+const PITCH_AMPLITUDE: f64 = 0.6;
+
+/// The parts of a frame that are timed separately.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Phase {
+    Simulation,
+    Interface,
+    Rendering,
+}
+
+impl Phase {
+    pub(crate) const ALL: [Phase; 3] = [Phase::Simulation, Phase::Interface, Phase::Rendering];
+
+    const fn name(self) -> &'static str {
+        match self {
+            Phase::Simulation => "Simulation",
+            Phase::Interface => "Interface",
+            Phase::Rendering => "Rendering",
+        }
+    }
+}
+
+/// What the benchmark ran on, listed at the top of the report.
+pub(crate) struct Setup {
+    pub viewport: (u32, u32),
+    pub pixel_ratio: f32,
+    pub orbit_lines: &'static str,
+    pub body_count: usize,
+}
+
+pub(crate) struct Benchmark {
+    /// The index of the tab the benchmark runs in.
+    pub session: usize,
+    /// Whether to quit once done, after printing the report.
+    pub exit_when_done: bool,
+    /// How many frames have been drawn, including the warm-up.
+    frame: usize,
+    /// The measured frames' times, in milliseconds.
+    frame_times: Vec<f64>,
+    /// The measured frames' time spent in each phase, in milliseconds.
+    phase_times: [Vec<f64>; Phase::ALL.len()],
+}
+
+impl Benchmark {
+    pub(crate) fn new(session: usize, exit_when_done: bool) -> Self {
+        Self {
+            session,
+            exit_when_done,
+            frame: 0,
+            frame_times: Vec::with_capacity(MEASURED_FRAMES),
+            phase_times: std::array::from_fn(|_| Vec::with_capacity(MEASURED_FRAMES)),
+        }
+    }
+
+    /// How far through its script the benchmark is, from 0 to 1.
+    fn progress(&self) -> f64 {
+        self.frame as f64 / (WARM_UP_FRAMES + MEASURED_FRAMES) as f64
+    }
+
+    /// Moves the camera to where the script has it this frame:
+    /// once around the star, tilting up and down, while zooming
+    /// in close and back out.
+    pub(crate) fn drive_camera(&self, control: &mut CameraControl, camera: &mut Camera) {
+        let progress = self.progress();
+        let yaw = TAU / (WARM_UP_FRAMES + MEASURED_FRAMES) as f64;
+        let pitch = PITCH_AMPLITUDE * TAU * (progress * TAU).cos()
+            / (WARM_UP_FRAMES + MEASURED_FRAMES) as f64;
+        camera.rotate_around_with_fixed_up(Vec3::new(0.0, 0.0, 0.0), yaw as f32, pitch as f32);
+        let pos = camera.position().normalize();
+        let up = camera.up();
+        camera.set_view(pos, Vec3::new(0.0, 0.0, 0.0), up);
+
+        control.zoom_to_distance(scripted_distance(progress));
+    }
+
+    /// Records a frame's time and the time spent in each phase,
+    /// in the order of [`Phase::ALL`], in milliseconds.
+    pub(crate) fn record_frame(&mut self, frame_ms: f64, phase_ms: [f64; Phase::ALL.len()]) {
+        if self.frame >= WARM_UP_FRAMES && !self.is_done() {
+            self.frame_times.push(frame_ms);
+            for (times, ms) in self.phase_times.iter_mut().zip(phase_ms) {
+                times.push(ms);
+            }
+        }
+        self.frame += 1;
+    }
+
+    pub(crate) fn is_done(&self) -> bool {
+        self.frame_times.len() >= MEASURED_FRAMES
+    }
+
+    /// The results as text, to be pasted into a bug report or compared with another run.
+    pub(crate) fn report(&self, setup: &Setup) -> String {
+        let platform = if cfg!(target_family = "wasm") {
+            "web"
+        } else {
+            std::env::consts::OS
+        };
+
+        let mut report = format!(
+            "keplerian_sim_demo benchmark\n\
+            Version: {}\n\
+            Platform: {platform}\n\
+            Viewport: {}×{} at {:.2}× pixel ratio\n\
+            Orbit lines: {}\n\
+            Bodies: {}\n\
+            Frames: {} after {WARM_UP_FRAMES} warm-up frames\n\
+            Average frame time: {}\n\
+            1% low: {}\n\
+            Slowest frame: {:.2} ms\n",
+            env!("CARGO_PKG_VERSION"),
+            setup.viewport.0,
+            setup.viewport.1,
+            setup.pixel_ratio,
+            setup.orbit_lines,
+            setup.body_count,
+            self.frame_times.len(),
+            describe_frame_time(average(&self.frame_times)),
+            describe_frame_time(one_percent_low(&self.frame_times)),
+            self.frame_times.iter().copied().fold(0.0, f64::max),
+        );
+
+        for (phase, times) in Phase::ALL.iter().zip(&self.phase_times) {
+            report += &format!(
+                "{}: {:.2} ms average, {:.2} ms 1% low\n",
+                phase.name(),
+                average(times),
+                one_percent_low(times),
+            );
+        }
+
+        report
+    }
+}
+
+/// The scene the benchmark plays: the biggest system the generator makes,
+/// with moons around every planet that can keep them.
+pub(crate) fn scene() -> Universe {
+    generator::generate(GeneratorSettings {
+        seed: SCENE_SEED,
+        planet_count: MAX_PLANET_COUNT,
+        chaos: 0.5,
+        moon_frequency: 1.0,
+    })
+}
+
+/// The time since some fixed point, in milliseconds.
+pub(crate) fn now_ms() -> f64 {
+    #[cfg(target_family = "wasm")]
+    {
+        web_sys::window()
+            .and_then(|w| w.performance())
+            .map(|p| p.now())
+            .unwrap_or_default()
+    }
+    #[cfg(not(target_family = "wasm"))]
+    {
+        static START: LazyLock<Instant> = LazyLock::new(Instant::now);
+        START.elapsed().as_secs_f64() * 1000.0
+    }
+}
+
+/// The camera's distance from the star partway through the script,
+/// easing from far out to close in and back, evenly on a log scale.
+fn scripted_distance(progress: f64) -> f64 {
+    let closeness = (1.0 - (progress * TAU).cos()) / 2.0;
+    (FAR_DISTANCE.ln() + (NEAR_DISTANCE.ln() - FAR_DISTANCE.ln()) * closeness).exp()
+}
+
+fn average(times: &[f64]) -> f64 {
+    if times.is_empty() {
+        return 0.0;
+    }
+    times.iter().sum::<f64>() / times.len() as f64
+}
+
+/// The average of the slowest 1% of the times, or of the slowest one
+/// if there are fewer than 100.
+fn one_percent_low(times: &[f64]) -> f64 {
+    let mut sorted = times.to_vec();
+    sorted.sort_unstable_by(|a, b| b.total_cmp(a));
+    let count = (sorted.len() / 100).max(1).min(sorted.len());
+    average(&sorted[..count])
+}
+
+fn describe_frame_time(ms: f64) -> String {
+    if ms > 0.0 {
+        format!("{ms:.2} ms ({:.1} FPS)", 1000.0 / ms)
+    } else {
+        String::from("—")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn one_percent_low_averages_the_slowest_frames() {
+        let mut times = vec![10.0; 198];
+        times.extend([40.0, 60.0]);
+        assert_eq!(average(&times), 10.4);
+        assert_eq!(one_percent_low(&times), 50.0);
+
+        assert_eq!(one_percent_low(&[5.0, 20.0, 10.0]), 20.0);
+        assert_eq!(one_percent_low(&[]), 0.0);
+    }
+
+    #[test]
+    fn only_measured_frames_are_recorded() {
+        let mut benchmark = Benchmark::new(0, false);
+        for frame in 0..WARM_UP_FRAMES + MEASURED_FRAMES + 10 {
+            let ms = if frame < WARM_UP_FRAMES { 1000.0 } else { 16.0 };
+            benchmark.record_frame(ms, [1.0, 2.0, 3.0]);
+        }
+
+        assert!(benchmark.is_done());
+        assert_eq!(benchmark.frame_times.len(), MEASURED_FRAMES);
+        assert_eq!(one_percent_low(&benchmark.frame_times), 16.0);
+        assert!(
+            benchmark
+                .phase_times
+                .iter()
+                .all(|t| t.len() == MEASURED_FRAMES)
+        );
+    }
+
+    #[test]
+    fn the_camera_zooms_in_and_back_out() {
+        assert!((scripted_distance(0.0) / FAR_DISTANCE - 1.0).abs() < 1e-9);
+        assert!((scripted_distance(0.5) / NEAR_DISTANCE - 1.0).abs() < 1e-9);
+        assert!((scripted_distance(1.0) / FAR_DISTANCE - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn the_scene_is_the_same_every_time() {
+        let names = |universe: &Universe| {
+            let mut names: Vec<String> = universe
+                .get_bodies()
+                .values()
+                .map(|w| w.body.name.clone())
+                .collect();
+            names.sort();
+            names
+        };
+        let first = scene();
+        assert!(first.get_bodies().len() > MAX_PLANET_COUNT as usize);
+        assert_eq!(names(&first), names(&scene()));
+    }
+}
@@ -0,0 +1,87 @@
+//! Copying all of the settings out as JSON and back in,
+//! for backups and moving them to another device.
+
+use std::{error::Error, fmt};
+
+use serde_json::{Map, Value, json};
+
+use crate::cfg::{
+    migration::{self, CONFIG_VERSION, VERSION_KEY},
+    storage,
+};
+
+/// Identifies an exported settings file.
+const FORMAT_NAME: &str = "keplerian-sim-demo settings";
+
+/// All of the saved settings as a JSON document.
+pub(crate) fn export_json() -> String {
+    let mut settings = storage::export_all();
+    settings.remove(VERSION_KEY);
+
+    let document = json!({
+        "format": FORMAT_NAME,
+        "version": migration::saved_version(),
+        "settings": settings,
+    });
+
+    serde_json::to_string_pretty(&document).unwrap_or_default()
+}
+
+/// Saves the settings from a document made by [`export_json`],
+/// then restarts the program so they take effect.
+///
+/// Settings from older versions are upgraded when the program starts again.
+pub(crate) fn import_json(text: &str) -> Result<(), ImportError> {
+    let document: Value = serde_json::from_str(text).map_err(ImportError::Parse)?;
+
+    if document.get("format").and_then(Value::as_str) != Some(FORMAT_NAME) {
+        return Err(ImportError::NotSettings);
+    }
+
+    let version = document
+        .get("version")
+        .and_then(Value::as_u64)
+        .and_then(|v| u32::try_from(v).ok())
+        .ok_or(ImportError::NotSettings)?;
+    if version > CONFIG_VERSION {
+        return Err(ImportError::TooNew(version));
+    }
+
+    let mut settings: Map<String, Value> = document
+        .get("settings")
+        .and_then(Value::as_object)
+        .cloned()
+        .ok_or(ImportError::NotSettings)?;
+    settings.insert(VERSION_KEY.to_owned(), Value::from(version));
+
+    storage::import_all(settings).map_err(|e| ImportError::Save(e.to_string()))?;
+    storage::restart().map_err(|e| ImportError::Restart(e.to_string()))
+}
+
+#[derive(Debug)]
+pub(crate) enum ImportError {
+    Parse(serde_json::Error),
+    /// The JSON isn't an exported settings file.
+    NotSettings,
+    /// The settings were exported by a newer version than this one.
+    TooNew(u32),
+    Save(String),
+    Restart(String),
+}
+
+impl fmt::Display for ImportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ImportError::Parse(error) => write!(f, "not valid JSON: {error}"),
+            ImportError::NotSettings => write!(f, "that isn't an exported settings file"),
+            ImportError::TooNew(version) => write!(
+                f,
+                "the settings are from a newer version (v{version}, this is v{CONFIG_VERSION})"
+            ),
+            ImportError::Save(error) => write!(f, "couldn't save the settings: {error}"),
+            ImportError::Restart(error) => write!(f, "couldn't restart: {error}"),
+        }
+    }
+}
+
+impl Error for ImportError {}
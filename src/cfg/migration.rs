@@ -0,0 +1,84 @@
+//! Versioning of the saved settings.
+//!
+//! Settings are stored by key with no structure around them, so when a key
+//! is renamed or its format changes, a step is added to [`MIGRATIONS`] that
+//! carries the old value over, instead of it silently going back to default.
+
+use crate::cfg::storage;
+
+/// The version of the settings this build reads and writes.
+pub(crate) const CONFIG_VERSION: u32 = 1;
+
+/// Where the version the settings were last saved with is stored.
+pub(crate) const VERSION_KEY: &str = "config_version";
+
+/// One step that brings settings saved by an older version up to date.
+///
+/// Steps go through [`storage`], so they work the same
+/// with both the config file and the browser's local storage.
+struct Migration {
+    /// The version this step upgrades from, to the one after it.
+    from: u32,
+    apply: fn() -> Result<(), storage::SaveError>,
+}
+
+/// Every step, in order.
+const MIGRATIONS: &[Migration] = &[Migration {
+    // Settings saved before versioning have no version key,
+    // and are otherwise the same as version 1
+    from: 0,
+    apply: || Ok(()),
+}];
+
+/// The version the saved settings are at. Settings saved before
+/// versioning, or with nothing saved at all, count as version 0.
+pub(crate) fn saved_version() -> u32 {
+    storage::load(VERSION_KEY).unwrap_or(0)
+}
+
+/// Brings the saved settings up to [`CONFIG_VERSION`].
+///
+/// Must run before any setting is read.
+/// Settings saved by a newer version are left alone.
+pub(crate) fn migrate() {
+    let mut version = saved_version();
+
+    if version > CONFIG_VERSION {
+        log::warn!(
+            "Settings were saved by a newer version (v{version}, this is v{CONFIG_VERSION}); \
+            some of them may not be read correctly"
+        );
+        return;
+    }
+
+    while version < CONFIG_VERSION {
+        let Some(step) = MIGRATIONS.iter().find(|step| step.from == version) else {
+            log::error!("No way to upgrade settings from v{version}");
+            return;
+        };
+
+        if let Err(e) = (step.apply)() {
+            log::warn!("Couldn't upgrade settings from v{version}: {e}");
+            return;
+        }
+
+        version += 1;
+        if let Err(e) = storage::save(VERSION_KEY, version) {
+            log::warn!("Couldn't save the settings version: {e}");
+            return;
+        }
+        log::info!("Upgraded settings to v{version}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_old_version_has_a_step() {
+        let froms: Vec<u32> = MIGRATIONS.iter().map(|step| step.from).collect();
+        let expected: Vec<u32> = (0..CONFIG_VERSION).collect();
+        assert_eq!(froms, expected);
+    }
+}
@@ -1,6 +1,9 @@
 use std::sync::Mutex;
 
+pub(crate) mod backup;
+pub(crate) mod migration;
 pub(crate) mod saved_cell;
+pub(crate) mod startup;
 
 #[cfg_attr(target_family = "wasm", path = "storage_web.rs")]
 #[cfg_attr(not(target_family = "wasm"), path = "storage_native.rs")]
@@ -10,9 +13,87 @@ pub(crate) use storage::reset;
 
 use saved_cell::SavedCell;
 
+use crate::{
+    gfx::object_conversion::DEFAULT_SUBSYSTEM_CULL_ANGLE_DEG,
+    gui::{
+        nudge::{
+            DEFAULT_ANGLE_STEP, DEFAULT_ANOMALY_STEP, DEFAULT_ECCENTRICITY_STEP,
+            DEFAULT_INCLINATION_STEP,
+        },
+        presentation::{DEFAULT_HIDE_DELAY_S, DEFAULT_WINDOW_OPACITY},
+        text_style::DEFAULT_FONT_SIZE,
+        toolbar::{DEFAULT_TOOLBAR_ITEMS, TOOLBAR_SLOTS},
+    },
+    history::{DEFAULT_EVENT_LOG_LIMIT_KIB, DEFAULT_LOG_LIMIT_KIB},
+    metrics::Metrics,
+    sim::{
+        speed::{DEFAULT_MAX_SIM_SPEED, DEFAULT_MIN_SIM_SPEED},
+        validation::DEFAULT_MAX_MASS_RATIO,
+    },
+};
+
+/// How often the derived info grid recomputes time-dependent rows, by default.
+pub(crate) const DEFAULT_INFO_REFRESH_INTERVAL_MS: u16 = 200;
+
 pub(crate) struct Config<'a> {
     pub show_body_list_help: SavedCell<'a, bool>,
     pub show_welcome_window: SavedCell<'a, bool>,
+    pub failed_startups: SavedCell<'a, u8>,
+    pub body_list_sort_mode: SavedCell<'a, u8>,
+    pub info_refresh_interval_ms: SavedCell<'a, u16>,
+    pub polyline_orbits: SavedCell<'a, bool>,
+    pub max_mass_ratio: SavedCell<'a, f64>,
+    pub window_docks: SavedCell<'a, [u8; 3]>,
+    pub line_width_multiplier: SavedCell<'a, f32>,
+    pub dim_eclipsed_bodies: SavedCell<'a, bool>,
+    pub precise_orbits: SavedCell<'a, bool>,
+    pub palette: SavedCell<'a, u8>,
+    pub orbit_patterns: SavedCell<'a, bool>,
+    pub show_scale_bar: SavedCell<'a, bool>,
+    pub show_grid: SavedCell<'a, bool>,
+    pub new_body_radius_ratio: SavedCell<'a, f64>,
+    pub new_body_eccentricity: SavedCell<'a, f64>,
+    pub new_body_cycle_colors: SavedCell<'a, bool>,
+    pub new_body_name_pattern: SavedCell<'a, u8>,
+    pub metrics_enabled: SavedCell<'a, bool>,
+    pub metrics: SavedCell<'a, Metrics>,
+    pub zoom_sensitivity: SavedCell<'a, f64>,
+    pub render_scale: SavedCell<'a, f32>,
+    pub ui_scale: SavedCell<'a, f32>,
+    pub log_history_kib: SavedCell<'a, u32>,
+    pub event_log_kib: SavedCell<'a, u32>,
+    /// In points, before the UI scale.
+    pub font_size: SavedCell<'a, f32>,
+    pub hide_distant_subsystems: SavedCell<'a, bool>,
+    /// In degrees.
+    pub subsystem_cull_angle: SavedCell<'a, f64>,
+    pub startup_universe: SavedCell<'a, u8>,
+    pub auto_depth_range: SavedCell<'a, bool>,
+    pub distant_body_dots: SavedCell<'a, bool>,
+    pub focus_handoff: SavedCell<'a, bool>,
+    pub scientific_number_entry: SavedCell<'a, bool>,
+    pub adaptive_quality: SavedCell<'a, bool>,
+    /// In simulated seconds per second.
+    pub min_sim_speed: SavedCell<'a, f64>,
+    /// In simulated seconds per second.
+    pub max_sim_speed: SavedCell<'a, f64>,
+    pub presentation_mode: SavedCell<'a, bool>,
+    pub presentation_window_opacity: SavedCell<'a, f32>,
+    /// In seconds.
+    pub presentation_hide_delay_s: SavedCell<'a, f32>,
+    pub presentation_click_through: SavedCell<'a, bool>,
+    pub surface_altitudes: SavedCell<'a, bool>,
+    /// Only used on native builds.
+    pub background_simulation: SavedCell<'a, bool>,
+    /// The bottom bar's buttons, in order, with 0 for empty slots.
+    pub toolbar_items: SavedCell<'a, [u8; TOOLBAR_SLOTS]>,
+    pub nudge_eccentricity_step: SavedCell<'a, f64>,
+    /// In degrees.
+    pub nudge_inclination_step: SavedCell<'a, f64>,
+    /// In degrees.
+    pub nudge_angle_step: SavedCell<'a, f64>,
+    /// In degrees.
+    pub nudge_anomaly_step: SavedCell<'a, f64>,
 }
 
 impl Config<'_> {
@@ -20,6 +101,70 @@ impl Config<'_> {
         Self {
             show_body_list_help: SavedCell::new("show_body_list_help", true),
             show_welcome_window: SavedCell::new("show_welcome_window", true),
+            failed_startups: SavedCell::new("failed_startups", 0),
+            body_list_sort_mode: SavedCell::new("body_list_sort_mode", 0),
+            info_refresh_interval_ms: SavedCell::new(
+                "info_refresh_interval_ms",
+                DEFAULT_INFO_REFRESH_INTERVAL_MS,
+            ),
+            polyline_orbits: SavedCell::new("polyline_orbits", false),
+            max_mass_ratio: SavedCell::new("max_mass_ratio", DEFAULT_MAX_MASS_RATIO),
+            window_docks: SavedCell::new("window_docks", [0; 3]),
+            line_width_multiplier: SavedCell::new("line_width_multiplier", 1.0),
+            dim_eclipsed_bodies: SavedCell::new("dim_eclipsed_bodies", true),
+            precise_orbits: SavedCell::new("precise_orbits", true),
+            palette: SavedCell::new("palette", 0),
+            orbit_patterns: SavedCell::new("orbit_patterns", false),
+            show_scale_bar: SavedCell::new("show_scale_bar", true),
+            show_grid: SavedCell::new("show_grid", false),
+            new_body_radius_ratio: SavedCell::new("new_body_radius_ratio", 0.1),
+            new_body_eccentricity: SavedCell::new("new_body_eccentricity", 0.0),
+            new_body_cycle_colors: SavedCell::new("new_body_cycle_colors", false),
+            new_body_name_pattern: SavedCell::new("new_body_name_pattern", 0),
+            metrics_enabled: SavedCell::new("metrics_enabled", false),
+            metrics: SavedCell::new("metrics", Metrics::new()),
+            zoom_sensitivity: SavedCell::new("zoom_sensitivity", 1.0),
+            render_scale: SavedCell::new("render_scale", 1.0),
+            ui_scale: SavedCell::new("ui_scale", 1.0),
+            log_history_kib: SavedCell::new("log_history_kib", DEFAULT_LOG_LIMIT_KIB),
+            event_log_kib: SavedCell::new("event_log_kib", DEFAULT_EVENT_LOG_LIMIT_KIB),
+            font_size: SavedCell::new("font_size", DEFAULT_FONT_SIZE),
+            hide_distant_subsystems: SavedCell::new("hide_distant_subsystems", false),
+            subsystem_cull_angle: SavedCell::new(
+                "subsystem_cull_angle",
+                DEFAULT_SUBSYSTEM_CULL_ANGLE_DEG,
+            ),
+            startup_universe: SavedCell::new("startup_universe", 0),
+            auto_depth_range: SavedCell::new("auto_depth_range", true),
+            distant_body_dots: SavedCell::new("distant_body_dots", true),
+            focus_handoff: SavedCell::new("focus_handoff", true),
+            scientific_number_entry: SavedCell::new("scientific_number_entry", false),
+            adaptive_quality: SavedCell::new("adaptive_quality", true),
+            min_sim_speed: SavedCell::new("min_sim_speed", DEFAULT_MIN_SIM_SPEED),
+            max_sim_speed: SavedCell::new("max_sim_speed", DEFAULT_MAX_SIM_SPEED),
+            presentation_mode: SavedCell::new("presentation_mode", false),
+            presentation_window_opacity: SavedCell::new(
+                "presentation_window_opacity",
+                DEFAULT_WINDOW_OPACITY,
+            ),
+            presentation_hide_delay_s: SavedCell::new(
+                "presentation_hide_delay_s",
+                DEFAULT_HIDE_DELAY_S,
+            ),
+            presentation_click_through: SavedCell::new("presentation_click_through", false),
+            surface_altitudes: SavedCell::new("surface_altitudes", false),
+            background_simulation: SavedCell::new("background_simulation", false),
+            toolbar_items: SavedCell::new("toolbar_items", DEFAULT_TOOLBAR_ITEMS),
+            nudge_eccentricity_step: SavedCell::new(
+                "nudge_eccentricity_step",
+                DEFAULT_ECCENTRICITY_STEP,
+            ),
+            nudge_inclination_step: SavedCell::new(
+                "nudge_inclination_step",
+                DEFAULT_INCLINATION_STEP,
+            ),
+            nudge_angle_step: SavedCell::new("nudge_angle_step", DEFAULT_ANGLE_STEP),
+            nudge_anomaly_step: SavedCell::new("nudge_anomaly_step", DEFAULT_ANOMALY_STEP),
         }
     }
 }
@@ -56,12 +56,29 @@ where
 
     pub fn save(&self) -> Result<(), storage::SaveError> {
         storage::save(self.key, self.cell.get())
+            .inspect_err(|e| log::warn!("Couldn't save setting `{}`: {e}", self.key))
     }
 
     pub fn load(&self) -> Result<T, storage::LoadError> {
-        let res = storage::load(self.key)?;
+        // Settings that were never saved keep their default, instead of
+        // being looked for in storage again every time they're read
         self.uninit.set(false);
+        let res = storage::load(self.key)?;
         self.cell.set(res);
         Ok(res)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_settings_are_only_looked_up_once() {
+        let cell = SavedCell::new("test_setting_that_is_never_saved", 7u32);
+        assert_eq!(cell.get(), 7);
+        assert!(!cell.uninit.get());
+        assert!(cell.load().is_err());
+        assert_eq!(cell.get(), 7);
+    }
+}
@@ -0,0 +1,80 @@
+//! Crash-loop detection.
+//!
+//! A startup is counted as failed until the program has been running
+//! healthily for [`HEALTHY_DURATION_MS`]. If too many startups fail in
+//! a row, the program boots into safe mode instead. Quitting cleanly
+//! before then doesn't count as a failure.
+
+use crate::cfg::CONFIG;
+
+/// How many consecutive failed startups trigger safe mode.
+const MAX_FAILED_STARTUPS: u8 = 2;
+
+/// How long the program needs to run before a startup is
+/// considered successful, in milliseconds.
+const HEALTHY_DURATION_MS: f64 = 10_000.0;
+
+pub(crate) struct StartupTracker {
+    safe_mode: bool,
+    healthy_ms: f64,
+    cleared: bool,
+}
+
+impl StartupTracker {
+    /// Marks the start of a startup attempt.
+    ///
+    /// The attempt stays marked as failed until [`StartupTracker::tick`]
+    /// has been called for long enough.
+    pub(crate) fn begin() -> Self {
+        let failed_startups = match CONFIG.try_lock() {
+            Ok(cfg) => {
+                let failed_startups = cfg.failed_startups.get();
+                let _ = cfg.failed_startups.set(failed_startups.saturating_add(1));
+                failed_startups
+            }
+            Err(_) => 0,
+        };
+
+        let safe_mode = failed_startups >= MAX_FAILED_STARTUPS;
+        if safe_mode {
+            log::warn!("{failed_startups} startups in a row failed, starting in safe mode");
+        }
+
+        Self {
+            safe_mode,
+            healthy_ms: 0.0,
+            cleared: false,
+        }
+    }
+
+    /// Whether the previous startups failed too many times in a row.
+    #[inline]
+    pub(crate) fn is_safe_mode(&self) -> bool {
+        self.safe_mode
+    }
+
+    /// Marks the program as shutting down cleanly,
+    /// so quitting before [`HEALTHY_DURATION_MS`] isn't counted as a failure.
+    pub(crate) fn end_cleanly() {
+        if let Ok(cfg) = CONFIG.try_lock() {
+            let _ = cfg.failed_startups.set(0);
+        }
+    }
+
+    /// Call this every frame with the elapsed time of the frame.
+    pub(crate) fn tick(&mut self, elapsed_ms: f64) {
+        if self.cleared {
+            return;
+        }
+
+        self.healthy_ms += elapsed_ms;
+
+        if self.healthy_ms >= HEALTHY_DURATION_MS
+            && let Ok(cfg) = CONFIG.try_lock()
+        {
+            let _ = cfg.failed_startups.set(0);
+            self.cleared = true;
+            log::debug!("Startup went fine, the failed startup count was reset");
+        }
+    }
+}
@@ -8,7 +8,7 @@ use std::{
     io::{self, Read, Write},
     path::PathBuf,
     process::Command,
-    sync::LazyLock,
+    sync::{LazyLock, atomic::Ordering},
 };
 
 use crate::HALT_FLAG;
@@ -48,26 +48,34 @@ fn get_table(cfg_path: &PathBuf) -> toml::value::Table {
 }
 
 pub(super) fn save<T: Serialize>(key: &str, value: T) -> Result<(), SaveError> {
+    let cfg_path = CONFIG_PATH.as_ref().ok_or(SaveError::NoSaveDirectory)?;
+
+    let value = toml::value::Value::try_from(value).map_err(|e| SaveError::SerializeValue(e))?;
+
+    let mut table = get_table(cfg_path);
+    table.insert(key.to_string(), value);
+    write_table(&table)
+}
+
+/// Replaces the config file with `table`, going through a temporary file
+/// so a crash halfway through doesn't leave it half-written.
+fn write_table(table: &toml::value::Table) -> Result<(), SaveError> {
     let cfg_dir = CONFIG_DIR.as_ref().ok_or(SaveError::NoSaveDirectory)?;
     let cfg_path = CONFIG_PATH.as_ref().ok_or(SaveError::NoSaveDirectory)?;
     let tmp_path = TEMP_CONFIG_PATH
         .as_ref()
         .ok_or(SaveError::NoSaveDirectory)?;
 
-    let value = toml::value::Value::try_from(value).map_err(|e| SaveError::SerializeValue(e))?;
-
     std::fs::create_dir_all(cfg_dir).map_err(|e| SaveError::CreateConfigDir(e))?;
 
-    let mut table = get_table(cfg_path);
-
     let mut tmp_file = OpenOptions::new()
         .write(true)
         .create(true)
+        .truncate(true)
         .open(tmp_path)
         .map_err(|e| SaveError::OpenTmpFile(e))?;
 
-    table.insert(key.to_string(), value);
-    let table_string = toml::to_string(&table).map_err(|e| SaveError::StringifyTable(e))?;
+    let table_string = toml::to_string(table).map_err(|e| SaveError::StringifyTable(e))?;
 
     tmp_file
         .write_all(table_string.as_bytes())
@@ -98,6 +106,43 @@ pub(super) fn load<T: for<'d> Deserialize<'d>>(key: &str) -> Result<T, LoadError
     value.try_into().map_err(|e| LoadError::DeserializeValue(e))
 }
 
+pub(super) fn remove(key: &str) -> Result<(), SaveError> {
+    let cfg_path = CONFIG_PATH.as_ref().ok_or(SaveError::NoSaveDirectory)?;
+
+    let mut table = get_table(cfg_path);
+    if table.remove(key).is_none() {
+        return Ok(());
+    }
+    write_table(&table)
+}
+
+/// Every saved setting, by key.
+pub(super) fn export_all() -> serde_json::Map<String, serde_json::Value> {
+    let Some(cfg_path) = CONFIG_PATH.as_ref() else {
+        return serde_json::Map::new();
+    };
+
+    get_table(cfg_path)
+        .into_iter()
+        .filter_map(|(key, value)| Some((key, serde_json::to_value(value).ok()?)))
+        .collect()
+}
+
+/// Saves all of the settings at once, keeping any that aren't in `settings`.
+pub(super) fn import_all(
+    settings: serde_json::Map<String, serde_json::Value>,
+) -> Result<(), SaveError> {
+    let cfg_path = CONFIG_PATH.as_ref().ok_or(SaveError::NoSaveDirectory)?;
+
+    let mut table = get_table(cfg_path);
+    for (key, value) in settings {
+        let value =
+            toml::value::Value::try_from(value).map_err(|e| SaveError::SerializeValue(e))?;
+        table.insert(key, value);
+    }
+    write_table(&table)
+}
+
 pub(crate) fn reset() -> Result<(), ResetError> {
     let Some(file) = CONFIG_PATH.as_ref() else {
         return Ok(());
@@ -108,6 +153,11 @@ pub(crate) fn reset() -> Result<(), ResetError> {
         Err(e) => return Err(ResetError::DeleteConfig(e)),
     };
 
+    restart()
+}
+
+/// Launches the program again with the same arguments, and quits this one.
+pub(super) fn restart() -> Result<(), ResetError> {
     let exe = env::current_exe().map_err(|e| ResetError::GetCurrentExe(e))?;
 
     Command::new(exe)
@@ -120,9 +170,7 @@ pub(crate) fn reset() -> Result<(), ResetError> {
         .spawn()
         .map_err(|e| ResetError::LaunchError(e))?;
 
-    unsafe {
-        HALT_FLAG = true;
-    }
+    HALT_FLAG.store(true, Ordering::Relaxed);
 
     Ok(())
 }
@@ -1,7 +1,7 @@
 use crate::HALT_FLAG;
 use gloo_storage::{LocalStorage, Storage, errors::StorageError};
 use serde::{Deserialize, Serialize};
-use std::fmt::Display;
+use std::{fmt::Display, sync::atomic::Ordering};
 
 const PREFIX: &str = "/keplerian-sim-demo | ";
 fn to_storage_key(key: &str) -> String {
@@ -19,20 +19,63 @@ pub(super) fn load<T: for<'a> Deserialize<'a>>(key: &str) -> Result<T, LoadError
     LocalStorage::get(&to_storage_key(key))
 }
 
+pub(super) fn remove(key: &str) -> Result<(), SaveError> {
+    LocalStorage::delete(&to_storage_key(key));
+    Ok(())
+}
+
+/// Every saved setting, by key.
+pub(super) fn export_all() -> serde_json::Map<String, serde_json::Value> {
+    LocalStorage::get_all::<serde_json::Map<String, serde_json::Value>>()
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|(key, value)| Some((key.strip_prefix(PREFIX)?.to_owned(), value)))
+        .collect()
+}
+
+/// Saves all of the settings at once, keeping any that aren't in `settings`.
+///
+/// Local storage saves one key at a time, so if one of them fails,
+/// the keys already saved are put back the way they were.
+pub(super) fn import_all(
+    settings: serde_json::Map<String, serde_json::Value>,
+) -> Result<(), SaveError> {
+    let previous: Vec<(String, Option<serde_json::Value>)> = settings
+        .keys()
+        .map(|key| (key.clone(), load(key).ok()))
+        .collect();
+
+    for (key, value) in settings {
+        if let Err(e) = save(&key, value) {
+            for (key, value) in previous {
+                let _ = match value {
+                    Some(value) => save(&key, value),
+                    None => remove(&key),
+                };
+            }
+            return Err(e);
+        }
+    }
+    Ok(())
+}
+
 pub(crate) fn reset() -> Result<(), ResetError> {
     LocalStorage::get_all::<serde_json::Map<String, serde_json::Value>>()
         .map_err(|e| ResetError::GetAll(e))?
         .keys()
         .filter(|k| k.starts_with(PREFIX))
         .for_each(|k| LocalStorage::delete(k));
+    restart()
+}
+
+/// Reloads the page, which starts the program again.
+pub(super) fn restart() -> Result<(), ResetError> {
     let window = web_sys::window().ok_or(ResetError::NoWindow)?;
     window
         .location()
         .reload()
         .map_err(|e| ResetError::Reload(e))?;
-    unsafe {
-        HALT_FLAG = true;
-    }
+    HALT_FLAG.store(true, Ordering::Relaxed);
     Ok(())
 }
 
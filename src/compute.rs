@@ -0,0 +1,121 @@
+//! Running heavy computations without freezing the GUI.
+//!
+//! Work is split into [`Job`] steps and handed off with [`spawn`], which
+//! returns a [`Task`] to poll for the result from the GUI each frame.
+//!
+//! Native builds run each job on its own thread. The web build can't share
+//! memory with a web worker without cross-origin isolation, so jobs run on the
+//! main thread instead, a few milliseconds' worth of steps at a time between frames.
+//!
+//! Orbit fits and the body meshes built at startup go through here. The
+//! position map is worked out on a worker thread by the background simulation
+//! on native builds instead, since it has to keep pace with every frame rather
+//! than finish eventually. There are no porkchop or closest-approach scans to
+//! move over yet; new scans like those should be written as jobs.
+//!
+//! Real web workers are left out on purpose: without shared memory, every job
+//! would have to serialize its inputs, like a whole universe, across to the
+//! worker, and GitHub Pages can't send the headers cross-origin isolation needs.
+
+use std::sync::{
+    Arc,
+    atomic::{AtomicBool, Ordering},
+    mpsc::{self, Receiver, TryRecvError},
+};
+
+/// A computation that can be done a bit at a time.
+pub(crate) trait Job: Send + 'static {
+    type Output: Send + 'static;
+
+    /// Does a bit of the work.
+    ///
+    /// Returns: The output, once the work is done.
+    fn step(&mut self) -> Option<Self::Output>;
+}
+
+/// The state of a spawned job.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum TaskStatus<T> {
+    Running,
+    Done(T),
+    /// The job panicked, or its result was already taken.
+    Failed,
+}
+
+/// A handle to a spawned job.
+///
+/// Dropping it cancels the job.
+pub(crate) struct Task<T> {
+    receiver: Receiver<T>,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl<T> Task<T> {
+    /// Checks on the job, taking its output if it's done.
+    pub(crate) fn poll(&self) -> TaskStatus<T> {
+        match self.receiver.try_recv() {
+            Ok(output) => TaskStatus::Done(output),
+            Err(TryRecvError::Empty) => TaskStatus::Running,
+            Err(TryRecvError::Disconnected) => TaskStatus::Failed,
+        }
+    }
+}
+
+impl<T> Drop for Task<T> {
+    fn drop(&mut self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Starts running a job in the background.
+pub(crate) fn spawn<J: Job>(mut job: J) -> Task<J::Output> {
+    let (sender, receiver) = mpsc::channel();
+    let cancelled = Arc::new(AtomicBool::new(false));
+    let task = Task {
+        receiver,
+        cancelled: Arc::clone(&cancelled),
+    };
+
+    #[cfg(not(target_family = "wasm"))]
+    std::thread::spawn(move || {
+        while !cancelled.load(Ordering::Relaxed) {
+            if let Some(output) = job.step() {
+                let _ = sender.send(output);
+                return;
+            }
+        }
+    });
+
+    #[cfg(target_family = "wasm")]
+    wasm_bindgen_futures::spawn_local(async move {
+        use gloo_timers::future::TimeoutFuture;
+
+        /// How long to keep stepping before letting the browser draw a frame.
+        const BUDGET_MS: f64 = 8.0;
+
+        let now = || {
+            web_sys::window()
+                .and_then(|w| w.performance())
+                .map(|p| p.now())
+                .unwrap_or_default()
+        };
+
+        loop {
+            // Let the frame that spawned this get drawn first
+            TimeoutFuture::new(0).await;
+
+            let start = now();
+            while now() - start < BUDGET_MS {
+                if cancelled.load(Ordering::Relaxed) {
+                    return;
+                }
+                if let Some(output) = job.step() {
+                    let _ = sender.send(output);
+                    return;
+                }
+            }
+        }
+    });
+
+    task
+}
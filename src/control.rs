@@ -3,8 +3,11 @@
 #[cfg(target_family = "wasm")]
 use std::sync::LazyLock;
 
+use glam::{DMat3, DVec3};
 use three_d::renderer::*;
 
+use crate::cfg::CONFIG;
+
 ///
 /// A control that makes the camera orbit around a target.
 ///
@@ -18,10 +21,32 @@ pub struct CameraControl {
     pub desired_distance: f64,
     /// The current distance to the target point.
     pub current_distance: f64,
+    /// How fast a pinch was zooming, in natural log units of distance
+    /// per millisecond, kept up for a moment after the fingers stop.
+    zoom_velocity: f64,
+    /// The frame the camera turned with last frame, if it's following one.
+    followed_frame: Option<DMat3>,
 }
 
+/// The camera's vertical field of view, in degrees.
+pub const FIELD_OF_VIEW_Y_DEG: f32 = 45.0;
+
 const ZOOM_APPROACH_SPEED: f64 = 0.03;
 
+/// How quickly pinch zoom momentum dies down, per millisecond.
+const ZOOM_MOMENTUM_DECAY: f64 = 0.008;
+
+/// Pinch zoom momentum slower than this stops, in natural log units per millisecond.
+const MIN_ZOOM_VELOCITY: f64 = 1e-6;
+
+/// How much room `frame` leaves around the framed points,
+/// as a multiple of the tightest fit.
+const FRAME_MARGIN: f64 = 1.15;
+
+/// How much one pixel of a trackpad pinch zooms, in natural log units.
+#[cfg(target_family = "wasm")]
+const PINCH_WHEEL_SCALE: f64 = 0.01;
+
 impl CameraControl {
     /// Creates a new orbit control with the given target and minimum and maximum distance to the target.
     pub fn new(min_distance: f64, max_distance: f64, desired_distance: f64) -> Self {
@@ -30,19 +55,37 @@ impl CameraControl {
             max_distance,
             desired_distance,
             current_distance: desired_distance,
+            zoom_velocity: 0.0,
+            followed_frame: None,
         }
     }
 
     /// Handles the events. Must be called each frame.
     pub fn handle_events(&mut self, camera: &mut Camera, events: &mut [Event], elapsed_time: f64) {
+        let sensitivity = CONFIG
+            .try_lock()
+            .map(|cfg| cfg.zoom_sensitivity.get())
+            .unwrap_or(1.0);
+
+        let mut pinch = 0.0;
         for event in events.iter_mut() {
-            self.handle_event(camera, event);
+            pinch += self.handle_event(camera, event, sensitivity);
+        }
+        #[cfg(target_family = "wasm")]
+        {
+            pinch += crate::web::gestures::take_pinch_zoom() * sensitivity;
         }
+
+        self.update_pinch(pinch, elapsed_time);
         self.reclamp();
         self.update_zoom(elapsed_time);
     }
 
-    fn handle_event(&mut self, camera: &mut Camera, event: &mut Event) {
+    /// Handles an event.
+    ///
+    /// Returns: How much the event pinch-zooms, in natural log units,
+    /// for `update_pinch` to apply along with its momentum.
+    fn handle_event(&mut self, camera: &mut Camera, event: &mut Event, sensitivity: f64) -> f64 {
         match event {
             Event::MouseMotion {
                 delta,
@@ -51,7 +94,7 @@ impl CameraControl {
                 ..
             } => {
                 if *handled {
-                    return;
+                    return 0.0;
                 }
                 if Some(MouseButton::Left) == *button {
                     let speed = 0.01;
@@ -67,10 +110,24 @@ impl CameraControl {
                     *handled = true;
                 }
             }
-            Event::MouseWheel { delta, handled, .. } => {
+            Event::MouseWheel {
+                delta,
+                modifiers,
+                handled,
+                ..
+            } => {
                 if *handled {
-                    return;
+                    return 0.0;
+                }
+                *handled = true;
+
+                // Browsers report trackpad pinches as wheel events with Ctrl held
+                #[cfg(target_family = "wasm")]
+                if modifiers.ctrl {
+                    return delta.1 as f64 * -PINCH_WHEEL_SCALE * sensitivity;
                 }
+                #[cfg(not(target_family = "wasm"))]
+                let _ = modifiers;
 
                 let delta = delta.1 as f64 * -0.02;
 
@@ -81,23 +138,115 @@ impl CameraControl {
                     delta * 0.1
                 };
 
-                self.zoom(delta);
-                *handled = true;
+                self.zoom(delta * sensitivity);
             }
             Event::PinchGesture { delta, handled, .. } => {
                 // This doesn't get run on mobile for some reason
                 if *handled {
-                    return;
+                    return 0.0;
                 }
-                self.zoom(*delta as f64);
                 *handled = true;
+                return *delta as f64 * sensitivity;
             }
             _ => {}
         }
+
+        0.0
     }
+    /// Zooms so that all the points, given in meters relative to the target,
+    /// fit in the viewport with some margin, keeping the view direction.
+    ///
+    /// The camera eases there like with any other zoom.
+    pub fn frame(&mut self, camera: &Camera, points: &[DVec3]) {
+        let to_camera = camera.position().normalize();
+        let up = camera.up();
+        let view = DVec3::new(to_camera.x as f64, to_camera.y as f64, to_camera.z as f64);
+        let up = DVec3::new(up.x as f64, up.y as f64, up.z as f64)
+            .reject_from(view)
+            .normalize();
+        let right = up.cross(view);
+
+        let tan_y = (FIELD_OF_VIEW_Y_DEG as f64 / 2.0).to_radians().tan();
+        let tan_x = tan_y * camera.viewport().aspect() as f64;
+
+        // Each point needs the camera far enough back for it to be
+        // within the view's edges at its depth
+        let distance = points
+            .iter()
+            .map(|point| {
+                let depth = point.dot(view);
+                let across = (point.dot(right).abs() / tan_x).max(point.dot(up).abs() / tan_y);
+                depth + across * FRAME_MARGIN
+            })
+            .fold(0.0, f64::max);
+
+        if !distance.is_finite() || distance <= 0.0 {
+            return;
+        }
+
+        self.zoom_velocity = 0.0;
+        self.desired_distance = distance.clamp(self.min_distance, self.max_distance);
+    }
+    /// Zooms until a sphere of `radius` meters around the target
+    /// fits in the viewport, keeping the view direction.
+    pub fn frame_sphere(&mut self, camera: &Camera, radius: f64) {
+        let points = [DVec3::X, DVec3::Y, DVec3::Z].map(|axis| axis * radius);
+        let points = points.map(|point| [point, -point]).concat();
+        self.frame(camera, &points);
+    }
+    /// Zooms to `distance` meters from the target, easing there like any other zoom.
+    pub fn zoom_to_distance(&mut self, distance: f64) {
+        if !distance.is_finite() || distance <= 0.0 {
+            return;
+        }
+        self.zoom_velocity = 0.0;
+        self.desired_distance = distance.clamp(self.min_distance, self.max_distance);
+    }
+    /// Turns the camera along with `frame`, so the view stays fixed in it
+    /// while still letting the camera be dragged around.
+    ///
+    /// Must be called each frame; passing None stops following.
+    pub fn follow_frame(&mut self, camera: &mut Camera, frame: Option<DMat3>) {
+        if let (Some(old), Some(new)) = (self.followed_frame, frame) {
+            let rotation = new * old.transpose();
+            let position = rotation * to_dvec3(camera.position());
+            let up = rotation * to_dvec3(camera.up());
+            set_view_direction(camera, position, up);
+        }
+        self.followed_frame = frame;
+    }
+    /// Turns the camera to look along `direction` from the target,
+    /// keeping `up` pointing up on screen as far as it can.
+    pub fn look_along(&mut self, camera: &mut Camera, direction: DVec3, up: DVec3) {
+        set_view_direction(camera, -direction, up);
+    }
+    /// Zooms from where the camera is headed, rather than where it is,
+    /// so several wheel steps in quick succession add up.
     fn zoom(&mut self, delta: f64) {
         self.desired_distance =
-            (self.current_distance * delta.exp()).clamp(self.min_distance, self.max_distance);
+            (self.desired_distance * delta.exp()).clamp(self.min_distance, self.max_distance);
+    }
+    /// Zooms by this frame's pinching, and keeps zooming for a moment
+    /// after the fingers stop, slowing down smoothly.
+    fn update_pinch(&mut self, pinch: f64, elapsed_time: f64) {
+        if pinch != 0.0 {
+            self.zoom(pinch);
+            if elapsed_time > 0.0 {
+                // Averaged, so one jumpy event doesn't fling the camera
+                self.zoom_velocity = (self.zoom_velocity + pinch / elapsed_time) / 2.0;
+            }
+            return;
+        }
+
+        if self.zoom_velocity == 0.0 {
+            return;
+        }
+
+        self.zoom(self.zoom_velocity * elapsed_time);
+        self.zoom_velocity *= (-ZOOM_MOMENTUM_DECAY * elapsed_time).exp();
+        if self.zoom_velocity.abs() < MIN_ZOOM_VELOCITY {
+            self.zoom_velocity = 0.0;
+        }
     }
     fn reclamp(&mut self) {
         self.desired_distance = self
@@ -127,6 +276,31 @@ static IS_WEB_MOBILE: LazyLock<bool> = LazyLock::new(|| {
     ua.contains("mobi") || ua.contains("android") || ua.contains("iphone") || ua.contains("ios")
 });
 
+fn to_dvec3(vec: Vec3) -> DVec3 {
+    DVec3::new(vec.x as f64, vec.y as f64, vec.z as f64)
+}
+
+/// Puts the camera in the direction `position` from the target, looking at it.
+fn set_view_direction(camera: &mut Camera, position: DVec3, up: DVec3) {
+    let Some(position) = position.try_normalize() else {
+        return;
+    };
+    // The up vector can't be along the view, or the view is undefined
+    let up = up
+        .reject_from(position)
+        .try_normalize()
+        .or_else(|| DVec3::Z.reject_from(position).try_normalize())
+        .unwrap_or(DVec3::X);
+
+    let position = position.as_vec3();
+    let up = up.as_vec3();
+    camera.set_view(
+        Vec3::new(position.x, position.y, position.z),
+        Vec3::zero(),
+        Vec3::new(up.x, up.y, up.z),
+    );
+}
+
 fn is_nan(vec: Vec3) -> bool {
     vec.x.is_nan() || vec.y.is_nan() || vec.z.is_nan()
 }
@@ -0,0 +1,228 @@
+//! Lowering the graphics quality while frames are slow, and raising it
+//! again once there's headroom.
+//!
+//! Only done on the web, where the browser's watchdog reloads the page if
+//! frames stop coming for too long, and where slow devices can't be told
+//! apart from fast ones up front.
+
+use crate::{
+    cfg::CONFIG,
+    gfx::object_conversion::TRAJECTORY_POINT_BUDGET,
+    notify::{Severity, notify},
+};
+
+/// How many steps the quality can go down by.
+const MAX_REDUCTION: u8 = 3;
+
+/// What the render scale setting is multiplied by at each step.
+const RENDER_SCALE_FACTORS: [f32; MAX_REDUCTION as usize + 1] = [1.0, 0.75, 0.5, 0.5];
+
+/// How many levels coarser the body spheres are drawn at each step.
+const LOD_BIASES: [usize; MAX_REDUCTION as usize + 1] = [0, 0, 1, 2];
+
+/// The average frame time above which the quality goes down, in milliseconds.
+const SLOW_FRAME_MS: f64 = 1000.0 / 25.0;
+
+/// The average frame time below which the quality goes back up, in milliseconds.
+///
+/// Well below [`SLOW_FRAME_MS`], so raising the quality again
+/// doesn't immediately make it slow enough to lower it.
+const FAST_FRAME_MS: f64 = 1000.0 / 50.0;
+
+/// How long frames have to stay slow before the quality goes down,
+/// in milliseconds.
+const STEP_DOWN_AFTER_MS: f64 = 3_000.0;
+
+/// How long frames have to stay fast before the quality goes back up,
+/// in milliseconds.
+const STEP_UP_AFTER_MS: f64 = 15_000.0;
+
+/// How far back the average frame time reaches, roughly, in milliseconds.
+const AVERAGE_WINDOW_MS: f64 = 1_000.0;
+
+/// Frames longer than this are left out of the average, in milliseconds.
+///
+/// Browsers stop drawing hidden tabs, so the first frame after switching
+/// back can be minutes long without the device being slow.
+const MAX_COUNTED_FRAME_MS: f64 = 1_000.0;
+
+pub(crate) struct AdaptiveQuality {
+    /// How many steps below full quality the graphics are.
+    reduction: u8,
+    /// The recent frame time, in milliseconds.
+    average_frame_ms: f64,
+    /// How long the average has been above [`SLOW_FRAME_MS`], in milliseconds.
+    slow_for_ms: f64,
+    /// How long the average has been below [`FAST_FRAME_MS`], in milliseconds.
+    fast_for_ms: f64,
+}
+
+impl AdaptiveQuality {
+    pub(crate) const fn new() -> Self {
+        Self {
+            reduction: 0,
+            average_frame_ms: 0.0,
+            slow_for_ms: 0.0,
+            fast_for_ms: 0.0,
+        }
+    }
+
+    /// What to multiply the render scale setting by.
+    pub(crate) fn render_scale_factor(&self) -> f32 {
+        RENDER_SCALE_FACTORS[self.reduction as usize]
+    }
+
+    /// How many levels coarser to draw body spheres than their size calls for.
+    pub(crate) fn lod_bias(&self) -> usize {
+        LOD_BIASES[self.reduction as usize]
+    }
+
+    /// How many points all orbit lines together get per frame.
+    pub(crate) fn point_budget(&self) -> u32 {
+        TRAJECTORY_POINT_BUDGET >> self.reduction
+    }
+
+    /// Counts a frame that took `elapsed_ms`, and steps the quality
+    /// down or up if frames have been slow or fast for long enough.
+    pub(crate) fn record_frame(&mut self, elapsed_ms: f64) {
+        if !enabled() {
+            *self = Self::new();
+            return;
+        }
+
+        if let Some(message) = self.count_frame(elapsed_ms) {
+            notify(Severity::Info, message);
+        }
+    }
+
+    /// Does the work of [`Self::record_frame`] whether or not it's enabled.
+    ///
+    /// Returns: A message saying how the quality changed, if it did.
+    fn count_frame(&mut self, elapsed_ms: f64) -> Option<String> {
+        if !(0.0..=MAX_COUNTED_FRAME_MS).contains(&elapsed_ms) {
+            return None;
+        }
+
+        let weight = (elapsed_ms / AVERAGE_WINDOW_MS).min(1.0);
+        self.average_frame_ms += (elapsed_ms - self.average_frame_ms) * weight;
+
+        if self.average_frame_ms > SLOW_FRAME_MS {
+            self.slow_for_ms += elapsed_ms;
+            self.fast_for_ms = 0.0;
+        } else if self.average_frame_ms < FAST_FRAME_MS {
+            self.fast_for_ms += elapsed_ms;
+            self.slow_for_ms = 0.0;
+        } else {
+            self.slow_for_ms = 0.0;
+            self.fast_for_ms = 0.0;
+        }
+
+        if self.slow_for_ms >= STEP_DOWN_AFTER_MS && self.reduction < MAX_REDUCTION {
+            self.reduction += 1;
+            self.slow_for_ms = 0.0;
+            Some(format!(
+                "Lowered the graphics quality to keep things smooth \
+                ({} of {MAX_REDUCTION} steps down). Turn off \"Adaptive quality\" \
+                in the settings to keep full quality.",
+                self.reduction
+            ))
+        } else if self.fast_for_ms >= STEP_UP_AFTER_MS && self.reduction > 0 {
+            self.reduction -= 1;
+            self.fast_for_ms = 0.0;
+            Some(if self.reduction == 0 {
+                "Graphics quality is back to full.".to_owned()
+            } else {
+                format!(
+                    "Raised the graphics quality ({} of {MAX_REDUCTION} steps down).",
+                    self.reduction
+                )
+            })
+        } else {
+            None
+        }
+    }
+}
+
+/// Whether the quality is adjusted: on the web,
+/// and only while the "Adaptive quality" setting is on.
+fn enabled() -> bool {
+    cfg!(target_family = "wasm")
+        && CONFIG
+            .try_lock()
+            .map(|cfg| cfg.adaptive_quality.get())
+            .unwrap_or(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Counts frames of `frame_ms` for `duration_ms`,
+    /// returning how many times the quality changed.
+    fn run(quality: &mut AdaptiveQuality, frame_ms: f64, duration_ms: f64) -> usize {
+        let frames = (duration_ms / frame_ms).round() as usize;
+        (0..frames)
+            .filter(|_| quality.count_frame(frame_ms).is_some())
+            .count()
+    }
+
+    #[test]
+    fn sustained_slow_frames_lower_the_quality() {
+        let mut quality = AdaptiveQuality::new();
+        assert_eq!(run(&mut quality, 50.0, STEP_DOWN_AFTER_MS), 0);
+        assert_eq!(quality.reduction, 0);
+
+        assert_eq!(run(&mut quality, 50.0, 3_000.0), 1);
+        assert_eq!(quality.reduction, 1);
+        assert!(quality.render_scale_factor() < 1.0);
+        assert!(quality.point_budget() < TRAJECTORY_POINT_BUDGET);
+
+        run(&mut quality, 50.0, 60_000.0);
+        assert_eq!(quality.reduction, MAX_REDUCTION);
+    }
+
+    #[test]
+    fn frames_from_hidden_tabs_are_left_out() {
+        let mut quality = AdaptiveQuality::new();
+        run(&mut quality, 10.0, 2_000.0);
+        quality.count_frame(120_000.0);
+        run(&mut quality, 10.0, 10_000.0);
+        assert_eq!(quality.reduction, 0);
+    }
+
+    #[test]
+    fn fast_frames_raise_the_quality_back_to_full() {
+        let mut quality = AdaptiveQuality::new();
+        run(&mut quality, 50.0, 60_000.0);
+        assert_eq!(quality.reduction, MAX_REDUCTION);
+
+        assert_eq!(run(&mut quality, 10.0, STEP_UP_AFTER_MS), 0);
+        assert_eq!(run(&mut quality, 10.0, STEP_UP_AFTER_MS), 1);
+        assert_eq!(quality.reduction, MAX_REDUCTION - 1);
+
+        run(&mut quality, 10.0, 120_000.0);
+        assert_eq!(quality.reduction, 0);
+        assert_eq!(quality.render_scale_factor(), 1.0);
+        assert_eq!(quality.lod_bias(), 0);
+    }
+
+    #[test]
+    fn frame_times_between_the_thresholds_keep_the_quality() {
+        let mut quality = AdaptiveQuality::new();
+        run(&mut quality, 50.0, 5_000.0);
+        assert_eq!(quality.reduction, 1);
+
+        assert_eq!(run(&mut quality, 30.0, 120_000.0), 0);
+        assert_eq!(quality.reduction, 1);
+    }
+
+    #[test]
+    fn short_slow_spells_dont_lower_the_quality() {
+        let mut quality = AdaptiveQuality::new();
+        for _ in 0..20 {
+            run(&mut quality, 50.0, 2_000.0);
+            run(&mut quality, 10.0, 2_000.0);
+        }
+        assert_eq!(quality.reduction, 0);
+    }
+}
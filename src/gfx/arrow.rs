@@ -0,0 +1,105 @@
+use std::sync::LazyLock;
+
+use glam::DVec3;
+use strum_macros::EnumIter;
+use three_d::{CpuMesh, Mat4, Srgba, Vec4};
+
+/// The proportion of the arrow's length taken up by its tail.
+const ARROW_TAIL_LENGTH: f32 = 0.8;
+
+/// The radius of the arrow's tail, relative to the radius of its head.
+const ARROW_TAIL_RADIUS: f32 = 0.35;
+
+const ARROW_SUBDIVS: u32 = 12;
+
+/// An arrow pointing towards +X, from the origin to (1, 0, 0).
+/// Its head has a radius of 1.
+pub static ARROW_MESH: LazyLock<CpuMesh> =
+    LazyLock::new(|| CpuMesh::arrow(ARROW_TAIL_LENGTH, ARROW_TAIL_RADIUS, ARROW_SUBDIVS));
+
+/// The on-screen length of an arrow per decade of magnitude,
+/// relative to the arrow's distance to the camera.
+pub const ARROW_LENGTH_PER_DECADE: f64 = 0.015;
+
+/// The head radius of an arrow, relative to the arrow's distance to the camera.
+pub const ARROW_HEAD_RADIUS: f64 = 0.002;
+
+pub const VELOCITY_ARROW_COLOR: Srgba = Srgba::new_opaque(96, 224, 128);
+pub const ACCELERATION_ARROW_COLOR: Srgba = Srgba::new_opaque(255, 160, 64);
+
+/// The velocity magnitude that corresponds to one decade
+/// of arrow length, in m/s.
+pub const VELOCITY_UNIT: f64 = 1.0;
+
+/// The acceleration magnitude that corresponds to one decade
+/// of arrow length, in m/s².
+pub const ACCELERATION_UNIT: f64 = 1e-6;
+
+/// Which vectors to draw on top of each body.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, EnumIter)]
+pub enum VectorOverlayMode {
+    #[default]
+    Off,
+    Velocity,
+    VelocityAndAcceleration,
+}
+
+impl VectorOverlayMode {
+    pub const fn name(self) -> &'static str {
+        match self {
+            VectorOverlayMode::Off => "Off",
+            VectorOverlayMode::Velocity => "Velocity",
+            VectorOverlayMode::VelocityAndAcceleration => "Vel. + accel.",
+        }
+    }
+
+    pub const fn description(self) -> &'static str {
+        match self {
+            VectorOverlayMode::Off => "Don't draw any vectors.",
+            VectorOverlayMode::Velocity => {
+                "Draw an arrow showing where each body is heading \
+                and how fast it is going."
+            }
+            VectorOverlayMode::VelocityAndAcceleration => {
+                "Draw an arrow showing each body's velocity, \
+                and another showing the gravitational pull it currently feels."
+            }
+        }
+    }
+
+    pub const fn shows_velocity(self) -> bool {
+        !matches!(self, VectorOverlayMode::Off)
+    }
+
+    pub const fn shows_acceleration(self) -> bool {
+        matches!(self, VectorOverlayMode::VelocityAndAcceleration)
+    }
+}
+
+/// Gets how many "decades" long an arrow representing a vector
+/// of the given magnitude should be.
+///
+/// Arrow lengths grow logarithmically so that both slow moons and
+/// fast planets get a readable arrow.
+pub fn get_arrow_decades(magnitude: f64, unit: f64) -> f64 {
+    (magnitude / unit).ln_1p() / core::f64::consts::LN_10
+}
+
+/// Gets the transformation matrix that turns [`ARROW_MESH`] into
+/// an arrow starting at `base` and pointing in `direction`.
+///
+/// Returns None if the direction is zero or not finite.
+pub fn get_arrow_matrix(base: DVec3, direction: DVec3, length: f64, radius: f64) -> Option<Mat4> {
+    let x = direction.try_normalize()?;
+    let y = x.any_orthonormal_vector();
+    let z = x.cross(y);
+
+    let (x, y, z) = (x * length, y * radius, z * radius);
+
+    Some(Mat4 {
+        x: Vec4::new(x.x as f32, x.y as f32, x.z as f32, 0.0),
+        y: Vec4::new(y.x as f32, y.y as f32, y.z as f32, 0.0),
+        z: Vec4::new(z.x as f32, z.y as f32, z.z as f32, 0.0),
+        w: Vec4::new(base.x as f32, base.y as f32, base.z as f32, 1.0),
+    })
+}
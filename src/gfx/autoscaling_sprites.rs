@@ -4,6 +4,8 @@
 use three_d::core::*;
 use three_d::renderer::*;
 
+use crate::gfx::trajectory::{SHADER_VERSION_HEADER, strip_version_header};
+
 ///
 /// A set of sprites, ie. a set of quads that orients itself towards the camera.
 ///
@@ -16,8 +18,13 @@ pub struct AutoscalingSprites {
     position_buffer: VertexBuffer<Vec3>,
     uv_buffer: VertexBuffer<Vec2>,
     center_buffer: InstanceBuffer<Vec3>,
+    /// The linear color of each sprite.
+    color_buffer: InstanceBuffer<Vec4>,
     direction: Option<Vec3>,
     pub scale: f32,
+    /// How many pixels across the sprites are, whatever the viewport.
+    /// Overrides `scale` when set.
+    pub pixel_size: Option<f32>,
 }
 
 impl AutoscalingSprites {
@@ -60,11 +67,32 @@ impl AutoscalingSprites {
             position_buffer,
             uv_buffer,
             center_buffer: InstanceBuffer::new_with_data(context, centers),
+            color_buffer: InstanceBuffer::new_with_data(
+                context,
+                &vec![Vec4::new(1.0, 1.0, 1.0, 1.0); centers.len()],
+            ),
             direction,
             scale,
+            pixel_size: None,
         }
     }
 
+    ///
+    /// Create a new set of sprites that are `pixel_size` pixels across wherever they are,
+    /// with one color per center.
+    ///
+    pub fn new_with_pixel_size(
+        context: &Context,
+        centers: &[Vec3],
+        colors: &[Srgba],
+        pixel_size: f32,
+    ) -> Self {
+        let mut sprites = Self::new(context, centers, None, 0.0);
+        sprites.set_colors(colors);
+        sprites.pixel_size = Some(pixel_size);
+        sprites
+    }
+
     ///
     /// Set a direction the sprite normals are always orthogonal to.
     ///
@@ -79,6 +107,27 @@ impl AutoscalingSprites {
         self.center_buffer.fill(centers);
     }
 
+    ///
+    /// Set the colors of the sprites, one per center.
+    ///
+    pub fn set_colors(&mut self, colors: &[Srgba]) {
+        let colors: Vec<Vec4> = colors.iter().map(|color| color.to_linear_srgb()).collect();
+        self.color_buffer.fill(&colors);
+    }
+
+    /// What the shader multiplies the distance to a sprite by to get its size.
+    fn scale_uniform(&self, viewer: &dyn Viewer) -> f32 {
+        match self.pixel_size {
+            Some(pixel_size) => {
+                // The projection's y scale is 1 / tan(fov / 2)
+                let tan_half_fov = viewer.projection().y.y.recip();
+                let height = viewer.viewport().height.max(1) as f32;
+                tan_half_fov * pixel_size / height
+            }
+            None => self.scale,
+        }
+    }
+
     fn draw(&self, program: &Program, render_states: RenderStates, viewer: &dyn Viewer) {
         program.use_uniform("eye", viewer.position());
         program.use_uniform("viewProjection", viewer.projection() * viewer.view());
@@ -86,8 +135,11 @@ impl AutoscalingSprites {
         if program.requires_attribute("uv_coordinate") {
             program.use_vertex_attribute("uv_coordinate", &self.uv_buffer);
         }
-        program.use_uniform("scaleTimesTanHalfFov", self.scale);
+        program.use_uniform("scaleTimesTanHalfFov", self.scale_uniform(viewer));
         program.use_instance_attribute("center", &self.center_buffer);
+        if program.requires_attribute("instance_color") {
+            program.use_instance_attribute("instance_color", &self.color_buffer);
+        }
         program.use_uniform("direction", self.direction.unwrap_or(vec3(0.0, 0.0, 0.0)));
         program.draw_arrays_instanced(
             render_states,
@@ -172,3 +224,41 @@ impl Geometry for AutoscalingSprites {
         AxisAlignedBoundingBox::INFINITE
     }
 }
+
+const DOT_FRAGMENT_SHADER_SOURCE: &'static str = strip_version_header(
+    include_str!("shaders/sprite_dot.frag"),
+    SHADER_VERSION_HEADER,
+);
+
+///
+/// Draws each sprite as a round dot in its instance color.
+///
+pub struct DotMaterial;
+
+impl Material for DotMaterial {
+    fn fragment_shader_source(&self, _lights: &[&dyn Light]) -> String {
+        let mut source = ColorMapping::fragment_shader_source().to_owned();
+        source.push_str(DOT_FRAGMENT_SHADER_SOURCE);
+        source
+    }
+
+    fn id(&self) -> EffectMaterialId {
+        EffectMaterialId(0x3899)
+    }
+
+    fn use_uniforms(&self, program: &Program, viewer: &dyn Viewer, _lights: &[&dyn Light]) {
+        viewer.color_mapping().use_uniforms(program);
+    }
+
+    fn render_states(&self) -> RenderStates {
+        RenderStates {
+            blend: Blend::TRANSPARENCY,
+            cull: Cull::None,
+            ..Default::default()
+        }
+    }
+
+    fn material_type(&self) -> MaterialType {
+        MaterialType::Transparent
+    }
+}
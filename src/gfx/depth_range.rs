@@ -0,0 +1,275 @@
+//! Fitting the camera's near and far planes around what's on screen.
+//!
+//! The depth buffer's precision is spread between the two planes,
+//! so keeping them as close together as the scene allows avoids
+//! surfaces and lines flickering through each other.
+
+use std::{collections::HashMap, f64::consts::SQRT_2};
+
+use glam::DVec3;
+use keplerian_sim::{Orbit, OrbitTrait};
+use three_d::Degrees;
+
+use crate::{
+    Program,
+    cfg::CONFIG,
+    control::FIELD_OF_VIEW_Y_DEG,
+    gfx::{grid, object_conversion, rings, trajectory},
+    sim::{
+        body::Rings,
+        universe::{BodyWrapper, Id, Universe},
+    },
+};
+
+/// The near plane used when the depth range isn't automatic, in render units.
+pub(crate) const FIXED_Z_NEAR: f32 = 0.001;
+/// The far plane used when the depth range isn't automatic, in render units.
+pub(crate) const FIXED_Z_FAR: f32 = 5e12;
+
+/// The closest the near plane gets, in render units.
+const MIN_Z_NEAR: f64 = 1e-7;
+
+/// How much of the distance to the nearest surface the near plane sits at.
+const NEAR_FRACTION: f64 = 0.5;
+
+/// How far past the outermost body or orbit the far plane sits,
+/// as a multiple of its distance.
+const FAR_MARGIN: f64 = 4.0;
+
+/// The near and far planes for a scene, in render units,
+/// where the camera is one unit away from its focus.
+///
+/// `nearest`: The distance from the camera to the closest surface.
+///
+/// `farthest`: The distance from the camera to the farthest point
+/// that should be drawn.
+pub(crate) fn auto_depth_range(nearest: f64, farthest: f64) -> (f32, f32) {
+    // `max` and `min` skip NaNs, so a broken scene falls back to the widest range
+    let near = (nearest * NEAR_FRACTION).max(MIN_Z_NEAR);
+    let far = (farthest * FAR_MARGIN)
+        .min(FIXED_Z_FAR as f64)
+        .max(near * 10.0);
+
+    (near as f32, far as f32)
+}
+
+impl Program {
+    /// Moves the camera's near and far planes to fit the focused body
+    /// and everything around it, or back to the fixed ones if the
+    /// "Automatic depth range" setting is off.
+    pub(crate) fn update_depth_range(&mut self, position_map: &HashMap<Id, DVec3>) {
+        let automatic = CONFIG
+            .try_lock()
+            .map(|cfg| cfg.auto_depth_range.get())
+            .unwrap_or(true);
+
+        let (near, far) = if automatic {
+            let session = self.session();
+            let camera_pos = session.camera.position();
+            let view = DepthView {
+                offset: self.camera_offset(position_map),
+                scale: 1.0 / session.control.current_distance,
+                position: DVec3::new(
+                    camera_pos.x as f64,
+                    camera_pos.y as f64,
+                    camera_pos.z as f64,
+                ),
+                show_grid: CONFIG
+                    .try_lock()
+                    .map(|cfg| cfg.show_grid.get())
+                    .unwrap_or(false),
+            };
+            let (nearest, farthest) =
+                scene_depth_bounds(&session.sim_state.universe, position_map, &view);
+            auto_depth_range(nearest, farthest)
+        } else {
+            (FIXED_Z_NEAR, FIXED_Z_FAR)
+        };
+
+        self.sessions[self.active_session]
+            .camera
+            .set_perspective_projection(
+                Degrees {
+                    0: FIELD_OF_VIEW_Y_DEG,
+                },
+                near,
+                far,
+            );
+    }
+}
+
+/// Where the scene is drawn from.
+struct DepthView {
+    /// The simulation-space position at the render origin.
+    offset: DVec3,
+    /// Render units per meter.
+    scale: f64,
+    /// The camera's position, in render units.
+    position: DVec3,
+    /// Whether the reference grid is drawn.
+    show_grid: bool,
+}
+
+/// The distances from the camera to the closest body surface or rings,
+/// and to the farthest body, orbit line, rings or grid edge, in render units.
+fn scene_depth_bounds(
+    universe: &Universe,
+    position_map: &HashMap<Id, DVec3>,
+    view: &DepthView,
+) -> (f64, f64) {
+    let to_render = |position: DVec3| (position - view.offset) * view.scale;
+
+    // The focus is one unit away, so nothing needs a nearer near plane
+    // unless a body is in between
+    let mut nearest = 1.0_f64;
+    let mut farthest = 1.0_f64;
+
+    if view.show_grid {
+        // The grid doesn't write depth, so it only has to be
+        // in front of the far plane
+        let center = DVec3::new(0.0, 0.0, -view.offset.z * view.scale);
+        let corner = grid::grid_half_size(view.scale) * SQRT_2;
+        farthest = farthest.max((center - view.position).length() + corner);
+    }
+
+    for (id, wrapper) in universe.get_bodies() {
+        let Some(position) = position_map.get(id) else {
+            continue;
+        };
+        let position = to_render(*position);
+        let radius = wrapper.body.radius * view.scale;
+        let distance = (position - view.position).length();
+
+        nearest = nearest.min(distance - radius);
+        farthest = farthest.max(distance + radius);
+
+        if let Some(rings) = &wrapper.rings {
+            nearest = nearest.min(distance_to_rings(rings, position, view));
+            farthest = farthest.max(distance + rings.outer_radius * view.scale);
+        }
+
+        let Some(orbit) = &wrapper.body.orbit else {
+            continue;
+        };
+        let Some(parent_position) = wrapper
+            .relations
+            .parent
+            .and_then(|parent| position_map.get(&parent))
+        else {
+            continue;
+        };
+        let parent_distance = (to_render(*parent_position) - view.position).length();
+
+        // Closed orbits are drawn all the way around their parent,
+        // and open ones out to the end of their drawn stretch
+        let reach = if orbit.is_closed() {
+            orbit.get_apoapsis()
+        } else {
+            hyperbola_reach(universe, wrapper, orbit)
+        };
+        farthest = farthest.max(parent_distance + reach * view.scale);
+    }
+
+    (nearest, farthest)
+}
+
+/// The distance from the camera to the closest point of a body's rings,
+/// in render units. `center` is the body's position, in render units.
+fn distance_to_rings(rings: &Rings, center: DVec3, view: &DepthView) -> f64 {
+    let offset = view.position - center;
+    let normal = rings::rings_normal(rings);
+    let height = offset.dot(normal);
+    let across = (offset - normal * height).length();
+
+    let inner = rings.inner_radius.max(0.0) * view.scale;
+    let outer = rings.outer_radius * view.scale;
+    let gap = across - across.clamp(inner, outer.max(inner));
+
+    height.hypot(gap)
+}
+
+/// How far from its parent the drawn stretch of an open orbit reaches,
+/// in meters.
+fn hyperbola_reach(universe: &Universe, wrapper: &BodyWrapper, orbit: &Orbit) -> f64 {
+    let farthest_anomaly = match object_conversion::hyperbola_bounds(universe, wrapper) {
+        Some((start, end)) => start.abs().max(end.abs()),
+        None => {
+            let time = wrapper.frozen_at.unwrap_or(universe.time);
+            let anomaly = orbit.get_eccentric_anomaly_at_time(time);
+            anomaly.abs() + trajectory::around_body_range(anomaly) / 2.0
+        }
+    };
+
+    // From r = |a| (e cosh H - 1), where |a| = r_pe / (e - 1)
+    let eccentricity = orbit.get_eccentricity();
+    let semi_major_axis = orbit.get_periapsis() / (eccentricity - 1.0);
+    semi_major_axis * (eccentricity * farthest_anomaly.cosh() - 1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::FRAC_PI_2;
+
+    use super::*;
+    use crate::sim::{body::Body, universe::HyperbolaRange};
+
+    fn view(position: DVec3) -> DepthView {
+        DepthView {
+            offset: DVec3::ZERO,
+            scale: 1.0,
+            position,
+            show_grid: false,
+        }
+    }
+
+    fn add(universe: &mut Universe, radius: f64, orbit: Option<Orbit>, parent: Option<Id>) -> Id {
+        let body = Body::new(String::from("Body"), 1.0, radius, orbit);
+        universe.add_body(body, parent).unwrap()
+    }
+
+    #[test]
+    fn rings_are_fitted_between_the_planes() {
+        let mut universe = Universe::default();
+        let planet = add(&mut universe, 2.0, None, None);
+        universe.get_body_mut(planet).unwrap().rings = Some(Rings::around(2.0));
+        let positions = HashMap::from([(planet, DVec3::ZERO)]);
+
+        // Just above the rings, which reach from 3 to 5
+        let camera = DVec3::new(4.0, 0.0, 0.5);
+        let (nearest, farthest) = scene_depth_bounds(&universe, &positions, &view(camera));
+
+        assert!((nearest - 0.5).abs() < 1e-12);
+        assert!((farthest - (camera.length() + 5.0)).abs() < 1e-12);
+    }
+
+    #[test]
+    fn open_orbits_are_fitted_out_to_the_end_of_their_line() {
+        let mut universe = Universe::default();
+        let star = add(&mut universe, 0.1, None, None);
+        let orbit = Orbit::new(2.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0);
+        let probe = add(&mut universe, 0.1, Some(orbit), Some(star));
+        universe.get_body_mut(probe).unwrap().hyperbola_range =
+            HyperbolaRange::TrueAnomaly(FRAC_PI_2);
+        let positions = HashMap::from([(star, DVec3::ZERO), (probe, DVec3::X)]);
+
+        // At a true anomaly of 90°, r = q (1 + e) = 3
+        let camera = DVec3::new(0.0, 0.0, 10.0);
+        let (_, farthest) = scene_depth_bounds(&universe, &positions, &view(camera));
+
+        assert!((farthest - 13.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn grid_is_fitted_in_front_of_the_far_plane() {
+        let universe = Universe::default();
+        let positions = HashMap::new();
+        let mut view = view(DVec3::new(0.0, 0.0, 1.0));
+
+        let (_, farthest) = scene_depth_bounds(&universe, &positions, &view);
+        assert_eq!(farthest, 1.0);
+
+        view.show_grid = true;
+        let (_, farthest) = scene_depth_bounds(&universe, &positions, &view);
+        assert!((farthest - (1.0 + grid::grid_half_size(1.0) * SQRT_2)).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,505 @@
+//! Exports the scene as a glTF file, to carry on with it in Blender
+//! or other 3D tools.
+//!
+//! The scene is a snapshot at the universe's current time. Each body is
+//! a node placed relative to its parent, holding a sphere of the body's
+//! radius and color, and its satellites' nodes and orbit lines.
+//! The file is self-contained, with its binary data embedded as base64.
+
+use core::f64::consts::{PI, TAU};
+use std::{collections::HashMap, fmt::Display};
+
+use glam::DVec3;
+use keplerian_sim::{Orbit, OrbitTrait};
+use serde_json::{Value, json};
+use three_d::Srgba;
+
+use crate::{
+    gfx::palette,
+    sim::universe::{Id, Universe},
+};
+
+/// How many meters one unit in the exported file is: a thousand kilometers,
+/// so that both planets and their orbits are workable sizes.
+pub(crate) const METERS_PER_UNIT: f64 = 1e6;
+
+/// How many rings of quads the exported spheres have, pole to pole.
+const SPHERE_RINGS: u32 = 16;
+
+/// How many quads around each ring of the exported spheres.
+const SPHERE_SEGMENTS: u32 = 32;
+
+/// How many points each exported orbit line goes through.
+const ORBIT_POINTS: usize = 256;
+
+/// How far towards its asymptotes an open orbit's line goes.
+const OPEN_ORBIT_EXTENT: f64 = 0.9;
+
+/// glTF buffer view targets.
+const ARRAY_BUFFER: u32 = 34962;
+const ELEMENT_ARRAY_BUFFER: u32 = 34963;
+
+/// glTF accessor component types.
+const FLOAT: u32 = 5126;
+const UNSIGNED_INT: u32 = 5125;
+
+/// glTF primitive modes.
+const LINE_LOOP: u32 = 2;
+const LINE_STRIP: u32 = 3;
+const TRIANGLES: u32 = 4;
+
+/// The binary data of the file, and the views and accessors into it.
+#[derive(Default)]
+struct Buffer {
+    bytes: Vec<u8>,
+    views: Vec<Value>,
+    accessors: Vec<Value>,
+}
+
+impl Buffer {
+    /// Adds a list of points, returning its accessor's index.
+    fn push_vec3s(&mut self, points: &[[f32; 3]]) -> usize {
+        let mut min = [f32::INFINITY; 3];
+        let mut max = [f32::NEG_INFINITY; 3];
+        for point in points {
+            for axis in 0..3 {
+                min[axis] = min[axis].min(point[axis]);
+                max[axis] = max[axis].max(point[axis]);
+            }
+        }
+
+        let floats = points.iter().flatten();
+        let view = self.push_view(floats.flat_map(|f| f.to_le_bytes()), ARRAY_BUFFER);
+        self.push_accessor(json!({
+            "bufferView": view,
+            "componentType": FLOAT,
+            "count": points.len(),
+            "type": "VEC3",
+            "min": min,
+            "max": max,
+        }))
+    }
+
+    /// Adds a list of vertex indices, returning its accessor's index.
+    fn push_indices(&mut self, indices: &[u32]) -> usize {
+        let view = self.push_view(
+            indices.iter().flat_map(|i| i.to_le_bytes()),
+            ELEMENT_ARRAY_BUFFER,
+        );
+        self.push_accessor(json!({
+            "bufferView": view,
+            "componentType": UNSIGNED_INT,
+            "count": indices.len(),
+            "type": "SCALAR",
+        }))
+    }
+
+    /// Adds a view over `bytes`, which are always whole 4-byte
+    /// values, so every view stays aligned.
+    fn push_view(&mut self, bytes: impl Iterator<Item = u8>, target: u32) -> usize {
+        let offset = self.bytes.len();
+        self.bytes.extend(bytes);
+        self.views.push(json!({
+            "buffer": 0,
+            "byteOffset": offset,
+            "byteLength": self.bytes.len() - offset,
+            "target": target,
+        }));
+        self.views.len() - 1
+    }
+
+    fn push_accessor(&mut self, accessor: Value) -> usize {
+        self.accessors.push(accessor);
+        self.accessors.len() - 1
+    }
+}
+
+/// Writes the universe, as it is at its current time, as a glTF file.
+pub(crate) fn to_gltf(universe: &Universe) -> String {
+    let positions = universe.get_all_body_positions();
+    let mut buffer = Buffer::default();
+
+    let (sphere_positions, sphere_indices) = unit_sphere();
+    let sphere_position_accessor = buffer.push_vec3s(&sphere_positions);
+    // On a unit sphere, the normals are the positions
+    let sphere_normal_accessor = buffer.push_vec3s(&sphere_positions);
+    let sphere_index_accessor = buffer.push_indices(&sphere_indices);
+
+    let mut ids: Vec<Id> = universe.get_bodies().keys().copied().collect();
+    ids.sort_unstable();
+
+    let mut materials = Vec::with_capacity(ids.len());
+    let mut meshes = Vec::new();
+    let mut nodes = Vec::new();
+    let mut body_nodes = HashMap::with_capacity(ids.len());
+
+    // Every body's node is added first, so their indices are known
+    // when linking them up with their parents afterwards
+    for &id in &ids {
+        let Some(wrapper) = universe.get_body(id) else {
+            continue;
+        };
+        let color = palette::display_color(universe, id).unwrap_or(wrapper.body.color);
+        let material = materials.len();
+        materials.push(json!({
+            "name": wrapper.body.name,
+            "pbrMetallicRoughness": {
+                "baseColorFactor": linear_color(color),
+                "metallicFactor": 0.0,
+                "roughnessFactor": 1.0,
+            },
+        }));
+
+        meshes.push(json!({
+            "name": wrapper.body.name,
+            "primitives": [{
+                "attributes": {
+                    "POSITION": sphere_position_accessor,
+                    "NORMAL": sphere_normal_accessor,
+                },
+                "indices": sphere_index_accessor,
+                "material": material,
+                "mode": TRIANGLES,
+            }],
+        }));
+        let radius = (wrapper.body.radius / METERS_PER_UNIT) as f32;
+        nodes.push(json!({
+            "name": format!("{} sphere", wrapper.body.name),
+            "mesh": meshes.len() - 1,
+            "scale": [radius; 3],
+        }));
+        let sphere_node = nodes.len() - 1;
+
+        let position = positions.get(&id).copied().unwrap_or_default();
+        let parent_position = wrapper
+            .relations
+            .parent
+            .and_then(|parent_id| positions.get(&parent_id))
+            .copied()
+            .unwrap_or_default();
+        nodes.push(json!({
+            "name": wrapper.body.name,
+            "translation": to_gltf_space(position - parent_position),
+            "children": [sphere_node],
+        }));
+        body_nodes.insert(id, (nodes.len() - 1, material));
+    }
+
+    let mut roots = Vec::new();
+    for &id in &ids {
+        let Some(wrapper) = universe.get_body(id) else {
+            continue;
+        };
+        let (node, material) = body_nodes[&id];
+        let Some(&(parent_node, _)) = wrapper.relations.parent.and_then(|p| body_nodes.get(&p))
+        else {
+            roots.push(node);
+            continue;
+        };
+
+        push_child(&mut nodes, parent_node, node);
+
+        if let Some(orbit) = &wrapper.body.orbit {
+            let accessor = buffer.push_vec3s(&orbit_points(orbit));
+            meshes.push(json!({
+                "name": format!("{} orbit", wrapper.body.name),
+                "primitives": [{
+                    "attributes": { "POSITION": accessor },
+                    "material": material,
+                    "mode": if orbit.is_closed() { LINE_LOOP } else { LINE_STRIP },
+                }],
+            }));
+            nodes.push(json!({
+                "name": format!("{} orbit", wrapper.body.name),
+                "mesh": meshes.len() - 1,
+            }));
+            let orbit_node = nodes.len() - 1;
+            push_child(&mut nodes, parent_node, orbit_node);
+        }
+    }
+
+    let gltf = json!({
+        "asset": {
+            "version": "2.0",
+            "generator": concat!("keplerian_sim_demo ", env!("CARGO_PKG_VERSION")),
+        },
+        "scene": 0,
+        "scenes": [{ "name": universe.metadata.name, "nodes": roots }],
+        "nodes": nodes,
+        "meshes": meshes,
+        "materials": materials,
+        "accessors": buffer.accessors,
+        "bufferViews": buffer.views,
+        "buffers": [{
+            "byteLength": buffer.bytes.len(),
+            "uri": format!("data:application/octet-stream;base64,{}", base64(&buffer.bytes)),
+        }],
+    });
+
+    gltf.to_string()
+}
+
+fn push_child(nodes: &mut [Value], parent: usize, child: usize) {
+    if let Some(children) = nodes[parent]["children"].as_array_mut() {
+        children.push(child.into());
+    }
+}
+
+/// Converts a position in meters to glTF's units and axes.
+///
+/// The simulation has Z up, while glTF has Y up.
+fn to_gltf_space(position: DVec3) -> [f32; 3] {
+    let position = position / METERS_PER_UNIT;
+    [position.x as f32, position.z as f32, -position.y as f32]
+}
+
+/// The points of an orbit line, relative to the parent.
+///
+/// Closed orbits are sampled evenly in eccentric anomaly, which puts
+/// more points where the orbit curves the most.
+fn orbit_points(orbit: &Orbit) -> Vec<[f32; 3]> {
+    let true_anomaly_at = |i: usize| {
+        let along = i as f64 / ORBIT_POINTS as f64;
+        if orbit.is_closed() {
+            orbit.get_true_anomaly_at_eccentric_anomaly(along * TAU)
+        } else {
+            let limit = orbit.get_true_anomaly_at_asymptote() * OPEN_ORBIT_EXTENT;
+            -limit + 2.0 * limit * along
+        }
+    };
+    // Closed orbits are drawn as loops, so their last point isn't repeated
+    let count = if orbit.is_closed() {
+        ORBIT_POINTS
+    } else {
+        ORBIT_POINTS + 1
+    };
+
+    (0..count)
+        .map(|i| to_gltf_space(orbit.get_position_at_true_anomaly(true_anomaly_at(i))))
+        .collect()
+}
+
+/// A sphere of radius 1, as positions and triangle indices.
+fn unit_sphere() -> (Vec<[f32; 3]>, Vec<u32>) {
+    let mut positions = Vec::new();
+    for ring in 0..=SPHERE_RINGS {
+        let polar = PI * ring as f64 / SPHERE_RINGS as f64;
+        for segment in 0..=SPHERE_SEGMENTS {
+            let azimuth = TAU * segment as f64 / SPHERE_SEGMENTS as f64;
+            positions.push([
+                (polar.sin() * azimuth.cos()) as f32,
+                polar.cos() as f32,
+                (polar.sin() * azimuth.sin()) as f32,
+            ]);
+        }
+    }
+
+    let row = SPHERE_SEGMENTS + 1;
+    let mut indices = Vec::new();
+    for ring in 0..SPHERE_RINGS {
+        for segment in 0..SPHERE_SEGMENTS {
+            let top_left = ring * row + segment;
+            let bottom_left = top_left + row;
+            // Wound counter-clockwise as seen from outside
+            indices.extend([top_left, top_left + 1, bottom_left]);
+            indices.extend([bottom_left, top_left + 1, bottom_left + 1]);
+        }
+    }
+
+    (positions, indices)
+}
+
+/// glTF colors are linear, while body colors are sRGB.
+fn linear_color(color: Srgba) -> [f32; 4] {
+    let linear = |c: u8| {
+        let c = c as f32 / 255.0;
+        if c <= 0.04045 {
+            c / 12.92
+        } else {
+            ((c + 0.055) / 1.055).powf(2.4)
+        }
+    };
+    [
+        linear(color.r),
+        linear(color.g),
+        linear(color.b),
+        color.a as f32 / 255.0,
+    ]
+}
+
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut text = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let bits = chunk.iter().enumerate().fold(0u32, |bits, (i, &byte)| {
+            bits | (byte as u32) << (16 - 8 * i)
+        });
+        for i in 0..4 {
+            if i <= chunk.len() {
+                text.push(ALPHABET[(bits >> (18 - 6 * i) & 0x3F) as usize] as char);
+            } else {
+                text.push('=');
+            }
+        }
+    }
+    text
+}
+
+/// A file name for the universe's export, from its name.
+pub(crate) fn file_name(universe: &Universe) -> String {
+    let name: String = universe
+        .metadata
+        .name
+        .trim()
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    let name = if name.is_empty() { "universe" } else { &name };
+    format!("{name}.gltf")
+}
+
+#[derive(Debug)]
+pub(crate) enum SaveError {
+    #[cfg(not(target_family = "wasm"))]
+    NoDownloadsFolder,
+    #[cfg(not(target_family = "wasm"))]
+    Io(std::io::Error),
+    #[cfg(target_family = "wasm")]
+    Browser(String),
+}
+
+impl Display for SaveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            #[cfg(not(target_family = "wasm"))]
+            SaveError::NoDownloadsFolder => write!(f, "couldn't find the downloads folder"),
+            #[cfg(not(target_family = "wasm"))]
+            SaveError::Io(e) => write!(f, "{e}"),
+            #[cfg(target_family = "wasm")]
+            SaveError::Browser(e) => write!(f, "the browser refused the download: {e}"),
+        }
+    }
+}
+
+/// Exports the universe and saves it: into the downloads folder on desktop,
+/// next to any earlier exports, or as a download on the web.
+///
+/// Returns where the file went, to tell the user.
+pub(crate) fn save(universe: &Universe) -> Result<String, SaveError> {
+    let contents = to_gltf(universe);
+    let file_name = file_name(universe);
+
+    #[cfg(target_family = "wasm")]
+    {
+        crate::web::download::download(&file_name, "model/gltf+json", &contents)
+            .map_err(SaveError::Browser)?;
+        Ok(file_name)
+    }
+    #[cfg(not(target_family = "wasm"))]
+    {
+        let dirs = directories::UserDirs::new().ok_or(SaveError::NoDownloadsFolder)?;
+        let folder = dirs.download_dir().ok_or(SaveError::NoDownloadsFolder)?;
+
+        // Numbered rather than overwriting an earlier export
+        let (stem, extension) = file_name.rsplit_once('.').unwrap_or((&file_name, "gltf"));
+        let mut path = folder.join(&file_name);
+        let mut number = 2;
+        while path.exists() {
+            path = folder.join(format!("{stem} ({number}).{extension}"));
+            number += 1;
+        }
+
+        std::fs::write(&path, contents).map_err(SaveError::Io)?;
+        Ok(path.display().to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sim::body::Body;
+
+    #[test]
+    fn base64_pads_partial_chunks() {
+        assert_eq!(base64(b"Man"), "TWFu");
+        assert_eq!(base64(b"Ma"), "TWE=");
+        assert_eq!(base64(b"M"), "TQ==");
+        assert_eq!(base64(b""), "");
+        assert_eq!(base64(&[0xFF, 0xEE, 0xDD, 0xCC]), "/+7dzA==");
+    }
+
+    #[test]
+    fn the_sphere_faces_outwards() {
+        let (positions, indices) = unit_sphere();
+        for triangle in indices.chunks(3) {
+            let [a, b, c] = [0, 1, 2].map(|i| {
+                let [x, y, z] = positions[triangle[i] as usize];
+                DVec3::new(x as f64, y as f64, z as f64)
+            });
+            let normal = (b - a).cross(c - a);
+            // Triangles at the poles have two corners in the same place
+            if normal.length() > 1e-9 {
+                assert!(normal.dot(a + b + c) > 0.0);
+            }
+        }
+    }
+
+    #[test]
+    fn satellites_are_placed_relative_to_their_parent() {
+        let mut universe = Universe::new(None);
+        let star = universe
+            .add_body(Body::new("Star".to_owned(), 2e30, 7e8, None), None)
+            .unwrap();
+        let orbit = Orbit::new(0.0, 1.5e11, 0.0, 0.0, 0.0, 0.0, 1.0);
+        let planet = universe
+            .add_body(
+                Body::new("Planet".to_owned(), 6e24, 6.4e6, Some(orbit)),
+                Some(star),
+            )
+            .unwrap();
+
+        let gltf: Value = serde_json::from_str(&to_gltf(&universe)).unwrap();
+
+        // Two bodies with a sphere node each, and the planet's orbit line
+        assert_eq!(gltf["nodes"].as_array().unwrap().len(), 5);
+        assert_eq!(gltf["meshes"].as_array().unwrap().len(), 3);
+        assert_eq!(gltf["scenes"][0]["nodes"].as_array().unwrap().len(), 1);
+
+        let node = |name: &str| {
+            gltf["nodes"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .find(|n| n["name"] == name)
+                .unwrap()
+                .clone()
+        };
+        assert_eq!(node("Star")["children"].as_array().unwrap().len(), 3);
+
+        let position = universe.get_body_position(planet).unwrap();
+        let expected = to_gltf_space(position);
+        let translation = node("Planet")["translation"].clone();
+        for axis in 0..3 {
+            let value = translation[axis].as_f64().unwrap();
+            assert!((value - expected[axis] as f64).abs() < 1e-3);
+        }
+        let scale = node("Planet sphere")["scale"][0].as_f64().unwrap();
+        assert!((scale - 6.4).abs() < 1e-6);
+    }
+
+    #[test]
+    fn file_names_are_safe() {
+        let mut universe = Universe::new(None);
+        universe.metadata.name = "Solar System: 2/3".to_owned();
+        assert_eq!(file_name(&universe), "Solar_System__2_3.gltf");
+        universe.metadata.name = "  ".to_owned();
+        assert_eq!(file_name(&universe), "universe.gltf");
+    }
+}
@@ -0,0 +1,425 @@
+//! Golden-image tests: small fixed scenes rendered offscreen and compared
+//! against reference images, to catch regressions in the trajectory shaders,
+//! level of detail selection and color handling that unit tests can't see.
+//!
+//! The scenes are rendered with a headless OpenGL context. Where there's
+//! no GPU, Mesa's software rasterizer works too (`LIBGL_ALWAYS_SOFTWARE=1`).
+//! Not every machine can make one, so the rendering tests are ignored by
+//! default and show up as such; run them with `cargo test -- --ignored`.
+//! Once asked for, failing to make a context is a failure, not a skip.
+//!
+//! The goldens are binary PPM images in `src/gfx/goldens`. A missing golden
+//! is a failure too, so a fresh checkout can't pass by writing its own.
+//! `UPDATE_GOLDENS=1` writes or rewrites them all after an intended change,
+//! and the new images should be checked by eye before being committed.
+//! When a render doesn't match, it's written next to its golden as
+//! `<name>.actual.ppm` to compare.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use glam::DVec3;
+use keplerian_sim::{Orbit, OrbitTrait};
+use three_d::{
+    AmbientLight, Blend, Camera, ClearState, ColorMaterial, Context, CpuMaterial, Cull, Degrees,
+    DepthTexture2D, DirectionalLight, Gm, HeadlessContext, Interpolation, Light, Mat4, Mesh,
+    Object, PhysicalMaterial, RenderStates, RenderTarget, Srgba, Texture2D, Vec3, Viewport,
+    Wrapping,
+};
+
+use crate::gfx::{
+    object_conversion::{SPHERE_MESHES, get_lod_type, get_radial_size},
+    palette::LinePattern,
+    trajectory::{Trajectory, TrajectoryMode},
+};
+
+const IMAGE_SIZE: u32 = 128;
+
+/// How far apart a channel of a rendered pixel and its golden can be
+/// before the pixel counts as different, out of 255.
+/// Leaves room for drivers rounding blends and gradients differently.
+const CHANNEL_TOLERANCE: u8 = 8;
+
+/// How many pixels may differ before the render fails, as a fraction
+/// of the image. Leaves room for drivers rasterizing edges differently.
+const MAX_DIFFERENT_FRACTION: f64 = 0.005;
+
+const SCENE_MU: f64 = 1.0;
+const LINE_THICKNESS: f32 = 2.0;
+const POINT_COUNT: u32 = 256;
+
+/// An RGB image, top row first.
+#[derive(Clone, Debug, PartialEq)]
+struct Image {
+    width: u32,
+    height: u32,
+    pixels: Vec<[u8; 3]>,
+}
+
+impl Image {
+    /// Reads a binary PPM with a maximum value of 255, as written by [`Image::to_ppm`].
+    fn from_ppm(bytes: &[u8]) -> Option<Self> {
+        let mut fields = Vec::with_capacity(4);
+        let mut rest = bytes;
+        while fields.len() < 4 {
+            let start = rest.iter().position(|b| !b.is_ascii_whitespace())?;
+            rest = &rest[start..];
+            let end = rest.iter().position(|b| b.is_ascii_whitespace())?;
+            fields.push(std::str::from_utf8(&rest[..end]).ok()?);
+            rest = &rest[end..];
+        }
+        // Exactly one whitespace byte separates the header from the pixels
+        let rest = &rest[1..];
+
+        let [magic, width, height, max] = fields[..] else {
+            return None;
+        };
+        if magic != "P6" || max != "255" {
+            return None;
+        }
+        let width: u32 = width.parse().ok()?;
+        let height: u32 = height.parse().ok()?;
+        if rest.len() != (width * height * 3) as usize {
+            return None;
+        }
+
+        Some(Self {
+            width,
+            height,
+            pixels: rest
+                .chunks_exact(3)
+                .map(|pixel| [pixel[0], pixel[1], pixel[2]])
+                .collect(),
+        })
+    }
+
+    fn to_ppm(&self) -> Vec<u8> {
+        let mut bytes = format!("P6\n{} {}\n255\n", self.width, self.height).into_bytes();
+        bytes.extend(self.pixels.iter().flatten());
+        bytes
+    }
+
+    /// The fraction of pixels that differ from `other` by more than
+    /// [`CHANNEL_TOLERANCE`] in any channel, or None if the sizes differ.
+    fn different_fraction(&self, other: &Image) -> Option<f64> {
+        if (self.width, self.height) != (other.width, other.height) {
+            return None;
+        }
+
+        let different = self
+            .pixels
+            .iter()
+            .zip(&other.pixels)
+            .filter(|(a, b)| {
+                a.iter()
+                    .zip(b.iter())
+                    .any(|(a, b)| a.abs_diff(*b) > CHANNEL_TOLERANCE)
+            })
+            .count();
+        Some(different as f64 / self.pixels.len() as f64)
+    }
+}
+
+/// A headless context to render with.
+fn headless_context() -> HeadlessContext {
+    HeadlessContext::new().unwrap_or_else(|e| {
+        panic!(
+            "Couldn't make a headless OpenGL context for the golden-image tests: {e}\n\
+            Without a GPU, try again with LIBGL_ALWAYS_SOFTWARE=1."
+        )
+    })
+}
+
+/// Looking down at the origin from `distance` away, at a slight tilt
+/// so the orbits' inclinations show.
+fn camera(distance: f32) -> Camera {
+    Camera::new_perspective(
+        Viewport::new_at_origo(IMAGE_SIZE, IMAGE_SIZE),
+        Vec3::new(0.0, -0.3, 1.0).normalize() * distance,
+        Vec3::new(0.0, 0.0, 0.0),
+        Vec3::new(0.0, 1.0, 0.0),
+        Degrees { 0: 45.0 },
+        0.01,
+        1000.0,
+    )
+}
+
+/// Renders `objects` into an offscreen image.
+fn render(context: &Context, camera: &Camera, objects: &[&dyn Object]) -> Image {
+    let mut color = Texture2D::new_empty::<[u8; 4]>(
+        context,
+        IMAGE_SIZE,
+        IMAGE_SIZE,
+        Interpolation::Nearest,
+        Interpolation::Nearest,
+        None,
+        Wrapping::ClampToEdge,
+        Wrapping::ClampToEdge,
+    );
+    let mut depth = DepthTexture2D::new::<f32>(
+        context,
+        IMAGE_SIZE,
+        IMAGE_SIZE,
+        Wrapping::ClampToEdge,
+        Wrapping::ClampToEdge,
+    );
+    let top_light = DirectionalLight::new(context, 1.0, Srgba::WHITE, Vec3::new(0.0, -0.5, -0.5));
+    let ambient_light = AmbientLight::new(context, 0.02, Srgba::WHITE);
+    let lights: [&dyn Light; 2] = [&top_light, &ambient_light];
+
+    let pixels = RenderTarget::new(color.as_color_target(None), depth.as_depth_target())
+        .clear(ClearState::color_and_depth(0.0, 0.0, 0.0, 1.0, 1.0))
+        .render(camera, objects, &lights)
+        .read_color::<[u8; 4]>();
+
+    // GL reads the bottom row first
+    let pixels = pixels
+        .chunks_exact(IMAGE_SIZE as usize)
+        .rev()
+        .flatten()
+        .map(|&[r, g, b, _]| [r, g, b])
+        .collect();
+
+    Image {
+        width: IMAGE_SIZE,
+        height: IMAGE_SIZE,
+        pixels,
+    }
+}
+
+fn golden_path(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("src/gfx/goldens")
+        .join(format!("{name}.ppm"))
+}
+
+/// Compares `image` against the golden called `name`,
+/// or writes the golden instead if it's being updated.
+fn assert_matches_golden(name: &str, image: &Image) {
+    let path = golden_path(name);
+    let actual_path = path.with_extension("actual.ppm");
+
+    if std::env::var_os("UPDATE_GOLDENS").is_some_and(|value| value == "1") {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, image.to_ppm()).unwrap();
+        eprintln!("Wrote golden image {}", path.display());
+        return;
+    }
+
+    let Ok(bytes) = fs::read(&path) else {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&actual_path, image.to_ppm()).unwrap();
+        panic!(
+            "{name} has no golden at {}, see {}\n\
+            If the render looks right, write the goldens with UPDATE_GOLDENS=1 and commit them.",
+            path.display(),
+            actual_path.display()
+        );
+    };
+    let Some(golden) = Image::from_ppm(&bytes) else {
+        panic!(
+            "{} isn't a binary PPM with a maximum of 255",
+            path.display()
+        );
+    };
+
+    match image.different_fraction(&golden) {
+        Some(fraction) if fraction <= MAX_DIFFERENT_FRACTION => {
+            let _ = fs::remove_file(&actual_path);
+        }
+        different => {
+            fs::write(&actual_path, image.to_ppm()).unwrap();
+            match different {
+                Some(fraction) => panic!(
+                    "{name} differs from its golden in {:.2}% of pixels, see {}",
+                    fraction * 100.0,
+                    actual_path.display()
+                ),
+                None => panic!(
+                    "{name} is {}×{}, but its golden is {}×{}",
+                    image.width, image.height, golden.width, golden.height
+                ),
+            }
+        }
+    }
+}
+
+/// A circular, an eccentric, an inclined and a hyperbolic orbit,
+/// each with its own color and pattern, one of them half transparent.
+fn trajectory_scene(context: &Context, mode: TrajectoryMode) -> Vec<Trajectory> {
+    let orbits = [
+        (
+            Orbit::new(0.0, 1.0, 0.0, 0.0, 0.0, 0.0, SCENE_MU),
+            Srgba::new(255, 255, 255, 255),
+            LinePattern::Solid,
+        ),
+        (
+            Orbit::new(0.6, 0.8, 0.0, 1.0, 0.0, 0.0, SCENE_MU),
+            Srgba::new(255, 96, 64, 255),
+            LinePattern::Dashed,
+        ),
+        (
+            Orbit::new(0.2, 1.5, 0.8, 0.0, 0.5, 0.0, SCENE_MU),
+            Srgba::new(64, 192, 255, 128),
+            LinePattern::Solid,
+        ),
+        (
+            Orbit::new(1.5, 0.5, 0.0, 3.0, 0.0, 0.0, SCENE_MU),
+            Srgba::new(128, 255, 96, 255),
+            LinePattern::Dotted,
+        ),
+    ];
+
+    orbits
+        .iter()
+        .map(|(orbit, color, pattern)| {
+            Trajectory::new(
+                context,
+                orbit,
+                DVec3::ZERO,
+                1.0,
+                orbit.get_eccentric_anomaly_at_time(0.0),
+                None,
+                POINT_COUNT,
+                LINE_THICKNESS,
+                *color,
+                *pattern,
+                mode,
+            )
+        })
+        .collect()
+}
+
+fn render_trajectory_scene(mode: TrajectoryMode) -> Image {
+    let context = headless_context();
+    let trajectories = trajectory_scene(&context, mode);
+    let objects: Vec<&dyn Object> = trajectories.iter().map(|t| t as &dyn Object).collect();
+    render(&context, &camera(6.0), &objects)
+}
+
+#[test]
+#[ignore = "needs a headless OpenGL context"]
+fn shader_trajectories_match_golden() {
+    let image = render_trajectory_scene(TrajectoryMode::Shader);
+    assert_matches_golden("trajectories_shader", &image);
+}
+
+#[test]
+#[ignore = "needs a headless OpenGL context"]
+fn polyline_trajectories_match_golden() {
+    let image = render_trajectory_scene(TrajectoryMode::Polyline);
+    assert_matches_golden("trajectories_polyline", &image);
+}
+
+/// Unit spheres at distances spanning the level of detail cutoffs,
+/// each drawn with the mesh its size on screen picks.
+#[test]
+#[ignore = "needs a headless OpenGL context"]
+fn body_levels_of_detail_match_golden() {
+    let context = headless_context();
+    let camera = camera(20.0);
+    let camera_pos = camera.position();
+    let camera_pos = DVec3::new(
+        camera_pos.x as f64,
+        camera_pos.y as f64,
+        camera_pos.z as f64,
+    );
+
+    let radius = 1.0;
+    let centers = [
+        DVec3::new(-6.0, 4.0, 0.0),
+        DVec3::new(0.0, 4.0, -20.0),
+        DVec3::new(6.0, 4.0, -60.0),
+        DVec3::new(-6.0, -4.0, -150.0),
+        DVec3::new(6.0, -4.0, -400.0),
+    ];
+
+    let spheres: Vec<_> = centers
+        .iter()
+        .filter_map(|&center| {
+            let radial_size = get_radial_size(radius, center.distance(camera_pos));
+            let lod = get_lod_type(radial_size)?;
+            let mut mesh = Mesh::new(&context, &SPHERE_MESHES[lod]);
+            mesh.set_transformation(
+                Mat4::from_translation(Vec3::new(
+                    center.x as f32,
+                    center.y as f32,
+                    center.z as f32,
+                )) * Mat4::from_scale(radius as f32),
+            );
+            Some(Gm::new(
+                mesh,
+                PhysicalMaterial::new_opaque(
+                    &context,
+                    &CpuMaterial {
+                        albedo: Srgba::new(200, 160, 96, 255),
+                        ..Default::default()
+                    },
+                ),
+            ))
+        })
+        .collect();
+    let objects: Vec<&dyn Object> = spheres.iter().map(|gm| gm as &dyn Object).collect();
+
+    let image = render(&context, &camera, &objects);
+    assert_matches_golden("body_lods", &image);
+}
+
+/// Flat colors, including a half transparent one over an opaque one,
+/// to catch changes to how colors are converted and blended.
+#[test]
+#[ignore = "needs a headless OpenGL context"]
+fn body_colors_match_golden() {
+    let context = headless_context();
+
+    let colors = [
+        (Vec3::new(-1.2, 0.0, 0.0), Srgba::new(255, 0, 0, 255)),
+        (Vec3::new(1.2, 0.0, 0.0), Srgba::new(0, 128, 255, 255)),
+        (Vec3::new(0.0, 0.0, 1.5), Srgba::new(255, 255, 255, 128)),
+    ];
+    let spheres: Vec<_> = colors
+        .iter()
+        .map(|&(center, color)| {
+            let mut mesh = Mesh::new(&context, &SPHERE_MESHES[0]);
+            mesh.set_transformation(Mat4::from_translation(center));
+            let is_transparent = color.a < 255;
+            let material = ColorMaterial {
+                color,
+                texture: None,
+                render_states: RenderStates {
+                    cull: Cull::Back,
+                    blend: if is_transparent {
+                        Blend::TRANSPARENCY
+                    } else {
+                        Blend::Disabled
+                    },
+                    ..Default::default()
+                },
+                is_transparent,
+            };
+            Gm::new(mesh, material)
+        })
+        .collect();
+    let objects: Vec<&dyn Object> = spheres.iter().map(|gm| gm as &dyn Object).collect();
+
+    let image = render(&context, &camera(8.0), &objects);
+    assert_matches_golden("body_colors", &image);
+}
+
+#[test]
+fn ppm_round_trips() {
+    let image = Image {
+        width: 2,
+        height: 1,
+        pixels: vec![[1, 2, 3], [250, 251, 252]],
+    };
+    assert_eq!(Image::from_ppm(&image.to_ppm()), Some(image.clone()));
+
+    let mut nearby = image.clone();
+    nearby.pixels[0][0] += CHANNEL_TOLERANCE;
+    assert_eq!(image.different_fraction(&nearby), Some(0.0));
+    nearby.pixels[1][2] -= CHANNEL_TOLERANCE + 1;
+    assert_eq!(image.different_fraction(&nearby), Some(0.5));
+}
@@ -0,0 +1,185 @@
+use glam::{DVec2, DVec3};
+use three_d::{
+    AxisAlignedBoundingBox, Blend, ColorMapping, Context, Cull, EffectMaterialId, ElementBuffer,
+    Geometry, GeometryId, Material, MaterialType, Object, Program, RenderStates, Srgba, Vec2, Vec3,
+    VertexBuffer, Viewer, WriteMask, render_with_material,
+};
+
+use crate::gfx::trajectory::{SHADER_VERSION_HEADER, strip_version_header};
+
+const GRID_COLOR: Srgba = Srgba::new(160, 176, 208, 96);
+
+/// How far the grid reaches from its center, in major cells.
+const HALF_SIZE_IN_MAJOR_CELLS: f64 = 20.0;
+
+/// The spacing of the reference grid's major lines, in meters,
+/// for a camera this far from its focus.
+///
+/// Minor lines are a tenth of the major spacing apart,
+/// and fade out as the camera zooms out towards the next decade.
+///
+/// Returns: (major spacing, minor line opacity)
+pub(crate) fn grid_spacing(camera_distance: f64) -> (f64, f32) {
+    let decades = camera_distance.max(1e-3).log10();
+    let major = 10f64.powf(decades.floor());
+
+    (major, (1.0 - decades.fract()) as f32)
+}
+
+/// How far the grid reaches from its center, in render-world units,
+/// where there are `camera_scale` of them per meter.
+pub(crate) fn grid_half_size(camera_scale: f64) -> f64 {
+    let (major, _) = grid_spacing(camera_scale.recip());
+    HALF_SIZE_IN_MAJOR_CELLS * major * camera_scale
+}
+
+/// A decade-spaced reference grid on the ecliptic (the XY plane),
+/// centered under the camera's focus.
+pub(crate) struct ReferenceGrid {
+    context: Context,
+    corners: VertexBuffer<Vec3>,
+    element_buffer: ElementBuffer<u32>,
+    /// Under the render origin, in render-world space.
+    center: Vec3,
+    half_size: f32,
+    /// Between minor lines, in render-world units.
+    spacing: f32,
+    /// The center's offset from the last major line, in minor cells.
+    phase: Vec2,
+    minor_alpha: f32,
+}
+
+impl ReferenceGrid {
+    /// Creates the grid for the current camera.
+    ///
+    /// `camera_offset`: The simulation-space position at the render origin.
+    ///
+    /// `camera_scale`: Render-space units per meter.
+    pub(crate) fn new(context: &Context, camera_offset: DVec3, camera_scale: f64) -> Self {
+        let (major, minor_alpha) = grid_spacing(camera_scale.recip());
+        let minor = major / 10.0;
+
+        // Computed in f64, as the offset can be many orders of
+        // magnitude larger than the spacing
+        let phase =
+            (DVec2::new(camera_offset.x, camera_offset.y) / minor).rem_euclid(DVec2::splat(10.0));
+
+        let corners = [
+            Vec3::new(-1.0, -1.0, 0.0),
+            Vec3::new(1.0, -1.0, 0.0),
+            Vec3::new(1.0, 1.0, 0.0),
+            Vec3::new(-1.0, 1.0, 0.0),
+        ];
+
+        Self {
+            context: context.clone(),
+            corners: VertexBuffer::new_with_data(context, &corners),
+            element_buffer: ElementBuffer::new_with_data(context, &[0u32, 1, 2, 2, 3, 0]),
+            center: Vec3::new(0.0, 0.0, (-camera_offset.z * camera_scale) as f32),
+            half_size: grid_half_size(camera_scale) as f32,
+            spacing: (minor * camera_scale) as f32,
+            phase: Vec2::new(phase.x as f32, phase.y as f32),
+            minor_alpha,
+        }
+    }
+}
+
+const VERTEX_SHADER_SOURCE: &'static str =
+    strip_version_header(include_str!("shaders/grid.vert"), SHADER_VERSION_HEADER);
+
+const FRAGMENT_SHADER_SOURCE: &'static str =
+    strip_version_header(include_str!("shaders/grid.frag"), SHADER_VERSION_HEADER);
+
+impl Geometry for ReferenceGrid {
+    fn draw(&self, viewer: &dyn Viewer, program: &Program, render_states: RenderStates) {
+        program.use_uniform("u_proj_view", viewer.projection() * viewer.view());
+        program.use_uniform("u_center", self.center);
+        program.use_uniform("u_half_size", self.half_size);
+        program.use_vertex_attribute("a_position", &self.corners);
+
+        program.draw_elements(render_states, viewer.viewport(), &self.element_buffer);
+    }
+
+    fn vertex_shader_source(&self) -> String {
+        VERTEX_SHADER_SOURCE.to_owned()
+    }
+
+    fn id(&self) -> GeometryId {
+        GeometryId(0x6A1D)
+    }
+
+    fn render_with_material(
+        &self,
+        material: &dyn three_d::Material,
+        viewer: &dyn three_d::Viewer,
+        lights: &[&dyn three_d::Light],
+    ) {
+        render_with_material(&self.context, viewer, &self, material, lights);
+    }
+
+    fn render_with_effect(
+        &self,
+        _material: &dyn three_d::Effect,
+        _viewer: &dyn three_d::Viewer,
+        _lights: &[&dyn three_d::Light],
+        _color_texture: Option<three_d::ColorTexture>,
+        _depth_texture: Option<three_d::DepthTexture>,
+    ) {
+        panic!("Rendering the reference grid with effects isn't supported");
+    }
+
+    fn aabb(&self) -> AxisAlignedBoundingBox {
+        AxisAlignedBoundingBox::INFINITE
+    }
+}
+
+impl Material for ReferenceGrid {
+    fn fragment_shader_source(&self, _lights: &[&dyn three_d::Light]) -> String {
+        let mut source = ColorMapping::fragment_shader_source().to_owned();
+        source.push_str(FRAGMENT_SHADER_SOURCE);
+        source
+    }
+
+    fn id(&self) -> EffectMaterialId {
+        EffectMaterialId(0x6A1D)
+    }
+
+    fn use_uniforms(
+        &self,
+        program: &Program,
+        viewer: &dyn Viewer,
+        _lights: &[&dyn three_d::Light],
+    ) {
+        viewer.color_mapping().use_uniforms(program);
+        program.use_uniform("surface_color", GRID_COLOR.to_linear_srgb());
+        program.use_uniform("spacing", self.spacing);
+        program.use_uniform("phase", self.phase);
+        program.use_uniform("minor_alpha", self.minor_alpha);
+        program.use_uniform("half_size", self.half_size);
+    }
+
+    fn render_states(&self) -> RenderStates {
+        RenderStates {
+            blend: Blend::TRANSPARENCY,
+            // Seen from below as well as above
+            cull: Cull::None,
+            // Shouldn't hide the orbit lines behind it
+            write_mask: WriteMask::COLOR,
+            ..Default::default()
+        }
+    }
+
+    fn material_type(&self) -> MaterialType {
+        MaterialType::Transparent
+    }
+}
+
+impl Object for ReferenceGrid {
+    fn material_type(&self) -> MaterialType {
+        <Self as Material>::material_type(self)
+    }
+
+    fn render(&self, viewer: &dyn Viewer, _lights: &[&dyn three_d::Light]) {
+        render_with_material(&self.context, viewer, self, self, &[]);
+    }
+}
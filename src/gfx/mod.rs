@@ -1,4 +1,17 @@
 use crate::gui::PreviewBody;
+pub(crate) mod adaptive_quality;
+pub(crate) mod arrow;
 mod autoscaling_sprites;
-mod object_conversion;
-mod trajectory;
+pub(crate) mod depth_range;
+pub(crate) mod export;
+#[cfg(all(test, not(target_family = "wasm")))]
+mod golden;
+pub(crate) mod grid;
+pub(crate) mod object_conversion;
+pub(crate) mod palette;
+pub(crate) mod projection;
+pub(crate) mod render_scale;
+mod rings;
+pub(crate) mod sampled_path;
+pub(crate) mod trajectory;
+pub(crate) mod trajectory_cache;
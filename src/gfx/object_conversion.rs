@@ -1,8 +1,8 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::LazyLock;
 
 use glam::DVec3;
-use keplerian_sim::OrbitTrait;
+use keplerian_sim::{Orbit, OrbitTrait};
 use three_d::{
     Blend, ColorMaterial, Context, CpuMaterial, CpuMesh, Cull, Gm, InstancedMesh, Instances, Mat4,
     Mesh, Object, PhysicalMaterial, RenderStates, Srgba, Vec3, Vec4,
@@ -10,10 +10,29 @@ use three_d::{
 
 use crate::{
     Program,
-    gfx::{PreviewBody, trajectory::Trajectory},
+    cfg::CONFIG,
+    gfx::{
+        PreviewBody,
+        arrow::{
+            ACCELERATION_ARROW_COLOR, ACCELERATION_UNIT, ARROW_HEAD_RADIUS,
+            ARROW_LENGTH_PER_DECADE, ARROW_MESH, VELOCITY_ARROW_COLOR, VELOCITY_UNIT,
+            get_arrow_decades, get_arrow_matrix,
+        },
+        autoscaling_sprites::{AutoscalingSprites, DotMaterial},
+        grid::ReferenceGrid,
+        palette::{BodyStyle, StyleSettings},
+        projection::WorldProjector,
+        rings::{annulus_mesh, get_rings_matrix},
+        sampled_path::SampledPath,
+        trajectory::{self, Trajectory, TrajectoryMode},
+        trajectory_cache::TrajectoryCache,
+    },
+    metrics,
     sim::{
         body::Body,
-        universe::{BodyWrapper, Id},
+        occlusion,
+        relative_path::{RELATIVE_PATH_SAMPLES, relative_path_span, sample_relative_path},
+        universe::{BodyWrapper, HyperbolaRange, Id, Universe},
     },
 };
 
@@ -28,7 +47,8 @@ pub const LOD_SUBDIVS: [u32; LOD_LEVEL_COUNT] = [32, 24, 16, 12, 9, 7, 5, 3];
 ///
 /// If the radial size of a sphere `theta` >= a cutoff `c_i`,
 /// then subdivision index `i` should be used. If theta is less
-/// than all the cutoffs, then the sphere should not be rendered at all.
+/// than all the cutoffs, then the sphere is drawn as a dot instead,
+/// or not at all if "Dots for distant bodies" is off.
 pub const LOD_CUTOFFS: [f64; LOD_LEVEL_COUNT] =
     [0.25, 0.125, 0.062, 0.031, 0.015, 0.007, 0.002, 0.0005];
 
@@ -40,11 +60,20 @@ pub const LOD_CUTOFFS: [f64; LOD_LEVEL_COUNT] =
 /// far plane.
 pub const MAX_BODY_SCALED_DISTANCE: f64 = 1e4;
 
+/// How many pixels across a body too small for the coarsest sphere is drawn,
+/// so distant planets stay visible along with their orbit lines.
+pub const BODY_DOT_DIAMETER_PX: f32 = 3.0;
+
 /// The minimum camera radial size to consider rendering an orbit.
 /// If an orbit is smaller than this, it is ignored.
 /// This uses the semi-major axis length and parent–camera distance.
 pub const MIN_ORBIT_RADIAL_SIZE: f64 = 0.002;
 
+/// The camera radial size below which orbit lines start getting thinner,
+/// down to half their thickness, so that orbits crowded together
+/// when zoomed out far don't blend into a blob.
+pub const THIN_ORBIT_RADIAL_SIZE: f64 = 0.05;
+
 /// The maximum scaled periapsis to consider rendering an orbit.
 /// This is calculated by multiplying the periapsis by the camera scale.
 ///
@@ -53,7 +82,29 @@ pub const MIN_ORBIT_RADIAL_SIZE: f64 = 0.002;
 /// This specific value is gotten through trial and error.
 pub const MAX_ORBIT_SCALED_PERIAPSIS: f64 = 1e3;
 
-const fn get_lod_type(radial_size: f64) -> Option<usize> {
+/// How many points all orbit lines together get per frame.
+///
+/// When the visible orbits want more than this, their point counts are scaled
+/// down in proportion to their size on screen, so large universes zoomed out
+/// get coarser lines instead of slower frames.
+/// Every orbit still keeps at least [`MIN_ORBIT_POINT_COUNT`] points.
+pub const TRAJECTORY_POINT_BUDGET: u32 = 100_000;
+
+const DEFAULT_ORBIT_POINT_COUNT: u32 = 512;
+const MIN_ORBIT_POINT_COUNT: u32 = 16;
+const MAX_ORBIT_POINT_COUNT: u32 = 8192;
+
+/// The default for the "Hide distant subsystems" angle, in degrees.
+///
+/// Satellites of a body that looks smaller than this aren't drawn,
+/// along with their orbit lines, when the setting is on.
+pub const DEFAULT_SUBSYSTEM_CULL_ANGLE_DEG: f64 = 0.01;
+
+/// How bright bodies in another body's shadow are drawn,
+/// relative to their usual color.
+pub const ECLIPSE_BRIGHTNESS: f32 = 0.3;
+
+pub(super) const fn get_lod_type(radial_size: f64) -> Option<usize> {
     let mut i = 0;
     while i < LOD_LEVEL_COUNT {
         if radial_size >= LOD_CUTOFFS[i] {
@@ -110,8 +161,24 @@ impl<'a> IntoIterator for &'a PreviewScene {
 
 pub(crate) struct Scene {
     bodies: [Gm<InstancedMesh, PhysicalMaterial>; LOD_LEVEL_COUNT],
-    lines: Box<[Trajectory]>,
+    /// Bodies too small for the coarsest sphere.
+    dots: Option<Gm<AutoscalingSprites, DotMaterial>>,
+    /// Orbit lines, by the body they belong to.
+    lines: Box<[(Id, Trajectory)]>,
+    relative_path: Option<SampledPath>,
+    /// A moved body's old orbit line, fading out.
+    reparent_fade: Option<Trajectory>,
+    vectors: Option<Gm<InstancedMesh, ColorMaterial>>,
     preview: Option<PreviewScene>,
+    grid: Option<ReferenceGrid>,
+    rings: Box<[Gm<Mesh, ColorMaterial>]>,
+}
+
+impl Scene {
+    /// Takes the orbit lines back out, to keep them for the next frame.
+    pub(crate) fn into_lines(self) -> impl Iterator<Item = (Id, Trajectory)> {
+        self.lines.into_iter()
+    }
 }
 
 /// Converts a Gm into an abstract Object.
@@ -133,29 +200,68 @@ impl<'a> IntoIterator for &'a Scene {
     type Item = &'a dyn Object;
     type IntoIter = std::iter::Chain<
         std::iter::Chain<
-            std::iter::Map<
-                core::slice::Iter<'a, Gm<InstancedMesh, PhysicalMaterial>>,
-                fn(&'a Gm<InstancedMesh, PhysicalMaterial>) -> &'a dyn Object,
-            >,
-            std::iter::Map<core::slice::Iter<'a, Trajectory>, fn(&'a Trajectory) -> &'a dyn Object>,
-        >,
-        std::iter::Flatten<
-            std::iter::Map<
-                core::option::IntoIter<&'a PreviewScene>,
-                fn(
-                    &'a PreviewScene,
-                ) -> std::iter::Chain<
-                    std::iter::Map<
-                        core::option::Iter<'a, Gm<Mesh, ColorMaterial>>,
-                        fn(&'a Gm<Mesh, ColorMaterial>) -> &'a dyn Object,
+            std::iter::Chain<
+                std::iter::Chain<
+                    std::iter::Chain<
+                        std::iter::Chain<
+                            std::iter::Chain<
+                                std::iter::Map<
+                                    core::slice::Iter<'a, Gm<InstancedMesh, PhysicalMaterial>>,
+                                    fn(&'a Gm<InstancedMesh, PhysicalMaterial>) -> &'a dyn Object,
+                                >,
+                                std::iter::Map<
+                                    core::slice::Iter<'a, (Id, Trajectory)>,
+                                    fn(&'a (Id, Trajectory)) -> &'a dyn Object,
+                                >,
+                            >,
+                            std::iter::Chain<
+                                std::iter::Map<
+                                    core::option::Iter<'a, SampledPath>,
+                                    fn(&'a SampledPath) -> &'a dyn Object,
+                                >,
+                                std::iter::Map<
+                                    core::option::Iter<'a, Trajectory>,
+                                    fn(&'a Trajectory) -> &'a dyn Object,
+                                >,
+                            >,
+                        >,
+                        std::iter::Map<
+                            core::option::Iter<'a, Gm<InstancedMesh, ColorMaterial>>,
+                            fn(&'a Gm<InstancedMesh, ColorMaterial>) -> &'a dyn Object,
+                        >,
                     >,
-                    std::iter::Map<
-                        core::option::Iter<'a, Trajectory>,
-                        fn(&'a Trajectory) -> &'a dyn Object,
+                    std::iter::Flatten<
+                        std::iter::Map<
+                            core::option::IntoIter<&'a PreviewScene>,
+                            fn(
+                                &'a PreviewScene,
+                            ) -> std::iter::Chain<
+                                std::iter::Map<
+                                    core::option::Iter<'a, Gm<Mesh, ColorMaterial>>,
+                                    fn(&'a Gm<Mesh, ColorMaterial>) -> &'a dyn Object,
+                                >,
+                                std::iter::Map<
+                                    core::option::Iter<'a, Trajectory>,
+                                    fn(&'a Trajectory) -> &'a dyn Object,
+                                >,
+                            >,
+                        >,
                     >,
                 >,
+                std::iter::Map<
+                    core::option::Iter<'a, ReferenceGrid>,
+                    fn(&'a ReferenceGrid) -> &'a dyn Object,
+                >,
+            >,
+            std::iter::Map<
+                core::slice::Iter<'a, Gm<Mesh, ColorMaterial>>,
+                fn(&'a Gm<Mesh, ColorMaterial>) -> &'a dyn Object,
             >,
         >,
+        std::iter::Map<
+            core::option::Iter<'a, Gm<AutoscalingSprites, DotMaterial>>,
+            fn(&'a Gm<AutoscalingSprites, DotMaterial>) -> &'a dyn Object,
+        >,
     >;
     fn into_iter(self) -> Self::IntoIter {
         self.bodies
@@ -167,8 +273,22 @@ impl<'a> IntoIterator for &'a Scene {
             .chain(
                 self.lines
                     .iter()
-                    .map((|t| t) as fn(&'a Trajectory) -> &'a dyn Object),
+                    .map((|(_, t)| t) as fn(&'a (Id, Trajectory)) -> &'a dyn Object),
             )
+            .chain(
+                self.relative_path
+                    .iter()
+                    .map((|p| p) as fn(&'a SampledPath) -> &'a dyn Object)
+                    .chain(
+                        self.reparent_fade
+                            .iter()
+                            .map((|t| t) as fn(&'a Trajectory) -> &'a dyn Object),
+                    ),
+            )
+            .chain(self.vectors.iter().map(
+                gm_to_object::<InstancedMesh, ColorMaterial>
+                    as fn(&Gm<InstancedMesh, ColorMaterial>) -> &dyn Object,
+            ))
             .chain(
                 self.preview
                     .as_ref()
@@ -179,13 +299,154 @@ impl<'a> IntoIterator for &'a Scene {
                     )
                     .flatten(),
             )
+            .chain(
+                self.grid
+                    .iter()
+                    .map((|g| g) as fn(&'a ReferenceGrid) -> &'a dyn Object),
+            )
+            .chain(self.rings.iter().map(
+                gm_to_object::<Mesh, ColorMaterial> as fn(&Gm<Mesh, ColorMaterial>) -> &dyn Object,
+            ))
+            .chain(self.dots.iter().map(
+                gm_to_object::<AutoscalingSprites, DotMaterial>
+                    as fn(&Gm<AutoscalingSprites, DotMaterial>) -> &dyn Object,
+            ))
     }
 }
 
-fn get_radial_size(radius: f64, distance: f64) -> f64 {
+/// An orbit line that's visible this frame, waiting to be built.
+struct OrbitLine<'a> {
+    /// The body whose orbit this is.
+    id: Id,
+    orbit: &'a Orbit,
+    parent_pos_premultiplied: DVec3,
+    eccentric_anomaly: f64,
+    hyperbola_bounds: Option<(f64, f64)>,
+    point_count: u32,
+    thickness: f32,
+    style: BodyStyle,
+    mode: TrajectoryMode,
+}
+
+impl OrbitLine<'_> {
+    fn build(&self, context: &Context, camera_scale: f64) -> Trajectory {
+        Trajectory::new(
+            context,
+            self.orbit,
+            self.parent_pos_premultiplied,
+            camera_scale,
+            self.eccentric_anomaly,
+            self.hyperbola_bounds,
+            self.point_count,
+            self.thickness,
+            self.style.color,
+            self.style.pattern,
+            self.mode,
+        )
+    }
+}
+
+/// The eccentric anomalies a body's hyperbolic orbit line is drawn between,
+/// or None to draw a stretch around the body.
+pub(super) fn hyperbola_bounds(universe: &Universe, wrapper: &BodyWrapper) -> Option<(f64, f64)> {
+    let orbit = wrapper
+        .body
+        .orbit
+        .as_ref()
+        .filter(|o| o.get_eccentricity() > 1.0)?;
+
+    let end = match wrapper.hyperbola_range {
+        HyperbolaRange::AroundBody => return None,
+        HyperbolaRange::WithinParentSoi => {
+            let soi = universe
+                .get_soi_radius(wrapper.relations.parent?)
+                .filter(|soi| soi.is_finite())?;
+            trajectory::hyperbolic_anomaly_at_radius(orbit, soi)?
+        }
+        HyperbolaRange::TrueAnomaly(true_anomaly) => {
+            trajectory::hyperbolic_anomaly_at_true_anomaly(orbit.get_eccentricity(), true_anomaly)
+        }
+    };
+
+    Some((-end, end))
+}
+
+/// Scales the lines' point counts down to fit in `budget`,
+/// if they don't already. Returns whether they had to be.
+///
+/// Every line keeps its minimum, and the rest of the budget is shared out
+/// in proportion to how many more points each line wanted, which follows
+/// its size on screen.
+fn fit_point_budget(lines: &mut [OrbitLine], budget: u32) -> bool {
+    let wanted: u64 = lines.iter().map(|line| line.point_count as u64).sum();
+    if wanted <= budget as u64 {
+        return false;
+    }
+
+    let minimum = lines.len() as u64 * MIN_ORBIT_POINT_COUNT as u64;
+    let spare = (budget as u64).saturating_sub(minimum);
+    let extra = wanted.saturating_sub(minimum);
+    let factor = if extra == 0 {
+        0.0
+    } else {
+        spare as f64 / extra as f64
+    };
+
+    for line in lines {
+        let extra = line.point_count.saturating_sub(MIN_ORBIT_POINT_COUNT);
+        line.point_count = MIN_ORBIT_POINT_COUNT + (extra as f64 * factor) as u32;
+    }
+
+    true
+}
+
+pub(super) fn get_radial_size(radius: f64, distance: f64) -> f64 {
     2.0 * radius / distance
 }
 
+/// The bodies below a body that looks smaller than `min_size` radians from `viewpoint`,
+/// in the same scaled space as `position_map`.
+///
+/// Bodies marked "Always show" are kept along with everything below them,
+/// even under a hidden body, and `focused` is always kept.
+fn culled_subsystems(
+    universe: &Universe,
+    position_map: &HashMap<Id, DVec3>,
+    viewpoint: DVec3,
+    min_size: f64,
+    focused: Id,
+) -> HashSet<Id> {
+    let mut hidden = HashSet::new();
+
+    for (&id, wrapper) in universe.get_bodies() {
+        if wrapper.relations.satellites.is_empty() || wrapper.always_show {
+            continue;
+        }
+        let Some(position) = position_map.get(&id) else {
+            continue;
+        };
+        let distance = (*position - viewpoint).length();
+        if get_radial_size(wrapper.body.radius, distance) >= min_size {
+            continue;
+        }
+
+        let mut stack = wrapper.relations.satellites.clone();
+        while let Some(satellite) = stack.pop() {
+            let Some(satellite_wrapper) = universe.get_body(satellite) else {
+                continue;
+            };
+            // Checked through `insert` too, so a broken hierarchy can't loop forever
+            if satellite_wrapper.always_show || !hidden.insert(satellite) {
+                continue;
+            }
+            stack.extend(&satellite_wrapper.relations.satellites);
+        }
+    }
+
+    hidden.remove(&focused);
+    hidden
+}
+
 fn get_matrix(position: DVec3, radius: f64) -> Mat4 {
     // let DVec3 { x, y, z } = position;
     let (x, y, z) = (position.x as f32, position.y as f32, position.z as f32);
@@ -197,14 +458,28 @@ fn get_matrix(position: DVec3, radius: f64) -> Mat4 {
         w: Vec4::new(x, y, z, 1.0),
     }
 }
+
+/// Bodies too small on screen for the coarsest sphere,
+/// which are drawn as dots instead.
+#[derive(Default)]
+struct BodyDots {
+    /// In render-world space.
+    centers: Vec<Vec3>,
+    colors: Vec<Srgba>,
+}
+
 fn add_body_instance(
     id: &Id,
     body_wrapper: &BodyWrapper,
+    color: Srgba,
     camera_offset: DVec3,
     camera_pos: DVec3,
     camera_scale: f64,
     position_map: &HashMap<Id, DVec3>,
+    eclipsed: &HashSet<Id>,
     instances_arr: &mut [Instances; LOD_LEVEL_COUNT],
+    lod_bias: usize,
+    dots: Option<&mut BodyDots>,
 ) {
     let body = &body_wrapper.body;
     let position = match position_map.get(id) {
@@ -218,74 +493,306 @@ fn add_body_instance(
         // Distance in render-worldspace too large, may flicker
         return;
     }
+    let color = if eclipsed.contains(id) {
+        dim_color(color)
+    } else {
+        color
+    };
     let lod_group = match get_lod_type(size) {
         Some(l) => l,
-        None => return,
+        None => {
+            if let Some(dots) = dots {
+                let center = position * camera_scale;
+                dots.centers
+                    .push(Vec3::new(center.x as f32, center.y as f32, center.z as f32));
+                dots.colors.push(color);
+            }
+            return;
+        }
     };
+    let lod_group = (lod_group + lod_bias).min(LOD_LEVEL_COUNT - 1);
     let matrix = get_matrix(position * camera_scale, body.radius * camera_scale);
     let instances = &mut instances_arr[lod_group];
     instances.transformations.push(matrix);
 
     if let Some(colors) = &mut instances.colors {
-        colors.push(body.color);
+        colors.push(color);
+    }
+}
+
+/// Scales an alpha by how far a line has faded in, from 0 to 1.
+fn fade_alpha(alpha: u8, amount: f64) -> u8 {
+    (alpha as f64 * amount.clamp(0.0, 1.0)).round() as u8
+}
+
+fn dim_color(color: Srgba) -> Srgba {
+    let dim = |channel: u8| (channel as f32 * ECLIPSE_BRIGHTNESS) as u8;
+
+    Srgba {
+        r: dim(color.r),
+        g: dim(color.g),
+        b: dim(color.b),
+        a: color.a,
     }
 }
 
 fn add_body_instances(
-    body_map: &HashMap<Id, BodyWrapper>,
+    universe: &Universe,
+    styles: StyleSettings,
     camera_offset: DVec3,
     camera_pos: DVec3,
     camera_scale: f64,
     position_map: &HashMap<Id, DVec3>,
+    eclipsed: &HashSet<Id>,
+    hidden: &HashSet<Id>,
     instances_arr: &mut [Instances; LOD_LEVEL_COUNT],
+    lod_bias: usize,
+    mut dots: Option<&mut BodyDots>,
 ) {
-    for (id, body_wrapper) in body_map {
+    for (id, body_wrapper) in universe.get_bodies() {
+        if hidden.contains(id) {
+            continue;
+        }
+        let color = styles
+            .body_style(universe, *id)
+            .map(|style| style.color)
+            .unwrap_or(body_wrapper.body.color);
+
         add_body_instance(
             id,
             body_wrapper,
+            color,
             camera_offset,
             camera_pos,
             camera_scale,
             position_map,
+            eclipsed,
             instances_arr,
+            lod_bias,
+            dots.as_deref_mut(),
         );
     }
 }
 
 impl Program {
-    pub(crate) fn to_objects(&self, position_map: &HashMap<Id, DVec3>) -> Scene {
-        let camera_offset = *position_map
-            .get(&self.sim_state.focused_body())
+    /// Gets the simulation-space position at the render origin.
+    pub(crate) fn camera_offset(&self, position_map: &HashMap<Id, DVec3>) -> DVec3 {
+        *position_map
+            .get(&self.session().sim_state.focused_body())
             .unwrap_or(&DVec3::ZERO)
-            + self.sim_state.focus_offset;
+            + self.session().sim_state.focus_offset
+    }
+
+    pub(crate) fn world_projector(
+        &self,
+        position_map: &HashMap<Id, DVec3>,
+        pixels_per_point: f32,
+    ) -> WorldProjector {
+        WorldProjector::new(
+            &self.session().camera,
+            self.camera_offset(position_map),
+            1.0 / self.session().control.current_distance,
+            pixels_per_point,
+        )
+    }
 
-        let camera_pos = self.camera.position();
+    /// Builds this frame's scene, reusing the orbit lines in `cache`.
+    ///
+    /// Hand the lines back with [`Scene::into_lines`] once it's drawn.
+    pub(crate) fn to_objects(
+        &self,
+        position_map: &HashMap<Id, DVec3>,
+        device_pixel_ratio: f32,
+        cache: &mut TrajectoryCache,
+    ) -> Scene {
+        let camera_offset = self.camera_offset(position_map);
+
+        let camera_pos = self.session().camera.position();
         let camera_pos = DVec3::new(
             camera_pos.x as f64,
             camera_pos.y as f64,
             camera_pos.z as f64,
         );
 
-        let camera_scale = 1.0 / self.control.current_distance;
+        let camera_scale = 1.0 / self.session().control.current_distance;
+        let line_scale = Self::line_scale(device_pixel_ratio);
+        let hidden = self.hidden_subsystems(camera_offset, camera_pos, camera_scale, position_map);
+
+        let show_dots = CONFIG
+            .try_lock()
+            .map(|cfg| cfg.distant_body_dots.get())
+            .unwrap_or(true);
+        let mut dots = show_dots.then(BodyDots::default);
+        let bodies = self.generate_body_gms(
+            camera_offset,
+            camera_pos,
+            camera_scale,
+            position_map,
+            &hidden,
+            dots.as_mut(),
+        );
 
         Scene {
-            bodies: self.generate_body_gms(camera_offset, camera_pos, camera_scale, position_map),
-            lines: self.generate_orbit_lines(camera_offset, camera_pos, camera_scale, position_map),
+            bodies,
+            dots: dots
+                .filter(|dots| !dots.centers.is_empty())
+                .map(|dots| self.generate_body_dots(dots, device_pixel_ratio)),
+            lines: self.generate_orbit_lines(
+                camera_offset,
+                camera_pos,
+                camera_scale,
+                position_map,
+                line_scale,
+                &hidden,
+                cache,
+            ),
+            relative_path: self.generate_relative_path(
+                camera_offset,
+                camera_scale,
+                position_map,
+                line_scale,
+            ),
+            reparent_fade: self.generate_reparent_fade(
+                camera_offset,
+                camera_pos,
+                camera_scale,
+                position_map,
+                line_scale,
+                &hidden,
+            ),
+            vectors: self.generate_vector_arrows(
+                camera_offset,
+                camera_pos,
+                camera_scale,
+                position_map,
+            ),
             preview: self.generate_preview_scene(
                 camera_offset,
                 camera_pos,
                 camera_scale,
                 position_map,
+                line_scale,
+            ),
+            grid: self.generate_grid(camera_offset, camera_scale),
+            rings: self.generate_rings(
+                camera_offset,
+                camera_pos,
+                camera_scale,
+                position_map,
+                &hidden,
             ),
         }
     }
 
+    /// The bodies to leave out of the scene because a body they orbit,
+    /// directly or further up, looks smaller than the "Hide distant subsystems" angle.
+    ///
+    /// Bodies marked "Always show" keep themselves and their satellites,
+    /// and the focused body is never hidden.
+    fn hidden_subsystems(
+        &self,
+        camera_offset: DVec3,
+        camera_pos: DVec3,
+        camera_scale: f64,
+        position_map: &HashMap<Id, DVec3>,
+    ) -> HashSet<Id> {
+        let (enabled, angle) = CONFIG
+            .try_lock()
+            .map(|cfg| {
+                (
+                    cfg.hide_distant_subsystems.get(),
+                    cfg.subsystem_cull_angle.get(),
+                )
+            })
+            .unwrap_or((false, DEFAULT_SUBSYSTEM_CULL_ANGLE_DEG));
+
+        if !enabled {
+            return HashSet::new();
+        }
+
+        let sim_state = &self.session().sim_state;
+        culled_subsystems(
+            &sim_state.universe,
+            position_map,
+            camera_offset + camera_pos / camera_scale,
+            angle.to_radians(),
+            sim_state.focused_body(),
+        )
+    }
+
+    fn generate_grid(&self, camera_offset: DVec3, camera_scale: f64) -> Option<ReferenceGrid> {
+        let show_grid = CONFIG
+            .try_lock()
+            .map(|cfg| cfg.show_grid.get())
+            .unwrap_or(false);
+
+        show_grid.then(|| ReferenceGrid::new(&self.context, camera_offset, camera_scale))
+    }
+
+    fn generate_rings(
+        &self,
+        camera_offset: DVec3,
+        camera_pos: DVec3,
+        camera_scale: f64,
+        position_map: &HashMap<Id, DVec3>,
+        hidden: &HashSet<Id>,
+    ) -> Box<[Gm<Mesh, ColorMaterial>]> {
+        let universe = &self.session().sim_state.universe;
+
+        universe
+            .get_bodies()
+            .iter()
+            .filter(|(id, _)| !hidden.contains(id))
+            .filter_map(|(id, body_wrapper)| {
+                let rings = body_wrapper.rings.as_ref()?;
+                let valid = rings.outer_radius > 0.0 && rings.inner_radius < rings.outer_radius;
+                if !valid {
+                    return None;
+                }
+
+                let position = position_map.get(id)? - camera_offset;
+                let distance = (position - camera_pos / camera_scale).length();
+
+                if distance * camera_scale > MAX_BODY_SCALED_DISTANCE {
+                    // Distance in render-worldspace too large, may flicker
+                    return None;
+                }
+                // Too small to see, skip
+                get_lod_type(get_radial_size(rings.outer_radius, distance))?;
+
+                let inner_ratio = (rings.inner_radius / rings.outer_radius).max(0.0) as f32;
+                let mut mesh = Mesh::new(&self.context, &annulus_mesh(inner_ratio));
+                mesh.set_transformation(get_rings_matrix(
+                    rings,
+                    position * camera_scale,
+                    rings.outer_radius * camera_scale,
+                ));
+
+                let material = ColorMaterial {
+                    color: rings.color,
+                    texture: None,
+                    render_states: RenderStates {
+                        // Seen from below as well as above
+                        cull: Cull::None,
+                        blend: Blend::TRANSPARENCY,
+                        ..Default::default()
+                    },
+                    is_transparent: true,
+                };
+
+                Some(Gm::new(mesh, material))
+            })
+            .collect()
+    }
+
     fn generate_body_gms(
         &self,
         camera_offset: DVec3,
         camera_pos: DVec3,
         camera_scale: f64,
         position_map: &HashMap<Id, DVec3>,
+        hidden: &HashSet<Id>,
+        dots: Option<&mut BodyDots>,
     ) -> [Gm<InstancedMesh, PhysicalMaterial>; LOD_LEVEL_COUNT] {
         let mut instances_arr: [Instances; LOD_LEVEL_COUNT] = core::array::from_fn(|_| Instances {
             transformations: Vec::new(),
@@ -293,17 +800,52 @@ impl Program {
             texture_transformations: None,
         });
 
-        let body_map = self.sim_state.universe.get_bodies();
+        let universe = &self.session().sim_state.universe;
+
+        let dim_eclipsed = CONFIG
+            .try_lock()
+            .map(|cfg| cfg.dim_eclipsed_bodies.get())
+            .unwrap_or(true);
+        let eclipsed = if dim_eclipsed {
+            occlusion::eclipsed_bodies(
+                universe,
+                position_map,
+                self.session().sim_state.focused_body(),
+                hidden,
+            )
+        } else {
+            HashSet::new()
+        };
 
         add_body_instances(
-            body_map,
+            universe,
+            StyleSettings::current(),
             camera_offset,
             camera_pos,
             camera_scale,
             position_map,
+            &eclipsed,
+            hidden,
             &mut instances_arr,
+            self.adaptive_quality.lod_bias(),
+            dots,
         );
 
+        if self.session().sim_state.safe_mode {
+            // Draw everything with the cheapest sphere mesh
+            let (coarsest, rest) = instances_arr
+                .split_last_mut()
+                .expect("there should be at least one LOD level");
+            for instances in rest {
+                coarsest
+                    .transformations
+                    .append(&mut instances.transformations);
+                if let (Some(dst), Some(src)) = (&mut coarsest.colors, &mut instances.colors) {
+                    dst.append(src);
+                }
+            }
+        }
+
         let mut material = PhysicalMaterial::new_opaque(&self.context, &CpuMaterial::default());
 
         material.render_states = RenderStates {
@@ -319,55 +861,286 @@ impl Program {
         })
     }
 
+    fn generate_body_dots(
+        &self,
+        dots: BodyDots,
+        device_pixel_ratio: f32,
+    ) -> Gm<AutoscalingSprites, DotMaterial> {
+        Gm::new(
+            AutoscalingSprites::new_with_pixel_size(
+                &self.context,
+                &dots.centers,
+                &dots.colors,
+                BODY_DOT_DIAMETER_PX * device_pixel_ratio,
+            ),
+            DotMaterial,
+        )
+    }
+
+    fn generate_vector_arrows(
+        &self,
+        camera_offset: DVec3,
+        camera_pos: DVec3,
+        camera_scale: f64,
+        position_map: &HashMap<Id, DVec3>,
+    ) -> Option<Gm<InstancedMesh, ColorMaterial>> {
+        let mode = self.session().sim_state.vector_overlay;
+        if !mode.shows_velocity() {
+            return None;
+        }
+
+        let universe = &self.session().sim_state.universe;
+        let velocities = universe.get_all_body_velocities();
+        let accelerations = if mode.shows_acceleration() {
+            universe.get_all_body_accelerations()
+        } else {
+            HashMap::new()
+        };
+
+        let mut instances = Instances {
+            transformations: Vec::new(),
+            colors: Some(Vec::new()),
+            texture_transformations: None,
+        };
+
+        for (id, position) in position_map {
+            let position = position - camera_offset;
+            let distance = (position - camera_pos / camera_scale).length();
+
+            if distance * camera_scale > MAX_BODY_SCALED_DISTANCE {
+                // Distance in render-worldspace too large, may flicker
+                continue;
+            }
+
+            let scaled_pos = position * camera_scale;
+            let scaled_distance = distance * camera_scale;
+            let radius = ARROW_HEAD_RADIUS * scaled_distance;
+
+            let vectors = [
+                (velocities.get(id), VELOCITY_UNIT, VELOCITY_ARROW_COLOR),
+                (
+                    accelerations.get(id),
+                    ACCELERATION_UNIT,
+                    ACCELERATION_ARROW_COLOR,
+                ),
+            ];
+
+            for (vector, unit, color) in vectors {
+                let Some(&vector) = vector else {
+                    continue;
+                };
+                let length = get_arrow_decades(vector.length(), unit)
+                    * ARROW_LENGTH_PER_DECADE
+                    * scaled_distance;
+                let Some(matrix) = get_arrow_matrix(scaled_pos, vector, length, radius) else {
+                    continue;
+                };
+
+                instances.transformations.push(matrix);
+                if let Some(colors) = &mut instances.colors {
+                    colors.push(color);
+                }
+            }
+        }
+
+        if instances.transformations.is_empty() {
+            return None;
+        }
+
+        let material = ColorMaterial {
+            color: Srgba::WHITE,
+            texture: None,
+            render_states: RenderStates {
+                cull: Cull::Back,
+                ..Default::default()
+            },
+            is_transparent: false,
+        };
+
+        Some(Gm::new(
+            InstancedMesh::new(&self.context, &instances, &ARROW_MESH),
+            material,
+        ))
+    }
+
+    /// The trajectory mode to render with, taking the graphics setting
+    /// and safe mode into account.
+    fn trajectory_mode(&self) -> TrajectoryMode {
+        let forced = self.session().sim_state.safe_mode
+            || CONFIG
+                .try_lock()
+                .map(|cfg| cfg.polyline_orbits.get())
+                .unwrap_or(false);
+
+        if forced {
+            TrajectoryMode::Polyline
+        } else {
+            self.trajectory_mode
+        }
+    }
+
+    /// How much to multiply line thicknesses by, taking the screen's
+    /// pixel density and the line width setting into account.
+    fn line_scale(device_pixel_ratio: f32) -> f32 {
+        let multiplier = CONFIG
+            .try_lock()
+            .map(|cfg| cfg.line_width_multiplier.get())
+            .unwrap_or(1.0);
+
+        device_pixel_ratio * multiplier
+    }
+
     const LINE_THICKNESS: f32 = 2.0;
     const FOCUSED_THICKNESS: f32 = Self::LINE_THICKNESS * 1.5;
 
+    /// The focused body's path as seen from the body picked in the
+    /// "Relative path" window, drawn around where that body is now.
+    ///
+    /// Covers one repeat of the relative motion, half of it
+    /// before the current time and half after.
+    fn generate_relative_path(
+        &self,
+        camera_offset: DVec3,
+        camera_scale: f64,
+        position_map: &HashMap<Id, DVec3>,
+        line_scale: f32,
+    ) -> Option<SampledPath> {
+        let sim_state = &self.session().sim_state;
+        let universe = &sim_state.universe;
+        let observer = sim_state.relative_path_observer?;
+        let target = sim_state.focused_body();
+        if observer == target {
+            return None;
+        }
+
+        let span = relative_path_span(universe, target, observer)?;
+        let start = universe.time - span / 2.0;
+        let offsets = sample_relative_path(
+            universe,
+            target,
+            observer,
+            start,
+            span,
+            RELATIVE_PATH_SAMPLES,
+        )?;
+
+        let anchor = *position_map.get(&observer)? - camera_offset;
+        let points: Vec<Vec3> = offsets
+            .into_iter()
+            .map(|offset| {
+                let point = (anchor + offset) * camera_scale;
+                Vec3::new(point.x as f32, point.y as f32, point.z as f32)
+            })
+            .collect();
+
+        let style = StyleSettings::current().body_style(universe, target)?;
+
+        SampledPath::new(
+            &self.context,
+            &points,
+            RELATIVE_PATH_SAMPLES / 2,
+            line_scale * Self::LINE_THICKNESS,
+            style.color,
+        )
+    }
+
     fn generate_orbit_lines(
         &self,
         camera_offset: DVec3,
         camera_pos: DVec3,
         camera_scale: f64,
         position_map: &HashMap<Id, DVec3>,
-    ) -> Box<[Trajectory]> {
-        self.sim_state
-            .universe
+        line_scale: f32,
+        hidden: &HashSet<Id>,
+        cache: &mut TrajectoryCache,
+    ) -> Box<[(Id, Trajectory)]> {
+        let mode = self.trajectory_mode();
+        let styles = StyleSettings::current();
+        let universe = &self.session().sim_state.universe;
+        let fade = &self.session().sim_state.reparent_fade;
+
+        let mut lines: Vec<OrbitLine> = universe
             .get_bodies()
             .iter()
+            .filter(|(id, _)| !hidden.contains(id))
             .filter_map(|(&id, body_wrapper)| {
-                Self::generate_orbit_line(
-                    &self.context,
+                let mut style = styles.body_style(universe, id)?;
+                if let Some(fade) = fade
+                    && fade.body_id == id
+                {
+                    style.color.a = fade_alpha(style.color.a, fade.progress);
+                }
+
+                Self::plan_orbit_line(
+                    mode,
+                    id,
                     &body_wrapper.body,
+                    style,
                     body_wrapper.relations.parent,
+                    hyperbola_bounds(universe, body_wrapper),
                     camera_offset,
                     camera_pos,
                     camera_scale,
                     position_map,
-                    self.sim_state.universe.time,
-                    if id == self.sim_state.focused_body() {
-                        Self::FOCUSED_THICKNESS
-                    } else {
-                        Self::LINE_THICKNESS
-                    },
+                    body_wrapper
+                        .frozen_at
+                        .unwrap_or(self.session().sim_state.universe.time),
+                    line_scale
+                        * if id == self.session().sim_state.focused_body() {
+                            Self::FOCUSED_THICKNESS
+                        } else {
+                            Self::LINE_THICKNESS
+                        },
                 )
             })
+            .collect();
+
+        let limited = fit_point_budget(&mut lines, self.adaptive_quality.point_budget());
+        let total_points = lines.iter().map(|line| line.point_count).sum();
+        metrics::record_trajectory_points(total_points, limited);
+
+        lines
+            .into_iter()
+            .map(|line| {
+                let trajectory = match cache.take(line.id) {
+                    // Reuse last frame's buffers, only refilling what changed
+                    Some(mut trajectory) if trajectory.mode() == line.mode => {
+                        trajectory.set_hyperbola_bounds(line.hyperbola_bounds);
+                        trajectory.update_from_orbit(
+                            line.orbit,
+                            line.parent_pos_premultiplied,
+                            camera_scale,
+                        );
+                        trajectory.set_point_count(line.point_count);
+                        trajectory.set_eccentric_anomaly(line.eccentric_anomaly);
+                        trajectory.thickness = line.thickness;
+                        trajectory.color = line.style.color;
+                        trajectory.pattern = line.style.pattern;
+                        trajectory
+                    }
+                    _ => line.build(&self.context, camera_scale),
+                };
+                (line.id, trajectory)
+            })
             .collect()
     }
 
-    fn generate_orbit_line(
-        context: &Context,
-        body: &Body,
+    /// Works out how to draw a body's orbit line, without building it yet,
+    /// so the point counts can be fitted into the budget first.
+    fn plan_orbit_line<'a>(
+        mode: TrajectoryMode,
+        id: Id,
+        body: &'a Body,
+        style: BodyStyle,
         parent_id: Option<Id>,
+        hyperbola_bounds: Option<(f64, f64)>,
         camera_offset: DVec3,
         camera_pos: DVec3,
         camera_scale: f64,
         position_map: &HashMap<Id, DVec3>,
         time: f64,
         thickness: f32,
-    ) -> Option<Trajectory> {
-        const DEFAULT_POINT_COUNT: u32 = 512;
-        const MIN_POINT_COUNT: u32 = 16;
-        const MAX_POINT_COUNT: u32 = 8192;
-
+    ) -> Option<OrbitLine<'a>> {
         let orbit = match &body.orbit {
             Some(o) => o,
             None => return None,
@@ -380,10 +1153,6 @@ impl Program {
         let parent_offset = parent_pos - camera_offset;
 
         let multiplied_offset = parent_offset * camera_scale;
-        let multiplied_offset_s = {
-            let v = multiplied_offset;
-            Vec3::new(v.x as f32, v.y as f32, v.z as f32)
-        };
 
         let eccentric_anomaly = orbit.get_eccentric_anomaly_at_time(time);
 
@@ -395,7 +1164,7 @@ impl Program {
             return None;
         }
 
-        let point_count = if orbit.get_eccentricity() < 1.0 {
+        let (point_count, thickness) = if orbit.get_eccentricity() < 1.0 {
             let semi_major_axis = orbit.get_semi_major_axis();
             let sma_size = get_radial_size(semi_major_axis, parent_distance_to_camera);
             if sma_size < MIN_ORBIT_RADIAL_SIZE {
@@ -403,23 +1172,107 @@ impl Program {
                 return None;
             }
 
-            (sma_size * DEFAULT_POINT_COUNT as f64)
+            let point_count = (sma_size * DEFAULT_ORBIT_POINT_COUNT as f64)
                 .abs()
-                .clamp(MIN_POINT_COUNT as f64, MAX_POINT_COUNT as f64) as u32
+                .clamp(MIN_ORBIT_POINT_COUNT as f64, MAX_ORBIT_POINT_COUNT as f64)
+                as u32;
+            let thinning = (sma_size / THIN_ORBIT_RADIAL_SIZE).clamp(0.5, 1.0) as f32;
+
+            (point_count, thickness * thinning)
         } else {
-            DEFAULT_POINT_COUNT
+            (DEFAULT_ORBIT_POINT_COUNT, thickness)
         };
 
-        Some(Trajectory::new(
-            context,
+        let precise_orbits = CONFIG
+            .try_lock()
+            .map(|cfg| cfg.precise_orbits.get())
+            .unwrap_or(true);
+
+        // The shader samples in f32, which visibly wobbles on some orbits
+        let mode = if precise_orbits && trajectory::needs_high_precision(orbit, camera_scale) {
+            TrajectoryMode::Polyline
+        } else {
+            mode
+        };
+
+        Some(OrbitLine {
+            id,
             orbit,
-            multiplied_offset_s,
-            camera_scale,
-            eccentric_anomaly as f32,
+            parent_pos_premultiplied: multiplied_offset,
+            eccentric_anomaly,
+            hyperbola_bounds,
             point_count,
             thickness,
-            body.color,
-        ))
+            style,
+            mode,
+        })
+    }
+
+    /// Builds a single orbit line at the current time, outside the cache
+    /// and the point budget, for lines that are only shown for a moment.
+    fn generate_orbit_line(
+        &self,
+        body: &Body,
+        style: BodyStyle,
+        parent_id: Option<Id>,
+        camera_offset: DVec3,
+        camera_pos: DVec3,
+        camera_scale: f64,
+        position_map: &HashMap<Id, DVec3>,
+        thickness: f32,
+    ) -> Option<Trajectory> {
+        // Not cached, so the ID is never looked at
+        let line = Self::plan_orbit_line(
+            self.trajectory_mode(),
+            Id::MAX,
+            body,
+            style,
+            parent_id,
+            None,
+            camera_offset,
+            camera_pos,
+            camera_scale,
+            position_map,
+            self.session().sim_state.universe.time,
+            thickness,
+        )?;
+        Some(line.build(&self.context, camera_scale))
+    }
+
+    /// The moved body's old orbit line, fading out, while a re-parent fade is running.
+    fn generate_reparent_fade(
+        &self,
+        camera_offset: DVec3,
+        camera_pos: DVec3,
+        camera_scale: f64,
+        position_map: &HashMap<Id, DVec3>,
+        line_scale: f32,
+        hidden: &HashSet<Id>,
+    ) -> Option<Trajectory> {
+        let sim_state = &self.session().sim_state;
+        let fade = sim_state.reparent_fade.as_ref()?;
+        if hidden.contains(&fade.body_id) {
+            return None;
+        }
+
+        let mut style = StyleSettings::current().body_style(&sim_state.universe, fade.body_id)?;
+        style.color.a = fade_alpha(style.color.a, 1.0 - fade.progress);
+        let thickness = if fade.body_id == sim_state.focused_body() {
+            Self::FOCUSED_THICKNESS
+        } else {
+            Self::LINE_THICKNESS
+        };
+
+        self.generate_orbit_line(
+            &fade.old.body,
+            style,
+            fade.old.parent_id,
+            camera_offset,
+            camera_pos,
+            camera_scale,
+            position_map,
+            line_scale * thickness,
+        )
     }
 
     fn generate_preview_body(
@@ -429,6 +1282,7 @@ impl Program {
         camera_scale: f64,
         position_map: &HashMap<Id, DVec3>,
         wrapper: &PreviewBody,
+        color: Srgba,
     ) -> Option<Gm<Mesh, ColorMaterial>> {
         let parent_pos = wrapper
             .parent_id
@@ -439,7 +1293,7 @@ impl Program {
             .body
             .orbit
             .as_ref()
-            .map(|o| o.get_position_at_time(self.sim_state.universe.time))
+            .map(|o| o.get_position_at_time(self.session().sim_state.universe.time))
             .unwrap_or(DVec3::ZERO)
             + parent_pos;
         let position = body_pos - camera_offset;
@@ -469,8 +1323,8 @@ impl Program {
 
         let material = ColorMaterial {
             color: Srgba {
-                a: (((wrapper.body.color.a as u16 * 127u16) + 127) / 255) as u8,
-                ..wrapper.body.color
+                a: (((color.a as u16 * 127u16) + 127) / 255) as u8,
+                ..color
             },
             texture: None,
             render_states: RenderStates {
@@ -492,8 +1346,19 @@ impl Program {
         camera_pos: DVec3,
         camera_scale: f64,
         position_map: &HashMap<Id, DVec3>,
+        line_scale: f32,
     ) -> Option<PreviewScene> {
-        let body_wrapper = self.sim_state.preview_body.as_ref()?;
+        let sim_state = &self.session().sim_state;
+        // A new body being placed takes priority over a move being previewed
+        let body_wrapper = sim_state
+            .preview_body
+            .as_ref()
+            .or(sim_state.reparent_preview.as_ref())?;
+        let style = StyleSettings::current().new_body_style(
+            &self.session().sim_state.universe,
+            body_wrapper.body.color,
+            body_wrapper.parent_id,
+        );
 
         let body_gm = self.generate_preview_body(
             camera_offset,
@@ -501,17 +1366,17 @@ impl Program {
             camera_scale,
             position_map,
             body_wrapper,
+            style.color,
         );
-        let path = Self::generate_orbit_line(
-            &self.context,
+        let path = self.generate_orbit_line(
             &body_wrapper.body,
+            style,
             body_wrapper.parent_id,
             camera_offset,
             camera_pos,
             camera_scale,
             position_map,
-            self.sim_state.universe.time,
-            Self::PREVIEW_POINT_SCALE,
+            line_scale * Self::PREVIEW_POINT_SCALE,
         );
 
         if body_gm.is_none() && path.is_none() {
@@ -524,3 +1389,93 @@ impl Program {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A star far away from the viewpoint, with a planet orbiting it,
+    /// and a moon and a station orbiting the planet.
+    struct System {
+        universe: Universe,
+        positions: HashMap<Id, DVec3>,
+        star: Id,
+        planet: Id,
+        moon: Id,
+        station: Id,
+    }
+
+    impl System {
+        fn new() -> Self {
+            let mut universe = Universe::default();
+            let mut add = |name: &str, parent| {
+                let body = Body::new(name.to_owned(), 1e20, 1.0, None);
+                universe.add_body(body, parent).unwrap()
+            };
+            let star = add("Star", None);
+            let planet = add("Planet", Some(star));
+            let moon = add("Moon", Some(planet));
+            let station = add("Station", Some(moon));
+
+            let far = DVec3::new(1e6, 0.0, 0.0);
+            let positions = [star, planet, moon, station]
+                .into_iter()
+                .map(|id| (id, far))
+                .collect();
+            Self {
+                universe,
+                positions,
+                star,
+                planet,
+                moon,
+                station,
+            }
+        }
+
+        fn hidden(&self, focused: Id) -> HashSet<Id> {
+            culled_subsystems(&self.universe, &self.positions, DVec3::ZERO, 1e-3, focused)
+        }
+
+        fn always_show(&mut self, id: Id) {
+            self.universe.get_body_mut(id).unwrap().always_show = true;
+        }
+    }
+
+    #[test]
+    fn small_bodies_hide_everything_below_them() {
+        let system = System::new();
+        let hidden = system.hidden(system.star);
+        assert_eq!(
+            hidden,
+            HashSet::from([system.planet, system.moon, system.station])
+        );
+    }
+
+    #[test]
+    fn large_bodies_keep_their_subsystems() {
+        let system = System::new();
+        let hidden = culled_subsystems(
+            &system.universe,
+            &system.positions,
+            DVec3::ZERO,
+            1e-9,
+            system.star,
+        );
+        assert!(hidden.is_empty());
+    }
+
+    #[test]
+    fn always_shown_bodies_keep_their_descendants() {
+        let mut system = System::new();
+        system.always_show(system.moon);
+        let hidden = system.hidden(system.star);
+        assert_eq!(hidden, HashSet::from([system.planet]));
+    }
+
+    #[test]
+    fn focused_body_is_kept() {
+        let system = System::new();
+        let hidden = system.hidden(system.moon);
+        assert_eq!(hidden, HashSet::from([system.planet, system.station]));
+    }
+}
@@ -0,0 +1,229 @@
+//! Body colors and orbit line patterns that stay tellable apart
+//! for color-blind users.
+//!
+//! Several presets have bodies with similar hues, so the accessible palettes
+//! don't use the bodies' own colors at all. Instead, each body gets a color
+//! (and optionally a dash pattern) from its place in the hierarchy, so that
+//! siblings, and bodies and their parents, always differ.
+
+use strum::{EnumCount, IntoEnumIterator};
+use strum_macros::{EnumCount, EnumIter};
+use three_d::Srgba;
+
+use crate::{
+    cfg::CONFIG,
+    sim::universe::{Id, Universe},
+};
+
+const fn rgb(hex: u32) -> Srgba {
+    Srgba {
+        r: (hex >> 16) as u8,
+        g: (hex >> 8) as u8,
+        b: hex as u8,
+        a: 255,
+    }
+}
+
+/// The Okabe–Ito palette, without black, which wouldn't show up on the background.
+const OKABE_ITO: [Srgba; 7] = [
+    rgb(0xE69F00),
+    rgb(0x56B4E9),
+    rgb(0x009E73),
+    rgb(0xF0E442),
+    rgb(0x0072B2),
+    rgb(0xD55E00),
+    rgb(0xCC79A7),
+];
+
+/// Paul Tol's "bright" qualitative palette.
+const TOL_BRIGHT: [Srgba; 7] = [
+    rgb(0x4477AA),
+    rgb(0x66CCEE),
+    rgb(0x228833),
+    rgb(0xCCBB44),
+    rgb(0xEE6677),
+    rgb(0xAA3377),
+    rgb(0xBBBBBB),
+];
+
+/// Where the colors of bodies and their orbits come from.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, EnumIter)]
+pub(crate) enum Palette {
+    #[default]
+    BodyColors,
+    OkabeIto,
+    TolBright,
+}
+
+impl Palette {
+    pub(crate) const fn name(self) -> &'static str {
+        match self {
+            Palette::BodyColors => "Body colors",
+            Palette::OkabeIto => "Okabe–Ito",
+            Palette::TolBright => "Tol bright",
+        }
+    }
+
+    pub(crate) const fn description(self) -> &'static str {
+        match self {
+            Palette::BodyColors => "Each body's own color, as set in the Edit Body window.",
+            Palette::OkabeIto => {
+                "Seven colors that stay distinct with any common kind of color blindness."
+            }
+            Palette::TolBright => {
+                "Paul Tol's bright palette.\n\
+                Distinct with any common kind of color blindness, and a bit lighter."
+            }
+        }
+    }
+
+    pub(crate) fn from_index(index: u8) -> Self {
+        Self::iter().nth(index as usize).unwrap_or_default()
+    }
+
+    pub(crate) fn to_index(self) -> u8 {
+        Self::iter()
+            .position(|palette| palette == self)
+            .unwrap_or(0) as u8
+    }
+
+    const fn colors(self) -> Option<&'static [Srgba]> {
+        match self {
+            Palette::BodyColors => None,
+            Palette::OkabeIto => Some(&OKABE_ITO),
+            Palette::TolBright => Some(&TOL_BRIGHT),
+        }
+    }
+}
+
+/// How an orbit line is broken up.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, EnumCount, EnumIter)]
+pub(crate) enum LinePattern {
+    #[default]
+    Solid,
+    Dashed,
+    Dotted,
+    DashDot,
+}
+
+impl LinePattern {
+    /// The value of the `pattern` uniform in trajectory.frag.
+    pub(crate) const fn shader_value(self) -> i32 {
+        match self {
+            LinePattern::Solid => 0,
+            LinePattern::Dashed => 1,
+            LinePattern::Dotted => 2,
+            LinePattern::DashDot => 3,
+        }
+    }
+}
+
+/// How a body and its orbit are drawn.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct BodyStyle {
+    pub(crate) color: Srgba,
+    pub(crate) pattern: LinePattern,
+}
+
+/// The palette and pattern settings, read once per frame.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub(crate) struct StyleSettings {
+    pub(crate) palette: Palette,
+    pub(crate) patterns: bool,
+}
+
+impl StyleSettings {
+    pub(crate) fn current() -> Self {
+        CONFIG
+            .try_lock()
+            .map(|cfg| Self {
+                palette: Palette::from_index(cfg.palette.get()),
+                patterns: cfg.orbit_patterns.get(),
+            })
+            .unwrap_or_default()
+    }
+
+    /// Gets the style of a body in the universe.
+    pub(crate) fn body_style(self, universe: &Universe, id: Id) -> Option<BodyStyle> {
+        let wrapper = universe.get_body(id)?;
+        let parent_id = wrapper.relations.parent;
+        let sibling_index = parent_id
+            .and_then(|parent_id| universe.get_body(parent_id))
+            .and_then(|parent| parent.relations.satellites.iter().position(|&s| s == id))
+            .unwrap_or(0);
+
+        Some(self.style(
+            wrapper.body.color,
+            style_index(universe, parent_id, sibling_index),
+        ))
+    }
+
+    /// Gets the style a body would have if it were added
+    /// as the newest satellite of `parent_id`.
+    pub(crate) fn new_body_style(
+        self,
+        universe: &Universe,
+        color: Srgba,
+        parent_id: Option<Id>,
+    ) -> BodyStyle {
+        let sibling_index = parent_id
+            .and_then(|parent_id| universe.get_body(parent_id))
+            .map(|parent| parent.relations.satellites.len())
+            .unwrap_or(0);
+
+        self.style(color, style_index(universe, parent_id, sibling_index))
+    }
+
+    fn style(self, body_color: Srgba, index: usize) -> BodyStyle {
+        let color = match self.palette.colors() {
+            Some(colors) => Srgba {
+                a: body_color.a,
+                ..colors[index % colors.len()]
+            },
+            None => body_color,
+        };
+
+        let pattern = if self.patterns {
+            LinePattern::iter()
+                .nth(index % LinePattern::COUNT)
+                .unwrap_or_default()
+        } else {
+            LinePattern::Solid
+        };
+
+        BodyStyle { color, pattern }
+    }
+}
+
+/// Which palette color and pattern a body gets.
+///
+/// The depth is added to the sibling index so that a body's
+/// first satellite doesn't look the same as the body itself.
+fn style_index(universe: &Universe, parent_id: Option<Id>, sibling_index: usize) -> usize {
+    let mut depth = 0;
+    let mut cur = parent_id;
+
+    // Bounded in case of a loop
+    for _ in 0..=universe.get_bodies().len() {
+        let Some(wrapper) = cur.and_then(|id| universe.get_body(id)) else {
+            break;
+        };
+        depth += 1;
+        cur = wrapper.relations.parent;
+    }
+
+    sibling_index + depth
+}
+
+/// Gets the `index`th color of the Okabe–Ito palette, wrapping around.
+pub(crate) fn cycled_color(index: usize) -> Srgba {
+    OKABE_ITO[index % OKABE_ITO.len()]
+}
+
+/// Gets the color a body is drawn in with the current settings,
+/// for showing it in the GUI.
+pub(crate) fn display_color(universe: &Universe, id: Id) -> Option<Srgba> {
+    StyleSettings::current()
+        .body_style(universe, id)
+        .map(|style| style.color)
+}
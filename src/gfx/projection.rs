@@ -0,0 +1,100 @@
+use glam::DVec3;
+use three_d::{Camera, Mat4, Vec4, egui::Pos2};
+
+/// Maps simulation-space positions to on-screen positions,
+/// as of the last rendered frame.
+#[derive(Clone, Copy)]
+pub(crate) struct WorldProjector {
+    proj_view: Mat4,
+    /// The simulation-space position at the render origin.
+    camera_offset: DVec3,
+    /// Render-space units per meter.
+    camera_scale: f64,
+    /// The camera position, in simulation space.
+    camera_pos: DVec3,
+    /// The size of the viewport, in egui points.
+    screen_size: (f32, f32),
+    /// How many meters one point spans at the focus' distance.
+    meters_per_point: f64,
+}
+
+impl WorldProjector {
+    pub(crate) fn new(
+        camera: &Camera,
+        camera_offset: DVec3,
+        camera_scale: f64,
+        pixels_per_point: f32,
+    ) -> Self {
+        let viewport = camera.viewport();
+        let camera_pos = camera.position();
+        let camera_pos = DVec3::new(
+            camera_pos.x as f64,
+            camera_pos.y as f64,
+            camera_pos.z as f64,
+        );
+
+        let target = camera.target();
+        let focus_distance =
+            (camera_pos - DVec3::new(target.x as f64, target.y as f64, target.z as f64)).length();
+        let screen_height = viewport.height as f64 / pixels_per_point as f64;
+        // The projection's y scale is 1 / tan(fov / 2), so this is
+        // the height of the view at the focus, in render units
+        let view_height = 2.0 * focus_distance / camera.projection().y.y as f64;
+
+        Self {
+            proj_view: camera.projection() * camera.view(),
+            camera_offset,
+            camera_scale,
+            camera_pos: camera_pos / camera_scale + camera_offset,
+            screen_size: (
+                viewport.width as f32 / pixels_per_point,
+                viewport.height as f32 / pixels_per_point,
+            ),
+            meters_per_point: view_height / camera_scale / screen_height.max(1.0),
+        }
+    }
+
+    /// Projects a simulation-space position onto the screen.
+    ///
+    /// Returns None if the position is behind the camera.
+    pub(crate) fn project(&self, position: DVec3) -> Option<Pos2> {
+        let render_pos = (position - self.camera_offset) * self.camera_scale;
+        let clip = self.proj_view
+            * Vec4::new(
+                render_pos.x as f32,
+                render_pos.y as f32,
+                render_pos.z as f32,
+                1.0,
+            );
+
+        if clip.w <= 0.0 {
+            return None;
+        }
+
+        let (ndc_x, ndc_y) = (clip.x / clip.w, clip.y / clip.w);
+        let (width, height) = self.screen_size;
+
+        Some(Pos2::new(
+            (ndc_x + 1.0) * 0.5 * width,
+            (1.0 - ndc_y) * 0.5 * height,
+        ))
+    }
+
+    /// How many meters one point on the screen spans at the focus' distance.
+    #[inline]
+    pub(crate) fn meters_per_point(&self) -> f64 {
+        self.meters_per_point
+    }
+
+    /// The camera's distance from its focus, in meters.
+    #[inline]
+    pub(crate) fn camera_distance(&self) -> f64 {
+        self.camera_scale.recip()
+    }
+
+    /// The camera position, in simulation space.
+    #[inline]
+    pub(crate) fn camera_position(&self) -> DVec3 {
+        self.camera_pos
+    }
+}
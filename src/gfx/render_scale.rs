@@ -0,0 +1,143 @@
+//! Rendering the 3D scene at a lower or higher resolution than the screen,
+//! then stretching it to fit. The GUI is drawn at full resolution on top.
+
+use std::collections::HashMap;
+
+use glam::DVec3;
+use three_d::{
+    ClearState, ColorTexture, Context, DepthTexture2D, FrameInput, Interpolation, Light,
+    RenderTarget, Texture2D, Viewport, Wrapping, WriteMask,
+};
+
+use crate::{Program, cfg::CONFIG, sim::universe::Id};
+
+pub(crate) const MIN_RENDER_SCALE: f32 = 0.5;
+pub(crate) const MAX_RENDER_SCALE: f32 = 2.0;
+
+/// The color and depth the scene is cleared to before rendering.
+fn clear_state() -> ClearState {
+    ClearState::color_and_depth(0.0, 0.0, 0.0, 1.0, 100000.0)
+}
+
+/// Textures to render the scene into before it's copied to the screen.
+pub(crate) struct ScaledTarget {
+    color: Texture2D,
+    depth: DepthTexture2D,
+}
+
+impl ScaledTarget {
+    fn new(context: &Context, viewport: Viewport) -> Self {
+        Self {
+            color: Texture2D::new_empty::<[u8; 4]>(
+                context,
+                viewport.width,
+                viewport.height,
+                Interpolation::Linear,
+                Interpolation::Linear,
+                None,
+                Wrapping::ClampToEdge,
+                Wrapping::ClampToEdge,
+            ),
+            depth: DepthTexture2D::new::<f32>(
+                context,
+                viewport.width,
+                viewport.height,
+                Wrapping::ClampToEdge,
+                Wrapping::ClampToEdge,
+            ),
+        }
+    }
+
+    fn fits(&self, viewport: Viewport) -> bool {
+        self.color.width() == viewport.width && self.color.height() == viewport.height
+    }
+
+    fn render_target(&mut self) -> RenderTarget<'_> {
+        RenderTarget::new(
+            self.color.as_color_target(None),
+            self.depth.as_depth_target(),
+        )
+    }
+}
+
+/// The render scale setting, as a fraction of the screen's resolution.
+fn render_scale() -> f32 {
+    let scale = CONFIG
+        .try_lock()
+        .map(|cfg| cfg.render_scale.get())
+        .unwrap_or(1.0);
+
+    let valid = scale > 0.0;
+    if !valid {
+        return 1.0;
+    }
+
+    scale.clamp(MIN_RENDER_SCALE, MAX_RENDER_SCALE)
+}
+
+/// The viewport `scale` times the size of `viewport`, at least one pixel across.
+fn scaled_viewport(viewport: Viewport, scale: f32) -> Viewport {
+    let scale_length = |length: u32| ((length as f32 * scale).round() as u32).max(1);
+
+    Viewport::new_at_origo(scale_length(viewport.width), scale_length(viewport.height))
+}
+
+impl Program {
+    /// Clears the screen and renders the 3D scene onto it,
+    /// at the resolution set by the render scale setting.
+    pub(crate) fn render_scene(
+        &mut self,
+        frame_input: &FrameInput,
+        position_map: &HashMap<Id, DVec3>,
+    ) {
+        let screen = frame_input.screen();
+        let lights: [&dyn Light; 2] = [&self.top_light, &self.ambient_light];
+        let scale =
+            (render_scale() * self.adaptive_quality.render_scale_factor()).max(MIN_RENDER_SCALE);
+
+        let mut cache = std::mem::take(&mut self.trajectory_cache);
+
+        if scale == 1.0 {
+            // Nothing to stretch, so skip the copy
+            self.scaled_target = None;
+            let objects = self.to_objects(position_map, frame_input.device_pixel_ratio, &mut cache);
+            screen
+                .clear(clear_state())
+                .render(&self.session().camera, &objects, &lights);
+            cache.restore(objects.into_lines());
+            self.trajectory_cache = cache;
+            return;
+        }
+
+        let viewport = scaled_viewport(frame_input.viewport, scale);
+        let mut target = match self.scaled_target.take() {
+            Some(target) if target.fits(viewport) => target,
+            _ => ScaledTarget::new(&self.context, viewport),
+        };
+
+        let mut camera = self.session().camera.clone();
+        camera.set_viewport(viewport);
+
+        // Lines are sized in pixels, so they keep their on-screen width
+        let objects = self.to_objects(
+            position_map,
+            frame_input.device_pixel_ratio * scale,
+            &mut cache,
+        );
+
+        target
+            .render_target()
+            .clear(clear_state())
+            .render(&camera, &objects, &lights);
+
+        screen.clear(clear_state()).copy_from_color(
+            ColorTexture::Single(&target.color),
+            frame_input.viewport,
+            WriteMask::COLOR,
+        );
+
+        self.scaled_target = Some(target);
+        cache.restore(objects.into_lines());
+        self.trajectory_cache = cache;
+    }
+}
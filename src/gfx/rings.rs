@@ -0,0 +1,62 @@
+use core::f32::consts::TAU;
+
+use glam::DVec3;
+use three_d::{CpuMesh, Indices, Mat4, Positions, Vec3, Vec4};
+
+use crate::sim::body::Rings;
+
+/// How many quads the rings are made of, around their circumference.
+const RING_SEGMENTS: u32 = 128;
+
+/// A flat annulus in the XY plane around the origin,
+/// with an outer radius of 1 and an inner radius of `inner_ratio`.
+pub fn annulus_mesh(inner_ratio: f32) -> CpuMesh {
+    let mut positions = Vec::with_capacity(2 * RING_SEGMENTS as usize);
+    let mut indices = Vec::with_capacity(6 * RING_SEGMENTS as usize);
+
+    for i in 0..RING_SEGMENTS {
+        let (sin, cos) = (i as f32 / RING_SEGMENTS as f32 * TAU).sin_cos();
+        positions.push(Vec3::new(cos * inner_ratio, sin * inner_ratio, 0.0));
+        positions.push(Vec3::new(cos, sin, 0.0));
+
+        let inner = 2 * i;
+        let outer = inner + 1;
+        let next_inner = (inner + 2) % (2 * RING_SEGMENTS);
+        let next_outer = next_inner + 1;
+        indices.extend_from_slice(&[inner, outer, next_outer, next_outer, next_inner, inner]);
+    }
+
+    CpuMesh {
+        positions: Positions::F32(positions),
+        indices: Indices::U32(indices),
+        ..Default::default()
+    }
+}
+
+/// The unit normal of the rings' plane.
+pub fn rings_normal(rings: &Rings) -> DVec3 {
+    let (sin_lan, cos_lan) = rings.long_asc_node.sin_cos();
+    let (sin_inc, cos_inc) = rings.inclination.sin_cos();
+    DVec3::new(sin_lan * sin_inc, -cos_lan * sin_inc, cos_inc)
+}
+
+/// Gets the transformation matrix that turns an [`annulus_mesh`]
+/// into the rings, centered at `center` and `outer_radius` in size.
+///
+/// Both are in render-world space.
+pub fn get_rings_matrix(rings: &Rings, center: DVec3, outer_radius: f64) -> Mat4 {
+    let (sin_lan, cos_lan) = rings.long_asc_node.sin_cos();
+    let (sin_inc, cos_inc) = rings.inclination.sin_cos();
+
+    // The ascending node, a perpendicular in the rings' plane, and their normal
+    let x = DVec3::new(cos_lan, sin_lan, 0.0) * outer_radius;
+    let y = DVec3::new(-sin_lan * cos_inc, cos_lan * cos_inc, sin_inc) * outer_radius;
+    let z = rings_normal(rings) * outer_radius;
+
+    Mat4 {
+        x: Vec4::new(x.x as f32, x.y as f32, x.z as f32, 0.0),
+        y: Vec4::new(y.x as f32, y.y as f32, y.z as f32, 0.0),
+        z: Vec4::new(z.x as f32, z.y as f32, z.z as f32, 0.0),
+        w: Vec4::new(center.x as f32, center.y as f32, center.z as f32, 1.0),
+    }
+}
@@ -0,0 +1,195 @@
+use three_d::{
+    AxisAlignedBoundingBox, Blend, ColorMapping, Context, Cull, EffectMaterialId, ElementBuffer,
+    Geometry, GeometryId, Material, MaterialType, Object, Program, RenderStates, Srgba, Vec2, Vec3,
+    VertexBuffer, Viewer, render_with_material,
+};
+
+use crate::gfx::trajectory::{
+    POLYLINE_VERTEX_SHADER_SOURCE, SHADER_VERSION_HEADER, strip_version_header,
+};
+
+/// A line through points worked out on the CPU, for paths that
+/// aren't a single conic, like one body's path as seen from another.
+///
+/// Drawn with the same constant on-screen thickness as orbit lines.
+pub(crate) struct SampledPath {
+    context: Context,
+    positions: VertexBuffer<Vec3>,
+    next_positions: VertexBuffer<Vec3>,
+    sides: VertexBuffer<f32>,
+    /// How far along the path each vertex is, from -1 to 1.
+    progress: VertexBuffer<f32>,
+    element_buffer: ElementBuffer<u32>,
+    thickness: f32,
+    color: Srgba,
+}
+
+impl SampledPath {
+    /// Creates a line through `points`, in render-world space.
+    ///
+    /// The line is brightest at `now`, the index of the point for the
+    /// current time, and fades out towards both ends.
+    ///
+    /// Returns None if there are fewer than two points.
+    pub(crate) fn new(
+        context: &Context,
+        points: &[Vec3],
+        now: usize,
+        thickness: f32,
+        color: Srgba,
+    ) -> Option<Self> {
+        if points.len() < 2 {
+            return None;
+        }
+
+        let vertex_count = points.len() * 2;
+        let mut positions = Vec::with_capacity(vertex_count);
+        let mut next_positions = Vec::with_capacity(vertex_count);
+        let mut sides = Vec::with_capacity(vertex_count);
+        let mut progress = Vec::with_capacity(vertex_count);
+
+        let before = now.max(1) as f32;
+        let after = (points.len() - 1).saturating_sub(now).max(1) as f32;
+
+        for (index, &point) in points.iter().enumerate() {
+            // The last point carries on in the direction it came from
+            let next = match points.get(index + 1) {
+                Some(&next) => next,
+                None => point + (point - points[index - 1]),
+            };
+            let along = if index < now {
+                (index as f32 - now as f32) / before
+            } else {
+                (index - now) as f32 / after
+            };
+
+            for side in [-1.0, 1.0] {
+                positions.push(point);
+                next_positions.push(next);
+                sides.push(side);
+                progress.push(along);
+            }
+        }
+
+        let segment_count = points.len() as u32 - 1;
+        let indices: Vec<u32> = (0..segment_count)
+            .flat_map(|segment| {
+                let base = segment * 2;
+                [base, base + 1, base + 2, base + 2, base + 1, base + 3]
+            })
+            .collect();
+
+        Some(Self {
+            context: context.clone(),
+            positions: VertexBuffer::new_with_data(context, &positions),
+            next_positions: VertexBuffer::new_with_data(context, &next_positions),
+            sides: VertexBuffer::new_with_data(context, &sides),
+            progress: VertexBuffer::new_with_data(context, &progress),
+            element_buffer: ElementBuffer::new_with_data(context, &indices),
+            thickness,
+            color,
+        })
+    }
+}
+
+const FRAGMENT_SHADER_SOURCE: &'static str = strip_version_header(
+    include_str!("shaders/sampled_path.frag"),
+    SHADER_VERSION_HEADER,
+);
+
+impl Geometry for SampledPath {
+    fn draw(&self, viewer: &dyn Viewer, program: &Program, render_states: RenderStates) {
+        program.use_uniform("u_proj_view", viewer.projection() * viewer.view());
+        program.use_uniform("u_thickness_px", self.thickness);
+        program.use_uniform(
+            "u_viewport",
+            Vec2::new(
+                viewer.viewport().width as f32,
+                viewer.viewport().height as f32,
+            ),
+        );
+        program.use_vertex_attribute("a_position", &self.positions);
+        program.use_vertex_attribute("a_next_position", &self.next_positions);
+        program.use_vertex_attribute("a_side", &self.sides);
+        program.use_vertex_attribute("a_ecc_anom", &self.progress);
+
+        program.draw_elements(render_states, viewer.viewport(), &self.element_buffer);
+    }
+
+    fn vertex_shader_source(&self) -> String {
+        POLYLINE_VERTEX_SHADER_SOURCE.to_owned()
+    }
+
+    fn id(&self) -> GeometryId {
+        GeometryId(0x5FA7)
+    }
+
+    fn render_with_material(
+        &self,
+        material: &dyn three_d::Material,
+        viewer: &dyn three_d::Viewer,
+        lights: &[&dyn three_d::Light],
+    ) {
+        render_with_material(&self.context, viewer, &self, material, lights);
+    }
+
+    fn render_with_effect(
+        &self,
+        _material: &dyn three_d::Effect,
+        _viewer: &dyn three_d::Viewer,
+        _lights: &[&dyn three_d::Light],
+        _color_texture: Option<three_d::ColorTexture>,
+        _depth_texture: Option<three_d::DepthTexture>,
+    ) {
+        panic!("Rendering sampled paths with effects isn't supported");
+    }
+
+    fn aabb(&self) -> AxisAlignedBoundingBox {
+        AxisAlignedBoundingBox::INFINITE
+    }
+}
+
+impl Material for SampledPath {
+    fn fragment_shader_source(&self, _lights: &[&dyn three_d::Light]) -> String {
+        let mut source = ColorMapping::fragment_shader_source().to_owned();
+        source.push_str(FRAGMENT_SHADER_SOURCE);
+        source
+    }
+
+    fn id(&self) -> EffectMaterialId {
+        EffectMaterialId(0x5FA7)
+    }
+
+    fn use_uniforms(
+        &self,
+        program: &Program,
+        viewer: &dyn Viewer,
+        _lights: &[&dyn three_d::Light],
+    ) {
+        viewer.color_mapping().use_uniforms(program);
+        program.use_uniform("surface_color", self.color.to_linear_srgb());
+    }
+
+    fn render_states(&self) -> RenderStates {
+        RenderStates {
+            blend: Blend::TRANSPARENCY,
+            // The path can cross over itself and turn either way
+            cull: Cull::None,
+            ..Default::default()
+        }
+    }
+
+    fn material_type(&self) -> MaterialType {
+        MaterialType::Transparent
+    }
+}
+
+impl Object for SampledPath {
+    fn material_type(&self) -> MaterialType {
+        <Self as Material>::material_type(self)
+    }
+
+    fn render(&self, viewer: &dyn Viewer, _lights: &[&dyn three_d::Light]) {
+        render_with_material(&self.context, viewer, self, self, &[]);
+    }
+}
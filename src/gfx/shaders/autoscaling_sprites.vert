@@ -6,6 +6,7 @@ uniform float scaleTimesTanHalfFov;
 uniform vec3 direction;
 
 in vec3 center;
+in vec4 instance_color;
 in vec3 position;
 in vec2 uv_coordinate;
 
@@ -32,7 +33,7 @@ mat4 get_tf(float distance) {
 void main()
 {
     uvs = uv_coordinate;
-    col = vec4(1.0);
+    col = instance_color;
 
     vec3 z = normalize(eye - center);
     vec3 y = direction;
@@ -0,0 +1,41 @@
+#version 330 core
+
+uniform vec4 surface_color;
+uniform float spacing;          // distance between minor lines, in render-world units
+uniform vec2 phase;             // the center's offset from a major line, in minor cells
+uniform float minor_alpha;      // fades minor lines out while zooming out
+uniform float half_size;
+
+in vec2 v_grid;
+
+layout (location = 0) out vec4 outColor;
+
+// How much of this pixel is covered by a grid line,
+// with lines being about a pixel wide
+float line_coverage(vec2 coord) {
+    vec2 width = max(fwidth(coord), vec2(1e-6));
+    vec2 dist = abs(fract(coord + 0.5) - 0.5) / width;
+    float coverage = 1.0 - min(min(dist.x, dist.y), 1.0);
+
+    // Lines closer together than a few pixels just turn into a haze
+    float crowding = max(width.x, width.y);
+    return coverage * (1.0 - smoothstep(0.1, 0.3, crowding));
+}
+
+void main()
+{
+    vec2 minor = v_grid / spacing + phase;
+    vec2 major = minor / 10.0;
+
+    float coverage = max(line_coverage(minor) * minor_alpha, line_coverage(major));
+
+    // Fade out towards the edge instead of ending abruptly
+    float edge_fade = 1.0 - smoothstep(0.5, 1.0, length(v_grid) / half_size);
+
+    outColor = surface_color;
+    outColor.a *= coverage * edge_fade;
+
+    // the definition of color_mapping is external
+    // and added at runtime; ignore the error
+    outColor.rgb = color_mapping(outColor.rgb);
+}
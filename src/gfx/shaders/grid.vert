@@ -0,0 +1,14 @@
+#version 330 core
+
+uniform mat4 u_proj_view;       // Projection * view
+uniform vec3 u_center;          // grid center on the ecliptic, in render-world space
+uniform float u_half_size;      // half the width of the grid, in render-world units
+
+in vec3 a_position;             // quad corner, -1.0 to 1.0 on x and y
+
+out vec2 v_grid;                // position relative to the center, in render-world units
+
+void main() {
+    v_grid = a_position.xy * u_half_size;
+    gl_Position = u_proj_view * vec4(u_center + vec3(v_grid, 0.0), 1.0);
+}
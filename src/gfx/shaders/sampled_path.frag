@@ -0,0 +1,35 @@
+#version 330 core
+
+// Drawn over trajectory_polyline.vert, whose eccentric anomaly attribute
+// holds how far along the path each point is instead: -1 at the start,
+// 0 at the current time and 1 at the end.
+
+uniform vec4 surface_color;
+
+in float v_ecc_anom;
+in float v_side;
+
+const float MIN_ALPHA = 0.15;
+const float MAX_ALPHA = 0.8;
+
+layout (location = 0) out vec4 outColor;
+
+// Fade out the outermost pixel on each side of the line
+// so its edges don't look jagged
+float edge_coverage(float v_side) {
+    float distance_to_edge = 1.0 - abs(v_side);
+    return clamp(distance_to_edge / max(fwidth(v_side), 1e-6), 0.0, 1.0);
+}
+
+void main()
+{
+    outColor = surface_color;
+
+    // Brightest around the current time
+    outColor.a *= mix(MAX_ALPHA, MIN_ALPHA, clamp(abs(v_ecc_anom), 0.0, 1.0));
+    outColor.a *= edge_coverage(v_side);
+
+    // the definition of color_mapping is external
+    // and added at runtime; ignore the error
+    outColor.rgb = color_mapping(outColor.rgb);
+}
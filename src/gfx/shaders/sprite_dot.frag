@@ -0,0 +1,23 @@
+#version 330 core
+
+in vec2 uvs;
+in vec4 col;
+
+layout (location = 0) out vec4 outColor;
+
+void main()
+{
+    float distance = length(uvs * 2.0 - 1.0);
+
+    // Fade out the outermost pixel so the dot's edge doesn't look jagged
+    float coverage = clamp((1.0 - distance) / max(fwidth(distance), 1e-6), 0.0, 1.0);
+    if (coverage <= 0.0) {
+        discard;
+    }
+
+    outColor = vec4(col.rgb, col.a * coverage);
+
+    // the definition of color_mapping is external
+    // and added at runtime; ignore the error
+    outColor.rgb = color_mapping(outColor.rgb);
+}
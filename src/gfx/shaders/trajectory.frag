@@ -4,13 +4,19 @@ uniform vec4 surface_color;
 uniform float curr_ecc_anom;
 uniform float anomaly_range;
 uniform float eccentricity;
+uniform int pattern;            // see LinePattern in palette.rs
 
 in float v_ecc_anom;
+in float v_side;
 
 const float MIN_ALPHA = 0.1;
 const float MAX_ALPHA = 0.5;
 const float DIFF_MULTIPLIER = MAX_ALPHA - MIN_ALPHA;
 
+// How many times the dash pattern repeats around a closed orbit
+const float PATTERN_REPEATS = 64.0;
+const float TAU = 6.28318530718;
+
 layout (location = 0) out vec4 outColor;
 
 float angle_diff(float a, float b) {
@@ -53,12 +59,42 @@ float extreme_alpha_dropoff(float v_ecc_anom) {
     return 10.0 - ecc_anom;
 }
 
+// Fade out the outermost pixel on each side of the line
+// so its edges don't look jagged
+float edge_coverage(float v_side) {
+    float distance_to_edge = 1.0 - abs(v_side);
+    return clamp(distance_to_edge / max(fwidth(v_side), 1e-6), 0.0, 1.0);
+}
+
+// Breaks the line up into dashes and/or dots
+// so orbits can be told apart without relying on color
+float pattern_coverage(float v_ecc_anom) {
+    if (pattern == 0) {
+        return 1.0;
+    }
+
+    float t = fract(abs(v_ecc_anom) * PATTERN_REPEATS / TAU);
+
+    if (pattern == 1) {
+        // Dashed
+        return t < 0.6 ? 1.0 : 0.0;
+    } else if (pattern == 2) {
+        // Dotted
+        return t < 0.2 ? 1.0 : 0.0;
+    } else {
+        // Dash-dot
+        return (t < 0.5 || (t >= 0.7 && t < 0.8)) ? 1.0 : 0.0;
+    }
+}
+
 void main()
 {
     outColor = surface_color;
 
     outColor.a *= get_alpha(v_ecc_anom, curr_ecc_anom);
     outColor.a *= extreme_alpha_dropoff(v_ecc_anom);
+    outColor.a *= edge_coverage(v_side);
+    outColor.a *= pattern_coverage(v_ecc_anom);
 
     // the definition of color_mapping is external
     // and added at runtime; ignore the error
@@ -17,6 +17,10 @@ uniform vec2 u_viewport;
 uniform float u_ecc_anom_range; // ecc. anom. range size
 
 out float v_ecc_anom;
+out float v_side;               // -1.0 to 1.0 across the line, for edge smoothing
+
+// Extra width for the smoothed edges to fade out in
+const float AA_FRINGE_PX = 1.0;
 
 float get_eccentric_anomaly(int idx) {
     if (u_eccentricity < 1.0) {
@@ -60,6 +64,7 @@ void main() {
     float next_ecc_anom = get_eccentric_anomaly(next_point_idx);
 
     v_ecc_anom = curr_ecc_anom;
+    v_side = side;
 
     vec3 curr_pqw = get_point_at_eccentric_anomaly(curr_ecc_anom);
     vec3 next_pqw = get_point_at_eccentric_anomaly(next_ecc_anom);
@@ -80,7 +85,7 @@ void main() {
     vec2 normal = vec2(-dir.y, dir.x);
 
     float ndc_per_pixel = (u_viewport.y > 0.0) ? (2.0 / u_viewport.y) : 0.0;
-    vec2 offset_ndc = normal * (u_thickness_px * 0.5 * ndc_per_pixel);
+    vec2 offset_ndc = normal * ((u_thickness_px + AA_FRINGE_PX) * 0.5 * ndc_per_pixel);
 
     offset_ndc.x /= aspect;
 
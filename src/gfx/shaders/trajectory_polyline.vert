@@ -0,0 +1,54 @@
+#version 330 core
+
+// Fallback for trajectory.vert on drivers that can't handle
+// gl_VertexID-based vertex pulling or unsigned integer uniforms.
+// The points are computed on the CPU instead; this shader only
+// extrudes them into a line with a constant on-screen thickness.
+
+uniform mat4 u_proj_view;       // Projection * view
+uniform float u_thickness_px;
+uniform vec2 u_viewport;
+
+in vec3 a_position;             // this point, in render-world space
+in vec3 a_next_position;        // the next point, in render-world space
+in float a_side;                // -1.0 or 1.0, the side of the line
+in float a_ecc_anom;            // eccentric anomaly of this point
+
+out float v_ecc_anom;
+out float v_side;               // -1.0 to 1.0 across the line, for edge smoothing
+
+// Extra width for the smoothed edges to fade out in
+const float AA_FRINGE_PX = 1.0;
+
+void main() {
+    v_ecc_anom = a_ecc_anom;
+    v_side = a_side;
+
+    vec4 curr_clip = u_proj_view * vec4(a_position, 1.0);
+    vec4 next_clip = u_proj_view * vec4(a_next_position, 1.0);
+
+    float aspect = u_viewport.x / u_viewport.y;
+
+    float eps = 1e-6;
+    vec2 curr_ndc = aspect * curr_clip.xy / max(curr_clip.w, eps);
+    vec2 next_ndc = aspect * next_clip.xy / max(next_clip.w, eps);
+
+    vec2 ndc_diff = next_ndc - curr_ndc;
+    vec2 dir = (length(ndc_diff) < 1e-4)
+        ? vec2(0.0, 1.0)
+        : normalize(ndc_diff);
+    vec2 normal = vec2(-dir.y, dir.x);
+
+    float ndc_per_pixel = (u_viewport.y > 0.0) ? (2.0 / u_viewport.y) : 0.0;
+    vec2 offset_ndc = normal * ((u_thickness_px + AA_FRINGE_PX) * 0.5 * ndc_per_pixel);
+
+    offset_ndc.x /= aspect;
+
+    vec4 offset_clip = vec4(
+        offset_ndc * curr_clip.w * a_side,
+        0.0,
+        0.0
+    );
+
+    gl_Position = curr_clip + offset_clip;
+}
@@ -1,10 +1,182 @@
+use core::f64::consts::PI;
+
+use glam::DVec3;
 use keplerian_sim::OrbitTrait;
 use three_d::{
     AxisAlignedBoundingBox, ColorMapping, Context, ElementBuffer, Geometry, GeometryId, Mat4,
-    Matrix4, Object, Program, RenderStates, Srgba, Vec2, Vec3, Vec4, Viewer, render_with_material,
+    Matrix4, Object, Program, RenderStates, Srgba, Vec2, Vec3, Vec4, VertexBuffer, Viewer,
+    render_with_material,
 };
 use three_d::{Blend, EffectMaterialId, HasContext, Material, MaterialType};
 
+use crate::{
+    gfx::palette::LinePattern,
+    notify::{Severity, notify},
+};
+
+/// How the vertices of a trajectory are generated.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum TrajectoryMode {
+    /// Vertices are generated on the GPU from the orbit's parameters.
+    Shader,
+    /// Vertices are computed on the CPU and uploaded as a regular mesh.
+    ///
+    /// Slower, but works on drivers that can't compile the shader path.
+    Polyline,
+}
+
+impl TrajectoryMode {
+    /// Picks the shader path if its program compiles and links
+    /// on this device, or the polyline path otherwise.
+    pub(crate) fn detect(context: &Context) -> Self {
+        match Program::from_source(context, VERTEX_SHADER_SOURCE, &fragment_shader_source()) {
+            Ok(_) => {
+                log::debug!("Drawing orbit lines with the shader");
+                Self::Shader
+            }
+            Err(e) => {
+                notify(
+                    Severity::Warning,
+                    format!("Orbit shader unsupported, drawing orbit lines on the CPU: {e}"),
+                );
+                Self::Polyline
+            }
+        }
+    }
+}
+
+/// Orbits with an eccentricity within this distance of 1 are better sampled
+/// on the CPU in f64, since `cos E - e` loses most of its precision in f32
+/// near the periapsis of nearly-parabolic orbits.
+const PRECISE_ECCENTRICITY_MARGIN: f64 = 0.05;
+
+/// Orbits with a semi-major axis larger than this many render units are better
+/// sampled on the CPU in f64, since f32 can't resolve the part of a huge orbit
+/// that's close to the camera.
+const PRECISE_RENDER_SIZE: f64 = 1e3;
+
+/// The furthest hyperbolic eccentric anomaly drawn.
+///
+/// Past this, the fragment shader has faded the line out,
+/// as f32 can't place points that far out precisely.
+const MAX_HYPERBOLIC_ANOMALY: f64 = 10.0;
+
+/// The hyperbolic eccentric anomaly at which a hyperbolic orbit
+/// reaches `radius` from its parent, on the outbound side.
+///
+/// Returns None if the orbit isn't hyperbolic,
+/// or its periapsis is already outside `radius`.
+pub(crate) fn hyperbolic_anomaly_at_radius(orbit: &impl OrbitTrait, radius: f64) -> Option<f64> {
+    let eccentricity = orbit.get_eccentricity();
+    let periapsis = orbit.get_periapsis();
+    let valid = eccentricity > 1.0 && periapsis <= radius;
+    if !valid {
+        return None;
+    }
+
+    // From r = |a| (e cosh H - 1), where |a| = r_pe / (e - 1)
+    let semi_major_axis = periapsis / (eccentricity - 1.0);
+    let cosh = (radius / semi_major_axis + 1.0) / eccentricity;
+
+    Some(cosh.acosh().min(MAX_HYPERBOLIC_ANOMALY))
+}
+
+/// The hyperbolic eccentric anomaly at a true anomaly, in radians,
+/// or [`MAX_HYPERBOLIC_ANOMALY`] if it's at or past the asymptote.
+pub(crate) fn hyperbolic_anomaly_at_true_anomaly(eccentricity: f64, true_anomaly: f64) -> f64 {
+    // From tanh(H/2) = sqrt((e - 1) / (e + 1)) tan(θ/2)
+    let tanh_half =
+        ((eccentricity - 1.0) / (eccentricity + 1.0)).sqrt() * (true_anomaly.abs() / 2.0).tan();
+
+    let reachable = (0.0..1.0).contains(&tanh_half) && true_anomaly.abs() < PI;
+    if !reachable {
+        return MAX_HYPERBOLIC_ANOMALY;
+    }
+
+    (2.0 * tanh_half.atanh()).min(MAX_HYPERBOLIC_ANOMALY)
+}
+
+/// How much eccentric anomaly the stretch of a hyperbolic orbit drawn
+/// around the body spans, with the body at `eccentric_anomaly`.
+pub(crate) fn around_body_range(eccentric_anomaly: f64) -> f64 {
+    if eccentric_anomaly > MAX_HYPERBOLIC_ANOMALY {
+        // We don't need a path anymore.
+        // It's way too far.
+        0.0
+    } else {
+        // Bell curve to prevent physical range from becoming
+        // too long too quickly.
+        30.0 * 2.0_f64.powf(-0.15 * eccentric_anomaly.powi(2))
+    }
+}
+
+/// Whether an orbit wobbles visibly when sampled by the shader in f32,
+/// and should be sampled on the CPU in f64 instead.
+pub(crate) fn needs_high_precision(orbit: &impl OrbitTrait, camera_scale: f64) -> bool {
+    (orbit.get_eccentricity() - 1.0).abs() < PRECISE_ECCENTRICITY_MARGIN
+        || (orbit.get_semi_major_axis() * camera_scale).abs() > PRECISE_RENDER_SIZE
+}
+
+/// The shape and placement of an orbit in f64, for sampling it on the CPU.
+#[derive(PartialEq)]
+struct PreciseConic {
+    /// Towards the periapsis, scaled by the periapsis distance.
+    p: DVec3,
+    /// Along the direction of travel at the periapsis, scaled likewise.
+    q: DVec3,
+    /// The position of the parent.
+    origin: DVec3,
+    eccentricity: f64,
+    a_norm: f64,
+    b_norm: f64,
+}
+
+impl PreciseConic {
+    fn new(orbit: &impl OrbitTrait, origin: DVec3, scale: f64) -> Self {
+        let matrix = orbit.get_transformation_matrix();
+        let rp = orbit.get_periapsis() * scale;
+        let eccentricity = orbit.get_eccentricity();
+        let a_norm = (1.0 - eccentricity).recip();
+
+        Self {
+            p: DVec3::new(matrix.e11, matrix.e21, matrix.e31) * rp,
+            q: DVec3::new(matrix.e12, matrix.e22, matrix.e32) * rp,
+            origin,
+            eccentricity,
+            a_norm,
+            b_norm: a_norm * (1.0 - eccentricity.powi(2)).abs().sqrt(),
+        }
+    }
+
+    /// CPU equivalent of `get_point_at_eccentric_anomaly` in trajectory.vert,
+    /// transformed into render-world space.
+    ///
+    /// Everything is kept in f64 until the final position, which is
+    /// relative to the render origin and so fits in f32 just fine.
+    fn point_at_eccentric_anomaly(&self, eccentric_anomaly: f64) -> Vec3 {
+        let (x, y) = if self.eccentricity < 1.0 {
+            let (sin, cos) = eccentric_anomaly.sin_cos();
+            (self.a_norm * (cos - self.eccentricity), self.b_norm * sin)
+        } else {
+            (
+                self.a_norm * (eccentric_anomaly.cosh() - self.eccentricity),
+                self.b_norm * eccentric_anomaly.sinh(),
+            )
+        };
+
+        let point = self.origin + self.p * x + self.q * y;
+        Vec3::new(point.x as f32, point.y as f32, point.z as f32)
+    }
+}
+
+/// Vertex attributes for the polyline path.
+struct PolylineBuffers {
+    positions: VertexBuffer<Vec3>,
+    next_positions: VertexBuffer<Vec3>,
+    sides: VertexBuffer<f32>,
+    eccentric_anomalies: VertexBuffer<f32>,
+}
+
 pub struct Trajectory {
     context: Context,
     eccentricity: f32,
@@ -14,8 +186,18 @@ pub struct Trajectory {
     pub curr_ecc_anom: f32,
     point_count: u32,
     pub thickness: f32,
+    /// The same orbit in f64, used to tessellate the polyline path.
+    conic: PreciseConic,
+    /// `curr_ecc_anom` in f64.
+    precise_ecc_anom: f64,
     element_buffer: ElementBuffer<u32>,
+    /// Only present when using [`TrajectoryMode::Polyline`].
+    polyline: Option<PolylineBuffers>,
+    /// The first and last eccentric anomalies drawn on a hyperbolic orbit,
+    /// or None to draw a stretch around `curr_ecc_anom`.
+    hyperbola_bounds: Option<(f64, f64)>,
     pub color: Srgba,
+    pub pattern: LinePattern,
 }
 
 impl Trajectory {
@@ -26,36 +208,17 @@ impl Trajectory {
     pub fn new(
         context: &Context,
         orbit: &impl OrbitTrait,
-        parent_pos_premultiplied: Vec3,
+        parent_pos_premultiplied: DVec3,
         camera_scale: f64,
-        eccentric_anomaly: f32,
+        eccentric_anomaly: f64,
+        hyperbola_bounds: Option<(f64, f64)>,
         point_count: u32,
         thickness: f32,
         color: Srgba,
+        pattern: LinePattern,
+        mode: TrajectoryMode,
     ) -> Self {
-        let matrix = orbit.get_transformation_matrix();
-        let rp = orbit.get_periapsis() * camera_scale;
-        let matrix = Matrix4 {
-            x: Vec4::new(
-                (matrix.e11 * rp) as f32,
-                (matrix.e21 * rp) as f32,
-                (matrix.e31 * rp) as f32,
-                0.0,
-            ),
-            y: Vec4::new(
-                (matrix.e12 * rp) as f32,
-                (matrix.e22 * rp) as f32,
-                (matrix.e32 * rp) as f32,
-                0.0,
-            ),
-            z: Vec4::new(0.0, 0.0, 0.0, 0.0),
-            w: Vec4::new(
-                parent_pos_premultiplied.x,
-                parent_pos_premultiplied.y,
-                parent_pos_premultiplied.z,
-                1.0,
-            ),
-        };
+        let matrix = Self::get_matrix(orbit, parent_pos_premultiplied, camera_scale);
         let eccentricity = orbit.get_eccentricity();
         let a_norm = (1.0 - eccentricity).recip();
         let b_norm = a_norm * (1.0 - eccentricity.powi(2)).abs().sqrt();
@@ -65,80 +228,168 @@ impl Trajectory {
         let indices = Self::get_indices(point_count, eccentricity as f32);
         let element_buffer = ElementBuffer::new_with_data(context, &indices);
 
-        Self {
+        let mut trajectory = Self {
             context: context.clone(),
             eccentricity: eccentricity as f32,
             a_norm: a_norm as f32,
             b_norm: b_norm as f32,
             matrix,
-            curr_ecc_anom: eccentric_anomaly,
+            curr_ecc_anom: eccentric_anomaly as f32,
             point_count,
             thickness,
+            conic: PreciseConic::new(orbit, parent_pos_premultiplied, camera_scale),
+            precise_ecc_anom: eccentric_anomaly,
             element_buffer,
+            polyline: None,
+            hyperbola_bounds,
             color,
+            pattern,
+        };
+
+        if mode == TrajectoryMode::Polyline {
+            trajectory.tessellate();
         }
+
+        trajectory
     }
 
     pub fn set_eccentric_anomaly(&mut self, eccentric_anomaly: f64) {
         if self.eccentricity < 1.0 {
-            self.curr_ecc_anom = eccentric_anomaly.rem_euclid(core::f64::consts::TAU) as f32;
+            self.precise_ecc_anom = eccentric_anomaly.rem_euclid(core::f64::consts::TAU);
+            self.curr_ecc_anom = self.precise_ecc_anom as f32;
         } else {
+            self.precise_ecc_anom = eccentric_anomaly;
             self.curr_ecc_anom = eccentric_anomaly as f32;
+            // Hyperbolic paths are centered on the current anomaly
+            if self.polyline.is_some() && self.hyperbola_bounds.is_none() {
+                self.tessellate();
+            }
         }
     }
 
-    pub fn update_from_orbit(&mut self, orbit: &impl OrbitTrait, parent_pos: Vec3) {
-        let matrix = orbit.get_transformation_matrix();
-        let rp = orbit.get_periapsis();
-        let matrix = Matrix4 {
-            x: Vec4::new(
-                (matrix.e11 * rp) as f32,
-                (matrix.e21 * rp) as f32,
-                (matrix.e31 * rp) as f32,
-                0.0,
-            ),
-            y: Vec4::new(
-                (matrix.e12 * rp) as f32,
-                (matrix.e22 * rp) as f32,
-                (matrix.e32 * rp) as f32,
-                0.0,
-            ),
-            z: Vec4::new(0.0, 0.0, 0.0, 0.0),
-            w: Vec4::new(parent_pos.x, parent_pos.y, parent_pos.z, 1.0),
-        };
+    /// Moves and reshapes the line to follow `orbit`, with its parent at
+    /// `parent_pos_premultiplied` in render-world space.
+    ///
+    /// Does nothing if neither the orbit nor where it's drawn changed,
+    /// and refills the existing buffers otherwise.
+    pub fn update_from_orbit(
+        &mut self,
+        orbit: &impl OrbitTrait,
+        parent_pos_premultiplied: DVec3,
+        camera_scale: f64,
+    ) {
+        let conic = PreciseConic::new(orbit, parent_pos_premultiplied, camera_scale);
+        if conic == self.conic {
+            return;
+        }
+
         let eccentricity = orbit.get_eccentricity();
         let a_norm = (1.0 - eccentricity).recip();
         let b_norm = a_norm * (1.0 - eccentricity.powi(2)).abs().sqrt();
 
         let old_eccentricity = self.eccentricity;
 
-        self.matrix = matrix;
+        self.matrix = Self::get_matrix(orbit, parent_pos_premultiplied, camera_scale);
+        self.conic = conic;
         self.eccentricity = eccentricity as f32;
         self.a_norm = a_norm as f32;
         self.b_norm = b_norm as f32;
 
         // Check if orbit kind changed (elliptic → hyperbolic, vice-versa)
         if (old_eccentricity < 1.0) != (eccentricity < 1.0) {
-            self.set_point_count(self.point_count);
+            self.update_indices();
+        }
+        if self.polyline.is_some() {
+            self.tessellate();
         }
     }
 
     pub fn set_point_count(&mut self, point_count: u32) {
-        self.point_count = point_count.max(3);
+        let point_count = point_count.max(3);
+        if point_count == self.point_count {
+            return;
+        }
 
-        let data = Self::get_indices(point_count, self.eccentricity);
+        self.point_count = point_count;
+        self.update_indices();
 
-        self.element_buffer = ElementBuffer::new_with_data(&self.context, &data);
+        if self.polyline.is_some() {
+            self.tessellate();
+        }
     }
 
-    fn get_indices(point_count: u32, eccentricity: f32) -> Vec<u32> {
+    /// Changes which part of a hyperbolic orbit is drawn.
+    /// See [`Trajectory::new`].
+    pub fn set_hyperbola_bounds(&mut self, hyperbola_bounds: Option<(f64, f64)>) {
+        if hyperbola_bounds == self.hyperbola_bounds {
+            return;
+        }
+
+        self.hyperbola_bounds = hyperbola_bounds;
+
+        if self.polyline.is_some() {
+            self.tessellate();
+        }
+    }
+
+    /// How the line's vertices are generated.
+    pub fn mode(&self) -> TrajectoryMode {
+        if self.polyline.is_some() {
+            TrajectoryMode::Polyline
+        } else {
+            TrajectoryMode::Shader
+        }
+    }
+
+    /// The matrix taking the orbit's plane, scaled by the periapsis distance,
+    /// into render-world space.
+    fn get_matrix(
+        orbit: &impl OrbitTrait,
+        parent_pos_premultiplied: DVec3,
+        camera_scale: f64,
+    ) -> Mat4 {
+        let matrix = orbit.get_transformation_matrix();
+        let rp = orbit.get_periapsis() * camera_scale;
+        Matrix4 {
+            x: Vec4::new(
+                (matrix.e11 * rp) as f32,
+                (matrix.e21 * rp) as f32,
+                (matrix.e31 * rp) as f32,
+                0.0,
+            ),
+            y: Vec4::new(
+                (matrix.e12 * rp) as f32,
+                (matrix.e22 * rp) as f32,
+                (matrix.e32 * rp) as f32,
+                0.0,
+            ),
+            z: Vec4::new(0.0, 0.0, 0.0, 0.0),
+            w: Vec4::new(
+                parent_pos_premultiplied.x as f32,
+                parent_pos_premultiplied.y as f32,
+                parent_pos_premultiplied.z as f32,
+                1.0,
+            ),
+        }
+    }
+
+    fn update_indices(&mut self) {
+        let data = Self::get_indices(self.point_count, self.eccentricity);
+        self.element_buffer.fill(&data);
+    }
+
+    fn get_segment_count(point_count: u32, eccentricity: f32) -> u32 {
         let point_count = point_count.max(3);
 
-        let segment_count = if eccentricity < 1.0 {
+        if eccentricity < 1.0 {
             point_count
         } else {
             point_count - 1
-        };
+        }
+    }
+
+    fn get_indices(point_count: u32, eccentricity: f32) -> Vec<u32> {
+        let segment_count = Self::get_segment_count(point_count, eccentricity);
         let mut indices: Vec<u32> = Vec::with_capacity((segment_count * 6) as usize);
         for i in 0..segment_count {
             let base = i * 2;
@@ -154,27 +405,93 @@ impl Trajectory {
         indices
     }
 
+    /// CPU equivalent of `get_eccentric_anomaly` in trajectory.vert, in f64.
+    fn get_point_eccentric_anomaly(&self, point_idx: u32, range: f64) -> f64 {
+        let frac = point_idx as f64 / self.point_count as f64;
+        if self.eccentricity < 1.0 {
+            frac * range
+        } else {
+            -frac * range - self.hyperbolic_start(range)
+        }
+    }
+
+    /// The first eccentric anomaly drawn on a hyperbolic orbit.
+    fn hyperbolic_start(&self, range: f64) -> f64 {
+        match self.hyperbola_bounds {
+            Some((start, _)) => start,
+            None => self.precise_ecc_anom - 0.5 * range,
+        }
+    }
+
+    /// Computes the vertices for the polyline path on the CPU.
+    ///
+    /// Points are sampled in f64, so this is also used for orbits
+    /// that are too eccentric or too large for the shader path.
+    fn tessellate(&mut self) {
+        let range = self.eccentric_anomaly_range() as f64;
+        let vertex_point_count = Self::get_segment_count(self.point_count, self.eccentricity) + 1;
+        let vertex_count = (vertex_point_count * 2) as usize;
+
+        let mut positions = Vec::with_capacity(vertex_count);
+        let mut next_positions = Vec::with_capacity(vertex_count);
+        let mut sides = Vec::with_capacity(vertex_count);
+        let mut eccentric_anomalies = Vec::with_capacity(vertex_count);
+
+        let mut curr_ecc_anom = self.get_point_eccentric_anomaly(0, range);
+        let mut curr_pos = self.conic.point_at_eccentric_anomaly(curr_ecc_anom);
+
+        for point_idx in 0..vertex_point_count {
+            let next_ecc_anom = self.get_point_eccentric_anomaly(point_idx + 1, range);
+            let next_pos = self.conic.point_at_eccentric_anomaly(next_ecc_anom);
+
+            for side in [-1.0, 1.0] {
+                positions.push(curr_pos);
+                next_positions.push(next_pos);
+                sides.push(side);
+                eccentric_anomalies.push(curr_ecc_anom as f32);
+            }
+
+            curr_ecc_anom = next_ecc_anom;
+            curr_pos = next_pos;
+        }
+
+        match &mut self.polyline {
+            Some(polyline) => {
+                polyline.positions.fill(&positions);
+                polyline.next_positions.fill(&next_positions);
+                polyline.sides.fill(&sides);
+                polyline.eccentric_anomalies.fill(&eccentric_anomalies);
+            }
+            None => {
+                self.polyline = Some(PolylineBuffers {
+                    positions: VertexBuffer::new_with_data(&self.context, &positions),
+                    next_positions: VertexBuffer::new_with_data(&self.context, &next_positions),
+                    sides: VertexBuffer::new_with_data(&self.context, &sides),
+                    eccentric_anomalies: VertexBuffer::new_with_data(
+                        &self.context,
+                        &eccentric_anomalies,
+                    ),
+                });
+            }
+        }
+    }
+
     fn eccentric_anomaly_range(&self) -> f32 {
         if self.eccentricity < 1.0 {
             core::f32::consts::TAU
+        } else if let Some((start, end)) = self.hyperbola_bounds {
+            (end - start) as f32
         } else {
-            if self.curr_ecc_anom > 10.0 {
-                // We don't need a path anymore.
-                // It's way too far.
-                0.0
-            } else {
-                // Bell curve to prevent physical range from becoming
-                // too long too quickly.
-                (30.0 * 2.0_f64.powf(-0.15 * (self.curr_ecc_anom as f64).powi(2))) as f32
-            }
+            around_body_range(self.curr_ecc_anom as f64) as f32
         }
     }
 }
 
-const VERTEX_SHADER_SOURCE: &'static str = {
-    const SHADER_VERSION_HEADER: &'static str = "#version 330 core";
-    const SHADER_FULL_SOURCE: &'static str = include_str!("shaders/trajectory.vert");
-    const fn trim_bytes_start_len<'a>(mut bytes: &[u8], mut len: usize) -> &[u8] {
+/// Removes the `#version` line from a shader source.
+///
+/// three-d adds its own version header depending on the platform.
+pub(super) const fn strip_version_header(source: &'static str, header: &str) -> &'static str {
+    const fn trim_bytes_start_len(mut bytes: &[u8], mut len: usize) -> &[u8] {
         while let [_first, rest @ ..] = bytes {
             if len == 0 {
                 break;
@@ -184,20 +501,48 @@ const VERTEX_SHADER_SOURCE: &'static str = {
         }
         bytes
     }
-    match core::str::from_utf8(trim_bytes_start_len(
-        SHADER_FULL_SOURCE.as_bytes(),
-        SHADER_VERSION_HEADER.len(),
-    )) {
+    match core::str::from_utf8(trim_bytes_start_len(source.as_bytes(), header.len())) {
         Ok(v) => v,
         Err(_) => panic!("resulting string not UTF-8"),
     }
-};
+}
+
+pub(super) const SHADER_VERSION_HEADER: &'static str = "#version 330 core";
+
+const VERTEX_SHADER_SOURCE: &'static str = strip_version_header(
+    include_str!("shaders/trajectory.vert"),
+    SHADER_VERSION_HEADER,
+);
+
+pub(super) const POLYLINE_VERTEX_SHADER_SOURCE: &'static str = strip_version_header(
+    include_str!("shaders/trajectory_polyline.vert"),
+    SHADER_VERSION_HEADER,
+);
 
 impl Geometry for Trajectory {
     fn draw(&self, viewer: &dyn Viewer, program: &Program, render_states: RenderStates) {
+        if let Some(polyline) = &self.polyline {
+            program.use_uniform("u_proj_view", viewer.projection() * viewer.view());
+            program.use_uniform("u_thickness_px", self.thickness);
+            program.use_uniform(
+                "u_viewport",
+                Vec2::new(
+                    viewer.viewport().width as f32,
+                    viewer.viewport().height as f32,
+                ),
+            );
+            program.use_vertex_attribute("a_position", &polyline.positions);
+            program.use_vertex_attribute("a_next_position", &polyline.next_positions);
+            program.use_vertex_attribute("a_side", &polyline.sides);
+            program.use_vertex_attribute("a_ecc_anom", &polyline.eccentric_anomalies);
+
+            program.draw_elements(render_states, viewer.viewport(), &self.element_buffer);
+            return;
+        }
+
         let eccentric_anomaly_range = self.eccentric_anomaly_range();
 
-        let start_eccentric_anomaly = self.curr_ecc_anom - 0.5 * eccentric_anomaly_range;
+        let start_eccentric_anomaly = self.hyperbolic_start(eccentric_anomaly_range as f64) as f32;
 
         program.use_uniform("u_proj_view", viewer.projection() * viewer.view());
         program.use_uniform("u_tf", self.matrix);
@@ -238,11 +583,17 @@ impl Geometry for Trajectory {
     }
 
     fn vertex_shader_source(&self) -> String {
-        VERTEX_SHADER_SOURCE.to_owned()
+        match self.polyline {
+            Some(_) => POLYLINE_VERTEX_SHADER_SOURCE.to_owned(),
+            None => VERTEX_SHADER_SOURCE.to_owned(),
+        }
     }
 
     fn id(&self) -> GeometryId {
-        GeometryId(0x5FA5)
+        match self.polyline {
+            Some(_) => GeometryId(0x5FA6),
+            None => GeometryId(0x5FA5),
+        }
     }
 
     fn render_with_material(
@@ -270,36 +621,23 @@ impl Geometry for Trajectory {
     }
 }
 
-const FRAGMENT_SHADER_SOURCE: &'static str = {
-    const VERSION_HEADER: &'static str = "#version 330 core";
-    const SHADER_FULL_SOURCE: &'static str = include_str!("shaders/trajectory.frag");
-    const fn trim_bytes_start_len<'a>(mut bytes: &[u8], mut len: usize) -> &[u8] {
-        while let [_first, rest @ ..] = bytes {
-            if len == 0 {
-                break;
-            }
-            bytes = rest;
-            len -= 1;
-        }
-        bytes
-    }
-    match core::str::from_utf8(trim_bytes_start_len(
-        SHADER_FULL_SOURCE.as_bytes(),
-        VERSION_HEADER.len(),
-    )) {
-        Ok(v) => v,
-        Err(_) => panic!("resulting string not UTF-8"),
-    }
-};
+const FRAGMENT_SHADER_SOURCE: &'static str = strip_version_header(
+    include_str!("shaders/trajectory.frag"),
+    SHADER_VERSION_HEADER,
+);
+
+fn fragment_shader_source() -> String {
+    let a = ColorMapping::fragment_shader_source();
+    let b = FRAGMENT_SHADER_SOURCE;
+    let mut string = String::with_capacity(a.len() + b.len());
+    string.push_str(a);
+    string.push_str(b);
+    string
+}
 
 impl Material for Trajectory {
     fn fragment_shader_source(&self, _lights: &[&dyn three_d::Light]) -> String {
-        let a = ColorMapping::fragment_shader_source();
-        let b = FRAGMENT_SHADER_SOURCE;
-        let mut string = String::with_capacity(a.len() + b.len());
-        string.push_str(a);
-        string.push_str(b);
-        string
+        fragment_shader_source()
     }
 
     fn id(&self) -> EffectMaterialId {
@@ -317,6 +655,7 @@ impl Material for Trajectory {
         program.use_uniform("curr_ecc_anom", self.curr_ecc_anom);
         program.use_uniform("anomaly_range", self.eccentric_anomaly_range());
         program.use_uniform("eccentricity", self.eccentricity);
+        program.use_uniform("pattern", self.pattern.shader_value());
     }
 
     fn render_states(&self) -> RenderStates {
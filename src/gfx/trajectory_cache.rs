@@ -0,0 +1,27 @@
+//! Orbit lines kept from one frame to the next, so their GPU buffers
+//! are refilled as orbits change instead of being created every frame.
+
+use std::collections::HashMap;
+
+use crate::{gfx::trajectory::Trajectory, sim::universe::Id};
+
+/// The orbit lines drawn last frame, by the body they belong to.
+#[derive(Default)]
+pub(crate) struct TrajectoryCache {
+    lines: HashMap<Id, Trajectory>,
+}
+
+impl TrajectoryCache {
+    /// Takes out the line drawn for `id` last frame, if there was one.
+    pub(crate) fn take(&mut self, id: Id) -> Option<Trajectory> {
+        self.lines.remove(&id)
+    }
+
+    /// Keeps the lines drawn this frame for the next one.
+    ///
+    /// Lines that weren't taken back out this frame, like those of
+    /// deleted or hidden bodies, are dropped.
+    pub(crate) fn restore(&mut self, lines: impl IntoIterator<Item = (Id, Trajectory)>) {
+        self.lines = lines.into_iter().collect();
+    }
+}
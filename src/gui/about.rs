@@ -1,6 +1,9 @@
 use three_d::egui::{Align, Context as EguiContext, Layout, RichText, Ui, Window};
 
-use crate::{assets::BANNER, gui::UiState};
+use crate::{
+    assets::BANNER,
+    gui::{UiState, text_style::TextRole},
+};
 
 /// Get the keplerian_sim version from build.rs
 const KEPLERIAN_SIM_VERSION: &str = match option_env!("KEPLERIAN_SIM_VERSION") {
@@ -73,7 +76,7 @@ fn window_contents(ui: &mut Ui) {
 }
 
 fn header(ui: &mut Ui) {
-    let text = RichText::new("Demo for keplerian_sim").size(26.0);
+    let text = RichText::new("Demo for keplerian_sim").text_style(TextRole::Title.into());
     ui.label(text);
 
     ui.label(format!("{}", KEPLERIAN_SIM_VERSION));
@@ -0,0 +1,63 @@
+//! Altitudes above a parent's surface, for the "Altitudes above surface" setting.
+//!
+//! Orbits are worked out from the parent's center, so distances like the
+//! apoapsis are too. With the setting on, the parent's radius is taken off
+//! where they're shown, to match how orbits like the ISS's are usually described.
+
+use std::collections::HashMap;
+
+use float_pretty_print::PrettyPrintFloat;
+use glam::DVec3;
+
+use crate::{
+    cfg::CONFIG,
+    sim::universe::{Id as UniverseId, Universe},
+    units::length::LengthUnit,
+};
+
+/// The radius to take off distances from `parent_id`'s center to show them
+/// as altitudes above its surface.
+///
+/// Returns None while altitudes are shown from the center,
+/// or if there's no such parent.
+pub(super) fn surface_radius(universe: &Universe, parent_id: Option<UniverseId>) -> Option<f64> {
+    let above_surface = CONFIG
+        .try_lock()
+        .map(|cfg| cfg.surface_altitudes.get())
+        .unwrap_or(false);
+    if !above_surface {
+        return None;
+    }
+    parent_id
+        .and_then(|id| universe.get_body(id))
+        .map(|wrapper| wrapper.body.radius)
+}
+
+/// A line for the HUD telling how high `id` is above its parent's surface,
+/// or how far it is from the parent's center while the setting is off.
+///
+/// Returns None for bodies without a parent.
+pub(super) fn readout(
+    universe: &Universe,
+    position_map: &HashMap<UniverseId, DVec3>,
+    id: UniverseId,
+) -> Option<String> {
+    let wrapper = universe.get_body(id)?;
+    let parent_id = wrapper.relations.parent?;
+    let parent = universe.get_body(parent_id)?;
+    let distance = position_map
+        .get(&id)?
+        .distance(*position_map.get(&parent_id)?);
+
+    let (value, text) = match surface_radius(universe, Some(parent_id)) {
+        Some(radius) => (distance - radius, "above"),
+        None => (distance, "from the center of"),
+    };
+    let unit = LengthUnit::largest_unit_from_base(value);
+    Some(format!(
+        "{}: {} {unit} {text} {}",
+        wrapper.body.name,
+        PrettyPrintFloat(value / unit.get_value()),
+        parent.body.name
+    ))
+}
@@ -0,0 +1,44 @@
+//! The results of a benchmark run, shown once it's done.
+
+use three_d::egui::{Align2, Color32, Context as EguiContext, RichText, Window};
+
+use crate::gui::{UiState, help::HelpText, text_style::TextRole};
+
+/// Draws the results while there are any.
+pub(super) fn draw(ctx: &EguiContext, ui_state: &mut UiState) {
+    let Some(report) = &ui_state.benchmark_report else {
+        return;
+    };
+
+    let mut close = false;
+    Window::new("Benchmark Results")
+        .collapsible(false)
+        .resizable(false)
+        .anchor(Align2::CENTER_CENTER, [0.0, 0.0])
+        .show(ctx, |ui| {
+            ui.label(RichText::new(report).monospace().color(Color32::WHITE));
+
+            ui.horizontal(|ui| {
+                let copy_button = ui
+                    .button(RichText::new("Copy").text_style(TextRole::Body.into()))
+                    .on_help_text(
+                        RichText::new("Copy the results to the clipboard as text.")
+                            .color(Color32::WHITE)
+                            .text_style(TextRole::Body.into()),
+                    );
+                if copy_button.clicked() {
+                    ui.ctx().copy_text(report.clone());
+                }
+                if ui
+                    .button(RichText::new("Close").text_style(TextRole::Body.into()))
+                    .clicked()
+                {
+                    close = true;
+                }
+            });
+        });
+
+    if close {
+        ui_state.benchmark_report = None;
+    }
+}
@@ -0,0 +1,141 @@
+use std::collections::HashMap;
+
+use float_pretty_print::PrettyPrintFloat;
+use glam::DVec3;
+use three_d::egui::{
+    Align2, Area, Button, Color32, Context, CursorIcon, Frame, Key, Margin, Order, RichText,
+    ScrollArea,
+};
+
+use crate::{
+    gui::{SimState, declare_id, text_style::TextRole},
+    sim::{proximity, universe::Id as UniverseId},
+    units::length::LengthUnit,
+};
+
+declare_id!(BODY_SWITCHER_AREA, b"NearbyTb");
+
+/// How tall the list gets before it scrolls.
+const MAX_LIST_HEIGHT: f32 = 400.0;
+
+#[derive(Default)]
+pub(crate) struct BodySwitcherState {
+    open: bool,
+    /// The index of the highlighted row, nearest first.
+    selected: usize,
+}
+
+impl BodySwitcherState {
+    /// Opens the switcher with the nearest body highlighted, or closes it.
+    pub(crate) fn toggle(&mut self) {
+        self.open ^= true;
+        self.selected = 0;
+    }
+}
+
+/// Draws the list of bodies nearest to the focused body, while it's open.
+///
+/// The arrow keys move the highlight, Enter focuses the highlighted body,
+/// and Escape closes the list.
+pub(super) fn draw(
+    ctx: &Context,
+    sim_state: &mut SimState,
+    position_map: &HashMap<UniverseId, DVec3>,
+) {
+    if !sim_state.ui.body_switcher_state.open {
+        return;
+    }
+
+    let bodies = proximity::by_distance(position_map, sim_state.focused_body());
+    let state = &mut sim_state.ui.body_switcher_state;
+
+    let (up, down, enter, escape) = ctx.input(|i| {
+        (
+            i.key_pressed(Key::ArrowUp),
+            i.key_pressed(Key::ArrowDown),
+            i.key_pressed(Key::Enter),
+            i.key_pressed(Key::Escape),
+        )
+    });
+    if escape {
+        state.open = false;
+        return;
+    }
+    if up {
+        state.selected = state.selected.saturating_sub(1);
+    }
+    if down {
+        state.selected += 1;
+    }
+    state.selected = state.selected.min(bodies.len().saturating_sub(1));
+    let selected = state.selected;
+    let moved = up || down;
+
+    let universe = &sim_state.universe;
+    let focused_name = universe
+        .get_body(sim_state.focused_body())
+        .map(|w| w.body.name.as_str())
+        .unwrap_or("?");
+
+    let clicked = Area::new(*BODY_SWITCHER_AREA_ID)
+        .order(Order::Foreground)
+        .anchor(Align2::CENTER_CENTER, [0.0, 0.0])
+        .show(ctx, |ui| {
+            Frame::new()
+                .fill(Color32::from_black_alpha(224))
+                .corner_radius(8.0)
+                .inner_margin(Margin::same(8))
+                .show(ui, |ui| {
+                    ui.visuals_mut().override_text_color = Some(Color32::WHITE);
+                    ui.label(
+                        RichText::new(format!("Nearest to {focused_name}"))
+                            .text_style(TextRole::Subheading.into()),
+                    );
+                    ui.separator();
+
+                    if bodies.is_empty() {
+                        ui.label("There are no other bodies.");
+                        return None;
+                    }
+
+                    let mut clicked = None;
+                    ScrollArea::vertical()
+                        .max_height(MAX_LIST_HEIGHT)
+                        .show(ui, |ui| {
+                            for (index, &(id, distance)) in bodies.iter().enumerate() {
+                                let name = universe
+                                    .get_body(id)
+                                    .map(|w| w.body.name.as_str())
+                                    .unwrap_or("?");
+                                let unit = LengthUnit::largest_unit_from_base(distance);
+                                let text = format!(
+                                    "{name}  —  {} {unit}",
+                                    PrettyPrintFloat(distance / unit.get_value())
+                                );
+
+                                let row = ui
+                                    .add(Button::selectable(
+                                        index == selected,
+                                        RichText::new(text).text_style(TextRole::Body.into()),
+                                    ))
+                                    .on_hover_cursor(CursorIcon::PointingHand);
+                                if index == selected && moved {
+                                    row.scroll_to_me(None);
+                                }
+                                if row.clicked() {
+                                    clicked = Some(id);
+                                }
+                            }
+                        });
+                    clicked
+                })
+                .inner
+        })
+        .inner;
+
+    let chosen = clicked.or_else(|| bodies.get(selected).filter(|_| enter).map(|&(id, _)| id));
+    if let Some(id) = chosen {
+        sim_state.switch_focus(id, position_map);
+        sim_state.ui.body_switcher_state.open = false;
+    }
+}
@@ -0,0 +1,175 @@
+//! Interface colors derived from a body's color, so windows, list rows
+//! and overlays about a body can be told apart at a glance.
+//!
+//! Body colors can be anything, from near-black to pure white, so the
+//! derived colors are lightened or darkened until they're readable.
+
+use three_d::Srgba;
+use three_d::egui::{Color32, Context, Frame, Margin, RichText, Stroke, Ui};
+
+use crate::{
+    gfx::palette,
+    gui::text_style::TextRole,
+    sim::universe::{Id as UniverseId, Universe},
+};
+
+/// The fill of windows and panels in the dark theme, which accents are read against.
+const BACKGROUND: Color32 = Color32::from_gray(27);
+
+/// The WCAG contrast ratio for normal text.
+const MIN_CONTRAST: f32 = 4.5;
+
+/// How much of the body's color is kept in the hover fill,
+/// the rest being the background.
+const HOVER_MIX: f32 = 0.45;
+
+/// How finely colors are stepped towards white or black
+/// until they're readable.
+const STEPS: u8 = 20;
+
+/// The colors used for a body's windows, rows and overlays.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct BodyTheme {
+    /// For outlines and markers, and text on the background.
+    pub(crate) accent: Color32,
+    /// Behind headers, which have white text on them.
+    pub(crate) header_fill: Color32,
+    /// Behind hovered rows, which have white text on them.
+    pub(crate) hover_fill: Color32,
+}
+
+impl BodyTheme {
+    pub(crate) fn from_color(color: Srgba) -> Self {
+        let color = Color32::from_rgb(color.r, color.g, color.b);
+
+        let accent = mix_until(color, Color32::WHITE, |c| {
+            contrast(c, BACKGROUND) >= MIN_CONTRAST
+        });
+        let header_fill = mix_until(color, Color32::BLACK, |c| {
+            contrast(c, Color32::WHITE) >= MIN_CONTRAST
+        });
+        let hover_fill = mix_until(
+            mix(color, BACKGROUND, 1.0 - HOVER_MIX),
+            Color32::BLACK,
+            |c| contrast(c, Color32::WHITE) >= MIN_CONTRAST,
+        );
+
+        Self {
+            accent,
+            header_fill,
+            hover_fill,
+        }
+    }
+
+    /// Gets the theme of a body, following the color it's drawn in.
+    pub(crate) fn of(universe: &Universe, id: UniverseId) -> Option<Self> {
+        palette::display_color(universe, id).map(Self::from_color)
+    }
+}
+
+/// The frame of a window about a body, outlined in its accent color.
+pub(super) fn window_frame(ctx: &Context, theme: Option<BodyTheme>) -> Frame {
+    let frame = Frame::window(&ctx.style());
+    match theme {
+        Some(theme) => frame.stroke(Stroke::new(1.5, theme.accent)),
+        None => frame,
+    }
+}
+
+/// A band with the body's name at the top of a window about it.
+pub(super) fn header(ui: &mut Ui, theme: BodyTheme, name: &str) {
+    Frame::new()
+        .fill(theme.header_fill)
+        .stroke(Stroke::new(1.0, theme.accent))
+        .corner_radius(4.0)
+        .inner_margin(Margin::symmetric(8, 4))
+        .show(ui, |ui| {
+            ui.set_width(ui.available_width());
+            ui.label(
+                RichText::new(name)
+                    .strong()
+                    .color(Color32::WHITE)
+                    .text_style(TextRole::Body.into()),
+            );
+        });
+}
+
+/// Mixes `color` towards `target` in small steps, returning the first
+/// mix that's `good`, or `target` if none is.
+fn mix_until(color: Color32, target: Color32, good: impl Fn(Color32) -> bool) -> Color32 {
+    (0..=STEPS)
+        .map(|step| mix(color, target, step as f32 / STEPS as f32))
+        .find(|&c| good(c))
+        .unwrap_or(target)
+}
+
+fn mix(a: Color32, b: Color32, t: f32) -> Color32 {
+    let channel = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * t).round() as u8;
+    Color32::from_rgb(
+        channel(a.r(), b.r()),
+        channel(a.g(), b.g()),
+        channel(a.b(), b.b()),
+    )
+}
+
+/// The WCAG relative luminance of a color.
+fn luminance(color: Color32) -> f32 {
+    let linear = |c: u8| {
+        let c = c as f32 / 255.0;
+        if c <= 0.04045 {
+            c / 12.92
+        } else {
+            ((c + 0.055) / 1.055).powf(2.4)
+        }
+    };
+    0.2126 * linear(color.r()) + 0.7152 * linear(color.g()) + 0.0722 * linear(color.b())
+}
+
+/// The WCAG contrast ratio between two colors, from 1 to 21.
+fn contrast(a: Color32, b: Color32) -> f32 {
+    let (a, b) = (luminance(a), luminance(b));
+    (a.max(b) + 0.05) / (a.min(b) + 0.05)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const fn srgba(r: u8, g: u8, b: u8) -> Srgba {
+        Srgba { r, g, b, a: 255 }
+    }
+
+    #[test]
+    fn themes_are_readable_for_any_body_color() {
+        let colors = [
+            srgba(0, 0, 0),
+            srgba(255, 255, 255),
+            srgba(0, 0, 255),
+            srgba(255, 255, 0),
+            srgba(30, 30, 40),
+            srgba(232, 231, 229),
+            srgba(154, 218, 235),
+        ];
+        for color in colors {
+            let theme = BodyTheme::from_color(color);
+            assert!(contrast(theme.accent, BACKGROUND) >= MIN_CONTRAST);
+            assert!(contrast(theme.header_fill, Color32::WHITE) >= MIN_CONTRAST);
+            assert!(contrast(theme.hover_fill, Color32::WHITE) >= MIN_CONTRAST);
+        }
+    }
+
+    #[test]
+    fn readable_colors_are_kept() {
+        let theme = BodyTheme::from_color(srgba(230, 140, 100));
+        assert_eq!(theme.accent, Color32::from_rgb(230, 140, 100));
+
+        let theme = BodyTheme::from_color(srgba(40, 60, 120));
+        assert_eq!(theme.header_fill, Color32::from_rgb(40, 60, 120));
+    }
+
+    #[test]
+    fn contrast_matches_known_values() {
+        assert!((contrast(Color32::BLACK, Color32::WHITE) - 21.0).abs() < 1e-3);
+        assert_eq!(contrast(BACKGROUND, BACKGROUND), 1.0);
+    }
+}
@@ -1,27 +1,38 @@
-use std::{
-    ops::RangeInclusive,
-    sync::{Arc, LazyLock},
-};
+use std::{ops::RangeInclusive, sync::Arc};
 
 use crate::{
     assets, cfg,
+    gfx::{
+        arrow::VectorOverlayMode,
+        palette::Palette,
+        render_scale::{MAX_RENDER_SCALE, MIN_RENDER_SCALE},
+    },
     gui::{
-        MIN_TOUCH_TARGET_LEN, MIN_TOUCH_TARGET_VEC, SimState, celestials::PreviewBody, declare_id,
+        MIN_TOUCH_TARGET_LEN, MIN_TOUCH_TARGET_VEC, SimState, TabAction, declare_id,
+        help::HelpText,
+        layout,
+        nudge::ElementKind,
+        quit, sci_input, sim_speed_limits,
+        text_style::{self, TextRole},
+        toolbar, ui_scale, warp_queue, windows,
     },
-    sim::{body::Body, universe::BulkMuSetterMode},
-    units::time::{TimeDisplayMode, TimeUnit},
+    notify::{Severity, notify},
+    sim::{
+        library::StartupUniverse,
+        speed::{DEFAULT_MAX_SIM_SPEED, DEFAULT_MIN_SIM_SPEED, is_at_max_speed},
+        universe::BulkMuSetterMode,
+        validation,
+    },
+    units::time::{self, TimeDisplayMode, TimeEntry, TimeUnit},
 };
 use float_pretty_print::PrettyPrintFloat;
-use keplerian_sim::Orbit;
+use keplerian_sim::OrbitTrait;
 use strum::IntoEnumIterator;
-use three_d::{
-    Srgba,
-    egui::{
-        Align2, Area, Atom, Button, Color32, ComboBox, Context, CornerRadius, CursorIcon,
-        DragValue, FontId, Frame, Image, ImageButton, Margin, Popup, PopupCloseBehavior, Rect,
-        RectAlign, Response, RichText, ScrollArea, Shape, Slider, Stroke, TextStyle,
-        TopBottomPanel, Ui, Vec2, style::HandleShape,
-    },
+use three_d::egui::{
+    Align, Align2, Area, Atom, Button, Checkbox, Color32, ComboBox, Context, CornerRadius,
+    CursorIcon, DragValue, Frame, Image, ImageButton, Key, Margin, Popup, PopupCloseBehavior, Pos2,
+    Rect, RectAlign, Response, RichText, ScrollArea, Shape, Slider, Stroke, TextEdit, TextStyle,
+    TopBottomPanel, Ui, Vec2, style::HandleShape,
 };
 
 declare_id!(BOTTOM_PANEL, b"BluRigel");
@@ -29,6 +40,20 @@ declare_id!(PANEL_SHOW_AREA, b"Huzzah!!");
 declare_id!(salt_only, TIME_CONTROL_COMBO_BOX, b"Solstice");
 declare_id!(BOTTOM_BAR_TOGGLE_BUTTON, b"$D0wn^Up");
 declare_id!(salt_only, MU_SETTER_COMBO_BOX, b"whichWAY");
+declare_id!(salt_only, VECTOR_OVERLAY_COMBO_BOX, b"->Arrow>");
+declare_id!(salt_only, LAYOUT_COMBO_BOX, b"[]|[]|[]");
+declare_id!(salt_only, PALETTE_COMBO_BOX, b"Palette!");
+declare_id!(salt_only, STARTUP_UNIVERSE_COMBO_BOX, b"StartUni");
+declare_id!(TIME_EDIT, b"TimeJump");
+declare_id!(salt_only, GRAVITY_INPUT, b"G=6.674!");
+declare_id!(salt_only, MIN_SIM_SPEED_INPUT, b"SlowTime");
+declare_id!(salt_only, MAX_SIM_SPEED_INPUT, b"FastTime");
+
+/// The smallest gravity multiplier that can be set.
+const MIN_GRAVITY: f64 = 1e-20;
+
+/// Tints the time controls while time runs backwards.
+const REVERSE_TINT: Color32 = Color32::from_rgb(255, 176, 96);
 
 pub(super) struct BottomBarState {
     time_disp: TimeDisplayMode,
@@ -38,6 +63,20 @@ pub(super) struct BottomBarState {
     time_speed_unit_auto: bool,
     expanded: bool,
     options_open: bool,
+    /// The UI scale while its slider is dragged, applied once it's let go
+    /// so the slider doesn't move out from under the pointer.
+    ui_scale_draft: Option<f32>,
+    /// The font size being dragged to, applied once the slider is let go.
+    font_size_draft: Option<f32>,
+    time_edit: Option<TimeEditState>,
+    /// How wide the toolbar was last frame, to center it by.
+    toolbar_width: f32,
+}
+
+/// The time display while it's being typed into.
+struct TimeEditState {
+    text: String,
+    requesting_focus: bool,
 }
 
 impl Default for BottomBarState {
@@ -50,13 +89,14 @@ impl Default for BottomBarState {
             time_speed_unit_auto: true,
             expanded: true,
             options_open: false,
+            ui_scale_draft: None,
+            font_size_draft: None,
+            time_edit: None,
+            toolbar_width: MIN_TOUCH_TARGET_VEC.x * 3.0,
         }
     }
 }
 
-pub(super) const TIME_SPEED_DRAG_VALUE_TEXT_STYLE_NAME: LazyLock<Arc<str>> =
-    LazyLock::new(|| Arc::from("TSDVF"));
-
 fn format_dv_number(number: f64, _: RangeInclusive<usize>) -> String {
     let number = PrettyPrintFloat(number);
     format!("{number:5.1}")
@@ -102,6 +142,8 @@ fn bottom_panel_contents(ui: &mut Ui, sim_state: &mut SimState, elapsed_time: f6
     ui.set_height(MIN_TOUCH_TARGET_LEN);
     ui.add_space(16.0);
     pause_button(ui, sim_state);
+    reverse_button(ui, sim_state);
+    warp_queue::queue_button(ui, sim_state);
 
     if ui.available_width() > 900.0 {
         time_display(ui, sim_state);
@@ -119,13 +161,14 @@ fn bottom_panel_contents(ui: &mut Ui, sim_state: &mut SimState, elapsed_time: f6
 
     let remaining_width = ui.available_width()
         - END_ITEMS_SIZE.x
-        - ui.spacing().item_spacing.x * 4.0
-        - WINDOW_TOGGLES_TOTAL_SIZE.x
+        - ui.spacing().item_spacing.x * 5.0
+        - sim_state.ui.bottom_bar_state.toolbar_width
         - 16.0; // 16.0 from the space before the pause button
     let spacing = remaining_width.max(0.0) / 2.0;
 
     ui.add_space(spacing);
-    window_toggles(ui, sim_state);
+    let toolbar = toolbar::draw(ui, sim_state);
+    sim_state.ui.bottom_bar_state.toolbar_width = toolbar.rect.width();
     ui.add_space(spacing);
 
     end_items(ui, sim_state);
@@ -136,7 +179,7 @@ fn time_manager(ui: &mut Ui, sim_state: &mut SimState, elapsed_time: f64) {
 
     let hover_text = RichText::new("Manage time")
         .color(Color32::WHITE)
-        .size(16.0);
+        .text_style(TextRole::Body.into());
 
     ui.scope(|ui| {
         ui.spacing_mut().button_padding = Vec2::ZERO;
@@ -151,7 +194,7 @@ fn time_manager(ui: &mut Ui, sim_state: &mut SimState, elapsed_time: f64) {
             .corner_radius(MIN_TOUCH_TARGET_LEN);
         let button = ui
             .add(button)
-            .on_hover_text(hover_text)
+            .on_help_text(hover_text)
             .on_hover_cursor(CursorIcon::PointingHand);
 
         let popup = Popup::menu(&button).close_behavior(PopupCloseBehavior::CloseOnClickOutside);
@@ -176,22 +219,50 @@ fn time_manager(ui: &mut Ui, sim_state: &mut SimState, elapsed_time: f64) {
             sim_state.sim_speed / sim_state.ui.bottom_bar_state.time_speed_unit.get_value()
         ),
     );
-    let text = RichText::new(string).monospace().color(Color32::WHITE);
-    ui.label(text);
+    let (_, max_speed) = sim_speed_limits();
+    if is_at_max_speed(sim_state.sim_speed, max_speed) {
+        let text = RichText::new(string).monospace().color(Color32::YELLOW);
+        ui.label(text).on_help_text(
+            RichText::new(format!(
+                "⚠ Time is running at the maximum speed of {}/s.\n\
+                The limit can be changed with \"Max time speed\" in the options.",
+                TimeDisplayMode::MultiUnit.format_time(max_speed)
+            ))
+            .color(Color32::WHITE)
+            .text_style(TextRole::Body.into()),
+        );
+    } else {
+        let text = RichText::new(string).monospace().color(Color32::WHITE);
+        ui.label(text);
+    }
+}
+
+/// The play icon, pointing left.
+fn reverse_play_image() -> Image<'static> {
+    assets::PLAY_IMAGE
+        .clone()
+        .uv(Rect::from_min_max(Pos2::new(1.0, 0.0), Pos2::new(0.0, 1.0)))
 }
 
 fn pause_button(ui: &mut Ui, sim_state: &mut SimState) {
-    let image: &Image<'static> = if sim_state.running {
-        &*assets::PAUSED_IMAGE
-    } else {
-        &*assets::PLAY_IMAGE
+    let reversed = sim_state.sim_speed < 0.0;
+
+    let image = match (sim_state.running, reversed) {
+        (true, false) => assets::PAUSED_IMAGE.clone(),
+        (true, true) => assets::PAUSED_IMAGE.clone().tint(REVERSE_TINT),
+        (false, false) => assets::PLAY_IMAGE.clone(),
+        (false, true) => reverse_play_image(),
     };
 
-    let hover_string = match sim_state.running {
-        true => "Currently running\nClick/tap to pause",
-        false => "Currently paused\nClick/tap to resume",
+    let hover_string = match (sim_state.running, reversed) {
+        (true, false) => "Currently running\nClick/tap to pause",
+        (true, true) => "Currently running backwards\nClick/tap to pause",
+        (false, false) => "Currently paused\nClick/tap to resume",
+        (false, true) => "Currently paused\nClick/tap to resume backwards",
     };
-    let hover_text = RichText::new(hover_string).color(Color32::WHITE).size(16.0);
+    let hover_text = RichText::new(hover_string)
+        .color(Color32::WHITE)
+        .text_style(TextRole::Body.into());
 
     ui.scope(|ui| {
         ui.spacing_mut().button_padding = Vec2::ZERO;
@@ -202,12 +273,12 @@ fn pause_button(ui: &mut Ui, sim_state: &mut SimState) {
         widget_styles.hovered.bg_stroke = Stroke::NONE;
         widget_styles.active.weak_bg_fill = Color32::from_white_alpha(64);
 
-        let button = ImageButton::new(image.clone().max_size(MIN_TOUCH_TARGET_VEC))
+        let button = ImageButton::new(image.max_size(MIN_TOUCH_TARGET_VEC))
             .corner_radius(CornerRadius::same(MIN_TOUCH_TARGET_LEN as u8));
 
         let button_instance = ui
             .add(button)
-            .on_hover_text(hover_text)
+            .on_help_text(hover_text)
             .on_hover_cursor(CursorIcon::PointingHand);
         if button_instance.clicked() {
             sim_state.running = !sim_state.running;
@@ -215,9 +286,56 @@ fn pause_button(ui: &mut Ui, sim_state: &mut SimState) {
     });
 }
 
+/// Flips the direction time runs in.
+fn reverse_button(ui: &mut Ui, sim_state: &mut SimState) {
+    let reversed = sim_state.sim_speed < 0.0;
+
+    let hover_string = match reversed {
+        true => "Time is running backwards\nClick/tap to run it forwards",
+        false => "Time is running forwards\nClick/tap to run it backwards",
+    };
+    let hover_text = RichText::new(hover_string)
+        .color(Color32::WHITE)
+        .text_style(TextRole::Body.into());
+
+    let image = reverse_play_image().fit_to_exact_size(MIN_TOUCH_TARGET_VEC * 0.6);
+    let image = if reversed {
+        image.tint(REVERSE_TINT)
+    } else {
+        image
+    };
+
+    ui.scope(|ui| {
+        ui.spacing_mut().button_padding = MIN_TOUCH_TARGET_VEC * 0.2;
+        let widget_styles = &mut ui.visuals_mut().widgets;
+        widget_styles.inactive.weak_bg_fill = Color32::TRANSPARENT;
+        widget_styles.inactive.bg_stroke = Stroke::NONE;
+        widget_styles.hovered.weak_bg_fill = Color32::from_white_alpha(16);
+        widget_styles.hovered.bg_stroke = Stroke::NONE;
+        widget_styles.active.weak_bg_fill = Color32::from_white_alpha(64);
+
+        let button = ImageButton::new(image)
+            .selected(reversed)
+            .corner_radius(CornerRadius::same(MIN_TOUCH_TARGET_LEN as u8));
+
+        let button_instance = ui
+            .add(button)
+            .on_help_text(hover_text)
+            .on_hover_cursor(CursorIcon::PointingHand);
+        if button_instance.clicked() {
+            sim_state.sim_speed = -sim_state.sim_speed;
+        }
+    });
+}
+
 fn time_display(ui: &mut Ui, sim_state: &mut SimState) {
     let display_size = Vec2::new(220.0, MIN_TOUCH_TARGET_LEN);
 
+    if sim_state.ui.bottom_bar_state.time_edit.is_some() {
+        time_edit(ui, sim_state, display_size);
+        return;
+    }
+
     let string = sim_state
         .ui
         .bottom_bar_state
@@ -227,14 +345,17 @@ fn time_display(ui: &mut Ui, sim_state: &mut SimState) {
     let text = RichText::new(string)
         .monospace()
         .color(Color32::WHITE)
-        .size(16.0);
+        .text_style(TextRole::Body.into());
 
     let hover_string = format!(
-        "Currently in {} mode\nLeft click to cycle, right click to cycle backwards",
+        "Currently in {} mode\nLeft click to cycle, right click to cycle backwards\n\
+        Double click to type in a time to jump to",
         sim_state.ui.bottom_bar_state.time_disp
     );
 
-    let hover_text = RichText::new(hover_string).color(Color32::WHITE).size(16.0);
+    let hover_text = RichText::new(hover_string)
+        .color(Color32::WHITE)
+        .text_style(TextRole::Body.into());
 
     ui.scope(|ui| {
         ui.spacing_mut().button_padding = Vec2::ZERO;
@@ -248,10 +369,18 @@ fn time_display(ui: &mut Ui, sim_state: &mut SimState) {
         let button = Button::new(text).wrap().min_size(display_size);
         let button_instance = ui
             .add(button)
-            .on_hover_text(hover_text)
+            .on_help_text(hover_text)
             .on_hover_cursor(CursorIcon::PointingHand);
 
-        if button_instance.clicked() {
+        if button_instance.double_clicked() {
+            // The first click of the two already cycled the format, so put it back
+            let state = &mut sim_state.ui.bottom_bar_state;
+            state.time_disp = state.time_disp.get_prev();
+            state.time_edit = Some(TimeEditState {
+                text: String::new(),
+                requesting_focus: true,
+            });
+        } else if button_instance.clicked() {
             sim_state.ui.bottom_bar_state.time_disp =
                 sim_state.ui.bottom_bar_state.time_disp.get_next();
         }
@@ -262,6 +391,104 @@ fn time_display(ui: &mut Ui, sim_state: &mut SimState) {
     });
 }
 
+fn time_edit(ui: &mut Ui, sim_state: &mut SimState, display_size: Vec2) {
+    let Some(mut state) = sim_state.ui.bottom_bar_state.time_edit.take() else {
+        return;
+    };
+
+    let hover_text = RichText::new(
+        "Type a time to jump to, like \"3.2 y\" or \"100 d 4 h\",\n\
+        or start with + or - to move by that much, like \"+30 d\".\n\
+        \"+1 orbit\" moves by the focused body's orbital period.\n\
+        Press Enter to jump, or Escape to cancel.",
+    )
+    .color(Color32::WHITE)
+    .text_style(TextRole::Body.into());
+
+    // The current time as a hint, since typing over it would round it
+    let hint = TimeDisplayMode::MultiUnit.format_time(sim_state.universe.time);
+    let text_edit = TextEdit::singleline(&mut state.text)
+        .id(*TIME_EDIT_ID)
+        .hint_text(hint)
+        .font(TextStyle::Monospace)
+        .desired_width(display_size.x)
+        .min_size(display_size)
+        .vertical_align(Align::Center);
+    let response = ui.add(text_edit).on_help_text(hover_text);
+
+    if state.requesting_focus {
+        response.request_focus();
+        if response.has_focus() {
+            state.requesting_focus = false;
+        }
+    }
+
+    if response.lost_focus() {
+        if !ui.input(|i| i.key_pressed(Key::Enter)) {
+            // Clicked away or pressed Escape
+            return;
+        }
+
+        match resolve_time_entry(sim_state, &state.text) {
+            Ok(time) => {
+                sim_state.universe.time = time;
+                return;
+            }
+            Err(reason) => {
+                notify(
+                    Severity::Warning,
+                    format!("Couldn't jump in time: {reason}"),
+                );
+                state.requesting_focus = true;
+            }
+        }
+    }
+
+    sim_state.ui.bottom_bar_state.time_edit = Some(state);
+}
+
+/// Works out the universe time to jump to from what was typed into the time display.
+pub(super) fn resolve_time_entry(sim_state: &SimState, text: &str) -> Result<f64, String> {
+    let now = sim_state.universe.time;
+    let entry = time::parse_time_entry(text).ok_or_else(|| {
+        format!(
+            "\"{}\" isn't a time, like \"3.2 y\" or \"+30 d\"",
+            text.trim()
+        )
+    })?;
+
+    let time = match entry {
+        TimeEntry::At(time) => time,
+        TimeEntry::By(delta) => now + delta,
+        TimeEntry::Orbits(orbits) => {
+            let wrapper = sim_state
+                .universe
+                .get_body(sim_state.focused_body())
+                .ok_or("no body is focused")?;
+            let name = &wrapper.body.name;
+            let orbit = wrapper
+                .body
+                .orbit
+                .as_ref()
+                .ok_or_else(|| format!("{name} doesn't orbit anything"))?;
+            let period = orbit.get_orbital_period();
+
+            let closed = period.is_finite() && period > 0.0;
+            if !closed {
+                return Err(format!("{name} isn't on a closed orbit"));
+            }
+
+            now + orbits * period
+        }
+    };
+
+    if time.is_finite() {
+        Ok(time)
+    } else {
+        Err("that's too far away".to_owned())
+    }
+}
+
 fn time_control(ui: &mut Ui, sim_state: &mut SimState, elapsed_time: f64, column_mode: bool) {
     ui.scope(|ui| {
         time_slider(ui, sim_state, elapsed_time, column_mode);
@@ -278,10 +505,11 @@ fn time_slider(ui: &mut Ui, sim_state: &mut SimState, elapsed_time: f64, column_
     let hover_text = RichText::new(
         "Move the slider left to decelerate time.\n\
         Move the slider right to accelerate time.\n\
+        This works the same when time runs backwards.\n\
         Let go to stop changing time.",
     )
     .color(Color32::WHITE)
-    .size(16.0);
+    .text_style(TextRole::Body.into());
     ui.spacing_mut().interact_size.y = MIN_TOUCH_TARGET_LEN;
     let slider = Slider::new(
         &mut sim_state.ui.bottom_bar_state.time_slider_pos,
@@ -294,11 +522,12 @@ fn time_slider(ui: &mut Ui, sim_state: &mut SimState, elapsed_time: f64, column_
         ui.spacing_mut().slider_width = ui.available_width();
     }
 
-    let slider_instance = ui.add(slider).on_hover_text(hover_text);
+    let slider_instance = ui.add(slider).on_help_text(hover_text);
 
     if slider_instance.is_pointer_button_down_on() {
         let base = 10.0f64.powf(sim_state.ui.bottom_bar_state.time_slider_pos);
         sim_state.sim_speed *= base.powf(elapsed_time / 1000.0);
+        sim_state.limit_sim_speed();
         ui.ctx().set_cursor_icon(CursorIcon::Grabbing);
     } else {
         sim_state.ui.bottom_bar_state.time_slider_pos *= (-5.0 * elapsed_time / 1000.0).exp();
@@ -318,12 +547,14 @@ fn time_drag_value(ui: &mut Ui, sim_state: &mut SimState) {
         Click/tap to enter in an amount manually.",
     )
     .color(Color32::WHITE)
-    .size(16.0);
-    let dv_instance = dv_instance.on_hover_text(hover_text);
-
-    if prev_speed_amt != sim_state.ui.bottom_bar_state.time_speed_amount {
-        sim_state.sim_speed = sim_state.ui.bottom_bar_state.time_speed_amount
-            * sim_state.ui.bottom_bar_state.time_speed_unit.get_value();
+    .text_style(TextRole::Body.into());
+    let dv_instance = dv_instance.on_help_text(hover_text);
+
+    // Typed-in infinities and NaNs are thrown away
+    let amount = sim_state.ui.bottom_bar_state.time_speed_amount;
+    if prev_speed_amt != amount && amount.is_finite() {
+        sim_state.sim_speed = amount * sim_state.ui.bottom_bar_state.time_speed_unit.get_value();
+        sim_state.limit_sim_speed();
     }
 
     if sim_state.ui.bottom_bar_state.time_speed_unit_auto && !dv_instance.dragged() {
@@ -335,11 +566,7 @@ fn time_drag_value(ui: &mut Ui, sim_state: &mut SimState) {
 }
 fn time_drag_value_inner(ui: &mut Ui, sim_state: &mut SimState) -> Response {
     let dv_size = Vec2::new(MIN_TOUCH_TARGET_LEN * 2.0, MIN_TOUCH_TARGET_LEN);
-    let style_name = Arc::clone(&*TIME_SPEED_DRAG_VALUE_TEXT_STYLE_NAME);
-    ui.style_mut()
-        .text_styles
-        .insert(TextStyle::Name(style_name.clone()), FontId::monospace(16.0));
-    ui.style_mut().drag_value_text_style = TextStyle::Name(style_name);
+    ui.style_mut().drag_value_text_style = TextRole::Monospace.into();
     ui.spacing_mut().button_padding = Vec2::new(16.0, 8.0);
     let widget_styles = &mut ui.visuals_mut().widgets;
     widget_styles.inactive.weak_bg_fill = Color32::TRANSPARENT;
@@ -356,12 +583,14 @@ fn time_drag_value_inner(ui: &mut Ui, sim_state: &mut SimState) -> Response {
 fn time_unit_box(ui: &mut Ui, sim_state: &mut SimState) {
     let unit_string = format!("{}/s", sim_state.ui.bottom_bar_state.time_speed_unit);
 
-    let unit_text = RichText::new(unit_string).color(Color32::WHITE).size(16.0);
+    let unit_text = RichText::new(unit_string)
+        .color(Color32::WHITE)
+        .text_style(TextRole::Body.into());
 
     let hover_text =
         RichText::new("Pick a different time speed unit or disable automatic unit selection")
             .color(Color32::WHITE)
-            .size(16.0);
+            .text_style(TextRole::Body.into());
 
     ui.spacing_mut().interact_size.y = MIN_TOUCH_TARGET_LEN;
     ui.spacing_mut().button_padding.x = 16.0;
@@ -371,11 +600,11 @@ fn time_unit_box(ui: &mut Ui, sim_state: &mut SimState) {
         .height(f32::INFINITY)
         .show_ui(ui, |ui| time_unit_box_inner(ui, sim_state, true))
         .response
-        .on_hover_text(hover_text)
+        .on_help_text(hover_text)
         .on_hover_cursor(CursorIcon::PointingHand);
 }
 fn time_unit_box_inner(ui: &mut Ui, sim_state: &mut SimState, per_second: bool) {
-    let font = FontId::proportional(16.0);
+    let font = TextRole::Body.resolve(ui.style());
 
     for unit in TimeUnit::iter() {
         let string = if per_second {
@@ -405,7 +634,7 @@ fn time_unit_box_inner(ui: &mut Ui, sim_state: &mut SimState, per_second: bool)
     }
 }
 fn time_unit_box_popup(ui: &mut Ui, sim_state: &mut SimState) {
-    let font = FontId::proportional(16.0);
+    let font = TextRole::Body.resolve(ui.style());
 
     let unit = sim_state.ui.bottom_bar_state.time_speed_unit;
     let title_string = if sim_state.ui.bottom_bar_state.time_speed_unit_auto {
@@ -423,118 +652,43 @@ fn time_unit_box_popup(ui: &mut Ui, sim_state: &mut SimState) {
     });
 }
 
-const WINDOW_TOGGLES_TOTAL_SIZE: Vec2 = Vec2::new(
-    WINDOW_TOGGLE_BUTTON_SIZE.x * 3.0,
-    WINDOW_TOGGLE_BUTTON_SIZE.y,
+const END_ITEMS_SIZE: Vec2 = Vec2::new(
+    HELP_TOGGLE_SIZE.x + OPTIONS_BUTTON_SIZE.x + COLLAPSE_TOGGLE_SIZE.x,
+    MIN_TOUCH_TARGET_VEC.y,
 );
-const WINDOW_TOGGLE_BUTTON_SIZE: Vec2 = MIN_TOUCH_TARGET_VEC;
-fn window_toggles(ui: &mut Ui, sim_state: &mut SimState) {
-    ui.spacing_mut().button_padding = Vec2::ZERO;
-    let widget_styles = &mut ui.visuals_mut().widgets;
-    widget_styles.inactive.weak_bg_fill = Color32::TRANSPARENT;
-    widget_styles.inactive.bg_stroke = Stroke::NONE;
-    widget_styles.hovered.weak_bg_fill = Color32::from_white_alpha(16);
-    widget_styles.hovered.bg_stroke = Stroke::NONE;
-    widget_styles.active.weak_bg_fill = Color32::from_white_alpha(64);
-
-    let list_open = &mut sim_state.ui.body_list_window_state.window_open;
-    let list_button = ImageButton::new(assets::TREE_LIST_IMAGE.clone()).selected(*list_open);
-    let list_button = ui
-        .add_sized(WINDOW_TOGGLE_BUTTON_SIZE, list_button)
-        .on_hover_text(
-            RichText::new("Toggle celestial body list")
-                .color(Color32::WHITE)
-                .size(16.0),
-        )
-        .on_hover_cursor(CursorIcon::PointingHand);
 
-    if list_button.clicked() {
-        *list_open ^= true;
-    }
-
-    let add_open = sim_state.preview_body.is_some();
-    let add_button = ImageButton::new(assets::ADD_ORBIT_IMAGE.clone()).selected(add_open);
-    let add_button = ui
-        .add_sized(WINDOW_TOGGLE_BUTTON_SIZE, add_button)
-        .on_hover_text(
-            RichText::new(
-                "Add a new celestial body\n\
-                Toggling this off will delete your uncommitted body!",
-            )
-            .color(Color32::WHITE)
-            .size(16.0),
-        )
-        .on_hover_cursor(CursorIcon::PointingHand);
-
-    if add_button.clicked() {
-        if sim_state.preview_body.is_some() {
-            sim_state.preview_body = None;
-        } else {
-            let root = sim_state
-                .universe
-                .get_bodies()
-                .iter()
-                .min_by_key(|(id, _)| **id);
-
-            if let Some((&root_id, root_wrapper)) = root {
-                let root_body = &root_wrapper.body;
-                sim_state.preview_body = Some(PreviewBody {
-                    body: Body {
-                        mass: 1.0,
-                        name: format!("Child of {}", &root_body.name),
-                        radius: root_body.radius * 0.1,
-                        color: Srgba::WHITE,
-                        orbit: Some(Orbit::new(
-                            0.0,
-                            root_body.radius * 2.0,
-                            0.0,
-                            0.0,
-                            0.0,
-                            0.0,
-                            root_body.mass * sim_state.universe.get_gravitational_constant(),
-                        )),
-                    },
-                    parent_id: Some(root_id),
-                })
-            } else {
-                sim_state.preview_body = Some(PreviewBody {
-                    body: Body::default(),
-                    parent_id: None,
-                })
-            }
-        }
-    }
+fn end_items(ui: &mut Ui, sim_state: &mut SimState) {
+    help_toggle(ui, sim_state);
+    options_button(ui, sim_state);
+    collapse_toggle(ui, sim_state);
+}
 
-    let edit_open = &mut sim_state.ui.edit_body_window_state.window_open;
-    let edit_button = ImageButton::new(assets::EDIT_ORBIT_IMAGE.clone()).selected(*edit_open);
-    let edit_button = ui
-        .add_sized(WINDOW_TOGGLE_BUTTON_SIZE, edit_button)
-        .on_hover_text(
+const HELP_TOGGLE_SIZE: Vec2 = MIN_TOUCH_TARGET_VEC;
+fn help_toggle(ui: &mut Ui, sim_state: &mut SimState) {
+    let help_mode = &mut sim_state.ui.help_mode;
+    let button = Button::selectable(
+        *help_mode,
+        RichText::new("?").text_style(TextRole::Symbol.into()),
+    )
+    .min_size(HELP_TOGGLE_SIZE);
+    let button = ui
+        .add(button)
+        .on_help_text(
             RichText::new(
-                "Toggle celestial body edit window\n\
-                This allows editing and viewing the \
-                currently-focused body's stats.",
+                "Help mode\n\
+                Shows descriptions right away, and keeps the last one \
+                in a panel on the side. Tapping a control also shows its description.",
             )
             .color(Color32::WHITE)
-            .size(16.0),
+            .text_style(TextRole::Body.into()),
         )
         .on_hover_cursor(CursorIcon::PointingHand);
 
-    if edit_button.clicked() {
-        *edit_open ^= true;
+    if button.clicked() {
+        *help_mode ^= true;
     }
 }
 
-const END_ITEMS_SIZE: Vec2 = Vec2::new(
-    OPTIONS_BUTTON_SIZE.x + COLLAPSE_TOGGLE_SIZE.x,
-    MIN_TOUCH_TARGET_VEC.y,
-);
-
-fn end_items(ui: &mut Ui, sim_state: &mut SimState) {
-    options_button(ui, sim_state);
-    collapse_toggle(ui, sim_state);
-}
-
 const OPTIONS_BUTTON_SIZE: Vec2 = MIN_TOUCH_TARGET_VEC;
 fn options_button(ui: &mut Ui, sim_state: &mut SimState) {
     let button = ImageButton::new(assets::OPTIONS.clone())
@@ -542,10 +696,10 @@ fn options_button(ui: &mut Ui, sim_state: &mut SimState) {
 
     let button = ui
         .add_sized(OPTIONS_BUTTON_SIZE, button)
-        .on_hover_text(
+        .on_help_text(
             RichText::new("Options and About")
                 .color(Color32::WHITE)
-                .size(16.0),
+                .text_style(TextRole::Body.into()),
         )
         .on_hover_cursor(CursorIcon::PointingHand);
 
@@ -579,21 +733,31 @@ fn options_menu(ui: &mut Ui, sim_state: &mut SimState) -> bool {
     const G_TOOLTIP: &'static str = "Gravity multiplier.\n\
         Change how strong the \"force\" of gravity is.\n\
         Default: 6.67e-11";
-    let tooltip = Arc::new(RichText::new(G_TOOLTIP).color(Color32::WHITE).size(16.0));
+    let tooltip = Arc::new(
+        RichText::new(G_TOOLTIP)
+            .color(Color32::WHITE)
+            .text_style(TextRole::Body.into()),
+    );
 
     let label_text = RichText::new("Gravity multi.")
         .color(Color32::WHITE)
-        .size(16.0);
-    ui.label(label_text).on_hover_text(Arc::clone(&tooltip));
+        .text_style(TextRole::Body.into());
+    ui.label(label_text).on_help_text(Arc::clone(&tooltip));
     let initial_g = sim_state.universe.get_gravitational_constant();
     let mut g = initial_g.clone();
-    let dv = DragValue::new(&mut g)
-        .speed(initial_g * 1e-3)
-        .range(1e-20..=f64::MAX)
-        .custom_formatter(|g, _| format!("{:15.15}", PrettyPrintFloat(g)))
-        .update_while_editing(false);
+    if sci_input::scientific_entry_enabled() {
+        sci_input::scientific_value(GRAVITY_INPUT_SALT, ui, &mut g, MIN_GRAVITY)
+            .response
+            .on_help_text(tooltip);
+    } else {
+        let dv = DragValue::new(&mut g)
+            .speed(initial_g * 1e-3)
+            .range(MIN_GRAVITY..=f64::MAX)
+            .custom_formatter(|g, _| format!("{:15.15}", PrettyPrintFloat(g)))
+            .update_while_editing(false);
 
-    ui.add(dv).on_hover_text(tooltip);
+        ui.add(dv).on_help_text(tooltip);
+    }
 
     if g != initial_g {
         sim_state
@@ -607,47 +771,1003 @@ fn options_menu(ui: &mut Ui, sim_state: &mut SimState) -> bool {
         Change the behavior of celestial bodies when their \
         gravitational parameter (parent mass × gravitational multiplier) is modified.";
 
-    let tooltip = Arc::new(RichText::new(MU_TOOLTIP).color(Color32::WHITE).size(16.0));
+    let tooltip = Arc::new(
+        RichText::new(MU_TOOLTIP)
+            .color(Color32::WHITE)
+            .text_style(TextRole::Body.into()),
+    );
 
     let label_text = RichText::new("µ setter mode")
         .color(Color32::WHITE)
-        .size(16.0);
+        .text_style(TextRole::Body.into());
 
-    ui.label(label_text).on_hover_text(Arc::clone(&tooltip));
+    ui.label(label_text).on_help_text(Arc::clone(&tooltip));
 
     let mode_text = RichText::new(sim_state.mu_setter_mode.name())
         .color(Color32::WHITE)
-        .size(16.0);
+        .text_style(TextRole::Body.into());
 
     let cb = ComboBox::from_id_salt(MU_SETTER_COMBO_BOX_SALT)
         .selected_text(mode_text)
         .show_ui(ui, |ui| mu_mode_menu(ui, &mut sim_state.mu_setter_mode));
 
-    cb.response.on_hover_text(Arc::clone(&tooltip));
+    cb.response.on_help_text(Arc::clone(&tooltip));
+
+    let mut force_open = cb.inner.unwrap_or(false);
+
+    ui.separator();
+
+    const OVERLAY_TOOLTIP: &str = "Vector overlay.\n\
+        Draw arrows on each body showing how it moves.\n\
+        Arrow lengths are logarithmic: each step of length is ten times the magnitude.";
+
+    let tooltip = Arc::new(
+        RichText::new(OVERLAY_TOOLTIP)
+            .color(Color32::WHITE)
+            .text_style(TextRole::Body.into()),
+    );
+
+    let label_text = RichText::new("Vector overlay")
+        .color(Color32::WHITE)
+        .text_style(TextRole::Body.into());
+
+    ui.label(label_text).on_help_text(Arc::clone(&tooltip));
+
+    let mode_text = RichText::new(sim_state.vector_overlay.name())
+        .color(Color32::WHITE)
+        .text_style(TextRole::Body.into());
+
+    let cb = ComboBox::from_id_salt(VECTOR_OVERLAY_COMBO_BOX_SALT)
+        .selected_text(mode_text)
+        .show_ui(ui, |ui| {
+            vector_overlay_menu(ui, &mut sim_state.vector_overlay)
+        });
+
+    cb.response.on_help_text(tooltip);
+
+    force_open |= cb.inner.unwrap_or(false);
+
+    ui.separator();
+
+    const LAYOUT_TOOLTIP: &str = "Window layout.\n\
+        Dock the body windows into sidebars on the sides of the screen, \
+        or let them float freely.\n\
+        Floating windows snap to the screen edges when released near one.";
+
+    let tooltip = Arc::new(
+        RichText::new(LAYOUT_TOOLTIP)
+            .color(Color32::WHITE)
+            .text_style(TextRole::Body.into()),
+    );
+
+    let label_text = RichText::new("Window layout")
+        .color(Color32::WHITE)
+        .text_style(TextRole::Body.into());
+
+    ui.label(label_text).on_help_text(Arc::clone(&tooltip));
+
+    let layout_text = RichText::new(layout::layout_name(&sim_state.ui.layout_state))
+        .color(Color32::WHITE)
+        .text_style(TextRole::Body.into());
+
+    let cb = ComboBox::from_id_salt(LAYOUT_COMBO_BOX_SALT)
+        .selected_text(layout_text)
+        .show_ui(ui, |ui| {
+            layout::layout_menu(ui, &mut sim_state.ui.layout_state)
+        });
+
+    cb.response.on_help_text(tooltip);
+
+    force_open |= cb.inner.unwrap_or(false);
+
+    ui.separator();
+
+    const REFRESH_TOOLTIP: &str = "Derived info refresh interval.\n\
+        How often the derived information of a body is recalculated while the simulation runs.\n\
+        Changes to the body are always shown immediately.\n\
+        Set to 0 to recalculate every frame.";
+
+    let tooltip = Arc::new(
+        RichText::new(REFRESH_TOOLTIP)
+            .color(Color32::WHITE)
+            .text_style(TextRole::Body.into()),
+    );
+
+    let label_text = RichText::new("Info refresh interval")
+        .color(Color32::WHITE)
+        .text_style(TextRole::Body.into());
+
+    ui.label(label_text).on_help_text(Arc::clone(&tooltip));
+
+    if let Ok(cfg) = cfg::CONFIG.try_lock() {
+        let initial_interval = cfg.info_refresh_interval_ms.get();
+        let mut interval = initial_interval;
+        let dv = DragValue::new(&mut interval)
+            .speed(10.0)
+            .range(0..=5000)
+            .suffix(" ms");
+
+        ui.add(dv).on_help_text(tooltip);
+
+        if interval != initial_interval {
+            let _ = cfg.info_refresh_interval_ms.set(interval);
+        }
+
+        let mut surface_altitudes = cfg.surface_altitudes.get();
+        let checkbox = ui
+            .checkbox(
+                &mut surface_altitudes,
+                RichText::new("Altitudes above surface").text_style(TextRole::Body.into()),
+            )
+            .on_help_text(
+                RichText::new(
+                    "Show altitudes from the parent's surface instead of its center, \
+                    like the roughly 400 km of the ISS rather than 6,771 km.\n\
+                    Applies to the derived info, the periapsis field, the apoapsis \
+                    next to the eccentricity, orbit markers and the scale bar's readout.",
+                )
+                .color(Color32::WHITE)
+                .text_style(TextRole::Body.into()),
+            );
+
+        if checkbox.changed() {
+            let _ = cfg.surface_altitudes.set(surface_altitudes);
+        }
+
+        let mut polyline_orbits = cfg.polyline_orbits.get();
+        let checkbox = ui
+            .checkbox(
+                &mut polyline_orbits,
+                RichText::new("CPU orbit lines").text_style(TextRole::Body.into()),
+            )
+            .on_help_text(
+                RichText::new(
+                    "Compute orbit lines on the CPU instead of in a shader.\n\
+                    Slower, but works on devices that can't draw orbits otherwise.\n\
+                    This is enabled automatically if the orbit shader fails to compile.",
+                )
+                .color(Color32::WHITE)
+                .text_style(TextRole::Body.into()),
+            );
+
+        if checkbox.changed() {
+            let _ = cfg.polyline_orbits.set(polyline_orbits);
+        }
+
+        let mut precise_orbits = cfg.precise_orbits.get();
+        let checkbox = ui
+            .add_enabled(
+                !polyline_orbits,
+                Checkbox::new(
+                    &mut precise_orbits,
+                    RichText::new("High-precision orbits").text_style(TextRole::Body.into()),
+                ),
+            )
+            .on_help_text(
+                RichText::new(
+                    "Compute nearly-parabolic and very large orbit lines on the CPU \
+                    in double precision, so they don't wobble or break up.\n\
+                    Other orbit lines are still drawn by the shader.",
+                )
+                .color(Color32::WHITE)
+                .text_style(TextRole::Body.into()),
+            )
+            .on_disabled_hover_text(
+                RichText::new(
+                    "All orbit lines are already computed on the CPU in double precision.",
+                )
+                .color(Color32::WHITE)
+                .text_style(TextRole::Body.into()),
+            );
+
+        if checkbox.changed() {
+            let _ = cfg.precise_orbits.set(precise_orbits);
+        }
+
+        let label_text = RichText::new("UI scale")
+            .color(Color32::WHITE)
+            .text_style(TextRole::Body.into());
+        let tooltip = Arc::new(
+            RichText::new(
+                "How big menus, windows and text are, \
+                on top of your screen's own scaling.\n\
+                Ctrl+= and Ctrl+- change it too, and Ctrl+0 resets it.\n\
+                Default: 100%",
+            )
+            .color(Color32::WHITE)
+            .text_style(TextRole::Body.into()),
+        );
+
+        ui.label(label_text).on_help_text(Arc::clone(&tooltip));
+
+        let draft = &mut sim_state.ui.bottom_bar_state.ui_scale_draft;
+        let mut percent = draft.unwrap_or(cfg.ui_scale.get()) * 100.0;
+        let slider = Slider::new(
+            &mut percent,
+            ui_scale::MIN_UI_SCALE * 100.0..=ui_scale::MAX_UI_SCALE * 100.0,
+        )
+        .step_by(5.0)
+        .suffix("%");
+
+        let slider = ui.add(slider).on_help_text(tooltip);
+
+        if slider.changed() {
+            *draft = Some(percent / 100.0);
+        }
+        if !slider.dragged()
+            && let Some(scale) = draft.take()
+        {
+            let _ = cfg.ui_scale.set(scale);
+        }
+
+        let label_text = RichText::new("Font size")
+            .color(Color32::WHITE)
+            .text_style(TextRole::Body.into());
+        let tooltip = Arc::new(
+            RichText::new(
+                "How big text is in every window, before the UI scale.\n\
+                Default: 16 pt",
+            )
+            .color(Color32::WHITE)
+            .text_style(TextRole::Body.into()),
+        );
+
+        ui.label(label_text).on_help_text(Arc::clone(&tooltip));
+
+        let draft = &mut sim_state.ui.bottom_bar_state.font_size_draft;
+        let mut size = draft.unwrap_or(cfg.font_size.get());
+        let slider = Slider::new(
+            &mut size,
+            text_style::MIN_FONT_SIZE..=text_style::MAX_FONT_SIZE,
+        )
+        .step_by(1.0)
+        .suffix(" pt");
+
+        let slider = ui.add(slider).on_help_text(tooltip);
+
+        if slider.changed() {
+            *draft = Some(size);
+        }
+        if !slider.dragged()
+            && let Some(size) = draft.take()
+        {
+            let _ = cfg.font_size.set(size);
+        }
+
+        let label_text = RichText::new("Render scale")
+            .color(Color32::WHITE)
+            .text_style(TextRole::Body.into());
+        let tooltip = Arc::new(
+            RichText::new(
+                "The resolution the 3D view is drawn at, relative to your screen.\n\
+                Lower it for smoother frame rates on 4K screens and phones, \
+                or raise it for smoother edges if your GPU has room to spare.\n\
+                Menus and windows always stay at full resolution.\n\
+                Default: 100%",
+            )
+            .color(Color32::WHITE)
+            .text_style(TextRole::Body.into()),
+        );
+
+        ui.label(label_text).on_help_text(Arc::clone(&tooltip));
+
+        let initial_percent = cfg.render_scale.get() * 100.0;
+        let mut percent = initial_percent;
+        let slider = Slider::new(
+            &mut percent,
+            MIN_RENDER_SCALE * 100.0..=MAX_RENDER_SCALE * 100.0,
+        )
+        .step_by(5.0)
+        .suffix("%");
+
+        ui.add(slider).on_help_text(tooltip);
+
+        if percent != initial_percent {
+            let _ = cfg.render_scale.set(percent / 100.0);
+        }
+
+        let label_text = RichText::new("Line width")
+            .color(Color32::WHITE)
+            .text_style(TextRole::Body.into());
+        let tooltip = Arc::new(
+            RichText::new(
+                "Orbit line width multiplier.\n\
+                Lines are already scaled to your screen's pixel density.\n\
+                Default: 1",
+            )
+            .color(Color32::WHITE)
+            .text_style(TextRole::Body.into()),
+        );
+
+        ui.label(label_text).on_help_text(Arc::clone(&tooltip));
+
+        let initial_multiplier = cfg.line_width_multiplier.get();
+        let mut multiplier = initial_multiplier;
+        let dv = DragValue::new(&mut multiplier)
+            .speed(0.01)
+            .range(0.25..=4.0)
+            .suffix("×");
+
+        ui.add(dv).on_help_text(tooltip);
+
+        if multiplier != initial_multiplier {
+            let _ = cfg.line_width_multiplier.set(multiplier);
+        }
+
+        let label_text = RichText::new("Zoom sensitivity")
+            .color(Color32::WHITE)
+            .text_style(TextRole::Body.into());
+        let tooltip = Arc::new(
+            RichText::new(
+                "How far the mouse wheel and trackpad pinches zoom.\n\
+                Default: 1",
+            )
+            .color(Color32::WHITE)
+            .text_style(TextRole::Body.into()),
+        );
 
-    let force_open = cb.inner.unwrap_or(false);
+        ui.label(label_text).on_help_text(Arc::clone(&tooltip));
+
+        let initial_sensitivity = cfg.zoom_sensitivity.get();
+        let mut sensitivity = initial_sensitivity;
+        let slider = Slider::new(&mut sensitivity, 0.1..=10.0)
+            .logarithmic(true)
+            .suffix("×");
+
+        ui.add(slider).on_help_text(tooltip);
+
+        if sensitivity != initial_sensitivity {
+            let _ = cfg.zoom_sensitivity.set(sensitivity);
+        }
+
+        let mut dim_eclipsed = cfg.dim_eclipsed_bodies.get();
+        let checkbox = ui
+            .checkbox(
+                &mut dim_eclipsed,
+                RichText::new("Dim eclipsed bodies").text_style(TextRole::Body.into()),
+            )
+            .on_help_text(
+                RichText::new(
+                    "Draw bodies that are in the shadow of another body darker.\n\
+                    A body's light comes from the top-most body it orbits, such as the Sun.\n\
+                    Only the focused body's system is checked.",
+                )
+                .color(Color32::WHITE)
+                .text_style(TextRole::Body.into()),
+            );
+
+        if checkbox.changed() {
+            let _ = cfg.dim_eclipsed_bodies.set(dim_eclipsed);
+        }
+
+        let mut hide_subsystems = cfg.hide_distant_subsystems.get();
+        let checkbox = ui
+            .checkbox(
+                &mut hide_subsystems,
+                RichText::new("Hide distant subsystems").text_style(TextRole::Body.into()),
+            )
+            .on_help_text(
+                RichText::new(
+                    "Skip drawing the satellites of bodies that look smaller than \
+                    the angle below, along with their orbit lines.\n\
+                    Speeds up universes with many moon systems. \
+                    Bodies can opt out with \"Always show\" in the Edit Body window.",
+                )
+                .color(Color32::WHITE)
+                .text_style(TextRole::Body.into()),
+            );
+
+        if checkbox.changed() {
+            let _ = cfg.hide_distant_subsystems.set(hide_subsystems);
+        }
+
+        if hide_subsystems {
+            let label_text = RichText::new("Subsystem cutoff")
+                .color(Color32::WHITE)
+                .text_style(TextRole::Body.into());
+            let tooltip = Arc::new(
+                RichText::new(
+                    "How big a body has to look for its satellites to be drawn, \
+                    as the angle it spans on screen.\n\
+                    Default: 0.01°",
+                )
+                .color(Color32::WHITE)
+                .text_style(TextRole::Body.into()),
+            );
+
+            ui.label(label_text).on_help_text(Arc::clone(&tooltip));
+
+            let initial_angle = cfg.subsystem_cull_angle.get();
+            let mut angle = initial_angle;
+            let dv = DragValue::new(&mut angle)
+                .speed(0.0005)
+                .range(0.0001..=5.0)
+                .max_decimals(4)
+                .suffix("°");
+
+            ui.add(dv).on_help_text(tooltip);
+
+            if angle != initial_angle {
+                let _ = cfg.subsystem_cull_angle.set(angle);
+            }
+        }
+
+        let label_text = RichText::new("Palette")
+            .color(Color32::WHITE)
+            .text_style(TextRole::Body.into());
+        let tooltip = Arc::new(
+            RichText::new(
+                "The colors bodies and their orbits are drawn in.\n\
+                The color-blind friendly palettes color bodies by their place \
+                in the hierarchy instead of by their own color.",
+            )
+            .color(Color32::WHITE)
+            .text_style(TextRole::Body.into()),
+        );
+
+        ui.label(label_text).on_help_text(Arc::clone(&tooltip));
+
+        let initial_palette = Palette::from_index(cfg.palette.get());
+        let mut palette = initial_palette;
+        let palette_text = RichText::new(palette.name())
+            .color(Color32::WHITE)
+            .text_style(TextRole::Body.into());
+
+        let cb = ComboBox::from_id_salt(PALETTE_COMBO_BOX_SALT)
+            .selected_text(palette_text)
+            .show_ui(ui, |ui| palette_menu(ui, &mut palette));
+
+        cb.response.on_help_text(tooltip);
+
+        force_open |= cb.inner.unwrap_or(false);
+
+        if palette != initial_palette {
+            let _ = cfg.palette.set(palette.to_index());
+        }
+
+        let label_text = RichText::new("Startup universe")
+            .color(Color32::WHITE)
+            .text_style(TextRole::Body.into());
+        let tooltip = Arc::new(
+            RichText::new(
+                "What the simulator opens with when it starts, and in new tabs.\n\
+                Default: Solar System",
+            )
+            .color(Color32::WHITE)
+            .text_style(TextRole::Body.into()),
+        );
+
+        ui.label(label_text).on_help_text(Arc::clone(&tooltip));
+
+        let initial_startup = StartupUniverse::from_index(cfg.startup_universe.get());
+        let mut startup = initial_startup;
+        let startup_text = RichText::new(startup.name())
+            .color(Color32::WHITE)
+            .text_style(TextRole::Body.into());
+
+        let cb = ComboBox::from_id_salt(STARTUP_UNIVERSE_COMBO_BOX_SALT)
+            .selected_text(startup_text)
+            .show_ui(ui, |ui| startup_universe_menu(ui, &mut startup));
+
+        cb.response.on_help_text(tooltip);
+
+        force_open |= cb.inner.unwrap_or(false);
+
+        if startup != initial_startup {
+            let _ = cfg.startup_universe.set(startup.to_index());
+        }
+
+        let mut orbit_patterns = cfg.orbit_patterns.get();
+        let checkbox = ui
+            .checkbox(
+                &mut orbit_patterns,
+                RichText::new("Dashed orbit lines").text_style(TextRole::Body.into()),
+            )
+            .on_help_text(
+                RichText::new(
+                    "Draw orbit lines with different dash patterns, \
+                    so neighboring orbits can be told apart without relying on color.",
+                )
+                .color(Color32::WHITE)
+                .text_style(TextRole::Body.into()),
+            );
+
+        if checkbox.changed() {
+            let _ = cfg.orbit_patterns.set(orbit_patterns);
+        }
+
+        let mut show_scale_bar = cfg.show_scale_bar.get();
+        let checkbox = ui
+            .checkbox(
+                &mut show_scale_bar,
+                RichText::new("Scale bar").text_style(TextRole::Body.into()),
+            )
+            .on_help_text(
+                RichText::new(
+                    "Show how long a distance on the screen is at the focused body, \
+                    like the scale bar on a map.",
+                )
+                .color(Color32::WHITE)
+                .text_style(TextRole::Body.into()),
+            );
+
+        if checkbox.changed() {
+            let _ = cfg.show_scale_bar.set(show_scale_bar);
+        }
+
+        let mut show_grid = cfg.show_grid.get();
+        let checkbox = ui
+            .checkbox(
+                &mut show_grid,
+                RichText::new("Reference grid").text_style(TextRole::Body.into()),
+            )
+            .on_help_text(
+                RichText::new(
+                    "Draw a grid on the ecliptic plane under the focused body.\n\
+                    Its lines are a power of ten meters apart, \
+                    with finer lines fading in as you zoom in.",
+                )
+                .color(Color32::WHITE)
+                .text_style(TextRole::Body.into()),
+            );
+
+        if checkbox.changed() {
+            let _ = cfg.show_grid.set(show_grid);
+        }
+
+        let mut presentation_mode = cfg.presentation_mode.get();
+        let checkbox = ui
+            .checkbox(
+                &mut presentation_mode,
+                RichText::new("Presentation mode").text_style(TextRole::Body.into()),
+            )
+            .on_help_text(
+                RichText::new(
+                    "For screencasting or projecting: windows become see-through, \
+                    and the bottom bar and FPS counter hide while nothing is touched.\n\
+                    P toggles it too.",
+                )
+                .color(Color32::WHITE)
+                .text_style(TextRole::Body.into()),
+            );
+
+        if checkbox.changed() {
+            let _ = cfg.presentation_mode.set(presentation_mode);
+        }
+
+        let mut click_through = cfg.presentation_click_through.get();
+        let checkbox = ui
+            .add_enabled(
+                presentation_mode,
+                Checkbox::new(
+                    &mut click_through,
+                    RichText::new("Click-through windows").text_style(TextRole::Body.into()),
+                ),
+            )
+            .on_help_text(
+                RichText::new(
+                    "In presentation mode, let clicks, drags and scrolling go \
+                    through windows to the 3D view.\n\
+                    Hold Alt to use the windows, or press P to leave presentation mode.",
+                )
+                .color(Color32::WHITE)
+                .text_style(TextRole::Body.into()),
+            );
+
+        if checkbox.changed() {
+            let _ = cfg.presentation_click_through.set(click_through);
+        }
+
+        let label_text = RichText::new("Window opacity")
+            .color(Color32::WHITE)
+            .text_style(TextRole::Body.into());
+        let tooltip = Arc::new(
+            RichText::new(
+                "How opaque window backgrounds are in presentation mode.\n\
+                Default: 50%",
+            )
+            .color(Color32::WHITE)
+            .text_style(TextRole::Body.into()),
+        );
+
+        ui.label(label_text).on_help_text(Arc::clone(&tooltip));
+
+        let initial_percent = cfg.presentation_window_opacity.get() * 100.0;
+        let mut percent = initial_percent;
+        let slider = Slider::new(&mut percent, 10.0..=100.0)
+            .step_by(5.0)
+            .suffix("%");
+
+        ui.add_enabled(presentation_mode, slider)
+            .on_help_text(tooltip);
+
+        if percent != initial_percent {
+            let _ = cfg.presentation_window_opacity.set(percent / 100.0);
+        }
+
+        let label_text = RichText::new("Hide bars after")
+            .color(Color32::WHITE)
+            .text_style(TextRole::Body.into());
+        let tooltip = Arc::new(
+            RichText::new(
+                "How long nothing has to be touched in presentation mode \
+                before the bottom bar and FPS counter hide.\n\
+                Default: 3 s",
+            )
+            .color(Color32::WHITE)
+            .text_style(TextRole::Body.into()),
+        );
+
+        ui.label(label_text).on_help_text(Arc::clone(&tooltip));
+
+        let initial_delay = cfg.presentation_hide_delay_s.get();
+        let mut delay = initial_delay;
+        let dv = DragValue::new(&mut delay)
+            .speed(0.1)
+            .range(0.5..=60.0)
+            .suffix(" s");
+
+        ui.add_enabled(presentation_mode, dv).on_help_text(tooltip);
+
+        if delay != initial_delay {
+            let _ = cfg.presentation_hide_delay_s.set(delay);
+        }
+
+        let mut auto_depth_range = cfg.auto_depth_range.get();
+        let checkbox = ui
+            .checkbox(
+                &mut auto_depth_range,
+                RichText::new("Automatic depth range").text_style(TextRole::Body.into()),
+            )
+            .on_help_text(
+                RichText::new(
+                    "Fit the camera's near and far clipping planes around the focused body \
+                    and the rest of the system every frame.\n\
+                    This stops surfaces and orbit lines from flickering through each other, \
+                    especially when zoomed in close to a small moon.",
+                )
+                .color(Color32::WHITE)
+                .text_style(TextRole::Body.into()),
+            );
+
+        if checkbox.changed() {
+            let _ = cfg.auto_depth_range.set(auto_depth_range);
+        }
+
+        let mut distant_body_dots = cfg.distant_body_dots.get();
+        let checkbox = ui
+            .checkbox(
+                &mut distant_body_dots,
+                RichText::new("Dots for distant bodies").text_style(TextRole::Body.into()),
+            )
+            .on_help_text(
+                RichText::new(
+                    "Draw bodies that are too small to see as a small dot in their color, \
+                    instead of leaving only their orbit line.",
+                )
+                .color(Color32::WHITE)
+                .text_style(TextRole::Body.into()),
+            );
+
+        if checkbox.changed() {
+            let _ = cfg.distant_body_dots.set(distant_body_dots);
+        }
+
+        let mut focus_handoff = cfg.focus_handoff.get();
+        let checkbox = ui
+            .checkbox(
+                &mut focus_handoff,
+                RichText::new("Focus handoff").text_style(TextRole::Body.into()),
+            )
+            .on_help_text(
+                RichText::new(
+                    "When the focused body is deleted, move the camera over to the body \
+                    whose sphere of influence it was in, and say where it went.\n\
+                    When off, the camera goes to the deleted body's parent without a word.",
+                )
+                .color(Color32::WHITE)
+                .text_style(TextRole::Body.into()),
+            );
+
+        if checkbox.changed() {
+            let _ = cfg.focus_handoff.set(focus_handoff);
+        }
+
+        #[cfg(target_family = "wasm")]
+        {
+            let mut adaptive_quality = cfg.adaptive_quality.get();
+            let checkbox = ui
+                .checkbox(
+                    &mut adaptive_quality,
+                    RichText::new("Adaptive quality").text_style(TextRole::Body.into()),
+                )
+                .on_help_text(
+                    RichText::new(
+                        "Lower the render scale, sphere detail and orbit line detail \
+                        when frames have been slow for a few seconds, \
+                        and raise them again once there's headroom.",
+                    )
+                    .color(Color32::WHITE)
+                    .text_style(TextRole::Body.into()),
+                );
+
+            if checkbox.changed() {
+                let _ = cfg.adaptive_quality.set(adaptive_quality);
+            }
+        }
+
+        #[cfg(not(target_family = "wasm"))]
+        {
+            let mut background_simulation = cfg.background_simulation.get();
+            let checkbox = ui
+                .checkbox(
+                    &mut background_simulation,
+                    RichText::new("Background simulation").text_style(TextRole::Body.into()),
+                )
+                .on_help_text(
+                    RichText::new(
+                        "Tick the universe and work out where the bodies are \
+                        on a separate thread, so huge universes don't slow down the interface.\n\
+                        What's shown trails a frame behind. While a window edits \
+                        the universe every frame, like the Edit Body window, \
+                        ticking happens on the main thread as usual.",
+                    )
+                    .color(Color32::WHITE)
+                    .text_style(TextRole::Body.into()),
+                );
+
+            if checkbox.changed() {
+                let _ = cfg.background_simulation.set(background_simulation);
+            }
+        }
+
+        let mut scientific_number_entry = cfg.scientific_number_entry.get();
+        let checkbox = ui
+            .checkbox(
+                &mut scientific_number_entry,
+                RichText::new("Scientific number entry").text_style(TextRole::Body.into()),
+            )
+            .on_help_text(
+                RichText::new(
+                    "Type masses, radii, periapses and the gravity multiplier \
+                    in scientific notation, like 5.972e24, instead of dragging them.\n\
+                    A unit can be typed after the number, like \"1.5e8 km\", \
+                    and the ×10 and ÷10 buttons step the exponent.",
+                )
+                .color(Color32::WHITE)
+                .text_style(TextRole::Body.into()),
+            );
+
+        if checkbox.changed() {
+            let _ = cfg.scientific_number_entry.set(scientific_number_entry);
+        }
+
+        let label_text = RichText::new("Mass ratio warning")
+            .color(Color32::WHITE)
+            .text_style(TextRole::Body.into());
+        let tooltip = Arc::new(
+            RichText::new(
+                "Warn in the Edit Body window when a body's mass is more than \
+                this fraction of its parent's mass.\n\
+                Past this point, the parent noticeably orbits the shared barycenter, \
+                which this simulation doesn't model.\n\
+                Default: 0.05",
+            )
+            .color(Color32::WHITE)
+            .text_style(TextRole::Body.into()),
+        );
+
+        ui.label(label_text).on_help_text(Arc::clone(&tooltip));
+
+        let initial_ratio = cfg.max_mass_ratio.get();
+        let mut ratio = initial_ratio;
+        let dv = DragValue::new(&mut ratio)
+            .speed(1e-3)
+            .range(validation::MIN_MAX_MASS_RATIO..=validation::MAX_MAX_MASS_RATIO)
+            .max_decimals(4);
+
+        ui.add(dv).on_help_text(tooltip);
+
+        if ratio != initial_ratio {
+            let _ = cfg.max_mass_ratio.set(ratio);
+        }
+
+        for kind in ElementKind::iter() {
+            let label_text = RichText::new(kind.name())
+                .color(Color32::WHITE)
+                .text_style(TextRole::Body.into());
+            let tooltip = Arc::new(
+                RichText::new(format!(
+                    "How far the up and down arrow keys step this orbit element \
+                    while its field is focused.\n\
+                    Alt steps a tenth of this, and Shift ten times it.\n\
+                    Default: {}{}",
+                    kind.default_step(),
+                    kind.suffix(),
+                ))
+                .color(Color32::WHITE)
+                .text_style(TextRole::Body.into()),
+            );
+
+            ui.label(label_text).on_help_text(Arc::clone(&tooltip));
+
+            let cell = kind.step_cell(&cfg);
+            let initial_step = cell.get();
+            let mut step = initial_step;
+            let dv = DragValue::new(&mut step)
+                .speed(initial_step * 0.01)
+                .range(1e-6..=90.0)
+                .suffix(kind.suffix());
+
+            ui.add(dv).on_help_text(tooltip);
+
+            if step != initial_step {
+                let _ = cell.set(step);
+            }
+        }
+
+        let label_text = RichText::new("Min time speed")
+            .color(Color32::WHITE)
+            .text_style(TextRole::Body.into());
+        let tooltip = Arc::new(
+            RichText::new(format!(
+                "The slowest time can run, in seconds per second, \
+                whichever way it's running.\n\
+                Default: {DEFAULT_MIN_SIM_SPEED:e}",
+            ))
+            .color(Color32::WHITE)
+            .text_style(TextRole::Body.into()),
+        );
+
+        ui.label(label_text).on_help_text(Arc::clone(&tooltip));
+
+        let mut min_speed = cfg.min_sim_speed.get();
+        let max_speed = cfg.max_sim_speed.get();
+        let input = sci_input::scientific_value(MIN_SIM_SPEED_INPUT_SALT, ui, &mut min_speed, 0.0);
+        input.response.on_help_text(tooltip);
+
+        if input.inner && min_speed > 0.0 && min_speed <= max_speed {
+            let _ = cfg.min_sim_speed.set(min_speed);
+        }
+
+        let label_text = RichText::new("Max time speed")
+            .color(Color32::WHITE)
+            .text_style(TextRole::Body.into());
+        let tooltip = Arc::new(
+            RichText::new(format!(
+                "The fastest time can run, in seconds per second, \
+                whichever way it's running.\n\
+                Very high speeds make orbits jump around \
+                and the universe's time lose precision.\n\
+                Default: {DEFAULT_MAX_SIM_SPEED:e}",
+            ))
+            .color(Color32::WHITE)
+            .text_style(TextRole::Body.into()),
+        );
+
+        ui.label(label_text).on_help_text(Arc::clone(&tooltip));
+
+        let min_speed = cfg.min_sim_speed.get();
+        let mut max_speed = cfg.max_sim_speed.get();
+        let input =
+            sci_input::scientific_value(MAX_SIM_SPEED_INPUT_SALT, ui, &mut max_speed, min_speed);
+        input.response.on_help_text(tooltip);
+
+        if input.inner {
+            let _ = cfg.max_sim_speed.set(max_speed);
+        }
+    }
 
     let reset_button = Button::new(
         RichText::new("Reset data & restart")
             .color(Color32::LIGHT_RED)
-            .size(16.0),
+            .text_style(TextRole::Body.into()),
     );
     if ui.add(reset_button).clicked() {
         let _res = cfg::reset();
         #[cfg(not(target_family = "wasm"))]
         if let Err(e) = _res {
-            eprintln!("Failed to reset data: {e}");
+            notify(Severity::Error, format!("Failed to reset data: {e}"));
         }
     }
 
-    let about_toggle = Button::selectable(
-        sim_state.ui.is_about_window_open,
-        RichText::new("About keplerian_sim").size(16.0),
+    let gizmo_toggle = Button::selectable(
+        sim_state.ui.gizmo_state.enabled,
+        RichText::new("Orbit gizmo").text_style(TextRole::Body.into()),
+    );
+    let gizmo_toggle = ui.add(gizmo_toggle).on_help_text(
+        RichText::new(
+            "Show draggable handles on the previewed or focused orbit.\n\
+            Red: periapsis distance. Orange: argument of periapsis.\n\
+            Blue: RAAN. Green: inclination.\n\
+            Hold Shift to snap.",
+        )
+        .color(Color32::WHITE)
+        .text_style(TextRole::Body.into()),
     );
-    let about_toggle = ui.add(about_toggle);
 
-    if about_toggle.clicked() {
-        sim_state.ui.is_about_window_open ^= true;
+    if gizmo_toggle.clicked() {
+        sim_state.ui.gizmo_state.enabled ^= true;
+    }
+
+    if sim_state.ui.gizmo_state.enabled {
+        ui.checkbox(
+            &mut sim_state.ui.gizmo_state.snap,
+            RichText::new("Always snap gizmo").text_style(TextRole::Body.into()),
+        );
+    }
+
+    let new_tab_button = ui
+        .button(RichText::new("New tab").text_style(TextRole::Body.into()))
+        .on_help_text(
+            RichText::new(
+                "Open the default universe in a new tab. \
+                Bodies can be copied between tabs from the body list.",
+            )
+            .color(Color32::WHITE)
+            .text_style(TextRole::Body.into()),
+        );
+
+    if new_tab_button.clicked() {
+        sim_state.ui.tab_action = Some(TabAction::New);
+    }
+
+    let toolbar_items = toolbar::items();
+    for window in windows::WINDOWS {
+        if toolbar::has_window(&toolbar_items, window) {
+            continue;
+        }
+        let toggle = Button::selectable(
+            window.is_open(sim_state),
+            RichText::new(window.name).text_style(TextRole::Body.into()),
+        );
+        let mut toggle = ui.add(toggle);
+        if let Some(description) = window.description {
+            toggle = toggle.on_help_text(
+                RichText::new(description)
+                    .color(Color32::WHITE)
+                    .text_style(TextRole::Body.into()),
+            );
+        }
+
+        if toggle.clicked() {
+            window.toggle(sim_state);
+        }
+    }
+
+    // Kept out of the way, since it takes over the program for a while
+    if ui.input(|i| i.modifiers.shift) {
+        let benchmark_button = ui
+            .button(RichText::new("Run benchmark").text_style(TextRole::Body.into()))
+            .on_help_text(
+                RichText::new(
+                    "Play a heavy scene with a scripted camera in a new tab \
+                    for about 20 seconds, then show how long the frames took.",
+                )
+                .color(Color32::WHITE)
+                .text_style(TextRole::Body.into()),
+            );
+        if benchmark_button.clicked() {
+            sim_state.ui.benchmark_requested = true;
+        }
+    }
+
+    // The page can't close itself on the web
+    if !cfg!(target_family = "wasm") {
+        ui.separator();
+        let quit_button = ui
+            .button(RichText::new("Quit").text_style(TextRole::Body.into()))
+            .on_help_text(
+                RichText::new(
+                    "Close the program (Ctrl+Q).\n\
+                Asks first if any universe was edited, since universes aren't saved.",
+                )
+                .color(Color32::WHITE)
+                .text_style(TextRole::Body.into()),
+            );
+        if quit_button.clicked() {
+            quit::request(&mut sim_state.ui);
+        }
     }
 
     force_open
@@ -661,12 +1781,12 @@ fn mu_mode_menu(ui: &mut Ui, mu_setter_mode: &mut BulkMuSetterMode) -> bool {
     let mut clicked = false;
 
     for mode in BulkMuSetterMode::iter() {
-        let text = RichText::new(mode.name()).size(16.0);
+        let text = RichText::new(mode.name()).text_style(TextRole::Body.into());
         let button = Button::selectable(*mu_setter_mode == mode, text);
-        let button = ui.add(button).on_hover_text(
+        let button = ui.add(button).on_help_text(
             RichText::new(mode.description())
                 .color(Color32::WHITE)
-                .size(16.0),
+                .text_style(TextRole::Body.into()),
         );
 
         if button.clicked() {
@@ -678,6 +1798,80 @@ fn mu_mode_menu(ui: &mut Ui, mu_setter_mode: &mut BulkMuSetterMode) -> bool {
     clicked
 }
 
+/// Returns whether or not any button was clicked
+fn startup_universe_menu(ui: &mut Ui, startup: &mut StartupUniverse) -> bool {
+    ui.visuals_mut().override_text_color = Some(Color32::WHITE);
+    ui.spacing_mut().interact_size = MIN_TOUCH_TARGET_VEC;
+
+    let mut clicked = false;
+
+    for option in StartupUniverse::iter() {
+        let text = RichText::new(option.name()).text_style(TextRole::Body.into());
+        let button = Button::selectable(*startup == option, text);
+        let button = ui.add(button).on_help_text(
+            RichText::new(option.description())
+                .color(Color32::WHITE)
+                .text_style(TextRole::Body.into()),
+        );
+
+        if button.clicked() {
+            *startup = option;
+            clicked = true;
+        }
+    }
+
+    clicked
+}
+
+/// Returns whether or not any button was clicked
+fn palette_menu(ui: &mut Ui, palette: &mut Palette) -> bool {
+    ui.visuals_mut().override_text_color = Some(Color32::WHITE);
+    ui.spacing_mut().interact_size = MIN_TOUCH_TARGET_VEC;
+
+    let mut clicked = false;
+
+    for option in Palette::iter() {
+        let text = RichText::new(option.name()).text_style(TextRole::Body.into());
+        let button = Button::selectable(*palette == option, text);
+        let button = ui.add(button).on_help_text(
+            RichText::new(option.description())
+                .color(Color32::WHITE)
+                .text_style(TextRole::Body.into()),
+        );
+
+        if button.clicked() {
+            *palette = option;
+            clicked = true;
+        }
+    }
+
+    clicked
+}
+
+fn vector_overlay_menu(ui: &mut Ui, vector_overlay: &mut VectorOverlayMode) -> bool {
+    ui.visuals_mut().override_text_color = Some(Color32::WHITE);
+    ui.spacing_mut().interact_size = MIN_TOUCH_TARGET_VEC;
+
+    let mut clicked = false;
+
+    for mode in VectorOverlayMode::iter() {
+        let text = RichText::new(mode.name()).text_style(TextRole::Body.into());
+        let button = Button::selectable(*vector_overlay == mode, text);
+        let button = ui.add(button).on_help_text(
+            RichText::new(mode.description())
+                .color(Color32::WHITE)
+                .text_style(TextRole::Body.into()),
+        );
+
+        if button.clicked() {
+            *vector_overlay = mode;
+            clicked = true;
+        }
+    }
+
+    clicked
+}
+
 const COLLAPSE_TOGGLE_SIZE: Vec2 = MIN_TOUCH_TARGET_VEC;
 fn collapse_toggle(ui: &mut Ui, sim_state: &mut SimState) {
     ui.spacing_mut().button_padding = Vec2::ZERO;
@@ -719,10 +1913,12 @@ fn collapse_toggle(ui: &mut Ui, sim_state: &mut SimState) {
     } else {
         "Show bottom bar"
     };
-    let hover_text = RichText::new(hover_text).color(Color32::WHITE).size(16.0);
+    let hover_text = RichText::new(hover_text)
+        .color(Color32::WHITE)
+        .text_style(TextRole::Body.into());
 
     button
         .response
-        .on_hover_text(hover_text)
+        .on_help_text(hover_text)
         .on_hover_cursor(CursorIcon::PointingHand);
 }
@@ -0,0 +1,142 @@
+use core::num::NonZeroU64;
+
+use three_d::egui::{
+    Color32, ComboBox, Context, CursorIcon, Grid, Id as EguiId, PopupCloseBehavior, RichText,
+    ScrollArea, TextWrapMode, Ui, Window,
+};
+
+use crate::{
+    gui::{
+        SimState,
+        celestials::{
+            info::{InfoCache, compared_info},
+            selectable_body_tree,
+        },
+        declare_id,
+    },
+    sim::universe::{Id as UniverseId, Universe},
+};
+
+declare_id!(salt_only, COMPARE_GRID, b"Compare!");
+declare_id!(salt_only, COMPARE_LEFT_COMBO_BOX, b"CmpLeft<");
+declare_id!(salt_only, COMPARE_RIGHT_COMBO_BOX, b">CmpRght");
+declare_id!(COMPARE_LEFT_TREE, b"CmpLTree");
+declare_id!(COMPARE_RIGHT_TREE, b"CmpRTree");
+
+#[derive(Default)]
+pub(crate) struct CompareWindowState {
+    pub(in super::super) open: bool,
+    bodies: [Option<UniverseId>; 2],
+    info_caches: [InfoCache; 2],
+}
+
+pub(super) fn compare_window(ctx: &Context, sim_state: &mut SimState) {
+    let mut open = sim_state.ui.compare_window_state.open;
+
+    Window::new("Compare Bodies")
+        .open(&mut open)
+        .default_width(480.0)
+        .show(ctx, |ui| window_contents(ui, sim_state));
+
+    sim_state.ui.compare_window_state.open &= open;
+}
+
+fn window_contents(ui: &mut Ui, sim_state: &mut SimState) {
+    ui.visuals_mut().override_text_color = Some(Color32::WHITE);
+
+    let universe = &sim_state.universe;
+    let state = &mut sim_state.ui.compare_window_state;
+
+    // Forget deleted bodies
+    for body in &mut state.bodies {
+        if body.is_some_and(|id| universe.get_body(id).is_none()) {
+            *body = None;
+        }
+    }
+
+    // Start off comparing against the focused body
+    if state.bodies == [None, None] {
+        state.bodies[0] = Some(sim_state.focused_body);
+    }
+
+    let [left, right] = &mut state.bodies;
+    ui.horizontal(|ui| {
+        body_picker(
+            ui,
+            universe,
+            COMPARE_LEFT_COMBO_BOX_SALT,
+            *COMPARE_LEFT_TREE_ID,
+            left,
+        );
+        ui.label("vs");
+        body_picker(
+            ui,
+            universe,
+            COMPARE_RIGHT_COMBO_BOX_SALT,
+            *COMPARE_RIGHT_TREE_ID,
+            right,
+        );
+    });
+
+    let [Some(left_id), Some(right_id)] = state.bodies else {
+        ui.label("Pick two bodies to compare.");
+        return;
+    };
+    let (Some(left), Some(right)) = (universe.get_body(left_id), universe.get_body(right_id))
+    else {
+        return;
+    };
+
+    for ((id, wrapper), cache) in [(left_id, left), (right_id, right)]
+        .into_iter()
+        .zip(&mut state.info_caches)
+    {
+        cache.refresh(
+            ui,
+            &wrapper.body,
+            wrapper.relations.parent,
+            universe,
+            universe.get_local_gravitational_constant(id),
+            wrapper.frozen_at.unwrap_or(universe.time),
+        );
+    }
+
+    ui.add_space(8.0);
+    ScrollArea::vertical().show(ui, |ui| {
+        Grid::new(COMPARE_GRID_SALT)
+            .num_columns(3)
+            .spacing([40.0, 4.0])
+            .striped(true)
+            .show(ui, |ui| {
+                ui.label("");
+                ui.label(RichText::new(&left.body.name).strong());
+                ui.label(RichText::new(&right.body.name).strong());
+                ui.end_row();
+
+                let [left_cache, right_cache] = &state.info_caches;
+                compared_info(ui, [left_cache, right_cache]);
+            });
+    });
+}
+
+fn body_picker(
+    ui: &mut Ui,
+    universe: &Universe,
+    salt: NonZeroU64,
+    tree_id: EguiId,
+    selected: &mut Option<UniverseId>,
+) {
+    let name = selected
+        .and_then(|id| universe.get_body(id))
+        .map_or("Pick a body", |w| w.body.name.as_str());
+
+    ComboBox::from_id_salt(salt)
+        .close_behavior(PopupCloseBehavior::CloseOnClickOutside)
+        .wrap_mode(TextWrapMode::Extend)
+        .selected_text(name)
+        .show_ui(ui, |ui| {
+            selectable_body_tree(ui, tree_id, universe, selected, None);
+        })
+        .response
+        .on_hover_cursor(CursorIcon::PointingHand);
+}
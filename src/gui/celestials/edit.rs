@@ -1,17 +1,52 @@
+use core::f64::consts::TAU;
+use std::{collections::HashSet, sync::Mutex};
+
 use crate::{
+    cfg::CONFIG,
+    compute::{self, Task, TaskStatus},
     gui::{
-        SimState,
-        celestials::{DisallowedData, info::body_window_info, selectable_body_tree},
+        SimState, altitude,
+        body_theme::{self, BodyTheme},
+        celestials::{
+            DisallowedData, PreviewBody, ReparentFade,
+            info::{InfoCache, body_window_info},
+            periapsis_rows, selectable_body_tree,
+        },
         declare_id,
+        help::HelpText,
+        layout::{self, DockableWindow},
+        nudge::{ElementKind, nudgeable},
+        phase_dial::phase_dial,
+        sci_input::precise_value_with_unit,
+        sparkline::sparkline,
+        text_style::TextRole,
         unit_dv::drag_value_with_unit,
     },
-    sim::universe::{BodyWrapper, BulkMuSetterMode, Id as UniverseId, Universe},
-    units::{AutoUnit, length::LengthUnit, mass::MassUnit},
+    metrics::{self, Feature},
+    notify::{Severity, notify},
+    sim::{
+        altitude_profile::AltitudeProfile,
+        body::Rings,
+        conversion::{ElementsParseError, OrbitalElements},
+        fit::{self, FitError, FitJob, OrbitFit},
+        perturbation::{self, JitterElement},
+        universe::{BodyWrapper, BulkMuSetterMode, HyperbolaRange, Id as UniverseId, Universe},
+        validation::{self, DEFAULT_MAX_MASS_RATIO, Warning},
+        zoom_levels::{self, ZoomLevel},
+    },
+    units::{
+        AutoUnit,
+        length::LengthUnit,
+        mass::MassUnit,
+        time::{TimeDisplayMode, TimeUnit},
+    },
 };
-use keplerian_sim::OrbitTrait;
+use float_pretty_print::PrettyPrintFloat;
+use keplerian_sim::{Orbit, OrbitTrait};
+use strum::IntoEnumIterator;
 use three_d::egui::{
-    Color32, ComboBox, Context, CursorIcon, DragValue, Grid, Label, PopupCloseBehavior, RichText,
-    Slider, TextEdit, TextWrapMode, Ui, Window,
+    Button, Color32, ComboBox, Context, CursorIcon, DragValue, Grid, Label, PopupCloseBehavior,
+    RichText, Slider, TextEdit, TextStyle, TextWrapMode, Ui, Window,
     color_picker::{Alpha, color_edit_button_srgba},
 };
 
@@ -23,11 +58,55 @@ declare_id!(salt_only, EDIT_BODY_RADIUS, b"m|Radius");
 declare_id!(salt_only, EDIT_BODY_PARENT_COMBO_BOX, b"mNoder3l");
 declare_id!(EDIT_BODY_PARENT_TREE, b"m|->N0d3");
 declare_id!(salt_only, EDIT_BODY_PERIAPSIS, b"m|PeDist");
+declare_id!(salt_only, EDIT_BODY_PERIOD_TOOL_GRID, b"m|PerGrd");
+declare_id!(salt_only, EDIT_BODY_DESIRED_PERIOD, b"m|Period");
+declare_id!(salt_only, EDIT_BODY_MANEUVER_GRID, b"m|Burns!");
+declare_id!(salt_only, EDIT_BODY_RINGS_GRID, b"m|Rings!");
+declare_id!(salt_only, EDIT_BODY_RING_INNER, b"m|RingIn");
+declare_id!(salt_only, EDIT_BODY_RING_OUTER, b"m|RingOu");
+declare_id!(salt_only, EDIT_BODY_HYPERBOLA_COMBO_BOX, b"m|HypRng");
+
+/// The true anomaly a hyperbola is drawn out to when first switching to
+/// [`HyperbolaRange::TrueAnomaly`], in degrees.
+const DEFAULT_HYPERBOLA_TRUE_ANOMALY_DEG: f64 = 120.0;
+
+/// How many points the altitude sparkline is drawn through.
+const ALTITUDE_SPARKLINE_SAMPLES: usize = 96;
+
+/// Orbital elements copied from the Edit Body window.
+///
+/// Like the body clipboard, this is shared between all tabs.
+static ELEMENTS_CLIPBOARD: Mutex<Option<OrbitalElements>> = Mutex::new(None);
 
 pub(crate) struct EditBodyWindowState {
     mass_unit: AutoUnit<MassUnit>,
     radius_unit: AutoUnit<LengthUnit>,
     periapsis_unit: AutoUnit<LengthUnit>,
+    ring_inner_unit: AutoUnit<LengthUnit>,
+    ring_outer_unit: AutoUnit<LengthUnit>,
+    /// The period chosen in the parent mass tool, and the body it was chosen for.
+    desired_period: Option<(UniverseId, f64)>,
+    period_unit: AutoUnit<TimeUnit>,
+    /// Whether (un)freezing a body also (un)freezes its satellites.
+    freeze_satellites: bool,
+    /// Whether eccentricity edits are kept from pushing the apoapsis
+    /// out of the parent's sphere of influence.
+    cap_eccentricity: bool,
+    /// The text pasted into the elements-as-text tool.
+    elements_text: String,
+    /// Why the pasted elements couldn't be read, and the body they were for.
+    elements_text_error: Option<(UniverseId, ElementsParseError)>,
+    /// The CSV pasted into the orbit fitting tool.
+    fit_samples: String,
+    /// The RMS error of the last fit, or why it failed, and the body it was for.
+    fit_result: Option<(UniverseId, Result<f64, FitError>)>,
+    /// The fit running in the background, and the body it is for.
+    fit_task: Option<(UniverseId, Task<Result<OrbitFit, FitError>>)>,
+    /// The parent picked for a body, shown as a preview until the move is confirmed.
+    pending_parent: Option<(UniverseId, UniverseId)>,
+    /// The fade for a move that was just confirmed, to hand over to the renderer.
+    reparent_fade: Option<ReparentFade>,
+    info_cache: InfoCache,
     pub(crate) window_open: bool,
 }
 
@@ -46,37 +125,138 @@ impl Default for EditBodyWindowState {
                 auto: true,
                 unit: LengthUnit::Meters,
             },
+            ring_inner_unit: AutoUnit {
+                auto: true,
+                unit: LengthUnit::Meters,
+            },
+            ring_outer_unit: AutoUnit {
+                auto: true,
+                unit: LengthUnit::Meters,
+            },
+            desired_period: None,
+            period_unit: AutoUnit {
+                auto: true,
+                unit: TimeUnit::Seconds,
+            },
+            freeze_satellites: true,
+            cap_eccentricity: true,
+            elements_text: String::new(),
+            elements_text_error: None,
+            fit_samples: String::new(),
+            fit_result: None,
+            fit_task: None,
+            pending_parent: None,
+            reparent_fade: None,
+            info_cache: InfoCache::default(),
             window_open: false,
         }
     }
 }
 
 pub(super) fn body_edit_window(ctx: &Context, sim_state: &mut SimState) {
+    poll_fit_task(
+        &mut sim_state.universe,
+        &mut sim_state.ui.edit_body_window_state,
+    );
+
     let mut open = sim_state.ui.edit_body_window_state.window_open;
 
     let body_id = sim_state.focused_body();
+    let theme = BodyTheme::of(&sim_state.universe, body_id);
 
-    Window::new("Edit Body")
+    let window = Window::new(DockableWindow::EditBody.title())
+        .frame(body_theme::window_frame(ctx, theme))
         .scroll([false, true])
         .resizable([false, true])
         .default_width(300.0)
         .min_width(300.0)
         .max_width(300.0)
         .min_height(200.0)
-        .open(&mut open)
-        .show(ctx, |ui| {
-            ui.scope(|ui| {
-                body_edit_window_contents(
-                    ui,
-                    &mut sim_state.universe,
-                    body_id,
-                    &mut sim_state.ui.edit_body_window_state,
-                    sim_state.mu_setter_mode,
-                );
-            });
+        .open(&mut open);
+
+    layout::place(ctx, sim_state, DockableWindow::EditBody, window).show(ctx, |ui| {
+        ui.scope(|ui| {
+            body_edit_window_contents(
+                ui,
+                &mut sim_state.universe,
+                body_id,
+                &mut sim_state.ui.edit_body_window_state,
+                sim_state.mu_setter_mode,
+                &mut sim_state.ui.frame_orbit_requested,
+                &mut sim_state.ui.zoom_level_requested,
+            );
         });
+    });
 
     sim_state.ui.edit_body_window_state.window_open = open;
+
+    let window_state = &mut sim_state.ui.edit_body_window_state;
+    // Forget the picked parent once the window moves on to another body,
+    // or the body gets locked
+    if !open
+        || window_state
+            .pending_parent
+            .is_some_and(|(id, _)| id != body_id)
+        || sim_state
+            .universe
+            .get_body(body_id)
+            .is_some_and(|w| w.locked)
+    {
+        window_state.pending_parent = None;
+    }
+    if let Some(fade) = window_state.reparent_fade.take() {
+        sim_state.reparent_fade = Some(fade);
+    }
+    sim_state.reparent_preview = window_state.pending_parent.and_then(|(id, parent_id)| {
+        let body = sim_state
+            .universe
+            .preview_move(id, parent_id, sim_state.mu_setter_mode)
+            .ok()?;
+        Some(PreviewBody {
+            body,
+            parent_id: Some(parent_id),
+        })
+    });
+}
+
+fn zoom_buttons(
+    ui: &mut Ui,
+    universe: &Universe,
+    body_id: UniverseId,
+    zoom_level_requested: &mut Option<ZoomLevel>,
+) {
+    ui.horizontal_wrapped(|ui| {
+        ui.label("Zoom to")
+            .on_help_text(
+                RichText::new(
+                    "Move the camera to a set distance from this body, \
+                    scaled to its radius and sphere of influence.",
+                )
+                .color(Color32::WHITE)
+                .text_style(TextRole::Body.into()),
+            )
+            .on_hover_cursor(CursorIcon::Help);
+
+        for level in ZoomLevel::iter() {
+            let available = zoom_levels::zoom_target(universe, body_id, level).is_some();
+            let text = format!("{} ({})", level.description(), level.key());
+            let button = ui
+                .add_enabled(available, Button::new(level.name()))
+                .on_help_text(
+                    RichText::new(text)
+                        .color(Color32::WHITE)
+                        .text_style(TextRole::Body.into()),
+                )
+                .on_disabled_hover_text(
+                    RichText::new("Not available for bodies without a parent.")
+                        .color(Color32::WHITE)
+                        .text_style(TextRole::Body.into()),
+                );
+            if button.clicked() {
+                *zoom_level_requested = Some(level);
+            }
+        }
+    });
 }
 
 fn body_edit_window_contents(
@@ -85,45 +265,105 @@ fn body_edit_window_contents(
     body_id: UniverseId,
     window_state: &mut EditBodyWindowState,
     mu_mode: BulkMuSetterMode,
+    frame_orbit_requested: &mut bool,
+    zoom_level_requested: &mut Option<ZoomLevel>,
 ) {
     ui.visuals_mut().override_text_color = Some(Color32::WHITE);
 
+    if let Some(wrapper) = universe.get_body(body_id)
+        && let Some(theme) = BodyTheme::of(universe, body_id)
+    {
+        body_theme::header(ui, theme, &wrapper.body.name);
+        ui.add_space(4.0);
+    }
+
+    let locked = lock_row(ui, universe, body_id);
+    ui.add_space(8.0);
+
     let text = RichText::new("Physical Characteristics")
         .underline()
-        .size(16.0);
+        .text_style(TextRole::Body.into());
     ui.label(text);
     ui.add_space(8.0);
-    Grid::new(EDIT_BODY_PHYS_SALT)
-        .num_columns(2)
-        .spacing([40.0, 4.0])
-        .striped(true)
-        .show(ui, |ui| {
-            edit_body_window_phys(ui, universe, body_id, window_state, mu_mode)
-        });
+    ui.add_enabled_ui(!locked, |ui| {
+        Grid::new(EDIT_BODY_PHYS_SALT)
+            .num_columns(2)
+            .spacing([40.0, 4.0])
+            .striped(true)
+            .show(ui, |ui| {
+                edit_body_window_phys(ui, universe, body_id, window_state, mu_mode)
+            });
+    });
+
+    validation_warnings(ui, universe, body_id);
+
+    ui.add_space(4.0);
+    zoom_buttons(ui, universe, body_id, zoom_level_requested);
 
     if let Some(w) = universe.get_body(body_id)
         && w.body.orbit.is_some()
         && w.relations.parent.is_some()
     {
-        let text = RichText::new("Orbital Parameters").underline().size(16.0);
+        let text = RichText::new("Orbital Parameters")
+            .underline()
+            .text_style(TextRole::Body.into());
         let label = Label::new(text);
         ui.add_space(12.0);
         ui.add(label);
         ui.add_space(8.0);
-        Grid::new(EDIT_BODY_ORBIT_SALT)
-            .num_columns(2)
-            .spacing([40.0, 4.0])
-            .striped(true)
-            .show(ui, |ui| {
-                edit_body_window_orbit(ui, universe, body_id, window_state, mu_mode)
-            });
+        ui.add_enabled_ui(!locked, |ui| {
+            Grid::new(EDIT_BODY_ORBIT_SALT)
+                .num_columns(2)
+                .spacing([40.0, 4.0])
+                .striped(true)
+                .show(ui, |ui| {
+                    edit_body_window_orbit(ui, universe, body_id, window_state, mu_mode)
+                });
+
+            ui.add_space(4.0);
+            elements_clipboard_row(ui, universe, body_id);
+        });
+
+        let frame_button = ui.button("Frame orbit").on_help_text(
+            RichText::new("Zoom the camera to fit this body's whole orbit. (F)")
+                .color(Color32::WHITE)
+                .text_style(TextRole::Body.into()),
+        );
+        if frame_button.clicked() {
+            *frame_orbit_requested = true;
+        }
+
+        ui.add_space(12.0);
+        ui.add_enabled_ui(!locked, |ui| {
+            orbit_tools(ui, universe, body_id, window_state);
+        });
     }
 
+    ui.add_space(12.0);
+    let rings = RichText::new("Rings")
+        .color(Color32::WHITE)
+        .text_style(TextRole::Body.into())
+        .underline();
+    let coll_res = ui
+        .add_enabled_ui(!locked, |ui| {
+            ui.collapsing(rings, |ui| {
+                Grid::new(EDIT_BODY_RINGS_GRID_SALT)
+                    .num_columns(2)
+                    .spacing([40.0, 4.0])
+                    .striped(true)
+                    .show(ui, |ui| rings_contents(ui, universe, body_id, window_state));
+            })
+        })
+        .inner;
+    coll_res
+        .header_response
+        .on_hover_cursor(CursorIcon::PointingHand);
+
     ui.add_space(12.0);
 
     let derived_info = RichText::new("Derived Information")
         .color(Color32::WHITE)
-        .size(16.0)
+        .text_style(TextRole::Body.into())
         .underline();
 
     if let Some(wrapper) = universe.get_body(body_id) {
@@ -133,7 +373,15 @@ fn body_edit_window_contents(
                 .num_columns(2)
                 .striped(true)
                 .show(ui, |ui| {
-                    body_window_info(ui, &wrapper.body, wrapper.relations.parent, universe);
+                    body_window_info(
+                        ui,
+                        &wrapper.body,
+                        wrapper.relations.parent,
+                        universe,
+                        universe.get_local_gravitational_constant(body_id),
+                        wrapper.frozen_at.unwrap_or(universe.time),
+                        &mut window_state.info_cache,
+                    );
                 });
         });
 
@@ -143,6 +391,134 @@ fn body_edit_window_contents(
     }
 }
 
+/// The collapsible tools under the orbital parameters, which all change the body.
+fn orbit_tools(
+    ui: &mut Ui,
+    universe: &mut Universe,
+    body_id: UniverseId,
+    window_state: &mut EditBodyWindowState,
+) {
+    let period_tool = RichText::new("Parent Mass From Period")
+        .color(Color32::WHITE)
+        .text_style(TextRole::Body.into())
+        .underline();
+    let coll_res = ui.collapsing(period_tool, |ui| {
+        period_tool_contents(ui, universe, body_id, window_state);
+    });
+    coll_res
+        .header_response
+        .on_hover_cursor(CursorIcon::PointingHand);
+
+    ui.add_space(12.0);
+    let maneuvers = RichText::new("Maneuver Nodes")
+        .color(Color32::WHITE)
+        .text_style(TextRole::Body.into())
+        .underline();
+    let coll_res = ui.collapsing(maneuvers, |ui| {
+        maneuver_nodes_contents(ui, universe, body_id);
+    });
+    coll_res
+        .header_response
+        .on_hover_cursor(CursorIcon::PointingHand);
+
+    ui.add_space(12.0);
+    let elements_tool = RichText::new("Elements As Text")
+        .color(Color32::WHITE)
+        .text_style(TextRole::Body.into())
+        .underline();
+    let coll_res = ui.collapsing(elements_tool, |ui| {
+        elements_text_contents(ui, universe, body_id, window_state);
+    });
+    coll_res
+        .header_response
+        .on_hover_cursor(CursorIcon::PointingHand);
+
+    ui.add_space(12.0);
+    let fit_tool = RichText::new("Fit Orbit To Samples")
+        .color(Color32::WHITE)
+        .text_style(TextRole::Body.into())
+        .underline();
+    let coll_res = ui.collapsing(fit_tool, |ui| {
+        fit_tool_contents(ui, universe, body_id, window_state);
+    });
+    coll_res
+        .header_response
+        .on_hover_cursor(CursorIcon::PointingHand);
+}
+
+/// Shows whether the body is locked and lets it be toggled,
+/// returning whether it's locked now.
+fn lock_row(ui: &mut Ui, universe: &mut Universe, body_id: UniverseId) -> bool {
+    let Some(locked) = universe.get_body(body_id).map(|w| w.locked) else {
+        return false;
+    };
+
+    let text = if locked {
+        "🔒 Locked"
+    } else {
+        "🔓 Unlocked"
+    };
+    let button = ui
+        .selectable_label(locked, text)
+        .on_help_text(
+            RichText::new(
+                "Keep this body from being edited, moved to another parent \
+                or deleted until it's unlocked.\n\
+                Bodies with a locked body orbiting them can't be deleted either.",
+            )
+            .color(Color32::WHITE)
+            .text_style(TextRole::Body.into()),
+        )
+        .on_hover_cursor(CursorIcon::PointingHand);
+    if button.clicked()
+        && let Some(wrapper) = universe.get_body_mut(body_id)
+    {
+        wrapper.locked = !locked;
+        return !locked;
+    }
+
+    locked
+}
+
+fn validation_warnings(ui: &mut Ui, universe: &Universe, body_id: UniverseId) {
+    let max_mass_ratio = CONFIG
+        .try_lock()
+        .map(|cfg| validation::clamp_max_mass_ratio(cfg.max_mass_ratio.get()))
+        .unwrap_or(DEFAULT_MAX_MASS_RATIO);
+
+    for warning in validation::validate_body(universe, body_id, max_mass_ratio) {
+        let (text, hover) = match warning {
+            Warning::HeavySatellite {
+                parent_id,
+                mass_ratio,
+            } => {
+                let parent_name = universe
+                    .get_body(parent_id)
+                    .map(|w| &*w.body.name)
+                    .unwrap_or("its parent");
+                (
+                    format!("⚠ {mass_ratio:.3}× the mass of {parent_name}"),
+                    "This body is heavy compared to its parent.\n\
+                    In reality, both would orbit their shared barycenter, \
+                    but this simulation keeps the parent fixed, \
+                    so the orbit and sphere of influence shown here are inaccurate.\n\
+                    Consider lowering the mass, or raise the warning threshold \
+                    in the settings popup.",
+                )
+            }
+        };
+
+        ui.add_space(4.0);
+        ui.label(RichText::new(text).color(Color32::YELLOW))
+            .on_help_text(
+                RichText::new(hover)
+                    .color(Color32::WHITE)
+                    .text_style(TextRole::Body.into()),
+            )
+            .on_hover_cursor(CursorIcon::Help);
+    }
+}
+
 fn edit_body_window_phys(
     ui: &mut Ui,
     universe: &mut Universe,
@@ -150,20 +526,22 @@ fn edit_body_window_phys(
     window_state: &mut EditBodyWindowState,
     mu_mode: BulkMuSetterMode,
 ) {
-    let wrapper = match universe.get_body_mut(body_id) {
-        Some(w) => w,
-        None => return,
+    // Edited as a copy, so the body is only written to once it changes
+    let Some(wrapper) = universe.get_body(body_id) else {
+        return;
     };
+    let mut body = wrapper.body.clone();
+    let mut g_multiplier = wrapper.g_multiplier;
 
     ui.label("Body name")
-        .on_hover_text(
+        .on_help_text(
             RichText::new("The name that will show up in the list of bodies.")
                 .color(Color32::WHITE)
-                .size(16.0),
+                .text_style(TextRole::Body.into()),
         )
         .on_hover_cursor(CursorIcon::Help);
     ui.add(
-        TextEdit::singleline(&mut wrapper.body.name)
+        TextEdit::singleline(&mut body.name)
             .char_limit(255)
             .hint_text("Enter new body name")
             .desired_width(f32::INFINITY),
@@ -171,62 +549,91 @@ fn edit_body_window_phys(
     ui.end_row();
 
     ui.label("Body color")
-        .on_hover_text(
+        .on_help_text(
             RichText::new("The color that this body will be rendered in.")
                 .color(Color32::WHITE)
-                .size(16.0),
+                .text_style(TextRole::Body.into()),
         )
         .on_hover_cursor(CursorIcon::Help);
     let original_srgba: Color32 = {
-        let [r, g, b, a] = wrapper.body.color.into();
+        let [r, g, b, a] = body.color.into();
         Color32::from_rgba_unmultiplied(r, g, b, a)
     };
     let mut srgba = original_srgba.clone();
     let editor = color_edit_button_srgba(ui, &mut srgba, Alpha::OnlyBlend);
     if editor.changed() {
-        wrapper.body.color = srgba.to_srgba_unmultiplied().into();
+        body.color = srgba.to_srgba_unmultiplied().into();
     }
     ui.end_row();
 
     ui.label("Mass")
-        .on_hover_text(
+        .on_help_text(
             RichText::new(
                 "The mass of the body.\n\
             Determines the speed of orbiting objects.",
             )
             .color(Color32::WHITE)
-            .size(16.0),
+            .text_style(TextRole::Body.into()),
         )
         .on_hover_cursor(CursorIcon::Help);
-    let mut mass = wrapper.body.mass;
-    drag_value_with_unit(
+    precise_value_with_unit(
         EDIT_BODY_MASS_SALT,
         ui,
-        &mut mass,
+        &mut body.mass,
         &mut window_state.mass_unit,
     );
     ui.end_row();
 
     ui.label("Radius")
-        .on_hover_text(
+        .on_help_text(
             RichText::new("The radius that this body will be rendered in.")
                 .color(Color32::WHITE)
-                .size(16.0),
+                .text_style(TextRole::Body.into()),
         )
         .on_hover_cursor(CursorIcon::Help);
-    drag_value_with_unit(
+    precise_value_with_unit(
         EDIT_BODY_RADIUS_SALT,
         ui,
-        &mut wrapper.body.radius,
+        &mut body.radius,
         &mut window_state.radius_unit,
     );
     ui.end_row();
 
-    if wrapper.body.mass != mass {
-        wrapper.body.mass = mass;
+    ui.label("G multiplier")
+        .on_help_text(
+            RichText::new(
+                "Multiplies the gravitational constant for the bodies orbiting this one, \
+                and everything below them.\n\
+                Multipliers further up the hierarchy compound with this one.",
+            )
+            .color(Color32::WHITE)
+            .text_style(TextRole::Body.into()),
+        )
+        .on_hover_cursor(CursorIcon::Help);
+    ui.add(
+        DragValue::new(&mut g_multiplier)
+            .speed(0.01)
+            .range(1e-6..=1e6)
+            .prefix("×"),
+    );
+    ui.end_row();
 
+    let mass_changed = wrapper.body.mass != body.mass;
+    if wrapper.body != body
+        && let Some(wrapper) = universe.get_body_mut(body_id)
+    {
+        wrapper.body = body;
+    }
+    if mass_changed {
         let _ = universe.update_children_gravitational_parameters(body_id, mu_mode);
     }
+
+    if universe
+        .get_body(body_id)
+        .is_some_and(|w| w.g_multiplier != g_multiplier)
+    {
+        universe.set_g_multiplier(body_id, g_multiplier, mu_mode);
+    }
 }
 
 fn edit_body_window_orbit(
@@ -241,27 +648,54 @@ fn edit_body_window_orbit(
         None => return,
     };
 
-    let parent_id = parent_selector(ui, universe, wrapper, body_id);
+    let pending_parent = window_state
+        .pending_parent
+        .filter(|&(id, _)| id == body_id)
+        .map(|(_, parent_id)| parent_id);
+    let parent_id = parent_selector(ui, universe, wrapper, body_id, pending_parent);
 
-    if parent_id != wrapper.relations.parent {
-        let res = universe.move_body(body_id, parent_id, mu_mode);
-        if let Err(e) = res {
-            eprintln!("{e}");
+    window_state.pending_parent = match parent_id {
+        Some(parent_id) if Some(parent_id) != wrapper.relations.parent => {
+            Some((body_id, parent_id))
         }
+        _ => None,
+    };
+    if let Some((_, parent_id)) = window_state.pending_parent {
+        pending_move_row(ui, universe, body_id, parent_id, window_state, mu_mode);
     }
 
-    let wrapper = match universe.get_body_mut(body_id) {
-        Some(w) => w,
-        None => return,
+    freeze_row(ui, universe, body_id, window_state);
+    precession_row(ui, universe, body_id);
+    jitter_row(ui, universe, body_id);
+    always_show_row(ui, universe, body_id);
+    hyperbola_range_row(ui, universe, body_id);
+
+    let parent_id = universe.get_body(body_id).and_then(|w| w.relations.parent);
+    let parent_soi = parent_id
+        .and_then(|id| universe.get_soi_radius(id))
+        .filter(|soi| soi.is_finite());
+    let surface_radius = altitude::surface_radius(universe, parent_id);
+    let min_periapsis = parent_id
+        .and_then(|id| universe.get_body(id))
+        .zip(universe.get_body(body_id))
+        .map(|(parent, wrapper)| {
+            validation::min_safe_periapsis(parent.body.radius, wrapper.body.radius)
+        });
+
+    let Some(wrapper) = universe.get_body(body_id) else {
+        return;
     };
+    let time = wrapper.frozen_at.unwrap_or(universe.time);
 
-    let orbit = match wrapper.body.orbit.as_mut() {
-        Some(o) => o,
-        None => return,
+    // Edited as a copy, so the body is only written to once it changes
+    let Some(original) = &wrapper.body.orbit else {
+        return;
     };
+    let mut edited = original.clone();
+    let orbit = &mut edited;
 
     ui.label("Eccentricity")
-        .on_hover_text(
+        .on_help_text(
             RichText::new(
                 "How eccentric the orbit is.\n\
             An eccentricity of 1 (parabolic) is not supported.\n\
@@ -269,76 +703,137 @@ fn edit_body_window_orbit(
             An eccentricity of more than one means the orbit never loops (is open; hyperbolic).",
             )
             .color(Color32::WHITE)
-            .size(16.0),
+            .text_style(TextRole::Body.into()),
         )
         .on_hover_cursor(CursorIcon::Help);
     let mut eccentricity = orbit.get_eccentricity();
-    let dv = DragValue::new(&mut eccentricity)
-        .range(0.0..=f64::MAX)
-        .speed(0.01);
-    let dv = ui.add_sized((ui.available_width(), 18.0), dv);
-    if dv.changed() {
-        orbit.set_eccentricity(eccentricity);
-    }
+    // Left alone if the orbit already leaves the SOI,
+    // so that the drag value doesn't snap it back on its own
+    let cap = parent_soi
+        .filter(|_| window_state.cap_eccentricity)
+        .and_then(|soi| max_eccentricity_within(orbit.get_periapsis(), soi))
+        .filter(|&cap| eccentricity <= cap);
+    ui.horizontal(|ui| {
+        let dv = nudgeable(
+            ui,
+            ElementKind::Eccentricity,
+            &mut eccentricity,
+            |ui, value| {
+                ui.add(
+                    DragValue::new(value)
+                        .range(0.0..=cap.unwrap_or(f64::MAX))
+                        .speed(0.01),
+                )
+            },
+        );
+        if dv.changed() {
+            orbit.set_eccentricity(eccentricity.clamp(0.0, cap.unwrap_or(f64::MAX)));
+        }
+
+        let prefix = if surface_radius.is_some() {
+            "Ap. alt."
+        } else {
+            "Ap."
+        };
+        let apoapsis_text = if orbit.is_closed() {
+            let apoapsis = orbit.get_apoapsis() - surface_radius.unwrap_or(0.0);
+            let unit = LengthUnit::largest_unit_from_base(apoapsis);
+            format!(
+                "{prefix} {} {unit}",
+                PrettyPrintFloat(apoapsis / unit.get_value())
+            )
+        } else {
+            format!("{prefix} ∞")
+        };
+        let apoapsis_text = match parent_soi {
+            Some(soi) if !orbit.is_closed() || orbit.get_apoapsis() > soi => {
+                RichText::new(format!("{apoapsis_text} ⚠")).color(Color32::YELLOW)
+            }
+            _ => RichText::new(apoapsis_text),
+        };
+        ui.label(apoapsis_text)
+            .on_help_text(
+                RichText::new(
+                    "The farthest this orbit gets from its parent, \
+                    from its surface while \"Altitudes above surface\" is on.\n\
+                    Marked when that's outside the parent's sphere of influence.",
+                )
+                .color(Color32::WHITE)
+                .text_style(TextRole::Body.into()),
+            )
+            .on_hover_cursor(CursorIcon::Help);
+    });
     ui.end_row();
 
-    ui.label("Periapsis")
-        .on_hover_text(
-            RichText::new(
-                "The minimum distance of the orbit \
-            to the center of the parent body.",
+    if parent_soi.is_some() {
+        ui.label("Stay in SOI")
+            .on_help_text(
+                RichText::new(
+                    "Keeps eccentricity edits from pushing the apoapsis \
+                outside the parent's sphere of influence.\n\
+                Turn this off to raise the eccentricity past that, \
+                up to escape orbits.",
+                )
+                .color(Color32::WHITE)
+                .text_style(TextRole::Body.into()),
             )
-            .color(Color32::WHITE)
-            .size(16.0),
-        )
-        .on_hover_cursor(CursorIcon::Help);
-    let mut periapsis = orbit.get_periapsis();
-    drag_value_with_unit(
-        EDIT_BODY_PERIAPSIS_SALT,
+            .on_hover_cursor(CursorIcon::Help);
+        ui.checkbox(&mut window_state.cap_eccentricity, "");
+        ui.end_row();
+    }
+
+    periapsis_rows(
         ui,
-        &mut periapsis,
+        EDIT_BODY_PERIAPSIS_SALT,
+        orbit,
         &mut window_state.periapsis_unit,
+        min_periapsis,
+        surface_radius,
     );
-    if periapsis != orbit.get_periapsis() {
-        orbit.set_periapsis(periapsis);
-    }
-    ui.end_row();
 
     ui.label("Inclination")
-        .on_hover_text(
+        .on_help_text(
             RichText::new("How inclined from the up axis the orbit is.")
                 .color(Color32::WHITE)
-                .size(16.0),
+                .text_style(TextRole::Body.into()),
         )
         .on_hover_cursor(CursorIcon::Help);
     let mut inclination = orbit.get_inclination().to_degrees();
-    let slider = Slider::new(&mut inclination, 0.0..=180.0).suffix('°');
-    let slider = ui.add_sized((ui.available_width(), 18.0), slider);
+    let slider = nudgeable(
+        ui,
+        ElementKind::Inclination,
+        &mut inclination,
+        |ui, value| {
+            let slider = Slider::new(value, 0.0..=180.0).suffix('°');
+            ui.add_sized((ui.available_width(), 18.0), slider)
+        },
+    );
     if slider.changed() {
-        orbit.set_inclination(inclination.to_radians());
+        orbit.set_inclination(inclination.clamp(0.0, 180.0).to_radians());
     }
     ui.end_row();
 
     ui.label("Arg. of Pe.")
-        .on_hover_text(
+        .on_help_text(
             RichText::new(
                 "The argument of periapsis of the orbit.\n\
             This is the angle offset of the periapsis along the orbital plane.",
             )
             .color(Color32::WHITE)
-            .size(16.0),
+            .text_style(TextRole::Body.into()),
         )
         .on_hover_cursor(CursorIcon::Help);
     let mut arg_pe = orbit.get_arg_pe().to_degrees();
-    let slider = Slider::new(&mut arg_pe, 0.0..=360.0).suffix('°');
-    let slider = ui.add(slider);
+    let slider = nudgeable(ui, ElementKind::Angle, &mut arg_pe, |ui, value| {
+        ui.add(Slider::new(value, 0.0..=360.0).suffix('°'))
+    });
     if slider.changed() {
-        orbit.set_arg_pe(arg_pe.to_radians());
+        orbit.set_arg_pe(arg_pe.rem_euclid(360.0).to_radians());
     }
     ui.end_row();
 
     ui.label("RAAN")
-        .on_hover_text(
+        .on_help_text(
             RichText::new(
                 "The right ascension of the ascending node.\n\
             a.k.a.: the longitude of ascending node.\n\
@@ -346,105 +841,1130 @@ fn edit_body_window_orbit(
             the reference plane (horizontal plane).",
             )
             .color(Color32::WHITE)
-            .size(16.0),
+            .text_style(TextRole::Body.into()),
         )
         .on_hover_cursor(CursorIcon::Help);
     let mut lan = orbit.get_long_asc_node().to_degrees();
-    let slider = Slider::new(&mut lan, 0.0..=360.0).suffix('°');
-    let slider = ui.add(slider);
+    let slider = nudgeable(ui, ElementKind::Angle, &mut lan, |ui, value| {
+        ui.add(Slider::new(value, 0.0..=360.0).suffix('°'))
+    });
     if slider.changed() {
-        orbit.set_long_asc_node(lan.to_radians());
+        orbit.set_long_asc_node(lan.rem_euclid(360.0).to_radians());
     }
     ui.end_row();
 
     let mut mean_anomaly = orbit.get_mean_anomaly_at_epoch().to_degrees();
     if orbit.get_eccentricity() < 1.0 {
         ui.label("Mean anom.")
-            .on_hover_text(
+            .on_help_text(
                 RichText::new(
                     "Mean anomaly at epoch.\n\
                 This is the offset to the mean anomaly.\n\
                 At time = 0, the mean anomaly of this orbit will be equal to this.",
                 )
                 .color(Color32::WHITE)
-                .size(16.0),
+                .text_style(TextRole::Body.into()),
             )
             .on_hover_cursor(CursorIcon::Help);
-        let slider = Slider::new(&mut mean_anomaly, 0.0..=360.0).suffix('°');
-        let slider = ui.add(slider);
+        let slider = nudgeable(ui, ElementKind::Anomaly, &mut mean_anomaly, |ui, value| {
+            ui.add(Slider::new(value, 0.0..=360.0).suffix('°'))
+        });
         if mean_anomaly < 0.0 || mean_anomaly > 360.0 {
             mean_anomaly = mean_anomaly.rem_euclid(360.0);
         }
         if slider.changed() {
             orbit.set_mean_anomaly_at_epoch(mean_anomaly.to_radians());
         }
+        ui.end_row();
+
+        ui.label("Phase")
+            .on_help_text(
+                RichText::new(
+                    "Where the body is around its orbit right now.\n\
+                The hand is the mean anomaly, and the blue dot is \
+                the true anomaly, where the body actually is.\n\
+                Drag the hand to move the body along its orbit. \
+                Hold Shift to snap to 5° steps.",
+                )
+                .color(Color32::WHITE)
+                .text_style(TextRole::Body.into()),
+            )
+            .on_hover_cursor(CursorIcon::Help);
+        let current_mean_anomaly = orbit.get_mean_anomaly_at_time(time).rem_euclid(TAU);
+        let true_anomaly =
+            orbit.get_true_anomaly_at_eccentric_anomaly(orbit.get_eccentric_anomaly_at_time(time));
+        let mut dial_anomaly = current_mean_anomaly;
+        let dial = phase_dial(ui, &mut dial_anomaly, true_anomaly);
+        if dial.changed() {
+            let epoch_anomaly =
+                orbit.get_mean_anomaly_at_epoch() + (dial_anomaly - current_mean_anomaly);
+            orbit.set_mean_anomaly_at_epoch(epoch_anomaly.rem_euclid(TAU));
+        }
     } else {
         ui.label("Hyp. m. anom.")
-            .on_hover_text(
+            .on_help_text(
                 RichText::new(
                     "Hyperbolic mean anomaly at epoch.\n\
                 This is the offset to the hyperbolic mean anomaly.\n\
                 At time = 0, the hyperbolic mean anomaly of this orbit will be equal to this.",
                 )
                 .color(Color32::WHITE)
-                .size(16.0),
+                .text_style(TextRole::Body.into()),
             )
             .on_hover_cursor(CursorIcon::Help);
-        let dv = DragValue::new(&mut mean_anomaly)
-            .range(f64::MIN..=f64::MAX)
-            .suffix('°');
-        let dv = ui.add(dv);
+        let dv = nudgeable(ui, ElementKind::Anomaly, &mut mean_anomaly, |ui, value| {
+            ui.add(DragValue::new(value).range(f64::MIN..=f64::MAX).suffix('°'))
+        });
         if dv.changed() {
             orbit.set_mean_anomaly_at_epoch(mean_anomaly.to_radians());
         }
     }
     ui.end_row();
+
+    altitude_sparkline_row(ui, orbit, time, surface_radius);
+
+    if *original != edited
+        && let Some(orbit) = universe
+            .get_body_mut(body_id)
+            .and_then(|w| w.body.orbit.as_mut())
+    {
+        *orbit = edited;
+    }
 }
 
-/// Returns the new parent ID
-fn parent_selector(
+/// Plots the distance from the parent over one period,
+/// or around periapsis on open orbits.
+fn altitude_sparkline_row(ui: &mut Ui, orbit: &Orbit, time: f64, surface_radius: Option<f64>) {
+    ui.label("Altitude")
+        .on_help_text(
+            RichText::new(
+                "How far the body is from its parent over time, from periapsis.\n\
+                Shows one period on closed orbits, and the time around \
+                periapsis on open ones. The dot is where the body is now.",
+            )
+            .color(Color32::WHITE)
+            .text_style(TextRole::Body.into()),
+        )
+        .on_hover_cursor(CursorIcon::Help);
+
+    let mut profile = AltitudeProfile::sample(orbit, time, ALTITUDE_SPARKLINE_SAMPLES);
+    if let Some(radius) = surface_radius {
+        profile.altitudes.iter_mut().for_each(|a| *a -= radius);
+    }
+
+    let plot = sparkline(ui, &profile.altitudes, profile.now);
+    if let Some((min, max)) = profile.range() {
+        let format_length = |length: f64| {
+            let unit = LengthUnit::largest_unit_from_base(length);
+            format!("{} {unit}", PrettyPrintFloat(length / unit.get_value()))
+        };
+        plot.on_help_text(
+            RichText::new(format!(
+                "{} to {} over {}",
+                format_length(min),
+                format_length(max),
+                TimeDisplayMode::MultiUnit.format_time(profile.duration),
+            ))
+            .color(Color32::WHITE)
+            .text_style(TextRole::Body.into()),
+        );
+    }
+    ui.end_row();
+}
+
+/// The row for freezing the body in place, optionally with its satellites.
+fn freeze_row(
     ui: &mut Ui,
-    universe: &Universe,
-    wrapper: &BodyWrapper,
+    universe: &mut Universe,
     body_id: UniverseId,
-) -> Option<UniverseId> {
-    ui.label("Parent body")
-        .on_hover_text(
-            RichText::new("The body that this body is orbiting around.")
-                .color(Color32::WHITE)
-                .size(16.0),
+    window_state: &mut EditBodyWindowState,
+) {
+    let Some(wrapper) = universe.get_body(body_id) else {
+        return;
+    };
+
+    ui.label("Frozen")
+        .on_help_text(
+            RichText::new(
+                "Stops this body from moving along its orbit \
+            while the rest of the universe keeps running.\n\
+            When unfrozen, the body carries on from where it stopped.",
+            )
+            .color(Color32::WHITE)
+            .text_style(TextRole::Body.into()),
         )
         .on_hover_cursor(CursorIcon::Help);
-    let mut parent_id = wrapper.relations.parent;
-    ComboBox::from_id_salt(EDIT_BODY_PARENT_COMBO_BOX_SALT)
-        .close_behavior(PopupCloseBehavior::CloseOnClickOutside)
-        .wrap_mode(TextWrapMode::Extend)
-        .selected_text(
-            wrapper
-                .relations
-                .parent
-                .and_then(|parent_id| universe.get_body(parent_id))
-                .map(|w| &*w.body.name)
-                .unwrap_or("—"),
-        )
-        .show_ui(ui, |ui| {
-            selectable_body_tree(
-                ui,
-                *EDIT_BODY_PARENT_TREE_ID,
-                universe,
-                &mut parent_id,
-                Some(DisallowedData {
-                    disallowed_set: &{
-                        let mut set = universe.get_descendants(body_id).unwrap_or_default();
-                        set.insert(body_id);
-                        set
-                    },
-                    reason: &RichText::new("cannot set new parent to self or own descendant")
-                        .color(Color32::LIGHT_RED),
-                }),
+
+    let mut frozen = wrapper.frozen_at.is_some();
+    let has_satellites = !wrapper.relations.satellites.is_empty();
+
+    ui.horizontal(|ui| {
+        if ui.checkbox(&mut frozen, "").changed() {
+            universe.set_body_frozen(
+                body_id,
+                frozen,
+                has_satellites && window_state.freeze_satellites,
             );
-        });
+        }
+
+        if has_satellites {
+            ui.checkbox(&mut window_state.freeze_satellites, "With satellites")
+                .on_help_text(
+                    RichText::new(
+                        "Whether toggling this also freezes or unfreezes \
+                    everything orbiting this body.",
+                    )
+                    .color(Color32::WHITE)
+                    .text_style(TextRole::Body.into()),
+                );
+        }
+    });
     ui.end_row();
+}
 
-    parent_id
+/// The highest eccentricity that keeps the apoapsis of an orbit
+/// with the given periapsis within `radius`.
+///
+/// Returns None if the periapsis is already outside it.
+fn max_eccentricity_within(periapsis: f64, radius: f64) -> Option<f64> {
+    // From r_ap = r_pe (1 + e) / (1 - e)
+    let valid = periapsis < radius;
+    if !valid {
+        return None;
+    }
+    Some((radius - periapsis) / (radius + periapsis))
+}
+
+fn always_show_row(ui: &mut Ui, universe: &mut Universe, body_id: UniverseId) {
+    let Some(mut always_show) = universe.get_body(body_id).map(|w| w.always_show) else {
+        return;
+    };
+
+    ui.label("Always show")
+        .on_help_text(
+            RichText::new(
+                "Keeps this body and its satellites drawn \
+            when \"Hide distant subsystems\" is on in the settings, \
+            no matter how far away they are.",
+            )
+            .color(Color32::WHITE)
+            .text_style(TextRole::Body.into()),
+        )
+        .on_hover_cursor(CursorIcon::Help);
+    if ui.checkbox(&mut always_show, "").changed()
+        && let Some(wrapper) = universe.get_body_mut(body_id)
+    {
+        wrapper.always_show = always_show;
+    }
+    ui.end_row();
+}
+
+/// Only shown while the orbit is hyperbolic, as closed orbits are always drawn whole.
+fn hyperbola_range_row(ui: &mut Ui, universe: &mut Universe, body_id: UniverseId) {
+    let Some(wrapper) = universe.get_body(body_id) else {
+        return;
+    };
+    let hyperbolic = wrapper
+        .body
+        .orbit
+        .as_ref()
+        .is_some_and(|o| o.get_eccentricity() > 1.0);
+    if !hyperbolic {
+        return;
+    }
+
+    ui.label("Hyperbola shown")
+        .on_help_text(
+            RichText::new("How much of this body's hyperbolic path is drawn.")
+                .color(Color32::WHITE)
+                .text_style(TextRole::Body.into()),
+        )
+        .on_hover_cursor(CursorIcon::Help);
+
+    let original = wrapper.hyperbola_range;
+    let mut range = original;
+    ui.horizontal(|ui| {
+        let range = &mut range;
+        let options = [
+            HyperbolaRange::AroundBody,
+            HyperbolaRange::WithinParentSoi,
+            HyperbolaRange::TrueAnomaly(DEFAULT_HYPERBOLA_TRUE_ANOMALY_DEG.to_radians()),
+        ];
+
+        ComboBox::from_id_salt(EDIT_BODY_HYPERBOLA_COMBO_BOX_SALT)
+            .wrap_mode(TextWrapMode::Extend)
+            .selected_text(range.name())
+            .show_ui(ui, |ui| {
+                for option in options {
+                    let selected =
+                        core::mem::discriminant(range) == core::mem::discriminant(&option);
+                    let button = ui.selectable_label(selected, option.name()).on_help_text(
+                        RichText::new(option.description())
+                            .color(Color32::WHITE)
+                            .text_style(TextRole::Body.into()),
+                    );
+                    if button.clicked() && !selected {
+                        *range = option;
+                    }
+                }
+            })
+            .response
+            .on_hover_cursor(CursorIcon::PointingHand);
+
+        if let HyperbolaRange::TrueAnomaly(true_anomaly) = range {
+            let mut degrees = true_anomaly.to_degrees();
+            let drag_value = ui
+                .add(
+                    DragValue::new(&mut degrees)
+                        .speed(0.5)
+                        .range(0.0..=180.0)
+                        .prefix("±")
+                        .suffix("°"),
+                )
+                .on_help_text(
+                    RichText::new(
+                        "How far either side of the periapsis to draw, as seen from the parent.",
+                    )
+                    .color(Color32::WHITE)
+                    .text_style(TextRole::Body.into()),
+                );
+            if drag_value.changed() {
+                *true_anomaly = degrees.to_radians();
+            }
+        }
+    });
+    ui.end_row();
+
+    if range != original
+        && let Some(wrapper) = universe.get_body_mut(body_id)
+    {
+        wrapper.hyperbola_range = range;
+    }
+}
+
+fn precession_row(ui: &mut Ui, universe: &mut Universe, body_id: UniverseId) {
+    let scale = universe.perturbation_settings.precession_scale;
+
+    let Some(wrapper) = universe.get_body(body_id) else {
+        return;
+    };
+    let mut precession = wrapper.perturbations.relativistic_precession;
+
+    ui.label("GR precession")
+        .on_help_text(
+            RichText::new(
+                "Slowly rotates this body's periapsis forward, \
+            as predicted by general relativity.\n\
+            The effect is tiny, about 43″ per century for Mercury; \
+            raise \"Precession scale\" in the Universe Settings window to see it.\n\
+            Only closed orbits precess.",
+            )
+            .color(Color32::WHITE)
+            .text_style(TextRole::Body.into()),
+        )
+        .on_hover_cursor(CursorIcon::Help);
+
+    let checkbox = ui.horizontal(|ui| {
+        let checkbox = ui.checkbox(&mut precession, "");
+
+        if let Some(orbit) = &wrapper.body.orbit {
+            const ARCSEC_PER_RAD: f64 = 180.0 * 3600.0 / core::f64::consts::PI;
+            const SECS_PER_CENTURY: f64 = 100.0 * 365.25 * 86400.0;
+
+            let rate = perturbation::relativistic_precession_rate(orbit)
+                * scale
+                * ARCSEC_PER_RAD
+                * SECS_PER_CENTURY;
+            ui.label(format!("{}″/century", PrettyPrintFloat(rate)));
+        }
+        checkbox
+    });
+    ui.end_row();
+
+    if checkbox.inner.changed()
+        && let Some(wrapper) = universe.get_body_mut(body_id)
+    {
+        wrapper.perturbations.relativistic_precession = precession;
+    }
+}
+
+fn jitter_row(ui: &mut Ui, universe: &mut Universe, body_id: UniverseId) {
+    let Some(original) = universe.get_body(body_id).map(|w| w.perturbations.jitter) else {
+        return;
+    };
+    let mut jitter = original;
+
+    ui.label("Jitter")
+        .on_help_text(
+            RichText::new(
+                "Makes the chosen orbital elements wander randomly over time, \
+            to show how a family of similar orbits spreads out.\n\
+            The amount is how far they typically wander per orbit: \
+            as is for the eccentricity, as a fraction of the periapsis \
+            for the periapsis, and as a fraction of a full turn for angles.\n\
+            Set the seed in the Universe Settings window.",
+            )
+            .color(Color32::WHITE)
+            .text_style(TextRole::Body.into()),
+        )
+        .on_hover_cursor(CursorIcon::Help);
+
+    ui.horizontal(|ui| {
+        ui.add(
+            DragValue::new(&mut jitter.amplitude)
+                .range(0.0..=1.0)
+                .speed(0.0005)
+                .max_decimals(4),
+        );
+        ui.menu_button("Elements", |ui| {
+            for element in JitterElement::iter() {
+                let mut enabled = jitter.is_enabled(element);
+                if ui.checkbox(&mut enabled, element.name()).changed() {
+                    jitter.set_enabled(element, enabled);
+                }
+            }
+        });
+    });
+    ui.end_row();
+
+    if jitter != original
+        && let Some(wrapper) = universe.get_body_mut(body_id)
+    {
+        wrapper.perturbations.jitter = jitter;
+    }
+}
+
+fn rings_contents(
+    ui: &mut Ui,
+    universe: &mut Universe,
+    body_id: UniverseId,
+    window_state: &mut EditBodyWindowState,
+) {
+    let Some(wrapper) = universe.get_body(body_id) else {
+        return;
+    };
+    let original = wrapper.rings;
+    let mut rings = original;
+    rings_rows(ui, &mut rings, wrapper.body.radius, window_state);
+
+    if rings != original
+        && let Some(wrapper) = universe.get_body_mut(body_id)
+    {
+        wrapper.rings = rings;
+    }
+}
+
+fn rings_rows(
+    ui: &mut Ui,
+    rings: &mut Option<Rings>,
+    body_radius: f64,
+    window_state: &mut EditBodyWindowState,
+) {
+    ui.label("Has rings")
+        .on_help_text(
+            RichText::new("Draw a flat, see-through ring system around this body.")
+                .color(Color32::WHITE)
+                .text_style(TextRole::Body.into()),
+        )
+        .on_hover_cursor(CursorIcon::Help);
+    let mut has_rings = rings.is_some();
+    if ui.checkbox(&mut has_rings, "").changed() {
+        *rings = has_rings.then(|| Rings::around(body_radius));
+    }
+    ui.end_row();
+
+    let Some(rings) = rings else {
+        return;
+    };
+
+    ui.label("Inner radius")
+        .on_help_text(
+            RichText::new("How far the rings' inner edge is from the body's center.")
+                .color(Color32::WHITE)
+                .text_style(TextRole::Body.into()),
+        )
+        .on_hover_cursor(CursorIcon::Help);
+    drag_value_with_unit(
+        EDIT_BODY_RING_INNER_SALT,
+        ui,
+        &mut rings.inner_radius,
+        &mut window_state.ring_inner_unit,
+    );
+    ui.end_row();
+
+    ui.label("Outer radius")
+        .on_help_text(
+            RichText::new("How far the rings' outer edge is from the body's center.")
+                .color(Color32::WHITE)
+                .text_style(TextRole::Body.into()),
+        )
+        .on_hover_cursor(CursorIcon::Help);
+    drag_value_with_unit(
+        EDIT_BODY_RING_OUTER_SALT,
+        ui,
+        &mut rings.outer_radius,
+        &mut window_state.ring_outer_unit,
+    );
+    ui.end_row();
+
+    rings.inner_radius = rings.inner_radius.min(rings.outer_radius);
+
+    ui.label("Inclination")
+        .on_help_text(
+            RichText::new(
+                "How inclined from the up axis the rings are.\n\
+                Real rings lie in their planet's equatorial plane.",
+            )
+            .color(Color32::WHITE)
+            .text_style(TextRole::Body.into()),
+        )
+        .on_hover_cursor(CursorIcon::Help);
+    let mut inclination = rings.inclination.to_degrees();
+    let slider = Slider::new(&mut inclination, 0.0..=180.0).suffix('°');
+    if ui.add(slider).changed() {
+        rings.inclination = inclination.to_radians();
+    }
+    ui.end_row();
+
+    ui.label("RAAN")
+        .on_help_text(
+            RichText::new(
+                "The longitude of the rings' ascending node.\n\
+                This is the angle offset of the line where the rings cross \
+                the reference plane (horizontal plane).",
+            )
+            .color(Color32::WHITE)
+            .text_style(TextRole::Body.into()),
+        )
+        .on_hover_cursor(CursorIcon::Help);
+    let mut lan = rings.long_asc_node.to_degrees();
+    let slider = Slider::new(&mut lan, 0.0..=360.0).suffix('°');
+    if ui.add(slider).changed() {
+        rings.long_asc_node = lan.to_radians();
+    }
+    ui.end_row();
+
+    ui.label("Color")
+        .on_help_text(
+            RichText::new(
+                "The color the rings will be rendered in.\n\
+                Lower the opacity to see more of what's behind them.",
+            )
+            .color(Color32::WHITE)
+            .text_style(TextRole::Body.into()),
+        )
+        .on_hover_cursor(CursorIcon::Help);
+    let [r, g, b, a] = rings.color.into();
+    let mut srgba = Color32::from_rgba_unmultiplied(r, g, b, a);
+    if color_edit_button_srgba(ui, &mut srgba, Alpha::OnlyBlend).changed() {
+        rings.color = srgba.to_srgba_unmultiplied().into();
+    }
+    ui.end_row();
+}
+
+fn maneuver_nodes_contents(ui: &mut Ui, universe: &mut Universe, body_id: UniverseId) {
+    let mut indices: Vec<usize> = (0..universe.maneuver_nodes.len())
+        .filter(|&i| universe.maneuver_nodes[i].body == body_id)
+        .collect();
+
+    if indices.is_empty() {
+        ui.label("Click on this body's orbit line to plan a maneuver.");
+        return;
+    }
+
+    indices.sort_by(|&a, &b| {
+        let nodes = &universe.maneuver_nodes;
+        nodes[a].time.total_cmp(&nodes[b].time)
+    });
+
+    let time = universe.time;
+    let nodes = universe.maneuver_nodes.clone();
+    let mut to_delete = None;
+
+    for index in indices {
+        let node = &mut universe.maneuver_nodes[index];
+
+        Grid::new((EDIT_BODY_MANEUVER_GRID_SALT, index))
+            .num_columns(2)
+            .spacing([40.0, 4.0])
+            .striped(true)
+            .show(ui, |ui| {
+                ui.label("Burns in");
+                ui.label(TimeDisplayMode::MultiUnit.format_time(node.time - time));
+                ui.end_row();
+
+                maneuver_component_row(
+                    ui,
+                    "Prograde",
+                    "Speeds the body up along its direction of motion.\n\
+                    Negative values slow it down instead.",
+                    &mut node.prograde,
+                );
+                maneuver_component_row(
+                    ui,
+                    "Normal",
+                    "Pushes the body out of its orbital plane, \
+                    along the orbit's angular momentum.",
+                    &mut node.normal,
+                );
+                maneuver_component_row(
+                    ui,
+                    "Radial",
+                    "Pushes the body away from its parent, \
+                    perpendicular to its direction of motion.",
+                    &mut node.radial,
+                );
+
+                ui.label("Total Δv");
+                ui.label(format!("{} m/s", PrettyPrintFloat(node.delta_v())));
+                ui.end_row();
+            });
+
+        if ui.button("Delete node").clicked() {
+            to_delete = Some(index);
+        }
+        ui.add_space(8.0);
+    }
+
+    if let Some(index) = to_delete {
+        universe.maneuver_nodes.remove(index);
+    }
+    if universe.maneuver_nodes != nodes {
+        universe.mark_edited();
+    }
+}
+
+fn maneuver_component_row(ui: &mut Ui, label: &str, description: &str, value: &mut f64) {
+    ui.label(label)
+        .on_help_text(
+            RichText::new(description)
+                .color(Color32::WHITE)
+                .text_style(TextRole::Body.into()),
+        )
+        .on_hover_cursor(CursorIcon::Help);
+    ui.add(DragValue::new(value).speed(1.0).suffix(" m/s"));
+    ui.end_row();
+}
+
+fn elements_clipboard_row(ui: &mut Ui, universe: &mut Universe, body_id: UniverseId) {
+    let has_copied = ELEMENTS_CLIPBOARD
+        .try_lock()
+        .map(|clipboard| clipboard.is_some())
+        .unwrap_or(false);
+
+    ui.horizontal(|ui| {
+        let copy_button = ui.button("Copy elements").on_help_text(
+            RichText::new(
+                "Copy this orbit's six elements, to paste them onto another body.\n\
+                They are also copied to the clipboard as text.",
+            )
+            .color(Color32::WHITE)
+            .text_style(TextRole::Body.into()),
+        );
+
+        let paste_button = ui
+            .add_enabled(has_copied, Button::new("Paste elements"))
+            .on_help_text(
+                RichText::new(
+                    "Give this body the copied orbital elements.\n\
+                    The orbit keeps the shape and position it had, \
+                    but moves at the speed this body's parent gives it.",
+                )
+                .color(Color32::WHITE)
+                .text_style(TextRole::Body.into()),
+            )
+            .on_disabled_hover_text(
+                RichText::new("Copy elements from a body first.")
+                    .color(Color32::WHITE)
+                    .text_style(TextRole::Body.into()),
+            );
+
+        if copy_button.clicked()
+            && let Some(orbit) = universe
+                .get_body(body_id)
+                .and_then(|w| w.body.orbit.as_ref())
+        {
+            let elements = OrbitalElements::from_orbit(orbit);
+            if let Ok(mut clipboard) = ELEMENTS_CLIPBOARD.try_lock() {
+                *clipboard = Some(elements);
+            }
+            ui.ctx().copy_text(elements.to_text());
+        }
+
+        if paste_button.clicked()
+            && let Some(elements) = ELEMENTS_CLIPBOARD
+                .try_lock()
+                .ok()
+                .and_then(|clipboard| *clipboard)
+        {
+            apply_elements(universe, body_id, elements);
+        }
+    });
+}
+
+fn elements_text_contents(
+    ui: &mut Ui,
+    universe: &mut Universe,
+    body_id: UniverseId,
+    window_state: &mut EditBodyWindowState,
+) {
+    ui.label(
+        "Paste elements from another tool, one \"name: value\" per line. \
+        Angles are in degrees and distances in meters, \
+        unless another unit like \"rad\" or \"km\" follows the value.",
+    );
+
+    let text_edit = TextEdit::multiline(&mut window_state.elements_text)
+        .font(TextStyle::Monospace)
+        .desired_rows(6)
+        .desired_width(f32::INFINITY)
+        .hint_text(OrbitalElements::default().to_text());
+    ui.add(text_edit);
+
+    let apply_button = ui.button("Apply elements").on_help_text(
+        RichText::new(
+            "Replace this body's orbit with the elements above.\n\
+            All six elements are needed.",
+        )
+        .color(Color32::WHITE)
+        .text_style(TextRole::Body.into()),
+    );
+
+    if apply_button.clicked() {
+        match OrbitalElements::from_text(&window_state.elements_text) {
+            Ok(elements) => {
+                window_state.elements_text_error = None;
+                apply_elements(universe, body_id, elements);
+            }
+            Err(e) => window_state.elements_text_error = Some((body_id, e)),
+        }
+    }
+
+    if let Some((id, e)) = window_state.elements_text_error
+        && id == body_id
+    {
+        ui.label(RichText::new(format!("⚠ Can't read the elements: {e}")).color(Color32::YELLOW));
+    }
+}
+
+/// Gives a body an orbit with the given elements,
+/// using the gravitational parameter of its own parent.
+///
+/// Warns the user instead if the elements can't be used,
+/// or the body has no orbit to replace.
+fn apply_elements(universe: &mut Universe, body_id: UniverseId, elements: OrbitalElements) {
+    if let Err(reason) = try_apply_elements(universe, body_id, elements) {
+        notify(
+            Severity::Warning,
+            format!("Couldn't apply the elements: {reason}"),
+        );
+    }
+}
+
+fn try_apply_elements(
+    universe: &mut Universe,
+    body_id: UniverseId,
+    elements: OrbitalElements,
+) -> Result<(), &'static str> {
+    if !elements.is_finite() {
+        return Err("every element must be a finite number.");
+    }
+    if elements.periapsis <= 0.0 {
+        return Err("the periapsis must be above 0.");
+    }
+    if elements.eccentricity < 0.0 {
+        return Err("the eccentricity can't be negative.");
+    }
+
+    let wrapper = universe
+        .get_body(body_id)
+        .ok_or("the body no longer exists.")?;
+    let parent_id = wrapper
+        .relations
+        .parent
+        .ok_or("the body doesn't orbit anything.")?;
+    if wrapper.body.orbit.is_none() {
+        return Err("the body has no orbit to replace.");
+    }
+    let mu = universe
+        .get_body_gravitational_parameter(parent_id)
+        .ok_or("the body's parent no longer exists.")?;
+
+    if let Some(orbit) = universe
+        .get_body_mut(body_id)
+        .and_then(|w| w.body.orbit.as_mut())
+    {
+        *orbit = elements.to_orbit(mu);
+        metrics::record_use(Feature::PasteElements);
+    }
+    Ok(())
+}
+
+fn fit_tool_contents(
+    ui: &mut Ui,
+    universe: &mut Universe,
+    body_id: UniverseId,
+    window_state: &mut EditBodyWindowState,
+) {
+    ui.label(
+        "Paste positions relative to the parent, one \"time, x, y, z\" per line, \
+        in seconds and meters.",
+    );
+
+    let text_edit = TextEdit::multiline(&mut window_state.fit_samples)
+        .font(TextStyle::Monospace)
+        .desired_rows(4)
+        .desired_width(f32::INFINITY)
+        .hint_text("0, 7000000, 0, 0");
+    ui.add(text_edit);
+
+    if window_state
+        .fit_task
+        .as_ref()
+        .is_some_and(|(id, _)| *id == body_id)
+    {
+        ui.horizontal(|ui| {
+            ui.spinner();
+            ui.label("Fitting...");
+            if ui.button("Cancel").clicked() {
+                window_state.fit_task = None;
+            }
+        });
+        ui.ctx().request_repaint();
+        return;
+    }
+
+    let fit_button = ui.button("Fit orbit").on_help_text(
+        RichText::new(
+            "Replace this body's orbit with the one that best matches the samples.\n\
+            At least three samples are needed, and more make for a better fit.",
+        )
+        .color(Color32::WHITE)
+        .text_style(TextRole::Body.into()),
+    );
+
+    if fit_button.clicked()
+        && let Some(orbit) = universe
+            .get_body(body_id)
+            .and_then(|w| w.body.orbit.as_ref())
+    {
+        let mu = orbit.get_gravitational_parameter();
+        let job =
+            fit::parse_csv(&window_state.fit_samples).and_then(|samples| FitJob::new(&samples, mu));
+
+        match job {
+            Ok(job) => {
+                metrics::record_use(Feature::FitOrbit);
+                window_state.fit_task = Some((body_id, compute::spawn(job)));
+                window_state.fit_result = None;
+            }
+            Err(e) => window_state.fit_result = Some((body_id, Err(e))),
+        }
+    }
+
+    match window_state.fit_result {
+        Some((id, Ok(rms_error))) if id == body_id => {
+            ui.label(format!(
+                "Fitted, with an RMS error of {} m",
+                PrettyPrintFloat(rms_error)
+            ));
+        }
+        Some((id, Err(e))) if id == body_id => {
+            ui.label(RichText::new(format!("⚠ Can't fit: {e}")).color(Color32::YELLOW));
+        }
+        _ => (),
+    }
+}
+
+/// Applies the background orbit fit once it's done,
+/// even if the window was closed in the meantime.
+fn poll_fit_task(universe: &mut Universe, window_state: &mut EditBodyWindowState) {
+    let Some((body_id, task)) = &window_state.fit_task else {
+        return;
+    };
+    let body_id = *body_id;
+
+    let result = match task.poll() {
+        TaskStatus::Running => return,
+        TaskStatus::Done(result) => result,
+        TaskStatus::Failed => {
+            notify(Severity::Error, "Orbit fitting stopped unexpectedly.");
+            window_state.fit_task = None;
+            return;
+        }
+    };
+    window_state.fit_task = None;
+
+    let Some(orbit) = universe
+        .get_body_mut(body_id)
+        .and_then(|w| w.body.orbit.as_mut())
+    else {
+        return;
+    };
+
+    let mu = orbit.get_gravitational_parameter();
+    let result = result.map(|fit| {
+        *orbit = fit.elements.to_orbit(mu);
+        fit.rms_error
+    });
+    window_state.fit_result = Some((body_id, result));
+}
+
+/// Lets the user pick an orbital period for the body, and computes the
+/// parent mass (or gravitational constant) that would result in it.
+fn period_tool_contents(
+    ui: &mut Ui,
+    universe: &mut Universe,
+    body_id: UniverseId,
+    window_state: &mut EditBodyWindowState,
+) {
+    let Some(wrapper) = universe.get_body(body_id) else {
+        return;
+    };
+    let (Some(orbit), Some(parent_id)) = (&wrapper.body.orbit, wrapper.relations.parent) else {
+        return;
+    };
+    let Some(parent) = universe.get_body(parent_id) else {
+        return;
+    };
+
+    if orbit.get_eccentricity() >= 1.0 {
+        ui.label("Open (hyperbolic) orbits do not have a period.");
+        window_state.desired_period = None;
+        return;
+    }
+
+    // µ = 4π²a³ / T²
+    let semi_major_axis = orbit.get_semi_major_axis();
+    let current_period = orbit.get_orbital_period();
+    let parent_mass = parent.body.mass;
+    let g = universe.get_local_gravitational_constant(parent_id);
+    // The parent and its ancestors' G multipliers
+    let g_multiplier = g / universe.get_gravitational_constant();
+
+    let mut desired_period = window_state
+        .desired_period
+        .filter(|(id, _)| *id == body_id)
+        .map(|(_, period)| period)
+        .unwrap_or(current_period);
+
+    let mut new_parent_mass = None;
+    let mut new_g = None;
+
+    Grid::new(EDIT_BODY_PERIOD_TOOL_GRID_SALT)
+        .num_columns(2)
+        .spacing([40.0, 4.0])
+        .striped(true)
+        .show(ui, |ui| {
+            ui.label("Desired period")
+                .on_help_text(
+                    RichText::new(
+                        "The orbital period you want this body to have, \
+                        keeping its current semi-major axis.",
+                    )
+                    .color(Color32::WHITE)
+                    .text_style(TextRole::Body.into()),
+                )
+                .on_hover_cursor(CursorIcon::Help);
+            drag_value_with_unit(
+                EDIT_BODY_DESIRED_PERIOD_SALT,
+                ui,
+                &mut desired_period,
+                &mut window_state.period_unit,
+            );
+            ui.end_row();
+
+            let mu = 4.0 * core::f64::consts::PI.powi(2) * semi_major_axis.powi(3)
+                / desired_period.powi(2);
+            let required_mass = mu / g;
+            let required_g = mu / parent_mass / g_multiplier;
+
+            ui.label("Parent mass")
+                .on_help_text(
+                    RichText::new(format!(
+                        "The mass {} needs for this period.\n\
+                        Applying this also affects every other body orbiting it.",
+                        parent.body.name
+                    ))
+                    .color(Color32::WHITE)
+                    .text_style(TextRole::Body.into()),
+                )
+                .on_hover_cursor(CursorIcon::Help);
+            let button = Button::new(format!("{:.6} kg", PrettyPrintFloat(required_mass)))
+                .right_text("Apply");
+            if ui.add(button).clicked() {
+                new_parent_mass = Some(required_mass);
+            }
+            ui.end_row();
+
+            ui.label("Gravity multi.")
+                .on_help_text(
+                    RichText::new(
+                        "The gravitational constant needed for this period.\n\
+                        Applying this affects every orbit in the universe.",
+                    )
+                    .color(Color32::WHITE)
+                    .text_style(TextRole::Body.into()),
+                )
+                .on_hover_cursor(CursorIcon::Help);
+            let button =
+                Button::new(format!("{:.6}", PrettyPrintFloat(required_g))).right_text("Apply");
+            if ui.add(button).clicked() {
+                new_g = Some(required_g);
+            }
+            ui.end_row();
+        });
+
+    window_state.desired_period = Some((body_id, desired_period));
+
+    // Keep the elements so that the semi-major axis (and thus the
+    // resulting period) stays as the user expects.
+    let mode = BulkMuSetterMode::KeepElements;
+
+    if let Some(mass) = new_parent_mass
+        && mass.is_finite()
+        && mass > 0.0
+    {
+        if let Some(parent) = universe.get_body_mut(parent_id) {
+            parent.body.mass = mass;
+        }
+        let _ = universe.update_children_gravitational_parameters(parent_id, mode);
+    }
+
+    if let Some(g) = new_g
+        && g.is_finite()
+        && g > 0.0
+    {
+        universe.set_gravitational_constant(g, mode);
+    }
+}
+
+/// Returns the picked parent ID, which is `pending_parent` if one
+/// was picked before and the current parent otherwise.
+fn parent_selector(
+    ui: &mut Ui,
+    universe: &Universe,
+    wrapper: &BodyWrapper,
+    body_id: UniverseId,
+    pending_parent: Option<UniverseId>,
+) -> Option<UniverseId> {
+    ui.label("Parent body")
+        .on_help_text(
+            RichText::new("The body that this body is orbiting around.")
+                .color(Color32::WHITE)
+                .text_style(TextRole::Body.into()),
+        )
+        .on_hover_cursor(CursorIcon::Help);
+    let mut parent_id = pending_parent.or(wrapper.relations.parent);
+    ComboBox::from_id_salt(EDIT_BODY_PARENT_COMBO_BOX_SALT)
+        .close_behavior(PopupCloseBehavior::CloseOnClickOutside)
+        .wrap_mode(TextWrapMode::Extend)
+        .selected_text(
+            parent_id
+                .and_then(|parent_id| universe.get_body(parent_id))
+                .map(|w| &*w.body.name)
+                .unwrap_or("—"),
+        )
+        .show_ui(ui, |ui| {
+            let disallowed_set: HashSet<UniverseId> = universe
+                .get_bodies()
+                .keys()
+                .copied()
+                .filter(|&id| universe.would_create_loop(body_id, id))
+                .collect();
+            selectable_body_tree(
+                ui,
+                *EDIT_BODY_PARENT_TREE_ID,
+                universe,
+                &mut parent_id,
+                Some(DisallowedData {
+                    disallowed_set: &disallowed_set,
+                    reason: &RichText::new("cannot set new parent to self or own descendant")
+                        .color(Color32::LIGHT_RED),
+                }),
+            );
+        });
+    ui.end_row();
+
+    parent_id
+}
+
+/// Moves the body to `parent_id`, fading its old orbit line out
+/// and noting the move in the event log.
+fn move_body(
+    universe: &mut Universe,
+    body_id: UniverseId,
+    parent_id: UniverseId,
+    window_state: &mut EditBodyWindowState,
+    mu_mode: BulkMuSetterMode,
+) {
+    let Some(wrapper) = universe.get_body(body_id) else {
+        return;
+    };
+    let old = PreviewBody {
+        body: wrapper.body.clone(),
+        parent_id: wrapper.relations.parent,
+    };
+    let body_name = |id: Option<UniverseId>| {
+        id.and_then(|id| universe.get_body(id))
+            .map(|w| w.body.name.clone())
+            .unwrap_or_else(|| String::from("nothing"))
+    };
+    let old_parent_name = body_name(old.parent_id);
+    let new_parent_name = body_name(Some(parent_id));
+
+    if let Err(e) = universe.move_body(body_id, Some(parent_id), mu_mode) {
+        notify(Severity::Warning, format!("Couldn't move the body: {e}"));
+        return;
+    }
+
+    let kept = match mu_mode {
+        BulkMuSetterMode::KeepElements => "its orbital elements",
+        BulkMuSetterMode::KeepPosition => "its position",
+        BulkMuSetterMode::KeepStateVectors => "its position and velocity",
+    };
+    notify(
+        Severity::Info,
+        format!(
+            "Moved {} from {old_parent_name} to {new_parent_name}, keeping {kept}.",
+            old.body.name
+        ),
+    );
+    window_state.reparent_fade = Some(ReparentFade {
+        body_id,
+        old,
+        progress: 0.0,
+    });
+}
+
+/// Confirms or cancels moving the body to the parent picked for it,
+/// while its orbit around the new parent is previewed.
+fn pending_move_row(
+    ui: &mut Ui,
+    universe: &mut Universe,
+    body_id: UniverseId,
+    parent_id: UniverseId,
+    window_state: &mut EditBodyWindowState,
+    mu_mode: BulkMuSetterMode,
+) {
+    ui.label("Pending move")
+        .on_help_text(
+            RichText::new(
+                "The orbit this body would have around the new parent is shown as a preview.\n\
+                The move only happens once it's confirmed.",
+            )
+            .color(Color32::WHITE)
+            .text_style(TextRole::Body.into()),
+        )
+        .on_hover_cursor(CursorIcon::Help);
+    ui.horizontal(|ui| {
+        if ui.button("Move").clicked() {
+            window_state.pending_parent = None;
+            move_body(universe, body_id, parent_id, window_state, mu_mode);
+        }
+        if ui.button("Cancel").clicked() {
+            window_state.pending_parent = None;
+        }
+    });
+    ui.end_row();
 }
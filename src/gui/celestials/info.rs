@@ -1,72 +1,356 @@
 use std::sync::Arc;
 
-use crate::sim::{
-    body::Body,
-    universe::{Id as UniverseId, Universe},
+use crate::{
+    cfg::{CONFIG, DEFAULT_INFO_REFRESH_INTERVAL_MS},
+    gfx::palette,
+    gui::{altitude, declare_id, help::HelpText, text_style::TextRole},
+    sim::{
+        body::Body,
+        universe::{Id as UniverseId, Universe},
+    },
+    units::time::TimeDisplayMode,
 };
 
 use float_pretty_print::PrettyPrintFloat;
 use keplerian_sim::OrbitTrait;
-use three_d::egui::{Align, Color32, CursorIcon, Label, Layout, RichText, Sense, Ui, WidgetText};
+use three_d::egui::{
+    Align, Color32, CursorIcon, Grid, Label, Layout, RichText, Sense, Ui, Vec2, WidgetText,
+};
+
+declare_id!(salt_only, BODY_HOVER_CARD_GRID, b"HovrCard");
+
+#[derive(Clone, Copy)]
+struct InfoRow {
+    measurement: &'static str,
+    value: f64,
+    unit: &'static str,
+    hover: &'static str,
+}
+
+/// The inputs the derived info depends on, other than the time.
+#[derive(Clone, PartialEq)]
+struct InfoInputs {
+    body: Body,
+    parent_id: Option<UniverseId>,
+    parent_mass: Option<f64>,
+    gravitational_constant: f64,
+    /// The parent's radius, while altitudes are shown above its surface.
+    surface_radius: Option<f64>,
+}
+
+/// The last computed rows of the derived info grid.
+///
+/// The rows are recomputed when the body, its parent, or G changes,
+/// or once the refresh interval has passed so time-dependent rows stay current.
+#[derive(Default)]
+pub(super) struct InfoCache {
+    rows: Vec<InfoRow>,
+    inputs: Option<InfoInputs>,
+    /// The egui time the rows were computed at, in seconds.
+    computed_at: f64,
+}
+
+impl InfoCache {
+    /// Returns whether or not the rows need to be recomputed.
+    fn is_stale(&self, inputs: &InfoInputs, now: f64, interval: f64) -> bool {
+        self.inputs.as_ref() != Some(inputs) || now - self.computed_at >= interval
+    }
+
+    /// Recomputes the rows if they're stale.
+    ///
+    /// See [`body_window_info`] for what the parameters mean.
+    pub(super) fn refresh(
+        &mut self,
+        ui: &Ui,
+        body: &Body,
+        parent_id: Option<UniverseId>,
+        universe: &Universe,
+        gravitational_constant: f64,
+        time: f64,
+    ) {
+        let inputs = InfoInputs {
+            body: body.clone(),
+            parent_id,
+            parent_mass: parent_id
+                .and_then(|id| universe.get_body(id))
+                .map(|w| w.body.mass),
+            gravitational_constant,
+            surface_radius: altitude::surface_radius(universe, parent_id),
+        };
+        let now = ui.input(|i| i.time);
+        let interval = CONFIG
+            .try_lock()
+            .map(|cfg| cfg.info_refresh_interval_ms.get())
+            .unwrap_or(DEFAULT_INFO_REFRESH_INTERVAL_MS) as f64
+            / 1000.0;
+
+        if self.is_stale(&inputs, now, interval) {
+            self.rows.clear();
+            compute_rows(
+                &mut self.rows,
+                body,
+                parent_id,
+                universe,
+                gravitational_constant,
+                inputs.surface_radius,
+                time,
+            );
+            self.inputs = Some(inputs);
+            self.computed_at = now;
+        }
+    }
+}
 
+/// Draws the derived info rows of a body into a grid.
+///
+/// Callers should only call this when the grid is visible,
+/// such as inside an open collapsing section,
+/// so that nothing is computed while it is hidden.
+///
+/// `gravitational_constant`: The G this body's satellites feel,
+/// which differs from the universe's G under a G multiplier.
+///
+/// `time`: The time to evaluate the orbit at,
+/// which differs from the universe's time for frozen bodies.
 pub(super) fn body_window_info(
     ui: &mut Ui,
     body: &Body,
     parent_id: Option<UniverseId>,
     universe: &Universe,
+    gravitational_constant: f64,
+    time: f64,
+    cache: &mut InfoCache,
 ) {
     ui.visuals_mut().override_text_color = Some(Color32::WHITE);
-    let mu = body.mass * universe.get_gravitational_constant();
-
-    fn add_value(ui: &mut Ui, text: impl Into<WidgetText>, hover: Arc<RichText>) {
-        ui.allocate_ui_with_layout(
-            ui.available_size(),
-            Layout::right_to_left(Align::Center),
-            |ui| {
-                ui.add_space(ui.spacing().menu_spacing);
-                let label = Label::new(text);
-                ui.add(label)
-                    .on_hover_text(hover)
-                    .on_hover_cursor(CursorIcon::Help);
-            },
-        );
-    }
 
-    fn format_number(number: f64, suffix: &str) -> String {
-        let number = PrettyPrintFloat(number);
-        if suffix.is_empty() {
-            number.to_string()
-        } else {
-            format!("{number} {suffix}")
-        }
-    }
+    cache.refresh(ui, body, parent_id, universe, gravitational_constant, time);
 
-    fn add_row(ui: &mut Ui, measurement: &str, value: f64, unit: &str, hover: &str) {
-        let hover = RichText::new(hover.trim()).color(Color32::WHITE).size(16.0);
-        let hover = Arc::new(hover);
+    for row in &cache.rows {
+        let hover = row_hover(row);
 
         let label = ui
-            .label(measurement)
-            .on_hover_text(Arc::clone(&hover))
+            .label(row.measurement)
+            .on_help_text(Arc::clone(&hover))
             .on_hover_cursor(CursorIcon::Help);
 
         let mut hitbox_rect = label.rect;
         hitbox_rect.set_width(hitbox_rect.width() + ui.available_width());
 
-        let value_text = format_number(value, unit);
+        let value_text = format_number(row.value, row.unit);
         add_value(ui, value_text, Arc::clone(&hover));
 
         ui.allocate_rect(hitbox_rect, Sense::HOVER)
-            .on_hover_text(hover)
+            .on_help_text(hover)
             .on_hover_cursor(CursorIcon::Help);
 
         ui.end_row();
     }
+}
+
+/// The derived info of a body as measurement names and formatted values,
+/// for text exports. The parameters are the same as [`body_window_info`]'s.
+pub(super) fn derived_rows(
+    body: &Body,
+    parent_id: Option<UniverseId>,
+    universe: &Universe,
+    gravitational_constant: f64,
+    time: f64,
+) -> Vec<(&'static str, String)> {
+    let mut rows = Vec::new();
+    compute_rows(
+        &mut rows,
+        body,
+        parent_id,
+        universe,
+        gravitational_constant,
+        altitude::surface_radius(universe, parent_id),
+        time,
+    );
+    rows.into_iter()
+        .map(|row| (row.measurement, format_number(row.value, row.unit)))
+        .collect()
+}
+
+/// Draws the derived info rows of two bodies next to each other,
+/// highlighting the values that differ between them.
+///
+/// The caches have to be refreshed with [`InfoCache::refresh`] first.
+/// Rows only one of the bodies has, like orbit rows next to a root body,
+/// are shown with a dash on the other side.
+pub(super) fn compared_info(ui: &mut Ui, caches: [&InfoCache; 2]) {
+    ui.visuals_mut().override_text_color = Some(Color32::WHITE);
+
+    let mut measurements: Vec<&InfoRow> = caches[0].rows.iter().collect();
+    for row in &caches[1].rows {
+        if measurements
+            .iter()
+            .all(|r| r.measurement != row.measurement)
+        {
+            measurements.push(row);
+        }
+    }
+
+    for template in measurements {
+        let hover = row_hover(template);
+        ui.label(template.measurement)
+            .on_help_text(Arc::clone(&hover))
+            .on_hover_cursor(CursorIcon::Help);
+
+        let rows = caches.map(|cache| {
+            cache
+                .rows
+                .iter()
+                .find(|r| r.measurement == template.measurement)
+        });
+        let differs = match rows {
+            [Some(a), Some(b)] => !values_match(a.value, b.value),
+            _ => true,
+        };
+
+        for row in rows {
+            let text = match row {
+                Some(row) => format_number(row.value, row.unit),
+                None => String::from("—"),
+            };
+            let text = if differs {
+                RichText::new(text).color(COMPARE_DIFFERENCE_COLOR)
+            } else {
+                RichText::new(text)
+            };
+            ui.label(text)
+                .on_help_text(Arc::clone(&hover))
+                .on_hover_cursor(CursorIcon::Help);
+        }
+
+        ui.end_row();
+    }
+}
+
+/// The color of compared values that differ between the two bodies.
+const COMPARE_DIFFERENCE_COLOR: Color32 = Color32::from_rgb(255, 210, 90);
+
+/// Whether two compared values are the same, ignoring rounding errors.
+fn values_match(a: f64, b: f64) -> bool {
+    a == b || (a - b).abs() <= 1e-9 * a.abs().max(b.abs())
+}
+
+fn row_hover(row: &InfoRow) -> Arc<RichText> {
+    let hover = RichText::new(row.hover.trim())
+        .color(Color32::WHITE)
+        .text_style(TextRole::Body.into());
+    Arc::new(hover)
+}
+
+fn add_value(ui: &mut Ui, text: impl Into<WidgetText>, hover: Arc<RichText>) {
+    ui.allocate_ui_with_layout(
+        ui.available_size(),
+        Layout::right_to_left(Align::Center),
+        |ui| {
+            ui.add_space(ui.spacing().menu_spacing);
+            let label = Label::new(text);
+            ui.add(label)
+                .on_help_text(hover)
+                .on_hover_cursor(CursorIcon::Help);
+        },
+    );
+}
+
+fn format_number(number: f64, suffix: &str) -> String {
+    let number = PrettyPrintFloat(number);
+    if suffix.is_empty() {
+        number.to_string()
+    } else {
+        format!("{number} {suffix}")
+    }
+}
+
+/// Draws a short summary of a body, for showing on hover
+/// without opening the edit window.
+pub(super) fn body_hover_card(ui: &mut Ui, universe: &Universe, id: UniverseId) {
+    let Some(wrapper) = universe.get_body(id) else {
+        return;
+    };
+    let body = &wrapper.body;
+
+    ui.visuals_mut().override_text_color = Some(Color32::WHITE);
+
+    ui.horizontal(|ui| {
+        let c = palette::display_color(universe, id).unwrap_or(body.color);
+        let (rect, _) = ui.allocate_exact_size(Vec2::splat(16.0), Sense::hover());
+        ui.painter()
+            .rect_filled(rect, 4.0, Color32::from_rgb(c.r, c.g, c.b));
+        ui.label(
+            RichText::new(&body.name)
+                .strong()
+                .text_style(TextRole::Body.into()),
+        );
+    });
+
+    Grid::new(BODY_HOVER_CARD_GRID_SALT)
+        .num_columns(2)
+        .spacing([40.0, 4.0])
+        .striped(true)
+        .show(ui, |ui| {
+            ui.label("Mass");
+            ui.label(format_number(body.mass, "kg"));
+            ui.end_row();
+
+            ui.label("Radius");
+            ui.label(format_number(body.radius, "m"));
+            ui.end_row();
+
+            if let Some(orbit) = &body.orbit
+                && wrapper.relations.parent.is_some()
+            {
+                ui.label("Semi-major axis");
+                ui.label(format_number(orbit.get_semi_major_axis(), "m"));
+                ui.end_row();
+
+                ui.label("Period");
+                if orbit.is_closed() {
+                    ui.label(TimeDisplayMode::MultiUnit.format_time(orbit.get_orbital_period()));
+                } else {
+                    ui.label("Open orbit");
+                }
+                ui.end_row();
+            }
+
+            ui.label("Satellites");
+            ui.label(wrapper.relations.satellites.len().to_string());
+            ui.end_row();
+        });
+}
+
+fn compute_rows(
+    rows: &mut Vec<InfoRow>,
+    body: &Body,
+    parent_id: Option<UniverseId>,
+    universe: &Universe,
+    gravitational_constant: f64,
+    surface_radius: Option<f64>,
+    time: f64,
+) {
+    let mu = body.mass * gravitational_constant;
+
+    fn add_row(
+        rows: &mut Vec<InfoRow>,
+        measurement: &'static str,
+        value: f64,
+        unit: &'static str,
+        hover: &'static str,
+    ) {
+        rows.push(InfoRow {
+            measurement,
+            value,
+            unit,
+            hover,
+        });
+    }
 
     use core::f64::consts::{PI, TAU};
 
     add_row(
-        ui,
+        rows,
         "Circumference",
         2.0 * PI * body.radius,
         "m",
@@ -74,7 +358,7 @@ pub(super) fn body_window_info(
     );
 
     add_row(
-        ui,
+        rows,
         "Surface area",
         4.0 * PI * body.radius.powi(2),
         "m^2",
@@ -82,7 +366,7 @@ pub(super) fn body_window_info(
     );
 
     add_row(
-        ui,
+        rows,
         "Volume",
         4.0 / 3.0 * PI * body.radius.powi(3),
         "m^3",
@@ -90,7 +374,7 @@ pub(super) fn body_window_info(
     );
 
     add_row(
-        ui,
+        rows,
         "Density",
         body.mass / (4.0 / 3.0 * PI * body.radius.powi(3)),
         "kg/m^3",
@@ -98,7 +382,7 @@ pub(super) fn body_window_info(
     );
 
     add_row(
-        ui,
+        rows,
         "Ideal surface gravity",
         mu / body.radius.powi(2),
         "m/s^2",
@@ -106,7 +390,7 @@ pub(super) fn body_window_info(
     );
 
     add_row(
-        ui,
+        rows,
         "Gravitational parameter",
         mu,
         "m^3 s^-2",
@@ -114,7 +398,7 @@ pub(super) fn body_window_info(
     );
 
     add_row(
-        ui,
+        rows,
         "Escape velocity",
         (2.0 * mu / body.radius).sqrt(),
         "m/s",
@@ -126,16 +410,25 @@ pub(super) fn body_window_info(
         None => return,
     };
 
-    add_row(
-        ui,
-        "Apoapsis",
-        orbit.get_apoapsis(),
-        "m",
-        include_str!("row_descs/apoapsis.txt"),
-    );
+    match surface_radius {
+        Some(radius) => add_row(
+            rows,
+            "Apoapsis altitude",
+            orbit.get_apoapsis() - radius,
+            "m",
+            include_str!("row_descs/apoapsis_altitude.txt"),
+        ),
+        None => add_row(
+            rows,
+            "Apoapsis",
+            orbit.get_apoapsis(),
+            "m",
+            include_str!("row_descs/apoapsis.txt"),
+        ),
+    }
 
     add_row(
-        ui,
+        rows,
         "Semi-major axis",
         orbit.get_semi_major_axis(),
         "m",
@@ -143,7 +436,7 @@ pub(super) fn body_window_info(
     );
 
     add_row(
-        ui,
+        rows,
         "Semi-minor axis",
         orbit.get_semi_minor_axis(),
         "m",
@@ -151,7 +444,7 @@ pub(super) fn body_window_info(
     );
 
     add_row(
-        ui,
+        rows,
         "Linear eccentricity",
         orbit.get_linear_eccentricity(),
         "m",
@@ -159,7 +452,7 @@ pub(super) fn body_window_info(
     );
 
     add_row(
-        ui,
+        rows,
         "Semi-latus rectum",
         orbit.get_semi_latus_rectum(),
         "m",
@@ -170,7 +463,7 @@ pub(super) fn body_window_info(
 
     if orbit.get_eccentricity() <= 1.0 {
         add_row(
-            ui,
+            rows,
             "Orbital period",
             period,
             "s",
@@ -190,7 +483,7 @@ pub(super) fn body_window_info(
         include_str!("row_descs/mean_anomaly.hyperbolic.txt")
     };
 
-    let mean_anomaly = orbit.get_mean_anomaly_at_time(universe.time);
+    let mean_anomaly = orbit.get_mean_anomaly_at_time(time);
 
     let mean_anomaly = if orbit.get_eccentricity() < 1.0 {
         mean_anomaly.rem_euclid(TAU)
@@ -198,7 +491,7 @@ pub(super) fn body_window_info(
         mean_anomaly
     };
 
-    add_row(ui, measurement, mean_anomaly, "rad", &hover);
+    add_row(rows, measurement, mean_anomaly, "rad", hover);
 
     let measurement = if orbit.get_eccentricity() < 1.0 {
         "Curr. ecc. anomaly"
@@ -214,12 +507,12 @@ pub(super) fn body_window_info(
 
     let eccentric_anomaly = orbit.get_eccentric_anomaly_at_mean_anomaly(mean_anomaly);
 
-    add_row(ui, measurement, eccentric_anomaly, "rad", hover);
+    add_row(rows, measurement, eccentric_anomaly, "rad", hover);
 
     let true_anomaly = orbit.get_true_anomaly_at_eccentric_anomaly(eccentric_anomaly);
 
     add_row(
-        ui,
+        rows,
         "Curr. true anomaly",
         true_anomaly,
         "rad",
@@ -228,18 +521,27 @@ pub(super) fn body_window_info(
 
     let altitude = orbit.get_altitude_at_true_anomaly(true_anomaly);
 
-    add_row(
-        ui,
-        "Curr. altitude",
-        altitude,
-        "m",
-        include_str!("row_descs/altitude.txt"),
-    );
+    match surface_radius {
+        Some(radius) => add_row(
+            rows,
+            "Curr. surface altitude",
+            altitude - radius,
+            "m",
+            include_str!("row_descs/surface_altitude.txt"),
+        ),
+        None => add_row(
+            rows,
+            "Curr. altitude",
+            altitude,
+            "m",
+            include_str!("row_descs/altitude.txt"),
+        ),
+    }
 
     let speed = orbit.get_speed_at_altitude(altitude);
 
     add_row(
-        ui,
+        rows,
         "Curr. speed",
         speed,
         "m/s",
@@ -251,7 +553,7 @@ pub(super) fn body_window_info(
     let pqw_position = orbit.get_pqw_position_at_true_anomaly_unchecked(altitude, true_sincos);
 
     add_row(
-        ui,
+        rows,
         "Curr. PQW pos P",
         pqw_position.x,
         "m",
@@ -259,7 +561,7 @@ pub(super) fn body_window_info(
     );
 
     add_row(
-        ui,
+        rows,
         "Curr. PQW pos Q",
         pqw_position.y,
         "m",
@@ -269,7 +571,7 @@ pub(super) fn body_window_info(
     let pqw_velocity = orbit.get_pqw_velocity_at_eccentric_anomaly(eccentric_anomaly);
 
     add_row(
-        ui,
+        rows,
         "Curr. PQW vel P",
         pqw_velocity.x,
         "m/s",
@@ -277,7 +579,7 @@ pub(super) fn body_window_info(
     );
 
     add_row(
-        ui,
+        rows,
         "Curr. PQW vel Q",
         pqw_velocity.y,
         "m/s",
@@ -288,21 +590,21 @@ pub(super) fn body_window_info(
     let velocity = orbit.transform_pqw_vector(pqw_velocity);
 
     add_row(
-        ui,
+        rows,
         "Curr. pos X",
         position.x,
         "m",
         include_str!("row_descs/cur_pos_x.txt"),
     );
     add_row(
-        ui,
+        rows,
         "Curr. pos Y",
         position.y,
         "m",
         include_str!("row_descs/cur_pos_y.txt"),
     );
     add_row(
-        ui,
+        rows,
         "Curr. pos Z",
         position.z,
         "m",
@@ -310,21 +612,21 @@ pub(super) fn body_window_info(
     );
 
     add_row(
-        ui,
+        rows,
         "Curr. vel X",
         velocity.x,
         "m/s",
         include_str!("row_descs/cur_vel_x.txt"),
     );
     add_row(
-        ui,
+        rows,
         "Curr. vel Y",
         velocity.y,
         "m/s",
         include_str!("row_descs/cur_vel_y.txt"),
     );
     add_row(
-        ui,
+        rows,
         "Curr. vel Z",
         velocity.z,
         "m/s",
@@ -334,7 +636,7 @@ pub(super) fn body_window_info(
     let f_asympt = orbit.get_true_anomaly_at_asymptote();
     if orbit.is_hyperbolic() {
         add_row(
-            ui,
+            rows,
             "True anom. asymptote",
             f_asympt,
             "rad",
@@ -345,7 +647,7 @@ pub(super) fn body_window_info(
     let longitude_of_periapsis = orbit.get_longitude_of_periapsis();
 
     add_row(
-        ui,
+        rows,
         "Longitude of periapsis",
         longitude_of_periapsis,
         "rad",
@@ -353,7 +655,7 @@ pub(super) fn body_window_info(
     );
 
     add_row(
-        ui,
+        rows,
         "Curr. true longitude",
         true_anomaly + longitude_of_periapsis,
         "rad",
@@ -373,32 +675,32 @@ pub(super) fn body_window_info(
 
         if orbit.is_open() {
             add_row(
-                ui,
+                rows,
                 "Time since SOI entry",
-                universe.time - entry_time,
+                time - entry_time,
                 "s",
                 include_str!("row_descs/soi_entry_time.txt"),
             );
             add_row(
-                ui,
+                rows,
                 "Time to SOI exit",
-                exit_time - universe.time,
+                exit_time - time,
                 "s",
                 include_str!("row_descs/soi_exit_time.txt"),
             );
         } else {
             add_row(
-                ui,
+                rows,
                 "Time since SOI entry",
-                (universe.time - entry_time).rem_euclid(period),
+                (time - entry_time).rem_euclid(period),
                 "s",
                 include_str!("row_descs/soi_entry_time.txt"),
             );
 
             add_row(
-                ui,
+                rows,
                 "Time to SOI exit",
-                (exit_time - universe.time).rem_euclid(period),
+                (exit_time - time).rem_euclid(period),
                 "s",
                 include_str!("row_descs/soi_exit_time.txt"),
             );
@@ -412,7 +714,7 @@ pub(super) fn body_window_info(
             orbit.get_semi_major_axis() * (body.mass / parent_wrapper.body.mass).powf(2.0 / 5.0);
 
         add_row(
-            ui,
+            rows,
             "SOI radius",
             soi_radius,
             "m",
@@ -427,17 +729,17 @@ pub(super) fn body_window_info(
     let t_dn = orbit.get_time_at_true_anomaly(f_dn);
 
     let (an_time_rel, dn_time_rel) = if orbit.is_open() {
-        (t_an - universe.time, t_dn - universe.time)
+        (t_an - time, t_dn - time)
     } else {
         (
-            (t_an - universe.time).rem_euclid(period),
-            (t_dn - universe.time).rem_euclid(period),
+            (t_an - time).rem_euclid(period),
+            (t_dn - time).rem_euclid(period),
         )
     };
 
     if orbit.is_closed() || f_an.abs() < f_asympt {
         add_row(
-            ui,
+            rows,
             "Time to AN",
             an_time_rel,
             "s",
@@ -446,7 +748,7 @@ pub(super) fn body_window_info(
     }
     if orbit.is_closed() || f_dn.abs() < f_asympt {
         add_row(
-            ui,
+            rows,
             "Time to DN",
             dn_time_rel,
             "s",
@@ -455,7 +757,7 @@ pub(super) fn body_window_info(
     }
 
     add_row(
-        ui,
+        rows,
         "Mean motion",
         orbit.get_mean_motion(),
         "rad/s",
@@ -463,7 +765,7 @@ pub(super) fn body_window_info(
     );
 
     add_row(
-        ui,
+        rows,
         "Periapsis speed",
         orbit.get_speed_at_periapsis(),
         "m/s",
@@ -472,7 +774,7 @@ pub(super) fn body_window_info(
 
     if orbit.is_closed() {
         add_row(
-            ui,
+            rows,
             "Apoapsis speed",
             orbit.get_speed_at_apoapsis(),
             "m/s",
@@ -480,7 +782,7 @@ pub(super) fn body_window_info(
         );
     } else {
         add_row(
-            ui,
+            rows,
             "Asymptote speed",
             orbit.get_speed_at_infinity(),
             "m/s",
@@ -490,13 +792,13 @@ pub(super) fn body_window_info(
 
     let periapsis_time = orbit.get_time_of_periapsis();
     let periapsis_time_rel = if orbit.is_open() {
-        periapsis_time - universe.time
+        periapsis_time - time
     } else {
-        (periapsis_time - universe.time).rem_euclid(period)
+        (periapsis_time - time).rem_euclid(period)
     };
 
     add_row(
-        ui,
+        rows,
         "Time to periapsis",
         periapsis_time_rel,
         "s",
@@ -505,10 +807,10 @@ pub(super) fn body_window_info(
 
     if orbit.is_closed() {
         let apoapsis_time = orbit.get_time_of_apoapsis();
-        let apoapsis_time_rel = apoapsis_time - universe.time;
+        let apoapsis_time_rel = apoapsis_time - time;
 
         add_row(
-            ui,
+            rows,
             "Time to apoapsis",
             apoapsis_time_rel,
             "s",
@@ -517,7 +819,7 @@ pub(super) fn body_window_info(
     }
 
     add_row(
-        ui,
+        rows,
         "Focal parameter",
         orbit.get_focal_parameter(),
         "",
@@ -525,7 +827,7 @@ pub(super) fn body_window_info(
     );
 
     add_row(
-        ui,
+        rows,
         "Spec. energy",
         orbit.get_specific_orbital_energy(),
         "J/kg",
@@ -533,7 +835,7 @@ pub(super) fn body_window_info(
     );
 
     add_row(
-        ui,
+        rows,
         "Ang. momentum",
         orbit.get_specific_angular_momentum(),
         "m^2/s",
@@ -541,7 +843,7 @@ pub(super) fn body_window_info(
     );
 
     add_row(
-        ui,
+        rows,
         "Area sweep rate",
         orbit.get_area_sweep_rate(),
         "m^2/s",
@@ -1,28 +1,107 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, sync::Mutex};
 
 use crate::{
     cfg::CONFIG,
+    gfx::palette,
     gui::{
-        PreviewBody, SimState,
-        celestials::{RENAME_TEXTEDIT_ID, selectable_body_button},
+        SimState,
+        celestials::{
+            RENAME_TEXTEDIT_ID, info::body_hover_card, radial::RadialMenuState,
+            selectable_body_button,
+        },
         declare_id,
+        help::HelpText,
+        layout::{self, DockableWindow},
+        new_body_preview,
+        text_style::TextRole,
     },
-    sim::{body::Body, universe::Id as UniverseId},
+    metrics::{self, Feature},
+    sim::universe::{Id as UniverseId, Universe},
 };
 use glam::DVec3;
-use keplerian_sim::Orbit;
-use three_d::{
-    Srgba,
-    egui::{
-        Button, Color32, Context, Id as EguiId, IntoAtoms, Key, Popup, Response, TextWrapMode, Ui,
-        Window,
-        collapsing_header::CollapsingState,
-        text::{CCursor, CCursorRange},
-        text_edit::TextEditState,
-    },
+use keplerian_sim::OrbitTrait;
+use strum::IntoEnumIterator;
+use strum_macros::EnumIter;
+use three_d::egui::{
+    Button, Color32, ComboBox, Context, Id as EguiId, IntoAtoms, Key, Popup, Response, RichText,
+    ScrollArea, TextStyle, TextWrapMode, Ui, Window,
+    collapsing_header::{CollapsingState, paint_default_icon},
+    text::{CCursor, CCursorRange},
+    text_edit::TextEditState,
 };
 
 declare_id!(BODY_PREFIX, b"Planets!");
+declare_id!(salt_only, SORT_MODE_COMBO_BOX, b"SortBy:)");
+
+/// Bodies copied from the body list, stored as a universe of their own.
+///
+/// This is shared between all tabs, so bodies can be copied across universes.
+static BODY_CLIPBOARD: Mutex<Option<Universe>> = Mutex::new(None);
+
+/// How the satellites of each body are ordered in the list.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, EnumIter)]
+enum BodySortMode {
+    #[default]
+    Manual,
+    SemiMajorAxis,
+    Mass,
+    Name,
+}
+
+impl BodySortMode {
+    const fn name(self) -> &'static str {
+        match self {
+            BodySortMode::Manual => "Manual",
+            BodySortMode::SemiMajorAxis => "Distance",
+            BodySortMode::Mass => "Mass",
+            BodySortMode::Name => "Name",
+        }
+    }
+
+    const fn description(self) -> &'static str {
+        match self {
+            BodySortMode::Manual => "The order set using \"Move up\" and \"Move down\".",
+            BodySortMode::SemiMajorAxis => "Closest orbits (by semi-major axis) first.",
+            BodySortMode::Mass => "Heaviest bodies first.",
+            BodySortMode::Name => "Alphabetical order.",
+        }
+    }
+
+    fn from_index(index: u8) -> Self {
+        Self::iter().nth(index as usize).unwrap_or_default()
+    }
+
+    fn to_index(self) -> u8 {
+        Self::iter().position(|mode| mode == self).unwrap_or(0) as u8
+    }
+
+    /// Sorts the given sibling IDs in-place according to this mode.
+    fn sort(self, universe: &Universe, ids: &mut [UniverseId]) {
+        match self {
+            BodySortMode::Manual => (),
+            BodySortMode::SemiMajorAxis => ids.sort_by(|a, b| {
+                let sma = |id| {
+                    universe
+                        .get_body(id)
+                        .and_then(|w| w.body.orbit.as_ref())
+                        .map(|o| o.get_semi_major_axis().abs())
+                        .unwrap_or(0.0)
+                };
+                sma(*a).total_cmp(&sma(*b))
+            }),
+            BodySortMode::Mass => ids.sort_by(|a, b| {
+                let mass = |id| universe.get_body(id).map(|w| w.body.mass).unwrap_or(0.0);
+                mass(*b).total_cmp(&mass(*a))
+            }),
+            BodySortMode::Name => ids.sort_by_cached_key(|id| {
+                universe
+                    .get_body(*id)
+                    .map(|w| w.body.name.to_lowercase())
+                    .unwrap_or_default()
+            }),
+        }
+    }
+}
 
 pub(super) struct RenameState {
     pub universe_id: UniverseId,
@@ -33,8 +112,10 @@ pub(super) struct RenameState {
 pub(crate) struct BodyListWindowState {
     pub(in super::super) listed_body_with_popup: Option<UniverseId>,
     listed_body_with_rename: Option<RenameState>,
+    pub(super) radial_menu: Option<RadialMenuState>,
     pub(in super::super) window_open: bool,
     pub(crate) scroll_to_focused: bool,
+    sort_mode: BodySortMode,
     show_help: bool,
     dont_show_again: bool,
 }
@@ -44,8 +125,13 @@ impl Default for BodyListWindowState {
         Self {
             listed_body_with_popup: None,
             listed_body_with_rename: None,
+            radial_menu: None,
             window_open: true,
             scroll_to_focused: false,
+            sort_mode: CONFIG
+                .try_lock()
+                .map(|cfg| BodySortMode::from_index(cfg.body_list_sort_mode.get()))
+                .unwrap_or_default(),
             show_help: CONFIG
                 .try_lock()
                 .map(|cfg| cfg.show_body_list_help.get())
@@ -67,7 +153,11 @@ pub(super) fn body_tree_window(
 ) {
     let mut open = sim_state.ui.body_list_window_state.window_open;
 
-    let window = Window::new("Celestial Bodies").scroll(true).open(&mut open);
+    // The tree scrolls by itself, so that only the visible rows are laid out
+    let window = Window::new(DockableWindow::BodyList.title())
+        .scroll(false)
+        .open(&mut open);
+    let window = layout::place(ctx, sim_state, DockableWindow::BodyList, window);
 
     window.show(ctx, |ui| {
         ui.scope(|ui| {
@@ -87,7 +177,9 @@ fn body_tree_window_contents(
         show_help(ui, &mut sim_state.ui.body_list_window_state);
     }
 
-    let roots: Box<[UniverseId]> = sim_state
+    sort_toolbar(ui, &mut sim_state.ui.body_list_window_state);
+
+    let mut roots: Box<[UniverseId]> = sim_state
         .universe
         .get_bodies()
         .iter()
@@ -97,11 +189,151 @@ fn body_tree_window_contents(
         })
         .collect();
 
+    // Roots have no manual order, so sort them by ID for stability
+    roots.sort_unstable();
+    sim_state
+        .ui
+        .body_list_window_state
+        .sort_mode
+        .sort(&sim_state.universe, &mut roots);
+
+    let sort_mode = sim_state.ui.body_list_window_state.sort_mode;
+    let scroll_to_focused = sim_state.ui.body_list_window_state.scroll_to_focused;
+    if scroll_to_focused {
+        expand_ancestors(ui.ctx(), &sim_state.universe, sim_state.focused_body());
+    }
+
+    let mut rows = Vec::new();
     for universe_id in roots {
-        body_tree_node(ui, sim_state, universe_id, position_map);
+        push_visible_rows(
+            ui.ctx(),
+            &sim_state.universe,
+            sort_mode,
+            universe_id,
+            0,
+            &mut rows,
+        );
+    }
+
+    let row_height = BODY_TREE_ICON_SIZE.max(ui.text_style_height(&TextStyle::Body))
+        + 2.0 * ui.spacing().button_padding.y;
+    let mut scroll_area = ScrollArea::vertical().auto_shrink([false, true]);
+    if scroll_to_focused {
+        sim_state.ui.body_list_window_state.scroll_to_focused = false;
+        let focused = sim_state.focused_body();
+        if let Some(index) = rows.iter().position(|row| row.id == focused) {
+            let spacing = ui.spacing().item_spacing.y;
+            scroll_area = scroll_area.vertical_scroll_offset(index as f32 * (row_height + spacing));
+        }
+    }
+
+    scroll_area.show_rows(ui, row_height, rows.len(), |ui, range| {
+        for row in &rows[range] {
+            body_tree_row(ui, sim_state, row, position_map);
+        }
+    });
+}
+
+/// Parents with more satellites than this start out collapsed,
+/// so that huge systems aren't listed until asked for.
+const MAX_SATELLITES_OPEN_BY_DEFAULT: usize = 32;
+
+/// A body in the list, as it's shown.
+struct TreeRow {
+    id: UniverseId,
+    depth: usize,
+    satellite_count: usize,
+}
+
+fn collapsing_state(
+    ctx: &Context,
+    universe_id: UniverseId,
+    satellite_count: usize,
+) -> CollapsingState {
+    CollapsingState::load_with_default_open(
+        ctx,
+        get_body_egui_id(universe_id),
+        satellite_count <= MAX_SATELLITES_OPEN_BY_DEFAULT,
+    )
+}
+
+/// Lists a body and its satellites, in order, skipping collapsed subtrees.
+fn push_visible_rows(
+    ctx: &Context,
+    universe: &Universe,
+    sort_mode: BodySortMode,
+    universe_id: UniverseId,
+    depth: usize,
+    rows: &mut Vec<TreeRow>,
+) {
+    let Some(wrapper) = universe.get_body(universe_id) else {
+        return;
+    };
+    let satellite_count = wrapper.relations.satellites.len();
+    rows.push(TreeRow {
+        id: universe_id,
+        depth,
+        satellite_count,
+    });
+
+    if satellite_count == 0 || !collapsing_state(ctx, universe_id, satellite_count).is_open() {
+        return;
+    }
+
+    let mut satellites = wrapper.relations.satellites.clone();
+    sort_mode.sort(universe, &mut satellites);
+    for id in satellites {
+        push_visible_rows(ctx, universe, sort_mode, id, depth + 1, rows);
     }
 }
 
+/// Expands everything above a body, so that it's listed.
+fn expand_ancestors(ctx: &Context, universe: &Universe, universe_id: UniverseId) {
+    let mut parent = universe
+        .get_body(universe_id)
+        .and_then(|w| w.relations.parent);
+    // Bounded in case the hierarchy somehow has a loop
+    for _ in 0..universe.get_bodies().len() {
+        let Some(id) = parent else {
+            return;
+        };
+        let Some(wrapper) = universe.get_body(id) else {
+            return;
+        };
+        let mut state = collapsing_state(ctx, id, wrapper.relations.satellites.len());
+        state.set_open(true);
+        state.store(ctx);
+        parent = wrapper.relations.parent;
+    }
+}
+
+fn sort_toolbar(ui: &mut Ui, state: &mut BodyListWindowState) {
+    ui.horizontal(|ui| {
+        ui.label(RichText::new("Sort by").color(Color32::WHITE));
+        ComboBox::from_id_salt(SORT_MODE_COMBO_BOX_SALT)
+            .selected_text(state.sort_mode.name())
+            .show_ui(ui, |ui| {
+                ui.visuals_mut().override_text_color = Some(Color32::WHITE);
+                for mode in BodySortMode::iter() {
+                    let button = Button::selectable(state.sort_mode == mode, mode.name());
+                    let button = ui.add(button).on_help_text(
+                        RichText::new(mode.description())
+                            .color(Color32::WHITE)
+                            .text_style(TextRole::Body.into()),
+                    );
+
+                    if button.clicked() && state.sort_mode != mode {
+                        state.sort_mode = mode;
+                        let _ = CONFIG
+                            .try_lock()
+                            .map(|cfg| cfg.body_list_sort_mode.set(mode.to_index()));
+                    }
+                }
+            });
+    });
+    ui.separator();
+}
+
 fn show_help(ui: &mut Ui, state: &mut BodyListWindowState) {
     ui.visuals_mut().override_text_color = Some(Color32::WHITE);
     ui.heading("Help");
@@ -109,6 +341,7 @@ fn show_help(ui: &mut Ui, state: &mut BodyListWindowState) {
         "Click on a body in the list to focus/edit a body.\n\
         (You'll need to open the body editor window to edit the body.)\n\
         Right-click on them or click the \"...\" button to open the context menu.\n\
+        On touch screens, long-press them to open a radial menu instead.\n\
         Double-click them to rename, and click on the triangles to show/hide children from the list.",
     );
     let row = ui.horizontal(|ui| {
@@ -128,48 +361,44 @@ fn show_help(ui: &mut Ui, state: &mut BodyListWindowState) {
     ui.separator();
 }
 
-fn body_tree_node(
+fn body_tree_row(
     ui: &mut Ui,
     sim_state: &mut SimState,
-    universe_id: UniverseId,
+    row: &TreeRow,
     position_map: &HashMap<UniverseId, DVec3>,
 ) {
-    let satellites = match sim_state.universe.get_body(universe_id) {
-        Some(wrapper) => &wrapper.relations.satellites,
-        None => return,
-    };
+    ui.horizontal(|ui| {
+        ui.add_space(row.depth as f32 * ui.spacing().indent);
 
-    if satellites.is_empty() {
-        ui.indent((*BODY_PREFIX_ID, universe_id), |ui| {
-            body_tree_base_node(ui, sim_state, universe_id, position_map);
-        });
-    } else {
-        body_tree_parent_node(ui, sim_state, universe_id, position_map);
-    }
-}
+        if row.satellite_count == 0 {
+            ui.add_space(ui.spacing().icon_width + ui.spacing().item_spacing.x);
+            body_tree_base_node(ui, sim_state, row.id, position_map);
+            return;
+        }
 
-fn body_tree_parent_node(
-    ui: &mut Ui,
-    sim_state: &mut SimState,
-    universe_id: UniverseId,
-    position_map: &HashMap<UniverseId, DVec3>,
-) {
-    let wrapper = match sim_state.universe.get_body(universe_id) {
-        Some(wrapper) => wrapper,
-        None => return,
-    };
-    let satellites = wrapper.relations.satellites.clone();
+        let mut state = collapsing_state(ui.ctx(), row.id, row.satellite_count);
+        state.show_toggle_button(ui, paint_default_icon);
+        state.store(ui.ctx());
+
+        if !state.is_open() {
+            // Shown before the body so that it isn't pushed off by the button's width
+            ui.label(
+                RichText::new(row.satellite_count.to_string())
+                    .small()
+                    .color(Color32::LIGHT_GRAY),
+            )
+            .on_help_text(
+                RichText::new(format!(
+                    "{} satellites, hidden while collapsed",
+                    row.satellite_count
+                ))
+                .color(Color32::WHITE)
+                .text_style(TextRole::Body.into()),
+            );
+        }
 
-    let egui_id = get_body_egui_id(universe_id);
-    CollapsingState::load_with_default_open(ui.ctx(), egui_id, true)
-        .show_header(ui, |ui| {
-            body_tree_base_node(ui, sim_state, universe_id, position_map);
-        })
-        .body(|ui| {
-            for id in satellites {
-                body_tree_node(ui, sim_state, id, position_map)
-            }
-        });
+        body_tree_base_node(ui, sim_state, row.id, position_map);
+    });
 }
 
 fn body_tree_base_node(
@@ -182,12 +411,14 @@ fn body_tree_base_node(
         Some(wrapper) => &wrapper.body,
         None => return,
     };
+    let color = palette::display_color(&sim_state.universe, universe_id).unwrap_or(body.color);
 
     let selected = sim_state.focused_body == universe_id;
 
-    let response = selectable_body_button(
+    let mut response = selectable_body_button(
         ui,
         body,
+        color,
         BODY_TREE_ICON_SIZE,
         selected,
         true,
@@ -199,14 +430,36 @@ fn body_tree_base_node(
             .filter(|state| state.universe_id == universe_id),
     );
 
-    if sim_state.focused_body() == universe_id
-        && sim_state.ui.body_list_window_state.scroll_to_focused
-    {
-        sim_state.ui.body_list_window_state.scroll_to_focused = false;
-        response.button_response.scroll_to_me(None);
+    // Shown after egui's usual tooltip delay
+    response.button_response = response
+        .button_response
+        .on_hover_ui(|ui| body_hover_card(ui, &sim_state.universe, universe_id));
+
+    let locked = sim_state
+        .universe
+        .get_body(universe_id)
+        .is_some_and(|w| w.locked);
+    if locked {
+        ui.label(RichText::new("🔒").color(Color32::LIGHT_GRAY))
+            .on_help_text(
+                RichText::new("Locked, so it can't be edited, moved or deleted.")
+                    .color(Color32::WHITE)
+                    .text_style(TextRole::Body.into()),
+            );
+    }
+
+    if response.button_response.long_touched() {
+        let center = response
+            .button_response
+            .interact_pointer_pos()
+            .unwrap_or(response.button_response.rect.center());
+        sim_state.ui.body_list_window_state.radial_menu = Some(RadialMenuState {
+            universe_id,
+            center,
+        });
     }
 
-    if response.button_response.double_clicked() {
+    if response.button_response.double_clicked() && !locked {
         set_rename_state(ui.ctx(), sim_state, universe_id);
     } else if response.button_response.clicked() {
         sim_state.switch_focus(universe_id, &position_map);
@@ -339,7 +592,10 @@ fn ellipsis_popup(
 
         ui.separator();
 
-        let up_enabled = cur_sibling_idx.map(|i| i > 0).unwrap_or(false);
+        // Manual reordering only makes sense when the manual order is shown
+        let manual_order = sim_state.ui.body_list_window_state.sort_mode == BodySortMode::Manual;
+
+        let up_enabled = manual_order && cur_sibling_idx.map(|i| i > 0).unwrap_or(false);
         let up_button = ui.scope(|ui| {
             if !up_enabled {
                 ui.disable();
@@ -348,10 +604,11 @@ fn ellipsis_popup(
         });
         let up_button = up_button.inner;
 
-        let down_enabled = cur_sibling_idx
-            .map(|i| siblings.map(|s| s.len() > i + 1))
-            .flatten()
-            .unwrap_or(false);
+        let down_enabled = manual_order
+            && cur_sibling_idx
+                .map(|i| siblings.map(|s| s.len() > i + 1))
+                .flatten()
+                .unwrap_or(false);
         let down_button = ui.scope(|ui| {
             if !down_enabled {
                 ui.disable();
@@ -370,7 +627,23 @@ fn ellipsis_popup(
             ui.add_sized((ui.available_width(), 16.0), button("Duplicate"))
         });
         let duplicate_button = duplicate_button.inner;
-        let delete_enabled = parent_id.is_some();
+        let copy_button = ui_button(ui, "Copy");
+        let copy_uuid_button = ui_button(ui, "Copy UUID");
+        let paste_enabled = BODY_CLIPBOARD
+            .try_lock()
+            .map(|clipboard| clipboard.is_some())
+            .unwrap_or(false);
+        let paste_button = ui.scope(|ui| {
+            if !paste_enabled {
+                ui.disable();
+            }
+
+            ui.add_sized((ui.available_width(), 16.0), button("Paste as child"))
+        });
+        let paste_button = paste_button.inner;
+        let locked = body_wrapper.is_some_and(|w| w.locked);
+        let delete_enabled =
+            parent_id.is_some() && !sim_state.universe.is_subtree_locked(universe_id);
         let delete_button = ui.scope(|ui| {
             if !delete_enabled {
                 ui.disable();
@@ -379,54 +652,25 @@ fn ellipsis_popup(
             ui.add_sized((ui.available_width(), 16.0), button("Delete"))
         });
         let delete_button = delete_button.inner;
-        let rename_button = ui_button(ui, "Rename");
+        let rename_button = ui.scope(|ui| {
+            if locked {
+                ui.disable();
+            }
+
+            ui_button(ui, "Rename")
+        });
+        let rename_button = rename_button.inner;
+        let lock_button = ui_button(ui, if locked { "🔓 Unlock" } else { "🔒 Lock" });
 
         if new_child_button.clicked() {
-            let this_radius = body_wrapper.map(|x| x.body.radius).unwrap_or(1.0);
-            let child_name = body_wrapper
-                .map(|w| format!("Child of {}", w.body.name))
-                .unwrap_or_else(|| "Child body".to_owned());
-            let mu = body_wrapper
-                .map(|w| w.body.mass * sim_state.universe.get_gravitational_constant())
-                .unwrap_or(1.0);
-
-            sim_state.preview_body = Some(PreviewBody {
-                body: Body {
-                    name: child_name,
-                    mass: 1.0,
-                    radius: this_radius * 0.1,
-                    color: Srgba::WHITE,
-                    orbit: Some(Orbit::new(0.0, this_radius * 2.0, 0.0, 0.0, 0.0, 0.0, mu)),
-                },
-                parent_id: Some(universe_id),
-            });
+            sim_state.preview_body = Some(new_body_preview(&sim_state.universe, Some(universe_id)));
             sim_state.ui.body_list_window_state.listed_body_with_popup = None;
             if let Some(state) = &mut sim_state.ui.new_body_window_state {
                 state.request_focus = true;
             }
         }
         if new_sibling_button.clicked() {
-            let parent = parent_id
-                .map(|id| sim_state.universe.get_body(id))
-                .flatten();
-            let parent_radius = parent.map(|w| w.body.radius).unwrap_or(1.0);
-            let mu = parent
-                .map(|w| w.body.mass * sim_state.universe.get_gravitational_constant())
-                .unwrap_or(1.0);
-            let sibling_name = parent
-                .map(|w| format!("Child of {}", w.body.name))
-                .unwrap_or_else(|| "Sibling body".to_owned());
-
-            sim_state.preview_body = Some(PreviewBody {
-                body: Body {
-                    name: sibling_name,
-                    mass: 1.0,
-                    radius: parent_radius * 0.1,
-                    color: Srgba::WHITE,
-                    orbit: Some(Orbit::new(0.0, parent_radius * 2.0, 0.0, 0.0, 0.0, 0.0, mu)),
-                },
-                parent_id: parent_id,
-            });
+            sim_state.preview_body = Some(new_body_preview(&sim_state.universe, parent_id));
             sim_state.ui.body_list_window_state.listed_body_with_popup = None;
             if let Some(state) = &mut sim_state.ui.new_body_window_state {
                 state.request_focus = true;
@@ -465,26 +709,54 @@ fn ellipsis_popup(
                 });
             }
         }
-        if delete_button.clicked() {
-            let bodies_removed = sim_state.universe.remove_body(universe_id);
-            if let Some(preview) = &sim_state.preview_body
-                && let Some(parent_id) = preview.parent_id
-                && bodies_removed.iter().any(|(id, _)| *id == parent_id)
-            {
-                sim_state.preview_body = None;
+        if copy_button.clicked() {
+            if let Ok(mut clipboard) = BODY_CLIPBOARD.try_lock() {
+                *clipboard = sim_state.universe.copy_bodies(universe_id);
             }
-            if bodies_removed
-                .iter()
-                .any(|(id, _)| *id == sim_state.focused_body())
-            {
-                sim_state.switch_focus(parent_id.unwrap_or(0), position_map);
+            sim_state.ui.body_list_window_state.listed_body_with_popup = None;
+        }
+        if copy_uuid_button.clicked() {
+            if let Some(wrapper) = sim_state.universe.get_body(universe_id) {
+                ui.ctx().copy_text(wrapper.stable_id.to_string());
             }
             sim_state.ui.body_list_window_state.listed_body_with_popup = None;
         }
+        if paste_button.clicked() {
+            let result = BODY_CLIPBOARD.try_lock().ok().and_then(|clipboard| {
+                let copied = clipboard.as_ref()?;
+                sim_state.universe.paste_bodies(copied, universe_id).ok()
+            });
+            if result.is_some() {
+                metrics::record_use(Feature::PasteBodies);
+            }
+            sim_state.ui.body_list_window_state.listed_body_with_popup = None;
+
+            if let Some(&universe_id) = result.as_ref().and_then(|ids| ids.first()) {
+                sim_state.ui.body_list_window_state.listed_body_with_rename = Some(RenameState {
+                    universe_id,
+                    name_buffer: sim_state
+                        .universe
+                        .get_body(universe_id)
+                        .map(|w| w.body.name.clone())
+                        .unwrap_or_default(),
+                    requesting_focus: true,
+                });
+            }
+        }
+        if delete_button.clicked() {
+            sim_state.remove_body(universe_id, position_map);
+            metrics::record_use(Feature::DeleteBody);
+        }
         if rename_button.clicked() {
             set_rename_state(ui.ctx(), sim_state, universe_id);
             sim_state.ui.body_list_window_state.listed_body_with_popup = None;
         }
+        if lock_button.clicked() {
+            if let Some(wrapper) = sim_state.universe.get_body_mut(universe_id) {
+                wrapper.locked = !locked;
+            }
+            sim_state.ui.body_list_window_state.listed_body_with_popup = None;
+        }
     });
     if outer_response.clicked_elsewhere()
         && inner_response.clicked_elsewhere()
@@ -1,6 +1,9 @@
 use std::collections::{HashMap, HashSet};
 
+use float_pretty_print::PrettyPrintFloat;
 use glam::DVec3;
+use keplerian_sim::{Orbit, OrbitTrait};
+use three_d::Srgba;
 use three_d::egui::{
     Atom, AtomLayout, Button, Color32, Context, CursorIcon, Id as EguiId, ImageButton, Pos2, Rect,
     Response, RichText, Stroke, TextEdit, Ui, Vec2, collapsing_header::CollapsingState,
@@ -8,35 +11,169 @@ use three_d::egui::{
 
 use crate::{
     assets,
-    gui::{SimState, declare_id, unit_dv::drag_value_with_unit},
+    gfx::palette,
+    gui::{
+        SimState, body_theme::BodyTheme, declare_id, help::HelpText,
+        sci_input::precise_value_with_unit, text_style::TextRole,
+    },
     sim::{
         body::Body,
         universe::{Id as UniverseId, Universe},
+        validation::SURFACE_MARGIN,
     },
+    units::{AutoUnit, length::LengthUnit},
 };
 
 declare_id!(RENAME_TEXTEDIT, b"OmgRen??");
 
+pub(super) mod compare;
 pub(super) mod edit;
 mod info;
 pub(super) mod list;
 pub(super) mod new;
+mod radial;
+pub(super) mod summary;
 
 pub(crate) struct PreviewBody {
     pub body: Body,
     pub parent_id: Option<UniverseId>,
 }
 
-pub(super) fn celestial_windows(
+/// How long a moved body's old orbit line takes to fade out
+/// while its new one fades in, in seconds.
+pub(crate) const REPARENT_FADE_DURATION: f64 = 0.5;
+
+/// The orbit line a body had before it was moved to another parent,
+/// fading out so the change doesn't look like a glitch.
+pub(crate) struct ReparentFade {
+    pub body_id: UniverseId,
+    /// The body as it was, around its old parent.
+    pub old: PreviewBody,
+    /// How far along the fade is, from 0 to 1.
+    pub progress: f64,
+}
+
+/// Draws the body list, along with the radial menu opened from it.
+pub(super) fn body_list_window(
     ctx: &Context,
     sim_state: &mut SimState,
     position_map: &HashMap<UniverseId, DVec3>,
 ) {
     list::body_tree_window(ctx, sim_state, position_map);
+    radial::radial_menu(ctx, sim_state, position_map);
+}
+
+pub(super) fn body_edit_window(ctx: &Context, sim_state: &mut SimState) {
     edit::body_edit_window(ctx, sim_state);
+}
+
+pub(super) fn new_body_window(ctx: &Context, sim_state: &mut SimState) {
     new::new_body_window(ctx, sim_state);
 }
 
+pub(super) fn compare_window(ctx: &Context, sim_state: &mut SimState) {
+    compare::compare_window(ctx, sim_state);
+}
+
+/// The periapsis row of an orbit form.
+///
+/// While the periapsis is below `min_periapsis`, which would put the body
+/// inside its parent, the field is marked in red, and a row under it
+/// shows the minimum and offers to raise the periapsis to it.
+///
+/// With a `surface_radius`, the periapsis is shown and edited
+/// as an altitude above the parent's surface.
+pub(super) fn periapsis_rows(
+    ui: &mut Ui,
+    id_salt: impl std::hash::Hash,
+    orbit: &mut Orbit,
+    unit: &mut AutoUnit<LengthUnit>,
+    min_periapsis: Option<f64>,
+    surface_radius: Option<f64>,
+) {
+    let (label, help) = match surface_radius {
+        Some(_) => (
+            "Periapsis alt.",
+            "The minimum altitude of the orbit above the parent body's surface.",
+        ),
+        None => (
+            "Periapsis",
+            "The minimum distance of the orbit to the center of the parent body.",
+        ),
+    };
+    ui.label(label)
+        .on_help_text(
+            RichText::new(help)
+                .color(Color32::WHITE)
+                .text_style(TextRole::Body.into()),
+        )
+        .on_hover_cursor(CursorIcon::Help);
+    let offset = surface_radius.unwrap_or(0.0);
+    let too_low = min_periapsis.filter(|&min| orbit.get_periapsis() < min);
+    // Compared as shown, so taking off and adding back the radius
+    // doesn't count as an edit
+    let shown = orbit.get_periapsis() - offset;
+    let mut periapsis = shown;
+    ui.scope(|ui| {
+        if too_low.is_some() {
+            let visuals = ui.visuals_mut();
+            visuals.override_text_color = Some(Color32::LIGHT_RED);
+            for widget in [
+                &mut visuals.widgets.inactive,
+                &mut visuals.widgets.hovered,
+                &mut visuals.widgets.active,
+            ] {
+                widget.bg_stroke = Stroke::new(1.0, Color32::LIGHT_RED);
+            }
+        }
+        precise_value_with_unit(id_salt, ui, &mut periapsis, unit);
+    });
+    if periapsis != shown {
+        orbit.set_periapsis(periapsis + offset);
+    }
+    ui.end_row();
+
+    let Some(min_periapsis) = too_low else {
+        return;
+    };
+    ui.label("");
+    ui.horizontal_wrapped(|ui| {
+        let min_shown = min_periapsis - offset;
+        let min_unit = LengthUnit::largest_unit_from_base(min_shown);
+        ui.label(
+            RichText::new(format!(
+                "⚠ Inside the parent, min. {} {min_unit}",
+                PrettyPrintFloat(min_shown / min_unit.get_value())
+            ))
+            .color(Color32::LIGHT_RED)
+            .text_style(TextRole::Body.into()),
+        )
+        .on_help_text(
+            RichText::new(format!(
+                "This orbit passes through the parent body.\n\
+                The minimum keeps this body's own radius plus {:.0}% of the \
+                parent's radius clear of the parent's surface.",
+                SURFACE_MARGIN * 100.0
+            ))
+            .color(Color32::WHITE)
+            .text_style(TextRole::Body.into()),
+        )
+        .on_hover_cursor(CursorIcon::Help);
+
+        let clamp_button = ui
+            .button(RichText::new("Clamp").text_style(TextRole::Body.into()))
+            .on_help_text(
+                RichText::new("Raise the periapsis to just above the parent's surface.")
+                    .color(Color32::WHITE)
+                    .text_style(TextRole::Body.into()),
+            );
+        if clamp_button.clicked() {
+            orbit.set_periapsis(min_periapsis);
+        }
+    });
+    ui.end_row();
+}
+
 struct BodySelectableButtonResponse {
     button_response: Response,
     rename_response: Option<Response>,
@@ -44,13 +181,13 @@ struct BodySelectableButtonResponse {
 }
 
 #[derive(Clone, Copy)]
-struct DisallowedData<'a> {
+pub(super) struct DisallowedData<'a> {
     disallowed_set: &'a HashSet<UniverseId>,
     reason: &'a RichText,
 }
 
 /// Returns whether the already-selected body was clicked again
-fn selectable_body_tree(
+pub(super) fn selectable_body_tree(
     ui: &mut Ui,
     egui_id: EguiId,
     universe: &Universe,
@@ -72,10 +209,13 @@ fn selectable_body_tree(
         };
 
         if wrapper.relations.satellites.is_empty() {
+            let color = palette::display_color(universe, universe_id).unwrap_or(wrapper.body.color);
+
             ui.indent((egui_id, [universe_id]), |ui| {
                 selectable_body_leaf(
                     ui,
                     &wrapper.body,
+                    color,
                     universe_id,
                     selected,
                     clicked_selected,
@@ -98,6 +238,7 @@ fn selectable_body_tree(
     fn selectable_body_leaf(
         ui: &mut Ui,
         body: &Body,
+        color: Srgba,
         universe_id: UniverseId,
         selected: &mut Option<UniverseId>,
         clicked_selected: &mut bool,
@@ -111,8 +252,15 @@ fn selectable_body_tree(
             ui.disable();
         }
 
-        let response =
-            selectable_body_button(ui, body, 16.0, *selected == Some(universe_id), false, None);
+        let response = selectable_body_button(
+            ui,
+            body,
+            color,
+            16.0,
+            *selected == Some(universe_id),
+            false,
+            None,
+        );
 
         if response.button_response.clicked() && enabled {
             if *selected == Some(universe_id) {
@@ -159,6 +307,7 @@ fn selectable_body_tree(
                 let response = selectable_body_button(
                     ui,
                     &wrapper.body,
+                    palette::display_color(universe, universe_id).unwrap_or(wrapper.body.color),
                     16.0,
                     *selected == Some(universe_id),
                     false,
@@ -228,6 +377,7 @@ fn selectable_body_tree(
 fn selectable_body_button(
     ui: &mut Ui,
     body: &Body,
+    color: Srgba,
     height: f32,
     selected: bool,
     ellipsis: bool,
@@ -238,10 +388,7 @@ fn selectable_body_button(
 
     let radius = height / 2.0;
     let center = Pos2::from([radius, radius]);
-    let fill_color = {
-        let c = body.color;
-        Color32::from_rgb(c.r, c.g, c.b)
-    };
+    let fill_color = Color32::from_rgb(color.r, color.g, color.b);
 
     let circle_atom = Atom::custom(*CIRCLE_ICON_ID, Vec2::splat(height));
 
@@ -272,9 +419,16 @@ fn selectable_body_button(
         layout.push_right(atom);
     }
 
+    // Rows are tinted with the body's color when hovered,
+    // then the usual hover look is put back for whatever comes next
+    let theme = BodyTheme::from_color(color);
+    let hovered = ui.visuals().widgets.hovered;
+    ui.visuals_mut().widgets.hovered.weak_bg_fill = theme.hover_fill;
+    ui.visuals_mut().widgets.hovered.bg_stroke = Stroke::new(1.0, theme.accent);
     let button_response = Button::selectable(selected, layout.atoms)
         .min_size(Vec2::new(ui.available_width(), height))
         .atom_ui(ui);
+    ui.visuals_mut().widgets.hovered = hovered;
 
     if let Some(rect) = button_response.rect(*CIRCLE_ICON_ID) {
         ui.painter().with_clip_rect(rect).circle_filled(
@@ -1,16 +1,29 @@
 use crate::{
     gui::{
-        PreviewBody, SimState,
-        celestials::{drag_value_with_unit, info::body_window_info, selectable_body_tree},
+        PreviewBody, SimState, altitude,
+        celestials::{
+            info::{InfoCache, body_window_info},
+            periapsis_rows, selectable_body_tree,
+        },
         declare_id,
+        help::HelpText,
+        layout::{self, DockableWindow},
+        nudge::{ElementKind, nudgeable},
+        sci_input::precise_value_with_unit,
+        text_style::TextRole,
+    },
+    metrics::{self, Feature},
+    notify::{Severity, notify},
+    sim::{
+        universe::{Id as UniverseId, Universe},
+        validation,
     },
-    sim::universe::{Id as UniverseId, Universe},
     units::{AutoUnit, length::LengthUnit, mass::MassUnit},
 };
 use keplerian_sim::{MuSetterMode, Orbit, OrbitTrait};
 use three_d::egui::{
-    Color32, ComboBox, Context, CursorIcon, DragValue, Grid, Label, PopupCloseBehavior, RichText,
-    Slider, TextEdit, TextWrapMode, Ui, Window,
+    Button, Color32, ComboBox, Context, CursorIcon, DragValue, Grid, Label, PopupCloseBehavior,
+    RichText, Slider, TextEdit, TextWrapMode, Ui, Window,
     color_picker::{Alpha, color_edit_button_srgba},
 };
 
@@ -27,6 +40,7 @@ pub(in super::super) struct NewBodyWindowState {
     mass_unit: AutoUnit<MassUnit>,
     radius_unit: AutoUnit<LengthUnit>,
     periapsis_unit: AutoUnit<LengthUnit>,
+    info_cache: InfoCache,
     pub(super) request_focus: bool,
 }
 
@@ -45,6 +59,7 @@ impl Default for NewBodyWindowState {
                 auto: true,
                 unit: LengthUnit::Meters,
             },
+            info_cache: InfoCache::default(),
             request_focus: true,
         }
     }
@@ -59,34 +74,36 @@ pub(super) fn new_body_window(ctx: &Context, sim_state: &mut SimState) {
         }
     };
 
-    let window_state = sim_state.ui.new_body_window_state.get_or_insert_default();
-
     let mut open = true;
 
-    let window = Window::new("New Body")
+    let builder = Window::new(DockableWindow::NewBody.title())
         .scroll([false, true])
         .resizable([false, true])
         .default_width(300.0)
         .min_width(300.0)
         .max_width(300.0)
         .min_height(200.0)
-        .open(&mut open)
-        .show(ctx, |ui| {
-            let wrapper = match wrapper.take() {
-                Some(w) => w,
-                None => return,
-            };
-            let time = sim_state.universe.time;
-            ui.scope(|ui| {
-                sim_state.preview_body = new_body_window_content(
-                    ui,
-                    &mut sim_state.universe,
-                    wrapper,
-                    window_state,
-                    sim_state.mu_setter_mode.to_mu_setter(time),
-                );
-            });
+        .open(&mut open);
+    let builder = layout::place(ctx, sim_state, DockableWindow::NewBody, builder);
+
+    let window_state = sim_state.ui.new_body_window_state.get_or_insert_default();
+
+    let window = builder.show(ctx, |ui| {
+        let wrapper = match wrapper.take() {
+            Some(w) => w,
+            None => return,
+        };
+        let time = sim_state.universe.time;
+        ui.scope(|ui| {
+            sim_state.preview_body = new_body_window_content(
+                ui,
+                &mut sim_state.universe,
+                wrapper,
+                window_state,
+                sim_state.mu_setter_mode.to_mu_setter(time),
+            );
         });
+    });
 
     if let Some(w) = wrapper {
         sim_state.preview_body = Some(w);
@@ -118,7 +135,7 @@ fn new_body_window_content(
 
     let text = RichText::new("Physical Characteristics")
         .underline()
-        .size(16.0);
+        .text_style(TextRole::Body.into());
     let label = Label::new(text);
     ui.add(label);
     ui.add_space(8.0);
@@ -130,7 +147,9 @@ fn new_body_window_content(
             new_body_window_phys(ui, &mut wrapper, window_state)
         });
 
-    let text = RichText::new("Orbital Parameters").underline().size(16.0);
+    let text = RichText::new("Orbital Parameters")
+        .underline()
+        .text_style(TextRole::Body.into());
     let label = Label::new(text);
     ui.add_space(12.0);
     ui.add(label);
@@ -144,6 +163,7 @@ fn new_body_window_content(
                 ui,
                 &mut wrapper.body.orbit,
                 &mut wrapper.parent_id,
+                wrapper.body.radius,
                 universe,
                 window_state,
                 mu_mode,
@@ -154,7 +174,7 @@ fn new_body_window_content(
 
     let derived_info = RichText::new("Derived Information")
         .color(Color32::WHITE)
-        .size(16.0)
+        .text_style(TextRole::Body.into())
         .underline();
 
     let coll_res = ui.collapsing(derived_info, |ui| {
@@ -164,7 +184,18 @@ fn new_body_window_content(
             // .spacing([40.0, 4.0])
             .striped(true)
             .show(ui, |ui| {
-                body_window_info(ui, &wrapper.body, wrapper.parent_id, universe);
+                body_window_info(
+                    ui,
+                    &wrapper.body,
+                    wrapper.parent_id,
+                    universe,
+                    wrapper
+                        .parent_id
+                        .map(|id| universe.get_local_gravitational_constant(id))
+                        .unwrap_or(universe.get_gravitational_constant()),
+                    universe.time,
+                    &mut window_state.info_cache,
+                );
             });
     });
 
@@ -173,24 +204,75 @@ fn new_body_window_content(
         .on_hover_cursor(CursorIcon::PointingHand);
 
     ui.add_space(16.0);
-    if ui.button("Confirm").clicked() {
-        let _ = universe.add_body(wrapper.body, wrapper.parent_id);
-        return None;
+
+    let problem = confirm_problem(universe, &wrapper);
+
+    if let Some(problem) = problem {
+        ui.label(RichText::new(format!("⚠ {problem}")).color(Color32::YELLOW));
+    }
+
+    let confirm_button = ui
+        .add_enabled(problem.is_none(), Button::new("Confirm"))
+        .on_disabled_hover_text(
+            RichText::new("Fix the problem above to add the body.")
+                .color(Color32::WHITE)
+                .text_style(TextRole::Body.into()),
+        );
+
+    if confirm_button.clicked() {
+        match universe.add_body(wrapper.body, wrapper.parent_id) {
+            Ok(_) => {
+                metrics::record_use(Feature::AddBody);
+                return None;
+            }
+            Err(e) => {
+                notify(
+                    Severity::Error,
+                    format!("Couldn't add the body: {}", e.cause()),
+                );
+                // Keep the body, so what was entered isn't lost
+                wrapper.body = e.into_body();
+            }
+        }
     }
 
     return Some(wrapper);
 }
 
+/// Finds why the preview body can't be added as it is.
+///
+/// Returns: A description of the problem, or None if the body can be added.
+fn confirm_problem(universe: &Universe, wrapper: &PreviewBody) -> Option<&'static str> {
+    if wrapper.body.name.trim().is_empty() {
+        return Some("The body needs a name.");
+    }
+
+    if let Some(parent_id) = wrapper.parent_id
+        && universe.get_body(parent_id).is_none()
+    {
+        return Some("The parent body was deleted. Pick another one.");
+    }
+
+    if let Some(orbit) = &wrapper.body.orbit {
+        let valid = orbit.get_periapsis() > 0.0;
+        if !valid {
+            return Some("The periapsis must be above 0.");
+        }
+    }
+
+    None
+}
+
 fn new_body_window_phys(
     ui: &mut Ui,
     wrapper: &mut PreviewBody,
     window_state: &mut NewBodyWindowState,
 ) {
     ui.label("Body name")
-        .on_hover_text(
+        .on_help_text(
             RichText::new("The name that will show up in the list of bodies.")
                 .color(Color32::WHITE)
-                .size(16.0),
+                .text_style(TextRole::Body.into()),
         )
         .on_hover_cursor(CursorIcon::Help);
     ui.add(
@@ -202,10 +284,10 @@ fn new_body_window_phys(
     ui.end_row();
 
     ui.label("Body color")
-        .on_hover_text(
+        .on_help_text(
             RichText::new("The color that this body will be rendered in.")
                 .color(Color32::WHITE)
-                .size(16.0),
+                .text_style(TextRole::Body.into()),
         )
         .on_hover_cursor(CursorIcon::Help);
     let original_srgba: Color32 = {
@@ -220,16 +302,16 @@ fn new_body_window_phys(
     ui.end_row();
 
     ui.label("Mass")
-        .on_hover_text(
+        .on_help_text(
             RichText::new(
                 "The mass of the body.\n\
             Determines the speed of orbiting objects.",
             )
             .color(Color32::WHITE)
-            .size(16.0),
+            .text_style(TextRole::Body.into()),
         )
         .on_hover_cursor(CursorIcon::Help);
-    drag_value_with_unit(
+    precise_value_with_unit(
         NEW_BODY_MASS_SALT,
         ui,
         &mut wrapper.body.mass,
@@ -238,13 +320,13 @@ fn new_body_window_phys(
     ui.end_row();
 
     ui.label("Radius")
-        .on_hover_text(
+        .on_help_text(
             RichText::new("The radius that this body will be rendered in.")
                 .color(Color32::WHITE)
-                .size(16.0),
+                .text_style(TextRole::Body.into()),
         )
         .on_hover_cursor(CursorIcon::Help);
-    drag_value_with_unit(
+    precise_value_with_unit(
         NEW_BODY_RADIUS_SALT,
         ui,
         &mut wrapper.body.radius,
@@ -257,15 +339,16 @@ fn new_body_window_orbit(
     ui: &mut Ui,
     orbit: &mut Option<Orbit>,
     parent_id: &mut Option<UniverseId>,
+    body_radius: f64,
     universe: &Universe,
     window_state: &mut NewBodyWindowState,
     mu_mode: MuSetterMode,
 ) {
     ui.label("Parent body")
-        .on_hover_text(
+        .on_help_text(
             RichText::new("The body that this body is orbiting around.")
                 .color(Color32::WHITE)
-                .size(16.0),
+                .text_style(TextRole::Body.into()),
         )
         .on_hover_cursor(CursorIcon::Help);
     ComboBox::from_id_salt(NEW_BODY_PARENT_COMBO_BOX_SALT)
@@ -285,9 +368,8 @@ fn new_body_window_orbit(
 
     if let Some(parent_id) = parent_id
         && let Some(orbit) = orbit.as_mut()
-        && let Some(parent) = universe.get_body(*parent_id)
+        && let Some(gravitational_parameter) = universe.get_body_gravitational_parameter(*parent_id)
     {
-        let gravitational_parameter = universe.get_gravitational_constant() * parent.body.mass;
         if orbit.get_gravitational_parameter() != gravitational_parameter {
             orbit.set_gravitational_parameter(gravitational_parameter, mu_mode);
         }
@@ -300,18 +382,14 @@ fn new_body_window_orbit(
     let orbit = orbit.get_or_insert_with(|| {
         let (periapsis, mu) = universe
             .get_body(parent_id)
-            .map(|w| {
-                (
-                    w.body.radius * 2.0,
-                    w.body.mass * universe.get_gravitational_constant(),
-                )
-            })
+            .zip(universe.get_body_gravitational_parameter(parent_id))
+            .map(|(w, mu)| (w.body.radius * 2.0, mu))
             .unwrap_or((2.0, 1.0));
         Orbit::new(0.0, periapsis, 0.0, 0.0, 0.0, 0.0, mu)
     });
 
     ui.label("Eccentricity")
-        .on_hover_text(
+        .on_help_text(
             RichText::new(
                 "How eccentric the orbit is.\n\
             An eccentricity of 1 (parabolic) is not supported.\n\
@@ -319,76 +397,79 @@ fn new_body_window_orbit(
             An eccentricity of more than one means the orbit never loops (is open; hyperbolic).",
             )
             .color(Color32::WHITE)
-            .size(16.0),
+            .text_style(TextRole::Body.into()),
         )
         .on_hover_cursor(CursorIcon::Help);
     let mut eccentricity = orbit.get_eccentricity();
-    let dv = DragValue::new(&mut eccentricity)
-        .range(0.0..=f64::MAX)
-        .speed(0.01);
-    let dv = ui.add_sized((ui.available_width(), 18.0), dv);
+    let dv = nudgeable(
+        ui,
+        ElementKind::Eccentricity,
+        &mut eccentricity,
+        |ui, value| {
+            let dv = DragValue::new(value).range(0.0..=f64::MAX).speed(0.01);
+            ui.add(dv)
+        },
+    );
     if dv.changed() {
-        orbit.set_eccentricity(eccentricity);
+        orbit.set_eccentricity(eccentricity.max(0.0));
     }
     ui.end_row();
 
-    ui.label("Periapsis")
-        .on_hover_text(
-            RichText::new(
-                "The minimum distance of the orbit \
-            to the center of the parent body.",
-            )
-            .color(Color32::WHITE)
-            .size(16.0),
-        )
-        .on_hover_cursor(CursorIcon::Help);
-    let mut periapsis = orbit.get_periapsis();
-    drag_value_with_unit(
-        NEW_BODY_PERIAPSIS_SALT,
+    let min_periapsis = universe
+        .get_body(parent_id)
+        .map(|parent| validation::min_safe_periapsis(parent.body.radius, body_radius));
+    periapsis_rows(
         ui,
-        &mut periapsis,
+        NEW_BODY_PERIAPSIS_SALT,
+        orbit,
         &mut window_state.periapsis_unit,
+        min_periapsis,
+        altitude::surface_radius(universe, Some(parent_id)),
     );
-    if periapsis != orbit.get_periapsis() {
-        orbit.set_periapsis(periapsis);
-    }
-    ui.end_row();
 
     ui.label("Inclination")
-        .on_hover_text(
+        .on_help_text(
             RichText::new("How inclined from the up axis the orbit is.")
                 .color(Color32::WHITE)
-                .size(16.0),
+                .text_style(TextRole::Body.into()),
         )
         .on_hover_cursor(CursorIcon::Help);
     let mut inclination = orbit.get_inclination().to_degrees();
-    let slider = Slider::new(&mut inclination, 0.0..=180.0).suffix('°');
-    let slider = ui.add_sized((ui.available_width(), 18.0), slider);
+    let slider = nudgeable(
+        ui,
+        ElementKind::Inclination,
+        &mut inclination,
+        |ui, value| {
+            let slider = Slider::new(value, 0.0..=180.0).suffix('°');
+            ui.add_sized((ui.available_width(), 18.0), slider)
+        },
+    );
     if slider.changed() {
-        orbit.set_inclination(inclination.to_radians());
+        orbit.set_inclination(inclination.clamp(0.0, 180.0).to_radians());
     }
     ui.end_row();
 
     ui.label("Arg. of Pe.")
-        .on_hover_text(
+        .on_help_text(
             RichText::new(
                 "The argument of periapsis of the orbit.\n\
             This is the angle offset of the periapsis along the orbital plane.",
             )
             .color(Color32::WHITE)
-            .size(16.0),
+            .text_style(TextRole::Body.into()),
         )
         .on_hover_cursor(CursorIcon::Help);
     let mut arg_pe = orbit.get_arg_pe().to_degrees();
-    let slider = Slider::new(&mut arg_pe, 0.0..=360.0).suffix('°');
-    let slider = ui.add(slider);
+    let slider = nudgeable(ui, ElementKind::Angle, &mut arg_pe, |ui, value| {
+        ui.add(Slider::new(value, 0.0..=360.0).suffix('°'))
+    });
     if slider.changed() {
-        orbit.set_arg_pe(arg_pe.to_radians());
+        orbit.set_arg_pe(arg_pe.rem_euclid(360.0).to_radians());
     }
     ui.end_row();
 
     ui.label("RAAN")
-        .on_hover_text(
+        .on_help_text(
             RichText::new(
                 "The right ascension of the ascending node.\n\
             a.k.a.: the longitude of ascending node.\n\
@@ -396,32 +477,34 @@ fn new_body_window_orbit(
             the reference plane (horizontal plane).",
             )
             .color(Color32::WHITE)
-            .size(16.0),
+            .text_style(TextRole::Body.into()),
         )
         .on_hover_cursor(CursorIcon::Help);
     let mut lan = orbit.get_long_asc_node().to_degrees();
-    let slider = Slider::new(&mut lan, 0.0..=360.0).suffix('°');
-    let slider = ui.add(slider);
+    let slider = nudgeable(ui, ElementKind::Angle, &mut lan, |ui, value| {
+        ui.add(Slider::new(value, 0.0..=360.0).suffix('°'))
+    });
     if slider.changed() {
-        orbit.set_long_asc_node(lan.to_radians());
+        orbit.set_long_asc_node(lan.rem_euclid(360.0).to_radians());
     }
     ui.end_row();
 
     let mut mean_anomaly = orbit.get_mean_anomaly_at_epoch().to_degrees();
     if orbit.get_eccentricity() < 1.0 {
         ui.label("Mean anom.")
-            .on_hover_text(
+            .on_help_text(
                 RichText::new(
                     "Mean anomaly at epoch.\n\
                 This is the offset to the mean anomaly.\n\
                 At time = 0, the mean anomaly of this orbit will be equal to this.",
                 )
                 .color(Color32::WHITE)
-                .size(16.0),
+                .text_style(TextRole::Body.into()),
             )
             .on_hover_cursor(CursorIcon::Help);
-        let slider = Slider::new(&mut mean_anomaly, 0.0..=360.0).suffix('°');
-        let slider = ui.add(slider);
+        let slider = nudgeable(ui, ElementKind::Anomaly, &mut mean_anomaly, |ui, value| {
+            ui.add(Slider::new(value, 0.0..=360.0).suffix('°'))
+        });
         if mean_anomaly < 0.0 || mean_anomaly > 360.0 {
             mean_anomaly = mean_anomaly.rem_euclid(360.0);
         }
@@ -430,20 +513,19 @@ fn new_body_window_orbit(
         }
     } else {
         ui.label("Hyp. m. anom.")
-            .on_hover_text(
+            .on_help_text(
                 RichText::new(
                     "Hyperbolic mean anomaly at epoch.\n\
                 This is the offset to the hyperbolic mean anomaly.\n\
                 At time = 0, the hyperbolic mean anomaly of this orbit will be equal to this.",
                 )
                 .color(Color32::WHITE)
-                .size(16.0),
+                .text_style(TextRole::Body.into()),
             )
             .on_hover_cursor(CursorIcon::Help);
-        let dv = DragValue::new(&mut mean_anomaly)
-            .range(f64::MIN..=f64::MAX)
-            .suffix('°');
-        let dv = ui.add(dv);
+        let dv = nudgeable(ui, ElementKind::Anomaly, &mut mean_anomaly, |ui, value| {
+            ui.add(DragValue::new(value).range(f64::MIN..=f64::MAX).suffix('°'))
+        });
         if dv.changed() {
             orbit.set_mean_anomaly_at_epoch(mean_anomaly.to_radians());
         }
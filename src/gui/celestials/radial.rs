@@ -0,0 +1,145 @@
+use core::f32::consts::TAU;
+use std::collections::HashMap;
+
+use glam::DVec3;
+use three_d::egui::{
+    Align2, Area, Button, Color32, Context, CornerRadius, CursorIcon, Key, Order, Pos2, Rect,
+    RichText, Sense, Stroke, Vec2,
+};
+
+use crate::{
+    gui::{MIN_TOUCH_TARGET_LEN, SimState, declare_id, new_body_preview, text_style::TextRole},
+    sim::universe::Id as UniverseId,
+};
+
+declare_id!(RADIAL_MENU_AREA, b"RadiAL!!");
+
+/// The distance from the menu center to the center of each button.
+const RADIAL_MENU_RADIUS: f32 = MIN_TOUCH_TARGET_LEN * 1.5;
+const RADIAL_BUTTON_SIZE: Vec2 = Vec2::new(MIN_TOUCH_TARGET_LEN * 2.0, MIN_TOUCH_TARGET_LEN);
+const RADIAL_MENU_EXTENT: f32 = RADIAL_MENU_RADIUS + RADIAL_BUTTON_SIZE.x / 2.0;
+
+pub(crate) struct RadialMenuState {
+    pub universe_id: UniverseId,
+    /// The screen position the menu is centered on.
+    pub center: Pos2,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum RadialAction {
+    Focus,
+    Edit,
+    NewChild,
+    Delete,
+}
+
+impl RadialAction {
+    /// Clockwise, starting from the top.
+    const ALL: [Self; 4] = [Self::Focus, Self::Edit, Self::Delete, Self::NewChild];
+
+    const fn label(self) -> &'static str {
+        match self {
+            RadialAction::Focus => "Focus",
+            RadialAction::Edit => "Edit",
+            RadialAction::NewChild => "New child",
+            RadialAction::Delete => "Delete",
+        }
+    }
+}
+
+/// Draws the long-press radial menu of the body list, if one is open.
+pub(super) fn radial_menu(
+    ctx: &Context,
+    sim_state: &mut SimState,
+    position_map: &HashMap<UniverseId, DVec3>,
+) {
+    let Some(state) = &sim_state.ui.body_list_window_state.radial_menu else {
+        return;
+    };
+    let (universe_id, center) = (state.universe_id, state.center);
+
+    let Some(wrapper) = sim_state.universe.get_body(universe_id) else {
+        sim_state.ui.body_list_window_state.radial_menu = None;
+        return;
+    };
+    let is_root = wrapper.relations.parent.is_none();
+    let deletable = !is_root && !sim_state.universe.is_subtree_locked(universe_id);
+    let name = wrapper.body.name.clone();
+
+    let area = Area::new(*RADIAL_MENU_AREA_ID)
+        .order(Order::Foreground)
+        .fixed_pos(center - Vec2::splat(RADIAL_MENU_EXTENT))
+        .show(ctx, |ui| {
+            let (rect, _) =
+                ui.allocate_exact_size(Vec2::splat(RADIAL_MENU_EXTENT * 2.0), Sense::hover());
+            let center = rect.center();
+
+            ui.painter().circle(
+                center,
+                RADIAL_MENU_RADIUS + RADIAL_BUTTON_SIZE.y,
+                Color32::from_black_alpha(192),
+                Stroke::new(1.0, Color32::from_white_alpha(32)),
+            );
+            ui.painter().text(
+                center,
+                Align2::CENTER_CENTER,
+                name,
+                TextRole::Body.resolve(ui.style()),
+                Color32::WHITE,
+            );
+
+            let mut chosen = None;
+            let action_count = RadialAction::ALL.len() as f32;
+
+            for (idx, action) in RadialAction::ALL.into_iter().enumerate() {
+                let angle = idx as f32 / action_count * TAU - TAU / 4.0;
+                let pos = center + Vec2::angled(angle) * RADIAL_MENU_RADIUS;
+                let enabled = action != RadialAction::Delete || deletable;
+
+                let button = Button::new(
+                    RichText::new(action.label())
+                        .color(Color32::WHITE)
+                        .text_style(TextRole::Body.into()),
+                )
+                .corner_radius(CornerRadius::same((MIN_TOUCH_TARGET_LEN / 2.0) as u8));
+
+                let response = ui.scope(|ui| {
+                    if !enabled {
+                        ui.disable();
+                    }
+                    ui.put(Rect::from_center_size(pos, RADIAL_BUTTON_SIZE), button)
+                });
+                let response = response.inner.on_hover_cursor(CursorIcon::PointingHand);
+
+                if response.clicked() {
+                    chosen = Some(action);
+                }
+            }
+
+            chosen
+        });
+
+    let close_requested =
+        area.response.clicked_elsewhere() || ctx.input(|i| i.key_pressed(Key::Escape));
+
+    if let Some(action) = area.inner {
+        match action {
+            RadialAction::Focus => sim_state.switch_focus(universe_id, position_map),
+            RadialAction::Edit => {
+                sim_state.switch_focus(universe_id, position_map);
+                sim_state.ui.edit_body_window_state.window_open = true;
+            }
+            RadialAction::NewChild => {
+                sim_state.preview_body =
+                    Some(new_body_preview(&sim_state.universe, Some(universe_id)));
+                if let Some(state) = &mut sim_state.ui.new_body_window_state {
+                    state.request_focus = true;
+                }
+            }
+            RadialAction::Delete => sim_state.remove_body(universe_id, position_map),
+        }
+        sim_state.ui.body_list_window_state.radial_menu = None;
+    } else if close_requested {
+        sim_state.ui.body_list_window_state.radial_menu = None;
+    }
+}
@@ -0,0 +1,252 @@
+//! A human-readable Markdown summary of a universe, for sharing on forums
+//! or keeping as a snapshot of a setup.
+
+use std::fmt::Write;
+
+use float_pretty_print::PrettyPrintFloat;
+use keplerian_sim::OrbitTrait;
+
+use crate::{
+    gui::celestials::info::derived_rows,
+    sim::universe::{Id as UniverseId, Universe},
+    units::time::TimeDisplayMode,
+};
+
+/// Writes up the universe's settings, its hierarchy,
+/// and every body's elements and derived info.
+pub(crate) fn universe_summary(universe: &Universe) -> String {
+    let mut summary = String::new();
+    let metadata = &universe.metadata;
+
+    let name = metadata.name.trim();
+    let _ = writeln!(
+        summary,
+        "# {}\n",
+        if name.is_empty() {
+            "Unnamed universe"
+        } else {
+            name
+        }
+    );
+    if !metadata.description.trim().is_empty() {
+        let _ = writeln!(summary, "{}\n", metadata.description.trim());
+    }
+
+    let _ = writeln!(summary, "## Settings\n");
+    if !metadata.author.trim().is_empty() {
+        let _ = writeln!(summary, "- Author: {}", metadata.author.trim());
+    }
+    if !metadata.epoch_label.trim().is_empty() {
+        let _ = writeln!(summary, "- Epoch: {}", metadata.epoch_label.trim());
+    }
+    let _ = writeln!(
+        summary,
+        "- Time: {}",
+        TimeDisplayMode::MultiUnit.format_time(universe.time)
+    );
+    let _ = writeln!(
+        summary,
+        "- Gravitational constant: {} m^3 kg^-1 s^-2",
+        PrettyPrintFloat(universe.get_gravitational_constant())
+    );
+    let _ = writeln!(
+        summary,
+        "- Precession scale: {}×",
+        PrettyPrintFloat(universe.perturbation_settings.precession_scale)
+    );
+    let _ = writeln!(summary, "- Bodies: {}\n", universe.get_bodies().len());
+
+    let _ = writeln!(summary, "## Hierarchy\n");
+    let order = hierarchy_order(universe);
+    for &(id, depth) in &order {
+        let _ = writeln!(
+            summary,
+            "{}- {}",
+            "  ".repeat(depth),
+            body_name(universe, id)
+        );
+    }
+
+    let _ = writeln!(summary, "\n## Bodies");
+    for &(id, _) in &order {
+        body_section(&mut summary, universe, id);
+    }
+
+    summary
+}
+
+/// Every body with how deep it is in the hierarchy, parents before their
+/// satellites. Roots are listed by id, and satellites in the order they were added.
+fn hierarchy_order(universe: &Universe) -> Vec<(UniverseId, usize)> {
+    let bodies = universe.get_bodies();
+    let mut roots: Vec<UniverseId> = bodies
+        .iter()
+        .filter(|(_, w)| w.relations.parent.is_none())
+        .map(|(&id, _)| id)
+        .collect();
+    roots.sort_unstable();
+
+    let mut order = Vec::with_capacity(bodies.len());
+    let mut stack: Vec<(UniverseId, usize)> = roots.into_iter().rev().map(|id| (id, 0)).collect();
+    while let Some((id, depth)) = stack.pop() {
+        // Bounded in case of a loop
+        if order.len() >= bodies.len() {
+            break;
+        }
+        order.push((id, depth));
+        if let Some(wrapper) = bodies.get(&id) {
+            stack.extend(
+                wrapper
+                    .relations
+                    .satellites
+                    .iter()
+                    .rev()
+                    .map(|&child| (child, depth + 1)),
+            );
+        }
+    }
+    order
+}
+
+fn body_name(universe: &Universe, id: UniverseId) -> &str {
+    universe
+        .get_body(id)
+        .map(|w| w.body.name.as_str())
+        .unwrap_or("?")
+}
+
+fn body_section(summary: &mut String, universe: &Universe, id: UniverseId) {
+    let Some(wrapper) = universe.get_body(id) else {
+        return;
+    };
+    let body = &wrapper.body;
+    let parent_id = wrapper.relations.parent;
+
+    let _ = writeln!(summary, "\n### {}\n", body.name);
+    match parent_id {
+        Some(parent_id) => {
+            let _ = writeln!(summary, "Orbits {}.\n", body_name(universe, parent_id));
+        }
+        None => {
+            let _ = writeln!(summary, "Root body.\n");
+        }
+    }
+
+    let _ = writeln!(summary, "| Quantity | Value |\n| --- | --- |");
+    let mut row = |quantity: &str, value: String| {
+        let _ = writeln!(summary, "| {quantity} | {value} |");
+    };
+    let number = |value: f64, unit: &str| format!("{} {unit}", PrettyPrintFloat(value));
+    let degrees = |radians: f64| format!("{}°", PrettyPrintFloat(radians.to_degrees()));
+
+    row("Mass", number(body.mass, "kg"));
+    row("Radius", number(body.radius, "m"));
+    if wrapper.g_multiplier != 1.0 {
+        row(
+            "G multiplier",
+            format!("{}×", PrettyPrintFloat(wrapper.g_multiplier)),
+        );
+    }
+    if let Some(orbit) = body.orbit.as_ref().filter(|_| parent_id.is_some()) {
+        row(
+            "Eccentricity",
+            PrettyPrintFloat(orbit.get_eccentricity()).to_string(),
+        );
+        row("Periapsis", number(orbit.get_periapsis(), "m"));
+        row("Inclination", degrees(orbit.get_inclination()));
+        row("Arg. of Pe.", degrees(orbit.get_arg_pe()));
+        row("RAAN", degrees(orbit.get_long_asc_node()));
+        row(
+            "Mean anomaly at epoch",
+            degrees(orbit.get_mean_anomaly_at_epoch()),
+        );
+    }
+    if wrapper.frozen_at.is_some() {
+        row("Frozen", String::from("yes"));
+    }
+
+    let time = wrapper.frozen_at.unwrap_or(universe.time);
+    let derived = derived_rows(
+        body,
+        parent_id,
+        universe,
+        universe.get_local_gravitational_constant(id),
+        time,
+    );
+    for (measurement, value) in derived {
+        row(measurement, value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use keplerian_sim::Orbit;
+
+    use super::*;
+    use crate::sim::body::Body;
+
+    /// A star with a planet, and a moon around the planet.
+    fn system() -> Universe {
+        let mut universe = Universe::default();
+        universe.metadata.name = String::from("Test system");
+        universe.metadata.author = String::from("Tester");
+
+        let mut add = |name: &str, mass, radius, orbit, parent| {
+            let body = Body::new(name.to_owned(), mass, radius, orbit);
+            universe.add_body(body, parent).unwrap()
+        };
+        let sun = add("Sun", 2e30, 7e8, None, None);
+        let earth_orbit = Orbit::new(0.0167, 1.47e11, 0.0, 0.0, 0.0, 0.0, 1.0);
+        let earth = add("Earth", 6e24, 6.4e6, Some(earth_orbit), Some(sun));
+        let moon_orbit = Orbit::new(0.055, 3.6e8, 0.09, 0.0, 0.0, 0.0, 1.0);
+        add("Moon", 7e22, 1.7e6, Some(moon_orbit), Some(earth));
+
+        universe
+    }
+
+    /// The text of each `### Body` section, by name.
+    fn sections(summary: &str) -> Vec<(&str, &str)> {
+        summary
+            .split("\n### ")
+            .skip(1)
+            .map(|section| section.split_once('\n').unwrap_or((section, "")))
+            .collect()
+    }
+
+    #[test]
+    fn summary_has_every_part() {
+        let summary = universe_summary(&system());
+
+        assert!(summary.starts_with("# Test system\n"));
+        assert!(summary.contains("## Settings\n"));
+        assert!(summary.contains("- Author: Tester\n"));
+        assert!(summary.contains("- Bodies: 3\n"));
+        assert!(summary.contains("## Hierarchy\n\n- Sun\n  - Earth\n    - Moon\n"));
+    }
+
+    #[test]
+    fn bodies_are_listed_parents_first() {
+        let summary = universe_summary(&system());
+        let sections = sections(&summary);
+
+        let names: Vec<&str> = sections.iter().map(|(name, _)| *name).collect();
+        assert_eq!(names, ["Sun", "Earth", "Moon"]);
+
+        let (_, sun) = sections[0];
+        assert!(sun.contains("Root body."));
+        assert!(sun.contains("| Mass | "));
+        assert!(!sun.contains("| Eccentricity | "));
+
+        let (_, moon) = sections[2];
+        assert!(moon.contains("Orbits Earth."));
+        assert!(moon.contains("| Eccentricity | "));
+        assert!(moon.contains("| Inclination | "));
+    }
+
+    #[test]
+    fn unnamed_universes_get_a_heading() {
+        let mut universe = system();
+        universe.metadata.name = String::from("  ");
+        assert!(universe_summary(&universe).starts_with("# Unnamed universe\n"));
+    }
+}
@@ -0,0 +1,285 @@
+use strum::IntoEnumIterator;
+use three_d::egui::{
+    Button, Color32, ComboBox, Context as EguiContext, CursorIcon, DragValue, Grid, Id as EguiId,
+    PopupCloseBehavior, RichText, TextWrapMode, Ui, Window,
+};
+
+use crate::{
+    gui::{
+        SimState, celestials::selectable_body_tree, declare_id, help::HelpText,
+        text_style::TextRole,
+    },
+    sim::{
+        constraint::{self, Constraint, ConstraintAddError, Parameter, ParameterRef},
+        universe::{Id as UniverseId, Universe},
+    },
+};
+
+declare_id!(salt_only, CONSTRAINT_LIST_GRID, b"LinkList");
+declare_id!(salt_only, CONSTRAINT_NEW_GRID, b"LinkNew!");
+declare_id!(salt_only, CONSTRAINT_TARGET_BODY_COMBO_BOX, b"LinkTgtB");
+declare_id!(salt_only, CONSTRAINT_TARGET_PARAM_COMBO_BOX, b"LinkTgtP");
+declare_id!(salt_only, CONSTRAINT_SOURCE_BODY_COMBO_BOX, b"LinkSrcB");
+declare_id!(salt_only, CONSTRAINT_SOURCE_PARAM_COMBO_BOX, b"LinkSrcP");
+declare_id!(CONSTRAINT_TARGET_TREE, b"LinkTgtT");
+declare_id!(CONSTRAINT_SOURCE_TREE, b"LinkSrcT");
+
+pub(super) struct ConstraintsWindowState {
+    pub(super) open: bool,
+    target_body: Option<UniverseId>,
+    target_parameter: Parameter,
+    source_body: Option<UniverseId>,
+    source_parameter: Parameter,
+    factor: f64,
+    add_error: Option<ConstraintAddError>,
+}
+
+impl Default for ConstraintsWindowState {
+    fn default() -> Self {
+        Self {
+            open: false,
+            target_body: None,
+            target_parameter: Parameter::Period,
+            source_body: None,
+            source_parameter: Parameter::Period,
+            factor: 2.0,
+            add_error: None,
+        }
+    }
+}
+
+pub(super) fn draw(ctx: &EguiContext, sim_state: &mut SimState) {
+    let mut open = sim_state.ui.constraints_window_state.open;
+
+    Window::new("Constraints")
+        .open(&mut open)
+        .default_width(360.0)
+        .vscroll(true)
+        .show(ctx, |ui| {
+            window_contents(
+                ui,
+                &mut sim_state.universe,
+                &mut sim_state.ui.constraints_window_state,
+            )
+        });
+
+    sim_state.ui.constraints_window_state.open &= open;
+}
+
+fn window_contents(ui: &mut Ui, universe: &mut Universe, state: &mut ConstraintsWindowState) {
+    ui.visuals_mut().override_text_color = Some(Color32::WHITE);
+
+    let constraints = universe.constraints.clone();
+    constraint_list(ui, universe);
+    if universe.constraints != constraints {
+        universe.mark_edited();
+    }
+
+    ui.separator();
+
+    let text = RichText::new("New Constraint")
+        .underline()
+        .text_style(TextRole::Body.into());
+    ui.label(text);
+    ui.add_space(8.0);
+
+    Grid::new(CONSTRAINT_NEW_GRID_SALT)
+        .num_columns(2)
+        .spacing([40.0, 4.0])
+        .striped(true)
+        .show(ui, |ui| new_constraint_grid(ui, universe, state));
+
+    let (Some(target_body), Some(source_body)) = (state.target_body, state.source_body) else {
+        ui.label("Pick a target and source body to add a constraint.");
+        return;
+    };
+
+    let add_button = ui.button("Add constraint").on_help_text(
+        RichText::new(
+            "Keep the target parameter at the factor times the source parameter.\n\
+            The target follows whenever the source changes.",
+        )
+        .color(Color32::WHITE)
+        .text_style(TextRole::Body.into()),
+    );
+
+    if add_button.clicked() {
+        let constraint = Constraint {
+            enabled: true,
+            target: ParameterRef {
+                body: target_body,
+                parameter: state.target_parameter,
+            },
+            source: ParameterRef {
+                body: source_body,
+                parameter: state.source_parameter,
+            },
+            factor: state.factor,
+        };
+
+        state.add_error = constraint::add_constraint(universe, constraint).err();
+        if state.add_error.is_none() {
+            universe.mark_edited();
+        }
+    }
+
+    if let Some(e) = state.add_error {
+        ui.label(RichText::new(format!("⚠ Can't add: {e}")).color(Color32::YELLOW));
+    }
+}
+
+fn constraint_list(ui: &mut Ui, universe: &mut Universe) {
+    if universe.constraints.is_empty() {
+        ui.label("No constraints yet.");
+        return;
+    }
+
+    let mut to_delete = None;
+
+    Grid::new(CONSTRAINT_LIST_GRID_SALT)
+        .num_columns(3)
+        .spacing([8.0, 4.0])
+        .striped(true)
+        .show(ui, |ui| {
+            for index in 0..universe.constraints.len() {
+                let constraint = universe.constraints[index];
+                let mut enabled = constraint.enabled;
+
+                ui.checkbox(&mut enabled, "").on_help_text(
+                    RichText::new("Whether this constraint is applied.")
+                        .color(Color32::WHITE)
+                        .text_style(TextRole::Body.into()),
+                );
+
+                let description = RichText::new(constraint.describe(universe));
+                if !constraint.is_valid(universe) {
+                    ui.label(description.color(Color32::GRAY))
+                        .on_help_text(
+                            RichText::new(
+                                "One of the parameters doesn't exist right now, \
+                                e.g. the period of an open orbit, so this constraint is skipped.",
+                            )
+                            .color(Color32::WHITE)
+                            .text_style(TextRole::Body.into()),
+                        )
+                        .on_hover_cursor(CursorIcon::Help);
+                } else if let Err(error) = constraint.check(universe) {
+                    ui.label(description.color(Color32::LIGHT_RED))
+                        .on_help_text(
+                            RichText::new(format!("This constraint is skipped because {error}."))
+                                .color(Color32::WHITE)
+                                .text_style(TextRole::Body.into()),
+                        )
+                        .on_hover_cursor(CursorIcon::Help);
+                } else {
+                    ui.label(description);
+                }
+
+                if ui.button("Delete").clicked() {
+                    to_delete = Some(index);
+                }
+
+                universe.constraints[index].enabled = enabled;
+                ui.end_row();
+            }
+        });
+
+    if let Some(index) = to_delete {
+        universe.constraints.remove(index);
+    }
+}
+
+fn new_constraint_grid(ui: &mut Ui, universe: &Universe, state: &mut ConstraintsWindowState) {
+    ui.label("Target")
+        .on_help_text(
+            RichText::new("The parameter that will be driven by the constraint.")
+                .color(Color32::WHITE)
+                .text_style(TextRole::Body.into()),
+        )
+        .on_hover_cursor(CursorIcon::Help);
+    ui.horizontal(|ui| {
+        body_combo_box(
+            ui,
+            CONSTRAINT_TARGET_BODY_COMBO_BOX_SALT,
+            *CONSTRAINT_TARGET_TREE_ID,
+            universe,
+            &mut state.target_body,
+        );
+        parameter_combo_box(
+            ui,
+            CONSTRAINT_TARGET_PARAM_COMBO_BOX_SALT,
+            &mut state.target_parameter,
+        );
+    });
+    ui.end_row();
+
+    ui.label("Factor")
+        .on_help_text(
+            RichText::new(
+                "What to multiply the source by.\n\
+                For example, a factor of 2 between two periods keeps them in a 1:2 ratio.",
+            )
+            .color(Color32::WHITE)
+            .text_style(TextRole::Body.into()),
+        )
+        .on_hover_cursor(CursorIcon::Help);
+    ui.add(DragValue::new(&mut state.factor).speed(0.01).prefix("× "));
+    ui.end_row();
+
+    ui.label("Source")
+        .on_help_text(
+            RichText::new("The parameter the target follows.")
+                .color(Color32::WHITE)
+                .text_style(TextRole::Body.into()),
+        )
+        .on_hover_cursor(CursorIcon::Help);
+    ui.horizontal(|ui| {
+        body_combo_box(
+            ui,
+            CONSTRAINT_SOURCE_BODY_COMBO_BOX_SALT,
+            *CONSTRAINT_SOURCE_TREE_ID,
+            universe,
+            &mut state.source_body,
+        );
+        parameter_combo_box(
+            ui,
+            CONSTRAINT_SOURCE_PARAM_COMBO_BOX_SALT,
+            &mut state.source_parameter,
+        );
+    });
+    ui.end_row();
+}
+
+fn body_combo_box(
+    ui: &mut Ui,
+    salt: impl core::hash::Hash,
+    tree_id: EguiId,
+    universe: &Universe,
+    selected: &mut Option<UniverseId>,
+) {
+    ComboBox::from_id_salt(salt)
+        .close_behavior(PopupCloseBehavior::CloseOnClickOutside)
+        .wrap_mode(TextWrapMode::Extend)
+        .selected_text(
+            selected
+                .and_then(|id| universe.get_body(id))
+                .map(|w| &*w.body.name)
+                .unwrap_or("—"),
+        )
+        .show_ui(ui, |ui| {
+            selectable_body_tree(ui, tree_id, universe, selected, None);
+        });
+}
+
+fn parameter_combo_box(ui: &mut Ui, salt: impl core::hash::Hash, selected: &mut Parameter) {
+    ComboBox::from_id_salt(salt)
+        .selected_text(selected.name())
+        .show_ui(ui, |ui| {
+            for parameter in Parameter::iter() {
+                let button = Button::selectable(*selected == parameter, parameter.name());
+                if ui.add(button).clicked() {
+                    *selected = parameter;
+                }
+            }
+        });
+}
@@ -0,0 +1,266 @@
+use float_pretty_print::PrettyPrintFloat;
+use glam::DVec3;
+use three_d::egui::{
+    Button, Color32, Context as EguiContext, CursorIcon, DragValue, Grid, RichText, Ui, Window,
+};
+
+use crate::{
+    gui::{UiState, declare_id, help::HelpText, text_style::TextRole},
+    sim::conversion::{self, OrbitalElements},
+};
+
+declare_id!(salt_only, CONVERTER_INPUT_GRID, b"InConvrt");
+declare_id!(salt_only, CONVERTER_OUTPUT_GRID, b"OutConvr");
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum ConversionDirection {
+    ElementsToVectors,
+    VectorsToElements,
+}
+
+pub(super) struct ConverterWindowState {
+    pub(super) open: bool,
+    direction: ConversionDirection,
+    /// Gravitational parameter, in m^3 s^-2.
+    mu: f64,
+    time: f64,
+    elements: OrbitalElements,
+    position: DVec3,
+    velocity: DVec3,
+}
+
+impl Default for ConverterWindowState {
+    fn default() -> Self {
+        // Earth's µ and a low-Earth orbit
+        let mu = 3.986004418e14;
+        let elements = OrbitalElements {
+            periapsis: 6.771e6,
+            ..Default::default()
+        };
+        let (position, velocity) = conversion::elements_to_state_vectors(elements, mu, 0.0);
+
+        Self {
+            open: false,
+            direction: ConversionDirection::ElementsToVectors,
+            mu,
+            time: 0.0,
+            elements,
+            position,
+            velocity,
+        }
+    }
+}
+
+pub(super) fn draw(ctx: &EguiContext, ui_state: &mut UiState) {
+    let state = &mut ui_state.converter_window_state;
+    let mut open = state.open;
+
+    Window::new("Orbit Converter")
+        .open(&mut open)
+        .default_width(360.0)
+        .vscroll(true)
+        .show(ctx, |ui| window_contents(ui, state));
+
+    state.open &= open;
+}
+
+fn window_contents(ui: &mut Ui, state: &mut ConverterWindowState) {
+    ui.visuals_mut().override_text_color = Some(Color32::WHITE);
+
+    ui.horizontal(|ui| {
+        let directions = [
+            (ConversionDirection::ElementsToVectors, "Elements → vectors"),
+            (ConversionDirection::VectorsToElements, "Vectors → elements"),
+        ];
+        for (direction, text) in directions {
+            let button = Button::selectable(state.direction == direction, text);
+            if ui.add(button).clicked() {
+                state.direction = direction;
+            }
+        }
+    });
+
+    ui.separator();
+
+    Grid::new(CONVERTER_INPUT_GRID_SALT)
+        .num_columns(2)
+        .spacing([40.0, 4.0])
+        .striped(true)
+        .show(ui, |ui| {
+            labelled_number(
+                ui,
+                "µ",
+                "Gravitational parameter of the parent body, in m³/s².",
+                &mut state.mu,
+                "",
+            );
+            labelled_number(
+                ui,
+                "Time",
+                "The time to get the state vectors at, in seconds.",
+                &mut state.time,
+                " s",
+            );
+            match state.direction {
+                ConversionDirection::ElementsToVectors => elements_input(ui, &mut state.elements),
+                ConversionDirection::VectorsToElements => {
+                    vector_input(ui, "Position", " m", &mut state.position);
+                    vector_input(ui, "Velocity", " m/s", &mut state.velocity);
+                }
+            }
+        });
+
+    state.mu = state.mu.max(f64::MIN_POSITIVE);
+
+    ui.separator();
+    ui.heading("Result");
+
+    let result = match state.direction {
+        ConversionDirection::ElementsToVectors => {
+            let (position, velocity) =
+                conversion::elements_to_state_vectors(state.elements, state.mu, state.time);
+            vec![
+                ("Position X", position.x, "m"),
+                ("Position Y", position.y, "m"),
+                ("Position Z", position.z, "m"),
+                ("Velocity X", velocity.x, "m/s"),
+                ("Velocity Y", velocity.y, "m/s"),
+                ("Velocity Z", velocity.z, "m/s"),
+            ]
+        }
+        ConversionDirection::VectorsToElements => {
+            let elements = conversion::state_vectors_to_elements(
+                state.position,
+                state.velocity,
+                state.mu,
+                state.time,
+            );
+            vec![
+                ("Eccentricity", elements.eccentricity, ""),
+                ("Periapsis", elements.periapsis, "m"),
+                ("Inclination", elements.inclination.to_degrees(), "°"),
+                ("Arg. of Pe.", elements.arg_pe.to_degrees(), "°"),
+                ("RAAN", elements.long_asc_node.to_degrees(), "°"),
+                ("Mean anom.", elements.mean_anomaly.to_degrees(), "°"),
+            ]
+        }
+    };
+
+    Grid::new(CONVERTER_OUTPUT_GRID_SALT)
+        .num_columns(2)
+        .spacing([40.0, 4.0])
+        .striped(true)
+        .show(ui, |ui| {
+            for (name, value, unit) in &result {
+                ui.label(*name);
+                ui.label(format!("{} {unit}", PrettyPrintFloat(*value)));
+                ui.end_row();
+            }
+        });
+
+    let copy_button = ui.button("Copy results").on_help_text(
+        RichText::new("Copy the results above to the clipboard.")
+            .color(Color32::WHITE)
+            .text_style(TextRole::Body.into()),
+    );
+
+    if copy_button.clicked() {
+        let text = result
+            .iter()
+            .map(|(name, value, unit)| format!("{name}: {value} {unit}"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        ui.ctx().copy_text(text);
+    }
+}
+
+pub(super) fn labelled_number(
+    ui: &mut Ui,
+    name: &str,
+    tooltip: &str,
+    value: &mut f64,
+    suffix: &str,
+) {
+    ui.label(name)
+        .on_help_text(
+            RichText::new(tooltip)
+                .color(Color32::WHITE)
+                .text_style(TextRole::Body.into()),
+        )
+        .on_hover_cursor(CursorIcon::Help);
+    let speed = (value.abs() * 4e-3).max(1e-3);
+    let dv = DragValue::new(value)
+        .custom_formatter(|num, _| format!("{:3.8}", PrettyPrintFloat(num)))
+        .speed(speed)
+        .suffix(suffix);
+    ui.add_sized((ui.available_width(), 18.0), dv);
+    ui.end_row();
+}
+
+fn angle_input(ui: &mut Ui, name: &str, tooltip: &str, radians: &mut f64) {
+    let mut degrees = radians.to_degrees();
+    labelled_number(ui, name, tooltip, &mut degrees, "°");
+    if degrees != radians.to_degrees() {
+        *radians = degrees.to_radians();
+    }
+}
+
+fn elements_input(ui: &mut Ui, elements: &mut OrbitalElements) {
+    labelled_number(
+        ui,
+        "Eccentricity",
+        "How eccentric the orbit is.\n\
+        An eccentricity of 1 (parabolic) is not supported.",
+        &mut elements.eccentricity,
+        "",
+    );
+    elements.eccentricity = elements.eccentricity.max(0.0);
+    labelled_number(
+        ui,
+        "Periapsis",
+        "The minimum distance of the orbit to the center of the parent body.",
+        &mut elements.periapsis,
+        " m",
+    );
+    elements.periapsis = elements.periapsis.max(f64::MIN_POSITIVE);
+    angle_input(
+        ui,
+        "Inclination",
+        "How inclined from the up axis the orbit is.",
+        &mut elements.inclination,
+    );
+    angle_input(
+        ui,
+        "Arg. of Pe.",
+        "The angle offset of the periapsis along the orbital plane.",
+        &mut elements.arg_pe,
+    );
+    angle_input(
+        ui,
+        "RAAN",
+        "The right ascension of the ascending node.",
+        &mut elements.long_asc_node,
+    );
+    angle_input(
+        ui,
+        "Mean anom.",
+        "Mean anomaly at epoch (time = 0).",
+        &mut elements.mean_anomaly,
+    );
+}
+
+fn vector_input(ui: &mut Ui, name: &str, suffix: &str, vector: &mut DVec3) {
+    for (axis, component) in [
+        ("X", &mut vector.x),
+        ("Y", &mut vector.y),
+        ("Z", &mut vector.z),
+    ] {
+        labelled_number(
+            ui,
+            &format!("{name} {axis}"),
+            &format!("The {axis} component of the {}.", name.to_lowercase()),
+            component,
+            suffix,
+        );
+    }
+}
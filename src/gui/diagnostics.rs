@@ -0,0 +1,234 @@
+use strum::IntoEnumIterator;
+use three_d::egui::{
+    Color32, Context as EguiContext, CursorIcon, DragValue, Grid, RichText, Ui, Window,
+};
+
+use crate::{
+    cfg::CONFIG,
+    gfx::object_conversion::TRAJECTORY_POINT_BUDGET,
+    gui::{UiState, declare_id, help::HelpText, text_style::TextRole},
+    history::{self, HistoryUsage, MAX_LIMIT_KIB, MIN_LIMIT_KIB},
+    logging,
+    metrics::{self, Feature},
+    notify::NOTIFIER,
+};
+
+declare_id!(salt_only, DIAGNOSTICS_PERF_GRID, b"Perf0rm!");
+declare_id!(salt_only, DIAGNOSTICS_USAGE_GRID, b"UsageCnt");
+declare_id!(salt_only, DIAGNOSTICS_MEMORY_GRID, b"MemUsage");
+
+pub(super) fn draw(ctx: &EguiContext, ui_state: &mut UiState) {
+    Window::new("Diagnostics")
+        .open(&mut ui_state.is_diagnostics_open)
+        .default_width(320.0)
+        .vscroll(true)
+        .show(ctx, window_contents);
+}
+
+fn window_contents(ui: &mut Ui) {
+    ui.label(
+        "Record frame times, body counts and how often some features are used, \
+        to include concrete numbers when reporting a performance problem.",
+    );
+    ui.label(
+        RichText::new("Everything stays on this device. Nothing is ever sent anywhere.").strong(),
+    );
+
+    let initial_enabled = CONFIG
+        .try_lock()
+        .map(|cfg| cfg.metrics_enabled.get())
+        .unwrap_or(false);
+    let mut enabled = initial_enabled;
+    ui.checkbox(&mut enabled, "Record statistics");
+    if enabled != initial_enabled {
+        metrics::set_enabled(enabled);
+    }
+
+    let metrics = metrics::snapshot();
+
+    ui.separator();
+    Grid::new(DIAGNOSTICS_PERF_GRID_SALT)
+        .num_columns(2)
+        .spacing([40.0, 4.0])
+        .striped(true)
+        .show(ui, |ui| {
+            ui.label("Frames");
+            ui.label(metrics.frames.to_string());
+            ui.end_row();
+
+            ui.label("Average frame time");
+            if metrics.frames == 0 {
+                ui.label("—");
+            } else {
+                ui.label(format!("{:.2} ms", metrics.average_frame_time_ms()));
+            }
+            ui.end_row();
+
+            ui.label("Slowest frame");
+            ui.label(format!("{:.1} ms", metrics.slowest_frame_ms));
+            ui.end_row();
+
+            ui.label("Most bodies")
+                .on_help_text(
+                    RichText::new("The most bodies a universe had while recording.")
+                        .color(Color32::WHITE)
+                        .text_style(TextRole::Body.into()),
+                )
+                .on_hover_cursor(CursorIcon::Help);
+            ui.label(metrics.max_body_count.to_string());
+            ui.end_row();
+
+            ui.label("Most orbit line points")
+                .on_help_text(
+                    RichText::new(format!(
+                        "The most points generated for all orbit lines in one frame.\n\
+                        They are limited to {} in total; when the visible orbits \
+                        want more, each gets a share based on its size on screen.",
+                        TRAJECTORY_POINT_BUDGET
+                    ))
+                    .color(Color32::WHITE)
+                    .text_style(TextRole::Body.into()),
+                )
+                .on_hover_cursor(CursorIcon::Help);
+            ui.label(metrics.max_trajectory_points.to_string());
+            ui.end_row();
+
+            ui.label("Frames over the point budget")
+                .on_help_text(
+                    RichText::new(
+                        "How many frames had their orbit lines drawn with fewer points \
+                        to stay within the budget.",
+                    )
+                    .color(Color32::WHITE)
+                    .text_style(TextRole::Body.into()),
+                )
+                .on_hover_cursor(CursorIcon::Help);
+            let share = if metrics.frames == 0 {
+                0.0
+            } else {
+                metrics.point_budget_frames as f64 / metrics.frames as f64 * 100.0
+            };
+            ui.label(format!("{} ({share:.1}%)", metrics.point_budget_frames));
+            ui.end_row();
+
+            for (label, count) in metrics::histogram_labels()
+                .iter()
+                .zip(metrics.frame_time_histogram)
+            {
+                ui.label(format!("Frames {label}"));
+                let share = if metrics.frames == 0 {
+                    0.0
+                } else {
+                    count as f64 / metrics.frames as f64 * 100.0
+                };
+                ui.label(format!("{count} ({share:.1}%)"));
+                ui.end_row();
+            }
+        });
+
+    ui.separator();
+    Grid::new(DIAGNOSTICS_USAGE_GRID_SALT)
+        .num_columns(2)
+        .spacing([40.0, 4.0])
+        .striped(true)
+        .show(ui, |ui| {
+            for feature in Feature::iter() {
+                ui.label(feature.name());
+                ui.label(metrics.uses(feature).to_string());
+                ui.end_row();
+            }
+        });
+
+    ui.separator();
+    memory_grid(ui);
+
+    ui.separator();
+    ui.horizontal(|ui| {
+        let copy_button = ui.button("Copy report").on_help_text(
+            RichText::new("Copy these statistics to the clipboard as text.")
+                .color(Color32::WHITE)
+                .text_style(TextRole::Body.into()),
+        );
+        if copy_button.clicked() {
+            ui.ctx().copy_text(metrics.to_report());
+        }
+
+        let clear_button = ui.button("Clear").on_help_text(
+            RichText::new("Forget everything recorded so far.")
+                .color(Color32::WHITE)
+                .text_style(TextRole::Body.into()),
+        );
+        if clear_button.clicked() {
+            metrics::clear();
+        }
+    });
+}
+
+fn memory_grid(ui: &mut Ui) {
+    let (log_kib, event_log_kib) = CONFIG
+        .try_lock()
+        .map(|cfg| (cfg.log_history_kib.get(), cfg.event_log_kib.get()))
+        .unwrap_or((
+            history::DEFAULT_LOG_LIMIT_KIB,
+            history::DEFAULT_EVENT_LOG_LIMIT_KIB,
+        ));
+    let log_usage = logging::usage();
+    let event_log_usage = NOTIFIER
+        .try_lock()
+        .map(|notifier| notifier.usage())
+        .unwrap_or_default();
+
+    Grid::new(DIAGNOSTICS_MEMORY_GRID_SALT)
+        .num_columns(3)
+        .spacing([20.0, 4.0])
+        .striped(true)
+        .show(ui, |ui| {
+            ui.label("Log window")
+                .on_help_text(
+                    RichText::new(
+                        "How much memory the Log window's records take up, and the most \
+                        they may take up before the oldest are forgotten.\n\
+                        Records are stored as only what differs from the one before, \
+                        so repeated messages take up very little.",
+                    )
+                    .color(Color32::WHITE)
+                    .text_style(TextRole::Body.into()),
+                )
+                .on_hover_cursor(CursorIcon::Help);
+            ui.label(usage_text(log_usage));
+            if let Some(kib) = limit_input(ui, log_kib) {
+                history::set_log_limit_kib(kib);
+            }
+            ui.end_row();
+
+            ui.label("Event log");
+            ui.label(usage_text(event_log_usage));
+            if let Some(kib) = limit_input(ui, event_log_kib) {
+                history::set_event_log_limit_kib(kib);
+            }
+            ui.end_row();
+        });
+}
+
+fn usage_text(usage: HistoryUsage) -> String {
+    let kib = |bytes: usize| bytes as f64 / 1024.0;
+    format!(
+        "{} records, {:.1} KiB ({:.1} KiB uncompressed)",
+        usage.records,
+        kib(usage.stored_bytes),
+        kib(usage.raw_bytes)
+    )
+}
+
+/// Shows the byte limit of a history, returning the new limit if it was changed.
+fn limit_input(ui: &mut Ui, kib: u32) -> Option<u32> {
+    let mut new_kib = kib;
+    ui.add(
+        DragValue::new(&mut new_kib)
+            .range(MIN_LIMIT_KIB..=MAX_LIMIT_KIB)
+            .speed(4.0)
+            .prefix("max ")
+            .suffix(" KiB"),
+    );
+    (new_kib != kib).then_some(new_kib)
+}
@@ -0,0 +1,214 @@
+use three_d::egui::{
+    Button, Color32, ComboBox, Context as EguiContext, CursorIcon, Grid, PopupCloseBehavior,
+    RichText, TextWrapMode, Ui, Window,
+};
+
+use crate::{
+    gui::{
+        SimState, celestials::selectable_body_tree, declare_id, help::HelpText,
+        text_style::TextRole, unit_dv::drag_value_with_unit,
+    },
+    sim::{
+        ephemeris::{self, EphemerisError},
+        universe::{Id as UniverseId, Universe},
+    },
+    units::{AutoUnit, time::TimeUnit},
+};
+
+declare_id!(salt_only, EPHEMERIS_TOOL_GRID, b"EphmGrid");
+declare_id!(salt_only, EPHEMERIS_BODY_COMBO_BOX, b"EphmBody");
+declare_id!(salt_only, EPHEMERIS_SPAN, b"EphmSpan");
+declare_id!(salt_only, EPHEMERIS_STEP, b"EphmStep");
+declare_id!(EPHEMERIS_BODY_TREE, b"EphmTree");
+
+/// The span the tool starts with, in seconds.
+const DEFAULT_SPAN: f64 = 30.0 * 86400.0;
+/// The step the tool starts with, in seconds.
+const DEFAULT_STEP: f64 = 86400.0;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum ExportFormat {
+    #[default]
+    Csv,
+    Json,
+}
+
+pub(super) struct EphemerisToolState {
+    pub(super) open: bool,
+    body: Option<UniverseId>,
+    /// In seconds.
+    span: f64,
+    span_unit: AutoUnit<TimeUnit>,
+    /// In seconds.
+    step: f64,
+    step_unit: AutoUnit<TimeUnit>,
+    format: ExportFormat,
+    /// How many rows were copied last, or why they couldn't be.
+    last_export: Option<Result<usize, EphemerisError>>,
+}
+
+impl Default for EphemerisToolState {
+    fn default() -> Self {
+        Self {
+            open: false,
+            body: None,
+            span: DEFAULT_SPAN,
+            span_unit: AutoUnit {
+                auto: true,
+                unit: TimeUnit::Days,
+            },
+            step: DEFAULT_STEP,
+            step_unit: AutoUnit {
+                auto: true,
+                unit: TimeUnit::Days,
+            },
+            format: ExportFormat::default(),
+            last_export: None,
+        }
+    }
+}
+
+pub(super) fn draw(ctx: &EguiContext, sim_state: &mut SimState) {
+    let mut open = sim_state.ui.ephemeris_tool_state.open;
+
+    Window::new("Ephemeris Export")
+        .open(&mut open)
+        .default_width(320.0)
+        .show(ctx, |ui| window_contents(ui, sim_state));
+
+    sim_state.ui.ephemeris_tool_state.open &= open;
+}
+
+fn window_contents(ui: &mut Ui, sim_state: &mut SimState) {
+    ui.visuals_mut().override_text_color = Some(Color32::WHITE);
+
+    let universe = &sim_state.universe;
+    let state = &mut sim_state.ui.ephemeris_tool_state;
+
+    // Forget deleted bodies
+    if state.body.is_some_and(|id| universe.get_body(id).is_none()) {
+        state.body = None;
+    }
+
+    Grid::new(EPHEMERIS_TOOL_GRID_SALT)
+        .num_columns(2)
+        .spacing([40.0, 4.0])
+        .striped(true)
+        .show(ui, |ui| ephemeris_grid(ui, universe, state));
+
+    let rows = ephemeris::row_count(state.span, state.step);
+    match rows {
+        Ok(rows) => ui.label(format!("{rows} rows, starting now")),
+        Err(e) => ui.label(RichText::new(format!("⚠ {e}")).color(Color32::YELLOW)),
+    };
+
+    let Some(body) = state.body else {
+        ui.label("Pick a body to export.");
+        return;
+    };
+
+    let copy_button = ui
+        .add_enabled(rows.is_ok(), Button::new("Copy table"))
+        .on_help_text(
+            RichText::new(
+                "Copy the table to the clipboard, \
+                to paste into a spreadsheet or compare with another tool.",
+            )
+            .color(Color32::WHITE)
+            .text_style(TextRole::Body.into()),
+        );
+
+    if copy_button.clicked() {
+        let result = ephemeris::sample(universe, body, universe.time, state.span, state.step);
+        state.last_export = Some(result.as_ref().map(Vec::len).map_err(|e| *e));
+        if let Ok(rows) = result {
+            let text = match state.format {
+                ExportFormat::Csv => ephemeris::to_csv(&rows),
+                ExportFormat::Json => ephemeris::to_json(&rows),
+            };
+            ui.ctx().copy_text(text);
+        }
+    }
+
+    match state.last_export {
+        Some(Ok(rows)) => {
+            ui.label(format!("Copied {rows} rows."));
+        }
+        Some(Err(e)) => {
+            ui.label(RichText::new(format!("⚠ Can't export: {e}")).color(Color32::YELLOW));
+        }
+        None => {}
+    }
+}
+
+fn ephemeris_grid(ui: &mut Ui, universe: &Universe, state: &mut EphemerisToolState) {
+    ui.label("Body")
+        .on_help_text(
+            RichText::new(
+                "The body to sample.\n\
+                Positions and velocities are relative to its parent.",
+            )
+            .color(Color32::WHITE)
+            .text_style(TextRole::Body.into()),
+        )
+        .on_hover_cursor(CursorIcon::Help);
+    let body_name = state
+        .body
+        .and_then(|id| universe.get_body(id))
+        .map_or("Pick a body", |w| w.body.name.as_str());
+    ComboBox::from_id_salt(EPHEMERIS_BODY_COMBO_BOX_SALT)
+        .close_behavior(PopupCloseBehavior::CloseOnClickOutside)
+        .wrap_mode(TextWrapMode::Extend)
+        .selected_text(body_name)
+        .show_ui(ui, |ui| {
+            selectable_body_tree(ui, *EPHEMERIS_BODY_TREE_ID, universe, &mut state.body, None);
+        });
+    ui.end_row();
+
+    ui.label("Span")
+        .on_help_text(
+            RichText::new("How far ahead of the current time to sample.")
+                .color(Color32::WHITE)
+                .text_style(TextRole::Body.into()),
+        )
+        .on_hover_cursor(CursorIcon::Help);
+    drag_value_with_unit(
+        EPHEMERIS_SPAN_SALT,
+        ui,
+        &mut state.span,
+        &mut state.span_unit,
+    );
+    ui.end_row();
+
+    ui.label("Step")
+        .on_help_text(
+            RichText::new("How much time passes between rows.")
+                .color(Color32::WHITE)
+                .text_style(TextRole::Body.into()),
+        )
+        .on_hover_cursor(CursorIcon::Help);
+    drag_value_with_unit(
+        EPHEMERIS_STEP_SALT,
+        ui,
+        &mut state.step,
+        &mut state.step_unit,
+    );
+    ui.end_row();
+
+    ui.label("Format")
+        .on_help_text(
+            RichText::new(
+                "CSV has a header line and one row per sample. \
+                JSON is an array of objects.\n\
+                Times are in seconds, distances in meters and speeds in meters per second.",
+            )
+            .color(Color32::WHITE)
+            .text_style(TextRole::Body.into()),
+        )
+        .on_hover_cursor(CursorIcon::Help);
+    ui.horizontal(|ui| {
+        ui.selectable_value(&mut state.format, ExportFormat::Csv, "CSV");
+        ui.selectable_value(&mut state.format, ExportFormat::Json, "JSON");
+    });
+    ui.end_row();
+}
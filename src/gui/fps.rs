@@ -4,9 +4,9 @@ use std::{
 };
 
 use ordered_float::NotNan;
-use three_d::egui::{Area, Color32, Context, FontId, Label, RichText, TextWrapMode, Ui};
+use three_d::egui::{Area, Color32, Context, Label, RichText, TextWrapMode, Ui, Vec2};
 
-use crate::gui::declare_id;
+use crate::gui::{declare_id, text_style::TextRole};
 
 declare_id!(FPS_AREA, b"PerfArea");
 
@@ -62,7 +62,7 @@ pub(super) fn fps_area(ctx: &Context, frame_data: &FrameData) {
     let pos = 12.0;
     Area::new(*FPS_AREA_ID)
         .constrain_to(ctx.screen_rect())
-        .fixed_pos((pos, pos))
+        .fixed_pos(ctx.available_rect().min + Vec2::splat(pos))
         .default_width(1000.0)
         .show(&ctx, |ui| fps_inner(ui, frame_data));
 }
@@ -77,11 +77,10 @@ fn fps_inner(ui: &mut Ui, frame_data: &FrameData) {
         format!("FPS: {fps:.0}\n1%L: {low:.0}")
     };
     const BACKGROUND_COLOR: Color32 = Color32::from_rgba_premultiplied(0, 0, 0, 128);
-    let font = FontId::monospace(11.0);
     let text = RichText::new(string)
         .background_color(BACKGROUND_COLOR)
         .color(Color32::WHITE)
-        .font(font);
+        .text_style(TextRole::CaptionMonospace.into());
     let label = Label::new(text)
         .wrap_mode(TextWrapMode::Extend)
         .selectable(false);
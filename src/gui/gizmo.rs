@@ -0,0 +1,342 @@
+use core::f64::consts::{PI, TAU};
+use std::collections::HashMap;
+
+use glam::DVec3;
+use keplerian_sim::{Orbit, OrbitTrait};
+use three_d::egui::{
+    Area, Color32, Context, CursorIcon, LayerId, Order, Pos2, Response, Sense, Stroke, Vec2,
+};
+
+use crate::{
+    gfx::projection::WorldProjector,
+    gui::{MIN_TOUCH_TARGET_LEN, SimState, declare_id},
+    sim::universe::Id as UniverseId,
+};
+
+declare_id!(GIZMO_HANDLE_AREA, b"GizmoHdl");
+
+const HANDLE_RADIUS: f32 = 8.0;
+
+/// How much the inclination changes per point of vertical drag, in radians.
+const INCLINATION_PER_POINT: f64 = PI / 360.0;
+
+/// The increment angles are snapped to, in radians.
+const ANGLE_SNAP: f64 = 5.0 * PI / 180.0;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+enum GizmoHandle {
+    /// Moves along the apsis line to change the periapsis distance.
+    Periapsis,
+    /// Rotates around the orbit normal to change the argument of periapsis.
+    Apsis,
+    /// Rotates around the up axis to change the RAAN.
+    AscendingNode,
+    /// Drags vertically to tilt the orbit.
+    Inclination,
+}
+
+impl GizmoHandle {
+    const ALL: [Self; 4] = [
+        Self::Periapsis,
+        Self::Apsis,
+        Self::AscendingNode,
+        Self::Inclination,
+    ];
+
+    const fn color(self) -> Color32 {
+        match self {
+            GizmoHandle::Periapsis => Color32::from_rgb(255, 96, 96),
+            GizmoHandle::Apsis => Color32::from_rgb(255, 192, 96),
+            GizmoHandle::AscendingNode => Color32::from_rgb(96, 160, 255),
+            GizmoHandle::Inclination => Color32::from_rgb(160, 255, 160),
+        }
+    }
+
+    fn get_value(self, orbit: &Orbit) -> f64 {
+        match self {
+            GizmoHandle::Periapsis => orbit.get_periapsis(),
+            GizmoHandle::Apsis => orbit.get_arg_pe(),
+            GizmoHandle::AscendingNode => orbit.get_long_asc_node(),
+            GizmoHandle::Inclination => orbit.get_inclination(),
+        }
+    }
+
+    fn set_value(self, orbit: &mut Orbit, value: f64) {
+        match self {
+            GizmoHandle::Periapsis => orbit.set_periapsis(value),
+            GizmoHandle::Apsis => orbit.set_arg_pe(value.rem_euclid(TAU)),
+            GizmoHandle::AscendingNode => orbit.set_long_asc_node(value.rem_euclid(TAU)),
+            GizmoHandle::Inclination => orbit.set_inclination(value),
+        }
+    }
+
+    fn snap(self, value: f64) -> f64 {
+        match self {
+            GizmoHandle::Periapsis => round_to_significant_figures(value, 2),
+            _ => (value / ANGLE_SNAP).round() * ANGLE_SNAP,
+        }
+    }
+}
+
+fn round_to_significant_figures(value: f64, figures: i32) -> f64 {
+    if value == 0.0 || !value.is_finite() {
+        return value;
+    }
+    let magnitude = 10f64.powi(figures - 1 - value.abs().log10().floor() as i32);
+    (value * magnitude).round() / magnitude
+}
+
+pub(super) struct GizmoState {
+    pub(super) enabled: bool,
+    pub(super) snap: bool,
+    /// The handle being dragged, and its value without snapping applied.
+    drag: Option<(GizmoHandle, f64)>,
+}
+
+impl Default for GizmoState {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            snap: false,
+            drag: None,
+        }
+    }
+}
+
+/// The parts of an orbit that handles are placed relative to.
+struct OrbitFrame {
+    parent_pos: DVec3,
+    /// Unit vector towards the periapsis.
+    p_hat: DVec3,
+    /// Unit vector normal to the orbital plane.
+    normal: DVec3,
+}
+
+impl OrbitFrame {
+    fn new(orbit: &Orbit, parent_pos: DVec3) -> Self {
+        let matrix = orbit.get_transformation_matrix();
+        let p_hat = DVec3::new(matrix.e11, matrix.e21, matrix.e31);
+        let q_hat = DVec3::new(matrix.e12, matrix.e22, matrix.e32);
+
+        Self {
+            parent_pos,
+            p_hat,
+            normal: p_hat.cross(q_hat),
+        }
+    }
+
+    fn handle_position(&self, orbit: &Orbit, handle: GizmoHandle) -> DVec3 {
+        let handle_radius = orbit.get_semi_latus_rectum();
+
+        self.parent_pos
+            + match handle {
+                GizmoHandle::Periapsis => self.p_hat * orbit.get_periapsis(),
+                GizmoHandle::Apsis => {
+                    let distance = if orbit.get_eccentricity() < 1.0 {
+                        orbit.get_apoapsis()
+                    } else {
+                        orbit.get_periapsis()
+                    };
+                    -self.p_hat * distance
+                }
+                GizmoHandle::AscendingNode => {
+                    let lan = orbit.get_long_asc_node();
+                    DVec3::new(lan.cos(), lan.sin(), 0.0) * handle_radius
+                }
+                GizmoHandle::Inclination => self.normal * handle_radius,
+            }
+    }
+
+    /// The axis a handle rotates around, if any.
+    fn rotation_axis(&self, handle: GizmoHandle) -> Option<DVec3> {
+        match handle {
+            GizmoHandle::Apsis => Some(self.normal),
+            GizmoHandle::AscendingNode => Some(DVec3::Z),
+            GizmoHandle::Periapsis | GizmoHandle::Inclination => None,
+        }
+    }
+}
+
+/// Draws draggable handles on the preview body's orbit, or the focused
+/// body's orbit if there is no preview body.
+pub(super) fn draw(
+    ctx: &Context,
+    sim_state: &mut SimState,
+    position_map: &HashMap<UniverseId, DVec3>,
+    projector: &WorldProjector,
+) {
+    let SimState {
+        universe,
+        preview_body,
+        focused_body,
+        ui: ui_state,
+        ..
+    } = sim_state;
+    let state = &mut ui_state.gizmo_state;
+
+    if !state.enabled {
+        state.drag = None;
+        return;
+    }
+
+    // Worked on as a copy, so the focused body is only
+    // written to, and counted as edited, once it's dragged
+    let (orbit, parent_id) = match preview_body {
+        Some(preview) => (preview.body.orbit.clone(), preview.parent_id),
+        None => match universe.get_body(*focused_body) {
+            Some(wrapper) if !wrapper.locked => {
+                (wrapper.body.orbit.clone(), wrapper.relations.parent)
+            }
+            _ => (None, None),
+        },
+    };
+
+    let Some(mut orbit) = orbit else {
+        state.drag = None;
+        return;
+    };
+    let orbit = &mut orbit;
+
+    let parent_pos = parent_id
+        .and_then(|id| position_map.get(&id).copied())
+        .unwrap_or(DVec3::ZERO);
+    let frame = OrbitFrame::new(orbit, parent_pos);
+
+    let Some(center) = projector.project(parent_pos) else {
+        return;
+    };
+
+    let painter = ctx.layer_painter(LayerId::background());
+    let snap = state.snap || ctx.input(|i| i.modifiers.shift);
+    let mut edited = false;
+
+    for handle in GizmoHandle::ALL {
+        let position = frame.handle_position(orbit, handle);
+        let Some(screen_pos) = projector.project(position) else {
+            continue;
+        };
+
+        painter.line_segment(
+            [center, screen_pos],
+            Stroke::new(1.0, handle.color().gamma_multiply(0.5)),
+        );
+
+        let response = handle_widget(ctx, handle, screen_pos);
+
+        if response.drag_started() {
+            state.drag = Some((handle, handle.get_value(orbit)));
+        }
+
+        let Some((dragged, raw_value)) = &mut state.drag else {
+            continue;
+        };
+        if *dragged != handle {
+            continue;
+        }
+
+        let delta = response.drag_delta();
+        if delta != Vec2::ZERO {
+            *raw_value = dragged_value(
+                handle, *raw_value, &frame, projector, center, screen_pos, &response,
+            );
+            let value = if snap {
+                handle.snap(*raw_value)
+            } else {
+                *raw_value
+            };
+            handle.set_value(orbit, value);
+            edited = true;
+        }
+
+        if response.drag_stopped() {
+            state.drag = None;
+        }
+    }
+
+    if !edited {
+        return;
+    }
+    let target = match preview_body {
+        Some(preview) => preview.body.orbit.as_mut(),
+        None => universe
+            .get_body_mut(*focused_body)
+            .and_then(|wrapper| wrapper.body.orbit.as_mut()),
+    };
+    if let Some(target) = target {
+        *target = orbit.clone();
+    }
+}
+
+fn handle_widget(ctx: &Context, handle: GizmoHandle, screen_pos: Pos2) -> Response {
+    let size = Vec2::splat(MIN_TOUCH_TARGET_LEN / 2.0);
+
+    Area::new(GIZMO_HANDLE_AREA_ID.with(handle))
+        .order(Order::Background)
+        .fixed_pos(screen_pos - size / 2.0)
+        .show(ctx, |ui| {
+            let (rect, response) = ui.allocate_exact_size(size, Sense::drag());
+            let stroke_color = if response.dragged() || response.hovered() {
+                Color32::WHITE
+            } else {
+                Color32::BLACK
+            };
+            ui.painter().circle(
+                rect.center(),
+                HANDLE_RADIUS,
+                handle.color(),
+                Stroke::new(2.0, stroke_color),
+            );
+            response
+                .on_hover_cursor(CursorIcon::Grab)
+                .on_hover_and_drag_cursor(CursorIcon::Grabbing)
+        })
+        .inner
+}
+
+/// Maps a screen-space drag to the new (unsnapped) value of a handle.
+fn dragged_value(
+    handle: GizmoHandle,
+    raw_value: f64,
+    frame: &OrbitFrame,
+    projector: &WorldProjector,
+    center: Pos2,
+    screen_pos: Pos2,
+    response: &Response,
+) -> f64 {
+    let delta = response.drag_delta();
+
+    match handle {
+        GizmoHandle::Periapsis => {
+            let outward = screen_pos - center;
+            let length = outward.length();
+            if length < 1.0 {
+                return raw_value;
+            }
+            // Dragging the handle outwards by its distance from
+            // the parent doubles the periapsis.
+            let along = delta.dot(outward / length) / length;
+            raw_value * (1.0 + along as f64).max(0.1)
+        }
+        GizmoHandle::Inclination => {
+            (raw_value - delta.y as f64 * INCLINATION_PER_POINT).clamp(0.0, PI)
+        }
+        GizmoHandle::Apsis | GizmoHandle::AscendingNode => {
+            let Some(pointer) = response.interact_pointer_pos() else {
+                return raw_value;
+            };
+            let prev = pointer - delta;
+            let angle = |p: Pos2| (p - center).angle() as f64;
+            // Screen-space Y goes down, so a positive screen angle is clockwise
+            let swept = -(angle(pointer) - angle(prev) + PI).rem_euclid(TAU) + PI;
+
+            let axis = frame.rotation_axis(handle).unwrap_or(DVec3::Z);
+            let facing_camera = axis.dot(projector.camera_position() - frame.parent_pos) >= 0.0;
+
+            if facing_camera {
+                raw_value + swept
+            } else {
+                raw_value - swept
+            }
+        }
+    }
+}
@@ -0,0 +1,109 @@
+//! Help mode, which shows hover texts right away and keeps the last one
+//! in a side panel, so they can also be read on touch screens.
+
+use three_d::egui::{
+    Color32, Context as EguiContext, CursorIcon, Response, RichText, ScrollArea, SidePanel,
+    WidgetText, style::Interaction,
+};
+
+use crate::gui::{UiState, declare_id, text_style::TextRole};
+
+declare_id!(HELP_PANEL, b"HelpPanl");
+declare_id!(HELP_MODE, b"HelpMode");
+declare_id!(HELP_TEXT, b"HelpText");
+
+pub(crate) trait HelpText {
+    /// Like [`Response::on_hover_text`], but also shows the text
+    /// in the help panel while help mode is on.
+    fn on_help_text(self, text: impl Into<WidgetText>) -> Self;
+}
+
+impl HelpText for Response {
+    fn on_help_text(self, text: impl Into<WidgetText>) -> Self {
+        let text = text.into();
+        // Touch screens only report hovering while a finger is down,
+        // which is enough to catch a tap.
+        if self.hovered() && is_enabled(&self.ctx) {
+            let string = text.text().trim().to_owned();
+            self.ctx
+                .data_mut(|data| data.insert_temp(*HELP_TEXT_ID, string));
+        }
+        self.on_hover_text(text)
+    }
+}
+
+fn is_enabled(ctx: &EguiContext) -> bool {
+    ctx.data(|data| data.get_temp(*HELP_MODE_ID))
+        .unwrap_or(false)
+}
+
+/// Applies help mode for this frame. Called before anything else is drawn.
+pub(super) fn begin_frame(ctx: &EguiContext, ui_state: &UiState) {
+    let enabled = ui_state.help_mode;
+    ctx.data_mut(|data| {
+        data.insert_temp(*HELP_MODE_ID, enabled);
+        data.remove::<String>(*HELP_TEXT_ID);
+    });
+    ctx.style_mut(|style| {
+        let defaults = Interaction::default();
+        if enabled {
+            style.interaction.tooltip_delay = 0.0;
+            style.interaction.show_tooltips_only_when_still = false;
+        } else {
+            style.interaction.tooltip_delay = defaults.tooltip_delay;
+            style.interaction.show_tooltips_only_when_still =
+                defaults.show_tooltips_only_when_still;
+        }
+    });
+}
+
+/// Keeps the hover text of whatever was hovered this frame,
+/// so it stays in the panel after the pointer moves on.
+pub(super) fn end_frame(ctx: &EguiContext, ui_state: &mut UiState) {
+    if !ui_state.help_mode {
+        ui_state.help_text = None;
+        return;
+    }
+    let hovered = ctx.data(|data| data.get_temp::<String>(*HELP_TEXT_ID));
+    if let Some(text) = hovered
+        && ui_state.help_text.as_ref() != Some(&text)
+    {
+        ui_state.help_text = Some(text);
+        ctx.request_repaint();
+    }
+}
+
+pub(super) fn draw_panel(ctx: &EguiContext, ui_state: &mut UiState) {
+    if !ui_state.help_mode {
+        return;
+    }
+
+    SidePanel::right(*HELP_PANEL_ID)
+        .resizable(true)
+        .default_width(280.0)
+        .show(ctx, |ui| {
+            ui.visuals_mut().override_text_color = Some(Color32::WHITE);
+            ui.horizontal(|ui| {
+                ui.heading("Help");
+                let close_button = ui
+                    .button("Exit help mode")
+                    .on_hover_cursor(CursorIcon::PointingHand);
+                if close_button.clicked() {
+                    ui_state.help_mode = false;
+                }
+            });
+            ui.separator();
+
+            ScrollArea::vertical().show(ui, |ui| match &ui_state.help_text {
+                Some(text) => {
+                    ui.label(RichText::new(text).text_style(TextRole::Body.into()));
+                }
+                None => {
+                    ui.label(
+                        "Hover over or tap a control to see what it does. \
+                        Its description stays here until you pick another one.",
+                    );
+                }
+            });
+        });
+}
@@ -0,0 +1,133 @@
+//! Every id declared with `declare_id!`, listed by the build script.
+//!
+//! Two declarations with the same bytes would silently share egui state,
+//! so debug builds refuse to start when that happens.
+
+use std::{collections::HashMap, ptr};
+
+use three_d::egui::{Color32, Context as EguiContext, CursorIcon, Grid, RichText, Ui, Window};
+
+use crate::gui::{UiState, declare_id, help::HelpText, text_style::TextRole};
+
+declare_id!(salt_only, ID_REGISTRY_GRID, b"IdRegstr");
+
+pub(super) struct DeclaredId {
+    pub name: &'static str,
+    /// The module the id was declared in, like `gui::celestials`.
+    pub module: &'static str,
+    /// Whether only a salt was declared, without an `Id`.
+    pub salt_only: bool,
+    pub bytes: [u8; 8],
+    pub file: &'static str,
+    pub line: u32,
+}
+
+impl DeclaredId {
+    /// Where the id was declared, as `file:line`.
+    fn site(&self) -> String {
+        format!("{}:{}", self.file, self.line)
+    }
+}
+
+pub(super) static DECLARED_IDS: &[DeclaredId] =
+    include!(concat!(env!("OUT_DIR"), "/declared_ids.rs"));
+
+/// Pairs of declarations that share their bytes, in declaration order.
+pub(super) fn collisions() -> Vec<(&'static DeclaredId, &'static DeclaredId)> {
+    let mut first_with_bytes: HashMap<[u8; 8], &DeclaredId> = HashMap::new();
+    let mut collisions = Vec::new();
+
+    for id in DECLARED_IDS {
+        match first_with_bytes.get(&id.bytes) {
+            Some(first) => collisions.push((*first, id)),
+            None => {
+                first_with_bytes.insert(id.bytes, id);
+            }
+        }
+    }
+    collisions
+}
+
+/// Panics if two declared ids collide, naming both declarations.
+///
+/// Only checks in debug builds.
+pub(crate) fn check() {
+    if !cfg!(debug_assertions) {
+        return;
+    }
+
+    if let Some((first, second)) = collisions().first() {
+        panic!(
+            "egui id {:?} is declared twice: as {}::{} at {} and as {}::{} at {}",
+            String::from_utf8_lossy(&first.bytes),
+            first.module,
+            first.name,
+            first.site(),
+            second.module,
+            second.name,
+            second.site(),
+        );
+    }
+}
+
+pub(super) fn draw(ctx: &EguiContext, ui_state: &mut UiState) {
+    Window::new("Declared Ids")
+        .open(&mut ui_state.is_id_registry_open)
+        .default_width(480.0)
+        .vscroll(true)
+        .show(ctx, window_contents);
+}
+
+fn window_contents(ui: &mut Ui) {
+    let collisions = collisions();
+    let colliding: Vec<&DeclaredId> = collisions
+        .iter()
+        .flat_map(|&(first, second)| [first, second])
+        .collect();
+
+    ui.label(format!(
+        "{} ids are declared with declare_id!, with {} collisions.",
+        DECLARED_IDS.len(),
+        collisions.len(),
+    ));
+    ui.separator();
+
+    Grid::new(ID_REGISTRY_GRID_SALT)
+        .num_columns(3)
+        .spacing([40.0, 4.0])
+        .striped(true)
+        .show(ui, |ui| {
+            ui.label(RichText::new("Bytes").strong());
+            ui.label(RichText::new("Name").strong())
+                .on_help_text(
+                    RichText::new(
+                        "Ids marked \"salt\" only declare a salt, \
+                        for grids, combo boxes and the like.",
+                    )
+                    .color(Color32::WHITE)
+                    .text_style(TextRole::Body.into()),
+                )
+                .on_hover_cursor(CursorIcon::Help);
+            ui.label(RichText::new("Declared at").strong());
+            ui.end_row();
+
+            for id in DECLARED_IDS {
+                let bytes = RichText::new(String::from_utf8_lossy(&id.bytes)).monospace();
+                if colliding.iter().any(|colliding| ptr::eq(*colliding, id)) {
+                    ui.label(bytes.color(Color32::LIGHT_RED));
+                } else {
+                    ui.label(bytes);
+                }
+
+                let name = format!("{}::{}", id.module, id.name);
+                if id.salt_only {
+                    ui.label(format!("{name} (salt)"));
+                } else {
+                    ui.label(name);
+                }
+
+                ui.label(id.site());
+                ui.end_row();
+            }
+        });
+}
@@ -0,0 +1,158 @@
+use float_pretty_print::PrettyPrintFloat;
+use keplerian_sim::OrbitTrait;
+use three_d::egui::{
+    Button, Color32, Context as EguiContext, CursorIcon, DragValue, Grid, RichText, Ui, Window,
+};
+
+use crate::{
+    gui::{
+        SimState,
+        body_theme::{self, BodyTheme},
+        declare_id,
+        help::HelpText,
+        text_style::TextRole,
+    },
+    notify::{Severity, notify},
+    sim::orbit_ops::{Kick, kick_body, kicked_orbit},
+};
+
+declare_id!(salt_only, KICK_TOOL_GRID, b"KickGrd!");
+
+#[derive(Default)]
+pub(super) struct KickToolState {
+    pub(super) open: bool,
+    kick: Kick,
+}
+
+pub(super) fn draw(ctx: &EguiContext, sim_state: &mut SimState) {
+    let mut open = sim_state.ui.kick_tool_state.open;
+
+    let theme = BodyTheme::of(&sim_state.universe, sim_state.focused_body());
+
+    Window::new("Velocity Kick")
+        .frame(body_theme::window_frame(ctx, theme))
+        .open(&mut open)
+        .default_width(280.0)
+        .show(ctx, |ui| window_contents(ui, sim_state));
+
+    sim_state.ui.kick_tool_state.open &= open;
+}
+
+fn window_contents(ui: &mut Ui, sim_state: &mut SimState) {
+    ui.visuals_mut().override_text_color = Some(Color32::WHITE);
+
+    let body_id = sim_state.focused_body();
+    let universe = &sim_state.universe;
+    let state = &mut sim_state.ui.kick_tool_state;
+
+    let Some(wrapper) = universe.get_body(body_id) else {
+        ui.label("Focus on a body to kick it.");
+        return;
+    };
+    let Some(orbit) = &wrapper.body.orbit else {
+        ui.label(format!("{} isn't orbiting anything.", wrapper.body.name));
+        return;
+    };
+    let time = wrapper.frozen_at.unwrap_or(universe.time);
+    let kicked = kicked_orbit(orbit, state.kick, time);
+    let locked = wrapper.locked;
+
+    Grid::new(KICK_TOOL_GRID_SALT)
+        .num_columns(2)
+        .spacing([40.0, 4.0])
+        .striped(true)
+        .show(ui, |ui| {
+            ui.label("Body");
+            ui.label(&wrapper.body.name);
+            ui.end_row();
+
+            kick_component_row(
+                ui,
+                "Prograde",
+                "Speeds the body up along its direction of motion.\n\
+                Negative values slow it down instead.",
+                &mut state.kick.prograde,
+            );
+            kick_component_row(
+                ui,
+                "Normal",
+                "Pushes the body out of its orbital plane, \
+                along the orbit's angular momentum.",
+                &mut state.kick.normal,
+            );
+            kick_component_row(
+                ui,
+                "Radial",
+                "Pushes the body away from its parent, \
+                perpendicular to its direction of motion.",
+                &mut state.kick.radial,
+            );
+
+            ui.label("Total Δv");
+            ui.label(format!("{} m/s", PrettyPrintFloat(state.kick.delta_v())));
+            ui.end_row();
+
+            ui.label("Eccentricity after")
+                .on_help_text(
+                    RichText::new(
+                        "The eccentricity of the orbit the kick would leave the body on.\n\
+                        Above 1, the body escapes its parent.",
+                    )
+                    .color(Color32::WHITE)
+                    .text_style(TextRole::Body.into()),
+                )
+                .on_hover_cursor(CursorIcon::Help);
+            match &kicked {
+                Ok(kicked) => ui.label(format!("{:.6}", kicked.get_eccentricity())),
+                Err(_) => ui.label("—"),
+            };
+            ui.end_row();
+        });
+
+    let mut apply = false;
+    ui.horizontal(|ui| {
+        apply = ui
+            .add_enabled(kicked.is_ok() && !locked, Button::new("Apply kick"))
+            .on_help_text(
+                RichText::new(
+                    "Change the body's velocity right now.\n\
+                    Unlike a maneuver node, this happens immediately.",
+                )
+                .color(Color32::WHITE)
+                .text_style(TextRole::Body.into()),
+            )
+            .on_disabled_hover_text(
+                RichText::new(if locked {
+                    "The body is locked. Unlock it in the Edit Body window first."
+                } else {
+                    "The kick doesn't leave the body on a valid orbit."
+                })
+                .color(Color32::WHITE)
+                .text_style(TextRole::Body.into()),
+            )
+            .clicked();
+
+        if ui.button("Reset").clicked() {
+            state.kick = Kick::default();
+        }
+    });
+
+    if apply {
+        let kick = state.kick;
+        if let Err(e) = kick_body(&mut sim_state.universe, body_id, kick) {
+            notify(Severity::Warning, format!("Couldn't apply the kick: {e}"));
+        }
+    }
+}
+
+fn kick_component_row(ui: &mut Ui, label: &str, description: &str, value: &mut f64) {
+    ui.label(label)
+        .on_help_text(
+            RichText::new(description)
+                .color(Color32::WHITE)
+                .text_style(TextRole::Body.into()),
+        )
+        .on_hover_cursor(CursorIcon::Help);
+    ui.add(DragValue::new(value).speed(1.0).suffix(" m/s"));
+    ui.end_row();
+}
@@ -0,0 +1,293 @@
+use strum::IntoEnumIterator;
+use strum_macros::EnumIter;
+use three_d::egui::{
+    Button, Color32, Context, Grid, Id as EguiId, Pos2, Rect, RichText, Ui, Vec2, Window,
+};
+
+use crate::{
+    cfg::CONFIG,
+    gui::{MIN_TOUCH_TARGET_VEC, SimState, declare_id, help::HelpText, text_style::TextRole},
+};
+
+declare_id!(salt_only, LAYOUT_DOCK_GRID, b"DockGrid");
+
+/// The content width of a docked window.
+const SIDEBAR_WIDTH: f32 = 300.0;
+
+/// Room taken by a window's frame and title bar,
+/// on top of its content size.
+const WINDOW_CHROME: Vec2 = Vec2::new(16.0, 48.0);
+
+/// How close a floating window has to be to a screen edge
+/// to snap to it when released.
+const SNAP_DISTANCE: f32 = 24.0;
+
+/// Where a window is placed.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, EnumIter)]
+pub(super) enum Dock {
+    /// Freely movable, snapping to screen edges when released near one.
+    #[default]
+    Floating,
+    /// Tiled into a sidebar on the left of the screen.
+    Left,
+    /// Tiled into a sidebar on the right of the screen.
+    Right,
+}
+
+impl Dock {
+    const fn name(self) -> &'static str {
+        match self {
+            Dock::Floating => "Float",
+            Dock::Left => "Left",
+            Dock::Right => "Right",
+        }
+    }
+
+    const fn from_index(index: u8) -> Self {
+        match index {
+            1 => Dock::Left,
+            2 => Dock::Right,
+            _ => Dock::Floating,
+        }
+    }
+
+    const fn to_index(self) -> u8 {
+        match self {
+            Dock::Floating => 0,
+            Dock::Left => 1,
+            Dock::Right => 2,
+        }
+    }
+}
+
+/// The windows that can be docked.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, EnumIter)]
+pub(super) enum DockableWindow {
+    BodyList,
+    EditBody,
+    NewBody,
+}
+
+impl DockableWindow {
+    const COUNT: usize = 3;
+
+    const fn index(self) -> usize {
+        match self {
+            DockableWindow::BodyList => 0,
+            DockableWindow::EditBody => 1,
+            DockableWindow::NewBody => 2,
+        }
+    }
+
+    /// The title of the window, which egui also uses as its ID.
+    pub(super) const fn title(self) -> &'static str {
+        match self {
+            DockableWindow::BodyList => "Celestial Bodies",
+            DockableWindow::EditBody => "Edit Body",
+            DockableWindow::NewBody => "New Body",
+        }
+    }
+
+    fn is_open(self, sim_state: &SimState) -> bool {
+        match self {
+            DockableWindow::BodyList => sim_state.ui.body_list_window_state.window_open,
+            DockableWindow::EditBody => sim_state.ui.edit_body_window_state.window_open,
+            DockableWindow::NewBody => sim_state.preview_body.is_some(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, EnumIter)]
+enum LayoutPreset {
+    Default,
+    Editing,
+    Presentation,
+}
+
+impl LayoutPreset {
+    const fn name(self) -> &'static str {
+        match self {
+            LayoutPreset::Default => "Default",
+            LayoutPreset::Editing => "Editing",
+            LayoutPreset::Presentation => "Presentation",
+        }
+    }
+
+    const fn description(self) -> &'static str {
+        match self {
+            LayoutPreset::Default => "All windows float freely.",
+            LayoutPreset::Editing => {
+                "The body list is docked on the left, \
+                and the body editors are docked on the right."
+            }
+            LayoutPreset::Presentation => {
+                "All windows are tiled into a sidebar on the right, \
+                keeping the rest of the screen clear."
+            }
+        }
+    }
+
+    const fn docks(self) -> [Dock; DockableWindow::COUNT] {
+        match self {
+            LayoutPreset::Default => [Dock::Floating; DockableWindow::COUNT],
+            LayoutPreset::Editing => [Dock::Left, Dock::Right, Dock::Right],
+            LayoutPreset::Presentation => [Dock::Right; DockableWindow::COUNT],
+        }
+    }
+}
+
+pub(super) struct LayoutState {
+    docks: [Dock; DockableWindow::COUNT],
+}
+
+impl Default for LayoutState {
+    fn default() -> Self {
+        let docks = CONFIG
+            .try_lock()
+            .map(|cfg| cfg.window_docks.get())
+            .unwrap_or_default()
+            .map(Dock::from_index);
+
+        Self { docks }
+    }
+}
+
+impl LayoutState {
+    fn set_docks(&mut self, docks: [Dock; DockableWindow::COUNT]) {
+        self.docks = docks;
+        let _ = CONFIG
+            .try_lock()
+            .map(|cfg| cfg.window_docks.set(docks.map(Dock::to_index)));
+    }
+
+    fn preset(&self) -> Option<LayoutPreset> {
+        LayoutPreset::iter().find(|preset| preset.docks() == self.docks)
+    }
+}
+
+/// Positions a window according to the layout.
+///
+/// Docked windows are stacked into their sidebar, splitting its height
+/// evenly between the open windows docked there.
+pub(super) fn place<'a>(
+    ctx: &Context,
+    sim_state: &SimState,
+    window: DockableWindow,
+    builder: Window<'a>,
+) -> Window<'a> {
+    let docks = &sim_state.ui.layout_state.docks;
+    let dock = docks[window.index()];
+    let available = ctx.available_rect();
+
+    if dock == Dock::Floating {
+        return snap_to_edges(ctx, window, available, builder);
+    }
+
+    let stacked: Vec<DockableWindow> = DockableWindow::iter()
+        .filter(|w| docks[w.index()] == dock && (*w == window || w.is_open(sim_state)))
+        .collect();
+    let slot = stacked.iter().position(|w| *w == window).unwrap_or(0);
+    let slot_height = available.height() / stacked.len().max(1) as f32;
+
+    let x = match dock {
+        Dock::Right => available.right() - SIDEBAR_WIDTH - WINDOW_CHROME.x,
+        _ => available.left(),
+    };
+    let pos = Pos2::new(x, available.top() + slot as f32 * slot_height);
+    let size = Vec2::new(SIDEBAR_WIDTH, slot_height - WINDOW_CHROME.y);
+
+    builder
+        .fixed_pos(pos)
+        .fixed_size(size.max(Vec2::ZERO))
+        .collapsible(false)
+}
+
+fn snap_to_edges<'a>(
+    ctx: &Context,
+    window: DockableWindow,
+    available: Rect,
+    builder: Window<'a>,
+) -> Window<'a> {
+    let released = ctx.input(|i| i.pointer.any_released());
+    let last_rect = ctx.memory(|m| m.area_rect(EguiId::new(window.title())));
+
+    let Some(rect) = last_rect.filter(|_| released) else {
+        return builder;
+    };
+
+    let mut pos = rect.min;
+
+    if (rect.left() - available.left()).abs() < SNAP_DISTANCE {
+        pos.x = available.left();
+    } else if (available.right() - rect.right()).abs() < SNAP_DISTANCE {
+        pos.x = available.right() - rect.width();
+    }
+
+    if (rect.top() - available.top()).abs() < SNAP_DISTANCE {
+        pos.y = available.top();
+    } else if (available.bottom() - rect.bottom()).abs() < SNAP_DISTANCE {
+        pos.y = available.bottom() - rect.height();
+    }
+
+    if pos == rect.min {
+        builder
+    } else {
+        builder.current_pos(pos)
+    }
+}
+
+/// The name of the current layout, for the combo box.
+pub(super) fn layout_name(state: &LayoutState) -> &'static str {
+    state.preset().map(LayoutPreset::name).unwrap_or("Custom")
+}
+
+/// Returns whether or not any button was clicked
+pub(super) fn layout_menu(ui: &mut Ui, state: &mut LayoutState) -> bool {
+    ui.visuals_mut().override_text_color = Some(Color32::WHITE);
+    ui.spacing_mut().interact_size = MIN_TOUCH_TARGET_VEC;
+
+    let mut clicked = false;
+    let current = state.preset();
+
+    for preset in LayoutPreset::iter() {
+        let text = RichText::new(preset.name()).text_style(TextRole::Body.into());
+        let button = Button::selectable(current == Some(preset), text);
+        let button = ui.add(button).on_help_text(
+            RichText::new(preset.description())
+                .color(Color32::WHITE)
+                .text_style(TextRole::Body.into()),
+        );
+
+        if button.clicked() {
+            state.set_docks(preset.docks());
+            clicked = true;
+        }
+    }
+
+    ui.separator();
+
+    Grid::new(LAYOUT_DOCK_GRID_SALT)
+        .num_columns(2)
+        .spacing([40.0, 4.0])
+        .striped(true)
+        .show(ui, |ui| {
+            for window in DockableWindow::iter() {
+                ui.label(window.title());
+                ui.horizontal(|ui| {
+                    for dock in Dock::iter() {
+                        let current = state.docks[window.index()];
+                        let button = Button::selectable(current == dock, dock.name());
+                        if ui.add(button).clicked() {
+                            let mut docks = state.docks;
+                            docks[window.index()] = dock;
+                            state.set_docks(docks);
+                            clicked = true;
+                        }
+                    }
+                });
+                ui.end_row();
+            }
+        });
+
+    clicked
+}
@@ -0,0 +1,136 @@
+use std::fmt::Write;
+
+use log::{Level, LevelFilter};
+use three_d::egui::{Button, Color32, Context as EguiContext, Grid, Label, RichText, Ui, Window};
+
+use crate::{
+    gui::{declare_id, help::HelpText, text_style::TextRole},
+    logging::{self, LogEntry},
+};
+
+declare_id!(salt_only, LOG_VIEWER_GRID, b"LogViewr");
+
+/// The levels the window can be filtered down to, most severe first.
+const FILTER_LEVELS: [LevelFilter; 4] = [
+    LevelFilter::Error,
+    LevelFilter::Warn,
+    LevelFilter::Info,
+    LevelFilter::Debug,
+];
+
+pub(super) struct LogViewerState {
+    pub(super) open: bool,
+    /// The least severe level shown.
+    min_level: LevelFilter,
+    /// The records last copied out of the log, oldest first,
+    /// kept until it changes so they're not decoded every frame.
+    entries: Vec<LogEntry>,
+    /// The version of the log `entries` were copied at.
+    version: Option<u64>,
+}
+
+impl Default for LogViewerState {
+    fn default() -> Self {
+        Self {
+            open: false,
+            min_level: LevelFilter::Info,
+            entries: Vec::new(),
+            version: None,
+        }
+    }
+}
+
+const fn level_color(level: Level) -> Color32 {
+    match level {
+        Level::Error => Color32::LIGHT_RED,
+        Level::Warn => Color32::YELLOW,
+        Level::Info => Color32::LIGHT_BLUE,
+        Level::Debug | Level::Trace => Color32::GRAY,
+    }
+}
+
+/// Lists the program's log records, newest first.
+pub(super) fn draw(ctx: &EguiContext, state: &mut LogViewerState) {
+    let mut open = state.open;
+
+    Window::new("Log")
+        .open(&mut open)
+        .default_width(480.0)
+        .vscroll(true)
+        .show(ctx, |ui| window_contents(ui, state));
+
+    state.open &= open;
+}
+
+fn window_contents(ui: &mut Ui, state: &mut LogViewerState) {
+    ui.visuals_mut().override_text_color = Some(Color32::WHITE);
+
+    if let Some((version, entries)) = logging::history_since(state.version) {
+        state.version = Some(version);
+        state.entries = entries;
+    }
+    let min_level = state.min_level;
+    let entries: Vec<&LogEntry> = state
+        .entries
+        .iter()
+        .filter(|entry| entry.level <= min_level)
+        .collect();
+
+    ui.horizontal_wrapped(|ui| {
+        ui.label("Show");
+        for level in FILTER_LEVELS {
+            let button = Button::selectable(state.min_level == level, level.as_str());
+            if ui.add(button).clicked() {
+                state.min_level = level;
+            }
+        }
+    });
+
+    ui.horizontal(|ui| {
+        let copy_button = ui
+            .add_enabled(!entries.is_empty(), Button::new("Copy"))
+            .on_help_text(
+                RichText::new(
+                    "Copy the shown records to the clipboard, to paste into a bug report.",
+                )
+                .color(Color32::WHITE)
+                .text_style(TextRole::Body.into()),
+            );
+        if copy_button.clicked() {
+            ui.ctx().copy_text(to_text(&entries));
+        }
+
+        if ui.button("Clear").clicked() {
+            logging::clear();
+        }
+    });
+
+    ui.separator();
+
+    if entries.is_empty() {
+        ui.label("Nothing has been logged at these levels yet.");
+        return;
+    }
+
+    Grid::new(LOG_VIEWER_GRID_SALT)
+        .num_columns(3)
+        .spacing([8.0, 4.0])
+        .striped(true)
+        .show(ui, |ui| {
+            for entry in entries.iter().rev() {
+                ui.label(RichText::new(entry.level.as_str()).color(level_color(entry.level)));
+                ui.label(RichText::new(&entry.target).weak());
+                ui.add(Label::new(&entry.message).wrap());
+                ui.end_row();
+            }
+        });
+}
+
+/// The records as plain text, one per line, oldest first.
+fn to_text(entries: &[&LogEntry]) -> String {
+    let mut text = String::new();
+    for entry in entries {
+        let _ = writeln!(text, "[{} {}] {}", entry.level, entry.target, entry.message);
+    }
+    text
+}
@@ -1,24 +1,83 @@
-use std::{collections::HashMap, sync::Arc};
+use std::collections::HashMap;
 
-use crate::sim::universe::{BulkMuSetterMode, Id as UniverseId, Universe};
+use crate::{
+    cfg::CONFIG,
+    gfx::{arrow::VectorOverlayMode, projection::WorldProjector},
+    metrics,
+    notify::{Severity, notify},
+    sim::{
+        body::Body,
+        position_cache::PositionCache,
+        proximity,
+        speed::{self, DEFAULT_MAX_SIM_SPEED, DEFAULT_MIN_SIM_SPEED},
+        startup_options::{self, StartupOptions},
+        universe::{BulkMuSetterMode, Id as UniverseId, Universe},
+        warp::{WarpQueue, WarpUpdate},
+        zoom_levels::ZoomLevel,
+    },
+};
 pub(crate) use celestials::PreviewBody;
 use glam::DVec3;
+use keplerian_sim::OrbitTrait;
+pub(crate) use new_body_defaults::new_body_preview;
 use ordered_float::NotNan;
+pub(crate) use tabs::TabAction;
 use three_d::{
     Context as ThreeDContext, Event as ThreeDEvent, GUI, Viewport,
     egui::{
-        self, Context as EguiContext, CursorIcon, FontData, FontFamily, FontId, OpenUrl,
-        OutputCommand, Vec2,
+        Context as EguiContext, CursorIcon, FontData, FontFamily, OpenUrl, OutputCommand, Vec2,
         epaint::text::{FontInsert, FontPriority, InsertFontFamily},
     },
 };
 
 mod about;
+mod altitude;
+mod benchmark_report;
+mod body_switcher;
+mod body_theme;
 mod bottom_bar;
 mod celestials;
+mod constraints;
+mod converter;
+mod diagnostics;
+mod ephemeris_tool;
 mod fps;
+mod gizmo;
+mod help;
+pub(crate) mod id_registry;
+mod kick_tool;
+mod layout;
+mod log_viewer;
+mod new_body_defaults;
+pub(crate) mod nudge;
+mod occlusion_hud;
+mod orbit_check_tool;
+mod orbit_marker;
+mod phase_dial;
+mod phase_tool;
+pub(crate) mod presentation;
+mod preset_editor;
+pub(crate) mod quit;
+mod relative_path_tool;
+mod rendezvous_tool;
+mod safe_mode;
+mod scale_bar;
+mod sci_input;
+mod settings_backup;
+mod sparkline;
+pub(crate) mod splash;
+mod tabs;
+pub(crate) mod text_style;
+mod toasts;
+pub(crate) mod toolbar;
+mod transfer_tool;
+pub(crate) mod ui_scale;
 mod unit_dv;
+mod universe_settings;
+mod vector_legend;
+mod warp_queue;
 mod welcome;
+mod windows;
 
 macro_rules! declare_id {
     (salt_only, $name:ident, $val:expr) => {
@@ -43,24 +102,111 @@ const MIN_TOUCH_TARGET_VEC: Vec2 = Vec2::splat(MIN_TOUCH_TARGET_LEN);
 
 pub(crate) struct UiState {
     bottom_bar_state: bottom_bar::BottomBarState,
+    pub(crate) body_switcher_state: body_switcher::BodySwitcherState,
     frame_data: fps::FrameData,
     pub(crate) body_list_window_state: celestials::list::BodyListWindowState,
     new_body_window_state: Option<celestials::new::NewBodyWindowState>,
     pub(crate) edit_body_window_state: celestials::edit::EditBodyWindowState,
+    compare_window_state: celestials::compare::CompareWindowState,
     welcome_window_state: welcome::WindowState,
     is_about_window_open: bool,
+    is_universe_settings_open: bool,
+    is_new_body_defaults_open: bool,
+    is_safe_mode_window_open: bool,
+    is_event_log_open: bool,
+    is_diagnostics_open: bool,
+    is_id_registry_open: bool,
+    is_toolbar_editor_open: bool,
+    log_viewer_state: log_viewer::LogViewerState,
+    orbit_check_state: orbit_check_tool::OrbitCheckState,
+    converter_window_state: converter::ConverterWindowState,
+    preset_editor_state: preset_editor::PresetEditorState,
+    constraints_window_state: constraints::ConstraintsWindowState,
+    phase_tool_state: phase_tool::PhaseToolState,
+    relative_path_state: relative_path_tool::RelativePathState,
+    rendezvous_state: rendezvous_tool::RendezvousState,
+    transfer_tool_state: transfer_tool::TransferToolState,
+    kick_tool_state: kick_tool::KickToolState,
+    ephemeris_tool_state: ephemeris_tool::EphemerisToolState,
+    settings_backup_state: settings_backup::SettingsBackupState,
+    set_date_state: universe_settings::SetDateState,
+    warp_queue_state: warp_queue::WarpQueueState,
+    gizmo_state: gizmo::GizmoState,
+    presentation_state: presentation::PresentationState,
+    orbit_marker: Option<orbit_marker::OrbitMarker>,
+    layout_state: layout::LayoutState,
+    /// A request to open, close or switch tabs, handled after the frame.
+    pub(crate) tab_action: Option<TabAction>,
+    /// Whether to zoom the camera out to fit the focused body's orbit,
+    /// handled after the frame.
+    pub(crate) frame_orbit_requested: bool,
+    /// A scale to zoom the camera to around the focused body,
+    /// handled after the frame.
+    pub(crate) zoom_level_requested: Option<ZoomLevel>,
+    /// Whether to turn the camera towards the rendezvous target,
+    /// handled after the frame.
+    pub(crate) rendezvous_aim_requested: bool,
+    /// Whether the user asked to quit, handled after the frame.
+    pub(crate) quit: quit::QuitState,
+    /// Whether the user asked to run the benchmark, handled after the frame.
+    pub(crate) benchmark_requested: bool,
+    /// The results of the benchmark that ran in this tab, until dismissed.
+    pub(crate) benchmark_report: Option<String>,
+    /// A universe picked or generated in a window, loaded after the frame.
+    universe_to_load: Option<Universe>,
+    /// Whether hover texts show up right away and stay in the help panel.
+    help_mode: bool,
+    /// The hover text shown in the help panel.
+    help_text: Option<String>,
 }
 
 impl Default for UiState {
     fn default() -> Self {
         Self {
             bottom_bar_state: bottom_bar::BottomBarState::default(),
+            body_switcher_state: body_switcher::BodySwitcherState::default(),
             frame_data: fps::FrameData::new(),
             body_list_window_state: celestials::list::BodyListWindowState::default(),
             new_body_window_state: None,
             edit_body_window_state: celestials::edit::EditBodyWindowState::default(),
+            compare_window_state: celestials::compare::CompareWindowState::default(),
             welcome_window_state: welcome::WindowState::default(),
             is_about_window_open: false,
+            is_universe_settings_open: false,
+            is_new_body_defaults_open: false,
+            is_safe_mode_window_open: false,
+            is_event_log_open: false,
+            is_diagnostics_open: false,
+            is_id_registry_open: false,
+            is_toolbar_editor_open: false,
+            log_viewer_state: log_viewer::LogViewerState::default(),
+            orbit_check_state: orbit_check_tool::OrbitCheckState::default(),
+            converter_window_state: converter::ConverterWindowState::default(),
+            preset_editor_state: preset_editor::PresetEditorState::default(),
+            constraints_window_state: constraints::ConstraintsWindowState::default(),
+            phase_tool_state: phase_tool::PhaseToolState::default(),
+            relative_path_state: relative_path_tool::RelativePathState::default(),
+            rendezvous_state: rendezvous_tool::RendezvousState::default(),
+            transfer_tool_state: transfer_tool::TransferToolState::default(),
+            kick_tool_state: kick_tool::KickToolState::default(),
+            ephemeris_tool_state: ephemeris_tool::EphemerisToolState::default(),
+            settings_backup_state: settings_backup::SettingsBackupState::default(),
+            set_date_state: universe_settings::SetDateState::default(),
+            warp_queue_state: warp_queue::WarpQueueState::default(),
+            gizmo_state: gizmo::GizmoState::default(),
+            presentation_state: presentation::PresentationState::default(),
+            orbit_marker: None,
+            layout_state: layout::LayoutState::default(),
+            tab_action: None,
+            frame_orbit_requested: false,
+            zoom_level_requested: None,
+            rendezvous_aim_requested: false,
+            quit: quit::QuitState::default(),
+            benchmark_requested: false,
+            benchmark_report: None,
+            universe_to_load: None,
+            help_mode: false,
+            help_text: None,
         }
     }
 }
@@ -73,16 +219,111 @@ pub(crate) struct SimState {
     focused_body: UniverseId,
     pub focus_offset: DVec3,
     pub preview_body: Option<celestials::PreviewBody>,
+    /// The focused body on the orbit it would have around the parent
+    /// picked for it in the Edit Body window, before the move is confirmed.
+    pub reparent_preview: Option<celestials::PreviewBody>,
+    /// A body that was just moved to another parent,
+    /// with its old orbit line fading out.
+    pub reparent_fade: Option<celestials::ReparentFade>,
+    pub vector_overlay: VectorOverlayMode,
+    /// The body to draw the focused body's path relative to, if any.
+    pub relative_path_observer: Option<UniverseId>,
+    /// The body the camera stays turned towards as it rides along
+    /// with the focused body's orbit, if any.
+    pub rendezvous_target: Option<UniverseId>,
+    /// Events to skip ahead to, one after another.
+    pub warp_queue: WarpQueue,
+    /// The positions of all bodies, kept until the universe changes.
+    pub position_cache: PositionCache,
+    /// Whether the program was started in safe mode after
+    /// crashing repeatedly.
+    pub safe_mode: bool,
+    /// The universe's edit count when it was loaded,
+    /// to tell whether it was edited since.
+    loaded_edit_count: u64,
     pub ui: UiState,
 }
 
 impl SimState {
     pub(crate) fn new(universe: Universe) -> Self {
         Self {
+            focused_body: universe.metadata.default_focus.unwrap_or(0),
+            loaded_edit_count: universe.edit_count(),
             universe,
             ..Default::default()
         }
     }
+    /// Creates a minimal simulation state with an empty universe.
+    pub(crate) fn new_safe_mode() -> Self {
+        let mut state = Self::new(Universe::default());
+        state.safe_mode = true;
+        state.ui.is_safe_mode_window_open = true;
+        state.ui.welcome_window_state = welcome::WindowState::closed();
+        state
+    }
+    /// Replaces the universe, focusing on its default focus.
+    pub(crate) fn load_universe(&mut self, universe: Universe) {
+        self.focused_body = universe.metadata.default_focus.unwrap_or(0);
+        self.focus_offset = DVec3::ZERO;
+        self.loaded_edit_count = universe.edit_count();
+        self.universe = universe;
+        self.preview_body = None;
+        self.reparent_preview = None;
+        self.reparent_fade = None;
+        self.relative_path_observer = None;
+        self.rendezvous_target = None;
+        if let Some(speed) = self.warp_queue.clear() {
+            self.sim_speed = speed;
+        }
+        self.ui.orbit_marker = None;
+        self.ui.body_list_window_state.listed_body_with_popup = None;
+    }
+    /// Whether the universe was edited since it was loaded.
+    /// Time passing and the simulation's own changes don't count.
+    pub(crate) fn is_edited(&self) -> bool {
+        self.universe.edit_count() != self.loaded_edit_count
+    }
+    /// Closes the windows that are only meant to be shown on startup,
+    /// for states that are opened later, e.g. in a new tab.
+    pub(crate) fn close_startup_windows(&mut self) {
+        self.ui.welcome_window_state = welcome::WindowState::closed();
+        self.ui.is_safe_mode_window_open = false;
+    }
+    /// Applies the options the program was started with, like from
+    /// the URL on the web. Names that don't match anything are reported.
+    pub(crate) fn apply_startup_options(&mut self, options: &StartupOptions) {
+        if options.is_empty() {
+            return;
+        }
+        // Links set up the view they want, so the welcome window
+        // only shows up if it's asked for
+        self.close_startup_windows();
+
+        if let Some(name) = &options.focus {
+            match startup_options::find_body(&self.universe, name) {
+                Some(id) => self.focused_body = id,
+                None => notify(
+                    Severity::Warning,
+                    format!("Couldn't focus on \"{name}\", as there's no body with that name."),
+                ),
+            }
+        }
+        if let Some(speed) = options.speed {
+            self.sim_speed = speed;
+        }
+        if let Some(paused) = options.paused {
+            self.running = !paused;
+        }
+        for name in &options.windows {
+            match windows::find(name) {
+                Some(entry) => *(entry.open)(self) = true,
+                None => notify(
+                    Severity::Warning,
+                    format!("Couldn't open \"{name}\", as there's no window with that name."),
+                ),
+            }
+        }
+    }
     pub(crate) fn switch_focus(
         &mut self,
         focus_body_id: UniverseId,
@@ -103,15 +344,166 @@ impl SimState {
             new_offset
         };
     }
+    /// Keeps the simulation speed within the limits in the settings,
+    /// putting it back to normal if it stopped being a number.
+    pub(crate) fn limit_sim_speed(&mut self) {
+        let (min, max) = sim_speed_limits();
+        self.sim_speed = speed::limit_sim_speed(self.sim_speed, min, max);
+    }
+    /// Moves the fade between a moved body's old and new orbit lines along.
+    pub(crate) fn update_reparent_fade(&mut self, elapsed_ms: f64) {
+        if let Some(fade) = &mut self.reparent_fade {
+            fade.progress += elapsed_ms / 1000.0 / celestials::REPARENT_FADE_DURATION;
+        }
+        if self
+            .reparent_fade
+            .as_ref()
+            .is_some_and(|fade| fade.progress >= 1.0)
+        {
+            self.reparent_fade = None;
+        }
+    }
+    /// Sets the simulation speed for a frame lasting `elapsed_ms`,
+    /// warping towards the first event in the warp queue.
+    pub(crate) fn update_warp(&mut self, elapsed_ms: f64) {
+        if !self.running {
+            return;
+        }
+
+        let (_, max) = sim_speed_limits();
+        let update = self.warp_queue.update(
+            &self.universe,
+            &mut self.sim_speed,
+            elapsed_ms / 1000.0,
+            max,
+        );
+
+        match update {
+            WarpUpdate::Idle | WarpUpdate::Warping => (),
+            WarpUpdate::Reached(event) => notify(
+                Severity::Info,
+                format!(
+                    "Warp reached: {}.",
+                    warp_queue::event_label(&self.universe, event)
+                ),
+            ),
+            WarpUpdate::Failed(event, e) => notify(
+                Severity::Warning,
+                format!(
+                    "Warp skipped {}: {e}.",
+                    warp_queue::event_label(&self.universe, event)
+                ),
+            ),
+        }
+    }
     #[inline]
     pub(crate) fn focused_body(&self) -> UniverseId {
         self.focused_body
     }
+    /// The corners of the focused body's orbit's bounding box in its plane,
+    /// relative to the body, for the camera to frame.
+    ///
+    /// Open orbits are boxed from the periapsis to where the body is now,
+    /// mirrored across the periapsis line.
+    pub(crate) fn focused_orbit_corners(&self) -> Option<[DVec3; 4]> {
+        let time = self.universe.get_body_time(self.focused_body)?;
+        let orbit = self
+            .universe
+            .get_body(self.focused_body)?
+            .body
+            .orbit
+            .as_ref()?;
+
+        let matrix = orbit.get_transformation_matrix();
+        let p_hat = DVec3::new(matrix.e11, matrix.e21, matrix.e31);
+        let q_hat = DVec3::new(matrix.e12, matrix.e22, matrix.e32);
+        let position = orbit.get_position_at_time(time);
+
+        let periapsis = orbit.get_periapsis();
+        let (far_end, half_width) = if orbit.get_eccentricity() < 1.0 {
+            (-orbit.get_apoapsis(), orbit.get_semi_minor_axis())
+        } else {
+            (position.dot(p_hat), position.dot(q_hat).abs())
+        };
+
+        let corners = [
+            (periapsis, half_width),
+            (periapsis, -half_width),
+            (far_end, half_width),
+            (far_end, -half_width),
+        ]
+        .map(|(p, q)| p_hat * p + q_hat * q - position);
+
+        corners
+            .iter()
+            .all(|corner| corner.is_finite())
+            .then_some(corners)
+    }
+    /// Moves the focus off a focused body that was just deleted.
+    ///
+    /// With "Focus handoff" on, the camera moves to the body whose sphere
+    /// of influence the deleted body was in, and says so. Otherwise it moves
+    /// to the deleted body's parent, or the first body.
+    fn hand_off_focus(
+        &mut self,
+        parent_id: Option<UniverseId>,
+        bodies_removed: &[(UniverseId, Body)],
+        position_map: &HashMap<UniverseId, DVec3>,
+    ) {
+        let handoff = CONFIG
+            .try_lock()
+            .map(|cfg| cfg.focus_handoff.get())
+            .unwrap_or(true);
+        if !handoff {
+            self.switch_focus(parent_id.unwrap_or(0), position_map);
+            return;
+        }
+
+        let focused = self.focused_body();
+        let name = bodies_removed
+            .iter()
+            .find(|(id, _)| *id == focused)
+            .map(|(_, body)| body.name.as_str())
+            .unwrap_or("The focused body");
+        let new_focus = position_map
+            .get(&focused)
+            .and_then(|&position| proximity::dominant_body(&self.universe, position_map, position))
+            .or(parent_id.filter(|&id| self.universe.get_body(id).is_some()));
+
+        match new_focus {
+            Some(id) => {
+                let new_name = self
+                    .universe
+                    .get_body(id)
+                    .map(|w| w.body.name.as_str())
+                    .unwrap_or("?");
+                notify(
+                    Severity::Info,
+                    format!("{name} was deleted, so the camera moved over to {new_name}."),
+                );
+                self.switch_focus(id, position_map);
+            }
+            None => {
+                notify(
+                    Severity::Info,
+                    format!("{name} was deleted, and there are no bodies left to focus on."),
+                );
+                self.switch_focus(0, position_map);
+            }
+        }
+    }
     pub(crate) fn remove_body(
         &mut self,
         universe_id: UniverseId,
         position_map: &HashMap<UniverseId, DVec3>,
     ) {
+        if self.universe.is_subtree_locked(universe_id) {
+            notify(
+                Severity::Warning,
+                "Couldn't delete the body: it or a body orbiting it is locked.",
+            );
+            return;
+        }
         let parent_id = self
             .universe
             .get_body(universe_id)
@@ -123,26 +515,66 @@ impl SimState {
         {
             self.preview_body = None;
         }
+        if let Some(target) = self.rendezvous_target
+            && bodies_removed.iter().any(|(id, _)| *id == target)
+        {
+            self.rendezvous_target = None;
+            notify(
+                Severity::Info,
+                "The rendezvous target was deleted, so the camera turns freely again.",
+            );
+        }
         if bodies_removed
             .iter()
             .any(|(id, _)| *id == self.focused_body())
         {
-            self.switch_focus(parent_id.unwrap_or(0), position_map);
+            self.hand_off_focus(parent_id, &bodies_removed, position_map);
         }
         self.ui.body_list_window_state.listed_body_with_popup = None;
     }
 }
 
+/// The slowest and fastest the simulation may run,
+/// from the "Min time speed" and "Max time speed" settings.
+pub(crate) fn sim_speed_limits() -> (f64, f64) {
+    let (min, max) = CONFIG
+        .try_lock()
+        .map(|cfg| (cfg.min_sim_speed.get(), cfg.max_sim_speed.get()))
+        .unwrap_or((DEFAULT_MIN_SIM_SPEED, DEFAULT_MAX_SIM_SPEED));
+
+    let min = if min > 0.0 && min.is_finite() {
+        min
+    } else {
+        DEFAULT_MIN_SIM_SPEED
+    };
+    let max = if max.is_finite() && max >= min {
+        max
+    } else {
+        DEFAULT_MAX_SIM_SPEED.max(min)
+    };
+    (min, max)
+}
+
 impl Default for SimState {
     fn default() -> Self {
+        let universe = Universe::default();
         Self {
-            universe: Universe::default(),
+            loaded_edit_count: universe.edit_count(),
+            universe,
             mu_setter_mode: BulkMuSetterMode::default(),
             sim_speed: 1.0,
             running: true,
             focused_body: 0,
             focus_offset: DVec3::ZERO,
             preview_body: None,
+            reparent_preview: None,
+            reparent_fade: None,
+            vector_overlay: VectorOverlayMode::default(),
+            relative_path_observer: None,
+            rendezvous_target: None,
+            warp_queue: WarpQueue::default(),
+            position_cache: PositionCache::default(),
+            safe_mode: false,
             ui: UiState::default(),
         }
     }
@@ -151,15 +583,15 @@ impl Default for SimState {
 pub(super) fn create(context: &ThreeDContext) -> GUI {
     let gui = GUI::new(context);
     egui_extras::install_image_loaders(gui.context());
-    gui.context().style_mut(|styles| {
-        styles.text_styles.insert(
-            egui::TextStyle::Name(Arc::clone(
-                &*bottom_bar::TIME_SPEED_DRAG_VALUE_TEXT_STYLE_NAME,
-            )),
-            FontId::monospace(16.0),
-        );
-    });
-    gui.context().add_font(FontInsert {
+    text_style::StyleSheet::current().install(gui.context());
+    gui
+}
+
+/// Adds the fallback font for symbols the default fonts lack.
+///
+/// Called while preloading, since rebuilding the font atlas takes a while.
+pub(crate) fn add_fonts(ctx: &EguiContext) {
+    ctx.add_font(FontInsert {
         name: String::from("DejaVuSans"),
         data: FontData::from_static(include_bytes!(
             "../assets/deja_vu_sans/DejaVuSans-subset.ttf"
@@ -169,44 +601,93 @@ pub(super) fn create(context: &ThreeDContext) -> GUI {
             priority: FontPriority::Lowest,
         }],
     });
-    gui
 }
 
 pub(super) fn update(
     gui: &mut GUI,
     sim_state: &mut SimState,
+    tab_titles: &[String],
+    active_tab: usize,
     events: &mut Vec<ThreeDEvent>,
     accumulated_time_ms: f64,
     viewport: Viewport,
-    device_pixel_ratio: f32,
+    pixels_per_point: f32,
     elapsed_time: f64,
     position_map: &HashMap<UniverseId, DVec3>,
+    projector: &WorldProjector,
 ) -> bool {
     if let Ok(frame_duration) = NotNan::new(elapsed_time / 1000.0)
         && frame_duration.is_finite()
     {
         sim_state.ui.frame_data.insert_frame_data(frame_duration);
     }
-    gui.update(
+    metrics::record_frame(elapsed_time, sim_state.universe.get_bodies().len());
+    presentation::record_input(&mut sim_state.ui.presentation_state, events, elapsed_time);
+
+    let click_through = presentation::take_click_through_events(events);
+    let handled = gui.update(
         events,
         accumulated_time_ms,
         viewport,
-        device_pixel_ratio,
-        |ctx| handle_ui(ctx, elapsed_time, sim_state, position_map),
-    )
+        pixels_per_point,
+        |ctx| {
+            handle_ui(
+                ctx,
+                elapsed_time,
+                sim_state,
+                tab_titles,
+                active_tab,
+                position_map,
+                projector,
+            )
+        },
+    );
+    presentation::restore_events(events, click_through);
+    handled
 }
 
 fn handle_ui(
     ctx: &EguiContext,
     elapsed_time: f64,
     sim_state: &mut SimState,
+    tab_titles: &[String],
+    active_tab: usize,
     position_map: &HashMap<UniverseId, DVec3>,
+    projector: &WorldProjector,
 ) {
-    fps::fps_area(ctx, &sim_state.ui.frame_data);
-    welcome::draw(ctx, &mut sim_state.ui.welcome_window_state);
-    bottom_bar::draw(ctx, sim_state, elapsed_time);
-    celestials::celestial_windows(ctx, sim_state, position_map);
-    about::draw(ctx, &mut sim_state.ui);
+    text_style::begin_frame(ctx);
+    help::begin_frame(ctx, &sim_state.ui);
+    presentation::begin_frame(ctx);
+    let bars_hidden = presentation::bars_hidden(&sim_state.ui.presentation_state);
+    tabs::draw(ctx, sim_state, tab_titles, active_tab);
+    if !bars_hidden {
+        fps::fps_area(ctx, &sim_state.ui.frame_data);
+    }
+    gizmo::draw(ctx, sim_state, position_map, projector);
+    orbit_marker::draw(ctx, sim_state, position_map, projector);
+    if !bars_hidden {
+        bottom_bar::draw(ctx, sim_state, elapsed_time);
+    }
+    help::draw_panel(ctx, &mut sim_state.ui);
+    vector_legend::draw(ctx, sim_state);
+    scale_bar::draw(ctx, sim_state, position_map, projector);
+    occlusion_hud::draw(ctx, sim_state, position_map, projector);
+    for window in windows::WINDOWS {
+        (window.draw)(ctx, sim_state, position_map);
+    }
+    celestials::new_body_window(ctx, sim_state);
+    body_switcher::draw(ctx, sim_state, position_map);
+    safe_mode::draw(ctx, sim_state);
+    quit::draw(ctx, &mut sim_state.ui);
+    benchmark_report::draw(ctx, &mut sim_state.ui);
+    toasts::draw(ctx, &mut sim_state.ui);
+    help::end_frame(ctx, &mut sim_state.ui);
+
+    // Loaded last, since everything above was given positions from the old universe
+    if let Some(universe) = sim_state.ui.universe_to_load.take() {
+        sim_state.load_universe(universe);
+    }
+
     ctx.output(|output| {
         for command in &output.commands {
             handle_command(&command);
@@ -283,7 +764,9 @@ fn set_cursor_icon(cursor: CursorIcon) {
 fn handle_command(command: &OutputCommand) {
     match command {
         OutputCommand::CopyText(text) => copy_text(&text),
-        OutputCommand::CopyImage(_) => eprintln!("Copying images is not implemented."),
+        OutputCommand::CopyImage(_) => {
+            notify(Severity::Warning, "Copying images is not implemented.")
+        }
         OutputCommand::OpenUrl(url) => open_url(url),
     }
 }
@@ -325,10 +808,13 @@ fn copy_text(text: &str) {
     {
         if let Ok(mut cb) = arboard::Clipboard::new() {
             if let Err(e) = cb.set_text(text.to_owned()) {
-                eprintln!("Failed to set clipboard text: {e}");
+                notify(
+                    Severity::Error,
+                    format!("Failed to set clipboard text: {e}"),
+                );
             }
         } else {
-            eprintln!("Failed to open clipboard");
+            notify(Severity::Error, "Failed to open clipboard");
         }
     }
 }
@@ -340,6 +826,9 @@ fn open_url(command: &OpenUrl) {
         .expect("url should be openable");
     #[cfg(not(target_family = "wasm"))]
     if let Err(e) = open::that_detached(&command.url) {
-        eprintln!("Failed to open URL '{}': {e}", &command.url);
+        notify(
+            Severity::Error,
+            format!("Failed to open URL '{}': {e}", &command.url),
+        );
     }
 }
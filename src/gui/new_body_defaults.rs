@@ -0,0 +1,312 @@
+//! What new bodies look like before they're edited,
+//! for the add button, the `N` key and the body list's "New child/sibling" buttons.
+
+use keplerian_sim::Orbit;
+use strum::IntoEnumIterator;
+use strum_macros::EnumIter;
+use three_d::{
+    Srgba,
+    egui::{
+        Checkbox, Color32, ComboBox, Context as EguiContext, CursorIcon, DragValue, Grid, RichText,
+        Ui, Window,
+    },
+};
+
+use crate::{
+    cfg::CONFIG,
+    gfx::palette,
+    gui::{UiState, celestials::PreviewBody, declare_id, help::HelpText, text_style::TextRole},
+    sim::{
+        body::Body,
+        universe::{Id as UniverseId, Universe},
+    },
+};
+
+declare_id!(salt_only, NEW_BODY_DEFAULTS_GRID, b"Newborn!");
+declare_id!(salt_only, NAME_PATTERN_COMBO_BOX, b"NameMe:)");
+
+/// How far a new body's periapsis is from its parent's center,
+/// in parent radii.
+const PERIAPSIS_RADIUS_RATIO: f64 = 2.0;
+
+/// How new bodies are named.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, EnumIter)]
+enum NamePattern {
+    /// "Child of Earth"
+    #[default]
+    ChildOf,
+    /// "Earth 2"
+    Numbered,
+    /// "Earth II", like the moons of Jupiter
+    Roman,
+    /// "Earth c", like exoplanets
+    Lettered,
+}
+
+impl NamePattern {
+    const fn name(self) -> &'static str {
+        match self {
+            NamePattern::ChildOf => "Child of …",
+            NamePattern::Numbered => "Numbered",
+            NamePattern::Roman => "Roman numerals",
+            NamePattern::Lettered => "Lettered",
+        }
+    }
+
+    fn from_index(index: u8) -> Self {
+        Self::iter().nth(index as usize).unwrap_or_default()
+    }
+
+    fn to_index(self) -> u8 {
+        Self::iter()
+            .position(|pattern| pattern == self)
+            .unwrap_or(0) as u8
+    }
+
+    /// Names the `nth` satellite of a parent, counting from 1.
+    fn apply(self, parent_name: &str, nth: usize) -> String {
+        match self {
+            NamePattern::ChildOf => format!("Child of {parent_name}"),
+            NamePattern::Numbered => format!("{parent_name} {nth}"),
+            NamePattern::Roman => format!("{parent_name} {}", roman_numeral(nth)),
+            NamePattern::Lettered => format!("{parent_name} {}", letter(nth)),
+        }
+    }
+}
+
+fn roman_numeral(mut n: usize) -> String {
+    const NUMERALS: [(usize, &str); 13] = [
+        (1000, "M"),
+        (900, "CM"),
+        (500, "D"),
+        (400, "CD"),
+        (100, "C"),
+        (90, "XC"),
+        (50, "L"),
+        (40, "XL"),
+        (10, "X"),
+        (9, "IX"),
+        (5, "V"),
+        (4, "IV"),
+        (1, "I"),
+    ];
+
+    let mut out = String::new();
+    for (value, numeral) in NUMERALS {
+        while n >= value {
+            out.push_str(numeral);
+            n -= value;
+        }
+    }
+    out
+}
+
+/// The letter of the `nth` planet of a star: 'b' for the first one,
+/// as 'a' is the star itself. Goes on to "aa", "ab" etc. past 'z'.
+fn letter(nth: usize) -> String {
+    let mut n = nth;
+    let mut out = Vec::new();
+    loop {
+        out.push(b'a' + (n % 26) as u8);
+        n /= 26;
+        if n == 0 {
+            break;
+        }
+        n -= 1;
+    }
+    out.reverse();
+    String::from_utf8(out).unwrap_or_default()
+}
+
+/// The new-body settings, read from the config.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct NewBodyDefaults {
+    radius_ratio: f64,
+    eccentricity: f64,
+    cycle_colors: bool,
+    name_pattern: NamePattern,
+}
+
+impl Default for NewBodyDefaults {
+    fn default() -> Self {
+        Self {
+            radius_ratio: 0.1,
+            eccentricity: 0.0,
+            cycle_colors: false,
+            name_pattern: NamePattern::ChildOf,
+        }
+    }
+}
+
+impl NewBodyDefaults {
+    fn current() -> Self {
+        CONFIG
+            .try_lock()
+            .map(|cfg| Self {
+                radius_ratio: cfg.new_body_radius_ratio.get(),
+                eccentricity: cfg.new_body_eccentricity.get(),
+                cycle_colors: cfg.new_body_cycle_colors.get(),
+                name_pattern: NamePattern::from_index(cfg.new_body_name_pattern.get()),
+            })
+            .unwrap_or_default()
+    }
+}
+
+/// Creates a preview body orbiting the given parent, using the new-body defaults.
+///
+/// Without a parent, this is a default body with no orbit.
+pub(crate) fn new_body_preview(universe: &Universe, parent_id: Option<UniverseId>) -> PreviewBody {
+    let Some(parent) = parent_id.and_then(|id| universe.get_body(id)) else {
+        return PreviewBody {
+            body: Body::default(),
+            parent_id: None,
+        };
+    };
+
+    let defaults = NewBodyDefaults::current();
+    let sibling_count = parent.relations.satellites.len();
+    let parent_radius = parent.body.radius;
+    let mu = parent_id
+        .and_then(|id| universe.get_body_gravitational_parameter(id))
+        .unwrap_or(1.0);
+
+    let color = if defaults.cycle_colors {
+        palette::cycled_color(sibling_count)
+    } else {
+        Srgba::WHITE
+    };
+
+    PreviewBody {
+        body: Body {
+            name: defaults
+                .name_pattern
+                .apply(&parent.body.name, sibling_count + 1),
+            mass: 1.0,
+            radius: parent_radius * defaults.radius_ratio,
+            color,
+            orbit: Some(Orbit::new(
+                defaults.eccentricity,
+                parent_radius * PERIAPSIS_RADIUS_RATIO,
+                0.0,
+                0.0,
+                0.0,
+                0.0,
+                mu,
+            )),
+        },
+        parent_id,
+    }
+}
+
+pub(super) fn draw(ctx: &EguiContext, ui_state: &mut UiState) {
+    Window::new("New Body Defaults")
+        .open(&mut ui_state.is_new_body_defaults_open)
+        .resizable(false)
+        .show(ctx, window_contents);
+}
+
+fn window_contents(ui: &mut Ui) {
+    let Ok(cfg) = CONFIG.try_lock() else {
+        return;
+    };
+
+    Grid::new(NEW_BODY_DEFAULTS_GRID_SALT)
+        .num_columns(2)
+        .spacing([40.0, 4.0])
+        .striped(true)
+        .show(ui, |ui| {
+            ui.label("Radius")
+                .on_help_text(
+                    RichText::new(
+                        "A new body's radius, as a fraction of its parent's radius.\n\
+                        Default: 0.1×",
+                    )
+                    .color(Color32::WHITE)
+                    .text_style(TextRole::Body.into()),
+                )
+                .on_hover_cursor(CursorIcon::Help);
+            let mut radius_ratio = cfg.new_body_radius_ratio.get();
+            let dv = DragValue::new(&mut radius_ratio)
+                .speed(1e-3)
+                .range(1e-6..=10.0)
+                .max_decimals(6)
+                .suffix("×");
+            if ui.add(dv).changed() {
+                let _ = cfg.new_body_radius_ratio.set(radius_ratio);
+            }
+            ui.end_row();
+
+            ui.label("Eccentricity")
+                .on_help_text(
+                    RichText::new(
+                        "A new body's orbital eccentricity.\n\
+                        Its periapsis is always two parent radii from the parent's center.\n\
+                        Default: 0",
+                    )
+                    .color(Color32::WHITE)
+                    .text_style(TextRole::Body.into()),
+                )
+                .on_hover_cursor(CursorIcon::Help);
+            let mut eccentricity = cfg.new_body_eccentricity.get();
+            let dv = DragValue::new(&mut eccentricity)
+                .speed(1e-3)
+                .range(0.0..=0.99)
+                .max_decimals(4);
+            if ui.add(dv).changed() {
+                let _ = cfg.new_body_eccentricity.set(eccentricity);
+            }
+            ui.end_row();
+
+            ui.label("Color")
+                .on_help_text(
+                    RichText::new(
+                        "Give each new satellite of a body the next color of \
+                        the Okabe–Ito palette, instead of making every new body white.",
+                    )
+                    .color(Color32::WHITE)
+                    .text_style(TextRole::Body.into()),
+                )
+                .on_hover_cursor(CursorIcon::Help);
+            let mut cycle_colors = cfg.new_body_cycle_colors.get();
+            let checkbox = Checkbox::new(&mut cycle_colors, "Cycle through palette");
+            if ui.add(checkbox).changed() {
+                let _ = cfg.new_body_cycle_colors.set(cycle_colors);
+            }
+            ui.end_row();
+
+            ui.label("Name")
+                .on_help_text(
+                    RichText::new(
+                        "How new bodies are named after their parent.\n\
+                        Numbers and letters count the parent's satellites.",
+                    )
+                    .color(Color32::WHITE)
+                    .text_style(TextRole::Body.into()),
+                )
+                .on_hover_cursor(CursorIcon::Help);
+            let initial_pattern = NamePattern::from_index(cfg.new_body_name_pattern.get());
+            let mut pattern = initial_pattern;
+            ComboBox::from_id_salt(NAME_PATTERN_COMBO_BOX_SALT)
+                .selected_text(pattern.name())
+                .show_ui(ui, |ui| name_pattern_menu(ui, &mut pattern));
+            if pattern != initial_pattern {
+                let _ = cfg.new_body_name_pattern.set(pattern.to_index());
+            }
+            ui.end_row();
+        });
+}
+
+fn name_pattern_menu(ui: &mut Ui, pattern: &mut NamePattern) {
+    for option in NamePattern::iter() {
+        let mut examples: Vec<_> = (1..=3).map(|nth| option.apply("Jupiter", nth)).collect();
+        examples.dedup();
+        let example = examples.join(", ");
+
+        ui.selectable_value(pattern, option, option.name())
+            .on_help_text(
+                RichText::new(format!("For example: {example}"))
+                    .color(Color32::WHITE)
+                    .text_style(TextRole::Body.into()),
+            );
+    }
+}
@@ -0,0 +1,234 @@
+//! Stepping orbit element fields with the arrow keys while they're focused,
+//! for finer control than dragging.
+//!
+//! Up and down step the value by the element's increment, set in the
+//! settings popup. Holding Alt steps by a tenth of it, and Shift by ten times.
+
+use float_pretty_print::PrettyPrintFloat;
+use strum_macros::EnumIter;
+use three_d::egui::{
+    Color32, CursorIcon, Event, Id as EguiId, Key, Modifiers, Response, RichText, Ui,
+};
+
+use crate::{
+    cfg::{CONFIG, Config, saved_cell::SavedCell},
+    gui::{declare_id, help::HelpText, text_style::TextRole},
+};
+
+declare_id!(salt_only, NUDGE_FIELD, b"NudgeFld");
+
+pub(crate) const DEFAULT_ECCENTRICITY_STEP: f64 = 0.01;
+/// In degrees.
+pub(crate) const DEFAULT_INCLINATION_STEP: f64 = 1.0;
+/// In degrees.
+pub(crate) const DEFAULT_ANGLE_STEP: f64 = 1.0;
+/// In degrees.
+pub(crate) const DEFAULT_ANOMALY_STEP: f64 = 1.0;
+
+/// How much larger a coarse step is than a normal one,
+/// and a normal one than a fine one.
+const SCALE_FACTOR: f64 = 10.0;
+
+/// The kinds of orbit elements, which each have their own increment.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, EnumIter)]
+pub(crate) enum ElementKind {
+    Eccentricity,
+    Inclination,
+    /// The argument of periapsis and the longitude of the ascending node.
+    Angle,
+    /// The mean anomaly at epoch.
+    Anomaly,
+}
+
+impl ElementKind {
+    pub(crate) const fn name(self) -> &'static str {
+        match self {
+            ElementKind::Eccentricity => "Eccentricity step",
+            ElementKind::Inclination => "Inclination step",
+            ElementKind::Angle => "Arg. of Pe./RAAN step",
+            ElementKind::Anomaly => "Mean anomaly step",
+        }
+    }
+
+    pub(crate) const fn default_step(self) -> f64 {
+        match self {
+            ElementKind::Eccentricity => DEFAULT_ECCENTRICITY_STEP,
+            ElementKind::Inclination => DEFAULT_INCLINATION_STEP,
+            ElementKind::Angle => DEFAULT_ANGLE_STEP,
+            ElementKind::Anomaly => DEFAULT_ANOMALY_STEP,
+        }
+    }
+
+    pub(crate) const fn suffix(self) -> &'static str {
+        match self {
+            ElementKind::Eccentricity => "",
+            _ => "°",
+        }
+    }
+
+    /// The setting holding the normal increment.
+    pub(crate) fn step_cell<'c, 'a>(self, cfg: &'c Config<'a>) -> &'c SavedCell<'a, f64> {
+        match self {
+            ElementKind::Eccentricity => &cfg.nudge_eccentricity_step,
+            ElementKind::Inclination => &cfg.nudge_inclination_step,
+            ElementKind::Angle => &cfg.nudge_angle_step,
+            ElementKind::Anomaly => &cfg.nudge_anomaly_step,
+        }
+    }
+
+    /// The normal increment, from the settings.
+    fn step(self) -> f64 {
+        CONFIG
+            .try_lock()
+            .map(|cfg| self.step_cell(&cfg).get())
+            .unwrap_or(self.default_step())
+    }
+}
+
+/// How big a step is, picked with the modifier keys.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Scale {
+    Fine,
+    Normal,
+    Coarse,
+}
+
+impl Scale {
+    fn from_modifiers(modifiers: Modifiers) -> Self {
+        if modifiers.shift {
+            Scale::Coarse
+        } else if modifiers.alt {
+            Scale::Fine
+        } else {
+            Scale::Normal
+        }
+    }
+
+    fn factor(self) -> f64 {
+        match self {
+            Scale::Fine => 1.0 / SCALE_FACTOR,
+            Scale::Normal => 1.0,
+            Scale::Coarse => SCALE_FACTOR,
+        }
+    }
+}
+
+/// Adds an orbit element field that the arrow keys step while it's focused.
+///
+/// `add_widget` adds the field itself. The value is stepped after it,
+/// and the caller is left to keep it in range.
+pub(super) fn nudgeable(
+    ui: &mut Ui,
+    kind: ElementKind,
+    value: &mut f64,
+    add_widget: impl FnOnce(&mut Ui, &mut f64) -> Response,
+) -> Response {
+    ui.horizontal(|ui| {
+        // The field's ID is only known once it's added,
+        // so whether it's focused is checked with last frame's
+        let memory_id = ui.id().with(NUDGE_FIELD_SALT);
+        let focused = ui
+            .data(|data| data.get_temp::<EguiId>(memory_id))
+            .is_some_and(|id| ui.memory(|mem| mem.has_focus(id)));
+
+        // Taken out before the field sees them, so sliders and
+        // drag values don't also step by their own amounts
+        let presses = if focused {
+            take_arrow_presses(ui)
+        } else {
+            Vec::new()
+        };
+
+        let mut response = add_widget(ui, value);
+        ui.data_mut(|data| data.insert_temp(memory_id, response.id));
+
+        let delta = nudge_delta(&presses, kind.step());
+        if delta != 0.0 {
+            *value += delta;
+            response.mark_changed();
+            // Makes a drag value being typed into show the new value
+            ui.data_mut(|data| data.remove::<String>(response.id));
+        }
+
+        if response.has_focus() {
+            let scale = Scale::from_modifiers(ui.input(|i| i.modifiers));
+            let step = kind.step() * scale.factor();
+            ui.label(
+                RichText::new(format!("±{}{}", PrettyPrintFloat(step), kind.suffix()))
+                    .small()
+                    .color(Color32::LIGHT_GRAY),
+            )
+            .on_help_text(
+                RichText::new(
+                    "The up and down arrow keys step this value.\n\
+                    Hold Alt for a tenth of the step, or Shift for ten times it.\n\
+                    The steps can be changed in the settings popup.",
+                )
+                .color(Color32::WHITE)
+                .text_style(TextRole::Body.into()),
+            )
+            .on_hover_cursor(CursorIcon::Help);
+        }
+
+        response
+    })
+    .inner
+}
+
+/// Removes this frame's up and down arrow presses from the input,
+/// returning whether each went up, and the modifiers held with it.
+fn take_arrow_presses(ui: &mut Ui) -> Vec<(bool, Modifiers)> {
+    let mut presses = Vec::new();
+    ui.input_mut(|input| {
+        input.events.retain(|event| match event {
+            Event::Key {
+                key: key @ (Key::ArrowUp | Key::ArrowDown),
+                pressed: true,
+                modifiers,
+                ..
+            } => {
+                presses.push((*key == Key::ArrowUp, *modifiers));
+                false
+            }
+            _ => true,
+        });
+    });
+    presses
+}
+
+fn nudge_delta(presses: &[(bool, Modifiers)], step: f64) -> f64 {
+    presses
+        .iter()
+        .map(|&(up, modifiers)| {
+            let step = step * Scale::from_modifiers(modifiers).factor();
+            if up { step } else { -step }
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn modifiers_scale_the_step() {
+        let presses = [
+            (true, Modifiers::NONE),
+            (true, Modifiers::SHIFT),
+            (false, Modifiers::ALT),
+        ];
+        assert!((nudge_delta(&presses, 1.0) - 10.9).abs() < 1e-12);
+        assert_eq!(nudge_delta(&[(false, Modifiers::NONE)], 0.01), -0.01);
+        assert_eq!(nudge_delta(&[], 1.0), 0.0);
+    }
+
+    #[test]
+    fn shift_wins_over_alt() {
+        let both = Modifiers {
+            shift: true,
+            alt: true,
+            ..Modifiers::NONE
+        };
+        assert_eq!(Scale::from_modifiers(both), Scale::Coarse);
+    }
+}
@@ -0,0 +1,57 @@
+use std::collections::HashMap;
+
+use glam::DVec3;
+use three_d::egui::{Align2, Area, Color32, Context, Frame, Margin, Stroke};
+
+use crate::{
+    gfx::projection::WorldProjector,
+    gui::{SimState, body_theme::BodyTheme, declare_id},
+    sim::{occlusion, universe::Id as UniverseId},
+};
+
+declare_id!(OCCLUSION_HUD_AREA, b"Hidden!?");
+
+/// Tells when the focused body is hidden behind another body,
+/// or is in another body's shadow.
+pub(super) fn draw(
+    ctx: &Context,
+    sim_state: &SimState,
+    position_map: &HashMap<UniverseId, DVec3>,
+    projector: &WorldProjector,
+) {
+    let universe = &sim_state.universe;
+    let focused = sim_state.focused_body();
+
+    let name = move |id| universe.get_body(id).map(|w| &*w.body.name).unwrap_or("?");
+
+    let occluder =
+        occlusion::find_occluder(universe, position_map, projector.camera_position(), focused);
+    let eclipser = occlusion::find_eclipser(universe, position_map, focused);
+
+    if occluder.is_none() && eclipser.is_none() {
+        return;
+    }
+
+    let accent = BodyTheme::of(universe, focused).map_or(Color32::TRANSPARENT, |t| t.accent);
+
+    Area::new(*OCCLUSION_HUD_AREA_ID)
+        .anchor(Align2::CENTER_TOP, [0.0, ctx.available_rect().top() + 12.0])
+        .interactable(false)
+        .show(ctx, |ui| {
+            Frame::new()
+                .fill(Color32::from_black_alpha(192))
+                .stroke(Stroke::new(1.5, accent))
+                .corner_radius(8.0)
+                .inner_margin(Margin::same(8))
+                .show(ui, |ui| {
+                    ui.visuals_mut().override_text_color = Some(Color32::WHITE);
+
+                    if let Some(id) = occluder {
+                        ui.label(format!("{} is behind {}", name(focused), name(id)));
+                    }
+                    if let Some(id) = eclipser {
+                        ui.label(format!("{} is eclipsed by {}", name(focused), name(id)));
+                    }
+                });
+        });
+}
@@ -0,0 +1,168 @@
+use keplerian_sim::Orbit;
+use three_d::egui::{
+    Button, Color32, Context as EguiContext, CursorIcon, DragValue, Grid, RichText, Ui, Window,
+};
+
+use crate::{
+    gui::{
+        SimState,
+        body_theme::{self, BodyTheme},
+        declare_id,
+        help::HelpText,
+        text_style::TextRole,
+    },
+    sim::orbit_check::{self, DEFAULT_SAMPLE_COUNT, Issue, IssueKind},
+};
+
+declare_id!(salt_only, ORBIT_CHECK_GRID, b"OrbCheck");
+
+/// The most issues listed in the window. The copied report has all of them.
+const MAX_LISTED_ISSUES: usize = 200;
+
+pub(super) struct OrbitCheckState {
+    pub(super) open: bool,
+    sample_count: usize,
+    /// The last sweep, with the orbit as it was swept.
+    result: Option<SweepResult>,
+}
+
+struct SweepResult {
+    body_name: String,
+    orbit: Orbit,
+    issues: Vec<Issue>,
+}
+
+impl Default for OrbitCheckState {
+    fn default() -> Self {
+        Self {
+            open: false,
+            sample_count: DEFAULT_SAMPLE_COUNT,
+            result: None,
+        }
+    }
+}
+
+pub(super) fn draw(ctx: &EguiContext, sim_state: &mut SimState) {
+    let mut open = sim_state.ui.orbit_check_state.open;
+
+    let theme = BodyTheme::of(&sim_state.universe, sim_state.focused_body());
+
+    Window::new("Orbit Check")
+        .frame(body_theme::window_frame(ctx, theme))
+        .open(&mut open)
+        .default_width(480.0)
+        .vscroll(true)
+        .show(ctx, |ui| window_contents(ui, sim_state));
+
+    sim_state.ui.orbit_check_state.open &= open;
+}
+
+fn window_contents(ui: &mut Ui, sim_state: &mut SimState) {
+    ui.visuals_mut().override_text_color = Some(Color32::WHITE);
+
+    ui.label(
+        "Evaluate the focused body's orbit all the way around, \
+        and list where the orbit math gives NaN, infinite or jumping values. \
+        The report has the exact elements, to include in a bug report for keplerian_sim.",
+    );
+
+    let focused = sim_state
+        .universe
+        .get_body(sim_state.focused_body())
+        .and_then(|w| Some((&w.body.name, w.body.orbit.as_ref()?)));
+    let state = &mut sim_state.ui.orbit_check_state;
+
+    ui.horizontal(|ui| {
+        ui.label("Samples")
+            .on_help_text(
+                RichText::new(
+                    "How many evenly spaced mean anomalies to evaluate the orbit at.\n\
+                    Open orbits are swept from 20 radians before periapsis to 20 after.",
+                )
+                .color(Color32::WHITE)
+                .text_style(TextRole::Body.into()),
+            )
+            .on_hover_cursor(CursorIcon::Help);
+        ui.add(DragValue::new(&mut state.sample_count).range(16..=100_000));
+
+        let run = ui
+            .add_enabled(focused.is_some(), Button::new("Check orbit"))
+            .on_disabled_hover_text(
+                RichText::new("The focused body isn't orbiting anything.")
+                    .color(Color32::WHITE)
+                    .text_style(TextRole::Body.into()),
+            );
+        if run.clicked()
+            && let Some((name, orbit)) = focused
+        {
+            state.result = Some(SweepResult {
+                body_name: name.clone(),
+                orbit: orbit.clone(),
+                issues: orbit_check::sweep(orbit, state.sample_count),
+            });
+        }
+    });
+
+    let Some(result) = &state.result else {
+        return;
+    };
+
+    ui.separator();
+    ui.horizontal(|ui| {
+        ui.label(format!(
+            "{} issues in the orbit of {}.",
+            result.issues.len(),
+            result.body_name
+        ));
+
+        let copy = ui.button("Copy report").on_help_text(
+            RichText::new(
+                "Copy the orbit's exact elements and every issue found, \
+                to paste into a bug report.",
+            )
+            .color(Color32::WHITE)
+            .text_style(TextRole::Body.into()),
+        );
+        if copy.clicked() {
+            ui.ctx()
+                .copy_text(orbit_check::report(&result.orbit, &result.issues));
+        }
+    });
+
+    if result.issues.is_empty() {
+        ui.label("Everything came out finite and continuous.");
+        return;
+    }
+
+    Grid::new(ORBIT_CHECK_GRID_SALT)
+        .num_columns(3)
+        .spacing([16.0, 4.0])
+        .striped(true)
+        .show(ui, |ui| {
+            ui.label(RichText::new("Mean anomaly").strong());
+            ui.label(RichText::new("Call").strong());
+            ui.label(RichText::new("Problem").strong());
+            ui.end_row();
+
+            for issue in result.issues.iter().take(MAX_LISTED_ISSUES) {
+                ui.label(format!("{:.6} rad", issue.mean_anomaly));
+                ui.label(RichText::new(issue.quantity.call()).monospace());
+                let problem = match issue.kind {
+                    IssueKind::NonFinite => format!("{}", issue.value),
+                    IssueKind::Jump { previous } => {
+                        format!("jumped from {previous:.6e} to {:.6e}", issue.value)
+                    }
+                    IssueKind::RoundTrip => format!("{:.3e} rad off", issue.value),
+                };
+                ui.label(RichText::new(problem).color(Color32::LIGHT_RED));
+                ui.end_row();
+            }
+        });
+
+    if result.issues.len() > MAX_LISTED_ISSUES {
+        ui.label(format!(
+            "…and {} more, included in the report.",
+            result.issues.len() - MAX_LISTED_ISSUES
+        ));
+    }
+}
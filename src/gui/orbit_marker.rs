@@ -0,0 +1,317 @@
+use core::f64::consts::TAU;
+use std::collections::HashMap;
+
+use float_pretty_print::PrettyPrintFloat;
+use glam::DVec3;
+use keplerian_sim::{Orbit, OrbitTrait};
+use three_d::egui::{
+    Area, Button, Color32, Context, Frame, LayerId, Margin, Order, Pos2, RichText, Stroke, Ui, Vec2,
+};
+
+use crate::{
+    gfx::projection::WorldProjector,
+    gui::{SimState, altitude, declare_id, help::HelpText, text_style::TextRole},
+    sim::{
+        maneuver::{self, ManeuverNode},
+        universe::{Id as UniverseId, Universe},
+    },
+    units::time::TimeDisplayMode,
+};
+
+declare_id!(ORBIT_MARKER_AREA, b"WarpHere");
+
+/// How close a click has to be to an orbit line to place a marker, in points.
+const PICK_DISTANCE: f32 = 8.0;
+
+/// How many segments orbits are split into when looking for the clicked point.
+const PICK_SEGMENTS: usize = 256;
+
+/// How much of the way to the asymptote open orbits are checked up to.
+const OPEN_ORBIT_EXTENT: f64 = 0.99;
+
+const MARKER_COLOR: Color32 = Color32::from_rgb(255, 224, 96);
+const NODE_COLOR: Color32 = Color32::from_rgb(96, 224, 255);
+
+/// A point picked on a body's orbit.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(super) struct OrbitMarker {
+    body: UniverseId,
+    true_anomaly: f64,
+}
+
+/// Places a marker where an orbit line is clicked, and offers to warp to it
+/// or plan a maneuver there. Also shows where the planned maneuvers are.
+pub(super) fn draw(
+    ctx: &Context,
+    sim_state: &mut SimState,
+    position_map: &HashMap<UniverseId, DVec3>,
+    projector: &WorldProjector,
+) {
+    let clicked = ctx.input(|i| i.pointer.primary_clicked()) && !ctx.is_pointer_over_area();
+
+    if clicked && let Some(click) = ctx.input(|i| i.pointer.interact_pos()) {
+        sim_state.ui.orbit_marker =
+            pick_orbit_marker(&sim_state.universe, position_map, projector, click);
+    }
+
+    let painter = ctx.layer_painter(LayerId::background());
+
+    for node in &sim_state.universe.maneuver_nodes {
+        let Some((orbit, parent_pos)) =
+            orbit_and_parent(&sim_state.universe, node.body, position_map)
+        else {
+            continue;
+        };
+        if let Some(pos) = projector.project(parent_pos + orbit.get_position_at_time(node.time)) {
+            painter.circle_stroke(pos, 5.0, Stroke::new(2.0, NODE_COLOR));
+        }
+    }
+
+    let Some(marker) = sim_state.ui.orbit_marker else {
+        return;
+    };
+
+    let Some((orbit, parent_pos)) =
+        orbit_and_parent(&sim_state.universe, marker.body, position_map)
+    else {
+        sim_state.ui.orbit_marker = None;
+        return;
+    };
+
+    let Some(screen_pos) =
+        projector.project(parent_pos + orbit.get_position_at_true_anomaly(marker.true_anomaly))
+    else {
+        return;
+    };
+
+    painter.circle(
+        screen_pos,
+        5.0,
+        MARKER_COLOR,
+        Stroke::new(2.0, Color32::BLACK),
+    );
+
+    let time_to_reach = time_to_reach(&sim_state.universe, marker);
+
+    Area::new(*ORBIT_MARKER_AREA_ID)
+        .order(Order::Foreground)
+        .fixed_pos(screen_pos + Vec2::new(12.0, 12.0))
+        .show(ctx, |ui| {
+            Frame::new()
+                .fill(Color32::from_black_alpha(192))
+                .corner_radius(8.0)
+                .inner_margin(Margin::same(8))
+                .show(ui, |ui| {
+                    marker_popup(ui, sim_state, position_map, marker, time_to_reach)
+                });
+        });
+}
+
+fn marker_popup(
+    ui: &mut Ui,
+    sim_state: &mut SimState,
+    position_map: &HashMap<UniverseId, DVec3>,
+    marker: OrbitMarker,
+    time_to_reach: Option<f64>,
+) {
+    ui.visuals_mut().override_text_color = Some(Color32::WHITE);
+
+    let wrapper = sim_state.universe.get_body(marker.body);
+    let locked = wrapper.is_some_and(|w| w.locked);
+    let name = wrapper.map(|w| w.body.name.as_str()).unwrap_or("?");
+    ui.label(RichText::new(name).strong());
+
+    if let Some(wrapper) = wrapper
+        && let Some(orbit) = &wrapper.body.orbit
+    {
+        let distance = orbit.get_altitude_at_true_anomaly(marker.true_anomaly);
+        match altitude::surface_radius(&sim_state.universe, wrapper.relations.parent) {
+            Some(radius) => ui.label(format!(
+                "Surface altitude {} m",
+                PrettyPrintFloat(distance - radius)
+            )),
+            None => ui.label(format!(
+                "Distance from parent {} m",
+                PrettyPrintFloat(distance)
+            )),
+        };
+    }
+
+    match time_to_reach {
+        Some(dt) => ui.label(format!(
+            "Reached in {}",
+            TimeDisplayMode::MultiUnit.format_time(dt)
+        )),
+        None => ui.label("Not reached again"),
+    };
+
+    let enabled = time_to_reach.is_some();
+
+    let warp_button = ui
+        .add_enabled(enabled, Button::new("Warp to this point"))
+        .on_help_text(
+            RichText::new("Advance the universe's time until the body gets here.")
+                .color(Color32::WHITE)
+                .text_style(TextRole::Body.into()),
+        )
+        .on_disabled_hover_text(
+            RichText::new(
+                "The body won't get here, \
+                either because it is frozen or because it's on an open orbit that already passed it.",
+            )
+            .color(Color32::WHITE)
+            .text_style(TextRole::Body.into()),
+        );
+
+    let node_button = ui
+        .add_enabled(enabled && !locked, Button::new("Create maneuver node here"))
+        .on_help_text(
+            RichText::new(
+                "Plan a burn for when the body gets here.\n\
+                Its delta-v can be set in the Edit Body window.",
+            )
+            .color(Color32::WHITE)
+            .text_style(TextRole::Body.into()),
+        )
+        .on_disabled_hover_text(
+            RichText::new(if locked {
+                "The body is locked. Unlock it in the Edit Body window first."
+            } else {
+                "The body won't get here."
+            })
+            .color(Color32::WHITE)
+            .text_style(TextRole::Body.into()),
+        );
+
+    let dismiss_button = ui.button("Dismiss");
+
+    if let Some(dt) = time_to_reach {
+        if warp_button.clicked() {
+            sim_state.universe.tick(dt);
+            sim_state.ui.orbit_marker = None;
+        }
+        if node_button.clicked() {
+            let time = sim_state.universe.time + dt;
+            let node = ManeuverNode::new(marker.body, time);
+            maneuver::add_node(&mut sim_state.universe, node);
+            sim_state.switch_focus(marker.body, position_map);
+            sim_state.ui.edit_body_window_state.window_open = true;
+            sim_state.ui.orbit_marker = None;
+        }
+    }
+    if dismiss_button.clicked() {
+        sim_state.ui.orbit_marker = None;
+    }
+}
+
+fn orbit_and_parent<'a>(
+    universe: &'a Universe,
+    body: UniverseId,
+    position_map: &HashMap<UniverseId, DVec3>,
+) -> Option<(&'a Orbit, DVec3)> {
+    let wrapper = universe.get_body(body)?;
+    let orbit = wrapper.body.orbit.as_ref()?;
+    let parent_pos = position_map.get(&wrapper.relations.parent?).copied()?;
+    Some((orbit, parent_pos))
+}
+
+/// How long until the body reaches the marker.
+///
+/// Returns None if the body is frozen, or is on an open orbit
+/// and has already passed the marker.
+fn time_to_reach(universe: &Universe, marker: OrbitMarker) -> Option<f64> {
+    let wrapper = universe.get_body(marker.body)?;
+    if wrapper.frozen_at.is_some() {
+        return None;
+    }
+    let orbit = wrapper.body.orbit.as_ref()?;
+
+    let dt = orbit.get_time_at_true_anomaly(marker.true_anomaly) - universe.time;
+
+    if orbit.is_closed() {
+        Some(dt.rem_euclid(orbit.get_orbital_period()))
+    } else {
+        (dt >= 0.0).then_some(dt)
+    }
+}
+
+/// Finds the orbit line closest to a click, if any is close enough.
+fn pick_orbit_marker(
+    universe: &Universe,
+    position_map: &HashMap<UniverseId, DVec3>,
+    projector: &WorldProjector,
+    click: Pos2,
+) -> Option<OrbitMarker> {
+    universe
+        .get_bodies()
+        .keys()
+        .filter_map(|&body| {
+            let (orbit, parent_pos) = orbit_and_parent(universe, body, position_map)?;
+            let (true_anomaly, distance) = pick_true_anomaly(orbit, parent_pos, projector, click)?;
+            Some((OrbitMarker { body, true_anomaly }, distance))
+        })
+        .filter(|&(_, distance)| distance <= PICK_DISTANCE)
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(marker, _)| marker)
+}
+
+/// Finds the point on an orbit line closest to a click on the screen.
+///
+/// This walks the conic the same way the trajectory renderer does
+/// (by eccentric anomaly for closed orbits), projects each point,
+/// then interpolates along the closest projected segment.
+///
+/// Returns: The true anomaly of that point, and its distance from the click in points.
+fn pick_true_anomaly(
+    orbit: &Orbit,
+    parent_pos: DVec3,
+    projector: &WorldProjector,
+    click: Pos2,
+) -> Option<(f64, f32)> {
+    // The parameter is the eccentric anomaly for closed orbits,
+    // and the true anomaly itself for open ones.
+    let (start, end) = if orbit.is_closed() {
+        (0.0, TAU)
+    } else {
+        let limit = orbit.get_true_anomaly_at_asymptote() * OPEN_ORBIT_EXTENT;
+        (-limit, limit)
+    };
+    let to_true_anomaly = |param: f64| {
+        if orbit.is_closed() {
+            orbit.get_true_anomaly_at_eccentric_anomaly(param)
+        } else {
+            param
+        }
+    };
+    let param_at = |i: usize| start + (end - start) * i as f64 / PICK_SEGMENTS as f64;
+    let project = |param: f64| {
+        projector.project(parent_pos + orbit.get_position_at_true_anomaly(to_true_anomaly(param)))
+    };
+
+    let mut best: Option<(f64, f32)> = None;
+    let mut prev = project(param_at(0));
+
+    for i in 1..=PICK_SEGMENTS {
+        let next = project(param_at(i));
+
+        if let (Some(a), Some(b)) = (prev, next) {
+            let segment = b - a;
+            let length_sq = segment.length_sq();
+            let t = if length_sq > 0.0 {
+                ((click - a).dot(segment) / length_sq).clamp(0.0, 1.0)
+            } else {
+                0.0
+            };
+            let distance = (a + segment * t).distance(click);
+
+            if best.is_none_or(|(_, best_distance)| distance < best_distance) {
+                let param = param_at(i - 1) + (param_at(i) - param_at(i - 1)) * t as f64;
+                best = Some((param, distance));
+            }
+        }
+
+        prev = next;
+    }
+
+    best.map(|(param, distance)| (to_true_anomaly(param), distance))
+}
@@ -0,0 +1,92 @@
+use core::f64::consts::{PI, TAU};
+
+use three_d::egui::{Align2, Color32, CursorIcon, Response, Sense, Stroke, Ui, Vec2};
+
+use crate::gui::{MIN_TOUCH_TARGET_LEN, text_style::TextRole};
+
+const DIAL_RADIUS: f32 = MIN_TOUCH_TARGET_LEN;
+
+/// The increment the dial snaps to while Shift is held, in radians.
+const DIAL_SNAP: f64 = 5.0 * PI / 180.0;
+
+const RIM_COLOR: Color32 = Color32::from_gray(160);
+const HAND_COLOR: Color32 = Color32::from_rgb(255, 200, 64);
+const TRUE_ANOMALY_COLOR: Color32 = Color32::from_rgb(96, 192, 255);
+
+/// A clock-like dial showing where a body is around its orbit.
+///
+/// Periapsis is at the top and apoapsis at the bottom, with angles
+/// going clockwise. The hand shows `mean_anomaly`, and can be dragged
+/// to change it. The dot on the rim shows `true_anomaly`, which is
+/// where the body actually is, and runs ahead of the hand near periapsis.
+///
+/// Both angles are in radians.
+pub(super) fn phase_dial(ui: &mut Ui, mean_anomaly: &mut f64, true_anomaly: f64) -> Response {
+    let (rect, mut response) =
+        ui.allocate_exact_size(Vec2::splat(DIAL_RADIUS * 2.0), Sense::click_and_drag());
+    let center = rect.center();
+    let radius = DIAL_RADIUS - 2.0;
+
+    if let Some(pointer) = response.interact_pointer_pos()
+        && (response.dragged() || response.clicked())
+        && pointer != center
+    {
+        let offset = pointer - center;
+        // Screen-space Y goes down, so this is clockwise from the top
+        let mut angle = (offset.x as f64).atan2(-offset.y as f64).rem_euclid(TAU);
+        if ui.input(|i| i.modifiers.shift) {
+            angle = ((angle / DIAL_SNAP).round() * DIAL_SNAP).rem_euclid(TAU);
+        }
+        if angle != *mean_anomaly {
+            *mean_anomaly = angle;
+            response.mark_changed();
+        }
+    }
+
+    let at_angle = |angle: f64, length: f32| {
+        let (sin, cos) = angle.sin_cos();
+        center + Vec2::new(sin as f32, -cos as f32) * length
+    };
+
+    let painter = ui.painter_at(rect.expand(2.0));
+    let stroke_width = if response.hovered() || response.dragged() {
+        2.0
+    } else {
+        1.0
+    };
+    painter.circle_stroke(center, radius, Stroke::new(stroke_width, RIM_COLOR));
+
+    for tick in 0..12 {
+        let angle = tick as f64 * TAU / 12.0;
+        let inner = if tick % 3 == 0 { 0.8 } else { 0.9 };
+        painter.line_segment(
+            [at_angle(angle, radius * inner), at_angle(angle, radius)],
+            Stroke::new(1.0, RIM_COLOR),
+        );
+    }
+
+    let font = TextRole::Caption.resolve(ui.style());
+    painter.text(
+        at_angle(0.0, radius * 0.6),
+        Align2::CENTER_CENTER,
+        "Pe",
+        font.clone(),
+        RIM_COLOR,
+    );
+    painter.text(
+        at_angle(PI, radius * 0.6),
+        Align2::CENTER_CENTER,
+        "Ap",
+        font,
+        RIM_COLOR,
+    );
+
+    painter.line_segment(
+        [center, at_angle(*mean_anomaly, radius * 0.85)],
+        Stroke::new(2.5, HAND_COLOR),
+    );
+    painter.circle_filled(center, 3.0, HAND_COLOR);
+    painter.circle_filled(at_angle(true_anomaly, radius), 4.0, TRUE_ANOMALY_COLOR);
+
+    response.on_hover_cursor(CursorIcon::Grab)
+}
@@ -0,0 +1,265 @@
+use three_d::egui::{
+    Button, Color32, ComboBox, Context as EguiContext, CursorIcon, DragValue, Grid,
+    PopupCloseBehavior, RichText, TextWrapMode, Ui, Window,
+};
+
+use crate::{
+    gui::{
+        SimState, celestials::selectable_body_tree, declare_id, help::HelpText,
+        text_style::TextRole,
+    },
+    sim::{
+        phase,
+        universe::{Id as UniverseId, Universe},
+    },
+    units::time::TimeDisplayMode,
+};
+
+declare_id!(salt_only, PHASE_TOOL_GRID, b"PhaseGrd");
+declare_id!(salt_only, PHASE_FROM_COMBO_BOX, b"PhaseFrm");
+declare_id!(salt_only, PHASE_TO_COMBO_BOX, b"PhaseTo!");
+declare_id!(PHASE_FROM_TREE, b"PhaseTre");
+
+pub(super) struct PhaseToolState {
+    pub(super) open: bool,
+    from: Option<UniverseId>,
+    to: Option<UniverseId>,
+    /// The phase angle to wait for, in degrees.
+    target: f64,
+}
+
+impl Default for PhaseToolState {
+    fn default() -> Self {
+        Self {
+            open: false,
+            from: None,
+            to: None,
+            target: 0.0,
+        }
+    }
+}
+
+pub(super) fn draw(ctx: &EguiContext, sim_state: &mut SimState) {
+    let mut open = sim_state.ui.phase_tool_state.open;
+
+    Window::new("Phase Matching")
+        .open(&mut open)
+        .default_width(320.0)
+        .show(ctx, |ui| window_contents(ui, sim_state));
+
+    sim_state.ui.phase_tool_state.open &= open;
+}
+
+fn window_contents(ui: &mut Ui, sim_state: &mut SimState) {
+    ui.visuals_mut().override_text_color = Some(Color32::WHITE);
+
+    let universe = &sim_state.universe;
+    let state = &mut sim_state.ui.phase_tool_state;
+
+    // Forget bodies that were deleted or moved away from each other
+    if state.from.is_some_and(|id| universe.get_body(id).is_none()) {
+        state.from = None;
+    }
+    if let (Some(from), Some(to)) = (state.from, state.to)
+        && siblings(universe, from).all(|id| id != to)
+    {
+        state.to = None;
+    }
+
+    Grid::new(PHASE_TOOL_GRID_SALT)
+        .num_columns(2)
+        .spacing([40.0, 4.0])
+        .striped(true)
+        .show(ui, |ui| phase_grid(ui, universe, state));
+
+    let (Some(from), Some(to)) = (state.from, state.to) else {
+        ui.label("Pick two bodies orbiting the same parent.");
+        return;
+    };
+
+    let time = match phase::time_until_phase(universe, from, to, state.target.to_radians()) {
+        Ok(Some(dt)) => dt,
+        Ok(None) => {
+            ui.label("The target phase angle isn't reached within the next synodic period.");
+            return;
+        }
+        Err(e) => {
+            ui.label(RichText::new(format!("⚠ Can't match phases: {e}")).color(Color32::YELLOW));
+            return;
+        }
+    };
+
+    ui.label(format!(
+        "Reached in {}",
+        TimeDisplayMode::MultiUnit.format_time(time)
+    ));
+
+    ui.horizontal(|ui| {
+        let warp_button = ui.button("Warp there").on_help_text(
+            RichText::new("Advance the universe's time until the phase angle is reached.")
+                .color(Color32::WHITE)
+                .text_style(TextRole::Body.into()),
+        );
+        let pause_button = ui.button("Warp and pause").on_help_text(
+            RichText::new(
+                "Advance the universe's time until the phase angle is reached, \
+                then pause the simulation there.",
+            )
+            .color(Color32::WHITE)
+            .text_style(TextRole::Body.into()),
+        );
+
+        if warp_button.clicked() || pause_button.clicked() {
+            sim_state.universe.tick(time);
+        }
+        if pause_button.clicked() {
+            sim_state.running = false;
+        }
+    });
+}
+
+fn phase_grid(ui: &mut Ui, universe: &Universe, state: &mut PhaseToolState) {
+    ui.label("From")
+        .on_help_text(
+            RichText::new(
+                "The body the phase angle is measured from, \
+                along the direction it orbits in.\n\
+                For a transfer, this is where it leaves from.",
+            )
+            .color(Color32::WHITE)
+            .text_style(TextRole::Body.into()),
+        )
+        .on_hover_cursor(CursorIcon::Help);
+    ComboBox::from_id_salt(PHASE_FROM_COMBO_BOX_SALT)
+        .close_behavior(PopupCloseBehavior::CloseOnClickOutside)
+        .wrap_mode(TextWrapMode::Extend)
+        .selected_text(body_name(universe, state.from))
+        .show_ui(ui, |ui| {
+            selectable_body_tree(ui, *PHASE_FROM_TREE_ID, universe, &mut state.from, None);
+        });
+    ui.end_row();
+
+    ui.label("To")
+        .on_help_text(
+            RichText::new(
+                "The body whose phase angle is measured.\n\
+                It has to orbit the same parent.",
+            )
+            .color(Color32::WHITE)
+            .text_style(TextRole::Body.into()),
+        )
+        .on_hover_cursor(CursorIcon::Help);
+    ComboBox::from_id_salt(PHASE_TO_COMBO_BOX_SALT)
+        .wrap_mode(TextWrapMode::Extend)
+        .selected_text(body_name(universe, state.to))
+        .show_ui(ui, |ui| {
+            let Some(from) = state.from else {
+                ui.label("Pick the first body first.");
+                return;
+            };
+            for id in siblings(universe, from) {
+                let button =
+                    Button::selectable(state.to == Some(id), body_name(universe, Some(id)));
+                if ui.add(button).clicked() {
+                    state.to = Some(id);
+                }
+            }
+        });
+    ui.end_row();
+
+    let (Some(from), Some(to)) = (state.from, state.to) else {
+        return;
+    };
+
+    ui.label("Current phase")
+        .on_help_text(
+            RichText::new(
+                "How far the second body is ahead of the first, as seen from their parent.\n\
+                0° is a conjunction, 180° an opposition.",
+            )
+            .color(Color32::WHITE)
+            .text_style(TextRole::Body.into()),
+        )
+        .on_hover_cursor(CursorIcon::Help);
+    match phase::phase_angle(universe, from, to) {
+        Ok(angle) => ui.label(format!("{:.2}°", angle.to_degrees())),
+        Err(_) => ui.label("—"),
+    };
+    ui.end_row();
+
+    ui.label("Synodic period")
+        .on_help_text(
+            RichText::new("How long it takes for the phase angle to come back around.")
+                .color(Color32::WHITE)
+                .text_style(TextRole::Body.into()),
+        )
+        .on_hover_cursor(CursorIcon::Help);
+    match phase::synodic_period(universe, from, to) {
+        Ok(period) => ui.label(TimeDisplayMode::MultiUnit.format_time(period)),
+        Err(_) => ui.label("—"),
+    };
+    ui.end_row();
+
+    ui.label("Target phase")
+        .on_help_text(
+            RichText::new("The phase angle to wait for.")
+                .color(Color32::WHITE)
+                .text_style(TextRole::Body.into()),
+        )
+        .on_hover_cursor(CursorIcon::Help);
+    ui.add(
+        DragValue::new(&mut state.target)
+            .speed(0.1)
+            .range(0.0..=360.0)
+            .max_decimals(3)
+            .suffix("°"),
+    );
+    ui.end_row();
+
+    ui.label("");
+    ui.horizontal_wrapped(|ui| {
+        if ui.button("Conjunction").clicked() {
+            state.target = 0.0;
+        }
+        if ui.button("Opposition").clicked() {
+            state.target = 180.0;
+        }
+
+        let hohmann = phase::hohmann_phase_angle(universe, from, to);
+        let hohmann_button = ui
+            .add_enabled(hohmann.is_ok(), Button::new("Hohmann transfer"))
+            .on_help_text(
+                RichText::new(
+                    "The phase angle for a Hohmann transfer from the first body's orbit \
+                    to meet the second body, treating both orbits as circles.",
+                )
+                .color(Color32::WHITE)
+                .text_style(TextRole::Body.into()),
+            );
+        if hohmann_button.clicked()
+            && let Ok(angle) = hohmann
+        {
+            state.target = angle.to_degrees();
+        }
+    });
+    ui.end_row();
+}
+
+/// The other bodies orbiting the same parent as this one.
+fn siblings(universe: &Universe, id: UniverseId) -> impl Iterator<Item = UniverseId> + '_ {
+    universe
+        .get_body(id)
+        .and_then(|w| w.relations.parent)
+        .and_then(|parent| universe.get_body(parent))
+        .map(|parent| parent.relations.satellites.as_slice())
+        .unwrap_or_default()
+        .iter()
+        .copied()
+        .filter(move |&sibling| sibling != id)
+}
+
+fn body_name(universe: &Universe, id: Option<UniverseId>) -> &str {
+    id.and_then(|id| universe.get_body(id))
+        .map(|w| &*w.body.name)
+        .unwrap_or("—")
+}
@@ -0,0 +1,127 @@
+//! Presentation mode, for screencasting or projecting the demo.
+//!
+//! Windows become see-through, the bottom bar and FPS counter get out of
+//! the way while nothing is touched, and windows can optionally let clicks
+//! through to the 3D view.
+
+use three_d::{
+    Event as ThreeDEvent, Modifiers,
+    egui::{Context as EguiContext, Visuals},
+};
+
+use crate::cfg::CONFIG;
+
+/// How opaque window backgrounds are in presentation mode by default.
+pub(crate) const DEFAULT_WINDOW_OPACITY: f32 = 0.5;
+
+/// How long nothing has to be touched, in seconds, before
+/// the bottom bar and FPS counter are hidden by default.
+pub(crate) const DEFAULT_HIDE_DELAY_S: f32 = 3.0;
+
+#[derive(Default)]
+pub(super) struct PresentationState {
+    /// How long it's been since the last input, in seconds.
+    idle_s: f64,
+}
+
+/// The presentation mode settings, while it's on.
+#[derive(Clone, Copy)]
+struct Settings {
+    window_opacity: f32,
+    hide_delay_s: f32,
+    click_through: bool,
+}
+
+fn settings() -> Option<Settings> {
+    let cfg = CONFIG.try_lock().ok()?;
+    cfg.presentation_mode.get().then(|| Settings {
+        window_opacity: cfg.presentation_window_opacity.get(),
+        hide_delay_s: cfg.presentation_hide_delay_s.get(),
+        click_through: cfg.presentation_click_through.get(),
+    })
+}
+
+/// Turns presentation mode on or off.
+pub(crate) fn toggle() {
+    if let Ok(cfg) = CONFIG.try_lock() {
+        let _ = cfg.presentation_mode.set(!cfg.presentation_mode.get());
+    }
+}
+
+/// Keeps track of how long nothing has been touched.
+///
+/// Call this with all of the frame's events, before any are held back
+/// from the GUI, so input that goes to the 3D view counts too.
+pub(super) fn record_input(state: &mut PresentationState, events: &[ThreeDEvent], elapsed_ms: f64) {
+    if events.is_empty() {
+        state.idle_s += elapsed_ms / 1000.0;
+    } else {
+        state.idle_s = 0.0;
+    }
+}
+
+/// Makes window backgrounds see-through while presentation mode is on.
+pub(super) fn begin_frame(ctx: &EguiContext) {
+    let opacity = settings()
+        .map(|settings| settings.window_opacity.clamp(0.0, 1.0))
+        .unwrap_or(1.0);
+    let visuals = if ctx.style().visuals.dark_mode {
+        Visuals::dark()
+    } else {
+        Visuals::light()
+    };
+    let fill = visuals.window_fill.gamma_multiply(opacity);
+
+    if ctx.style().visuals.window_fill != fill {
+        ctx.style_mut(|style| style.visuals.window_fill = fill);
+    }
+}
+
+/// Whether the bottom bar and FPS counter are hidden, because presentation
+/// mode is on and nothing has been touched for a while.
+pub(super) fn bars_hidden(state: &PresentationState) -> bool {
+    settings().is_some_and(|settings| state.idle_s >= settings.hide_delay_s as f64)
+}
+
+/// Takes out the pointer events that should go to the 3D view instead of
+/// windows, along with where they were, to be put back with [`restore_events`].
+///
+/// Only does anything while presentation mode has click-through on.
+/// Holding Alt lets the pointer reach windows again.
+pub(super) fn take_click_through_events(
+    events: &mut Vec<ThreeDEvent>,
+) -> Vec<(usize, ThreeDEvent)> {
+    if !settings().is_some_and(|settings| settings.click_through) {
+        return Vec::new();
+    }
+
+    let mut taken = Vec::new();
+    let mut index = 0;
+    events.retain(|event| {
+        let through = pointer_modifiers(event).is_some_and(|modifiers| !modifiers.alt);
+        if through {
+            taken.push((index, event.clone()));
+        }
+        index += 1;
+        !through
+    });
+    taken
+}
+
+/// Puts events taken out by [`take_click_through_events`] back where they were.
+pub(super) fn restore_events(events: &mut Vec<ThreeDEvent>, taken: Vec<(usize, ThreeDEvent)>) {
+    for (index, event) in taken {
+        events.insert(index.min(events.len()), event);
+    }
+}
+
+fn pointer_modifiers(event: &ThreeDEvent) -> Option<&Modifiers> {
+    match event {
+        ThreeDEvent::MousePress { modifiers, .. }
+        | ThreeDEvent::MouseRelease { modifiers, .. }
+        | ThreeDEvent::MouseMotion { modifiers, .. }
+        | ThreeDEvent::MouseWheel { modifiers, .. }
+        | ThreeDEvent::PinchGesture { modifiers, .. } => Some(modifiers),
+        _ => None,
+    }
+}
@@ -0,0 +1,524 @@
+use three_d::egui::{
+    Button, Color32, ComboBox, Context as EguiContext, CursorIcon, Grid, RichText, TextEdit, Ui,
+    Window,
+};
+
+use crate::{
+    gui::{UiState, converter::labelled_number, declare_id, help::HelpText, text_style::TextRole},
+    notify::{Severity, notify},
+    sim::{
+        PRESETS_TOML,
+        preset_schema::{self, EccentricityDefiner, PresetCatalog, PresetEntry, PresetError},
+    },
+};
+
+declare_id!(salt_only, PRESET_EDITOR_ENTRY_COMBO_BOX, b"PrstPick");
+declare_id!(salt_only, PRESET_EDITOR_GRID, b"PrstGrid");
+
+#[derive(Default)]
+pub(super) struct PresetEditorState {
+    pub(super) open: bool,
+    /// Loaded from the bundled presets when the editor is first opened.
+    catalog: Option<PresetCatalog>,
+    selected: usize,
+    /// The color field of the selected entry, as typed.
+    color_text: String,
+    color_error: Option<PresetError>,
+    import_text: String,
+    import_result: Option<Result<usize, PresetError>>,
+}
+
+impl PresetEditorState {
+    fn select(&mut self, index: usize) {
+        self.selected = index;
+        self.color_error = None;
+        self.color_text = self
+            .catalog
+            .as_ref()
+            .and_then(|c| c.entries.get(index))
+            .map(|e| preset_schema::color_to_toml(e.color))
+            .unwrap_or_default();
+    }
+
+    fn reload(&mut self) {
+        self.catalog = match preset_schema::parse_catalog(PRESETS_TOML) {
+            Ok(c) => Some(c),
+            Err(e) => {
+                notify(
+                    Severity::Error,
+                    format!("Failed to load bundled presets: {e}"),
+                );
+                Some(PresetCatalog::default())
+            }
+        };
+        self.select(0);
+    }
+}
+
+pub(super) fn draw(ctx: &EguiContext, ui_state: &mut UiState) {
+    let state = &mut ui_state.preset_editor_state;
+
+    if !state.open {
+        return;
+    }
+
+    if state.catalog.is_none() {
+        state.reload();
+    }
+
+    let mut open = state.open;
+
+    Window::new("Preset Editor")
+        .open(&mut open)
+        .default_width(360.0)
+        .vscroll(true)
+        .show(ctx, |ui| window_contents(ui, state));
+
+    state.open &= open;
+}
+
+fn window_contents(ui: &mut Ui, state: &mut PresetEditorState) {
+    ui.visuals_mut().override_text_color = Some(Color32::WHITE);
+
+    toolbar(ui, state);
+    ui.separator();
+
+    let Some(catalog) = &mut state.catalog else {
+        return;
+    };
+
+    if let Some(entry) = catalog.entries.get_mut(state.selected) {
+        Grid::new(PRESET_EDITOR_GRID_SALT)
+            .num_columns(2)
+            .spacing([40.0, 4.0])
+            .striped(true)
+            .show(ui, |ui| {
+                entry_fields(ui, entry, &mut state.color_text, &mut state.color_error)
+            });
+    } else {
+        ui.label("The catalog is empty. Add an entry to start editing.");
+    }
+
+    ui.separator();
+    export_buttons(ui, state);
+    ui.separator();
+    import_section(ui, state);
+}
+
+fn toolbar(ui: &mut Ui, state: &mut PresetEditorState) {
+    let Some(catalog) = &mut state.catalog else {
+        return;
+    };
+
+    let selected_text = catalog
+        .entries
+        .get(state.selected)
+        .map(|e| e.key.as_str())
+        .unwrap_or("None");
+
+    let mut selection = None;
+
+    ComboBox::from_id_salt(PRESET_EDITOR_ENTRY_COMBO_BOX_SALT)
+        .selected_text(selected_text)
+        .width(ui.available_width())
+        .show_ui(ui, |ui| {
+            for (index, entry) in catalog.entries.iter().enumerate() {
+                let text = format!("{} ({})", entry.key, entry.name);
+                if ui
+                    .add(Button::selectable(index == state.selected, text))
+                    .clicked()
+                {
+                    selection = Some(index);
+                }
+            }
+        });
+
+    let mut new_button = false;
+    let mut delete_button = false;
+    let mut reload_button = false;
+
+    ui.horizontal(|ui| {
+        new_button = ui.button("New entry").clicked();
+        delete_button = ui
+            .add_enabled(!catalog.entries.is_empty(), Button::new("Delete entry"))
+            .clicked();
+        reload_button = ui
+            .button("Reload catalog")
+            .on_help_text(
+                RichText::new(
+                    "Discard all changes and reload the presets \
+                    this version of the app was built with.",
+                )
+                .color(Color32::WHITE)
+                .text_style(TextRole::Body.into()),
+            )
+            .clicked();
+    });
+
+    if new_button {
+        let entry = new_entry(catalog);
+        catalog.entries.push(entry);
+        selection = Some(catalog.entries.len() - 1);
+    }
+
+    if delete_button && state.selected < catalog.entries.len() {
+        catalog.entries.remove(state.selected);
+        selection = Some(state.selected.saturating_sub(1));
+    }
+
+    if reload_button {
+        state.reload();
+    } else if let Some(index) = selection {
+        state.select(index);
+    }
+}
+
+fn new_entry(catalog: &PresetCatalog) -> PresetEntry {
+    let key = (1..)
+        .map(|i| format!("new_body_{i}"))
+        .find(|key| catalog.entries.iter().all(|e| &e.key != key))
+        .unwrap_or_default();
+
+    PresetEntry {
+        key,
+        name: String::from("New Body"),
+        docname: None,
+        description: None,
+        mass: 1.0,
+        radius: 1.0,
+        ecc_definer: EccentricityDefiner::Eccentricity(0.0),
+        periapsis: 1.0,
+        inclination: 0.0,
+        arg_pe: 0.0,
+        long_asc_node: 0.0,
+        mean_anomaly: 0.0,
+        color: [255; 4],
+        epoch: None,
+    }
+}
+
+fn text_row(ui: &mut Ui, name: &str, tooltip: &str, value: &mut String) {
+    ui.label(name)
+        .on_help_text(
+            RichText::new(tooltip)
+                .color(Color32::WHITE)
+                .text_style(TextRole::Body.into()),
+        )
+        .on_hover_cursor(CursorIcon::Help);
+    ui.add(TextEdit::singleline(value).desired_width(f32::INFINITY));
+    ui.end_row();
+}
+
+fn optional_text_row(ui: &mut Ui, name: &str, tooltip: &str, value: &mut Option<String>) {
+    let mut text = value.clone().unwrap_or_default();
+    text_row(ui, name, tooltip, &mut text);
+
+    if text.is_empty() {
+        *value = None;
+    } else if value.as_ref() != Some(&text) {
+        *value = Some(text);
+    }
+}
+
+fn entry_fields(
+    ui: &mut Ui,
+    entry: &mut PresetEntry,
+    color_text: &mut String,
+    color_error: &mut Option<PresetError>,
+) {
+    text_row(
+        ui,
+        "Table name",
+        "The name of the entry's TOML table.\n\
+        It becomes the preset's function name, so it must be snake_case.",
+        &mut entry.key,
+    );
+    text_row(
+        ui,
+        "Name",
+        "The body's name, in Title Case.",
+        &mut entry.name,
+    );
+    optional_text_row(
+        ui,
+        "Doc. name",
+        "The name as used mid-sentence, e.g. \"the Sun\".\n\
+        Leave empty to use the name.",
+        &mut entry.docname,
+    );
+    optional_text_row(
+        ui,
+        "Description",
+        "A short mid-sentence description, e.g. \"the closest planet to the Sun\".\n\
+        Leave empty to omit.",
+        &mut entry.description,
+    );
+
+    labelled_number(ui, "Mass", "The mass of the body.", &mut entry.mass, " kg");
+    labelled_number(
+        ui,
+        "Radius",
+        "The radius of the body.",
+        &mut entry.radius,
+        " m",
+    );
+
+    ui.label("Orbit shape")
+        .on_help_text(
+            RichText::new(
+                "Whether the orbit's shape is given by its eccentricity or its apoapsis.\n\
+                Sources differ in which they give, so pick whichever yours has.\n\
+                An entry may only define one of them.",
+            )
+            .color(Color32::WHITE)
+            .text_style(TextRole::Body.into()),
+        )
+        .on_hover_cursor(CursorIcon::Help);
+    ui.horizontal(|ui| {
+        let is_eccentricity = matches!(entry.ecc_definer, EccentricityDefiner::Eccentricity(_));
+        if ui
+            .add(Button::selectable(is_eccentricity, "Eccentricity"))
+            .clicked()
+        {
+            entry.ecc_definer = EccentricityDefiner::Eccentricity(entry.eccentricity());
+        }
+        // Open orbits have no apoapsis
+        let apoapsis_button = ui.add_enabled(
+            entry.eccentricity() < 1.0,
+            Button::selectable(!is_eccentricity, "Apoapsis"),
+        );
+        if apoapsis_button.clicked() && is_eccentricity {
+            let apoapsis =
+                entry.periapsis * (1.0 + entry.eccentricity()) / (1.0 - entry.eccentricity());
+            entry.ecc_definer = EccentricityDefiner::Apoapsis(apoapsis);
+        }
+    });
+    ui.end_row();
+
+    match &mut entry.ecc_definer {
+        EccentricityDefiner::Eccentricity(eccentricity) => labelled_number(
+            ui,
+            "Eccentricity",
+            "How eccentric the orbit is.",
+            eccentricity,
+            "",
+        ),
+        EccentricityDefiner::Apoapsis(apoapsis) => labelled_number(
+            ui,
+            "Apoapsis",
+            "The maximum distance of the orbit to the center of the parent body.",
+            apoapsis,
+            " m",
+        ),
+    }
+
+    labelled_number(
+        ui,
+        "Periapsis",
+        "The minimum distance of the orbit to the center of the parent body.",
+        &mut entry.periapsis,
+        " m",
+    );
+    labelled_number(
+        ui,
+        "Inclination",
+        "How inclined from the ecliptic the orbit is.",
+        &mut entry.inclination,
+        "°",
+    );
+    labelled_number(
+        ui,
+        "Arg. of Pe.",
+        "The angle offset of the periapsis along the orbital plane.",
+        &mut entry.arg_pe,
+        "°",
+    );
+    labelled_number(
+        ui,
+        "RAAN",
+        "The longitude of the ascending node.",
+        &mut entry.long_asc_node,
+        "°",
+    );
+    labelled_number(
+        ui,
+        "Mean anom.",
+        "The mean anomaly at the entry's epoch.",
+        &mut entry.mean_anomaly,
+        "°",
+    );
+
+    ui.label("Color")
+        .on_help_text(
+            RichText::new(
+                "The sRGB color of the body, written as it would be in the file:\n\
+                either an integer like 0xFFF3EAFF, \
+                or an array like [255, 243, 234] or [255, 243, 234, 255].",
+            )
+            .color(Color32::WHITE)
+            .text_style(TextRole::Body.into()),
+        )
+        .on_hover_cursor(CursorIcon::Help);
+    let color_edit = ui.add(TextEdit::singleline(color_text).desired_width(f32::INFINITY));
+    if color_edit.changed() {
+        match preset_schema::parse_color(color_text) {
+            Ok(color) => {
+                entry.color = color;
+                *color_error = None;
+            }
+            Err(e) => *color_error = Some(e),
+        }
+    }
+    ui.end_row();
+
+    ui.label("Own epoch")
+        .on_help_text(
+            RichText::new(
+                "The Julian Date the mean anomaly is measured at.\n\
+                When unchecked, the file's top-level epoch is used.",
+            )
+            .color(Color32::WHITE)
+            .text_style(TextRole::Body.into()),
+        )
+        .on_hover_cursor(CursorIcon::Help);
+    let mut has_epoch = entry.epoch.is_some();
+    if ui.checkbox(&mut has_epoch, "").changed() {
+        // J2000
+        entry.epoch = has_epoch.then_some(2451545.0);
+    }
+    ui.end_row();
+
+    if let Some(epoch) = &mut entry.epoch {
+        labelled_number(ui, "Epoch", "A Julian Date (TDB).", epoch, " JD");
+    }
+}
+
+/// Finds a reason the catalog can't be exported, if any.
+fn catalog_problem(state: &PresetEditorState) -> Option<String> {
+    if let Some(e) = &state.color_error {
+        return Some(e.to_string());
+    }
+
+    let catalog = state.catalog.as_ref()?;
+
+    if let Some(entry) = catalog.entries.get(state.selected)
+        && let Err(e) = entry.validate()
+    {
+        return Some(e.to_string());
+    }
+
+    catalog
+        .to_toml()
+        .and_then(|toml| preset_schema::parse_catalog(&toml))
+        .err()
+        .map(|e| e.to_string())
+}
+
+fn export_buttons(ui: &mut Ui, state: &PresetEditorState) {
+    let problem = catalog_problem(state);
+
+    if let Some(problem) = &problem {
+        ui.label(RichText::new(format!("⚠ {problem}")).color(Color32::YELLOW));
+    }
+
+    let Some(catalog) = &state.catalog else {
+        return;
+    };
+    let entry = catalog.entries.get(state.selected);
+
+    ui.horizontal(|ui| {
+        let copy_entry = ui
+            .add_enabled(
+                problem.is_none() && entry.is_some(),
+                Button::new("Copy entry"),
+            )
+            .on_help_text(
+                RichText::new("Copy the selected entry as a presets.toml snippet.")
+                    .color(Color32::WHITE)
+                    .text_style(TextRole::Body.into()),
+            );
+        if copy_entry.clicked()
+            && let Some(Ok(toml)) = entry.map(PresetEntry::to_toml)
+        {
+            ui.ctx().copy_text(toml);
+        }
+
+        let copy_catalog = ui
+            .add_enabled(problem.is_none(), Button::new("Copy catalog"))
+            .on_help_text(
+                RichText::new(
+                    "Copy every entry as a whole presets.toml file.\n\
+                    Comments in the original file are not kept.",
+                )
+                .color(Color32::WHITE)
+                .text_style(TextRole::Body.into()),
+            );
+        if copy_catalog.clicked()
+            && let Ok(toml) = catalog.to_toml()
+        {
+            ui.ctx().copy_text(toml);
+        }
+    });
+}
+
+fn import_section(ui: &mut Ui, state: &mut PresetEditorState) {
+    let header = RichText::new("Import")
+        .color(Color32::WHITE)
+        .text_style(TextRole::Body.into())
+        .underline();
+
+    let coll_res = ui.collapsing(header, |ui| {
+        ui.label(
+            "Paste presets.toml entries below. \
+            Entries with the same table name as existing ones replace them.",
+        );
+        ui.add(
+            TextEdit::multiline(&mut state.import_text)
+                .code_editor()
+                .desired_rows(8)
+                .desired_width(f32::INFINITY),
+        );
+
+        if ui.button("Import").clicked() {
+            state.import_result = Some(import(state));
+        }
+
+        match &state.import_result {
+            Some(Ok(count)) => {
+                ui.label(format!("Imported {count} entries."));
+            }
+            Some(Err(e)) => {
+                ui.label(RichText::new(format!("⚠ {e}")).color(Color32::YELLOW));
+            }
+            None => (),
+        }
+    });
+
+    coll_res
+        .header_response
+        .on_hover_cursor(CursorIcon::PointingHand);
+}
+
+/// Merges the import text into the catalog.
+///
+/// Returns: How many entries were imported.
+fn import(state: &mut PresetEditorState) -> Result<usize, PresetError> {
+    let imported = preset_schema::parse_catalog(&state.import_text)?;
+    let catalog = state.catalog.get_or_insert_default();
+    let count = imported.entries.len();
+
+    if imported.epoch.is_some() {
+        catalog.epoch = imported.epoch;
+    }
+
+    for entry in imported.entries {
+        match catalog.entries.iter_mut().find(|e| e.key == entry.key) {
+            Some(existing) => *existing = entry,
+            None => catalog.entries.push(entry),
+        }
+    }
+
+    state.select(state.selected);
+
+    Ok(count)
+}
@@ -0,0 +1,84 @@
+//! Quitting the program from the options menu or with Ctrl+Q.
+//!
+//! Universes aren't saved anywhere, so quitting while any tab's universe
+//! has been edited asks first. On the web, the browser tab is closed instead,
+//! so quit requests are ignored there.
+
+use three_d::egui::{Align2, Color32, Context as EguiContext, RichText, Window};
+
+use crate::gui::{UiState, text_style::TextRole};
+
+/// Where quitting is at. Requests are handled by the program after the frame,
+/// since only it can see every tab.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub(crate) enum QuitState {
+    #[default]
+    Idle,
+    /// Quitting was asked for, but the tabs haven't been checked yet.
+    Requested,
+    /// Waiting for the user to confirm losing the edits to these tabs.
+    Confirming(Vec<String>),
+    /// The user agreed to quit.
+    Confirmed,
+}
+
+/// Asks to quit, if the program can quit itself.
+pub(crate) fn request(ui_state: &mut UiState) {
+    if cfg!(target_family = "wasm") {
+        log::debug!("Ignored a request to quit, since the page can't close itself");
+        return;
+    }
+    if ui_state.quit == QuitState::Idle {
+        ui_state.quit = QuitState::Requested;
+    }
+}
+
+/// Draws the confirmation while one is needed.
+pub(super) fn draw(ctx: &EguiContext, ui_state: &mut UiState) {
+    let QuitState::Confirming(edited_tabs) = &ui_state.quit else {
+        return;
+    };
+
+    let mut choice = None;
+    Window::new("Quit?")
+        .collapsible(false)
+        .resizable(false)
+        .anchor(Align2::CENTER_CENTER, [0.0, 0.0])
+        .show(ctx, |ui| {
+            ui.label(
+                RichText::new("These universes were changed since they were loaded:")
+                    .color(Color32::WHITE)
+                    .text_style(TextRole::Body.into()),
+            );
+            for title in edited_tabs {
+                ui.label(RichText::new(format!("• {title}")).text_style(TextRole::Body.into()));
+            }
+            ui.label(
+                RichText::new(
+                    "Universes aren't saved, so the changes will be lost. \
+                    Settings are kept either way.",
+                )
+                .color(Color32::WHITE)
+                .text_style(TextRole::Body.into()),
+            );
+
+            ui.horizontal(|ui| {
+                if ui
+                    .button(RichText::new("Quit").text_style(TextRole::Body.into()))
+                    .clicked()
+                {
+                    choice = Some(QuitState::Confirmed);
+                }
+                if ui
+                    .button(RichText::new("Cancel").text_style(TextRole::Body.into()))
+                    .clicked()
+                {
+                    choice = Some(QuitState::Idle);
+                }
+            });
+        });
+
+    if let Some(choice) = choice {
+        ui_state.quit = choice;
+    }
+}
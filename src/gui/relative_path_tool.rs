@@ -0,0 +1,140 @@
+use three_d::egui::{
+    Color32, ComboBox, Context as EguiContext, CursorIcon, Grid, PopupCloseBehavior, RichText,
+    TextWrapMode, Ui, Window,
+};
+
+use crate::{
+    gui::{
+        SimState, celestials::selectable_body_tree, declare_id, help::HelpText,
+        text_style::TextRole,
+    },
+    sim::{
+        relative_path::relative_path_span,
+        universe::{Id as UniverseId, Universe},
+    },
+    units::time::TimeDisplayMode,
+};
+
+declare_id!(salt_only, RELATIVE_PATH_GRID, b"RelPaGrd");
+declare_id!(salt_only, RELATIVE_PATH_COMBO_BOX, b"RelPaObs");
+declare_id!(RELATIVE_PATH_TREE, b"RelPaTre");
+
+#[derive(Default)]
+pub(super) struct RelativePathState {
+    pub(super) open: bool,
+}
+
+pub(super) fn draw(ctx: &EguiContext, sim_state: &mut SimState) {
+    let mut open = sim_state.ui.relative_path_state.open;
+
+    Window::new("Relative Path")
+        .open(&mut open)
+        .default_width(320.0)
+        .show(ctx, |ui| window_contents(ui, sim_state));
+
+    sim_state.ui.relative_path_state.open &= open;
+}
+
+fn window_contents(ui: &mut Ui, sim_state: &mut SimState) {
+    ui.visuals_mut().override_text_color = Some(Color32::WHITE);
+
+    let target = sim_state.focused_body();
+    let universe = &sim_state.universe;
+    let observer = &mut sim_state.relative_path_observer;
+
+    // Forget bodies that were deleted
+    if observer.is_some_and(|id| universe.get_body(id).is_none()) {
+        *observer = None;
+    }
+
+    Grid::new(RELATIVE_PATH_GRID_SALT)
+        .num_columns(2)
+        .spacing([40.0, 4.0])
+        .striped(true)
+        .show(ui, |ui| relative_path_grid(ui, universe, target, observer));
+
+    let Some(observer) = *observer else {
+        ui.label("Pick a body to see the focused body's path from.");
+        return;
+    };
+    if observer == target {
+        ui.label("Focus on a different body to see its path from here.");
+        return;
+    }
+
+    if ui
+        .button("Stop showing")
+        .on_help_text(
+            RichText::new("Hide the relative path.")
+                .color(Color32::WHITE)
+                .text_style(TextRole::Body.into()),
+        )
+        .clicked()
+    {
+        sim_state.relative_path_observer = None;
+    }
+}
+
+fn relative_path_grid(
+    ui: &mut Ui,
+    universe: &Universe,
+    target: UniverseId,
+    observer: &mut Option<UniverseId>,
+) {
+    ui.label("Path of")
+        .on_help_text(
+            RichText::new("The focused body, whose path is drawn.")
+                .color(Color32::WHITE)
+                .text_style(TextRole::Body.into()),
+        )
+        .on_hover_cursor(CursorIcon::Help);
+    ui.label(body_name(universe, Some(target)));
+    ui.end_row();
+
+    ui.label("Seen from")
+        .on_help_text(
+            RichText::new(
+                "The body the path is drawn around, as if it stood still.\n\
+                Try Earth while focused on Mars to see its retrograde loops.",
+            )
+            .color(Color32::WHITE)
+            .text_style(TextRole::Body.into()),
+        )
+        .on_hover_cursor(CursorIcon::Help);
+    ComboBox::from_id_salt(RELATIVE_PATH_COMBO_BOX_SALT)
+        .close_behavior(PopupCloseBehavior::CloseOnClickOutside)
+        .wrap_mode(TextWrapMode::Extend)
+        .selected_text(body_name(universe, *observer))
+        .show_ui(ui, |ui| {
+            selectable_body_tree(ui, *RELATIVE_PATH_TREE_ID, universe, observer, None);
+        });
+    ui.end_row();
+
+    let Some(observer) = *observer else {
+        return;
+    };
+
+    ui.label("Time span")
+        .on_help_text(
+            RichText::new(
+                "How much time the path covers, centered on now.\n\
+                For bodies orbiting the same parent, this is their synodic period, \
+                after which the path repeats.\n\
+                Otherwise it's the longer of the two orbital periods.",
+            )
+            .color(Color32::WHITE)
+            .text_style(TextRole::Body.into()),
+        )
+        .on_hover_cursor(CursorIcon::Help);
+    match relative_path_span(universe, target, observer) {
+        Some(span) if observer != target => ui.label(TimeDisplayMode::MultiUnit.format_time(span)),
+        _ => ui.label("—"),
+    };
+    ui.end_row();
+}
+
+fn body_name(universe: &Universe, id: Option<UniverseId>) -> &str {
+    id.and_then(|id| universe.get_body(id))
+        .map(|w| &*w.body.name)
+        .unwrap_or("—")
+}
@@ -0,0 +1,172 @@
+use float_pretty_print::PrettyPrintFloat;
+use three_d::egui::{
+    Color32, ComboBox, Context as EguiContext, CursorIcon, Grid, PopupCloseBehavior, RichText,
+    TextWrapMode, Ui, Window,
+};
+
+use crate::{
+    gui::{
+        SimState, celestials::selectable_body_tree, declare_id, help::HelpText,
+        text_style::TextRole,
+    },
+    sim::{
+        rendezvous::relative_state,
+        universe::{Id as UniverseId, Universe},
+    },
+};
+
+declare_id!(salt_only, RENDEZVOUS_GRID, b"RdvzGrid");
+declare_id!(salt_only, RENDEZVOUS_COMBO_BOX, b"RdvzTrgt");
+declare_id!(RENDEZVOUS_TREE, b"RdvzTree");
+
+#[derive(Default)]
+pub(super) struct RendezvousState {
+    pub(super) open: bool,
+}
+
+pub(super) fn draw(ctx: &EguiContext, sim_state: &mut SimState) {
+    let mut open = sim_state.ui.rendezvous_state.open;
+
+    Window::new("Rendezvous Camera")
+        .open(&mut open)
+        .default_width(300.0)
+        .show(ctx, |ui| window_contents(ui, sim_state));
+
+    sim_state.ui.rendezvous_state.open &= open;
+}
+
+fn window_contents(ui: &mut Ui, sim_state: &mut SimState) {
+    ui.visuals_mut().override_text_color = Some(Color32::WHITE);
+
+    let observer = sim_state.focused_body();
+    let universe = &sim_state.universe;
+    let mut target = sim_state.rendezvous_target;
+
+    Grid::new(RENDEZVOUS_GRID_SALT)
+        .num_columns(2)
+        .spacing([40.0, 4.0])
+        .striped(true)
+        .show(ui, |ui| {
+            rendezvous_grid(ui, universe, observer, &mut target)
+        });
+
+    if target != sim_state.rendezvous_target {
+        sim_state.rendezvous_target = target;
+        sim_state.ui.rendezvous_aim_requested = true;
+    }
+
+    let Some(target) = target else {
+        ui.label("Pick a body to keep the camera pointed at.");
+        return;
+    };
+    if target == observer {
+        ui.label("Focus on a different body to watch this one from it.");
+        return;
+    }
+
+    ui.horizontal(|ui| {
+        let aim = ui.button("Aim at target").on_help_text(
+            RichText::new(
+                "Turn the camera to look past the focused body towards the target again.",
+            )
+            .color(Color32::WHITE)
+            .text_style(TextRole::Body.into()),
+        );
+        if aim.clicked() {
+            sim_state.ui.rendezvous_aim_requested = true;
+        }
+
+        let stop = ui.button("Stop").on_help_text(
+            RichText::new("Let the camera turn freely again.")
+                .color(Color32::WHITE)
+                .text_style(TextRole::Body.into()),
+        );
+        if stop.clicked() {
+            sim_state.rendezvous_target = None;
+        }
+    });
+}
+
+fn rendezvous_grid(
+    ui: &mut Ui,
+    universe: &Universe,
+    observer: UniverseId,
+    target: &mut Option<UniverseId>,
+) {
+    ui.label("Camera on")
+        .on_help_text(
+            RichText::new(
+                "The focused body, which the camera rides along with.\n\
+                The camera turns with its orbit, so the target's drift \
+                shows up the way it would from a docking camera.",
+            )
+            .color(Color32::WHITE)
+            .text_style(TextRole::Body.into()),
+        )
+        .on_hover_cursor(CursorIcon::Help);
+    ui.label(body_name(universe, Some(observer)));
+    ui.end_row();
+
+    ui.label("Target")
+        .on_help_text(
+            RichText::new("The body to watch drift relative to the focused body.")
+                .color(Color32::WHITE)
+                .text_style(TextRole::Body.into()),
+        )
+        .on_hover_cursor(CursorIcon::Help);
+    ComboBox::from_id_salt(RENDEZVOUS_COMBO_BOX_SALT)
+        .close_behavior(PopupCloseBehavior::CloseOnClickOutside)
+        .wrap_mode(TextWrapMode::Extend)
+        .selected_text(body_name(universe, *target))
+        .show_ui(ui, |ui| {
+            selectable_body_tree(ui, *RENDEZVOUS_TREE_ID, universe, target, None);
+        });
+    ui.end_row();
+
+    let Some(state) = target
+        .filter(|&target| target != observer)
+        .and_then(|target| relative_state(universe, observer, target))
+    else {
+        return;
+    };
+    let distance = state.position.length();
+    // Positive while the gap is shrinking
+    let closing_speed = if distance > 0.0 {
+        -state.position.dot(state.velocity) / distance
+    } else {
+        0.0
+    };
+
+    ui.label("Distance");
+    ui.label(format!("{} m", PrettyPrintFloat(distance)));
+    ui.end_row();
+
+    ui.label("Relative speed")
+        .on_help_text(
+            RichText::new("How fast the target moves, as seen from the focused body.")
+                .color(Color32::WHITE)
+                .text_style(TextRole::Body.into()),
+        )
+        .on_hover_cursor(CursorIcon::Help);
+    ui.label(format!("{} m/s", PrettyPrintFloat(state.velocity.length())));
+    ui.end_row();
+
+    ui.label("Closing speed")
+        .on_help_text(
+            RichText::new(
+                "How fast the distance is shrinking.\n\
+                Negative while the bodies are drifting apart.",
+            )
+            .color(Color32::WHITE)
+            .text_style(TextRole::Body.into()),
+        )
+        .on_hover_cursor(CursorIcon::Help);
+    ui.label(format!("{} m/s", PrettyPrintFloat(closing_speed)));
+    ui.end_row();
+}
+
+fn body_name(universe: &Universe, id: Option<UniverseId>) -> &str {
+    id.and_then(|id| universe.get_body(id))
+        .map(|w| &*w.body.name)
+        .unwrap_or("—")
+}
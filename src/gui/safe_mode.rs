@@ -0,0 +1,67 @@
+use three_d::egui::{Button, Color32, Context as EguiContext, RichText, Ui, Window};
+
+use crate::{
+    cfg,
+    gui::{SimState, help::HelpText, text_style::TextRole},
+    notify::{Severity, notify},
+    sim,
+};
+
+pub(super) fn draw(ctx: &EguiContext, sim_state: &mut SimState) {
+    let mut open = sim_state.ui.is_safe_mode_window_open;
+
+    Window::new("Safe Mode")
+        .open(&mut open)
+        .resizable(false)
+        .collapsible(false)
+        .show(ctx, |ui| window_contents(ui, sim_state));
+
+    sim_state.ui.is_safe_mode_window_open &= open;
+}
+
+fn window_contents(ui: &mut Ui, sim_state: &mut SimState) {
+    ui.visuals_mut().override_text_color = Some(Color32::WHITE);
+    ui.set_max_width(360.0);
+
+    ui.label(
+        "The simulator failed to start several times in a row, \
+        so it was started in safe mode.",
+    );
+    ui.label(
+        "An empty universe was loaded and bodies are drawn \
+        at the lowest level of detail.",
+    );
+    ui.add_space(8.0);
+
+    ui.horizontal_wrapped(|ui| {
+        let load_button = ui.button("Load default universe").on_help_text(
+            RichText::new("Load the preset universe anyway.\nThis might crash again.")
+                .color(Color32::WHITE)
+                .text_style(TextRole::Body.into()),
+        );
+
+        if load_button.clicked() {
+            sim_state.universe = sim::create_universe();
+            let default_focus = sim_state.universe.metadata.default_focus.unwrap_or(0);
+            let position_map = sim_state.universe.get_all_body_positions();
+            sim_state.switch_focus(default_focus, &position_map);
+            sim_state.ui.is_safe_mode_window_open = false;
+        }
+
+        let reset_button =
+            Button::new(RichText::new("Reset data & restart").color(Color32::LIGHT_RED));
+        let reset_button = ui.add(reset_button).on_help_text(
+            RichText::new("Delete all saved settings and restart the simulator.")
+                .color(Color32::WHITE)
+                .text_style(TextRole::Body.into()),
+        );
+
+        if reset_button.clicked() {
+            let _res = cfg::reset();
+            #[cfg(not(target_family = "wasm"))]
+            if let Err(e) = _res {
+                notify(Severity::Error, format!("Failed to reset data: {e}"));
+            }
+        }
+    });
+}
@@ -0,0 +1,151 @@
+use std::collections::HashMap;
+
+use glam::DVec3;
+use three_d::egui::{Align2, Area, Color32, Context, Frame, Margin, Pos2, Sense, Stroke, Ui, Vec2};
+
+use crate::{
+    cfg::CONFIG,
+    gfx::{grid::grid_spacing, projection::WorldProjector},
+    gui::{SimState, altitude, declare_id, text_style::TextRole},
+    sim::universe::Id as UniverseId,
+};
+
+declare_id!(SCALE_BAR_AREA, b"ScaleBar");
+
+/// The longest the bar gets, in points.
+/// It is shortened from this to the nearest round distance.
+const MAX_BAR_WIDTH: f32 = 160.0;
+
+const BAR_HEIGHT: f32 = 28.0;
+
+/// Units the bar is labelled in, from smallest to largest.
+const UNITS: [(f64, &str); 4] = [
+    (1.0, "m"),
+    (1e3, "km"),
+    (149597870700.0, "AU"),
+    (9460730472580800.0, "ly"),
+];
+
+/// Shows how long a distance on the screen is at the focus,
+/// how high the focused body is, and how far apart the reference grid's lines are.
+pub(super) fn draw(
+    ctx: &Context,
+    sim_state: &SimState,
+    position_map: &HashMap<UniverseId, DVec3>,
+    projector: &WorldProjector,
+) {
+    let (show_scale_bar, show_grid) = CONFIG
+        .try_lock()
+        .map(|cfg| (cfg.show_scale_bar.get(), cfg.show_grid.get()))
+        .unwrap_or((true, false));
+
+    if !show_scale_bar && !show_grid {
+        return;
+    }
+
+    Area::new(*SCALE_BAR_AREA_ID)
+        .anchor(Align2::RIGHT_BOTTOM, [-12.0, -80.0])
+        .interactable(false)
+        .show(ctx, |ui| {
+            Frame::new()
+                .fill(Color32::from_black_alpha(192))
+                .corner_radius(8.0)
+                .inner_margin(Margin::same(8))
+                .show(ui, |ui| {
+                    ui.visuals_mut().override_text_color = Some(Color32::WHITE);
+
+                    if show_scale_bar {
+                        scale_bar(ui, projector.meters_per_point());
+                        let focused = sim_state.focused_body();
+                        if let Some(readout) =
+                            altitude::readout(&sim_state.universe, position_map, focused)
+                        {
+                            ui.label(readout);
+                        }
+                    }
+                    if show_grid {
+                        let (major, _) = grid_spacing(projector.camera_distance());
+                        let exponent = major.log10().round() as i32;
+                        ui.label(format!(
+                            "Grid: 1e{exponent} m, fine lines 1e{} m",
+                            exponent - 1
+                        ));
+                    }
+                });
+        });
+}
+
+fn scale_bar(ui: &mut Ui, meters_per_point: f64) {
+    let (length, label) = round_length(meters_per_point * MAX_BAR_WIDTH as f64);
+    let width = (length / meters_per_point) as f32;
+
+    let (rect, _) = ui.allocate_exact_size(Vec2::new(MAX_BAR_WIDTH, BAR_HEIGHT), Sense::hover());
+    let painter = ui.painter();
+
+    let baseline = rect.bottom() - 4.0;
+    let left = rect.right() - width;
+    let stroke = Stroke::new(2.0, Color32::WHITE);
+
+    painter.line_segment(
+        [Pos2::new(left, baseline), Pos2::new(rect.right(), baseline)],
+        stroke,
+    );
+    for x in [left, rect.right()] {
+        painter.line_segment(
+            [Pos2::new(x, baseline - 6.0), Pos2::new(x, baseline)],
+            stroke,
+        );
+    }
+
+    painter.text(
+        Pos2::new(rect.right(), baseline - 8.0),
+        Align2::RIGHT_BOTTOM,
+        label,
+        TextRole::Caption.resolve(ui.style()),
+        Color32::WHITE,
+    );
+}
+
+/// Rounds a length down to 1, 2 or 5 times a power of ten
+/// in the largest unit that fits, like on a map.
+///
+/// Returns: The rounded length in meters, and its label.
+fn round_length(max_length: f64) -> (f64, String) {
+    let (unit, unit_name) = UNITS
+        .iter()
+        .rev()
+        .find(|(unit, _)| *unit <= max_length)
+        .copied()
+        .unwrap_or(UNITS[0]);
+
+    let max_value = max_length / unit;
+    let power = 10f64.powf(max_value.log10().floor());
+    let mantissa = [5.0, 2.0, 1.0]
+        .into_iter()
+        .find(|m| m * power <= max_value)
+        .unwrap_or(1.0);
+    let value = mantissa * power;
+
+    (
+        value * unit,
+        format!("{} {unit_name}", format_number(value)),
+    )
+}
+
+/// Formats a number with thousands separators,
+/// or with just enough decimals if it's below 1.
+fn format_number(value: f64) -> String {
+    if value < 1.0 {
+        return format!("{}", (value * 1e6).round() / 1e6);
+    }
+
+    let digits = format!("{:.0}", value);
+    let mut out = String::with_capacity(digits.len() + digits.len() / 3);
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i) % 3 == 0 {
+            out.push(',');
+        }
+        out.push(c);
+    }
+    out
+}
@@ -0,0 +1,220 @@
+use three_d::egui::{
+    Align, Color32, Id as EguiId, InnerResponse, Key, Layout, RichText, TextEdit, Ui,
+};
+
+use crate::{
+    cfg::CONFIG,
+    gui::{
+        declare_id,
+        help::HelpText,
+        text_style::TextRole,
+        unit_dv::{drag_value_with_unit, unit_combo_box},
+    },
+    units::{
+        AutoUnit, UnitEnum,
+        scientific::{
+            format_scientific, parse_scientific, parse_scientific_with_unit, shift_exponent,
+        },
+    },
+};
+
+declare_id!(salt_only, SCIENTIFIC_INPUT_PREFIX, b"6.02e23!");
+
+/// How wide the scientific input is when it isn't filling a grid cell.
+const SCIENTIFIC_INPUT_WIDTH: f32 = 160.0;
+
+/// Whether the "Scientific number entry" setting is on.
+pub(super) fn scientific_entry_enabled() -> bool {
+    CONFIG
+        .try_lock()
+        .map(|cfg| cfg.scientific_number_entry.get())
+        .unwrap_or(false)
+}
+
+/// A value with a unit, typed in as text if the
+/// "Scientific number entry" setting is on, or dragged otherwise.
+pub(super) fn precise_value_with_unit<U>(
+    id_salt: impl std::hash::Hash,
+    ui: &mut Ui,
+    base_val: &mut f64,
+    unit: &mut AutoUnit<U>,
+) where
+    U: UnitEnum,
+{
+    if scientific_entry_enabled() {
+        scientific_value_with_unit(id_salt, ui, base_val, unit);
+    } else {
+        drag_value_with_unit(id_salt, ui, base_val, unit);
+    }
+}
+
+/// A positive value with a unit, typed in as text like "5.972e24".
+///
+/// A unit name can be typed after the number to switch to that unit.
+/// The value only changes once the text is confirmed with Enter or by
+/// clicking away, and text that can't be read is thrown away then.
+pub(super) fn scientific_value_with_unit<U>(
+    id_salt: impl std::hash::Hash,
+    ui: &mut Ui,
+    base_val: &mut f64,
+    unit: &mut AutoUnit<U>,
+) where
+    U: UnitEnum,
+{
+    ui.scope(|ui| {
+        ui.set_width(ui.available_width());
+        ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
+            let id = ui.make_persistent_id((SCIENTIFIC_INPUT_PREFIX_SALT, &id_salt));
+            unit_combo_box(id, ui, unit);
+
+            let unit_scale = unit.get_value();
+            let scaled_val = *base_val / unit_scale;
+
+            if let Some(shifted) = exponent_steppers(ui, scaled_val) {
+                *base_val = shifted * unit_scale;
+                ui.data_mut(|data| data.remove::<String>(id));
+            }
+
+            let width = ui.available_width();
+            let entered = scientific_text_edit(ui, id, width, scaled_val, |text| {
+                let (value, typed_unit) =
+                    parse_scientific_with_unit::<U>(text).map_err(|e| e.to_string())?;
+                let scale = typed_unit.map_or(unit_scale, |u| u.get_value());
+                Ok((positive(value)?, typed_unit, scale))
+            });
+
+            if let Some((value, typed_unit, scale)) = entered {
+                *base_val = value * scale;
+                if let Some(typed_unit) = typed_unit {
+                    unit.unit = typed_unit;
+                    unit.auto = false;
+                }
+            }
+
+            let editing = ui.data(|data| data.get_temp::<String>(id).is_some());
+            if !editing {
+                unit.update(*base_val);
+            }
+        });
+    });
+}
+
+/// A value typed in as text like "6.674e-11", kept at or above `min`.
+///
+/// Returns whether the value was changed.
+pub(super) fn scientific_value(
+    id_salt: impl std::hash::Hash,
+    ui: &mut Ui,
+    value: &mut f64,
+    min: f64,
+) -> InnerResponse<bool> {
+    ui.horizontal(|ui| {
+        let id = ui.make_persistent_id((SCIENTIFIC_INPUT_PREFIX_SALT, id_salt));
+
+        let entered = scientific_text_edit(ui, id, SCIENTIFIC_INPUT_WIDTH, *value, |text| {
+            parse_scientific(text)
+                .map(|value| value.max(min))
+                .map_err(|e| e.to_string())
+        });
+        if let Some(entered) = entered {
+            *value = entered;
+            return true;
+        }
+
+        match exponent_steppers(ui, *value) {
+            Some(shifted) => {
+                *value = shifted.max(min);
+                ui.data_mut(|data| data.remove::<String>(id));
+                true
+            }
+            None => false,
+        }
+    })
+}
+
+fn positive(value: f64) -> Result<f64, String> {
+    if value <= 0.0 {
+        return Err("must be more than zero".to_owned());
+    }
+    Ok(value)
+}
+
+/// The text box behind the scientific inputs.
+///
+/// What's being typed is kept under `id` until it's confirmed,
+/// and the parsed value is returned then. Text that can't be parsed
+/// is shown in red with the reason on hover.
+/// Pressing Escape throws the text away instead.
+fn scientific_text_edit<T>(
+    ui: &mut Ui,
+    id: EguiId,
+    width: f32,
+    value: f64,
+    parse: impl Fn(&str) -> Result<T, String>,
+) -> Option<T> {
+    let mut text = ui
+        .data(|data| data.get_temp::<String>(id))
+        .unwrap_or_else(|| format_scientific(value));
+
+    let parsed = parse(&text);
+    let error = parsed.err();
+
+    let mut text_edit = TextEdit::singleline(&mut text)
+        .id(id.with("text"))
+        .desired_width(width);
+    if error.is_some() {
+        text_edit = text_edit.text_color(Color32::LIGHT_RED);
+    }
+    let response = ui.add(text_edit);
+
+    let response = match &error {
+        Some(error) => response.on_help_text(
+            RichText::new(format!("Can't use this: {error}"))
+                .color(Color32::WHITE)
+                .text_style(TextRole::Body.into()),
+        ),
+        None => response,
+    };
+
+    if response.lost_focus() {
+        ui.data_mut(|data| data.remove::<String>(id));
+        if ui.input(|i| i.key_pressed(Key::Escape)) {
+            return None;
+        }
+        return parse(&text).ok();
+    }
+
+    if response.has_focus() {
+        ui.data_mut(|data| data.insert_temp(id, text));
+    }
+
+    None
+}
+
+/// Buttons that move the value's decimal exponent up or down by one.
+///
+/// Returns the new value if one was clicked.
+fn exponent_steppers(ui: &mut Ui, value: f64) -> Option<f64> {
+    // Added right to left in unit fields, so the order is flipped there
+    let right_to_left = ui.layout().prefer_right_to_left();
+    let steps = if right_to_left { [1, -1] } else { [-1, 1] };
+
+    let mut shifted = None;
+    for step in steps {
+        let (label, description) = if step > 0 {
+            ("×10", "Multiply by ten.")
+        } else {
+            ("÷10", "Divide by ten.")
+        };
+        let button = ui.small_button(label).on_help_text(
+            RichText::new(description)
+                .color(Color32::WHITE)
+                .text_style(TextRole::Body.into()),
+        );
+        if button.clicked() {
+            shifted = Some(shift_exponent(value, step));
+        }
+    }
+
+    shifted
+}
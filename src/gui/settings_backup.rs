@@ -0,0 +1,86 @@
+use three_d::egui::{
+    Button, Color32, Context as EguiContext, RichText, TextEdit, TextStyle, Ui, Window,
+};
+
+use crate::{
+    cfg::backup::{self, ImportError},
+    gui::{help::HelpText, text_style::TextRole},
+};
+
+#[derive(Default)]
+pub(super) struct SettingsBackupState {
+    pub(super) open: bool,
+    /// The settings pasted in to import.
+    import_text: String,
+    /// Why the last import failed.
+    import_error: Option<ImportError>,
+    /// Whether the settings were copied since the window was opened.
+    copied: bool,
+}
+
+pub(super) fn draw(ctx: &EguiContext, state: &mut SettingsBackupState) {
+    let mut open = state.open;
+
+    Window::new("Settings Backup")
+        .open(&mut open)
+        .default_width(360.0)
+        .show(ctx, |ui| window_contents(ui, state));
+
+    if !open {
+        state.copied = false;
+    }
+    state.open &= open;
+}
+
+fn window_contents(ui: &mut Ui, state: &mut SettingsBackupState) {
+    ui.visuals_mut().override_text_color = Some(Color32::WHITE);
+
+    ui.horizontal(|ui| {
+        let copy_button = ui.button("Copy settings").on_help_text(
+            RichText::new(
+                "Copy all of your settings to the clipboard as JSON, \
+                to keep as a backup or to import on another device.",
+            )
+            .color(Color32::WHITE)
+            .text_style(TextRole::Body.into()),
+        );
+        if copy_button.clicked() {
+            ui.ctx().copy_text(backup::export_json());
+            state.copied = true;
+        }
+        if state.copied {
+            ui.label("Copied.");
+        }
+    });
+
+    ui.separator();
+    ui.label("Paste exported settings here to import them:");
+    ui.add(
+        TextEdit::multiline(&mut state.import_text)
+            .font(TextStyle::Monospace)
+            .desired_rows(6)
+            .desired_width(f32::INFINITY),
+    );
+
+    let import_button = ui
+        .add_enabled(
+            !state.import_text.trim().is_empty(),
+            Button::new(RichText::new("Import & restart").color(Color32::LIGHT_RED)),
+        )
+        .on_help_text(
+            RichText::new(
+                "Replace your settings with the pasted ones and restart.\n\
+                Settings from older versions are upgraded, \
+                and ones that aren't in the pasted text are kept.",
+            )
+            .color(Color32::WHITE)
+            .text_style(TextRole::Body.into()),
+        );
+    if import_button.clicked() {
+        state.import_error = backup::import_json(&state.import_text).err();
+    }
+
+    if let Some(error) = &state.import_error {
+        ui.label(RichText::new(format!("⚠ Can't import: {error}")).color(Color32::YELLOW));
+    }
+}
@@ -0,0 +1,76 @@
+use three_d::egui::{Color32, Pos2, Rect, Response, Sense, Shape, Stroke, Ui, Vec2};
+
+const SPARKLINE_SIZE: Vec2 = Vec2::new(160.0, 32.0);
+
+const BASELINE_COLOR: Color32 = Color32::from_gray(80);
+const LINE_COLOR: Color32 = Color32::from_rgb(96, 192, 255);
+const MARKER_COLOR: Color32 = Color32::from_rgb(255, 200, 64);
+
+/// A small line plot of `values`, spread evenly across its width,
+/// with the lowest value at the bottom and the highest at the top.
+///
+/// `marker` draws a dot on the line at that fraction of the way along,
+/// from 0 at the left to 1 at the right. Non-finite values are skipped.
+pub(super) fn sparkline(ui: &mut Ui, values: &[f64], marker: Option<f64>) -> Response {
+    let (rect, response) = ui.allocate_exact_size(SPARKLINE_SIZE, Sense::hover());
+    let rect = rect.shrink(3.0);
+    let painter = ui.painter_at(rect.expand(3.0));
+
+    painter.line_segment(
+        [rect.left_bottom(), rect.right_bottom()],
+        Stroke::new(1.0, BASELINE_COLOR),
+    );
+
+    let (min, max) = values
+        .iter()
+        .copied()
+        .filter(|value| value.is_finite())
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), value| {
+            (min.min(value), max.max(value))
+        });
+    if values.len() < 2 || min > max {
+        return response;
+    }
+
+    let point_at = |fraction: f64, value: f64| point_in(rect, fraction, value, min, max);
+    let last = (values.len() - 1) as f64;
+
+    // Split at non-finite values, so the line doesn't jump across them
+    let mut run = Vec::new();
+    for (index, &value) in values.iter().enumerate() {
+        if value.is_finite() {
+            run.push(point_at(index as f64 / last, value));
+        } else if !run.is_empty() {
+            painter.add(Shape::line(
+                std::mem::take(&mut run),
+                Stroke::new(1.5, LINE_COLOR),
+            ));
+        }
+    }
+    if !run.is_empty() {
+        painter.add(Shape::line(run, Stroke::new(1.5, LINE_COLOR)));
+    }
+
+    if let Some(marker) = marker {
+        let index = (marker.clamp(0.0, 1.0) * last).round() as usize;
+        if let Some(&value) = values.get(index).filter(|value| value.is_finite()) {
+            painter.circle_filled(point_at(marker, value), 3.0, MARKER_COLOR);
+        }
+    }
+
+    response
+}
+
+/// Where `value` goes in `rect`, `fraction` of the way across.
+/// A flat line is drawn through the middle.
+fn point_in(rect: Rect, fraction: f64, value: f64, min: f64, max: f64) -> Pos2 {
+    let height = if max > min {
+        ((value - min) / (max - min)) as f32
+    } else {
+        0.5
+    };
+    Pos2::new(
+        rect.left() + fraction as f32 * rect.width(),
+        rect.bottom() - height * rect.height(),
+    )
+}
@@ -0,0 +1,50 @@
+//! The screen shown while assets are preloaded, before the simulator starts.
+
+use three_d::{
+    Event as ThreeDEvent, GUI, Viewport,
+    egui::{Align, CentralPanel, Color32, Frame, Layout, ProgressBar, RichText},
+};
+
+use crate::gui::text_style::TextRole;
+
+/// Draws the splash screen with a progress bar.
+///
+/// `progress`: How much has been loaded, from 0 to 1.
+///
+/// `step`: What's being loaded right now.
+pub(crate) fn update(
+    gui: &mut GUI,
+    events: &mut Vec<ThreeDEvent>,
+    accumulated_time_ms: f64,
+    viewport: Viewport,
+    pixels_per_point: f32,
+    progress: f32,
+    step: &str,
+) {
+    gui.update(
+        events,
+        accumulated_time_ms,
+        viewport,
+        pixels_per_point,
+        |ctx| {
+            CentralPanel::default()
+                .frame(Frame::NONE.fill(Color32::BLACK))
+                .show(ctx, |ui| {
+                    ui.with_layout(Layout::top_down(Align::Center), |ui| {
+                        ui.add_space(ui.available_height() * 0.4);
+                        ui.label(
+                            RichText::new("Keplerian Orbital Simulator Demo")
+                                .color(Color32::WHITE)
+                                .text_style(TextRole::Splash.into()),
+                        );
+                        ui.add_space(12.0);
+                        ui.add(
+                            ProgressBar::new(progress)
+                                .desired_width(240.0)
+                                .text(RichText::new(step).color(Color32::WHITE)),
+                        );
+                    });
+                });
+        },
+    );
+}
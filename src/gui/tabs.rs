@@ -0,0 +1,78 @@
+use three_d::egui::{
+    Button, Color32, Context, Frame, Margin, RichText, ScrollArea, TopBottomPanel, Ui,
+};
+
+use crate::gui::{SimState, declare_id, help::HelpText, text_style::TextRole};
+
+declare_id!(TAB_PANEL, b"TabStrip");
+
+/// A change to the open tabs, requested from the GUI.
+///
+/// Tabs hold whole sessions, which the GUI can't reach,
+/// so these are handled by the program after the frame is drawn.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum TabAction {
+    Switch(usize),
+    New,
+    Close(usize),
+}
+
+/// Draws the tab strip at the top of the screen.
+///
+/// The strip is only shown when more than one universe is open;
+/// new tabs are opened from the settings popup until then.
+pub(super) fn draw(ctx: &Context, sim_state: &mut SimState, titles: &[String], active: usize) {
+    if titles.len() <= 1 {
+        return;
+    }
+
+    TopBottomPanel::top(*TAB_PANEL_ID)
+        .show_separator_line(false)
+        .frame(Frame {
+            inner_margin: Margin::symmetric(8, 4),
+            fill: Color32::from_black_alpha(192),
+            ..Default::default()
+        })
+        .show(ctx, |ui| {
+            ui.style_mut().always_scroll_the_only_direction = true;
+            ScrollArea::horizontal()
+                .auto_shrink([false, true])
+                .show(ui, |ui| {
+                    ui.horizontal(|ui| tab_strip(ui, sim_state, titles, active));
+                });
+        });
+}
+
+fn tab_strip(ui: &mut Ui, sim_state: &mut SimState, titles: &[String], active: usize) {
+    for (index, title) in titles.iter().enumerate() {
+        let tab = Button::selectable(
+            index == active,
+            RichText::new(title).text_style(TextRole::Body.into()),
+        );
+        if ui.add(tab).clicked() && index != active {
+            sim_state.ui.tab_action = Some(TabAction::Switch(index));
+        }
+
+        let close = ui.small_button("×").on_help_text(
+            RichText::new(format!("Close {title}"))
+                .color(Color32::WHITE)
+                .text_style(TextRole::Body.into()),
+        );
+        if close.clicked() {
+            sim_state.ui.tab_action = Some(TabAction::Close(index));
+        }
+
+        ui.separator();
+    }
+
+    let new_tab = ui
+        .button(RichText::new("+").text_style(TextRole::Body.into()))
+        .on_help_text(
+            RichText::new("Open the default universe in a new tab.")
+                .color(Color32::WHITE)
+                .text_style(TextRole::Body.into()),
+        );
+    if new_tab.clicked() {
+        sim_state.ui.tab_action = Some(TabAction::New);
+    }
+}
@@ -0,0 +1,139 @@
+//! The text styles used across the GUI, kept in one style sheet and referred
+//! to by name, so changing the font size setting resizes all of them together.
+
+use std::sync::Arc;
+
+use strum::{EnumIter, IntoEnumIterator};
+use three_d::egui::{Context as EguiContext, FontId, Style, TextStyle};
+
+use crate::cfg::CONFIG;
+
+pub(crate) const DEFAULT_FONT_SIZE: f32 = 16.0;
+pub(crate) const MIN_FONT_SIZE: f32 = 10.0;
+pub(crate) const MAX_FONT_SIZE: f32 = 28.0;
+
+/// What a piece of text is for, which decides how big it's drawn.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, EnumIter)]
+pub(crate) enum TextRole {
+    /// Labels, buttons and hover texts.
+    Body,
+    /// Numbers that shouldn't jump around as their digits change.
+    Monospace,
+    /// Small text drawn over the scene, like scale markings and readouts.
+    Caption,
+    /// Small numbers drawn over the scene.
+    CaptionMonospace,
+    /// Captions over a list or a section.
+    Subheading,
+    /// Symbols standing in for a button's label.
+    Symbol,
+    /// The splash screen's heading.
+    Splash,
+    /// A window's own title inside it.
+    Title,
+}
+
+impl TextRole {
+    const fn name(self) -> &'static str {
+        match self {
+            TextRole::Body => "Body",
+            TextRole::Monospace => "Monospace",
+            TextRole::Caption => "Caption",
+            TextRole::CaptionMonospace => "Caption monospace",
+            TextRole::Subheading => "Subheading",
+            TextRole::Symbol => "Symbol",
+            TextRole::Splash => "Splash",
+            TextRole::Title => "Title",
+        }
+    }
+
+    /// How big this role is, relative to the base font size.
+    const fn scale(self) -> f32 {
+        match self {
+            TextRole::Caption | TextRole::CaptionMonospace => 0.75,
+            TextRole::Body | TextRole::Monospace => 1.0,
+            TextRole::Subheading => 1.125,
+            TextRole::Symbol => 1.25,
+            TextRole::Splash => 1.5,
+            TextRole::Title => 1.625,
+        }
+    }
+
+    fn font_id(self, base_size: f32) -> FontId {
+        let size = base_size * self.scale();
+        match self {
+            TextRole::Monospace | TextRole::CaptionMonospace => FontId::monospace(size),
+            _ => FontId::proportional(size),
+        }
+    }
+
+    /// The font this role has in `style`, for text drawn with a painter.
+    pub(crate) fn resolve(self, style: &Style) -> FontId {
+        TextStyle::from(self).resolve(style)
+    }
+}
+
+impl From<TextRole> for TextStyle {
+    fn from(role: TextRole) -> Self {
+        TextStyle::Name(Arc::from(role.name()))
+    }
+}
+
+/// The font size setting, in points.
+pub(crate) fn font_size() -> f32 {
+    let size = CONFIG
+        .try_lock()
+        .map(|cfg| cfg.font_size.get())
+        .unwrap_or(DEFAULT_FONT_SIZE);
+
+    let valid = size > 0.0;
+    if !valid {
+        return DEFAULT_FONT_SIZE;
+    }
+
+    size.clamp(MIN_FONT_SIZE, MAX_FONT_SIZE)
+}
+
+/// The text styles every role gets for `base_size`.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct StyleSheet {
+    base_size: f32,
+}
+
+impl StyleSheet {
+    /// The style sheet for the current font size setting.
+    pub(crate) fn current() -> Self {
+        Self {
+            base_size: font_size(),
+        }
+    }
+
+    /// Puts every role's font into the context's style.
+    pub(crate) fn install(&self, ctx: &EguiContext) {
+        ctx.style_mut(|style| {
+            for role in TextRole::iter() {
+                style
+                    .text_styles
+                    .insert(role.into(), role.font_id(self.base_size));
+            }
+        });
+    }
+
+    fn is_installed(&self, ctx: &EguiContext) -> bool {
+        ctx.style()
+            .text_styles
+            .get(&TextRole::Body.into())
+            .is_some_and(|font| font.size == self.base_size)
+    }
+}
+
+/// Swaps in the style sheet for the font size setting if it changed.
+///
+/// Called before anything is drawn, so a frame is laid out with the old
+/// sizes or the new ones throughout, never a mix of both.
+pub(super) fn begin_frame(ctx: &EguiContext) {
+    let sheet = StyleSheet::current();
+    if !sheet.is_installed(ctx) {
+        sheet.install(ctx);
+    }
+}
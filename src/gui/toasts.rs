@@ -0,0 +1,147 @@
+use three_d::egui::{
+    Align2, Area, Color32, Context, Frame, Grid, Label, Margin, Order, RichText, Sense, Stroke, Ui,
+    Window,
+};
+
+use crate::{
+    gui::{UiState, declare_id, help::HelpText, text_style::TextRole},
+    notify::{NOTIFIER, Notification, Severity},
+};
+
+declare_id!(TOAST_AREA, b"Toast!!!");
+declare_id!(salt_only, EVENT_LOG_GRID, b"EventLog");
+
+/// How many toasts are shown at once.
+/// Older ones wait their turn, and are in the event log regardless.
+const MAX_TOASTS: usize = 4;
+
+const TOAST_WIDTH: f32 = 320.0;
+
+const fn severity_color(severity: Severity) -> Color32 {
+    match severity {
+        Severity::Info => Color32::LIGHT_BLUE,
+        Severity::Warning => Color32::YELLOW,
+        Severity::Error => Color32::LIGHT_RED,
+    }
+}
+
+/// Shows the newest notifications in the top right corner.
+pub(super) fn draw(ctx: &Context, ui_state: &mut UiState) {
+    let Ok(mut notifier) = NOTIFIER.lock() else {
+        return;
+    };
+
+    let now = ctx.input(|i| i.time);
+
+    let mut toasts: Vec<_> = notifier.toasts_mut().collect();
+    let skip = toasts.len().saturating_sub(MAX_TOASTS);
+    let toasts = &mut toasts[skip..];
+
+    if toasts.is_empty() {
+        return;
+    }
+
+    let mut open_log = false;
+
+    Area::new(*TOAST_AREA_ID)
+        .order(Order::Foreground)
+        .anchor(
+            Align2::RIGHT_TOP,
+            [-12.0, ctx.available_rect().top() + 12.0],
+        )
+        .show(ctx, |ui| {
+            ui.set_max_width(TOAST_WIDTH);
+            for toast in toasts.iter_mut().rev() {
+                let shown_at = *toast.shown_at.get_or_insert(now);
+                let remaining = toast.severity.toast_duration() - (now - shown_at);
+
+                if remaining <= 0.0 {
+                    toast.dismissed = true;
+                    continue;
+                }
+
+                ctx.request_repaint_after_secs(remaining as f32);
+
+                toast_frame(ui, toast, &mut open_log);
+                ui.add_space(4.0);
+            }
+        });
+
+    if open_log {
+        ui_state.is_event_log_open = true;
+    }
+}
+
+fn toast_frame(ui: &mut Ui, toast: &mut Notification, open_log: &mut bool) {
+    let color = severity_color(toast.severity);
+
+    Frame::new()
+        .fill(Color32::from_black_alpha(224))
+        .stroke(Stroke::new(1.0, color))
+        .corner_radius(8.0)
+        .inner_margin(Margin::same(8))
+        .show(ui, |ui| {
+            ui.horizontal(|ui| {
+                ui.label(
+                    RichText::new(toast.severity.icon())
+                        .color(color)
+                        .text_style(TextRole::Body.into()),
+                );
+
+                let message = Label::new(RichText::new(&toast.message).color(Color32::WHITE))
+                    .sense(Sense::click());
+                let message = ui.add(message).on_help_text(
+                    RichText::new("Click to open the event log.")
+                        .color(Color32::WHITE)
+                        .text_style(TextRole::Body.into()),
+                );
+                if message.clicked() {
+                    *open_log = true;
+                }
+
+                if ui.small_button("×").clicked() {
+                    toast.dismissed = true;
+                }
+            });
+        });
+}
+
+/// Lists every remembered notification, newest first.
+pub(super) fn draw_event_log(ctx: &Context, ui_state: &mut UiState) {
+    Window::new("Event Log")
+        .open(&mut ui_state.is_event_log_open)
+        .default_width(400.0)
+        .vscroll(true)
+        .show(ctx, event_log_contents);
+}
+
+fn event_log_contents(ui: &mut Ui) {
+    ui.visuals_mut().override_text_color = Some(Color32::WHITE);
+
+    let Ok(mut notifier) = NOTIFIER.lock() else {
+        return;
+    };
+
+    if notifier.is_history_empty() {
+        ui.label("Nothing has gone wrong yet.");
+        return;
+    }
+
+    if ui.button("Clear").clicked() {
+        notifier.clear();
+        return;
+    }
+
+    Grid::new(EVENT_LOG_GRID_SALT)
+        .num_columns(2)
+        .spacing([8.0, 4.0])
+        .striped(true)
+        .show(ui, |ui| {
+            for notification in notifier.history().iter().rev() {
+                let color = severity_color(notification.severity);
+                ui.label(RichText::new(notification.severity.icon()).color(color));
+                ui.label(&notification.message);
+                ui.end_row();
+            }
+        });
+}
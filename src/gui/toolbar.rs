@@ -0,0 +1,395 @@
+//! The customizable buttons in the middle of the bottom bar.
+//!
+//! Which buttons are shown, and in what order, is saved as a list of
+//! item indices. The "Toolbar" window edits that list.
+
+use strum::IntoEnumIterator;
+use strum_macros::EnumIter;
+use three_d::egui::{
+    Button, Color32, Context as EguiContext, CursorIcon, Image, ImageButton, Popup,
+    PopupCloseBehavior, Response, RichText, Stroke, Ui, Vec2, Window,
+};
+
+use crate::{
+    assets,
+    cfg::CONFIG,
+    gui::{
+        MIN_TOUCH_TARGET_VEC, SimState, TabAction, UiState, declare_id,
+        help::HelpText,
+        new_body_preview,
+        text_style::TextRole,
+        windows::{self, WindowEntry},
+    },
+    sim::library::PresetUniverse,
+};
+
+declare_id!(TOOLBAR_EDITOR_ROW, b"TbarRow!");
+
+/// How many buttons the toolbar can hold.
+pub(crate) const TOOLBAR_SLOTS: usize = 16;
+
+/// The body list and Edit Body toggles, then the Add body button.
+pub(crate) const DEFAULT_TOOLBAR_ITEMS: [u8; TOOLBAR_SLOTS] =
+    [1, 2, 3, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+
+const TOOLBAR_BUTTON_SIZE: Vec2 = MIN_TOUCH_TARGET_VEC;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, EnumIter)]
+pub(super) enum ToolbarItem {
+    BodyList,
+    EditBody,
+    AddBody,
+    FrameOrbit,
+    LoadPreset,
+    NewTab,
+    CompareBodies,
+    UniverseSettings,
+    Constraints,
+    EventLog,
+}
+
+impl ToolbarItem {
+    pub(super) const fn name(self) -> &'static str {
+        match self {
+            ToolbarItem::BodyList => "Body list",
+            ToolbarItem::EditBody => "Edit body",
+            ToolbarItem::AddBody => "Add body",
+            ToolbarItem::FrameOrbit => "Frame orbit",
+            ToolbarItem::LoadPreset => "Load preset",
+            ToolbarItem::NewTab => "New tab",
+            ToolbarItem::CompareBodies => "Compare bodies",
+            ToolbarItem::UniverseSettings => "Universe settings",
+            ToolbarItem::Constraints => "Constraints",
+            ToolbarItem::EventLog => "Event log",
+        }
+    }
+
+    /// The text on the button, for items without an icon.
+    const fn label(self) -> &'static str {
+        match self {
+            ToolbarItem::FrameOrbit => "Frame",
+            ToolbarItem::LoadPreset => "Presets",
+            ToolbarItem::CompareBodies => "Compare",
+            ToolbarItem::UniverseSettings => "Universe",
+            ToolbarItem::EventLog => "Events",
+            item => item.name(),
+        }
+    }
+
+    const fn description(self) -> &'static str {
+        match self {
+            ToolbarItem::AddBody => {
+                "Add a new celestial body\n\
+                Toggling this off will delete your uncommitted body!"
+            }
+            ToolbarItem::FrameOrbit => "Zoom the camera to fit the focused body's whole orbit. (F)",
+            ToolbarItem::LoadPreset => "Replace the universe with one of the presets.",
+            ToolbarItem::NewTab => {
+                "Open the default universe in a new tab. \
+                Bodies can be copied between tabs from the body list."
+            }
+            ToolbarItem::BodyList
+            | ToolbarItem::EditBody
+            | ToolbarItem::CompareBodies
+            | ToolbarItem::UniverseSettings
+            | ToolbarItem::Constraints
+            | ToolbarItem::EventLog => "Toggle the window.",
+        }
+    }
+
+    /// The name of the window the item toggles, as listed in [`windows::WINDOWS`].
+    const fn window_name(self) -> Option<&'static str> {
+        match self {
+            ToolbarItem::BodyList => Some("Celestial body list"),
+            ToolbarItem::EditBody => Some("Edit celestial body"),
+            ToolbarItem::CompareBodies => Some("Compare bodies"),
+            ToolbarItem::UniverseSettings => Some("Universe settings"),
+            ToolbarItem::Constraints => Some("Constraints"),
+            ToolbarItem::EventLog => Some("Event log"),
+            ToolbarItem::AddBody
+            | ToolbarItem::FrameOrbit
+            | ToolbarItem::LoadPreset
+            | ToolbarItem::NewTab => None,
+        }
+    }
+
+    fn window(self) -> Option<&'static WindowEntry> {
+        self.window_name().and_then(windows::find)
+    }
+
+    fn icon(self) -> Option<Image<'static>> {
+        match self {
+            ToolbarItem::AddBody => Some(assets::ADD_ORBIT_IMAGE.clone()),
+            _ => self
+                .window()
+                .and_then(|window| window.icon)
+                .map(|icon| icon()),
+        }
+    }
+
+    /// The index the item is saved as. 0 is an empty slot.
+    const fn to_index(self) -> u8 {
+        match self {
+            ToolbarItem::BodyList => 1,
+            ToolbarItem::EditBody => 2,
+            ToolbarItem::AddBody => 3,
+            ToolbarItem::FrameOrbit => 4,
+            ToolbarItem::LoadPreset => 5,
+            ToolbarItem::NewTab => 6,
+            ToolbarItem::CompareBodies => 7,
+            ToolbarItem::UniverseSettings => 8,
+            ToolbarItem::Constraints => 9,
+            ToolbarItem::EventLog => 10,
+        }
+    }
+
+    fn from_index(index: u8) -> Option<Self> {
+        Self::iter().find(|item| item.to_index() == index)
+    }
+}
+
+/// The items on the toolbar, in order.
+pub(super) fn items() -> Vec<ToolbarItem> {
+    let slots = CONFIG
+        .try_lock()
+        .map(|cfg| cfg.toolbar_items.get())
+        .unwrap_or(DEFAULT_TOOLBAR_ITEMS);
+
+    let mut items: Vec<ToolbarItem> = Vec::with_capacity(TOOLBAR_SLOTS);
+    for item in slots.into_iter().filter_map(ToolbarItem::from_index) {
+        if !items.contains(&item) {
+            items.push(item);
+        }
+    }
+    items
+}
+
+fn save_items(items: &[ToolbarItem]) {
+    let mut slots = [0; TOOLBAR_SLOTS];
+    for (slot, item) in slots.iter_mut().zip(items) {
+        *slot = item.to_index();
+    }
+    let _ = CONFIG.try_lock().map(|cfg| cfg.toolbar_items.set(slots));
+}
+
+/// Whether the window is toggled from the toolbar,
+/// instead of from the options menu.
+pub(super) fn has_window(items: &[ToolbarItem], window: &WindowEntry) -> bool {
+    items
+        .iter()
+        .any(|item| item.window_name() == Some(window.name))
+}
+
+/// Draws the toolbar's buttons in a row.
+///
+/// Returns: The response of the whole row, to measure it by.
+pub(super) fn draw(ui: &mut Ui, sim_state: &mut SimState) -> Response {
+    ui.horizontal(|ui| {
+        ui.spacing_mut().button_padding = Vec2::ZERO;
+        let widget_styles = &mut ui.visuals_mut().widgets;
+        widget_styles.inactive.weak_bg_fill = Color32::TRANSPARENT;
+        widget_styles.inactive.bg_stroke = Stroke::NONE;
+        widget_styles.hovered.weak_bg_fill = Color32::from_white_alpha(16);
+        widget_styles.hovered.bg_stroke = Stroke::NONE;
+        widget_styles.active.weak_bg_fill = Color32::from_white_alpha(64);
+
+        for item in items() {
+            item_button(ui, sim_state, item);
+        }
+    })
+    .response
+}
+
+fn item_button(ui: &mut Ui, sim_state: &mut SimState, item: ToolbarItem) {
+    let selected = match item.window() {
+        Some(window) => window.is_open(sim_state),
+        None => item == ToolbarItem::AddBody && sim_state.preview_body.is_some(),
+    };
+
+    let button = match item.icon() {
+        Some(icon) => ui.add_sized(
+            TOOLBAR_BUTTON_SIZE,
+            ImageButton::new(icon).selected(selected),
+        ),
+        None => {
+            let text = RichText::new(item.label()).text_style(TextRole::Body.into());
+            let padding = ui.spacing().item_spacing.x;
+            ui.spacing_mut().button_padding = Vec2::new(padding, 0.0);
+            let button = Button::selectable(selected, text).min_size(TOOLBAR_BUTTON_SIZE);
+            let response = ui.add(button);
+            ui.spacing_mut().button_padding = Vec2::ZERO;
+            response
+        }
+    };
+
+    let description = item
+        .window()
+        .and_then(|window| window.description)
+        .unwrap_or(item.description());
+    let button = button
+        .on_help_text(
+            RichText::new(description)
+                .color(Color32::WHITE)
+                .text_style(TextRole::Body.into()),
+        )
+        .on_hover_cursor(CursorIcon::PointingHand);
+
+    if item == ToolbarItem::LoadPreset {
+        preset_popup(&button, sim_state);
+        return;
+    }
+
+    if !button.clicked() {
+        return;
+    }
+
+    if let Some(window) = item.window() {
+        window.toggle(sim_state);
+        return;
+    }
+
+    match item {
+        ToolbarItem::AddBody => {
+            if sim_state.preview_body.is_some() {
+                sim_state.preview_body = None;
+            } else {
+                let root_id = sim_state.universe.get_bodies().keys().min().copied();
+                sim_state.preview_body = Some(new_body_preview(&sim_state.universe, root_id));
+            }
+        }
+        ToolbarItem::FrameOrbit => sim_state.ui.frame_orbit_requested = true,
+        ToolbarItem::NewTab => sim_state.ui.tab_action = Some(TabAction::New),
+        _ => (),
+    }
+}
+
+fn preset_popup(button: &Response, sim_state: &mut SimState) {
+    let popup = Popup::menu(button).close_behavior(PopupCloseBehavior::CloseOnClick);
+    popup.show(|ui| {
+        ui.visuals_mut().override_text_color = Some(Color32::WHITE);
+        for preset in PresetUniverse::iter() {
+            let button = ui
+                .button(RichText::new(preset.name()).text_style(TextRole::Body.into()))
+                .on_help_text(
+                    RichText::new(preset.description())
+                        .color(Color32::WHITE)
+                        .text_style(TextRole::Body.into()),
+                );
+            if button.clicked() {
+                sim_state.ui.universe_to_load = Some(preset.create());
+            }
+        }
+    });
+}
+
+pub(super) fn draw_editor(ctx: &EguiContext, ui_state: &mut UiState) {
+    Window::new("Toolbar")
+        .open(&mut ui_state.is_toolbar_editor_open)
+        .default_width(260.0)
+        .show(ctx, editor_contents);
+}
+
+fn editor_contents(ui: &mut Ui) {
+    ui.visuals_mut().override_text_color = Some(Color32::WHITE);
+
+    let mut items = items();
+    let original = items.clone();
+
+    ui.label("Drag the buttons to reorder them.");
+    ui.add_space(4.0);
+
+    let mut removed = None;
+    let mut moved = None;
+    for (index, &item) in items.iter().enumerate() {
+        ui.horizontal(|ui| {
+            let id = TOOLBAR_EDITOR_ROW_ID.with(index);
+            let row = ui
+                .dnd_drag_source(id, index, |ui| {
+                    ui.label(
+                        RichText::new(format!("☰  {}", item.name()))
+                            .text_style(TextRole::Body.into()),
+                    )
+                })
+                .response;
+
+            if let Some(insert_at) = drop_position(ui, &row, index) {
+                moved = Some(insert_at);
+            }
+
+            let remove = ui.small_button("✖").on_help_text(
+                RichText::new("Take the button off the toolbar.")
+                    .color(Color32::WHITE)
+                    .text_style(TextRole::Body.into()),
+            );
+            if remove.clicked() {
+                removed = Some(index);
+            }
+        });
+    }
+
+    if let Some((from, insert_at)) = moved {
+        let item = items.remove(from);
+        let insert_at = if from < insert_at {
+            insert_at - 1
+        } else {
+            insert_at
+        };
+        items.insert(insert_at, item);
+    }
+    if let Some(index) = removed {
+        items.remove(index);
+    }
+
+    ui.separator();
+    ui.label("Add a button:");
+    ui.horizontal_wrapped(|ui| {
+        for item in ToolbarItem::iter().filter(|item| !original.contains(item)) {
+            let add = ui
+                .button(item.name())
+                .on_help_text(
+                    RichText::new(item.description())
+                        .color(Color32::WHITE)
+                        .text_style(TextRole::Body.into()),
+                )
+                .on_hover_cursor(CursorIcon::PointingHand);
+            if add.clicked() {
+                items.push(item);
+            }
+        }
+    });
+
+    ui.separator();
+    if ui.button("Reset to default").clicked() {
+        items = DEFAULT_TOOLBAR_ITEMS
+            .into_iter()
+            .filter_map(ToolbarItem::from_index)
+            .collect();
+    }
+
+    if items != original {
+        save_items(&items);
+    }
+}
+
+/// Where a row dragged onto the row at `index` goes,
+/// and shows the spot while it's hovered.
+///
+/// Returns: The index of the dragged row, and the index to insert it at,
+/// once it's dropped.
+fn drop_position(ui: &Ui, row: &Response, index: usize) -> Option<(usize, usize)> {
+    let pointer = ui.input(|i| i.pointer.interact_pos())?;
+    row.dnd_hover_payload::<usize>()?;
+
+    let above = pointer.y < row.rect.center().y;
+    let line_y = if above {
+        row.rect.top()
+    } else {
+        row.rect.bottom()
+    };
+    let stroke = Stroke::new(2.0, Color32::WHITE);
+    ui.painter().hline(row.rect.x_range(), line_y, stroke);
+
+    let from = *row.dnd_release_payload::<usize>()?;
+    let insert_at = if above { index } else { index + 1 };
+    Some((from, insert_at))
+}
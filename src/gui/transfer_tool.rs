@@ -0,0 +1,429 @@
+use float_pretty_print::PrettyPrintFloat;
+use glam::DVec3;
+use keplerian_sim::{OrbitTrait, StateVectors};
+use three_d::egui::{
+    Button, Color32, ComboBox, Context as EguiContext, CursorIcon, DragValue, Grid,
+    PopupCloseBehavior, RichText, TextWrapMode, Ui, Window,
+};
+
+use crate::{
+    gui::{
+        SimState, celestials::selectable_body_tree, declare_id, help::HelpText, new_body_preview,
+        text_style::TextRole, unit_dv::drag_value_with_unit,
+    },
+    sim::{
+        conversion::state_vectors_to_elements,
+        lambert::{self, Direction, Transfer},
+        universe::{Id as UniverseId, Universe},
+    },
+    units::{AutoUnit, time::TimeUnit},
+};
+
+declare_id!(salt_only, TRANSFER_TOOL_GRID, b"XferGrid");
+declare_id!(salt_only, TRANSFER_RESULTS_GRID, b"XferRslt");
+declare_id!(salt_only, TRANSFER_PARENT_COMBO_BOX, b"XferPrnt");
+declare_id!(salt_only, TRANSFER_DEPARTURE_COMBO_BOX, b"XferDept");
+declare_id!(salt_only, TRANSFER_ARRIVAL_COMBO_BOX, b"XferArrv");
+declare_id!(salt_only, TRANSFER_TIME_OF_FLIGHT, b"XferTime");
+declare_id!(TRANSFER_PARENT_TREE, b"XferTree");
+
+/// The time of flight the tool starts with, in seconds.
+const DEFAULT_TIME_OF_FLIGHT: f64 = 86400.0;
+
+/// One end of the transfer.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Endpoint {
+    /// Wherever a body orbiting the parent is at the time.
+    Body(UniverseId),
+    /// A fixed position relative to the parent, in meters.
+    Position(DVec3),
+}
+
+impl Default for Endpoint {
+    fn default() -> Self {
+        Self::Position(DVec3::ZERO)
+    }
+}
+
+pub(super) struct TransferToolState {
+    pub(super) open: bool,
+    parent: Option<UniverseId>,
+    departure: Endpoint,
+    arrival: Endpoint,
+    /// In seconds.
+    time_of_flight: f64,
+    time_of_flight_unit: AutoUnit<TimeUnit>,
+    direction: Direction,
+}
+
+impl Default for TransferToolState {
+    fn default() -> Self {
+        Self {
+            open: false,
+            parent: None,
+            departure: Endpoint::default(),
+            arrival: Endpoint::default(),
+            time_of_flight: DEFAULT_TIME_OF_FLIGHT,
+            time_of_flight_unit: AutoUnit {
+                auto: true,
+                unit: TimeUnit::Days,
+            },
+            direction: Direction::default(),
+        }
+    }
+}
+
+pub(super) fn draw(ctx: &EguiContext, sim_state: &mut SimState) {
+    let mut open = sim_state.ui.transfer_tool_state.open;
+
+    Window::new("Transfer Orbit")
+        .open(&mut open)
+        .default_width(360.0)
+        .show(ctx, |ui| window_contents(ui, sim_state));
+
+    sim_state.ui.transfer_tool_state.open &= open;
+}
+
+fn window_contents(ui: &mut Ui, sim_state: &mut SimState) {
+    ui.visuals_mut().override_text_color = Some(Color32::WHITE);
+
+    let universe = &sim_state.universe;
+    let state = &mut sim_state.ui.transfer_tool_state;
+
+    // Forget bodies that were deleted or moved to another parent
+    if state
+        .parent
+        .is_some_and(|id| universe.get_body(id).is_none())
+    {
+        state.parent = None;
+    }
+    for endpoint in [&mut state.departure, &mut state.arrival] {
+        if let Endpoint::Body(id) = *endpoint
+            && universe.get_body(id).and_then(|w| w.relations.parent) != state.parent
+        {
+            *endpoint = Endpoint::default();
+        }
+    }
+
+    Grid::new(TRANSFER_TOOL_GRID_SALT)
+        .num_columns(2)
+        .spacing([40.0, 4.0])
+        .striped(true)
+        .show(ui, |ui| transfer_grid(ui, universe, state));
+
+    let Some(parent) = state.parent else {
+        ui.label("Pick the body to transfer around.");
+        return;
+    };
+    let Some(mu) = universe.get_body_gravitational_parameter(parent) else {
+        return;
+    };
+
+    let departure_time = universe.time;
+    let arrival_time = departure_time + state.time_of_flight;
+    let departure = endpoint_state(universe, state.departure, departure_time);
+    let arrival = endpoint_state(universe, state.arrival, arrival_time);
+
+    let transfer = lambert::solve(
+        departure.position,
+        arrival.position,
+        state.time_of_flight,
+        mu,
+        state.direction,
+    );
+    let transfer = match transfer {
+        Ok(transfer) => transfer,
+        Err(e) => {
+            ui.label(RichText::new(format!("⚠ No transfer: {e}")).color(Color32::YELLOW));
+            return;
+        }
+    };
+
+    ui.separator();
+    results_grid(ui, transfer, departure, arrival, state);
+
+    let preview_button = ui.button("Preview as new body").on_help_text(
+        RichText::new(
+            "Open the New Body window with a body on this transfer orbit, \
+            leaving from the departure point now.",
+        )
+        .color(Color32::WHITE)
+        .text_style(TextRole::Body.into()),
+    );
+
+    if preview_button.clicked() {
+        let elements = state_vectors_to_elements(
+            departure.position,
+            transfer.departure_velocity,
+            mu,
+            departure_time,
+        );
+        let mut preview = new_body_preview(universe, Some(parent));
+        preview.body.orbit = Some(elements.to_orbit(mu));
+        sim_state.preview_body = Some(preview);
+    }
+}
+
+fn transfer_grid(ui: &mut Ui, universe: &Universe, state: &mut TransferToolState) {
+    ui.label("Parent")
+        .on_help_text(
+            RichText::new("The body the transfer orbit goes around.")
+                .color(Color32::WHITE)
+                .text_style(TextRole::Body.into()),
+        )
+        .on_hover_cursor(CursorIcon::Help);
+    ComboBox::from_id_salt(TRANSFER_PARENT_COMBO_BOX_SALT)
+        .close_behavior(PopupCloseBehavior::CloseOnClickOutside)
+        .wrap_mode(TextWrapMode::Extend)
+        .selected_text(body_name(universe, state.parent))
+        .show_ui(ui, |ui| {
+            selectable_body_tree(
+                ui,
+                *TRANSFER_PARENT_TREE_ID,
+                universe,
+                &mut state.parent,
+                None,
+            );
+        });
+    ui.end_row();
+
+    let Some(parent) = state.parent else {
+        return;
+    };
+
+    ui.label("From")
+        .on_help_text(
+            RichText::new(
+                "Where the transfer leaves from: a body orbiting the parent, \
+                where it is now, or a position relative to the parent.",
+            )
+            .color(Color32::WHITE)
+            .text_style(TextRole::Body.into()),
+        )
+        .on_hover_cursor(CursorIcon::Help);
+    endpoint_picker(
+        ui,
+        TRANSFER_DEPARTURE_COMBO_BOX_SALT,
+        universe,
+        parent,
+        &mut state.departure,
+    );
+    ui.end_row();
+    position_row(ui, &mut state.departure);
+
+    ui.label("To")
+        .on_help_text(
+            RichText::new(
+                "Where the transfer arrives: a body orbiting the parent, \
+                where it will be on arrival, or a position relative to the parent.",
+            )
+            .color(Color32::WHITE)
+            .text_style(TextRole::Body.into()),
+        )
+        .on_hover_cursor(CursorIcon::Help);
+    endpoint_picker(
+        ui,
+        TRANSFER_ARRIVAL_COMBO_BOX_SALT,
+        universe,
+        parent,
+        &mut state.arrival,
+    );
+    ui.end_row();
+    position_row(ui, &mut state.arrival);
+
+    ui.label("Time of flight")
+        .on_help_text(
+            RichText::new("How long the transfer takes.")
+                .color(Color32::WHITE)
+                .text_style(TextRole::Body.into()),
+        )
+        .on_hover_cursor(CursorIcon::Help);
+    drag_value_with_unit(
+        TRANSFER_TIME_OF_FLIGHT_SALT,
+        ui,
+        &mut state.time_of_flight,
+        &mut state.time_of_flight_unit,
+    );
+    ui.end_row();
+
+    ui.label("Direction")
+        .on_help_text(
+            RichText::new(
+                "Which way around the parent the transfer goes, \
+                as seen from above the reference plane.\n\
+                Prograde is counterclockwise, like nearly everything in the solar system.",
+            )
+            .color(Color32::WHITE)
+            .text_style(TextRole::Body.into()),
+        )
+        .on_hover_cursor(CursorIcon::Help);
+    ui.horizontal(|ui| {
+        ui.selectable_value(&mut state.direction, Direction::Prograde, "Prograde");
+        ui.selectable_value(&mut state.direction, Direction::Retrograde, "Retrograde");
+    });
+    ui.end_row();
+}
+
+fn endpoint_picker(
+    ui: &mut Ui,
+    id_salt: impl std::hash::Hash,
+    universe: &Universe,
+    parent: UniverseId,
+    endpoint: &mut Endpoint,
+) {
+    let selected_text = match *endpoint {
+        Endpoint::Body(id) => body_name(universe, Some(id)),
+        Endpoint::Position(_) => "Position",
+    };
+
+    ComboBox::from_id_salt(id_salt)
+        .wrap_mode(TextWrapMode::Extend)
+        .selected_text(selected_text)
+        .show_ui(ui, |ui| {
+            let is_position = matches!(endpoint, Endpoint::Position(_));
+            if ui
+                .add(Button::selectable(is_position, "Position"))
+                .clicked()
+                && !is_position
+            {
+                *endpoint = Endpoint::default();
+            }
+
+            let satellites = universe
+                .get_body(parent)
+                .map(|w| w.relations.satellites.as_slice())
+                .unwrap_or_default();
+            for &id in satellites {
+                let button = Button::selectable(
+                    *endpoint == Endpoint::Body(id),
+                    body_name(universe, Some(id)),
+                );
+                if ui.add(button).clicked() {
+                    *endpoint = Endpoint::Body(id);
+                }
+            }
+        });
+}
+
+/// The coordinates of a position endpoint, on their own row.
+fn position_row(ui: &mut Ui, endpoint: &mut Endpoint) {
+    let Endpoint::Position(position) = endpoint else {
+        return;
+    };
+
+    ui.label("");
+    ui.horizontal(|ui| {
+        for coordinate in [&mut position.x, &mut position.y, &mut position.z] {
+            let speed = (coordinate.abs() * 4e-3).max(1.0);
+            ui.add(
+                DragValue::new(coordinate)
+                    .custom_formatter(|num, _| format!("{}", PrettyPrintFloat(num)))
+                    .speed(speed)
+                    .suffix(" m"),
+            );
+        }
+    });
+    ui.end_row();
+}
+
+fn results_grid(
+    ui: &mut Ui,
+    transfer: Transfer,
+    departure: StateVectors,
+    arrival: StateVectors,
+    state: &TransferToolState,
+) {
+    Grid::new(TRANSFER_RESULTS_GRID_SALT)
+        .num_columns(2)
+        .spacing([40.0, 4.0])
+        .striped(true)
+        .show(ui, |ui| {
+            ui.label("Departure speed")
+                .on_help_text(
+                    RichText::new("How fast the transfer leaves, relative to the parent.")
+                        .color(Color32::WHITE)
+                        .text_style(TextRole::Body.into()),
+                )
+                .on_hover_cursor(CursorIcon::Help);
+            ui.label(speed_text(transfer.departure_velocity.length()));
+            ui.end_row();
+
+            ui.label("Arrival speed")
+                .on_help_text(
+                    RichText::new("How fast the transfer arrives, relative to the parent.")
+                        .color(Color32::WHITE)
+                        .text_style(TextRole::Body.into()),
+                )
+                .on_hover_cursor(CursorIcon::Help);
+            ui.label(speed_text(transfer.arrival_velocity.length()));
+            ui.end_row();
+
+            if matches!(state.departure, Endpoint::Body(_)) {
+                ui.label("Departure Δv")
+                    .on_help_text(
+                        RichText::new(
+                            "The velocity change needed to go from the departure body's \
+                            orbit onto the transfer, ignoring the body's own gravity.",
+                        )
+                        .color(Color32::WHITE)
+                        .text_style(TextRole::Body.into()),
+                    )
+                    .on_hover_cursor(CursorIcon::Help);
+                ui.label(speed_text(
+                    transfer.departure_velocity.distance(departure.velocity),
+                ));
+                ui.end_row();
+            }
+
+            if matches!(state.arrival, Endpoint::Body(_)) {
+                ui.label("Arrival Δv")
+                    .on_help_text(
+                        RichText::new(
+                            "The velocity change needed to match the arrival body's \
+                            orbit at the end of the transfer, ignoring the body's own gravity.",
+                        )
+                        .color(Color32::WHITE)
+                        .text_style(TextRole::Body.into()),
+                    )
+                    .on_hover_cursor(CursorIcon::Help);
+                ui.label(speed_text(
+                    transfer.arrival_velocity.distance(arrival.velocity),
+                ));
+                ui.end_row();
+            }
+        });
+}
+
+/// Where an endpoint is, and how fast it's moving, relative to the parent.
+///
+/// Positions are standing still, and so are frozen bodies,
+/// which stay where they were frozen.
+fn endpoint_state(universe: &Universe, endpoint: Endpoint, time: f64) -> StateVectors {
+    let standing_still = |position| StateVectors {
+        position,
+        velocity: DVec3::ZERO,
+    };
+
+    let wrapper = match endpoint {
+        Endpoint::Position(position) => return standing_still(position),
+        Endpoint::Body(id) => universe.get_body(id),
+    };
+
+    match wrapper.map(|w| (&w.body.orbit, w.frozen_at)) {
+        Some((Some(orbit), None)) => orbit.get_state_vectors_at_time(time),
+        Some((Some(orbit), Some(frozen_at))) => {
+            standing_still(orbit.get_position_at_time(frozen_at))
+        }
+        _ => standing_still(DVec3::ZERO),
+    }
+}
+
+fn speed_text(speed: f64) -> String {
+    format!("{} m/s", PrettyPrintFloat(speed))
+}
+
+fn body_name(universe: &Universe, id: Option<UniverseId>) -> &str {
+    id.and_then(|id| universe.get_body(id))
+        .map(|w| &*w.body.name)
+        .unwrap_or("—")
+}
@@ -0,0 +1,46 @@
+//! Scaling the whole GUI, on top of the screen's own pixel density,
+//! for screens where the default sizes are too big or too small.
+
+use crate::cfg::CONFIG;
+
+pub(crate) const MIN_UI_SCALE: f32 = 0.75;
+pub(crate) const MAX_UI_SCALE: f32 = 2.0;
+
+/// How much one press of a zoom shortcut changes the scale.
+const SHORTCUT_STEP: f32 = 0.1;
+
+/// The UI scale setting, as a multiplier of the screen's pixel density.
+pub(crate) fn ui_scale() -> f32 {
+    let scale = CONFIG
+        .try_lock()
+        .map(|cfg| cfg.ui_scale.get())
+        .unwrap_or(1.0);
+
+    let valid = scale > 0.0;
+    if !valid {
+        return 1.0;
+    }
+
+    scale.clamp(MIN_UI_SCALE, MAX_UI_SCALE)
+}
+
+pub(crate) fn set_ui_scale(scale: f32) {
+    if let Ok(cfg) = CONFIG.try_lock() {
+        let _ = cfg.ui_scale.set(scale.clamp(MIN_UI_SCALE, MAX_UI_SCALE));
+    }
+}
+
+/// Applies a zoom shortcut, given the character typed with Ctrl held:
+/// `+` or `=` zooms in, `-` zooms out and `0` resets.
+/// Other characters are ignored.
+pub(crate) fn handle_shortcut(char: char) {
+    let scale = match char {
+        '+' | '=' => ui_scale() + SHORTCUT_STEP,
+        '-' => ui_scale() - SHORTCUT_STEP,
+        '0' => 1.0,
+        _ => return,
+    };
+
+    // Snapped to the step, so a scale set with the slider lines back up
+    set_ui_scale((scale / SHORTCUT_STEP).round() * SHORTCUT_STEP);
+}
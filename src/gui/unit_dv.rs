@@ -40,7 +40,26 @@ fn drag_value_with_unit_inner<'a, U>(
         .custom_formatter(|num, _| format!("{:3.8}", PrettyPrintFloat(num)))
         .range(f64::MIN_POSITIVE..=f64::MAX)
         .speed(speed);
-    let cb = ComboBox::from_id_salt((DRAG_VALUE_WITH_UNIT_PREFIX_SALT, id_salt))
+    unit_combo_box((DRAG_VALUE_WITH_UNIT_PREFIX_SALT, id_salt), ui, unit);
+
+    let dv = ui.add_sized([ui.available_width(), 18.0], dv);
+
+    if dv.changed() {
+        *base_val = scaled_val * unit_scale;
+    }
+
+    if !dv.dragged() && !dv.has_focus() {
+        unit.update(*base_val);
+    }
+}
+
+/// The unit picker shown next to a value, with an option
+/// to pick the unit automatically from the value.
+pub(super) fn unit_combo_box<U>(id_salt: impl std::hash::Hash, ui: &mut Ui, unit: &mut AutoUnit<U>)
+where
+    U: UnitEnum,
+{
+    let cb = ComboBox::from_id_salt(id_salt)
         .close_behavior(PopupCloseBehavior::CloseOnClickOutside)
         .selected_text(unit.unit.to_string());
 
@@ -59,14 +78,4 @@ fn drag_value_with_unit_inner<'a, U>(
             unit.auto ^= true;
         }
     });
-
-    let dv = ui.add_sized([ui.available_width(), 18.0], dv);
-
-    if dv.changed() {
-        *base_val = scaled_val * unit_scale;
-    }
-
-    if !dv.dragged() && !dv.has_focus() {
-        unit.update(*base_val);
-    }
 }
@@ -0,0 +1,340 @@
+use std::collections::HashMap;
+
+use glam::DVec3;
+use three_d::egui::{
+    Color32, ComboBox, Context as EguiContext, CursorIcon, DragValue, Grid, PopupCloseBehavior,
+    RichText, TextEdit, TextWrapMode, Ui, Window,
+};
+
+use crate::{
+    gfx::export,
+    gui::{
+        SimState,
+        celestials::{selectable_body_tree, summary},
+        declare_id,
+        help::HelpText,
+        text_style::TextRole,
+    },
+    notify::{Severity, notify},
+    sim::{
+        epoch,
+        universe::{Id as UniverseId, Universe},
+    },
+};
+
+declare_id!(salt_only, UNIVERSE_SETTINGS_GRID, b"Cosm0s!!");
+declare_id!(salt_only, PERTURBATION_GRID, b"Wobbl3s!");
+declare_id!(salt_only, DEFAULT_FOCUS_COMBO_BOX, b"1stLook!");
+declare_id!(DEFAULT_FOCUS_TREE, b"1stTree!");
+
+pub(super) struct SetDateState {
+    year: i32,
+    month: u32,
+    day: u32,
+    /// How many bodies were moved the last time the date was set.
+    last_moved: Option<usize>,
+}
+
+impl Default for SetDateState {
+    fn default() -> Self {
+        // The date the presets were fetched at
+        Self {
+            year: 2025,
+            month: 9,
+            day: 27,
+            last_moved: None,
+        }
+    }
+}
+
+pub(super) fn draw(
+    ctx: &EguiContext,
+    sim_state: &mut SimState,
+    position_map: &HashMap<UniverseId, DVec3>,
+) {
+    let mut open = sim_state.ui.is_universe_settings_open;
+
+    Window::new("Universe Settings")
+        .open(&mut open)
+        .default_width(320.0)
+        .vscroll(true)
+        .show(ctx, |ui| window_contents(ui, sim_state, position_map));
+
+    sim_state.ui.is_universe_settings_open &= open;
+}
+
+fn window_contents(
+    ui: &mut Ui,
+    sim_state: &mut SimState,
+    position_map: &HashMap<UniverseId, DVec3>,
+) {
+    ui.visuals_mut().override_text_color = Some(Color32::WHITE);
+
+    let metadata = sim_state.universe.metadata.clone();
+    let perturbation_settings = sim_state.universe.perturbation_settings;
+
+    Grid::new(UNIVERSE_SETTINGS_GRID_SALT)
+        .num_columns(2)
+        .spacing([40.0, 4.0])
+        .striped(true)
+        .show(ui, |ui| metadata_grid(ui, &mut sim_state.universe));
+
+    ui.add_space(8.0);
+
+    ui.horizontal(|ui| {
+        let focus_button = ui.button("Focus default body").on_help_text(
+            RichText::new("Move the camera to the body chosen as the default focus.")
+                .color(Color32::WHITE)
+                .text_style(TextRole::Body.into()),
+        );
+
+        if focus_button.clicked()
+            && let Some(id) = sim_state.universe.metadata.default_focus
+        {
+            sim_state.switch_focus(id, position_map);
+        }
+
+        let summary_button = ui.button("Export summary").on_help_text(
+            RichText::new(
+                "Copy a Markdown summary of the universe: its settings, \
+                the body hierarchy, and every body's elements and derived info.\n\
+                Handy for sharing a setup on a forum.",
+            )
+            .color(Color32::WHITE)
+            .text_style(TextRole::Body.into()),
+        );
+
+        if summary_button.clicked() {
+            ui.ctx()
+                .copy_text(summary::universe_summary(&sim_state.universe));
+        }
+
+        let gltf_button = ui.button("Export glTF").on_help_text(
+            RichText::new(
+                "Save the scene as it is right now as a glTF file, \
+                to open in Blender or other 3D tools.\n\
+                Each body is a sphere of its radius and color, placed \
+                relative to its parent, with its orbit as a line. \
+                One unit in the file is 1000 km.\n\
+                The file goes to your downloads folder.",
+            )
+            .color(Color32::WHITE)
+            .text_style(TextRole::Body.into()),
+        );
+
+        if gltf_button.clicked() {
+            match export::save(&sim_state.universe) {
+                Ok(location) => notify(Severity::Info, format!("Exported the scene to {location}")),
+                Err(e) => notify(Severity::Error, format!("Couldn't export the scene: {e}")),
+            }
+        }
+    });
+
+    ui.separator();
+    set_date(ui, sim_state);
+
+    ui.separator();
+    Grid::new(PERTURBATION_GRID_SALT)
+        .num_columns(2)
+        .spacing([40.0, 4.0])
+        .striped(true)
+        .show(ui, |ui| perturbation_grid(ui, &mut sim_state.universe));
+
+    let universe = &mut sim_state.universe;
+    if universe.metadata != metadata || universe.perturbation_settings != perturbation_settings {
+        universe.mark_edited();
+    }
+}
+
+fn perturbation_grid(ui: &mut Ui, universe: &mut Universe) {
+    ui.label("Precession scale")
+        .on_help_text(
+            RichText::new(
+                "A multiplier on how fast the periapsis of bodies with \
+                \"GR precession\" enabled rotates.\n\
+                At 1×, the rate is the real one. \
+                Try around 10⁶× with Mercury to watch its orbit turn.",
+            )
+            .color(Color32::WHITE)
+            .text_style(TextRole::Body.into()),
+        )
+        .on_hover_cursor(CursorIcon::Help);
+    let scale = &mut universe.perturbation_settings.precession_scale;
+    let speed = (*scale * 0.01).max(0.01);
+    ui.add(
+        DragValue::new(scale)
+            .range(0.0..=1e12)
+            .speed(speed)
+            .suffix("×"),
+    );
+    ui.end_row();
+
+    ui.label("Jitter seed")
+        .on_help_text(
+            RichText::new(
+                "Where the random walks of bodies with \"Jitter\" set start from.\n\
+                Changing it restarts them, so the same seed gives the same \
+                wandering orbits again.",
+            )
+            .color(Color32::WHITE)
+            .text_style(TextRole::Body.into()),
+        )
+        .on_hover_cursor(CursorIcon::Help);
+    let mut seed = universe.perturbation_settings.jitter_seed();
+    ui.horizontal(|ui| {
+        let seed_input = ui.add(DragValue::new(&mut seed).speed(1.0));
+        let restart = ui.button("Restart").on_help_text(
+            RichText::new("Start the random walks over from the seed.")
+                .color(Color32::WHITE)
+                .text_style(TextRole::Body.into()),
+        );
+        if seed_input.changed() || restart.clicked() {
+            universe.perturbation_settings.set_jitter_seed(seed);
+        }
+    });
+    ui.end_row();
+}
+
+fn set_date(ui: &mut Ui, sim_state: &mut SimState) {
+    let state = &mut sim_state.ui.set_date_state;
+
+    ui.label("Set to date")
+        .on_help_text(
+            RichText::new(
+                "Move the preset bodies to where they were (or will be) \
+                on a calendar date, and reset the time to 0.\n\
+                Bodies are matched by name, so renamed or custom bodies are not moved. \
+                Locked bodies are not moved either.\n\
+                Bodies that aren't moved stay where they are as the time is reset.",
+            )
+            .color(Color32::WHITE)
+            .text_style(TextRole::Body.into()),
+        )
+        .on_hover_cursor(CursorIcon::Help);
+
+    ui.horizontal(|ui| {
+        ui.add(DragValue::new(&mut state.year).range(-4712..=9999));
+        ui.label("-");
+        ui.add(DragValue::new(&mut state.month).range(1..=12));
+        ui.label("-");
+        let days = epoch::days_in_month(state.year, state.month);
+        state.day = state.day.min(days);
+        ui.add(DragValue::new(&mut state.day).range(1..=days));
+
+        if ui.button("Apply").clicked() {
+            if !epoch::is_valid_date(state.year, state.month, state.day) {
+                notify(
+                    Severity::Warning,
+                    format!(
+                        "{:04}-{:02}-{:02} isn't a calendar date",
+                        state.year, state.month, state.day
+                    ),
+                );
+                return;
+            }
+            let julian_date = epoch::julian_date(state.year, state.month, state.day, 0.0);
+            let universe = &mut sim_state.universe;
+            state.last_moved = Some(epoch::set_to_date(universe, julian_date));
+            universe.metadata.epoch_label =
+                format!("{:04}-{:02}-{:02}", state.year, state.month, state.day);
+        }
+    });
+
+    if let Some(moved) = state.last_moved {
+        ui.label(format!("Moved {moved} bodies."));
+    }
+}
+
+fn metadata_grid(ui: &mut Ui, universe: &mut Universe) {
+    ui.label("Name")
+        .on_help_text(
+            RichText::new("The name of this universe.")
+                .color(Color32::WHITE)
+                .text_style(TextRole::Body.into()),
+        )
+        .on_hover_cursor(CursorIcon::Help);
+    ui.add(
+        TextEdit::singleline(&mut universe.metadata.name)
+            .char_limit(255)
+            .hint_text("Untitled universe")
+            .desired_width(f32::INFINITY),
+    );
+    ui.end_row();
+
+    ui.label("Author")
+        .on_help_text(
+            RichText::new("Who made this universe.")
+                .color(Color32::WHITE)
+                .text_style(TextRole::Body.into()),
+        )
+        .on_hover_cursor(CursorIcon::Help);
+    ui.add(
+        TextEdit::singleline(&mut universe.metadata.author)
+            .char_limit(255)
+            .hint_text("Anonymous")
+            .desired_width(f32::INFINITY),
+    );
+    ui.end_row();
+
+    ui.label("Epoch")
+        .on_help_text(
+            RichText::new(
+                "A label for what time = 0 represents.\n\
+                For example, a calendar date such as \"J2000\".",
+            )
+            .color(Color32::WHITE)
+            .text_style(TextRole::Body.into()),
+        )
+        .on_hover_cursor(CursorIcon::Help);
+    ui.add(
+        TextEdit::singleline(&mut universe.metadata.epoch_label)
+            .char_limit(255)
+            .hint_text("Unspecified")
+            .desired_width(f32::INFINITY),
+    );
+    ui.end_row();
+
+    ui.label("Description")
+        .on_help_text(
+            RichText::new("A longer description of what this universe contains.")
+                .color(Color32::WHITE)
+                .text_style(TextRole::Body.into()),
+        )
+        .on_hover_cursor(CursorIcon::Help);
+    ui.add(
+        TextEdit::multiline(&mut universe.metadata.description)
+            .hint_text("No description")
+            .desired_rows(3)
+            .desired_width(f32::INFINITY),
+    );
+    ui.end_row();
+
+    ui.label("Default focus")
+        .on_help_text(
+            RichText::new("The body the camera focuses on when this universe is loaded.")
+                .color(Color32::WHITE)
+                .text_style(TextRole::Body.into()),
+        )
+        .on_hover_cursor(CursorIcon::Help);
+    let mut default_focus = universe.metadata.default_focus;
+    ComboBox::from_id_salt(DEFAULT_FOCUS_COMBO_BOX_SALT)
+        .close_behavior(PopupCloseBehavior::CloseOnClickOutside)
+        .wrap_mode(TextWrapMode::Extend)
+        .selected_text(
+            default_focus
+                .and_then(|id| universe.get_body(id))
+                .map(|w| &*w.body.name)
+                .unwrap_or("—"),
+        )
+        .show_ui(ui, |ui| {
+            selectable_body_tree(
+                ui,
+                *DEFAULT_FOCUS_TREE_ID,
+                universe,
+                &mut default_focus,
+                None,
+            );
+        });
+    universe.metadata.default_focus = default_focus;
+    ui.end_row();
+}
@@ -0,0 +1,107 @@
+use three_d::{
+    Srgba,
+    egui::{Align2, Area, Color32, Context, Frame, Margin, Pos2, Sense, Stroke, Ui, Vec2},
+};
+
+use crate::{
+    gfx::arrow::{
+        ACCELERATION_ARROW_COLOR, ACCELERATION_UNIT, VELOCITY_ARROW_COLOR, VELOCITY_UNIT,
+        get_arrow_decades,
+    },
+    gui::{SimState, declare_id, text_style::TextRole},
+};
+
+declare_id!(VECTOR_LEGEND_AREA, b"LegendV!");
+
+/// The width of one decade in the legend's scale, in points.
+const DECADE_WIDTH: f32 = 24.0;
+
+/// How many decades each scale shows.
+const DECADE_COUNT: i32 = 7;
+
+const SCALE_HEIGHT: f32 = 36.0;
+
+pub(super) fn draw(ctx: &Context, sim_state: &SimState) {
+    let mode = sim_state.vector_overlay;
+    if !mode.shows_velocity() {
+        return;
+    }
+
+    Area::new(*VECTOR_LEGEND_AREA_ID)
+        .anchor(Align2::LEFT_BOTTOM, [12.0, -80.0])
+        .interactable(false)
+        .show(ctx, |ui| {
+            Frame::new()
+                .fill(Color32::from_black_alpha(192))
+                .corner_radius(8.0)
+                .inner_margin(Margin::same(8))
+                .show(ui, |ui| {
+                    ui.visuals_mut().override_text_color = Some(Color32::WHITE);
+                    ui.label("Arrow length scale (logarithmic)");
+                    scale(ui, "Velocity", "m/s", VELOCITY_UNIT, VELOCITY_ARROW_COLOR);
+                    if mode.shows_acceleration() {
+                        scale(
+                            ui,
+                            "Acceleration",
+                            "m/s²",
+                            ACCELERATION_UNIT,
+                            ACCELERATION_ARROW_COLOR,
+                        );
+                    }
+                });
+        });
+}
+
+fn to_color32(color: Srgba) -> Color32 {
+    Color32::from_rgb(color.r, color.g, color.b)
+}
+
+/// Draws a ruler showing how long an arrow gets for each power of ten.
+fn scale(ui: &mut Ui, name: &str, unit_name: &str, unit: f64, color: Srgba) {
+    let color = to_color32(color);
+    let width = DECADE_WIDTH * DECADE_COUNT as f32;
+    let (rect, _) = ui.allocate_exact_size(Vec2::new(width + 48.0, SCALE_HEIGHT), Sense::hover());
+    let painter = ui.painter();
+
+    let baseline = rect.top() + 14.0;
+    let origin = Pos2::new(rect.left(), baseline);
+
+    painter.text(
+        Pos2::new(rect.left(), rect.top()),
+        Align2::LEFT_TOP,
+        name,
+        TextRole::Caption.resolve(ui.style()),
+        color,
+    );
+
+    let stroke = Stroke::new(2.0, color);
+    painter.arrow(origin, Vec2::new(width + 8.0, 0.0), stroke);
+
+    let base_exponent = unit.log10().round() as i32;
+
+    for i in (0..DECADE_COUNT).step_by(2) {
+        let exponent = base_exponent + i + 1;
+        let magnitude = 10f64.powi(exponent);
+        let x = rect.left() + (get_arrow_decades(magnitude, unit) as f32) * DECADE_WIDTH;
+
+        painter.line_segment(
+            [Pos2::new(x, baseline - 3.0), Pos2::new(x, baseline + 3.0)],
+            Stroke::new(1.0, Color32::WHITE),
+        );
+        painter.text(
+            Pos2::new(x, baseline + 4.0),
+            Align2::CENTER_TOP,
+            format!("1e{exponent}"),
+            TextRole::CaptionMonospace.resolve(ui.style()),
+            Color32::WHITE,
+        );
+    }
+
+    painter.text(
+        Pos2::new(rect.right(), baseline),
+        Align2::RIGHT_CENTER,
+        unit_name,
+        TextRole::Caption.resolve(ui.style()),
+        Color32::WHITE,
+    );
+}
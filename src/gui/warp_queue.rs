@@ -0,0 +1,212 @@
+use three_d::egui::{
+    Button, Color32, CursorIcon, Grid, Key, Popup, PopupCloseBehavior, RichText, TextEdit, Ui, Vec2,
+};
+
+use crate::{
+    gui::{
+        MIN_TOUCH_TARGET_LEN, SimState, bottom_bar::resolve_time_entry, declare_id, help::HelpText,
+        text_style::TextRole,
+    },
+    notify::{Severity, notify},
+    sim::{
+        universe::Universe,
+        warp::{WarpEvent, next_event_time},
+    },
+    units::time::TimeDisplayMode,
+};
+
+declare_id!(salt_only, WARP_QUEUE_GRID, b"WarpList");
+
+#[derive(Default)]
+pub(super) struct WarpQueueState {
+    /// The date typed in to warp to.
+    date_text: String,
+}
+
+/// What a warp event is, like "Periapsis of Moon".
+pub(super) fn event_label(universe: &Universe, event: WarpEvent) -> String {
+    match event {
+        WarpEvent::Date(time) => format!(
+            "{} at {}",
+            event.name(),
+            TimeDisplayMode::MultiUnit.format_time(time)
+        ),
+        _ => match event.body().and_then(|id| universe.get_body(id)) {
+            Some(wrapper) => format!("{} of {}", event.name(), wrapper.body.name),
+            None => event.name().to_owned(),
+        },
+    }
+}
+
+/// The button in the bottom bar for the warp queue,
+/// which lists the queued events and adds new ones.
+pub(super) fn queue_button(ui: &mut Ui, sim_state: &mut SimState) {
+    let count = sim_state.warp_queue.entries().len();
+    let text = if count == 0 {
+        "Warp".to_owned()
+    } else {
+        format!("Warp ({count})")
+    };
+
+    let button = Button::new(RichText::new(text).text_style(TextRole::Body.into()))
+        .min_size(Vec2::new(0.0, MIN_TOUCH_TARGET_LEN))
+        .frame(false);
+    let button = ui
+        .add(button)
+        .on_help_text(
+            RichText::new(
+                "Skip ahead to upcoming events, like the focused body's next periapsis, \
+                one after another",
+            )
+            .color(Color32::WHITE)
+            .text_style(TextRole::Body.into()),
+        )
+        .on_hover_cursor(CursorIcon::PointingHand);
+
+    let popup = Popup::menu(&button).close_behavior(PopupCloseBehavior::CloseOnClickOutside);
+    popup.show(|ui| {
+        ui.set_max_width(320.0);
+        ui.visuals_mut().override_text_color = Some(Color32::WHITE);
+        queue_list(ui, sim_state);
+        ui.separator();
+        add_events(ui, sim_state);
+    });
+}
+
+fn queue_list(ui: &mut Ui, sim_state: &mut SimState) {
+    if sim_state.warp_queue.is_empty() {
+        ui.label("Nothing to warp to yet.");
+        return;
+    }
+
+    let universe = &sim_state.universe;
+    let mut cancelled = None;
+
+    Grid::new(WARP_QUEUE_GRID_SALT)
+        .num_columns(3)
+        .spacing([16.0, 4.0])
+        .striped(true)
+        .show(ui, |ui| {
+            for (index, event) in sim_state.warp_queue.entries().enumerate() {
+                ui.label(event_label(universe, event));
+
+                // Later events are worked out from now, since
+                // they aren't known for sure until the warp gets there
+                let target = match sim_state.warp_queue.target_time() {
+                    Some(target) if index == 0 => Ok(target),
+                    _ => next_event_time(universe, event),
+                };
+                match target {
+                    Ok(target) => ui.label(format!(
+                        "in {}",
+                        TimeDisplayMode::MultiUnit.format_time(target - universe.time)
+                    )),
+                    Err(e) => ui
+                        .label(RichText::new("⚠").color(Color32::YELLOW))
+                        .on_help_text(
+                            RichText::new(format!("This will be skipped: {e}."))
+                                .color(Color32::WHITE)
+                                .text_style(TextRole::Body.into()),
+                        )
+                        .on_hover_cursor(CursorIcon::Help),
+                };
+
+                if ui
+                    .small_button("×")
+                    .on_help_text(
+                        RichText::new("Take this out of the queue")
+                            .color(Color32::WHITE)
+                            .text_style(TextRole::Body.into()),
+                    )
+                    .clicked()
+                {
+                    cancelled = Some(index);
+                }
+                ui.end_row();
+            }
+        });
+
+    if let Some(index) = cancelled
+        && let Some(speed) = sim_state.warp_queue.cancel(index)
+    {
+        sim_state.sim_speed = speed;
+    }
+
+    if ui.button("Cancel all").clicked()
+        && let Some(speed) = sim_state.warp_queue.clear()
+    {
+        sim_state.sim_speed = speed;
+    }
+}
+
+fn add_events(ui: &mut Ui, sim_state: &mut SimState) {
+    let focused = sim_state.focused_body();
+    let name = sim_state
+        .universe
+        .get_body(focused)
+        .map(|w| w.body.name.clone())
+        .unwrap_or_default();
+
+    ui.label(format!("Add for {name}:"));
+    ui.horizontal_wrapped(|ui| {
+        let events = [
+            (WarpEvent::Periapsis(focused), "Next periapsis"),
+            (WarpEvent::Apoapsis(focused), "Next apoapsis"),
+            (WarpEvent::SoiExit(focused), "SOI exit"),
+        ];
+        for (event, label) in events {
+            let result = next_event_time(&sim_state.universe, event);
+            let button = ui.add_enabled(result.is_ok(), Button::new(label));
+            let button = match result {
+                Ok(_) => button,
+                Err(e) => button.on_disabled_hover_text(
+                    RichText::new(format!("Can't warp there: {e}."))
+                        .color(Color32::WHITE)
+                        .text_style(TextRole::Body.into()),
+                ),
+            };
+            if button.clicked() {
+                queue_event(sim_state, event);
+            }
+        }
+    });
+
+    ui.horizontal(|ui| {
+        let text_edit = TextEdit::singleline(&mut sim_state.ui.warp_queue_state.date_text)
+            .hint_text("Date, like \"3.2 y\" or \"+30 d\"")
+            .desired_width(200.0);
+        let response = ui.add(text_edit);
+        let submitted = response.lost_focus() && ui.input(|i| i.key_pressed(Key::Enter));
+
+        if ui.button("Add").clicked() || submitted {
+            let text = std::mem::take(&mut sim_state.ui.warp_queue_state.date_text);
+            match resolve_time_entry(sim_state, &text) {
+                Ok(time) => queue_event(sim_state, WarpEvent::Date(time)),
+                Err(reason) => {
+                    notify(
+                        Severity::Warning,
+                        format!("Couldn't add to the warp: {reason}"),
+                    );
+                    sim_state.ui.warp_queue_state.date_text = text;
+                }
+            }
+        }
+    });
+}
+
+/// Adds `event` to the end of the queue, if it's going to happen.
+fn queue_event(sim_state: &mut SimState, event: WarpEvent) {
+    if let Err(e) = next_event_time(&sim_state.universe, event) {
+        notify(
+            Severity::Warning,
+            format!(
+                "Couldn't warp to {}: {e}.",
+                event_label(&sim_state.universe, event)
+            ),
+        );
+        return;
+    }
+
+    sim_state.warp_queue.push(event);
+    sim_state.running = true;
+}
@@ -1,15 +1,43 @@
+use strum::IntoEnumIterator;
 use three_d::egui::{
-    CollapsingResponse, Color32, Context as EguiContext, Grid, OpenUrl, Response, RichText, Ui,
-    WidgetText, Window,
+    CollapsingResponse, Color32, Context as EguiContext, CursorIcon, Grid, OpenUrl, Response,
+    RichText, Sense, Slider, Stroke, TextEdit, Ui, Vec2, WidgetText, Window,
 };
 
-use crate::{cfg::CONFIG, gui::declare_id};
+use crate::{
+    cfg::CONFIG,
+    gui::{declare_id, help::HelpText, text_style::TextRole},
+    metrics::{self, Feature},
+    sim::{
+        generator::{self, GeneratorSettings, MAX_PLANET_COUNT},
+        library::PresetUniverse,
+        universe::Universe,
+    },
+};
 
 declare_id!(salt_only, KEYBINDS_GRID, b"BINGINGS");
+declare_id!(salt_only, GENERATOR_GRID, b"Big8ang!");
 
 pub(super) struct WindowState {
-    open: bool,
+    pub(super) open: bool,
     dont_show_again: bool,
+    generator: GeneratorState,
+}
+
+/// The options in the system generator wizard.
+struct GeneratorState {
+    /// The seed as typed; numbers are used as-is, anything else gets hashed.
+    seed_text: String,
+    settings: GeneratorSettings,
+}
+
+impl Default for GeneratorState {
+    fn default() -> Self {
+        Self {
+            seed_text: String::from("1"),
+            settings: GeneratorSettings::default(),
+        }
+    }
 }
 
 impl Default for WindowState {
@@ -20,18 +48,37 @@ impl Default for WindowState {
                 .map(|c| c.show_welcome_window.get())
                 .unwrap_or(true),
             dont_show_again: false,
+            generator: GeneratorState::default(),
+        }
+    }
+}
+
+impl WindowState {
+    pub(super) fn closed() -> Self {
+        Self {
+            open: false,
+            dont_show_again: false,
+            generator: GeneratorState::default(),
         }
     }
 }
 
-pub(super) fn draw(ctx: &EguiContext, state: &mut WindowState) {
+/// Returns: A preset or newly-generated universe to load, if the user asked for one.
+pub(super) fn draw(
+    ctx: &EguiContext,
+    state: &mut WindowState,
+    universe: &Universe,
+) -> Option<Universe> {
     let mut open = state.open;
-    Window::new("Welcome")
+    let generated = Window::new("Welcome")
         .open(&mut open)
         .vscroll(true)
         .default_height(480.0)
-        .show(ctx, |ui| draw_window_contents(ui, state));
+        .show(ctx, |ui| draw_window_contents(ui, state, universe))
+        .and_then(|response| response.inner)
+        .flatten();
     state.open &= open;
+    generated
 }
 
 fn hyperlink_button(ui: &mut Ui, label: impl Into<WidgetText>, url: impl ToString) -> Response {
@@ -44,7 +91,11 @@ fn hyperlink_button(ui: &mut Ui, label: impl Into<WidgetText>, url: impl ToStrin
     button
 }
 
-fn draw_window_contents(ui: &mut Ui, state: &mut WindowState) {
+fn draw_window_contents(
+    ui: &mut Ui,
+    state: &mut WindowState,
+    universe: &Universe,
+) -> Option<Universe> {
     // ui.spacing_mut().interact_size = MIN_TOUCH_TARGET_VEC;
     ui.visuals_mut().override_text_color = Some(Color32::WHITE);
     ui.heading("Welcome to the keplerian_sim demo");
@@ -56,7 +107,28 @@ fn draw_window_contents(ui: &mut Ui, state: &mut WindowState) {
         If there's a term or UI element that seems unfamiliar, try hovering \
         on it; it might show a description or hint on what it does.",
     );
+    draw_universe_info(ui, universe);
     section(ui, "Keplerian orbits", draw_intro);
+    let preset = ui
+        .collapsing(
+            RichText::new("Preset universes")
+                .heading()
+                .color(Color32::WHITE)
+                .underline(),
+            draw_presets,
+        )
+        .body_returned
+        .flatten();
+    let generated = ui
+        .collapsing(
+            RichText::new("Generate a star system")
+                .heading()
+                .color(Color32::WHITE)
+                .underline(),
+            |ui| draw_generator(ui, &mut state.generator),
+        )
+        .body_returned
+        .flatten();
     section(ui, "Keybinds", draw_keybinds);
     section(ui, "Links", draw_links);
     section(ui, "Issues", draw_issues);
@@ -70,6 +142,8 @@ fn draw_window_contents(ui: &mut Ui, state: &mut WindowState) {
     {
         let _ = cfg.show_welcome_window.set(!state.dont_show_again);
     }
+
+    preset.or(generated)
 }
 
 fn section<I>(ui: &mut Ui, title: &str, content: fn(&mut Ui) -> I) -> CollapsingResponse<I> {
@@ -84,6 +158,25 @@ fn section<I>(ui: &mut Ui, title: &str, content: fn(&mut Ui) -> I) -> Collapsing
     collapsing
 }
 
+fn draw_universe_info(ui: &mut Ui, universe: &Universe) {
+    let metadata = &universe.metadata;
+    if metadata.name.is_empty() {
+        return;
+    }
+
+    ui.separator();
+    ui.label(RichText::new(format!("Loaded universe: {}", metadata.name)).strong());
+    if !metadata.author.is_empty() {
+        ui.label(format!("By {}", metadata.author));
+    }
+    if !metadata.epoch_label.is_empty() {
+        ui.label(format!("Epoch: {}", metadata.epoch_label));
+    }
+    if !metadata.description.is_empty() {
+        ui.label(&metadata.description);
+    }
+}
+
 fn draw_intro(ui: &mut Ui) {
     ui.label(
         "Keplerian orbits are special in that they are more stable \
@@ -99,14 +192,177 @@ fn draw_intro(ui: &mut Ui) {
     );
 }
 
+fn draw_presets(ui: &mut Ui) -> Option<Universe> {
+    let mut chosen = None;
+
+    for preset in PresetUniverse::iter() {
+        ui.horizontal(|ui| {
+            draw_thumbnail(ui, preset);
+            ui.vertical(|ui| {
+                ui.label(RichText::new(preset.name()).strong());
+                ui.label(preset.description());
+
+                let button = ui.button("Load").on_help_text(
+                    RichText::new(
+                        "Replace the current universe with this one.\n\
+                        Open a new tab first to keep the current one.",
+                    )
+                    .color(Color32::WHITE)
+                    .text_style(TextRole::Body.into()),
+                );
+                if button.clicked() {
+                    chosen = Some(preset);
+                }
+            });
+        });
+        ui.add_space(8.0);
+    }
+
+    chosen.map(PresetUniverse::create)
+}
+
+/// Draws a small sketch of a preset universe, seen from above.
+fn draw_thumbnail(ui: &mut Ui, preset: PresetUniverse) {
+    const SIZE: f32 = 72.0;
+
+    let (rect, _) = ui.allocate_exact_size(Vec2::splat(SIZE), Sense::hover());
+    let painter = ui.painter_at(rect);
+    let center = rect.center();
+    let orbit_stroke = Stroke::new(1.0, Color32::from_white_alpha(64));
+
+    painter.rect_filled(rect, 6.0, Color32::from_black_alpha(160));
+
+    match preset {
+        PresetUniverse::SolarSystem => {
+            painter.circle_filled(center, 4.0, Color32::from_rgb(255, 243, 234));
+            for (radius, angle, color) in [
+                (10.0, 0.8, Color32::from_rgb(232, 231, 229)),
+                (16.0, 2.9, Color32::from_rgb(154, 218, 235)),
+                (23.0, 4.6, Color32::from_rgb(230, 140, 100)),
+                (32.0, 1.7, Color32::from_rgb(216, 190, 160)),
+            ] {
+                painter.circle_stroke(center, radius, orbit_stroke);
+                let planet = center + Vec2::angled(angle) * radius;
+                painter.circle_filled(planet, 2.0, color);
+            }
+        }
+        PresetUniverse::TwinSuns => {
+            // The stars, on opposite sides of their shared orbit
+            let offset = Vec2::angled(-0.3) * 6.0;
+            painter.circle_stroke(center, 6.0, orbit_stroke);
+            painter.circle_filled(center + offset, 3.5, Color32::from_rgb(255, 243, 234));
+            painter.circle_filled(center - offset, 3.2, Color32::from_rgb(255, 214, 170));
+            for (radius, angle, color) in [
+                (16.0, 0.5, Color32::from_rgb(176, 150, 120)),
+                (24.0, 2.6, Color32::from_rgb(214, 190, 150)),
+                (33.0, 4.4, Color32::from_rgb(140, 170, 210)),
+            ] {
+                painter.circle_stroke(center, radius, orbit_stroke);
+                let planet = center + Vec2::angled(angle) * radius;
+                painter.circle_filled(planet, 2.0, color);
+            }
+        }
+    }
+}
+
+fn draw_generator(ui: &mut Ui, state: &mut GeneratorState) -> Option<Universe> {
+    ui.label(
+        "Create a whole new star system, with planets, moons and asteroid belts. \
+        The same seed and settings always give the same system, \
+        so you can share them with others.",
+    );
+
+    Grid::new(GENERATOR_GRID_SALT)
+        .num_columns(2)
+        .spacing([40.0, 4.0])
+        .striped(true)
+        .show(ui, |ui| {
+            ui.label("Seed")
+                .on_help_text(
+                    RichText::new(
+                        "Any text or number.\n\
+                        Numbers are used directly; other text is turned into a number.",
+                    )
+                    .color(Color32::WHITE)
+                    .text_style(TextRole::Body.into()),
+                )
+                .on_hover_cursor(CursorIcon::Help);
+            ui.horizontal(|ui| {
+                ui.add(TextEdit::singleline(&mut state.seed_text).desired_width(120.0));
+                if ui.button("Random").clicked() {
+                    let time = ui.input(|i| i.time);
+                    state.seed_text = generator::seed_from_text(&time.to_string()).to_string();
+                }
+            });
+            ui.end_row();
+
+            ui.label("Planets")
+                .on_help_text(
+                    RichText::new("How many planets orbit the star.")
+                        .color(Color32::WHITE)
+                        .text_style(TextRole::Body.into()),
+                )
+                .on_hover_cursor(CursorIcon::Help);
+            ui.add(Slider::new(
+                &mut state.settings.planet_count,
+                1..=MAX_PLANET_COUNT,
+            ));
+            ui.end_row();
+
+            ui.label("Chaos")
+                .on_help_text(
+                    RichText::new(
+                        "How far from neat, circular, flat and evenly-spaced orbits the system is.\n\
+                        Chaotic systems also get asteroid belts more often.",
+                    )
+                    .color(Color32::WHITE)
+                    .text_style(TextRole::Body.into()),
+                )
+                .on_hover_cursor(CursorIcon::Help);
+            ui.add(Slider::new(&mut state.settings.chaos, 0.0..=1.0));
+            ui.end_row();
+
+            ui.label("Moon frequency")
+                .on_help_text(
+                    RichText::new(
+                        "How likely each planet is to have moons.\n\
+                        Gas giants can hold more moons than rocky planets.",
+                    )
+                    .color(Color32::WHITE)
+                    .text_style(TextRole::Body.into()),
+                )
+                .on_hover_cursor(CursorIcon::Help);
+            ui.add(Slider::new(&mut state.settings.moon_frequency, 0.0..=1.0));
+            ui.end_row();
+        });
+
+    let button = ui.button("Generate").on_help_text(
+        RichText::new(
+            "Replace the current universe with the generated system.\n\
+            Open a new tab first to keep the current one.",
+        )
+        .color(Color32::WHITE)
+        .text_style(TextRole::Body.into()),
+    );
+
+    if button.clicked() {
+        state.settings.seed = generator::seed_from_text(&state.seed_text);
+        metrics::record_use(Feature::GenerateSystem);
+        Some(generator::generate(state.settings))
+    } else {
+        None
+    }
+}
+
 fn draw_keybinds(ui: &mut Ui) {
-    const KEYBINDS: [(&str, &str); 8] = [
+    const KEYBINDS: [(&str, &str); 9] = [
         (",", "Multiply time by 0.5×"),
         (".", "Multiply time by 2×"),
         ("Shift + ,", "Multiply time by 0.1×"),
         ("Shift + .", "Multiply time by 10×"),
         ("N", "Create a new body"),
         ("E", "Edit the currently-focused body"),
+        ("F", "Zoom to fit the focused body's orbit"),
         ("[", "Switch focus to the previous body in the list"),
         ("]", "Switch focus to the next body in the list"),
     ];
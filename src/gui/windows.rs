@@ -0,0 +1,267 @@
+//! The registry of toggleable windows.
+//!
+//! Each window is listed once here, and the bottom bar's toggle row and
+//! options menu are built from this list, so adding a window only takes
+//! a new entry.
+
+use std::collections::HashMap;
+
+use glam::DVec3;
+use three_d::egui::{Context as EguiContext, Image};
+
+use crate::{
+    assets,
+    gui::{
+        SimState, about, celestials, constraints, converter, diagnostics, ephemeris_tool,
+        id_registry, kick_tool, log_viewer, new_body_defaults, orbit_check_tool, phase_tool,
+        preset_editor, relative_path_tool, rendezvous_tool, settings_backup, toasts, toolbar,
+        transfer_tool, universe_settings, welcome,
+    },
+    sim::{startup_options::names_match, universe::Id as UniverseId},
+};
+
+pub(super) struct WindowEntry {
+    pub name: &'static str,
+    /// Shown when hovering over the window's toggle.
+    pub description: Option<&'static str>,
+    /// Shown on the window's toolbar button, if it has one.
+    pub icon: Option<fn() -> Image<'static>>,
+    pub open: fn(&mut SimState) -> &mut bool,
+    /// Draws the window. Called every frame, even while it's closed.
+    pub draw: fn(&EguiContext, &mut SimState, &HashMap<UniverseId, DVec3>),
+}
+
+impl WindowEntry {
+    pub(super) fn is_open(&self, sim_state: &mut SimState) -> bool {
+        *(self.open)(sim_state)
+    }
+
+    pub(super) fn toggle(&self, sim_state: &mut SimState) {
+        *(self.open)(sim_state) ^= true;
+    }
+}
+
+/// All toggleable windows, in the order their toggles are listed.
+pub(super) const WINDOWS: &[WindowEntry] = &[
+    WindowEntry {
+        name: "Celestial body list",
+        description: Some("Toggle celestial body list"),
+        icon: Some(|| assets::TREE_LIST_IMAGE.clone()),
+        open: |s| &mut s.ui.body_list_window_state.window_open,
+        draw: celestials::body_list_window,
+    },
+    WindowEntry {
+        name: "Edit celestial body",
+        description: Some(
+            "Toggle celestial body edit window\n\
+            This allows editing and viewing the \
+            currently-focused body's stats.",
+        ),
+        icon: Some(|| assets::EDIT_ORBIT_IMAGE.clone()),
+        open: |s| &mut s.ui.edit_body_window_state.window_open,
+        draw: |ctx, s, _| celestials::body_edit_window(ctx, s),
+    },
+    WindowEntry {
+        name: "Compare bodies",
+        description: Some(
+            "Show the derived information of two bodies side by side, \
+            with the values that differ highlighted.",
+        ),
+        icon: None,
+        open: |s| &mut s.ui.compare_window_state.open,
+        draw: |ctx, s, _| celestials::compare_window(ctx, s),
+    },
+    WindowEntry {
+        name: "Universe settings",
+        description: None,
+        icon: None,
+        open: |s| &mut s.ui.is_universe_settings_open,
+        draw: universe_settings::draw,
+    },
+    WindowEntry {
+        name: "New body defaults",
+        description: Some(
+            "Choose the size, orbit, color and name that newly added bodies start with.",
+        ),
+        icon: None,
+        open: |s| &mut s.ui.is_new_body_defaults_open,
+        draw: |ctx, s, _| new_body_defaults::draw(ctx, &mut s.ui),
+    },
+    WindowEntry {
+        name: "Orbit converter",
+        description: None,
+        icon: None,
+        open: |s| &mut s.ui.converter_window_state.open,
+        draw: |ctx, s, _| converter::draw(ctx, &mut s.ui),
+    },
+    WindowEntry {
+        name: "Preset editor",
+        description: Some(
+            "Edit the bundled presets and copy them out as presets.toml entries, \
+            checked the same way the build script checks them.",
+        ),
+        icon: None,
+        open: |s| &mut s.ui.preset_editor_state.open,
+        draw: |ctx, s, _| preset_editor::draw(ctx, &mut s.ui),
+    },
+    WindowEntry {
+        name: "Constraints",
+        description: Some(
+            "Link parameters of different bodies together, \
+            like keeping one moon's period twice another's.",
+        ),
+        icon: None,
+        open: |s| &mut s.ui.constraints_window_state.open,
+        draw: |ctx, s, _| constraints::draw(ctx, s),
+    },
+    WindowEntry {
+        name: "Phase matching",
+        description: Some(
+            "Find out when two bodies orbiting the same parent line up at a given angle, \
+            and warp there.",
+        ),
+        icon: None,
+        open: |s| &mut s.ui.phase_tool_state.open,
+        draw: |ctx, s, _| phase_tool::draw(ctx, s),
+    },
+    WindowEntry {
+        name: "Relative path",
+        description: Some(
+            "Draw the focused body's path as seen from another body, \
+            like the loops Mars makes in Earth's sky.",
+        ),
+        icon: None,
+        open: |s| &mut s.ui.relative_path_state.open,
+        draw: |ctx, s, _| relative_path_tool::draw(ctx, s),
+    },
+    WindowEntry {
+        name: "Transfer orbit",
+        description: Some(
+            "Find the orbit that goes from one point or body to another \
+            in a given time, and preview it as a new body.",
+        ),
+        icon: None,
+        open: |s| &mut s.ui.transfer_tool_state.open,
+        draw: |ctx, s, _| transfer_tool::draw(ctx, s),
+    },
+    WindowEntry {
+        name: "Velocity kick",
+        description: Some(
+            "Change the focused body's velocity right now, \
+            like pushing an asteroid off course.",
+        ),
+        icon: None,
+        open: |s| &mut s.ui.kick_tool_state.open,
+        draw: |ctx, s, _| kick_tool::draw(ctx, s),
+    },
+    WindowEntry {
+        name: "Rendezvous camera",
+        description: Some(
+            "Ride along with the focused body, turning with its orbit, \
+            and watch how another body drifts relative to it.",
+        ),
+        icon: None,
+        open: |s| &mut s.ui.rendezvous_state.open,
+        draw: |ctx, s, _| rendezvous_tool::draw(ctx, s),
+    },
+    WindowEntry {
+        name: "Ephemeris export",
+        description: Some(
+            "Sample where a body will be over a span of time, \
+            and copy the table as CSV or JSON.",
+        ),
+        icon: None,
+        open: |s| &mut s.ui.ephemeris_tool_state.open,
+        draw: |ctx, s, _| ephemeris_tool::draw(ctx, s),
+    },
+    WindowEntry {
+        name: "Welcome window",
+        description: Some(
+            "Shows the welcome window again, which also has the star system generator.",
+        ),
+        icon: None,
+        open: |s| &mut s.ui.welcome_window_state.open,
+        draw: |ctx, s, _| {
+            let universe = welcome::draw(ctx, &mut s.ui.welcome_window_state, &s.universe);
+            if universe.is_some() {
+                s.ui.universe_to_load = universe;
+            }
+        },
+    },
+    WindowEntry {
+        name: "Settings backup",
+        description: Some(
+            "Copy your settings as JSON, or import settings copied from another device.",
+        ),
+        icon: None,
+        open: |s| &mut s.ui.settings_backup_state.open,
+        draw: |ctx, s, _| settings_backup::draw(ctx, &mut s.ui.settings_backup_state),
+    },
+    WindowEntry {
+        name: "Event log",
+        description: Some("Lists the errors and warnings that were shown as notifications."),
+        icon: None,
+        open: |s| &mut s.ui.is_event_log_open,
+        draw: |ctx, s, _| toasts::draw_event_log(ctx, &mut s.ui),
+    },
+    WindowEntry {
+        name: "Log",
+        description: Some(
+            "Shows what the program logged, including details that aren't \
+            shown as notifications. Handy to copy into a bug report.",
+        ),
+        icon: None,
+        open: |s| &mut s.ui.log_viewer_state.open,
+        draw: |ctx, s, _| log_viewer::draw(ctx, &mut s.ui.log_viewer_state),
+    },
+    WindowEntry {
+        name: "Diagnostics",
+        description: Some(
+            "Optionally record frame times and feature usage on this device, \
+            to help report performance problems. Nothing is sent anywhere.",
+        ),
+        icon: None,
+        open: |s| &mut s.ui.is_diagnostics_open,
+        draw: |ctx, s, _| diagnostics::draw(ctx, &mut s.ui),
+    },
+    WindowEntry {
+        name: "Toolbar",
+        description: Some("Choose which buttons the bottom bar shows, and in what order."),
+        icon: None,
+        open: |s| &mut s.ui.is_toolbar_editor_open,
+        draw: |ctx, s, _| toolbar::draw_editor(ctx, &mut s.ui),
+    },
+    WindowEntry {
+        name: "Orbit check",
+        description: Some(
+            "Checks the focused body's orbit for NaN, infinite or jumping values \
+            from the orbit math. Handy for reporting bugs in keplerian_sim.",
+        ),
+        icon: None,
+        open: |s| &mut s.ui.orbit_check_state.open,
+        draw: |ctx, s, _| orbit_check_tool::draw(ctx, s),
+    },
+    WindowEntry {
+        name: "Declared ids",
+        description: Some(
+            "Lists the egui ids declared in the source and where, \
+            marking any that collide. For development.",
+        ),
+        icon: None,
+        open: |s| &mut s.ui.is_id_registry_open,
+        draw: |ctx, s, _| id_registry::draw(ctx, &mut s.ui),
+    },
+    WindowEntry {
+        name: "About keplerian_sim",
+        description: None,
+        icon: None,
+        open: |s| &mut s.ui.is_about_window_open,
+        draw: |ctx, s, _| about::draw(ctx, &mut s.ui),
+    },
+];
+
+/// The window listed as `name`, ignoring case and separators,
+/// like `edit-celestial-body`.
+pub(super) fn find(name: &str) -> Option<&'static WindowEntry> {
+    WINDOWS.iter().find(|entry| names_match(entry.name, name))
+}
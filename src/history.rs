@@ -0,0 +1,343 @@
+//! Bounded, compressed storage for records that pile up over a long session,
+//! like the Log window's records and the event log.
+//!
+//! Each record is stored as how much of its encoding it shares with the
+//! record before it, followed by only the bytes that differ. Records from the
+//! same module or repeating the same message shrink to a few bytes this way.
+//! The oldest records are dropped once either the record limit or the byte
+//! limit is reached.
+
+use std::collections::VecDeque;
+
+use crate::cfg::CONFIG;
+
+/// The default byte limit of the Log window's records, in KiB.
+pub(crate) const DEFAULT_LOG_LIMIT_KIB: u32 = 256;
+/// The default byte limit of the event log, in KiB.
+pub(crate) const DEFAULT_EVENT_LOG_LIMIT_KIB: u32 = 64;
+
+pub(crate) const MIN_LIMIT_KIB: u32 = 16;
+pub(crate) const MAX_LIMIT_KIB: u32 = 16 * 1024;
+
+/// A record that can be stored in a [`History`].
+pub(crate) trait Record: Sized {
+    /// Writes the record's bytes to `out`.
+    ///
+    /// Only the part after what it shares with the previous record's bytes
+    /// is stored, so the parts most likely to repeat should come first.
+    fn encode(&self, out: &mut Vec<u8>);
+
+    /// Reads a record back from what [`Record::encode`] wrote.
+    fn decode(bytes: &[u8]) -> Option<Self>;
+}
+
+/// How much memory a history is using.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) struct HistoryUsage {
+    pub records: usize,
+    /// The bytes the records take up as stored.
+    pub stored_bytes: usize,
+    /// The bytes the records would take up without the delta encoding.
+    pub raw_bytes: usize,
+}
+
+pub(crate) struct History<T> {
+    /// Each record's shared prefix length, then its differing suffix.
+    /// The oldest record never shares anything.
+    records: VecDeque<Box<[u8]>>,
+    /// The newest record's full encoding, to compare the next one against.
+    last: Vec<u8>,
+    stored_bytes: usize,
+    raw_bytes: usize,
+    max_records: usize,
+    max_bytes: usize,
+    /// Goes up whenever the records change.
+    version: u64,
+    _record: core::marker::PhantomData<T>,
+}
+
+impl<T: Record> History<T> {
+    pub(crate) const fn new(max_records: usize, max_bytes: usize) -> Self {
+        Self {
+            records: VecDeque::new(),
+            last: Vec::new(),
+            stored_bytes: 0,
+            raw_bytes: 0,
+            max_records,
+            max_bytes,
+            version: 0,
+            _record: core::marker::PhantomData,
+        }
+    }
+
+    pub(crate) fn push(&mut self, record: &T) {
+        let mut full = Vec::new();
+        record.encode(&mut full);
+
+        let shared = if self.records.is_empty() {
+            0
+        } else {
+            shared_prefix_len(&self.last, &full)
+        };
+        let mut stored = Vec::with_capacity(full.len() - shared + 2);
+        write_varint(&mut stored, shared);
+        stored.extend_from_slice(&full[shared..]);
+
+        self.stored_bytes += stored.len();
+        self.raw_bytes += full.len();
+        self.records.push_back(stored.into_boxed_slice());
+        self.last = full;
+        self.version += 1;
+
+        self.trim();
+    }
+
+    /// Changes the limits, dropping the oldest records if there are too many now.
+    pub(crate) fn set_limits(&mut self, max_records: usize, max_bytes: usize) {
+        self.max_records = max_records;
+        self.max_bytes = max_bytes;
+        self.trim();
+        self.version += 1;
+    }
+
+    /// Drops the oldest records until the history is within its limits,
+    /// always keeping the newest one.
+    fn trim(&mut self) {
+        while self.records.len() > 1
+            && (self.records.len() > self.max_records || self.stored_bytes > self.max_bytes)
+        {
+            self.pop_front();
+        }
+    }
+
+    fn pop_front(&mut self) {
+        let Some(front) = self.records.pop_front() else {
+            return;
+        };
+        let front_full = split_stored(&front).map(|(_, suffix)| suffix.to_vec());
+        self.stored_bytes -= front.len();
+        self.raw_bytes -= front_full.as_ref().map_or(0, Vec::len);
+
+        // The new oldest record was stored against the one just dropped,
+        // so it's stored in full instead
+        let (Some(front_full), Some(next)) = (front_full, self.records.front_mut()) else {
+            return;
+        };
+        let Some((shared, suffix)) = split_stored(next) else {
+            return;
+        };
+        let mut full = Vec::with_capacity(1 + shared + suffix.len());
+        write_varint(&mut full, 0);
+        full.extend_from_slice(&front_full[..shared.min(front_full.len())]);
+        full.extend_from_slice(suffix);
+
+        self.stored_bytes = self.stored_bytes - next.len() + full.len();
+        *next = full.into_boxed_slice();
+    }
+
+    /// Decodes every record, oldest first.
+    /// Records that can't be decoded are left out.
+    pub(crate) fn to_vec(&self) -> Vec<T> {
+        let mut full = Vec::new();
+        let mut records = Vec::with_capacity(self.records.len());
+        for stored in &self.records {
+            let Some((shared, suffix)) = split_stored(stored) else {
+                continue;
+            };
+            full.truncate(shared);
+            full.extend_from_slice(suffix);
+            records.extend(T::decode(&full));
+        }
+        records
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.records.clear();
+        self.last.clear();
+        self.stored_bytes = 0;
+        self.raw_bytes = 0;
+        self.version += 1;
+    }
+
+    /// Changes whenever the records do, so a decoded copy of them
+    /// can be kept until it's out of date.
+    pub(crate) fn version(&self) -> u64 {
+        self.version
+    }
+
+    pub(crate) fn usage(&self) -> HistoryUsage {
+        HistoryUsage {
+            records: self.records.len(),
+            stored_bytes: self.stored_bytes,
+            raw_bytes: self.raw_bytes,
+        }
+    }
+}
+
+fn shared_prefix_len(a: &[u8], b: &[u8]) -> usize {
+    a.iter().zip(b).take_while(|(a, b)| a == b).count()
+}
+
+/// Writes `value` in LEB128, seven bits at a time.
+fn write_varint(out: &mut Vec<u8>, mut value: usize) {
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            out.push(byte);
+            return;
+        }
+        out.push(byte | 0x80);
+    }
+}
+
+/// Splits a stored record into its shared prefix length and its suffix.
+fn split_stored(stored: &[u8]) -> Option<(usize, &[u8])> {
+    let mut value = 0usize;
+    for (index, &byte) in stored.iter().enumerate().take(10) {
+        value |= ((byte & 0x7f) as usize) << (7 * index);
+        if byte & 0x80 == 0 {
+            return Some((value, &stored[index + 1..]));
+        }
+    }
+    None
+}
+
+/// Applies the history size settings to the Log window's records
+/// and the event log.
+pub(crate) fn apply_limits() {
+    let (log_kib, event_log_kib) = CONFIG
+        .try_lock()
+        .map(|cfg| (cfg.log_history_kib.get(), cfg.event_log_kib.get()))
+        .unwrap_or((DEFAULT_LOG_LIMIT_KIB, DEFAULT_EVENT_LOG_LIMIT_KIB));
+
+    let to_bytes = |kib: u32| kib.clamp(MIN_LIMIT_KIB, MAX_LIMIT_KIB) as usize * 1024;
+    crate::logging::set_byte_limit(to_bytes(log_kib));
+    crate::notify::set_byte_limit(to_bytes(event_log_kib));
+}
+
+pub(crate) fn set_log_limit_kib(kib: u32) {
+    if let Ok(cfg) = CONFIG.try_lock() {
+        let _ = cfg
+            .log_history_kib
+            .set(kib.clamp(MIN_LIMIT_KIB, MAX_LIMIT_KIB));
+    }
+    apply_limits();
+}
+
+pub(crate) fn set_event_log_limit_kib(kib: u32) {
+    if let Ok(cfg) = CONFIG.try_lock() {
+        let _ = cfg
+            .event_log_kib
+            .set(kib.clamp(MIN_LIMIT_KIB, MAX_LIMIT_KIB));
+    }
+    apply_limits();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Clone, Debug, PartialEq)]
+    struct Text(String);
+
+    impl Record for Text {
+        fn encode(&self, out: &mut Vec<u8>) {
+            out.extend_from_slice(self.0.as_bytes());
+        }
+
+        fn decode(bytes: &[u8]) -> Option<Self> {
+            String::from_utf8(bytes.to_vec()).ok().map(Text)
+        }
+    }
+
+    fn text(s: &str) -> Text {
+        Text(s.to_owned())
+    }
+
+    #[test]
+    fn records_round_trip_in_order() {
+        let mut history = History::new(100, usize::MAX);
+        let records = [
+            text("orbit line cache cleared"),
+            text("orbit line cache rebuilt"),
+            text("orbit"),
+            text(""),
+            text("Ünïcödé ☄"),
+            text("Ünïcödé ☄ again"),
+        ];
+        for record in &records {
+            history.push(record);
+        }
+
+        assert_eq!(history.to_vec(), records);
+    }
+
+    #[test]
+    fn repeated_records_are_stored_smaller() {
+        let mut history = History::new(100, usize::MAX);
+        for _ in 0..50 {
+            history.push(&text("Couldn't copy to the clipboard: it's busy"));
+        }
+
+        let usage = history.usage();
+        assert_eq!(usage.records, 50);
+        assert!(usage.stored_bytes * 10 < usage.raw_bytes, "{usage:?}");
+    }
+
+    #[test]
+    fn the_oldest_records_are_dropped_first() {
+        let mut history = History::new(3, usize::MAX);
+        for i in 0..10 {
+            history.push(&text(&format!("record number {i}")));
+        }
+        // Still changes once the record count stays the same
+        assert_eq!(history.version(), 10);
+
+        assert_eq!(
+            history.to_vec(),
+            [
+                text("record number 7"),
+                text("record number 8"),
+                text("record number 9")
+            ]
+        );
+        assert_eq!(history.usage().raw_bytes, 3 * "record number 0".len());
+    }
+
+    #[test]
+    fn the_byte_limit_is_kept_to() {
+        let mut history = History::new(usize::MAX, 64);
+        for i in 0..100 {
+            history.push(&text(&format!("{i} is a different start every time")));
+        }
+
+        let usage = history.usage();
+        assert!(usage.stored_bytes <= 64, "{usage:?}");
+        assert_eq!(
+            history.to_vec().last(),
+            Some(&text("99 is a different start every time"))
+        );
+
+        history.set_limits(1, 64);
+        assert_eq!(history.usage().records, 1);
+
+        history.clear();
+        assert!(history.is_empty());
+        assert_eq!(history.usage(), HistoryUsage::default());
+    }
+
+    #[test]
+    fn varints_round_trip() {
+        for value in [0, 1, 127, 128, 300, 16_383, 16_384, usize::MAX] {
+            let mut bytes = Vec::new();
+            write_varint(&mut bytes, value);
+            bytes.extend_from_slice(b"rest");
+            assert_eq!(split_stored(&bytes), Some((value, &b"rest"[..])));
+        }
+    }
+}
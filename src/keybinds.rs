@@ -1,16 +1,21 @@
-use std::collections::HashMap;
+use std::{
+    collections::HashMap,
+    sync::atomic::{AtomicBool, Ordering},
+};
 
-use keplerian_sim::Orbit;
-use three_d::{Event, GUI, Key, Modifiers, Srgba};
+use three_d::{Event, GUI, Key, Modifiers};
 
 use crate::{
-    gui::{PreviewBody, SimState},
+    gui::{SimState, new_body_preview, presentation, quit, ui_scale},
     sim::{
-        body::Body,
         universe::{BodyWrapper, Id, Universe},
+        zoom_levels::ZoomLevel,
     },
 };
 
+/// Whether Ctrl (or Cmd on Mac) is held, as of the last modifier change.
+static COMMAND_HELD: AtomicBool = AtomicBool::new(false);
+
 pub(super) fn handle_keybinds(sim_state: &mut SimState, events: &mut [Event], gui: &GUI) {
     for event in events {
         match event {
@@ -19,7 +24,15 @@ pub(super) fn handle_keybinds(sim_state: &mut SimState, events: &mut [Event], gu
                 modifiers,
                 handled,
             } => handle_keypress(sim_state, key, modifiers, handled),
+            Event::ModifiersChange { modifiers } => {
+                COMMAND_HELD.store(modifiers.ctrl || modifiers.command, Ordering::Relaxed);
+            }
             Event::Text(text) => {
+                // Zooming works even while typing, like in a browser
+                if COMMAND_HELD.load(Ordering::Relaxed) {
+                    text.chars().for_each(ui_scale::handle_shortcut);
+                    continue;
+                }
                 if gui.context().wants_keyboard_input() {
                     continue;
                 }
@@ -28,12 +41,17 @@ pub(super) fn handle_keybinds(sim_state: &mut SimState, events: &mut [Event], gu
             _ => (),
         }
     }
+
+    #[cfg(target_family = "wasm")]
+    for char in crate::web::shortcuts::take_zoom_keys() {
+        ui_scale::handle_shortcut(char);
+    }
 }
 
 fn handle_keypress(
     sim_state: &mut SimState,
     key: &mut Key,
-    _modifiers: &mut Modifiers,
+    modifiers: &mut Modifiers,
     handled: &mut bool,
 ) {
     if *handled {
@@ -41,6 +59,14 @@ fn handle_keypress(
     }
 
     match key {
+        Key::Q if modifiers.command => {
+            quit::request(&mut sim_state.ui);
+            *handled = true;
+        }
+        Key::Tab => {
+            sim_state.ui.body_switcher_state.toggle();
+            *handled = true;
+        }
         Key::Space => {
             sim_state.running ^= true;
             *handled = true;
@@ -76,9 +102,13 @@ fn handle_char_input(sim_state: &mut SimState, char: char) {
         '.' => sim_state.sim_speed *= 2.0,
         '<' => sim_state.sim_speed /= 10.0,
         '>' => sim_state.sim_speed *= 10.0,
+        'r' | 'R' => sim_state.sim_speed = -sim_state.sim_speed,
         'e' | 'E' => {
             sim_state.ui.edit_body_window_state.window_open ^= true;
         }
+        'f' | 'F' => sim_state.ui.frame_orbit_requested = true,
+        'p' | 'P' => presentation::toggle(),
+        '1'..='4' => sim_state.ui.zoom_level_requested = ZoomLevel::from_key(char),
         _ => (),
     }
 }
@@ -88,38 +118,9 @@ fn add_new_body(sim_state: &mut SimState) {
         return;
     }
 
-    let root = sim_state
-        .universe
-        .get_bodies()
-        .iter()
-        .min_by_key(|(id, _)| **id);
-
-    if let Some((&root_id, root_wrapper)) = root {
-        let root_body = &root_wrapper.body;
-        sim_state.preview_body = Some(PreviewBody {
-            body: Body {
-                mass: 1.0,
-                name: format!("Child of {}", &root_body.name),
-                radius: root_body.radius * 0.1,
-                color: Srgba::WHITE,
-                orbit: Some(Orbit::new(
-                    0.0,
-                    root_body.radius * 2.0,
-                    0.0,
-                    0.0,
-                    0.0,
-                    0.0,
-                    root_body.mass * sim_state.universe.get_gravitational_constant(),
-                )),
-            },
-            parent_id: Some(root_id),
-        })
-    } else {
-        sim_state.preview_body = Some(PreviewBody {
-            body: Body::default(),
-            parent_id: None,
-        })
-    }
+    let root_id = sim_state.universe.get_bodies().keys().min().copied();
+
+    sim_state.preview_body = Some(new_body_preview(&sim_state.universe, root_id));
 }
 
 fn switch_to_prev_body(sim_state: &mut SimState) {
@@ -3,7 +3,7 @@
 
 use main::*;
 use wasm_bindgen::prelude::*;
-use web_sys::Document;
+use web_sys::{Document, HtmlCanvasElement};
 
 mod main;
 mod web;
@@ -18,6 +18,7 @@ fn start() {
 
     clear_dom(&document);
     init_canvas(&document);
+    web::shortcuts::capture_zoom_keys(&window);
 
     main::run();
 }
@@ -36,4 +37,8 @@ fn init_canvas(document: &Document) {
         .expect("body should exist")
         .append_child(&canvas)
         .expect("canvas attachmint should work");
+
+    if let Some(canvas) = canvas.dyn_ref::<HtmlCanvasElement>() {
+        web::gestures::capture_gestures(canvas);
+    }
 }
@@ -0,0 +1,167 @@
+//! The backend for the `log` macros used throughout the program.
+//!
+//! Records are printed to stderr on native and to the browser console on
+//! the web, where stderr goes nowhere. The most recent ones are also kept
+//! for the Log window, so they can be looked at and copied into a bug report
+//! without a terminal or developer tools.
+//!
+//! On native, the `RUST_LOG` environment variable sets the lowest level
+//! printed to stderr, like `RUST_LOG=debug`. The Log window gets everything
+//! from debug up regardless.
+
+use std::sync::Mutex;
+
+use log::{Level, LevelFilter, Log, Metadata, Record};
+
+use crate::history::{self, History, HistoryUsage};
+
+/// How many records the Log window remembers.
+const HISTORY_LIMIT: usize = 500;
+
+/// The lowest level that's recorded at all.
+const MAX_LEVEL: LevelFilter = LevelFilter::Debug;
+
+/// The lowest level printed when `RUST_LOG` isn't set.
+#[cfg(not(target_family = "wasm"))]
+const DEFAULT_PRINT_LEVEL: LevelFilter = LevelFilter::Info;
+
+#[derive(Clone, Debug)]
+pub(crate) struct LogEntry {
+    pub(crate) level: Level,
+    /// The module the record came from.
+    pub(crate) target: String,
+    pub(crate) message: String,
+}
+
+impl history::Record for LogEntry {
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.push(self.level as u8);
+        out.extend_from_slice(self.target.as_bytes());
+        out.push(0);
+        out.extend_from_slice(self.message.as_bytes());
+    }
+
+    fn decode(bytes: &[u8]) -> Option<Self> {
+        let (&level, rest) = bytes.split_first()?;
+        let level = [
+            Level::Error,
+            Level::Warn,
+            Level::Info,
+            Level::Debug,
+            Level::Trace,
+        ]
+        .into_iter()
+        .find(|&l| l as u8 == level)?;
+        let separator = rest.iter().position(|&b| b == 0)?;
+        Some(Self {
+            level,
+            target: String::from_utf8_lossy(&rest[..separator]).into_owned(),
+            message: String::from_utf8_lossy(&rest[separator + 1..]).into_owned(),
+        })
+    }
+}
+
+struct Logger {
+    history: Mutex<History<LogEntry>>,
+}
+
+static LOGGER: Logger = Logger {
+    history: Mutex::new(History::new(
+        HISTORY_LIMIT,
+        history::DEFAULT_LOG_LIMIT_KIB as usize * 1024,
+    )),
+};
+
+impl Log for Logger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= MAX_LEVEL
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+
+        let entry = LogEntry {
+            level: record.level(),
+            target: record.target().to_owned(),
+            message: record.args().to_string(),
+        };
+
+        print(&entry);
+
+        // Don't wait on the lock, in case something logs while the Log window reads
+        if let Ok(mut history) = self.history.try_lock() {
+            history.push(&entry);
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+#[cfg(not(target_family = "wasm"))]
+fn print(entry: &LogEntry) {
+    use std::sync::LazyLock;
+
+    static PRINT_LEVEL: LazyLock<LevelFilter> = LazyLock::new(|| {
+        std::env::var("RUST_LOG")
+            .ok()
+            .and_then(|level| level.trim().parse().ok())
+            .unwrap_or(DEFAULT_PRINT_LEVEL)
+    });
+
+    if entry.level <= *PRINT_LEVEL {
+        eprintln!("[{} {}] {}", entry.level, entry.target, entry.message);
+    }
+}
+
+#[cfg(target_family = "wasm")]
+fn print(entry: &LogEntry) {
+    use wasm_bindgen::JsValue;
+    use web_sys::console;
+
+    // The console has its own level filter, so everything is sent to it
+    let text = JsValue::from(format!("[{}] {}", entry.target, entry.message));
+    match entry.level {
+        Level::Error => console::error_1(&text),
+        Level::Warn => console::warn_1(&text),
+        Level::Info => console::info_1(&text),
+        Level::Debug | Level::Trace => console::debug_1(&text),
+    }
+}
+
+/// Sets up the logger. Records logged before this are dropped.
+pub(crate) fn init() {
+    if log::set_logger(&LOGGER).is_ok() {
+        log::set_max_level(MAX_LEVEL);
+    }
+}
+
+/// Copies out the remembered records, oldest first, but only if they
+/// changed since `version`, along with the version they're at.
+pub(crate) fn history_since(version: Option<u64>) -> Option<(u64, Vec<LogEntry>)> {
+    let history = LOGGER.history.lock().ok()?;
+    let current = history.version();
+    (version != Some(current)).then(|| (current, history.to_vec()))
+}
+
+/// How much memory the remembered records take up.
+pub(crate) fn usage() -> HistoryUsage {
+    LOGGER
+        .history
+        .lock()
+        .map(|history| history.usage())
+        .unwrap_or_default()
+}
+
+pub(crate) fn set_byte_limit(max_bytes: usize) {
+    if let Ok(mut history) = LOGGER.history.lock() {
+        history.set_limits(HISTORY_LIMIT, max_bytes);
+    }
+}
+
+pub(crate) fn clear() {
+    if let Ok(mut history) = LOGGER.history.lock() {
+        history.clear();
+    }
+}
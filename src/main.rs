@@ -1,30 +1,61 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
 use three_d::{
     AmbientLight, Camera, ClearState, Context, Degrees, DirectionalLight, FrameInput, FrameOutput,
     GUI, InnerSpace, Srgba, Vec3, Viewport,
     window::{Window, WindowSettings},
 };
 
-use gui::SimState;
+use assets::preload::Preloader;
+use benchmark::Benchmark;
+use cfg::startup::StartupTracker;
+use gfx::{
+    adaptive_quality::AdaptiveQuality,
+    depth_range::{FIXED_Z_FAR, FIXED_Z_NEAR},
+    render_scale::ScaledTarget,
+    trajectory::TrajectoryMode,
+    trajectory_cache::TrajectoryCache,
+};
+use gui::{SimState, TabAction, quit::QuitState};
+use metrics::Feature;
+use sim::{
+    library::StartupUniverse,
+    startup_options::StartupOptions,
+    zoom_levels::{self, ZoomTarget},
+};
 
 use self::control::CameraControl;
 #[path = "assets/mod.rs"]
 pub mod assets;
+#[path = "benchmark.rs"]
+pub mod benchmark;
 #[path = "cfg/mod.rs"]
 pub mod cfg;
+#[path = "compute.rs"]
+pub mod compute;
 #[path = "control.rs"]
 pub mod control;
 #[path = "gfx/mod.rs"]
 pub mod gfx;
 #[path = "gui/mod.rs"]
 pub mod gui;
+#[path = "history.rs"]
+pub mod history;
 #[path = "keybinds.rs"]
 pub mod keybinds;
+#[path = "logging.rs"]
+pub mod logging;
+#[path = "metrics.rs"]
+pub mod metrics;
+#[path = "notify.rs"]
+pub mod notify;
 #[path = "sim/mod.rs"]
 pub mod sim;
 #[path = "units/mod.rs"]
 pub mod units;
 
-pub static mut HALT_FLAG: bool = false;
+/// Set to exit the program after the current frame.
+pub static HALT_FLAG: AtomicBool = AtomicBool::new(false);
 
 #[cfg(not(target_family = "wasm"))]
 fn main() {
@@ -37,17 +68,44 @@ fn main() {
     unsafe { std::hint::unreachable_unchecked() }
 }
 
+/// One open universe, shown as a tab.
+///
+/// Everything that isn't specific to a universe,
+/// like the GPU context and the GUI, is shared between sessions.
+pub(crate) struct Session {
+    pub(crate) sim_state: SimState,
+    pub(crate) camera: Camera,
+    pub(crate) control: CameraControl,
+    /// Ticks the universe on a worker thread, while "Background simulation" is on.
+    #[cfg(not(target_family = "wasm"))]
+    background: Option<sim::background::BackgroundSim>,
+}
+
 pub(crate) struct Program {
     window: Option<Window>,
     context: Context,
-    camera: Camera,
-    control: CameraControl,
     gui: GUI,
 
     top_light: DirectionalLight,
     ambient_light: AmbientLight,
 
-    sim_state: SimState,
+    sessions: Vec<Session>,
+    /// The index of the session being shown and simulated.
+    active_session: usize,
+    startup: StartupTracker,
+    /// The trajectory mode supported by this device.
+    trajectory_mode: TrajectoryMode,
+    /// Where the scene is rendered when the render scale isn't 100%,
+    /// kept between frames while the size stays the same.
+    scaled_target: Option<ScaledTarget>,
+    /// Lowers the graphics quality on the web while frames are slow.
+    adaptive_quality: AdaptiveQuality,
+    /// The orbit lines drawn last frame, reused for the next one.
+    trajectory_cache: TrajectoryCache,
+    /// Loads assets behind a splash screen, until it's done.
+    preloader: Option<Preloader>,
+    /// The benchmark being run, if one is.
+    benchmark: Option<Benchmark>,
 }
 
 impl Program {
@@ -63,7 +121,7 @@ impl Program {
                 if cfg!(target_family = "wasm") {
                     panic!("Error when creating window: {e}");
                 } else {
-                    println!("Error when creating window: {e}");
+                    log::error!("Error when creating window: {e}");
                     std::process::exit(1);
                 }
             }
@@ -75,9 +133,11 @@ impl Program {
             Vec3::new(6.2, 2.6, 4.2).normalize(),
             Vec3::new(0.0, 0.0, 0.0),
             Vec3::new(0.0, 0.0, 1.0),
-            Degrees { 0: 45.0 },
-            0.001,
-            5e12,
+            Degrees {
+                0: control::FIELD_OF_VIEW_Y_DEG,
+            },
+            FIXED_Z_NEAR,
+            FIXED_Z_FAR,
         )
     }
     fn new_control() -> CameraControl {
@@ -89,31 +149,333 @@ impl Program {
     fn new_ambient_light(context: &Context) -> AmbientLight {
         AmbientLight::new(&context, 0.02, Srgba::WHITE)
     }
-    fn generate_sim_state() -> SimState {
-        SimState::new(sim::create_universe())
+    fn generate_sim_state(safe_mode: bool, preset: Option<StartupUniverse>) -> SimState {
+        if safe_mode {
+            SimState::new_safe_mode()
+        } else {
+            let startup_universe = preset.unwrap_or_else(|| {
+                cfg::CONFIG
+                    .try_lock()
+                    .map(|cfg| StartupUniverse::from_index(cfg.startup_universe.get()))
+                    .unwrap_or_default()
+            });
+            SimState::new(startup_universe.create())
+        }
+    }
+    /// The options given in the page's URL on the web,
+    /// or on the command line natively.
+    /// Parameters that couldn't be understood are reported.
+    fn startup_options() -> StartupOptions {
+        #[cfg(target_family = "wasm")]
+        {
+            let (options, errors) = StartupOptions::parse(&crate::web::query::search());
+            for error in errors {
+                notify::notify(
+                    notify::Severity::Warning,
+                    format!("Ignored a URL parameter: {error}"),
+                );
+            }
+            options
+        }
+        #[cfg(not(target_family = "wasm"))]
+        {
+            let (options, errors) = StartupOptions::from_args(std::env::args().skip(1));
+            for error in errors {
+                notify::notify(
+                    notify::Severity::Warning,
+                    format!("Ignored a command line argument: {error}"),
+                );
+            }
+            options
+        }
+    }
+
+    fn new_session(sim_state: SimState, viewport: Viewport) -> Session {
+        Session {
+            sim_state,
+            camera: Self::new_camera(viewport),
+            control: Self::new_control(),
+            #[cfg(not(target_family = "wasm"))]
+            background: None,
+        }
     }
 
     pub(crate) fn new() -> Self {
+        cfg::migration::migrate();
+        history::apply_limits();
+        let startup = StartupTracker::begin();
+        let options = Self::startup_options();
         let window = Self::new_window();
         let context = window.gl();
-        let camera = Self::new_camera(window.viewport());
-        let control = Self::new_control();
         let gui = gui::create(&context);
 
         let top_light = Self::new_dir_light(&context);
         let ambient_light = Self::new_ambient_light(&context);
 
-        let sim_state = Self::generate_sim_state();
+        let mut sim_state = Self::generate_sim_state(startup.is_safe_mode(), options.preset);
+        if !startup.is_safe_mode() {
+            sim_state.apply_startup_options(&options);
+        }
+        let viewport = window.viewport();
+        let session = Self::new_session(sim_state, viewport);
+        let trajectory_mode = TrajectoryMode::detect(&context);
 
-        Self {
+        let mut program = Self {
             window: Some(window),
             context,
-            camera,
-            control,
             gui,
             top_light,
             ambient_light,
-            sim_state,
+            sessions: vec![session],
+            active_session: 0,
+            startup,
+            trajectory_mode,
+            scaled_target: None,
+            adaptive_quality: AdaptiveQuality::new(),
+            trajectory_cache: TrajectoryCache::default(),
+            preloader: Some(Preloader::start()),
+            benchmark: None,
+        };
+
+        if options.benchmark && !program.startup.is_safe_mode() {
+            // Natively, it's run from a terminal to get the report printed
+            program.start_benchmark(viewport, !cfg!(target_family = "wasm"));
+        }
+
+        program
+    }
+
+    /// The session being shown.
+    #[inline]
+    pub(crate) fn session(&self) -> &Session {
+        &self.sessions[self.active_session]
+    }
+
+    fn tab_titles(&self) -> Vec<String> {
+        self.sessions
+            .iter()
+            .enumerate()
+            .map(|(index, session)| {
+                let name = &session.sim_state.universe.metadata.name;
+                if name.trim().is_empty() {
+                    format!("Universe {}", index + 1)
+                } else {
+                    name.clone()
+                }
+            })
+            .collect()
+    }
+
+    fn handle_tab_action(&mut self, action: TabAction, viewport: Viewport) {
+        match action {
+            TabAction::Switch(index) => {
+                if index < self.sessions.len() {
+                    self.active_session = index;
+                }
+            }
+            TabAction::New => {
+                metrics::record_use(Feature::NewTab);
+                let mut sim_state = Self::generate_sim_state(self.startup.is_safe_mode(), None);
+                sim_state.close_startup_windows();
+                self.sessions.push(Self::new_session(sim_state, viewport));
+                self.active_session = self.sessions.len() - 1;
+            }
+            TabAction::Close(index) => {
+                if self.sessions.len() <= 1 || index >= self.sessions.len() {
+                    return;
+                }
+                self.sessions.remove(index);
+                if index < self.active_session || self.active_session >= self.sessions.len() {
+                    self.active_session -= 1;
+                }
+            }
+        }
+    }
+
+    /// Opens the benchmark's scene in a new tab and starts playing it.
+    fn start_benchmark(&mut self, viewport: Viewport, exit_when_done: bool) {
+        if self.benchmark.is_some() {
+            return;
+        }
+        log::info!("Starting the benchmark");
+
+        let mut sim_state = SimState::new(benchmark::scene());
+        sim_state.close_startup_windows();
+        sim_state.sim_speed = benchmark::SIM_SPEED;
+        sim_state.running = true;
+        self.sessions.push(Self::new_session(sim_state, viewport));
+        self.active_session = self.sessions.len() - 1;
+        self.benchmark = Some(Benchmark::new(self.active_session, exit_when_done));
+    }
+
+    /// Reports the benchmark's results once it's done,
+    /// or stops it if its tab isn't the one being shown anymore.
+    fn update_benchmark(&mut self, viewport: Viewport, pixel_ratio: f32) {
+        let Some(benchmark) = &self.benchmark else {
+            return;
+        };
+        if benchmark.session != self.active_session {
+            self.benchmark = None;
+            notify::notify(
+                notify::Severity::Warning,
+                "The benchmark was stopped, since its tab was switched away from or closed.",
+            );
+            return;
+        }
+        if !benchmark.is_done() {
+            return;
+        }
+
+        let session = &mut self.sessions[self.active_session];
+        let report = benchmark.report(&benchmark::Setup {
+            viewport: (viewport.width, viewport.height),
+            pixel_ratio,
+            orbit_lines: match self.trajectory_mode {
+                TrajectoryMode::Shader => "shader",
+                TrajectoryMode::Polyline => "polyline",
+            },
+            body_count: session.sim_state.universe.get_bodies().len(),
+        });
+        log::info!("Benchmark done:\n{report}");
+
+        if benchmark.exit_when_done {
+            println!("{report}");
+            Self::quit();
+        } else {
+            session.sim_state.ui.benchmark_report = Some(report);
+        }
+        self.benchmark = None;
+    }
+
+    /// Quits once asked to, checking first whether
+    /// any tab has edits that would be lost.
+    fn handle_quit(&mut self, quit: QuitState) {
+        match quit {
+            QuitState::Requested => {
+                let edited_tabs: Vec<String> = self
+                    .tab_titles()
+                    .into_iter()
+                    .zip(&self.sessions)
+                    .filter(|(_, session)| session.sim_state.is_edited())
+                    .map(|(title, _)| title)
+                    .collect();
+                if edited_tabs.is_empty() {
+                    Self::quit();
+                } else {
+                    self.sessions[self.active_session].sim_state.ui.quit =
+                        QuitState::Confirming(edited_tabs);
+                }
+            }
+            QuitState::Confirmed => Self::quit(),
+            // Kept until the user picks an answer
+            quit => self.sessions[self.active_session].sim_state.ui.quit = quit,
+        }
+    }
+
+    fn quit() {
+        StartupTracker::end_cleanly();
+        metrics::flush();
+        HALT_FLAG.store(true, Ordering::Relaxed);
+    }
+
+    /// Advances the active session's universe by a frame's worth of time,
+    /// on the worker thread if "Background simulation" is on.
+    fn tick_universe(session: &mut Session, elapsed_ms: f64) {
+        let sim_state = &mut session.sim_state;
+        let dt = if sim_state.running {
+            sim_state.sim_speed * elapsed_ms / 1000.0
+        } else {
+            0.0
+        };
+
+        #[cfg(not(target_family = "wasm"))]
+        {
+            let enabled = cfg::CONFIG
+                .try_lock()
+                .map(|cfg| cfg.background_simulation.get())
+                .unwrap_or(false);
+            if !enabled {
+                session.background = None;
+            } else if session.background.is_none() {
+                session.background = sim::background::BackgroundSim::spawn();
+            }
+
+            if let Some(background) = &mut session.background {
+                let running = background.step(
+                    &mut sim_state.universe,
+                    &mut sim_state.position_cache,
+                    dt,
+                    sim_state.mu_setter_mode,
+                );
+                if running {
+                    return;
+                }
+                log::error!(
+                    "The background simulation thread stopped, ticking on this one instead"
+                );
+                session.background = None;
+                if let Ok(cfg) = cfg::CONFIG.try_lock() {
+                    let _ = cfg.background_simulation.set(false);
+                }
+            }
+        }
+
+        if sim_state.running {
+            sim_state.universe.tick(dt);
+        }
+        sim::constraint::solve(&mut sim_state.universe, sim_state.mu_setter_mode);
+    }
+
+    /// Turns the camera with the focused body's orbit while it has
+    /// a rendezvous target, and towards the target when asked to.
+    fn update_rendezvous_camera(session: &mut Session) {
+        let sim_state = &mut session.sim_state;
+        let focused = sim_state.focused_body();
+        let target = sim_state
+            .rendezvous_target
+            .filter(|&target| target != focused && sim_state.universe.get_body(target).is_some());
+        let frame = target.and_then(|_| sim::rendezvous::local_frame(&sim_state.universe, focused));
+
+        session.control.follow_frame(&mut session.camera, frame);
+
+        if std::mem::take(&mut sim_state.ui.rendezvous_aim_requested)
+            && let Some(target) = target
+            && let Some(direction) =
+                sim::rendezvous::direction_to(&sim_state.universe, focused, target)
+        {
+            let up = frame.map(|frame| frame.z_axis).unwrap_or(glam::DVec3::Z);
+            session
+                .control
+                .look_along(&mut session.camera, direction, up);
+        }
+    }
+
+    /// Draws the splash screen instead of the simulator, while preloading.
+    fn show_splash(&mut self, mut frame_input: FrameInput) -> FrameOutput {
+        let Some(preloader) = &self.preloader else {
+            return FrameOutput::default();
+        };
+        let pixels_per_point = frame_input.device_pixel_ratio * gui::ui_scale::ui_scale();
+
+        gui::splash::update(
+            &mut self.gui,
+            &mut frame_input.events,
+            frame_input.accumulated_time,
+            frame_input.viewport,
+            pixels_per_point,
+            preloader.progress(),
+            preloader.current_step(),
+        );
+
+        frame_input
+            .screen()
+            .clear(ClearState::color(0.0, 0.0, 0.0, 1.0))
+            .write(|| self.gui.render())
+            .unwrap();
+
+        FrameOutput {
+            exit: HALT_FLAG.load(Ordering::Relaxed),
+            ..Default::default()
         }
     }
 
@@ -126,60 +488,143 @@ impl Program {
     fn tick(&mut self, mut frame_input: FrameInput) -> FrameOutput {
         #[cfg(all(target_family = "wasm", not(feature = "is-bin")))]
         crate::web::heartbeat::update_frame_time();
+        // Changing the quality partway through would skew the benchmark
+        if self.benchmark.is_none() {
+            self.adaptive_quality.record_frame(frame_input.elapsed_time);
+        }
+        self.startup.tick(frame_input.elapsed_time);
 
-        if self.sim_state.running {
-            self.sim_state
-                .universe
-                .tick(self.sim_state.sim_speed * frame_input.elapsed_time / 1000.0);
+        if let Some(preloader) = &mut self.preloader {
+            if preloader.step(self.gui.context()) {
+                self.preloader = None;
+            } else {
+                return self.show_splash(frame_input);
+            }
         }
-        self.sim_state.focus_offset *= (-0.025 * frame_input.elapsed_time).exp();
-        let position_map = self.sim_state.universe.get_all_body_positions();
 
+        let simulation_start = benchmark::now_ms();
+        // The benchmark simulates the same time every frame, so every run does the same work
+        let sim_elapsed_time = match self.benchmark {
+            Some(_) => benchmark::FRAME_STEP_MS,
+            None => frame_input.elapsed_time,
+        };
+
+        // Only the active session is simulated;
+        // universes in other tabs are paused until switched back to.
+        let session = &mut self.sessions[self.active_session];
+        let sim_state = &mut session.sim_state;
+
+        sim_state.update_warp(frame_input.elapsed_time);
+        sim_state.limit_sim_speed();
+        Self::tick_universe(session, sim_elapsed_time);
+        let sim_state = &mut session.sim_state;
+        sim_state.focus_offset *= (-0.025 * frame_input.elapsed_time).exp();
+        sim_state.update_reparent_fade(frame_input.elapsed_time);
+        let position_map = sim_state.position_cache.positions(&sim_state.universe);
+        let simulation_ms = benchmark::now_ms() - simulation_start;
+
+        session.camera.set_viewport(frame_input.viewport);
+        // The GUI is scaled on top of the screen's pixel density,
+        // so everything drawn in its points has to be too
+        let pixels_per_point = frame_input.device_pixel_ratio * gui::ui_scale::ui_scale();
+        let projector = self.world_projector(&position_map, pixels_per_point);
+        let tab_titles = self.tab_titles();
+
+        let session = &mut self.sessions[self.active_session];
+
+        let interface_start = benchmark::now_ms();
         gui::update(
             &mut self.gui,
-            &mut self.sim_state,
+            &mut session.sim_state,
+            &tab_titles,
+            self.active_session,
             &mut frame_input.events,
             frame_input.accumulated_time,
             frame_input.viewport,
-            frame_input.device_pixel_ratio,
+            pixels_per_point,
             frame_input.elapsed_time,
             &position_map,
+            &projector,
         );
+        let interface_ms = benchmark::now_ms() - interface_start;
 
-        self.camera.set_viewport(frame_input.viewport);
-        self.control.min_distance = self
+        session.control.min_distance = session
             .sim_state
             .universe
-            .get_body(self.sim_state.focused_body())
+            .get_body(session.sim_state.focused_body())
             .map(|wrapper| 1.5 * wrapper.body.radius)
             .unwrap_or(1e-3);
-        self.control.max_distance = self.control.min_distance * 1e16;
-        self.control.handle_events(
-            &mut self.camera,
+        session.control.max_distance = session.control.min_distance * 1e16;
+        if let Some(benchmark) = &self.benchmark {
+            benchmark.drive_camera(&mut session.control, &mut session.camera);
+        }
+        session.control.handle_events(
+            &mut session.camera,
             &mut frame_input.events,
             frame_input.elapsed_time,
         );
+        Self::update_rendezvous_camera(session);
 
-        keybinds::handle_keybinds(&mut self.sim_state, &mut frame_input.events, &self.gui);
+        keybinds::handle_keybinds(&mut session.sim_state, &mut frame_input.events, &self.gui);
 
-        frame_input
-            .screen()
-            .clear(ClearState::color_and_depth(0.0, 0.0, 0.0, 1.0, 100000.0))
-            .render(
-                &self.camera,
-                &self.to_objects(&position_map),
-                &[&self.top_light, &self.ambient_light],
-            )
-            .write(|| self.gui.render())
-            .unwrap();
+        if std::mem::take(&mut session.sim_state.ui.frame_orbit_requested)
+            && let Some(corners) = session.sim_state.focused_orbit_corners()
+        {
+            session.control.frame(&session.camera, &corners);
+        }
+
+        let zoom_target = session
+            .sim_state
+            .ui
+            .zoom_level_requested
+            .take()
+            .and_then(|level| {
+                let focused = session.sim_state.focused_body();
+                zoom_levels::zoom_target(&session.sim_state.universe, focused, level)
+            });
+        match zoom_target {
+            Some(ZoomTarget::Distance(distance)) => session.control.zoom_to_distance(distance),
+            Some(ZoomTarget::Sphere(radius)) => {
+                session.control.frame_sphere(&session.camera, radius)
+            }
+            None => (),
+        }
+
+        let tab_action = session.sim_state.ui.tab_action.take();
+        let quit = std::mem::take(&mut session.sim_state.ui.quit);
+        let benchmark_requested = std::mem::take(&mut session.sim_state.ui.benchmark_requested);
+
+        let rendering_start = benchmark::now_ms();
+        self.update_depth_range(&position_map);
+        self.render_scene(&frame_input, &position_map);
+        frame_input.screen().write(|| self.gui.render()).unwrap();
+        let rendering_ms = benchmark::now_ms() - rendering_start;
+
+        if let Some(benchmark) = &mut self.benchmark {
+            benchmark.record_frame(
+                frame_input.elapsed_time,
+                [simulation_ms, interface_ms, rendering_ms],
+            );
+        }
+
+        if let Some(action) = tab_action {
+            self.handle_tab_action(action, frame_input.viewport);
+        }
+        if benchmark_requested {
+            self.start_benchmark(frame_input.viewport, false);
+        }
+        self.update_benchmark(frame_input.viewport, frame_input.device_pixel_ratio);
+        self.handle_quit(quit);
 
         FrameOutput {
-            exit: unsafe { HALT_FLAG },
+            exit: HALT_FLAG.load(Ordering::Relaxed),
             ..Default::default()
         }
     }
 }
 
 pub fn run() {
+    logging::init();
+    gui::id_registry::check();
     Program::new().run();
 }
@@ -0,0 +1,292 @@
+//! Opt-in usage and performance statistics, kept only on this device.
+//!
+//! Nothing here is ever sent anywhere. The numbers are saved with the
+//! rest of the settings and shown in the Diagnostics window,
+//! so they can be copied into a bug report by hand.
+
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use strum::{EnumCount, IntoEnumIterator};
+use strum_macros::{EnumCount, EnumIter};
+
+use crate::cfg::CONFIG;
+
+/// How much recorded time passes between saves, in milliseconds.
+///
+/// Saving rewrites the whole config, so it isn't done every frame.
+/// Up to this much is lost if the program is closed.
+const SAVE_INTERVAL_MS: f64 = 30_000.0;
+
+/// The upper ends of the frame time histogram's buckets, in milliseconds.
+/// The last bucket holds everything slower.
+pub(crate) const FRAME_TIME_BUCKETS_MS: [f64; 5] =
+    [1000.0 / 120.0, 1000.0 / 60.0, 1000.0 / 30.0, 50.0, 100.0];
+
+/// Actions whose uses are counted.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, EnumCount, EnumIter)]
+pub(crate) enum Feature {
+    AddBody,
+    DeleteBody,
+    PasteBodies,
+    PasteElements,
+    FitOrbit,
+    NewTab,
+    GenerateSystem,
+}
+
+impl Feature {
+    pub(crate) const fn name(self) -> &'static str {
+        match self {
+            Feature::AddBody => "Bodies added",
+            Feature::DeleteBody => "Bodies deleted",
+            Feature::PasteBodies => "Bodies pasted",
+            Feature::PasteElements => "Elements pasted",
+            Feature::FitOrbit => "Orbits fitted",
+            Feature::NewTab => "Tabs opened",
+            Feature::GenerateSystem => "Systems generated",
+        }
+    }
+
+    fn index(self) -> usize {
+        Self::iter().position(|f| f == self).unwrap_or(0)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct Metrics {
+    pub(crate) frames: u64,
+    /// The sum of all frame times, in milliseconds.
+    pub(crate) total_frame_time_ms: f64,
+    pub(crate) slowest_frame_ms: f64,
+    /// How many frames fell into each of `FRAME_TIME_BUCKETS_MS`,
+    /// plus one for the frames slower than all of them.
+    pub(crate) frame_time_histogram: [u64; FRAME_TIME_BUCKETS_MS.len() + 1],
+    /// The most bodies a universe had while recording.
+    pub(crate) max_body_count: u32,
+    /// How many times each `Feature` was used.
+    pub(crate) feature_uses: [u32; Feature::COUNT],
+    /// The most orbit line points generated for a single frame.
+    #[serde(default)]
+    pub(crate) max_trajectory_points: u32,
+    /// How many frames had their orbit lines simplified
+    /// to fit the trajectory point budget.
+    #[serde(default)]
+    pub(crate) point_budget_frames: u64,
+}
+
+impl Metrics {
+    pub(crate) const fn new() -> Self {
+        Self {
+            frames: 0,
+            total_frame_time_ms: 0.0,
+            slowest_frame_ms: 0.0,
+            frame_time_histogram: [0; FRAME_TIME_BUCKETS_MS.len() + 1],
+            max_body_count: 0,
+            feature_uses: [0; Feature::COUNT],
+            max_trajectory_points: 0,
+            point_budget_frames: 0,
+        }
+    }
+
+    /// Returns NaN if no frames were recorded yet.
+    pub(crate) fn average_frame_time_ms(&self) -> f64 {
+        self.total_frame_time_ms / self.frames as f64
+    }
+
+    pub(crate) fn uses(&self, feature: Feature) -> u32 {
+        self.feature_uses[feature.index()]
+    }
+
+    /// Writes the statistics as plain text, for pasting into a bug report.
+    pub(crate) fn to_report(&self) -> String {
+        let mut report = format!(
+            "keplerian_sim_demo local diagnostics\n\
+            Frames: {}\n\
+            Average frame time: {:.2} ms\n\
+            Slowest frame: {:.1} ms\n\
+            Most bodies: {}\n\
+            Most orbit line points: {}\n\
+            Frames over the point budget: {}\n",
+            self.frames,
+            self.average_frame_time_ms(),
+            self.slowest_frame_ms,
+            self.max_body_count,
+            self.max_trajectory_points,
+            self.point_budget_frames,
+        );
+
+        for (label, count) in histogram_labels().iter().zip(self.frame_time_histogram) {
+            report += &format!("Frames {label}: {count}\n");
+        }
+        for feature in Feature::iter() {
+            report += &format!("{}: {}\n", feature.name(), self.uses(feature));
+        }
+
+        report
+    }
+}
+
+/// Describes each bucket of the frame time histogram, e.g. "8.3–16.7 ms".
+pub(crate) fn histogram_labels() -> [String; FRAME_TIME_BUCKETS_MS.len() + 1] {
+    std::array::from_fn(|i| match i {
+        0 => format!("under {:.1} ms", FRAME_TIME_BUCKETS_MS[0]),
+        i if i == FRAME_TIME_BUCKETS_MS.len() => {
+            format!("over {:.0} ms", FRAME_TIME_BUCKETS_MS[i - 1])
+        }
+        i => format!(
+            "{:.1}–{:.1} ms",
+            FRAME_TIME_BUCKETS_MS[i - 1],
+            FRAME_TIME_BUCKETS_MS[i]
+        ),
+    })
+}
+
+struct Recorder {
+    /// Loaded from the config on first use.
+    metrics: Option<Metrics>,
+    /// Recorded time since the metrics were last saved, in milliseconds.
+    unsaved_ms: f64,
+    dirty: bool,
+}
+
+impl Recorder {
+    const fn new() -> Self {
+        Self {
+            metrics: None,
+            unsaved_ms: 0.0,
+            dirty: false,
+        }
+    }
+
+    /// Gets the metrics if recording is enabled.
+    fn metrics(&mut self) -> Option<&mut Metrics> {
+        let cfg = CONFIG.try_lock().ok()?;
+        if !cfg.metrics_enabled.get() {
+            return None;
+        }
+
+        Some(self.metrics.get_or_insert_with(|| cfg.metrics.get()))
+    }
+
+    fn save(&mut self) {
+        self.unsaved_ms = 0.0;
+        if !self.dirty {
+            return;
+        }
+
+        if let (Some(metrics), Ok(cfg)) = (self.metrics, CONFIG.try_lock()) {
+            let _ = cfg.metrics.set(metrics);
+            self.dirty = false;
+        }
+    }
+}
+
+static RECORDER: Mutex<Recorder> = Mutex::new(Recorder::new());
+
+/// Records how long a frame took and how many bodies there were,
+/// if recording is enabled.
+pub(crate) fn record_frame(frame_time_ms: f64, body_count: usize) {
+    if !frame_time_ms.is_finite() || frame_time_ms < 0.0 {
+        return;
+    }
+
+    let Ok(mut recorder) = RECORDER.try_lock() else {
+        return;
+    };
+    let Some(metrics) = recorder.metrics() else {
+        return;
+    };
+
+    metrics.frames += 1;
+    metrics.total_frame_time_ms += frame_time_ms;
+    metrics.slowest_frame_ms = metrics.slowest_frame_ms.max(frame_time_ms);
+    let bucket = FRAME_TIME_BUCKETS_MS
+        .iter()
+        .position(|&max| frame_time_ms < max)
+        .unwrap_or(FRAME_TIME_BUCKETS_MS.len());
+    metrics.frame_time_histogram[bucket] += 1;
+    metrics.max_body_count = metrics
+        .max_body_count
+        .max(body_count.try_into().unwrap_or(u32::MAX));
+
+    recorder.dirty = true;
+    recorder.unsaved_ms += frame_time_ms;
+    if recorder.unsaved_ms >= SAVE_INTERVAL_MS {
+        recorder.save();
+    }
+}
+
+/// Records how many orbit line points a frame generated, and whether
+/// they had to be cut down to fit the budget, if recording is enabled.
+pub(crate) fn record_trajectory_points(points: u32, limited: bool) {
+    let Ok(mut recorder) = RECORDER.try_lock() else {
+        return;
+    };
+    let Some(metrics) = recorder.metrics() else {
+        return;
+    };
+
+    metrics.max_trajectory_points = metrics.max_trajectory_points.max(points);
+    if limited {
+        metrics.point_budget_frames += 1;
+    }
+    recorder.dirty = true;
+}
+
+/// Counts a use of a feature, if recording is enabled.
+pub(crate) fn record_use(feature: Feature) {
+    let Ok(mut recorder) = RECORDER.try_lock() else {
+        return;
+    };
+    let Some(metrics) = recorder.metrics() else {
+        return;
+    };
+
+    let uses = &mut metrics.feature_uses[feature.index()];
+    *uses = uses.saturating_add(1);
+    recorder.dirty = true;
+}
+
+/// Gets what was recorded so far, including what hasn't been saved yet.
+pub(crate) fn snapshot() -> Metrics {
+    if let Ok(recorder) = RECORDER.try_lock()
+        && let Some(metrics) = recorder.metrics
+    {
+        return metrics;
+    }
+
+    CONFIG
+        .try_lock()
+        .map(|cfg| cfg.metrics.get())
+        .unwrap_or(Metrics::new())
+}
+
+/// Saves what was recorded since the last save, e.g. before quitting.
+pub(crate) fn flush() {
+    if let Ok(mut recorder) = RECORDER.try_lock() {
+        recorder.save();
+    }
+}
+
+/// Turns recording on or off, saving what was recorded so far.
+pub(crate) fn set_enabled(enabled: bool) {
+    if let Ok(mut recorder) = RECORDER.try_lock() {
+        recorder.save();
+    }
+    if let Ok(cfg) = CONFIG.try_lock() {
+        let _ = cfg.metrics_enabled.set(enabled);
+    }
+}
+
+/// Forgets everything that was recorded.
+pub(crate) fn clear() {
+    if let Ok(mut recorder) = RECORDER.try_lock() {
+        recorder.metrics = Some(Metrics::new());
+        recorder.dirty = false;
+        recorder.unsaved_ms = 0.0;
+    }
+    if let Ok(cfg) = CONFIG.try_lock() {
+        let _ = cfg.metrics.set(Metrics::new());
+    }
+}
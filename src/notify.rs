@@ -0,0 +1,166 @@
+//! Messages about things that went wrong without stopping the simulator,
+//! like a failed clipboard copy.
+//!
+//! Anything can post one with [`notify`]. The GUI shows new ones as toasts
+//! and keeps the rest in the event log.
+
+use std::{collections::VecDeque, sync::Mutex};
+
+use crate::history::{self, History, HistoryUsage};
+
+/// How many notifications the event log remembers.
+const HISTORY_LIMIT: usize = 200;
+
+/// How many toasts wait their turn before the oldest are dropped,
+/// in case the GUI isn't drawing them.
+const TOAST_LIMIT: usize = 32;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Severity {
+    Info,
+    Warning,
+    Error,
+}
+
+impl Severity {
+    const ALL: [Severity; 3] = [Severity::Info, Severity::Warning, Severity::Error];
+
+    pub(crate) const fn icon(self) -> &'static str {
+        match self {
+            Self::Info => "ℹ",
+            Self::Warning => "⚠",
+            Self::Error => "✖",
+        }
+    }
+
+    pub(crate) const fn log_level(self) -> log::Level {
+        match self {
+            Self::Info => log::Level::Info,
+            Self::Warning => log::Level::Warn,
+            Self::Error => log::Level::Error,
+        }
+    }
+
+    /// How long the toast stays up, in seconds.
+    pub(crate) const fn toast_duration(self) -> f64 {
+        match self {
+            Self::Info => 4.0,
+            Self::Warning => 8.0,
+            Self::Error => 12.0,
+        }
+    }
+}
+
+/// A notification as remembered in the event log.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct LoggedNotification {
+    pub(crate) severity: Severity,
+    pub(crate) message: String,
+}
+
+impl history::Record for LoggedNotification {
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.push(self.severity as u8);
+        out.extend_from_slice(self.message.as_bytes());
+    }
+
+    fn decode(bytes: &[u8]) -> Option<Self> {
+        let (&severity, message) = bytes.split_first()?;
+        Some(Self {
+            severity: *Severity::ALL.get(severity as usize)?,
+            message: String::from_utf8_lossy(message).into_owned(),
+        })
+    }
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct Notification {
+    pub(crate) severity: Severity,
+    pub(crate) message: String,
+    /// When the toast was first drawn, in egui's input time.
+    /// `None` until the GUI gets to it.
+    pub(crate) shown_at: Option<f64>,
+    /// Whether the toast was closed or has expired.
+    /// It is still kept in the event log.
+    pub(crate) dismissed: bool,
+}
+
+pub(crate) struct Notifier {
+    history: History<LoggedNotification>,
+    toasts: VecDeque<Notification>,
+}
+
+impl Notifier {
+    const fn new() -> Self {
+        Self {
+            history: History::new(
+                HISTORY_LIMIT,
+                history::DEFAULT_EVENT_LOG_LIMIT_KIB as usize * 1024,
+            ),
+            toasts: VecDeque::new(),
+        }
+    }
+
+    fn push(&mut self, severity: Severity, message: String) {
+        self.history.push(&LoggedNotification {
+            severity,
+            message: message.clone(),
+        });
+
+        if self.toasts.len() >= TOAST_LIMIT {
+            self.toasts.pop_front();
+        }
+        self.toasts.push_back(Notification {
+            severity,
+            message,
+            shown_at: None,
+            dismissed: false,
+        });
+    }
+
+    /// Every remembered notification, oldest first.
+    pub(crate) fn history(&self) -> Vec<LoggedNotification> {
+        self.history.to_vec()
+    }
+
+    pub(crate) fn is_history_empty(&self) -> bool {
+        self.history.is_empty()
+    }
+
+    /// How much memory the event log takes up.
+    pub(crate) fn usage(&self) -> HistoryUsage {
+        self.history.usage()
+    }
+
+    /// The notifications that should still be shown as toasts, oldest first.
+    pub(crate) fn toasts_mut(&mut self) -> impl Iterator<Item = &mut Notification> {
+        self.toasts.retain(|n| !n.dismissed);
+        self.toasts.iter_mut()
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.history.clear();
+        self.toasts.clear();
+    }
+}
+
+pub(crate) static NOTIFIER: Mutex<Notifier> = Mutex::new(Notifier::new());
+
+/// Posts a notification.
+///
+/// It is also logged, in case it happens before the GUI is up.
+pub(crate) fn notify(severity: Severity, message: impl Into<String>) {
+    let message = message.into();
+
+    log::log!(severity.log_level(), "{message}");
+
+    if let Ok(mut notifier) = NOTIFIER.lock() {
+        notifier.push(severity, message);
+    }
+}
+
+pub(crate) fn set_byte_limit(max_bytes: usize) {
+    if let Ok(mut notifier) = NOTIFIER.lock() {
+        notifier.history.set_limits(HISTORY_LIMIT, max_bytes);
+    }
+}
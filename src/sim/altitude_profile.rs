@@ -0,0 +1,121 @@
+//! How far a body gets from its parent over time, sampled for plotting.
+
+use core::f64::consts::TAU;
+
+use keplerian_sim::{Orbit, OrbitTrait};
+
+/// How far the profile goes on either side of periapsis on open orbits,
+/// in radians of hyperbolic mean anomaly. They never come back,
+/// so a fixed span around the closest approach is shown.
+const OPEN_MEAN_ANOMALY_SPAN: f64 = 6.0;
+
+/// Distances from the parent's center at evenly spaced times.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct AltitudeProfile {
+    /// Starting at periapsis on closed orbits,
+    /// and the start of the span on open ones.
+    pub altitudes: Vec<f64>,
+    /// How long the profile covers, in seconds.
+    /// One period on closed orbits.
+    pub duration: f64,
+    /// Where the body is now, from 0 at the start of the profile
+    /// to 1 at the end, if it's within it.
+    pub now: Option<f64>,
+}
+
+impl AltitudeProfile {
+    /// Samples `orbit` at `sample_count` times, with the body at `time` marked.
+    pub(crate) fn sample(orbit: &Orbit, time: f64, sample_count: usize) -> Self {
+        let (start, span) = if orbit.is_closed() {
+            (0.0, TAU)
+        } else {
+            (-OPEN_MEAN_ANOMALY_SPAN, 2.0 * OPEN_MEAN_ANOMALY_SPAN)
+        };
+        let last = sample_count.saturating_sub(1).max(1) as f64;
+
+        let altitudes = (0..sample_count)
+            .map(|i| {
+                let mean_anomaly = start + span * i as f64 / last;
+                let eccentric_anomaly = orbit.get_eccentric_anomaly_at_mean_anomaly(mean_anomaly);
+                let true_anomaly = orbit.get_true_anomaly_at_eccentric_anomaly(eccentric_anomaly);
+                orbit.get_altitude_at_true_anomaly(true_anomaly)
+            })
+            .collect();
+
+        let mean_anomaly = orbit.get_mean_anomaly_at_time(time);
+        let mean_anomaly = if orbit.is_closed() {
+            mean_anomaly.rem_euclid(TAU)
+        } else {
+            mean_anomaly
+        };
+        let now = Some((mean_anomaly - start) / span).filter(|now| (0.0..=1.0).contains(now));
+
+        Self {
+            altitudes,
+            duration: span / orbit.get_mean_motion(),
+            now,
+        }
+    }
+
+    /// The lowest and highest altitudes in the profile,
+    /// or None if it has no finite altitudes.
+    pub(crate) fn range(&self) -> Option<(f64, f64)> {
+        self.altitudes
+            .iter()
+            .copied()
+            .filter(|altitude| altitude.is_finite())
+            .fold(None, |range, altitude| match range {
+                None => Some((altitude, altitude)),
+                Some((min, max)) => Some((min.min(altitude), max.max(altitude))),
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MU: f64 = 3.986e14;
+
+    fn orbit(eccentricity: f64) -> Orbit {
+        Orbit::new(eccentricity, 7e6, 0.3, 0.5, 1.0, 0.0, MU)
+    }
+
+    fn assert_close(a: f64, b: f64) {
+        assert!((a - b).abs() <= 1e-6 * b.abs(), "{a} != {b}");
+    }
+
+    #[test]
+    fn circular_orbits_are_flat() {
+        let profile = AltitudeProfile::sample(&orbit(0.0), 0.0, 32);
+        let (min, max) = profile.range().unwrap();
+        assert_close(min, 7e6);
+        assert_close(max, 7e6);
+    }
+
+    #[test]
+    fn closed_orbits_go_from_periapsis_to_apoapsis_and_back() {
+        let orbit = orbit(0.5);
+        let profile = AltitudeProfile::sample(&orbit, 0.0, 33);
+
+        assert_close(profile.altitudes[0], orbit.get_periapsis());
+        assert_close(profile.altitudes[16], orbit.get_apoapsis());
+        assert_close(profile.altitudes[32], orbit.get_periapsis());
+        assert_close(profile.duration, orbit.get_orbital_period());
+        assert_eq!(profile.now, Some(0.0));
+    }
+
+    #[test]
+    fn open_orbits_are_centered_on_periapsis() {
+        let orbit = orbit(2.0);
+        let profile = AltitudeProfile::sample(&orbit, 0.0, 33);
+
+        assert_close(profile.range().unwrap().0, orbit.get_periapsis());
+        assert_close(profile.altitudes[16], orbit.get_periapsis());
+        assert_close(profile.altitudes[0], profile.altitudes[32]);
+        assert_eq!(profile.now, Some(0.5));
+
+        let far_future = 1e3 * profile.duration;
+        assert_eq!(AltitudeProfile::sample(&orbit, far_future, 33).now, None);
+    }
+}
@@ -0,0 +1,340 @@
+//! Ticking the universe on a worker thread, on native builds.
+//!
+//! The worker keeps its own copy of the universe. Each frame, the GUI thread
+//! takes the last step the worker finished, with every body's position already
+//! worked out, and asks for the next one, which is computed while the frame is drawn.
+//! Only one step is asked for at a time. If the worker falls behind, the frames'
+//! time adds up and is asked for all at once when it's done, so steps can't queue up.
+//!
+//! The GUI keeps editing its own copy as usual. If it ends a frame different
+//! from the step it took, that frame is ticked on the GUI thread instead, along
+//! with the time the worker hadn't caught up on yet, and the edited universe is
+//! sent to the worker to carry on from. Steps the worker had already started
+//! from the old one are dropped. Windows only borrow bodies mutably when they
+//! actually change something, so an open Edit Body window doesn't count as an edit.
+
+use std::{
+    collections::HashMap,
+    sync::mpsc::{self, Receiver, Sender, TryRecvError},
+    thread,
+};
+
+use glam::DVec3;
+
+use crate::sim::{
+    constraint,
+    position_cache::PositionCache,
+    universe::{BulkMuSetterMode, Id, Universe},
+};
+
+enum Command {
+    /// Carry on from this universe instead.
+    Replace {
+        universe: Box<Universe>,
+        generation: u64,
+    },
+    Step {
+        dt: f64,
+        mu_mode: BulkMuSetterMode,
+    },
+}
+
+/// A finished step.
+struct Step {
+    universe: Universe,
+    positions: HashMap<Id, DVec3>,
+    /// Which replacement the step was computed from.
+    generation: u64,
+    /// How much time has been ticked since that replacement, in seconds.
+    elapsed: f64,
+}
+
+/// Everything about a universe that edits from the GUI can change.
+///
+/// The bodies are compared through their structure version, which changes
+/// whenever they're mutably accessed, and everything else through the edit
+/// count, so nothing has to be copied to tell whether the universe changed.
+#[derive(Clone, Copy, PartialEq)]
+struct Fingerprint {
+    time: u64,
+    structure_version: u64,
+    edit_count: u64,
+}
+
+impl Fingerprint {
+    fn of(universe: &Universe) -> Self {
+        Self {
+            time: universe.time.to_bits(),
+            structure_version: universe.structure_version(),
+            edit_count: universe.edit_count(),
+        }
+    }
+}
+
+/// A worker thread ticking a universe in the background.
+///
+/// Dropping it stops the worker.
+pub(crate) struct BackgroundSim {
+    commands: Sender<Command>,
+    steps: Receiver<Step>,
+    /// Bumped every time the worker is given a universe to carry on from.
+    generation: u64,
+    /// How much time has been asked for since the last replacement, in seconds,
+    /// including time that hasn't been sent to the worker yet.
+    requested: f64,
+    /// How much of the time asked for is waiting for the step
+    /// in flight to finish before it's sent, in seconds.
+    unsent: f64,
+    /// Whether the worker is working on a step.
+    in_flight: bool,
+    /// How much of that time the universe has been ticked by, in seconds.
+    taken_elapsed: f64,
+    /// The state of the universe as of the last step taken from the worker,
+    /// to tell whether the GUI has edited it since.
+    taken: Option<Fingerprint>,
+}
+
+impl BackgroundSim {
+    /// Starts a worker thread.
+    ///
+    /// Returns None if the thread couldn't be started.
+    pub(crate) fn spawn() -> Option<Self> {
+        let (commands, command_receiver) = mpsc::channel();
+        let (step_sender, steps) = mpsc::channel();
+
+        thread::Builder::new()
+            .name(String::from("universe"))
+            .spawn(move || run_worker(command_receiver, step_sender))
+            .map_err(|e| log::error!("Couldn't start the background simulation thread: {e}"))
+            .ok()?;
+
+        Some(Self {
+            commands,
+            steps,
+            generation: 0,
+            requested: 0.0,
+            unsent: 0.0,
+            in_flight: false,
+            taken_elapsed: 0.0,
+            taken: None,
+        })
+    }
+
+    /// Ticks `universe` by `dt` seconds and solves its constraints.
+    /// Call once per frame, before anything reads the universe or the positions.
+    ///
+    /// Usually, this takes the latest step the worker finished, along with the
+    /// positions it worked out, and asks for the next one, so the universe
+    /// trails a frame or so behind. If `universe` was edited since the last step
+    /// was taken, it's ticked right here instead, and handed over to the worker.
+    ///
+    /// Returns: Whether the worker is still running.
+    pub(crate) fn step(
+        &mut self,
+        universe: &mut Universe,
+        position_cache: &mut PositionCache,
+        dt: f64,
+        mu_mode: BulkMuSetterMode,
+    ) -> bool {
+        let fingerprint = Fingerprint::of(universe);
+        if self.taken != Some(fingerprint) {
+            // Catch up on what the worker hadn't finished, too
+            universe.tick(self.requested - self.taken_elapsed + dt);
+            constraint::solve(universe, mu_mode);
+
+            // A step still in flight is from the old universe,
+            // so it's dropped when it comes back
+            self.generation += 1;
+            self.requested = 0.0;
+            self.unsent = 0.0;
+            self.in_flight = false;
+            self.taken_elapsed = 0.0;
+            self.taken = Some(Fingerprint::of(universe));
+            let command = Command::Replace {
+                universe: Box::new(universe.clone()),
+                generation: self.generation,
+            };
+            return self.commands.send(command).is_ok();
+        }
+
+        let mut latest = None;
+        loop {
+            match self.steps.try_recv() {
+                Ok(step) if step.generation == self.generation => {
+                    latest = Some(step);
+                    self.in_flight = false;
+                }
+                Ok(_) => (),
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => return false,
+            }
+        }
+
+        if let Some(step) = latest {
+            *universe = step.universe;
+            position_cache.insert(universe, step.positions);
+            self.taken_elapsed = step.elapsed;
+            self.taken = Some(Fingerprint::of(universe));
+        }
+
+        self.requested += dt;
+        self.unsent += dt;
+        if self.in_flight {
+            return true;
+        }
+
+        let dt = std::mem::take(&mut self.unsent);
+        self.in_flight = true;
+        self.commands.send(Command::Step { dt, mu_mode }).is_ok()
+    }
+}
+
+fn run_worker(commands: Receiver<Command>, steps: Sender<Step>) {
+    let mut universe = Universe::default();
+    let mut generation = 0;
+    let mut elapsed = 0.0;
+    let mut positions = HashMap::new();
+
+    // Ends once the BackgroundSim is dropped
+    while let Ok(command) = commands.recv() {
+        match command {
+            Command::Replace {
+                universe: new_universe,
+                generation: new_generation,
+            } => {
+                universe = *new_universe;
+                generation = new_generation;
+                elapsed = 0.0;
+            }
+            Command::Step { dt, mu_mode } => {
+                universe.tick(dt);
+                constraint::solve(&mut universe, mu_mode);
+                elapsed += dt;
+                universe.fill_all_body_positions(&mut positions);
+
+                // The worker's copy carries on, so the GUI gets a copy of it.
+                // The positions aren't needed here anymore, so they're moved
+                let step = Step {
+                    universe: universe.clone(),
+                    positions: std::mem::take(&mut positions),
+                    generation,
+                    elapsed,
+                };
+                if steps.send(step).is_err() {
+                    return;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::sim::body::Body;
+
+    const MU_MODE: BulkMuSetterMode = BulkMuSetterMode::KeepElements;
+
+    /// Steps by nothing until a step from the worker is taken,
+    /// or gives up after a while.
+    fn wait_for_worker(
+        sim: &mut BackgroundSim,
+        universe: &mut Universe,
+        cache: &mut PositionCache,
+    ) {
+        let time = universe.time;
+        for _ in 0..1000 {
+            assert!(sim.step(universe, cache, 0.0, MU_MODE));
+            if universe.time != time {
+                return;
+            }
+            thread::sleep(Duration::from_millis(1));
+        }
+        panic!("the worker never finished a step");
+    }
+
+    #[test]
+    fn steps_come_back_with_positions() {
+        let mut universe = Universe::default();
+        let root = universe
+            .add_body(Body::new("Root".to_owned(), 1e24, 1e6, None), None)
+            .unwrap();
+        let mut cache = PositionCache::default();
+        let mut sim = BackgroundSim::spawn().unwrap();
+
+        // The first step hands the universe over, ticking it right away
+        sim.step(&mut universe, &mut cache, 10.0, MU_MODE);
+        assert_eq!(universe.time, 10.0);
+
+        sim.step(&mut universe, &mut cache, 5.0, MU_MODE);
+        wait_for_worker(&mut sim, &mut universe, &mut cache);
+
+        assert_eq!(universe.time, 15.0);
+        let positions = cache.positions(&universe);
+        assert_eq!(positions.get(&root), Some(&DVec3::ZERO));
+    }
+
+    #[test]
+    fn edits_are_kept_and_handed_over() {
+        let mut universe = Universe::default();
+        let mut cache = PositionCache::default();
+        let mut sim = BackgroundSim::spawn().unwrap();
+
+        sim.step(&mut universe, &mut cache, 10.0, MU_MODE);
+        sim.step(&mut universe, &mut cache, 5.0, MU_MODE);
+        wait_for_worker(&mut sim, &mut universe, &mut cache);
+
+        // Like warping and renaming from the GUI
+        universe.time = 1000.0;
+        universe.metadata.name = String::from("Edited");
+        universe.mark_edited();
+        sim.step(&mut universe, &mut cache, 10.0, MU_MODE);
+        assert_eq!(universe.time, 1010.0);
+
+        sim.step(&mut universe, &mut cache, 1.0, MU_MODE);
+        wait_for_worker(&mut sim, &mut universe, &mut cache);
+        assert_eq!(universe.time, 1011.0);
+        assert_eq!(universe.metadata.name, "Edited");
+    }
+
+    #[test]
+    fn edits_catch_up_on_unfinished_steps() {
+        let mut universe = Universe::default();
+        let mut cache = PositionCache::default();
+        let mut sim = BackgroundSim::spawn().unwrap();
+
+        sim.step(&mut universe, &mut cache, 10.0, MU_MODE);
+        // Asked for, but edited before it could be taken
+        sim.step(&mut universe, &mut cache, 5.0, MU_MODE);
+        universe.metadata.name = String::from("Edited");
+        universe.mark_edited();
+        sim.step(&mut universe, &mut cache, 1.0, MU_MODE);
+
+        assert_eq!(universe.time, 16.0);
+    }
+
+    #[test]
+    fn steps_asked_for_while_busy_are_merged() {
+        let mut universe = Universe::default();
+        let mut cache = PositionCache::default();
+        let mut sim = BackgroundSim::spawn().unwrap();
+
+        sim.step(&mut universe, &mut cache, 10.0, MU_MODE);
+        for _ in 0..100 {
+            sim.step(&mut universe, &mut cache, 1.0, MU_MODE);
+            assert!(sim.in_flight);
+        }
+
+        // Everything asked for arrives, in far fewer steps than frames
+        for _ in 0..1000 {
+            if universe.time == 110.0 {
+                return;
+            }
+            assert!(sim.step(&mut universe, &mut cache, 0.0, MU_MODE));
+            thread::sleep(Duration::from_millis(1));
+        }
+        panic!("the worker only caught up to {}", universe.time);
+    }
+}
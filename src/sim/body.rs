@@ -61,3 +61,36 @@ impl Default for Body {
         }
     }
 }
+
+/// A flat ring system around a body, like Saturn's.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Rings {
+    /// The distance from the body's center to the rings' inner edge, in meters.
+    pub inner_radius: f64,
+
+    /// The distance from the body's center to the rings' outer edge, in meters.
+    pub outer_radius: f64,
+
+    /// The inclination of the rings' plane to the ecliptic, in radians.
+    pub inclination: f64,
+
+    /// The longitude of the ascending node of the rings' plane, in radians.
+    pub long_asc_node: f64,
+
+    /// The color of the rings. The alpha channel is their opacity.
+    pub color: Srgba,
+}
+
+impl Rings {
+    /// Creates rings around a body of the given radius,
+    /// in the ecliptic plane.
+    pub fn around(radius: f64) -> Self {
+        Self {
+            inner_radius: radius * 1.5,
+            outer_radius: radius * 2.5,
+            inclination: 0.0,
+            long_asc_node: 0.0,
+            color: Srgba::new(210, 190, 150, 160),
+        }
+    }
+}
@@ -0,0 +1,611 @@
+//! Links between parameters of different bodies,
+//! such as keeping one moon's period twice another's.
+//!
+//! Each constraint drives one parameter (the target) from another (the source).
+//! Constraints are re-solved every frame, so editing a source
+//! moves its targets along with it.
+
+use core::f64::consts::TAU;
+use std::fmt::{self, Display};
+
+use keplerian_sim::OrbitTrait;
+use strum_macros::EnumIter;
+
+use crate::sim::universe::{BulkMuSetterMode, Id, Universe};
+
+/// Relative differences smaller than this aren't worth writing back,
+/// and would only cause churn from floating-point noise.
+const TOLERANCE: f64 = 1e-12;
+
+/// The highest eccentricity a constraint can give a closed orbit,
+/// so it doesn't turn into an open one partway through solving.
+const MAX_CLOSED_ECCENTRICITY: f64 = 1.0 - 1e-6;
+
+/// The lowest eccentricity a constraint can give an open orbit.
+const MIN_OPEN_ECCENTRICITY: f64 = 1.0 + 1e-6;
+
+/// A parameter of a body that can be linked.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, EnumIter)]
+pub enum Parameter {
+    Mass,
+    Radius,
+    Periapsis,
+    Eccentricity,
+    Inclination,
+    ArgPe,
+    LongAscNode,
+    MeanAnomaly,
+    Period,
+}
+
+impl Parameter {
+    pub const fn name(self) -> &'static str {
+        match self {
+            Parameter::Mass => "mass",
+            Parameter::Radius => "radius",
+            Parameter::Periapsis => "periapsis",
+            Parameter::Eccentricity => "eccentricity",
+            Parameter::Inclination => "inclination",
+            Parameter::ArgPe => "arg. of periapsis",
+            Parameter::LongAscNode => "RAAN",
+            Parameter::MeanAnomaly => "mean anomaly",
+            Parameter::Period => "period",
+        }
+    }
+
+    /// Whether this parameter belongs to the body's orbit,
+    /// as opposed to the body itself.
+    pub const fn is_orbital(self) -> bool {
+        !matches!(self, Parameter::Mass | Parameter::Radius)
+    }
+}
+
+/// A parameter of a specific body.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ParameterRef {
+    pub body: Id,
+    pub parameter: Parameter,
+}
+
+/// Keeps `target = factor × source`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Constraint {
+    pub enabled: bool,
+    pub target: ParameterRef,
+    pub source: ParameterRef,
+    pub factor: f64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConstraintAddError {
+    /// A parameter can't be linked to itself.
+    SelfReference,
+    /// The target is already driven by another constraint.
+    TargetAlreadyDriven,
+    /// The new constraint would make a parameter depend on itself.
+    CreatesLoop,
+    /// The target belongs to a locked body.
+    TargetLocked,
+}
+
+impl Display for ConstraintAddError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConstraintAddError::SelfReference => write!(f, "a parameter can't drive itself"),
+            ConstraintAddError::TargetAlreadyDriven => {
+                write!(f, "that parameter is already driven by another constraint")
+            }
+            ConstraintAddError::CreatesLoop => {
+                write!(f, "that would create a loop of constraints")
+            }
+            ConstraintAddError::TargetLocked => write!(f, "the target body is locked"),
+        }
+    }
+}
+
+impl std::error::Error for ConstraintAddError {}
+
+/// Why the value a constraint works out can't be written to its target.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InvalidValue {
+    NotFinite,
+    /// Masses, radii, periapses and periods have to be above 0.
+    NotPositive,
+    NegativeEccentricity,
+}
+
+impl Display for InvalidValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InvalidValue::NotFinite => write!(f, "the target would not be a finite number"),
+            InvalidValue::NotPositive => write!(f, "the target would be 0 or below"),
+            InvalidValue::NegativeEccentricity => {
+                write!(f, "the target eccentricity would be negative")
+            }
+        }
+    }
+}
+
+impl std::error::Error for InvalidValue {}
+
+/// Gets the current value of a parameter.
+///
+/// Returns None if the body wasn't found, or the parameter
+/// is orbital and the body isn't orbiting anything.
+/// Periods are only defined for closed orbits.
+pub fn get_value(universe: &Universe, param: ParameterRef) -> Option<f64> {
+    let body = &universe.get_body(param.body)?.body;
+
+    if !param.parameter.is_orbital() {
+        return Some(match param.parameter {
+            Parameter::Mass => body.mass,
+            _ => body.radius,
+        });
+    }
+
+    let orbit = body.orbit.as_ref()?;
+
+    Some(match param.parameter {
+        Parameter::Periapsis => orbit.get_periapsis(),
+        Parameter::Eccentricity => orbit.get_eccentricity(),
+        Parameter::Inclination => orbit.get_inclination(),
+        Parameter::ArgPe => orbit.get_arg_pe(),
+        Parameter::LongAscNode => orbit.get_long_asc_node(),
+        Parameter::MeanAnomaly => orbit.get_mean_anomaly_at_epoch(),
+        Parameter::Period if orbit.is_closed() => {
+            TAU * (orbit.get_semi_major_axis().powi(3) / orbit.get_gravitational_parameter()).sqrt()
+        }
+        _ => return None,
+    })
+}
+
+/// Checks a value before it's written to a parameter.
+///
+/// Eccentricities are clamped so closed orbits stay closed
+/// and open orbits stay open.
+fn checked_value(
+    universe: &Universe,
+    param: ParameterRef,
+    value: f64,
+) -> Result<f64, InvalidValue> {
+    if !value.is_finite() {
+        return Err(InvalidValue::NotFinite);
+    }
+
+    match param.parameter {
+        Parameter::Mass | Parameter::Radius | Parameter::Periapsis | Parameter::Period
+            if value <= 0.0 =>
+        {
+            Err(InvalidValue::NotPositive)
+        }
+        Parameter::Eccentricity if value < 0.0 => Err(InvalidValue::NegativeEccentricity),
+        Parameter::Eccentricity => {
+            let closed = universe
+                .get_body(param.body)
+                .and_then(|w| w.body.orbit.as_ref())
+                .is_some_and(|orbit| orbit.is_closed());
+            Ok(if closed {
+                value.min(MAX_CLOSED_ECCENTRICITY)
+            } else {
+                value.max(MIN_OPEN_ECCENTRICITY)
+            })
+        }
+        _ => Ok(value),
+    }
+}
+
+/// Sets a parameter, if it differs enough from its current value.
+///
+/// Returns: Whether or not the parameter was changed,
+/// or why the value can't be written to it.
+fn set_value(
+    universe: &mut Universe,
+    param: ParameterRef,
+    value: f64,
+    mu_mode: BulkMuSetterMode,
+) -> Result<bool, InvalidValue> {
+    let Some(current) = get_value(universe, param) else {
+        return Ok(false);
+    };
+
+    let value = checked_value(universe, param, value)?;
+    if (value - current).abs() <= TOLERANCE * current.abs().max(value.abs()) {
+        return Ok(false);
+    }
+
+    let Some(wrapper) = universe.get_body_mut(param.body) else {
+        return Ok(false);
+    };
+    let body = &mut wrapper.body;
+
+    match param.parameter {
+        Parameter::Mass => {
+            body.mass = value;
+            let _ = universe.update_children_gravitational_parameters(param.body, mu_mode);
+            return Ok(true);
+        }
+        Parameter::Radius => {
+            body.radius = value;
+            return Ok(true);
+        }
+        _ => (),
+    }
+
+    let Some(orbit) = body.orbit.as_mut() else {
+        return Ok(false);
+    };
+
+    match param.parameter {
+        Parameter::Periapsis => orbit.set_periapsis(value),
+        Parameter::Eccentricity => orbit.set_eccentricity(value),
+        Parameter::Inclination => orbit.set_inclination(value),
+        Parameter::ArgPe => orbit.set_arg_pe(value),
+        Parameter::LongAscNode => orbit.set_long_asc_node(value),
+        Parameter::MeanAnomaly => orbit.set_mean_anomaly_at_epoch(value),
+        Parameter::Period => {
+            // Keep the eccentricity and change the size of the orbit:
+            // T = 2π √(a³/µ)  =>  a = ∛(µ (T/2π)²)
+            let mu = orbit.get_gravitational_parameter();
+            let semi_major_axis = (mu * (value / TAU).powi(2)).cbrt();
+            orbit.set_periapsis(semi_major_axis * (1.0 - orbit.get_eccentricity()));
+        }
+        Parameter::Mass | Parameter::Radius => unreachable!(),
+    }
+
+    Ok(true)
+}
+
+/// Whether the parameter belongs to a locked body.
+fn is_locked(universe: &Universe, parameter: ParameterRef) -> bool {
+    universe.get_body(parameter.body).is_some_and(|w| w.locked)
+}
+
+impl Constraint {
+    /// Whether both ends of the constraint still exist in the universe.
+    pub fn is_valid(&self, universe: &Universe) -> bool {
+        get_value(universe, self.source).is_some() && get_value(universe, self.target).is_some()
+    }
+
+    /// Checks that the value the constraint works out can be written to its target.
+    pub fn check(&self, universe: &Universe) -> Result<(), InvalidValue> {
+        match get_value(universe, self.source) {
+            Some(source) => checked_value(universe, self.target, source * self.factor).map(|_| ()),
+            None => Ok(()),
+        }
+    }
+
+    /// Formats the constraint like `Phobos.periapsis = 2 × Deimos.periapsis`.
+    pub fn describe(&self, universe: &Universe) -> String {
+        let name = |id| {
+            universe
+                .get_body(id)
+                .map(|w| w.body.name.as_str())
+                .unwrap_or("(deleted)")
+        };
+
+        format!(
+            "{}.{} = {} × {}.{}",
+            name(self.target.body),
+            self.target.parameter.name(),
+            self.factor,
+            name(self.source.body),
+            self.source.parameter.name(),
+        )
+    }
+}
+
+/// Adds a constraint to the universe, if it doesn't conflict with existing ones.
+pub fn add_constraint(
+    universe: &mut Universe,
+    constraint: Constraint,
+) -> Result<(), ConstraintAddError> {
+    if constraint.target == constraint.source {
+        return Err(ConstraintAddError::SelfReference);
+    }
+    if is_locked(universe, constraint.target) {
+        return Err(ConstraintAddError::TargetLocked);
+    }
+
+    let constraints = &universe.constraints;
+
+    if constraints.iter().any(|c| c.target == constraint.target) {
+        return Err(ConstraintAddError::TargetAlreadyDriven);
+    }
+
+    // Walk up the chain of parameters driving the source;
+    // reaching the new target means the new constraint closes a loop.
+    // Each parameter is driven by at most one constraint, so this is a single chain.
+    let mut current = constraint.source;
+    for _ in 0..=constraints.len() {
+        if current == constraint.target {
+            return Err(ConstraintAddError::CreatesLoop);
+        }
+        match constraints.iter().find(|c| c.target == current) {
+            Some(c) => current = c.source,
+            None => break,
+        }
+    }
+
+    universe.constraints.push(constraint);
+    Ok(())
+}
+
+/// Applies every enabled constraint.
+///
+/// Constraints can feed into each other, so this repeats until nothing changes.
+///
+/// Returns: How many parameters were changed.
+pub fn solve(universe: &mut Universe, mu_mode: BulkMuSetterMode) -> usize {
+    // Follows the edits that changed the sources, so isn't an edit itself
+    universe.without_edits(|universe| {
+        let mut changed = 0;
+
+        // Chains can be at most as long as the number of constraints
+        for _ in 0..universe.constraints.len() {
+            let mut changed_this_pass = false;
+
+            for index in 0..universe.constraints.len() {
+                let constraint = universe.constraints[index];
+
+                // Bodies locked after the constraint was added stay put
+                if !constraint.enabled || is_locked(universe, constraint.target) {
+                    continue;
+                }
+
+                let Some(source) = get_value(universe, constraint.source) else {
+                    continue;
+                };
+
+                // Values that can't be written leave the target as it is,
+                // and are shown in the Constraints window
+                let value = source * constraint.factor;
+                if set_value(universe, constraint.target, value, mu_mode) == Ok(true) {
+                    changed += 1;
+                    changed_this_pass = true;
+                }
+            }
+
+            if !changed_this_pass {
+                break;
+            }
+        }
+
+        changed
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use keplerian_sim::Orbit;
+    use strum::IntoEnumIterator;
+
+    use super::*;
+    use crate::sim::body::Body;
+
+    const MU_MODE: BulkMuSetterMode = BulkMuSetterMode::KeepElements;
+
+    fn radius(body: Id) -> ParameterRef {
+        ParameterRef {
+            body,
+            parameter: Parameter::Radius,
+        }
+    }
+
+    fn link(target: ParameterRef, source: ParameterRef, factor: f64) -> Constraint {
+        Constraint {
+            enabled: true,
+            target,
+            source,
+            factor,
+        }
+    }
+
+    fn body(universe: &mut Universe, radius: f64) -> Id {
+        let body = Body::new(String::from("Body"), 1e20, radius, None);
+        universe.add_body(body, None).unwrap()
+    }
+
+    /// A star with two planets on different orbits.
+    fn planets() -> (Universe, Id, Id) {
+        let mut universe = Universe::default();
+        let star = body(&mut universe, 7e8);
+        let mut planet = |periapsis, eccentricity, angle| {
+            let orbit = Orbit::new(eccentricity, periapsis, angle, angle, angle, angle, 1.0);
+            let body = Body::new(String::from("Planet"), 6e24, 6e6, Some(orbit));
+            universe.add_body(body, Some(star)).unwrap()
+        };
+        let source = planet(1.5e11, 0.1, 0.2);
+        let target = planet(7.8e11, 0.05, 0.3);
+        (universe, source, target)
+    }
+
+    fn assert_close(a: Option<f64>, b: Option<f64>) {
+        let (a, b) = (a.unwrap(), b.unwrap());
+        assert!((a - b).abs() <= 1e-9 * b.abs(), "{a} != {b}");
+    }
+
+    #[test]
+    fn locked_bodies_are_not_driven() {
+        let mut universe = Universe::default();
+        let source = body(&mut universe, 1e3);
+        let target = body(&mut universe, 5e3);
+        let constraint = link(radius(target), radius(source), 2.0);
+
+        universe.get_body_mut(target).unwrap().locked = true;
+        assert_eq!(
+            add_constraint(&mut universe, constraint),
+            Err(ConstraintAddError::TargetLocked)
+        );
+
+        // Locked after the constraint was added
+        universe.get_body_mut(target).unwrap().locked = false;
+        add_constraint(&mut universe, constraint).unwrap();
+        universe.get_body_mut(target).unwrap().locked = true;
+        assert_eq!(solve(&mut universe, MU_MODE), 0);
+        assert_eq!(get_value(&universe, radius(target)), Some(5e3));
+
+        universe.get_body_mut(target).unwrap().locked = false;
+        assert_eq!(solve(&mut universe, MU_MODE), 1);
+        assert_eq!(get_value(&universe, radius(target)), Some(2e3));
+    }
+
+    #[test]
+    fn every_parameter_can_be_driven() {
+        for parameter in Parameter::iter() {
+            let (mut universe, source, target) = planets();
+            let source = ParameterRef {
+                body: source,
+                parameter,
+            };
+            let target = ParameterRef {
+                body: target,
+                parameter,
+            };
+            add_constraint(&mut universe, link(target, source, 2.0)).unwrap();
+
+            assert_eq!(solve(&mut universe, MU_MODE), 1, "{}", parameter.name());
+            assert_close(
+                get_value(&universe, target),
+                get_value(&universe, source).map(|v| v * 2.0),
+            );
+        }
+    }
+
+    #[test]
+    fn closed_orbits_stay_closed() {
+        let (mut universe, source, target) = planets();
+        let eccentricity = |body| ParameterRef {
+            body,
+            parameter: Parameter::Eccentricity,
+        };
+        let constraint = link(eccentricity(target), eccentricity(source), 20.0);
+        add_constraint(&mut universe, constraint).unwrap();
+
+        assert_eq!(constraint.check(&universe), Ok(()));
+        assert_eq!(solve(&mut universe, MU_MODE), 1);
+        let e = get_value(&universe, eccentricity(target)).unwrap();
+        assert!(e < 1.0, "{e}");
+
+        universe.constraints[0].factor = -1.0;
+        assert_eq!(
+            universe.constraints[0].check(&universe),
+            Err(InvalidValue::NegativeEccentricity)
+        );
+        assert_eq!(solve(&mut universe, MU_MODE), 0);
+        assert_eq!(get_value(&universe, eccentricity(target)), Some(e));
+    }
+
+    #[test]
+    fn non_positive_sizes_are_refused() {
+        let mut universe = Universe::default();
+        let [a, b] = [1e3, 5e3].map(|r| body(&mut universe, r));
+        let mass = |body| ParameterRef {
+            body,
+            parameter: Parameter::Mass,
+        };
+
+        let shrink = link(radius(b), radius(a), -2.0);
+        add_constraint(&mut universe, shrink).unwrap();
+        assert_eq!(shrink.check(&universe), Err(InvalidValue::NotPositive));
+
+        let empty = link(mass(b), mass(a), 0.0);
+        add_constraint(&mut universe, empty).unwrap();
+        assert_eq!(empty.check(&universe), Err(InvalidValue::NotPositive));
+
+        assert_eq!(solve(&mut universe, MU_MODE), 0);
+        assert_eq!(get_value(&universe, radius(b)), Some(5e3));
+        assert_eq!(get_value(&universe, mass(b)), Some(1e20));
+    }
+
+    #[test]
+    fn chains_are_solved_in_one_call() {
+        let mut universe = Universe::default();
+        let [a, b, c] = [1e3, 1e3, 1e3].map(|r| body(&mut universe, r));
+
+        // Added downstream first, so one pass in order isn't enough
+        add_constraint(&mut universe, link(radius(c), radius(b), 2.0)).unwrap();
+        add_constraint(&mut universe, link(radius(b), radius(a), 3.0)).unwrap();
+
+        // Two in the first pass, then c again once b has moved
+        assert_eq!(solve(&mut universe, MU_MODE), 3);
+        assert_eq!(get_value(&universe, radius(c)), Some(6e3));
+        assert_eq!(solve(&mut universe, MU_MODE), 0);
+    }
+
+    #[test]
+    fn disabled_constraints_are_skipped() {
+        let mut universe = Universe::default();
+        let [a, b] = [1e3, 5e3].map(|r| body(&mut universe, r));
+        let constraint = Constraint {
+            enabled: false,
+            ..link(radius(b), radius(a), 2.0)
+        };
+        add_constraint(&mut universe, constraint).unwrap();
+
+        assert_eq!(solve(&mut universe, MU_MODE), 0);
+        assert_eq!(get_value(&universe, radius(b)), Some(5e3));
+    }
+
+    #[test]
+    fn conflicting_constraints_are_refused() {
+        let mut universe = Universe::default();
+        let [a, b, c] = [1e3, 1e3, 1e3].map(|r| body(&mut universe, r));
+
+        assert_eq!(
+            add_constraint(&mut universe, link(radius(a), radius(a), 2.0)),
+            Err(ConstraintAddError::SelfReference)
+        );
+
+        add_constraint(&mut universe, link(radius(b), radius(a), 2.0)).unwrap();
+        add_constraint(&mut universe, link(radius(c), radius(b), 2.0)).unwrap();
+        assert_eq!(
+            add_constraint(&mut universe, link(radius(c), radius(a), 2.0)),
+            Err(ConstraintAddError::TargetAlreadyDriven)
+        );
+        assert_eq!(
+            add_constraint(&mut universe, link(radius(a), radius(c), 2.0)),
+            Err(ConstraintAddError::CreatesLoop)
+        );
+    }
+
+    #[test]
+    fn loops_that_slipped_in_still_end() {
+        let mut universe = Universe::default();
+        let [a, b] = [1e3, 1e3].map(|r| body(&mut universe, r));
+
+        // Loaded from a file, say, without going through `add_constraint`
+        universe.constraints.push(link(radius(b), radius(a), 2.0));
+        universe.constraints.push(link(radius(a), radius(b), 2.0));
+
+        // One pass per constraint, each changing at most every target
+        assert!(solve(&mut universe, MU_MODE) <= 4);
+    }
+
+    #[test]
+    fn missing_ends_are_skipped() {
+        let mut universe = Universe::default();
+        let [a, b, c] = [1e3, 1e3, 1e3].map(|r| body(&mut universe, r));
+        add_constraint(&mut universe, link(radius(b), radius(a), 2.0)).unwrap();
+        add_constraint(&mut universe, link(radius(c), radius(b), 2.0)).unwrap();
+
+        // Deleting a body takes its constraints with it
+        universe.remove_body(a);
+        assert_eq!(universe.constraints.len(), 1);
+        assert_eq!(solve(&mut universe, MU_MODE), 1);
+
+        // Ones left pointing at it anyway are skipped
+        let dangling = link(radius(c), radius(a), 2.0);
+        universe.constraints = vec![dangling, link(radius(a), radius(c), 2.0)];
+        assert!(!dangling.is_valid(&universe));
+        assert!(dangling.describe(&universe).contains("(deleted)"));
+        assert_eq!(solve(&mut universe, MU_MODE), 0);
+
+        // As are orbital parameters of bodies without an orbit
+        let periapsis = |body| ParameterRef {
+            body,
+            parameter: Parameter::Periapsis,
+        };
+        universe.constraints = vec![link(periapsis(c), periapsis(b), 2.0)];
+        assert_eq!(solve(&mut universe, MU_MODE), 0);
+    }
+}
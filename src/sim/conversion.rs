@@ -0,0 +1,362 @@
+//! A thin adapter over keplerian_sim for converting between
+//! Keplerian orbital elements and Cartesian state vectors.
+
+use std::{error::Error, fmt, str::FromStr};
+
+use glam::DVec3;
+use keplerian_sim::{Orbit, OrbitTrait, StateVectors};
+
+use crate::units::length::LengthUnit;
+
+/// The six classical orbital elements.
+///
+/// Angles are in radians and distances are in meters.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct OrbitalElements {
+    pub eccentricity: f64,
+    pub periapsis: f64,
+    pub inclination: f64,
+    pub arg_pe: f64,
+    pub long_asc_node: f64,
+    /// The mean anomaly at time = 0.
+    pub mean_anomaly: f64,
+}
+
+impl Default for OrbitalElements {
+    fn default() -> Self {
+        Self {
+            eccentricity: 0.0,
+            periapsis: 1.0,
+            inclination: 0.0,
+            arg_pe: 0.0,
+            long_asc_node: 0.0,
+            mean_anomaly: 0.0,
+        }
+    }
+}
+
+impl OrbitalElements {
+    pub fn to_orbit(self, mu: f64) -> Orbit {
+        Orbit::new(
+            self.eccentricity,
+            self.periapsis,
+            self.inclination,
+            self.arg_pe,
+            self.long_asc_node,
+            self.mean_anomaly,
+            mu,
+        )
+    }
+
+    pub fn from_orbit(orbit: &impl OrbitTrait) -> Self {
+        Self {
+            eccentricity: orbit.get_eccentricity(),
+            periapsis: orbit.get_periapsis(),
+            inclination: orbit.get_inclination(),
+            arg_pe: orbit.get_arg_pe(),
+            long_asc_node: orbit.get_long_asc_node(),
+            mean_anomaly: orbit.get_mean_anomaly_at_epoch(),
+        }
+    }
+
+    /// Whether every element is a finite number.
+    pub fn is_finite(self) -> bool {
+        [
+            self.eccentricity,
+            self.periapsis,
+            self.inclination,
+            self.arg_pe,
+            self.long_asc_node,
+            self.mean_anomaly,
+        ]
+        .iter()
+        .all(|value| value.is_finite())
+    }
+
+    /// Writes the elements as text, one `Name: value unit` line per element.
+    ///
+    /// Angles are written in degrees. This is the same format
+    /// the orbit converter copies its results in.
+    pub fn to_text(self) -> String {
+        format!(
+            "Eccentricity: {}\n\
+            Periapsis: {} m\n\
+            Inclination: {} °\n\
+            Arg. of Pe.: {} °\n\
+            RAAN: {} °\n\
+            Mean anom.: {} °",
+            self.eccentricity,
+            self.periapsis,
+            self.inclination.to_degrees(),
+            self.arg_pe.to_degrees(),
+            self.long_asc_node.to_degrees(),
+            self.mean_anomaly.to_degrees(),
+        )
+    }
+
+    /// Reads elements from text, one `name: value [unit]` line per element.
+    ///
+    /// Names are matched loosely, so both `Arg. of Pe.` and `argument_of_periapsis`
+    /// work, as do short names like `e`, `q`, `i`, `w`, `raan` and `M`.
+    /// `=` may be used instead of `:`.
+    ///
+    /// Angles are in degrees unless followed by `rad`.
+    /// Distances are in meters unless followed by a unit like `km` or `AU`.
+    ///
+    /// Empty lines and lines starting with `#` are skipped.
+    /// All six elements must be given.
+    pub fn from_text(text: &str) -> Result<Self, ElementsParseError> {
+        let mut values: [Option<f64>; 6] = [None; 6];
+
+        for (index, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let line_number = index + 1;
+            let invalid = ElementsParseError::InvalidLine { line: line_number };
+
+            let (key, value) = line.split_once([':', '=']).ok_or(invalid)?;
+            let element = Element::from_name(key).ok_or(invalid)?;
+            let value = element.parse_value(value).ok_or(invalid)?;
+
+            values[element as usize] = Some(value);
+        }
+
+        let get = |element: Element| {
+            values[element as usize].ok_or(ElementsParseError::Missing(element.name()))
+        };
+
+        Ok(Self {
+            eccentricity: get(Element::Eccentricity)?,
+            periapsis: get(Element::Periapsis)?,
+            inclination: get(Element::Inclination)?,
+            arg_pe: get(Element::ArgPe)?,
+            long_asc_node: get(Element::LongAscNode)?,
+            mean_anomaly: get(Element::MeanAnomaly)?,
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ElementsParseError {
+    /// A line isn't a known element followed by a number.
+    /// Lines are counted from 1.
+    InvalidLine { line: usize },
+    /// An element wasn't given.
+    Missing(&'static str),
+}
+
+impl fmt::Display for ElementsParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ElementsParseError::InvalidLine { line } => {
+                write!(f, "line {line} isn't an orbital element and its value")
+            }
+            ElementsParseError::Missing(name) => write!(f, "the {name} is missing"),
+        }
+    }
+}
+
+impl Error for ElementsParseError {}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Element {
+    Eccentricity,
+    Periapsis,
+    Inclination,
+    ArgPe,
+    LongAscNode,
+    MeanAnomaly,
+}
+
+impl Element {
+    const fn name(self) -> &'static str {
+        match self {
+            Element::Eccentricity => "eccentricity",
+            Element::Periapsis => "periapsis",
+            Element::Inclination => "inclination",
+            Element::ArgPe => "argument of periapsis",
+            Element::LongAscNode => "RAAN",
+            Element::MeanAnomaly => "mean anomaly",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        // Ignores case, spaces and punctuation, so "Arg. of Pe." becomes "argofpe"
+        let name: String = name
+            .chars()
+            .filter(|c| c.is_alphanumeric())
+            .flat_map(char::to_lowercase)
+            .collect();
+
+        match name.as_str() {
+            "e" | "ecc" | "eccentricity" => Some(Element::Eccentricity),
+            "q" | "pe" | "rp" | "periapsis" | "periapsisdistance" => Some(Element::Periapsis),
+            "i" | "inc" | "inclination" => Some(Element::Inclination),
+            "w" | "argpe" | "argofpe" | "argp" | "argumentofperiapsis" => Some(Element::ArgPe),
+            "raan" | "lan" | "node" | "longascnode" | "longitudeofascendingnode" => {
+                Some(Element::LongAscNode)
+            }
+            "m" | "ma" | "m0" | "meananom" | "meananomaly" | "meananomalyatepoch" | "hypmanom" => {
+                Some(Element::MeanAnomaly)
+            }
+            _ => None,
+        }
+    }
+
+    /// Reads a value and its optional unit, converting it to meters or radians.
+    ///
+    /// Values too large to be finite, like `1e999`, aren't accepted.
+    fn parse_value(self, text: &str) -> Option<f64> {
+        let text = text.trim();
+        let number_end = text
+            .find(|c: char| !(c.is_ascii_digit() || matches!(c, '.' | '-' | '+' | 'e' | 'E')))
+            .unwrap_or(text.len());
+        let (number, unit) = text.split_at(number_end);
+        let number: f64 = number.parse().ok()?;
+        let unit = unit.trim();
+
+        let value = match self {
+            Element::Eccentricity => unit.is_empty().then_some(number),
+            Element::Periapsis => match unit {
+                "" | "m" => Some(number),
+                unit => LengthUnit::from_str(unit)
+                    .ok()
+                    .map(|unit| number * unit.get_value()),
+            },
+            Element::Inclination | Element::ArgPe | Element::LongAscNode | Element::MeanAnomaly => {
+                match unit {
+                    "" | "°" | "deg" => Some(number.to_radians()),
+                    "rad" => Some(number),
+                    _ => None,
+                }
+            }
+        };
+        value.filter(|value| value.is_finite())
+    }
+}
+
+/// Gets the position (m) and velocity (m/s) of an orbiting body
+/// at the given time.
+pub fn elements_to_state_vectors(elements: OrbitalElements, mu: f64, time: f64) -> (DVec3, DVec3) {
+    let state_vectors = elements.to_orbit(mu).get_state_vectors_at_time(time);
+    (state_vectors.position, state_vectors.velocity)
+}
+
+/// Gets the orbital elements of a body with the given position (m)
+/// and velocity (m/s) at the given time.
+pub fn state_vectors_to_elements(
+    position: DVec3,
+    velocity: DVec3,
+    mu: f64,
+    time: f64,
+) -> OrbitalElements {
+    let orbit = StateVectors { position, velocity }.to_cached_orbit(mu, time);
+    OrbitalElements::from_orbit(&orbit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn elements() -> OrbitalElements {
+        OrbitalElements {
+            eccentricity: 0.0167,
+            periapsis: 1.471e11,
+            inclination: 0.00005,
+            arg_pe: 1.9933,
+            long_asc_node: -0.1965,
+            mean_anomaly: 6.2398,
+        }
+    }
+
+    fn assert_close(a: OrbitalElements, b: OrbitalElements) {
+        let pairs = [
+            (a.eccentricity, b.eccentricity),
+            (a.periapsis, b.periapsis),
+            (a.inclination, b.inclination),
+            (a.arg_pe, b.arg_pe),
+            (a.long_asc_node, b.long_asc_node),
+            (a.mean_anomaly, b.mean_anomaly),
+        ];
+        for (a, b) in pairs {
+            assert!((a - b).abs() <= 1e-12 * b.abs().max(1.0), "{a} != {b}");
+        }
+    }
+
+    #[test]
+    fn text_round_trips() {
+        let text = elements().to_text();
+        assert_close(OrbitalElements::from_text(&text).unwrap(), elements());
+    }
+
+    #[test]
+    fn loose_names_and_units_are_read() {
+        let text = "# From another tool\n\
+            e = 0.0167\n\
+            q: 147100000 km\n\
+            \n\
+            i: 0.00005 rad\n\
+            w: 1.9933 rad\n\
+            raan: -0.1965 rad\n\
+            M: 6.2398 rad";
+        assert_close(OrbitalElements::from_text(text).unwrap(), elements());
+    }
+
+    #[test]
+    fn bad_lines_are_reported_by_number() {
+        let text = "e: 0.1\nperiapsis 7000000";
+        assert_eq!(
+            OrbitalElements::from_text(text),
+            Err(ElementsParseError::InvalidLine { line: 2 })
+        );
+
+        let unknown_unit = "e: 0.1\ni: 5 furlongs";
+        assert_eq!(
+            OrbitalElements::from_text(unknown_unit),
+            Err(ElementsParseError::InvalidLine { line: 2 })
+        );
+
+        let unknown_name = "tilt: 5";
+        assert_eq!(
+            OrbitalElements::from_text(unknown_name),
+            Err(ElementsParseError::InvalidLine { line: 1 })
+        );
+    }
+
+    #[test]
+    fn missing_elements_are_reported() {
+        let text = "e: 0.1\nq: 7000000\ni: 0\nw: 0\nraan: 0";
+        assert_eq!(
+            OrbitalElements::from_text(text),
+            Err(ElementsParseError::Missing("mean anomaly"))
+        );
+    }
+
+    #[test]
+    fn overflowing_values_are_rejected() {
+        let text = "e: 0.1\nq: 1e999\ni: 0\nw: 0\nraan: 0\nM: 0";
+        assert_eq!(
+            OrbitalElements::from_text(text),
+            Err(ElementsParseError::InvalidLine { line: 2 })
+        );
+
+        let text = "e: 0.1\nq: 1e300 ly\ni: 0\nw: 0\nraan: 0\nM: 0";
+        assert_eq!(
+            OrbitalElements::from_text(text),
+            Err(ElementsParseError::InvalidLine { line: 2 })
+        );
+    }
+
+    #[test]
+    fn non_finite_elements_are_caught() {
+        assert!(elements().is_finite());
+        let elements = OrbitalElements {
+            periapsis: f64::NAN,
+            ..elements()
+        };
+        assert!(!elements.is_finite());
+    }
+}
@@ -0,0 +1,204 @@
+//! Tables of where a body will be over a span of time, worked out from
+//! its orbit without advancing the universe, for checking against other tools.
+
+use std::{
+    error::Error,
+    fmt::{self, Write},
+};
+
+use glam::DVec3;
+use keplerian_sim::OrbitTrait;
+
+use crate::sim::universe::{Id, Universe};
+
+/// The most rows one table can have, so a tiny step can't freeze the program.
+pub const MAX_EPHEMERIS_ROWS: usize = 100_000;
+
+/// One sample of a body's state, relative to its parent.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct EphemerisRow {
+    /// The universe time, in seconds.
+    pub time: f64,
+    /// In meters.
+    pub position: DVec3,
+    /// In meters per second.
+    pub velocity: DVec3,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EphemerisError {
+    /// The body doesn't exist, or isn't orbiting anything.
+    NoOrbit,
+    /// The step isn't positive, or the span is negative.
+    InvalidSpan,
+    /// The span would need more than [`MAX_EPHEMERIS_ROWS`] rows.
+    TooManyRows,
+}
+
+impl fmt::Display for EphemerisError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EphemerisError::NoOrbit => write!(f, "the body isn't orbiting anything"),
+            EphemerisError::InvalidSpan => {
+                write!(
+                    f,
+                    "the step has to be positive and the span can't be negative"
+                )
+            }
+            EphemerisError::TooManyRows => {
+                write!(
+                    f,
+                    "that would be more than {MAX_EPHEMERIS_ROWS} rows; use a larger step"
+                )
+            }
+        }
+    }
+}
+
+impl Error for EphemerisError {}
+
+/// How many rows a span and step give, including both ends.
+pub fn row_count(span: f64, step: f64) -> Result<usize, EphemerisError> {
+    let valid = step > 0.0 && span >= 0.0;
+    if !valid {
+        return Err(EphemerisError::InvalidSpan);
+    }
+
+    let count = (span / step).floor() + 1.0;
+    if count > MAX_EPHEMERIS_ROWS as f64 {
+        return Err(EphemerisError::TooManyRows);
+    }
+
+    Ok(count as usize)
+}
+
+/// Samples a body's position and velocity relative to its parent,
+/// every `step` seconds from `start` until `start + span`.
+///
+/// Only the body's current orbit is followed, so maneuver nodes and
+/// perturbations along the way aren't taken into account.
+/// A frozen body stays where it was frozen.
+pub fn sample(
+    universe: &Universe,
+    id: Id,
+    start: f64,
+    span: f64,
+    step: f64,
+) -> Result<Vec<EphemerisRow>, EphemerisError> {
+    let wrapper = universe.get_body(id).ok_or(EphemerisError::NoOrbit)?;
+    let orbit = match (&wrapper.body.orbit, wrapper.relations.parent) {
+        (Some(orbit), Some(_)) => orbit,
+        _ => return Err(EphemerisError::NoOrbit),
+    };
+    let count = row_count(span, step)?;
+
+    let rows = (0..count)
+        .map(|index| {
+            let time = start + index as f64 * step;
+            let (position, velocity) = match wrapper.frozen_at {
+                Some(frozen_at) => (orbit.get_position_at_time(frozen_at), DVec3::ZERO),
+                None => (
+                    orbit.get_position_at_time(time),
+                    orbit.get_velocity_at_time(time),
+                ),
+            };
+            EphemerisRow {
+                time,
+                position,
+                velocity,
+            }
+        })
+        .collect();
+
+    Ok(rows)
+}
+
+/// Formats the rows as CSV with a header line.
+pub fn to_csv(rows: &[EphemerisRow]) -> String {
+    let mut csv = String::from("time_s,x_m,y_m,z_m,vx_m_s,vy_m_s,vz_m_s\n");
+
+    for row in rows {
+        let [x, y, z] = row.position.to_array();
+        let [vx, vy, vz] = row.velocity.to_array();
+        let _ = writeln!(csv, "{},{x},{y},{z},{vx},{vy},{vz}", row.time);
+    }
+
+    csv
+}
+
+/// Formats the rows as a JSON array of objects,
+/// with times in seconds and lengths in meters.
+pub fn to_json(rows: &[EphemerisRow]) -> String {
+    let mut json = String::from("[\n");
+
+    for (index, row) in rows.iter().enumerate() {
+        let [x, y, z] = row.position.to_array().map(json_number);
+        let [vx, vy, vz] = row.velocity.to_array().map(json_number);
+        let separator = if index + 1 < rows.len() { "," } else { "" };
+        let _ = writeln!(
+            json,
+            "  {{\"time\": {}, \"position\": [{x}, {y}, {z}], \"velocity\": [{vx}, {vy}, {vz}]}}{separator}",
+            json_number(row.time),
+        );
+    }
+
+    json.push(']');
+    json
+}
+
+/// JSON has no infinities or NaNs, so those become `null`.
+fn json_number(value: f64) -> String {
+    if value.is_finite() {
+        value.to_string()
+    } else {
+        String::from("null")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn row_count_includes_both_ends() {
+        assert_eq!(row_count(10.0, 1.0), Ok(11));
+        assert_eq!(row_count(10.5, 1.0), Ok(11));
+        assert_eq!(row_count(0.0, 1.0), Ok(1));
+        assert_eq!(row_count(10.0, 0.0), Err(EphemerisError::InvalidSpan));
+        assert_eq!(row_count(-1.0, 1.0), Err(EphemerisError::InvalidSpan));
+        assert_eq!(row_count(f64::NAN, 1.0), Err(EphemerisError::InvalidSpan));
+        assert_eq!(
+            row_count(MAX_EPHEMERIS_ROWS as f64, 1.0),
+            Err(EphemerisError::TooManyRows)
+        );
+    }
+
+    #[test]
+    fn formats_rows() {
+        let rows = [
+            EphemerisRow {
+                time: 0.0,
+                position: DVec3::new(1.0, 2.0, 3.0),
+                velocity: DVec3::new(4.0, 5.0, 6.0),
+            },
+            EphemerisRow {
+                time: 60.0,
+                position: DVec3::new(-1.5, 0.0, f64::INFINITY),
+                velocity: DVec3::ZERO,
+            },
+        ];
+
+        assert_eq!(
+            to_csv(&rows),
+            "time_s,x_m,y_m,z_m,vx_m_s,vy_m_s,vz_m_s\n\
+            0,1,2,3,4,5,6\n\
+            60,-1.5,0,inf,0,0,0\n"
+        );
+        assert_eq!(
+            to_json(&rows),
+            "[\n  \
+            {\"time\": 0, \"position\": [1, 2, 3], \"velocity\": [4, 5, 6]},\n  \
+            {\"time\": 60, \"position\": [-1.5, 0, null], \"velocity\": [0, 0, 0]}\n]"
+        );
+    }
+}
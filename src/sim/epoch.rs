@@ -0,0 +1,201 @@
+use core::f64::consts::TAU;
+use std::collections::HashSet;
+
+use keplerian_sim::OrbitTrait;
+
+use crate::sim::{
+    presets::PRESET_EPOCHS,
+    universe::{Id as UniverseId, Universe},
+};
+
+const SECONDS_PER_DAY: f64 = 86400.0;
+
+/// The mean anomaly of a preset body at a known date.
+pub(crate) struct PresetEpoch {
+    /// The name of the body, used to find it in a universe.
+    pub name: &'static str,
+    /// The Julian Date (TDB) the mean anomaly is measured at.
+    pub julian_date: f64,
+    /// The mean anomaly at that date, in radians.
+    pub mean_anomaly: f64,
+}
+
+/// Converts a Gregorian calendar date to a Julian Date.
+///
+/// Algorithm from Jean Meeus' *Astronomical Algorithms*, chapter 7.
+pub(crate) fn julian_date(year: i32, month: u32, day: u32, hour: f64) -> f64 {
+    let (year, month) = if month <= 2 {
+        (year as f64 - 1.0, month as f64 + 12.0)
+    } else {
+        (year as f64, month as f64)
+    };
+
+    let a = (year / 100.0).floor();
+    let b = 2.0 - a + (a / 4.0).floor();
+
+    (365.25 * (year + 4716.0)).floor() + (30.6001 * (month + 1.0)).floor() + day as f64 + b - 1524.5
+        + hour / 24.0
+}
+
+/// How many days a month of the Gregorian calendar has.
+pub(crate) fn days_in_month(year: i32, month: u32) -> u32 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Whether a day exists in the Gregorian calendar.
+pub(crate) fn is_valid_date(year: i32, month: u32, day: u32) -> bool {
+    (1..=12).contains(&month) && (1..=days_in_month(year, month)).contains(&day)
+}
+
+/// Sets the mean anomaly of every preset body in the universe so that,
+/// at time = 0, it is where it would be at the given Julian Date,
+/// then resets the time to 0.
+///
+/// Bodies are matched by name, so renamed bodies aren't moved.
+/// Locked bodies aren't moved either. The epochs of the bodies that
+/// aren't moved are shifted with the time, so they stay where they are.
+///
+/// Returns how many bodies were moved.
+pub(crate) fn set_to_date(universe: &mut Universe, julian_date: f64) -> usize {
+    let mut moved = HashSet::new();
+
+    for preset in PRESET_EPOCHS {
+        let Some(id) = universe.get_body_index_with_name(preset.name) else {
+            continue;
+        };
+        if universe.get_body(id).is_none_or(|w| w.locked) {
+            continue;
+        }
+        let Some(orbit) = universe
+            .get_body_mut(id)
+            .and_then(|w| w.body.orbit.as_mut())
+        else {
+            continue;
+        };
+
+        let elapsed = (julian_date - preset.julian_date) * SECONDS_PER_DAY;
+        let mean_anomaly = preset.mean_anomaly + orbit.get_mean_motion() * elapsed;
+
+        let mean_anomaly = if orbit.is_closed() {
+            mean_anomaly.rem_euclid(TAU)
+        } else {
+            mean_anomaly
+        };
+
+        orbit.set_mean_anomaly_at_epoch(mean_anomaly);
+        moved.insert(id);
+    }
+
+    reset_time(universe, &moved);
+    moved.len()
+}
+
+/// Sets the time back to 0 without moving any body but the `moved` ones.
+fn reset_time(universe: &mut Universe, moved: &HashSet<UniverseId>) {
+    let shift = universe.time;
+    let ids: Vec<UniverseId> = universe.get_bodies().keys().copied().collect();
+
+    for id in ids {
+        let Some(wrapper) = universe.get_body_mut(id) else {
+            continue;
+        };
+        if let Some(frozen_at) = &mut wrapper.frozen_at {
+            *frozen_at -= shift;
+        }
+        if moved.contains(&id) {
+            continue;
+        }
+        let Some(orbit) = &mut wrapper.body.orbit else {
+            continue;
+        };
+
+        let mean_anomaly = orbit.get_mean_anomaly_at_epoch() + orbit.get_mean_motion() * shift;
+        let mean_anomaly = if orbit.is_closed() {
+            mean_anomaly.rem_euclid(TAU)
+        } else {
+            mean_anomaly
+        };
+        orbit.set_mean_anomaly_at_epoch(mean_anomaly);
+    }
+
+    for node in &mut universe.maneuver_nodes {
+        node.time -= shift;
+    }
+    universe.time = 0.0;
+}
+
+#[cfg(test)]
+mod tests {
+    use keplerian_sim::Orbit;
+
+    use super::*;
+    use crate::sim::body::Body;
+
+    #[test]
+    fn set_to_date_leaves_locked_bodies_alone() {
+        let mut universe = Universe::default();
+        let sun = Body::new("Sun".to_owned(), 2e30, 7e8, None);
+        let sun = universe.add_body(sun, None).unwrap();
+        let orbit = Orbit::new(0.1, 4e11, 0.2, 0.0, 0.0, 1.0, 1.0);
+        let mut add = |name: &str| {
+            let body = Body::new(name.to_owned(), 1e21, 5e5, Some(orbit.clone()));
+            universe.add_body(body, Some(sun)).unwrap()
+        };
+        let ceres = add("Ceres");
+        let callisto = add("Callisto");
+        universe.get_body_mut(ceres).unwrap().locked = true;
+        let before = universe.get_body(ceres).unwrap().body.orbit.clone();
+
+        assert_eq!(set_to_date(&mut universe, julian_date(2030, 1, 1, 0.0)), 1);
+        assert_eq!(universe.get_body(ceres).unwrap().body.orbit, before);
+        assert_ne!(universe.get_body(callisto).unwrap().body.orbit, before);
+    }
+
+    #[test]
+    fn set_to_date_keeps_other_bodies_in_place() {
+        let mut universe = Universe::default();
+        let sun = Body::new("Sun".to_owned(), 2e30, 7e8, None);
+        let sun = universe.add_body(sun, None).unwrap();
+        let orbit = Orbit::new(0.1, 4e11, 0.2, 0.0, 0.0, 1.0, 1.327e20);
+        let mut add = |name: &str| {
+            let body = Body::new(name.to_owned(), 1e21, 5e5, Some(orbit.clone()));
+            universe.add_body(body, Some(sun)).unwrap()
+        };
+        let custom = add("Custom");
+        let locked = add("Ceres");
+        let frozen = add("Frozen");
+        universe.get_body_mut(locked).unwrap().locked = true;
+        universe.time = 1e6;
+        universe.set_body_frozen(frozen, true, false);
+        universe.time = 3e6;
+        let positions = [custom, locked, frozen].map(|id| universe.get_body_position(id).unwrap());
+
+        set_to_date(&mut universe, julian_date(2030, 1, 1, 0.0));
+
+        assert_eq!(universe.time, 0.0);
+        for (id, before) in [custom, locked, frozen].into_iter().zip(positions) {
+            let after = universe.get_body_position(id).unwrap();
+            assert!(
+                (after - before).length() < before.length() * 1e-9,
+                "{id} moved"
+            );
+        }
+    }
+
+    #[test]
+    fn invalid_dates_are_caught() {
+        assert!(is_valid_date(2024, 2, 29));
+        assert!(!is_valid_date(2023, 2, 29));
+        assert!(!is_valid_date(2023, 2, 31));
+        assert!(!is_valid_date(1900, 2, 29));
+        assert!(is_valid_date(2000, 2, 29));
+        assert!(!is_valid_date(2023, 4, 31));
+        assert!(!is_valid_date(2023, 13, 1));
+        assert!(!is_valid_date(2023, 1, 0));
+    }
+}
@@ -0,0 +1,513 @@
+//! Fitting Keplerian orbits to timestamped position samples,
+//! such as ones pasted from a spreadsheet or recorded from a simulation
+//! where bodies don't follow fixed conics.
+
+use std::{error::Error, fmt};
+
+use glam::DVec3;
+use keplerian_sim::{OrbitTrait, StateVectors};
+
+use crate::{
+    compute::Job,
+    sim::conversion::{OrbitalElements, state_vectors_to_elements},
+};
+
+/// The most Levenberg-Marquardt iterations to try before giving up.
+const MAX_ITERATIONS: usize = 200;
+
+/// Iteration stops once a step changes the state by less than this,
+/// relative to its size.
+const CONVERGENCE_THRESHOLD: f64 = 1e-13;
+
+/// The step used to estimate derivatives, relative to the state's size.
+const DIFFERENCE_STEP: f64 = 1e-7;
+
+/// Where a body was relative to its parent at some time.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Sample {
+    /// The universe time, in seconds.
+    pub time: f64,
+    /// The position relative to the parent, in meters.
+    pub position: DVec3,
+}
+
+/// The result of a successful fit.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct OrbitFit {
+    pub elements: OrbitalElements,
+    /// The root-mean-square distance between the samples and the fitted orbit,
+    /// in meters.
+    pub rms_error: f64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FitError {
+    /// A line of the CSV couldn't be read. Lines are counted from 1.
+    InvalidLine { line: usize },
+    /// At least three samples are needed to estimate the motion.
+    TooFewSamples,
+    /// Two samples were taken at the same time.
+    DuplicateTime,
+    /// The samples don't describe any orbit, e.g. they're all at the parent's center.
+    Degenerate,
+}
+
+impl fmt::Display for FitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FitError::InvalidLine { line } => {
+                write!(f, "line {line} isn't a time followed by three coordinates")
+            }
+            FitError::TooFewSamples => write!(f, "at least three samples are needed"),
+            FitError::DuplicateTime => write!(f, "two samples share the same time"),
+            FitError::Degenerate => write!(f, "the samples don't describe an orbit"),
+        }
+    }
+}
+
+impl Error for FitError {}
+
+/// Reads samples from CSV text, one `time, x, y, z` sample per line.
+///
+/// Values may be separated by commas, semicolons or whitespace.
+/// Empty lines and lines starting with `#` are skipped,
+/// and so is the first line if it is a header.
+pub fn parse_csv(text: &str) -> Result<Vec<Sample>, FitError> {
+    let mut samples = Vec::new();
+
+    for (index, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let values: Result<Vec<f64>, _> = line
+            .split(|c: char| c == ',' || c == ';' || c.is_whitespace())
+            .filter(|v| !v.is_empty())
+            .map(str::parse)
+            .collect();
+
+        match values.as_deref() {
+            Ok(&[time, x, y, z]) => samples.push(Sample {
+                time,
+                position: DVec3::new(x, y, z),
+            }),
+            Err(_) if samples.is_empty() && index == 0 => continue,
+            _ => return Err(FitError::InvalidLine { line: index + 1 }),
+        }
+    }
+
+    Ok(samples)
+}
+
+/// The velocity at the middle of three samples,
+/// from the derivative of the parabola through them.
+fn estimate_velocity(samples: &[Sample]) -> DVec3 {
+    let [a, b, c] = samples else {
+        unreachable!("estimate_velocity takes three samples");
+    };
+    let (t0, t1, t2) = (a.time, b.time, c.time);
+
+    a.position * ((t1 - t2) / ((t0 - t1) * (t0 - t2)))
+        + b.position * ((2.0 * t1 - t0 - t2) / ((t1 - t0) * (t1 - t2)))
+        + c.position * ((t1 - t0) / ((t2 - t0) * (t2 - t1)))
+}
+
+/// The position and velocity at the epoch, scaled to be around 1.
+type State = [f64; 6];
+
+struct Fitter {
+    samples: Vec<Sample>,
+    mu: f64,
+    /// The time the state is at.
+    epoch: f64,
+    position_scale: f64,
+    velocity_scale: f64,
+}
+
+impl Fitter {
+    fn to_state(&self, position: DVec3, velocity: DVec3) -> State {
+        let p = position / self.position_scale;
+        let v = velocity / self.velocity_scale;
+        [p.x, p.y, p.z, v.x, v.y, v.z]
+    }
+
+    fn state_vectors(&self, state: &State) -> (DVec3, DVec3) {
+        (
+            DVec3::new(state[0], state[1], state[2]) * self.position_scale,
+            DVec3::new(state[3], state[4], state[5]) * self.velocity_scale,
+        )
+    }
+
+    /// The difference between where the state puts the body
+    /// and each sample, scaled like the state.
+    fn residuals(&self, state: &State) -> Vec<f64> {
+        let (position, velocity) = self.state_vectors(state);
+        let orbit = StateVectors { position, velocity }.to_cached_orbit(self.mu, self.epoch);
+
+        self.samples
+            .iter()
+            .flat_map(|sample| {
+                let diff = (orbit.get_position_at_time(sample.time) - sample.position)
+                    / self.position_scale;
+                [diff.x, diff.y, diff.z]
+            })
+            .collect()
+    }
+}
+
+/// Finds the Keplerian orbit around a parent with gravitational parameter `mu`
+/// that best matches a set of samples, in the least-squares sense.
+///
+/// The motion is first estimated from the samples around the middle one,
+/// then refined against every sample with Levenberg-Marquardt.
+/// Fitting many samples can take a while, so this is done one iteration
+/// at a time, e.g. in the background with `compute::spawn`.
+pub struct FitJob {
+    fitter: Fitter,
+    state: State,
+    residuals: Vec<f64>,
+    cost: f64,
+    damping: f64,
+    iterations: usize,
+    converged: bool,
+}
+
+impl FitJob {
+    pub fn new(samples: &[Sample], mu: f64) -> Result<Self, FitError> {
+        if samples.len() < 3 {
+            return Err(FitError::TooFewSamples);
+        }
+        if !(mu.is_finite() && mu > 0.0) {
+            return Err(FitError::Degenerate);
+        }
+
+        let mut samples = samples.to_vec();
+        samples.sort_by(|a, b| a.time.total_cmp(&b.time));
+        if samples.windows(2).any(|w| w[0].time == w[1].time) {
+            return Err(FitError::DuplicateTime);
+        }
+
+        let mid = samples.len() / 2;
+        let epoch = samples[mid].time;
+        let position = samples[mid].position;
+        let velocity = estimate_velocity(&samples[mid - 1..=mid + 1]);
+
+        if position == DVec3::ZERO || !velocity.is_finite() {
+            return Err(FitError::Degenerate);
+        }
+
+        let fitter = Fitter {
+            samples,
+            mu,
+            epoch,
+            position_scale: position.length(),
+            velocity_scale: velocity.length().max(position.length() * 1e-9),
+        };
+        let state = fitter.to_state(position, velocity);
+        let residuals = fitter.residuals(&state);
+
+        Ok(Self {
+            cost: sum_sq(&residuals),
+            fitter,
+            state,
+            residuals,
+            damping: 1e-3,
+            iterations: 0,
+            converged: false,
+        })
+    }
+
+    /// Does one iteration, with the Jacobian estimated by forward differences.
+    ///
+    /// Returns: Whether the fit is done, because it stopped improving.
+    pub fn iterate(&mut self) -> bool {
+        if self.converged {
+            return true;
+        }
+
+        self.iterations += 1;
+        if self.iterations > MAX_ITERATIONS || !self.cost.is_finite() || self.cost == 0.0 {
+            self.converged = true;
+            return true;
+        }
+
+        let state = self.state;
+        let jacobian: [Vec<f64>; 6] = core::array::from_fn(|i| {
+            let mut nudged = state;
+            let step = DIFFERENCE_STEP * state[i].abs().max(1.0);
+            nudged[i] += step;
+            self.fitter
+                .residuals(&nudged)
+                .iter()
+                .zip(&self.residuals)
+                .map(|(n, r)| (n - r) / step)
+                .collect()
+        });
+
+        // JᵀJ and Jᵀr
+        let mut normal = [[0.0; 6]; 6];
+        let mut gradient = [0.0; 6];
+        for i in 0..6 {
+            for j in 0..6 {
+                normal[i][j] = dot(&jacobian[i], &jacobian[j]);
+            }
+            gradient[i] = dot(&jacobian[i], &self.residuals);
+        }
+
+        while self.damping < 1e12 {
+            let mut damped = normal;
+            for i in 0..6 {
+                damped[i][i] += self.damping * normal[i][i].max(f64::EPSILON);
+            }
+
+            let Some(step) = solve(damped, gradient.map(|g| -g)) else {
+                self.damping *= 10.0;
+                continue;
+            };
+
+            let mut candidate = state;
+            for i in 0..6 {
+                candidate[i] += step[i];
+            }
+
+            let candidate_residuals = self.fitter.residuals(&candidate);
+            let candidate_cost = sum_sq(&candidate_residuals);
+
+            if candidate_cost < self.cost {
+                let step_size = step.iter().map(|s| s * s).sum::<f64>().sqrt();
+                let state_size = state.iter().map(|s| s * s).sum::<f64>().sqrt();
+
+                self.state = candidate;
+                self.residuals = candidate_residuals;
+                self.cost = candidate_cost;
+                self.damping = (self.damping / 3.0).max(1e-12);
+                self.converged = step_size <= CONVERGENCE_THRESHOLD * state_size;
+                return self.converged;
+            }
+
+            self.damping *= 4.0;
+        }
+
+        // No step makes it any better
+        self.converged = true;
+        true
+    }
+
+    /// The best fit so far.
+    pub fn result(&self) -> Result<OrbitFit, FitError> {
+        let (position, velocity) = self.fitter.state_vectors(&self.state);
+
+        if !(position.is_finite() && velocity.is_finite()) {
+            return Err(FitError::Degenerate);
+        }
+
+        let rms_error =
+            (self.cost / self.fitter.samples.len() as f64).sqrt() * self.fitter.position_scale;
+        if !rms_error.is_finite() {
+            return Err(FitError::Degenerate);
+        }
+
+        Ok(OrbitFit {
+            elements: state_vectors_to_elements(
+                position,
+                velocity,
+                self.fitter.mu,
+                self.fitter.epoch,
+            ),
+            rms_error,
+        })
+    }
+}
+
+impl Job for FitJob {
+    type Output = Result<OrbitFit, FitError>;
+
+    fn step(&mut self) -> Option<Self::Output> {
+        self.iterate().then(|| self.result())
+    }
+}
+
+fn sum_sq(values: &[f64]) -> f64 {
+    values.iter().map(|v| v * v).sum()
+}
+
+fn dot(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b).map(|(a, b)| a * b).sum()
+}
+
+/// Solves a 6×6 linear system by Gaussian elimination with partial pivoting.
+///
+/// Returns: None if the system is singular.
+fn solve(mut a: [[f64; 6]; 6], mut b: [f64; 6]) -> Option<[f64; 6]> {
+    for col in 0..6 {
+        let pivot = (col..6).max_by(|&i, &j| a[i][col].abs().total_cmp(&a[j][col].abs()))?;
+        if a[pivot][col].abs() < f64::MIN_POSITIVE || !a[pivot][col].is_finite() {
+            return None;
+        }
+        a.swap(col, pivot);
+        b.swap(col, pivot);
+
+        for row in col + 1..6 {
+            let factor = a[row][col] / a[col][col];
+            for k in col..6 {
+                a[row][k] -= factor * a[col][k];
+            }
+            b[row] -= factor * b[col];
+        }
+    }
+
+    let mut x = [0.0; 6];
+    for row in (0..6).rev() {
+        let sum: f64 = (row + 1..6).map(|k| a[row][k] * x[k]).sum();
+        x[row] = (b[row] - sum) / a[row][row];
+    }
+
+    Some(x)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MU: f64 = 3.986e14;
+
+    fn fit(samples: &[Sample], mu: f64) -> Result<OrbitFit, FitError> {
+        let mut job = FitJob::new(samples, mu)?;
+        while !job.iterate() {}
+        job.result()
+    }
+
+    fn sample_orbit(elements: OrbitalElements, start: f64, end: f64, count: usize) -> Vec<Sample> {
+        let orbit = elements.to_orbit(MU);
+        (0..count)
+            .map(|i| {
+                let time = start + (end - start) * i as f64 / (count - 1) as f64;
+                Sample {
+                    time,
+                    position: orbit.get_position_at_time(time),
+                }
+            })
+            .collect()
+    }
+
+    fn assert_same_path(expected: OrbitalElements, fitted: OrbitalElements, times: &[f64]) {
+        let expected = expected.to_orbit(MU);
+        let fitted = fitted.to_orbit(MU);
+
+        for &time in times {
+            let a = expected.get_position_at_time(time);
+            let b = fitted.get_position_at_time(time);
+            assert!(
+                a.distance(b) < a.length() * 1e-6,
+                "at t={time}: expected {a}, got {b}"
+            );
+        }
+    }
+
+    #[test]
+    fn fits_elliptic_orbit() {
+        let elements = OrbitalElements {
+            eccentricity: 0.3,
+            periapsis: 7.0e6,
+            inclination: 0.5,
+            arg_pe: 1.0,
+            long_asc_node: 2.0,
+            mean_anomaly: 0.3,
+        };
+        let period = elements.to_orbit(MU).get_orbital_period();
+        let samples = sample_orbit(elements, 0.0, period, 60);
+
+        let fit = fit(&samples, MU).unwrap();
+
+        assert!((fit.elements.eccentricity - 0.3).abs() < 1e-6);
+        assert!((fit.elements.periapsis / 7.0e6 - 1.0).abs() < 1e-6);
+        assert!(fit.rms_error < 1.0);
+        assert_same_path(elements, fit.elements, &[0.0, period * 0.37, period * 1.5]);
+    }
+
+    #[test]
+    fn fits_hyperbolic_orbit() {
+        let elements = OrbitalElements {
+            eccentricity: 1.8,
+            periapsis: 1.0e7,
+            inclination: 1.2,
+            arg_pe: 4.0,
+            long_asc_node: 0.7,
+            mean_anomaly: 0.0,
+        };
+        let samples = sample_orbit(elements, -2.0e4, 2.0e4, 40);
+
+        let fit = fit(&samples, MU).unwrap();
+
+        assert!((fit.elements.eccentricity - 1.8).abs() < 1e-6);
+        assert_same_path(elements, fit.elements, &[-1.5e4, 0.0, 1.0e4]);
+    }
+
+    #[test]
+    fn fits_noisy_samples() {
+        let elements = OrbitalElements {
+            eccentricity: 0.1,
+            periapsis: 4.2e7,
+            inclination: 0.05,
+            arg_pe: 0.0,
+            long_asc_node: 0.0,
+            mean_anomaly: 1.0,
+        };
+        let period = elements.to_orbit(MU).get_orbital_period();
+        let mut samples = sample_orbit(elements, 0.0, period * 0.8, 80);
+
+        // Deterministic noise of up to 10 km on every axis
+        let mut seed = 12345u64;
+        let mut noise = || {
+            seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1);
+            ((seed >> 11) as f64 / (1u64 << 53) as f64 - 0.5) * 2.0e4
+        };
+        for sample in &mut samples {
+            sample.position += DVec3::new(noise(), noise(), noise());
+        }
+
+        let fit = fit(&samples, MU).unwrap();
+
+        assert!((fit.elements.eccentricity - 0.1).abs() < 1e-3);
+        assert!((fit.elements.periapsis / 4.2e7 - 1.0).abs() < 1e-3);
+        assert!(fit.rms_error < 2.0e4);
+    }
+
+    #[test]
+    fn parses_csv() {
+        let text = "time,x,y,z\n\
+            # a comment\n\
+            0, 1, 2, 3\n\
+            \n\
+            1.5;4e3;-5;6\n\
+            3\t7 8\t9\n";
+
+        let samples = parse_csv(text).unwrap();
+
+        assert_eq!(samples.len(), 3);
+        assert_eq!(samples[1].time, 1.5);
+        assert_eq!(samples[1].position, DVec3::new(4e3, -5.0, 6.0));
+        assert_eq!(
+            parse_csv("0,1,2,3\n1,2,3"),
+            Err(FitError::InvalidLine { line: 2 })
+        );
+    }
+
+    #[test]
+    fn rejects_bad_samples() {
+        let sample = |time| Sample {
+            time,
+            position: DVec3::X,
+        };
+
+        assert_eq!(
+            fit(&[sample(0.0), sample(1.0)], MU),
+            Err(FitError::TooFewSamples)
+        );
+        assert_eq!(
+            fit(&[sample(0.0), sample(1.0), sample(1.0)], MU),
+            Err(FitError::DuplicateTime)
+        );
+    }
+}
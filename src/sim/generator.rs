@@ -0,0 +1,525 @@
+//! Procedural generation of plausible star systems.
+//!
+//! Generation is fully deterministic: the same settings always give the same
+//! system on every platform, so seeds can be shared.
+
+use core::f64::consts::{PI, TAU};
+
+use keplerian_sim::Orbit;
+use three_d::Srgba;
+
+use crate::{
+    sim::{
+        body::Body,
+        universe::{Id, Universe},
+    },
+    units::{length::LengthUnit, mass::MassUnit},
+};
+
+const SOLAR_MASS: f64 = MassUnit::SolarMasses.get_value();
+const EARTH_MASS: f64 = MassUnit::EarthMasses.get_value();
+const SOLAR_RADIUS: f64 = LengthUnit::SolarRadii.get_value();
+const EARTH_RADIUS: f64 = LengthUnit::EarthRadii.get_value();
+const AU: f64 = LengthUnit::AstronomicalUnits.get_value();
+
+/// The Sun's surface temperature, in kelvin.
+const SOLAR_TEMPERATURE: f64 = 5772.0;
+
+/// Beyond this distance (in AU, for a Sun-like star), volatiles freeze
+/// and planets can grow into gas giants.
+const FROST_LINE: f64 = 2.7;
+
+/// Moons are kept within this fraction of their planet's Hill sphere,
+/// where their orbits would be stable.
+const STABLE_HILL_FRACTION: f64 = 0.4;
+
+pub const MAX_PLANET_COUNT: u32 = 12;
+
+const PLANET_LETTERS: &[u8] = b"bcdefghijklmnopq";
+const MOON_NUMERALS: [&str; 6] = ["I", "II", "III", "IV", "V", "VI"];
+
+/// The options for generating a system.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GeneratorSettings {
+    pub seed: u64,
+    /// How many planets to generate, up to `MAX_PLANET_COUNT`.
+    pub planet_count: u32,
+    /// How far from neat, circular, evenly-spaced orbits the system is, from 0 to 1.
+    pub chaos: f64,
+    /// How likely planets are to have moons, from 0 to 1.
+    pub moon_frequency: f64,
+}
+
+impl Default for GeneratorSettings {
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            planet_count: 6,
+            chaos: 0.3,
+            moon_frequency: 0.5,
+        }
+    }
+}
+
+/// Turns a piece of text into a seed.
+///
+/// This uses FNV-1a, which unlike the standard library's hashers
+/// is guaranteed to give the same result everywhere.
+pub fn seed_from_text(text: &str) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    let text = text.trim();
+    if let Ok(seed) = text.parse() {
+        return seed;
+    }
+
+    text.bytes().fold(OFFSET_BASIS, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(PRIME)
+    })
+}
+
+/// A small deterministic random number generator (SplitMix64).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) struct Rng(u64);
+
+impl Rng {
+    pub(super) const fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    pub(super) fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// A number in [0, 1).
+    fn unit(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    pub(super) fn range(&mut self, min: f64, max: f64) -> f64 {
+        min + (max - min) * self.unit()
+    }
+
+    /// A number between `min` and `max`, evenly spread across orders of magnitude.
+    fn log_range(&mut self, min: f64, max: f64) -> f64 {
+        (self.range(min.ln(), max.ln())).exp()
+    }
+
+    fn chance(&mut self, probability: f64) -> bool {
+        self.unit() < probability
+    }
+
+    fn angle(&mut self) -> f64 {
+        self.range(0.0, TAU)
+    }
+
+    fn pick<T: Copy>(&mut self, options: &[T]) -> T {
+        options[(self.next_u64() % options.len() as u64) as usize]
+    }
+}
+
+/// Generates a whole star system.
+///
+/// The star is the first body, and the universe's default focus.
+pub fn generate(settings: GeneratorSettings) -> Universe {
+    let mut rng = Rng::new(settings.seed);
+    let chaos = settings.chaos.clamp(0.0, 1.0);
+    let moon_frequency = settings.moon_frequency.clamp(0.0, 1.0);
+
+    let mut universe = Universe::default();
+    let g = universe.get_gravitational_constant();
+
+    let star_name = star_name(&mut rng);
+    let star = star(&mut rng, star_name.clone());
+    let star_mass = star.mass;
+    // Main-sequence luminosity, relative to the Sun
+    let luminosity = (star_mass / SOLAR_MASS).powf(3.5);
+    let star_id = universe
+        .add_body(star, None)
+        .expect("root bodies don't need a parent");
+
+    // Titius-Bode-like spacing: each planet is a roughly constant factor further out.
+    // Distances scale with the square root of the luminosity,
+    // so planets get about the same amount of light as they would around the Sun.
+    let light_scale = luminosity.sqrt();
+    let frost_line = FROST_LINE * AU * light_scale;
+    let spacing = rng.range(1.5, 1.9);
+    let mut semi_major_axis = rng.range(0.2, 0.5) * AU * light_scale;
+    let mut previous_axis = None;
+    let mut belt_count = 0;
+
+    for index in 0..settings.planet_count.min(MAX_PLANET_COUNT) {
+        let letter = PLANET_LETTERS[index as usize] as char;
+        let name = format!("{star_name} {letter}");
+
+        // A wide gap between two planets sometimes holds an asteroid belt instead
+        if let Some(previous_axis) = previous_axis
+            && semi_major_axis / previous_axis > 1.7
+            && rng.chance(0.15 + 0.3 * chaos)
+        {
+            let belt_axis = (semi_major_axis * previous_axis).sqrt();
+            belt_count += 1;
+            add_belt(
+                &mut universe,
+                &mut rng,
+                star_id,
+                &star_name,
+                belt_count,
+                belt_axis,
+                chaos,
+            );
+        }
+
+        let planet = planet(
+            &mut rng,
+            name,
+            semi_major_axis,
+            semi_major_axis > frost_line,
+            chaos,
+            g * star_mass,
+        );
+        let planet_mass = planet.mass;
+        let planet_radius = planet.radius;
+        let Ok(planet_id) = universe.add_body(planet, Some(star_id)) else {
+            continue;
+        };
+
+        let hill_radius = semi_major_axis * (planet_mass / (3.0 * star_mass)).cbrt();
+        add_moons(
+            &mut universe,
+            &mut rng,
+            planet_id,
+            planet_mass,
+            planet_radius,
+            hill_radius * STABLE_HILL_FRACTION,
+            moon_frequency,
+            chaos,
+        );
+
+        previous_axis = Some(semi_major_axis);
+        let jitter = 1.0 + rng.range(-0.25, 0.25) * chaos;
+        semi_major_axis *= spacing * jitter;
+    }
+
+    universe.metadata.name = format!("{star_name} system");
+    universe.metadata.author = String::from("System generator");
+    universe.metadata.description = format!(
+        "A procedurally generated system.\n\
+        Seed {}, {} planets, {:.0}% chaos, {:.0}% moon frequency.",
+        settings.seed,
+        settings.planet_count.min(MAX_PLANET_COUNT),
+        chaos * 100.0,
+        moon_frequency * 100.0,
+    );
+    universe.metadata.default_focus = Some(star_id);
+
+    universe
+}
+
+fn star_name(rng: &mut Rng) -> String {
+    const STARTS: [&str; 12] = [
+        "Al", "Be", "Ca", "Dra", "E", "Ke", "Lu", "Mi", "No", "Sa", "Te", "Vo",
+    ];
+    const MIDDLES: [&str; 8] = ["ra", "li", "ne", "to", "ga", "ri", "sa", "ve"];
+    const ENDS: [&str; 8] = ["n", "s", "x", "ra", "th", "mir", "lia", "on"];
+
+    let mut name = String::from(rng.pick(&STARTS));
+    if rng.chance(0.5) {
+        name.push_str(rng.pick(&MIDDLES));
+    }
+    name.push_str(rng.pick(&ENDS));
+    name
+}
+
+fn star(rng: &mut Rng, name: String) -> Body {
+    // Small stars are far more common than big ones
+    let mass = rng.log_range(0.3, 3.0);
+    let radius = mass.powf(0.8);
+    let temperature = SOLAR_TEMPERATURE * mass.powf(0.505);
+
+    Body {
+        name,
+        mass: mass * SOLAR_MASS,
+        radius: radius * SOLAR_RADIUS,
+        color: blackbody_color(temperature),
+        orbit: None,
+    }
+}
+
+fn planet(
+    rng: &mut Rng,
+    name: String,
+    semi_major_axis: f64,
+    beyond_frost_line: bool,
+    chaos: f64,
+    mu: f64,
+) -> Body {
+    let gas_giant = beyond_frost_line && rng.chance(0.75);
+
+    let (mass, radius, color) = if gas_giant {
+        let mass = rng.log_range(10.0, 600.0);
+        // Gas giants barely grow in size past a Saturn mass
+        let radius = (mass.powf(0.55)).min(11.5);
+        let color = Srgba::new_opaque(
+            rng.range(150.0, 240.0) as u8,
+            rng.range(120.0, 210.0) as u8,
+            rng.range(90.0, 220.0) as u8,
+        );
+        (mass, radius, color)
+    } else {
+        let mass = rng.log_range(0.05, 5.0);
+        let radius = mass.powf(0.28);
+        let grey = rng.range(110.0, 200.0);
+        let color = Srgba::new_opaque(
+            (grey * rng.range(0.9, 1.2)).min(255.0) as u8,
+            grey as u8,
+            (grey * rng.range(0.7, 1.1)) as u8,
+        );
+        (mass, radius, color)
+    };
+
+    let eccentricity = rng.range(0.0, 0.02 + 0.3 * chaos);
+
+    Body {
+        name,
+        mass: mass * EARTH_MASS,
+        radius: radius * EARTH_RADIUS,
+        color,
+        orbit: Some(random_orbit(rng, semi_major_axis, eccentricity, chaos, mu)),
+    }
+}
+
+fn random_orbit(
+    rng: &mut Rng,
+    semi_major_axis: f64,
+    eccentricity: f64,
+    chaos: f64,
+    mu: f64,
+) -> Orbit {
+    let max_inclination = (1.0 + 14.0 * chaos).to_radians();
+
+    Orbit::new(
+        eccentricity,
+        semi_major_axis * (1.0 - eccentricity),
+        rng.range(0.0, max_inclination),
+        rng.angle(),
+        rng.angle(),
+        rng.angle(),
+        mu,
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+fn add_moons(
+    universe: &mut Universe,
+    rng: &mut Rng,
+    planet_id: Id,
+    planet_mass: f64,
+    planet_radius: f64,
+    max_distance: f64,
+    moon_frequency: f64,
+    chaos: f64,
+) {
+    let g = universe.get_gravitational_constant();
+    // Giant planets collect more moons
+    let max_moons = if planet_mass > 10.0 * EARTH_MASS {
+        MOON_NUMERALS.len()
+    } else {
+        2
+    };
+    let planet_name = universe
+        .get_body(planet_id)
+        .map(|w| w.body.name.clone())
+        .unwrap_or_default();
+
+    let mut distance = planet_radius * rng.range(3.0, 8.0);
+
+    for numeral in MOON_NUMERALS.iter().take(max_moons) {
+        if distance > max_distance || !rng.chance(moon_frequency) {
+            break;
+        }
+
+        let mass = planet_mass * rng.log_range(1e-6, 1e-2);
+        // Roughly rocky/icy density
+        let radius = EARTH_RADIUS * (mass / EARTH_MASS).cbrt() * rng.range(0.9, 1.3);
+        let grey = rng.range(120.0, 220.0) as u8;
+        let eccentricity = rng.range(0.0, 0.01 + 0.1 * chaos);
+
+        let moon = Body {
+            name: format!("{planet_name} {numeral}"),
+            mass,
+            radius,
+            color: Srgba::new_opaque(grey, grey, grey),
+            orbit: Some(random_orbit(
+                rng,
+                distance,
+                eccentricity,
+                chaos,
+                g * planet_mass,
+            )),
+        };
+        let _ = universe.add_body(moon, Some(planet_id));
+
+        distance *= rng.range(1.4, 2.2);
+    }
+}
+
+fn add_belt(
+    universe: &mut Universe,
+    rng: &mut Rng,
+    star_id: Id,
+    star_name: &str,
+    belt_number: u32,
+    semi_major_axis: f64,
+    chaos: f64,
+) {
+    let Some(star_mass) = universe.get_body(star_id).map(|w| w.body.mass) else {
+        return;
+    };
+    let mu = universe.get_gravitational_constant() * star_mass;
+    let count = rng.range(6.0, 12.0) as u32;
+
+    for index in 1..=count {
+        let axis = semi_major_axis * rng.range(0.9, 1.1);
+        let eccentricity = rng.range(0.0, 0.05 + 0.2 * chaos);
+        let radius = rng.log_range(5e3, 5e5);
+        // Roughly rocky density
+        let mass = 4.0 / 3.0 * PI * radius.powi(3) * 2500.0;
+        let grey = rng.range(90.0, 170.0) as u8;
+
+        let asteroid = Body {
+            name: format!("{star_name} belt {belt_number}-{index}"),
+            mass,
+            radius,
+            color: Srgba::new_opaque(grey, grey, grey),
+            orbit: Some(random_orbit(rng, axis, eccentricity, chaos, mu)),
+        };
+        let _ = universe.add_body(asteroid, Some(star_id));
+    }
+}
+
+/// Approximates the color of a black body at the given temperature, in kelvin.
+///
+/// Based on Tanner Helland's curve fit of blackbody colors,
+/// which is accurate enough for picking star colors.
+fn blackbody_color(temperature: f64) -> Srgba {
+    let t = temperature.clamp(1000.0, 40000.0) / 100.0;
+
+    let red = if t <= 66.0 {
+        255.0
+    } else {
+        329.698727446 * (t - 60.0).powf(-0.1332047592)
+    };
+    let green = if t <= 66.0 {
+        99.4708025861 * t.ln() - 161.1195681661
+    } else {
+        288.1221695283 * (t - 60.0).powf(-0.0755148492)
+    };
+    let blue = if t >= 66.0 {
+        255.0
+    } else if t <= 19.0 {
+        0.0
+    } else {
+        138.5177312231 * (t - 10.0).ln() - 305.0447927307
+    };
+
+    Srgba::new_opaque(
+        red.clamp(0.0, 255.0) as u8,
+        green.clamp(0.0, 255.0) as u8,
+        blue.clamp(0.0, 255.0) as u8,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A body's name, mass, radius, orbit and parent's name.
+    type BodySummary = (String, f64, f64, Option<Orbit>, Option<String>);
+
+    /// Every body, sorted by name.
+    fn bodies(universe: &Universe) -> Vec<BodySummary> {
+        let mut bodies: Vec<_> = universe
+            .get_bodies()
+            .values()
+            .map(|wrapper| {
+                let parent = wrapper
+                    .relations
+                    .parent
+                    .and_then(|id| universe.get_body(id))
+                    .map(|parent| parent.body.name.clone());
+                let body = &wrapper.body;
+                (
+                    body.name.clone(),
+                    body.mass,
+                    body.radius,
+                    body.orbit.clone(),
+                    parent,
+                )
+            })
+            .collect();
+        bodies.sort_by(|a, b| a.0.cmp(&b.0));
+        bodies
+    }
+
+    #[test]
+    fn the_same_seed_gives_the_same_system() {
+        let settings = GeneratorSettings {
+            seed: 42,
+            planet_count: MAX_PLANET_COUNT,
+            chaos: 0.8,
+            moon_frequency: 1.0,
+        };
+
+        let first = generate(settings);
+        let second = generate(settings);
+
+        assert_eq!(bodies(&first), bodies(&second));
+        assert_eq!(first.metadata.name, second.metadata.name);
+    }
+
+    #[test]
+    fn different_seeds_give_different_systems() {
+        let first = generate(GeneratorSettings {
+            seed: 1,
+            ..Default::default()
+        });
+        let second = generate(GeneratorSettings {
+            seed: 2,
+            ..Default::default()
+        });
+
+        assert_ne!(bodies(&first), bodies(&second));
+    }
+
+    #[test]
+    fn planet_count_is_followed() {
+        let settings = GeneratorSettings {
+            planet_count: 4,
+            moon_frequency: 0.0,
+            ..Default::default()
+        };
+        let universe = generate(settings);
+        let star = universe.metadata.default_focus.unwrap();
+
+        let planets = bodies(&universe)
+            .into_iter()
+            .filter(|(name, ..)| !name.contains(" belt "))
+            .filter(|(.., parent)| parent.is_some())
+            .count();
+        assert_eq!(planets, 4);
+        assert!(universe.get_body(star).unwrap().relations.parent.is_none());
+    }
+
+    #[test]
+    fn text_seeds_are_stable() {
+        assert_eq!(seed_from_text(" 1234 "), 1234);
+        assert_eq!(seed_from_text("Kepler"), seed_from_text("Kepler"));
+        assert_ne!(seed_from_text("Kepler"), seed_from_text("kepler"));
+    }
+}
@@ -0,0 +1,297 @@
+//! Lambert's problem: finding the orbit that goes from one position
+//! to another in a given time, around a single parent.
+//!
+//! This is the building block of transfer planning. Solving it for
+//! the positions of two bodies at departure and arrival gives the
+//! transfer orbit between them, and the velocity changes it takes.
+//!
+//! Only transfers of less than one revolution are solved, using the
+//! universal variable formulation with bisection, which converges for
+//! elliptic, parabolic and hyperbolic transfers alike.
+
+use core::f64::consts::{PI, TAU};
+use std::{error::Error, fmt};
+
+use glam::DVec3;
+
+use crate::sim::roots;
+
+/// The universal variable ψ (the square of the change in eccentric anomaly
+/// for elliptic transfers) is below this for transfers of less than one revolution.
+const MAX_PSI: f64 = TAU * TAU;
+
+/// How far below [`MAX_PSI`] the search stops, relative to it.
+/// C(ψ) rounds to zero any closer, and the transfer takes practically forever there.
+const MAX_PSI_MARGIN: f64 = 1e-6;
+
+/// Where the search for ψ starts from below. It's widened as needed
+/// for fast hyperbolic transfers.
+const INITIAL_MIN_PSI: f64 = -4.0 * PI;
+
+/// How many times the lower end of the search may be doubled.
+const MAX_WIDENINGS: usize = 64;
+
+/// ψ is narrowed down until it's known to within this.
+const PSI_TOLERANCE: f64 = 1e-12;
+
+/// ψ this close to zero uses the series expansions of the Stumpff functions,
+/// which are more precise there than the closed forms.
+const SERIES_THRESHOLD: f64 = 1e-6;
+
+/// Which way around the parent the transfer goes.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Direction {
+    /// Counterclockwise when seen from above the reference plane (+Z),
+    /// like nearly everything in the solar system.
+    #[default]
+    Prograde,
+    Retrograde,
+}
+
+/// The velocities at both ends of a transfer orbit.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Transfer {
+    /// The velocity right after leaving the first position, in m/s.
+    pub departure_velocity: DVec3,
+    /// The velocity right before reaching the second position, in m/s.
+    pub arrival_velocity: DVec3,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LambertError {
+    /// The time of flight is zero, negative or not a number.
+    InvalidTime,
+    /// One of the positions is at the parent's center.
+    AtCenter,
+    /// The positions are in a straight line through the parent,
+    /// so which plane the transfer is in is undefined.
+    Collinear,
+    /// No transfer of less than one revolution takes that long.
+    NoSolution,
+}
+
+impl fmt::Display for LambertError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LambertError::InvalidTime => write!(f, "the time of flight has to be positive"),
+            LambertError::AtCenter => write!(f, "a position is at the parent's center"),
+            LambertError::Collinear => write!(
+                f,
+                "the positions are lined up through the parent, \
+                so the transfer's plane is undefined"
+            ),
+            LambertError::NoSolution => write!(f, "no transfer takes that long"),
+        }
+    }
+}
+
+impl Error for LambertError {}
+
+/// The Stumpff functions C(ψ) and S(ψ).
+fn stumpff(psi: f64) -> (f64, f64) {
+    if psi > SERIES_THRESHOLD {
+        let sqrt = psi.sqrt();
+        ((1.0 - sqrt.cos()) / psi, (sqrt - sqrt.sin()) / (sqrt * psi))
+    } else if psi < -SERIES_THRESHOLD {
+        let sqrt = (-psi).sqrt();
+        (
+            (sqrt.cosh() - 1.0) / -psi,
+            (sqrt.sinh() - sqrt) / (sqrt * -psi),
+        )
+    } else {
+        (0.5 - psi / 24.0, 1.0 / 6.0 - psi / 120.0)
+    }
+}
+
+/// Finds the transfer orbit from `from` to `to` that takes `time_of_flight`
+/// seconds, around a parent with a gravitational parameter of `mu`.
+///
+/// Both positions are relative to the parent, in meters.
+pub fn solve(
+    from: DVec3,
+    to: DVec3,
+    time_of_flight: f64,
+    mu: f64,
+    direction: Direction,
+) -> Result<Transfer, LambertError> {
+    let valid_time = time_of_flight > 0.0;
+    if !valid_time {
+        return Err(LambertError::InvalidTime);
+    }
+
+    let r1 = from.length();
+    let r2 = to.length();
+    if r1 == 0.0 || r2 == 0.0 {
+        return Err(LambertError::AtCenter);
+    }
+
+    let cos_dnu = (from.dot(to) / (r1 * r2)).clamp(-1.0, 1.0);
+    let normal = from.cross(to);
+    if normal.length() <= f64::EPSILON * r1 * r2 {
+        return Err(LambertError::Collinear);
+    }
+
+    // Going the short way around agrees with the direction
+    // if the positions turn that way around +Z
+    let short_way = match direction {
+        Direction::Prograde => normal.z >= 0.0,
+        Direction::Retrograde => normal.z < 0.0,
+    };
+    let sign = if short_way { 1.0 } else { -1.0 };
+    let a = sign * (r1 * r2 * (1.0 + cos_dnu)).sqrt();
+
+    // y(ψ), which has to be positive for the transfer to exist
+    let y = |psi: f64| {
+        let (c, s) = stumpff(psi);
+        r1 + r2 + a * (psi * s - 1.0) / c.sqrt()
+    };
+
+    // How much longer than wanted the transfer for ψ takes,
+    // which only grows with ψ
+    let sqrt_mu = mu.sqrt();
+    let excess_time = |psi: f64| {
+        let y = y(psi);
+        if y < 0.0 {
+            // Too short to even exist
+            return -time_of_flight;
+        }
+        let (c, s) = stumpff(psi);
+        let chi = (y / c).sqrt();
+        (chi.powi(3) * s + a * y.sqrt()) / sqrt_mu - time_of_flight
+    };
+
+    let hi = MAX_PSI * (1.0 - MAX_PSI_MARGIN);
+    let mut lo = INITIAL_MIN_PSI;
+    let mut widenings = 0;
+    while excess_time(lo) > 0.0 {
+        widenings += 1;
+        if widenings > MAX_WIDENINGS {
+            return Err(LambertError::NoSolution);
+        }
+        lo *= 2.0;
+    }
+
+    let psi = roots::bisect(excess_time, lo, hi, PSI_TOLERANCE).ok_or(LambertError::NoSolution)?;
+
+    // The Lagrange coefficients
+    let y = y(psi);
+    let f = 1.0 - y / r1;
+    let g = a * (y / mu).sqrt();
+    let g_dot = 1.0 - y / r2;
+
+    let transfer = Transfer {
+        departure_velocity: (to - f * from) / g,
+        arrival_velocity: (g_dot * to - from) / g,
+    };
+
+    if transfer.departure_velocity.is_finite() && transfer.arrival_velocity.is_finite() {
+        Ok(transfer)
+    } else {
+        Err(LambertError::NoSolution)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use keplerian_sim::OrbitTrait;
+
+    use super::*;
+    use crate::sim::conversion::OrbitalElements;
+
+    const MU: f64 = 3.986e14;
+
+    /// Checks that solving between two points on an orbit gives back that orbit's velocities.
+    fn assert_recovers(elements: OrbitalElements, start: f64, end: f64, direction: Direction) {
+        let orbit = elements.to_orbit(MU);
+        let departure = orbit.get_state_vectors_at_time(start);
+        let arrival = orbit.get_state_vectors_at_time(end);
+
+        let transfer = solve(
+            departure.position,
+            arrival.position,
+            end - start,
+            MU,
+            direction,
+        )
+        .unwrap();
+
+        let tolerance = departure.velocity.length() * 1e-6;
+        assert!(
+            transfer.departure_velocity.distance(departure.velocity) < tolerance,
+            "expected {}, got {}",
+            departure.velocity,
+            transfer.departure_velocity
+        );
+        assert!(
+            transfer.arrival_velocity.distance(arrival.velocity) < tolerance,
+            "expected {}, got {}",
+            arrival.velocity,
+            transfer.arrival_velocity
+        );
+    }
+
+    #[test]
+    fn recovers_elliptic_orbit() {
+        let elements = OrbitalElements {
+            eccentricity: 0.2,
+            periapsis: 7.0e6,
+            inclination: 0.3,
+            arg_pe: 1.0,
+            long_asc_node: 0.5,
+            mean_anomaly: 0.0,
+        };
+        let period = elements.to_orbit(MU).get_orbital_period();
+
+        // Both the short and the long way around
+        assert_recovers(elements, 0.0, period * 0.3, Direction::Prograde);
+        assert_recovers(elements, period * 0.1, period * 0.85, Direction::Prograde);
+    }
+
+    #[test]
+    fn recovers_retrograde_orbit() {
+        let elements = OrbitalElements {
+            eccentricity: 0.05,
+            periapsis: 4.2e7,
+            inclination: 2.8,
+            arg_pe: 0.0,
+            long_asc_node: 1.0,
+            mean_anomaly: 0.0,
+        };
+        let period = elements.to_orbit(MU).get_orbital_period();
+
+        assert_recovers(elements, 0.0, period * 0.4, Direction::Retrograde);
+    }
+
+    #[test]
+    fn recovers_hyperbolic_orbit() {
+        let elements = OrbitalElements {
+            eccentricity: 1.5,
+            periapsis: 1.0e7,
+            inclination: 0.1,
+            arg_pe: 2.0,
+            long_asc_node: 0.0,
+            mean_anomaly: 0.0,
+        };
+
+        assert_recovers(elements, -1.0e4, 5.0e3, Direction::Prograde);
+    }
+
+    #[test]
+    fn rejects_bad_input() {
+        let from = DVec3::new(7.0e6, 0.0, 0.0);
+        let to = DVec3::new(0.0, 8.0e6, 0.0);
+
+        assert_eq!(
+            solve(from, to, 0.0, MU, Direction::Prograde),
+            Err(LambertError::InvalidTime)
+        );
+        assert_eq!(
+            solve(DVec3::ZERO, to, 1.0e3, MU, Direction::Prograde),
+            Err(LambertError::AtCenter)
+        );
+        assert_eq!(
+            solve(from, -2.0 * from, 1.0e3, MU, Direction::Prograde),
+            Err(LambertError::Collinear)
+        );
+    }
+}
@@ -0,0 +1,252 @@
+//! The preset universes that can be loaded from the Welcome window.
+
+use core::f64::consts::PI;
+
+use keplerian_sim::{MuSetterMode, Orbit, OrbitTrait};
+use strum::IntoEnumIterator;
+use strum_macros::EnumIter;
+use three_d::Srgba;
+
+use crate::{
+    sim::{self, body::Body, universe::Universe},
+    units::{length::LengthUnit, mass::MassUnit},
+};
+
+const SOLAR_MASS: f64 = MassUnit::SolarMasses.get_value();
+const EARTH_MASS: f64 = MassUnit::EarthMasses.get_value();
+const JUPITER_MASS: f64 = MassUnit::JupiterMasses.get_value();
+const SOLAR_RADIUS: f64 = LengthUnit::SolarRadii.get_value();
+const EARTH_RADIUS: f64 = LengthUnit::EarthRadii.get_value();
+const JUPITER_RADIUS: f64 = LengthUnit::JupiterRadii.get_value();
+const AU: f64 = LengthUnit::AstronomicalUnits.get_value();
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, EnumIter)]
+pub(crate) enum PresetUniverse {
+    SolarSystem,
+    TwinSuns,
+}
+
+impl PresetUniverse {
+    pub(crate) const fn name(self) -> &'static str {
+        match self {
+            PresetUniverse::SolarSystem => "Solar System",
+            PresetUniverse::TwinSuns => "Twin suns",
+        }
+    }
+
+    pub(crate) const fn description(self) -> &'static str {
+        match self {
+            PresetUniverse::SolarSystem => {
+                "The Sun, its planets and a selection of moons, \
+                dwarf planets and spacecraft. This is what the demo starts with."
+            }
+            PresetUniverse::TwinSuns => {
+                "A pair of Sun-like stars circling each other, \
+                with three planets orbiting around both of them."
+            }
+        }
+    }
+
+    pub(crate) fn create(self) -> Universe {
+        match self {
+            PresetUniverse::SolarSystem => sim::create_universe(),
+            PresetUniverse::TwinSuns => create_twin_suns(),
+        }
+    }
+}
+
+/// What the first tab opens with when the program starts.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, EnumIter)]
+pub(crate) enum StartupUniverse {
+    #[default]
+    SolarSystem,
+    TwinSuns,
+    Empty,
+}
+
+impl StartupUniverse {
+    pub(crate) const fn name(self) -> &'static str {
+        match self {
+            StartupUniverse::SolarSystem => PresetUniverse::SolarSystem.name(),
+            StartupUniverse::TwinSuns => PresetUniverse::TwinSuns.name(),
+            StartupUniverse::Empty => "Empty universe",
+        }
+    }
+
+    pub(crate) const fn description(self) -> &'static str {
+        match self {
+            StartupUniverse::SolarSystem => PresetUniverse::SolarSystem.description(),
+            StartupUniverse::TwinSuns => PresetUniverse::TwinSuns.description(),
+            StartupUniverse::Empty => "A universe without any bodies, to build your own from.",
+        }
+    }
+
+    pub(crate) fn from_index(index: u8) -> Self {
+        Self::iter().nth(index as usize).unwrap_or_default()
+    }
+
+    pub(crate) fn to_index(self) -> u8 {
+        Self::iter()
+            .position(|startup| startup == self)
+            .unwrap_or(0) as u8
+    }
+
+    pub(crate) fn create(self) -> Universe {
+        match self {
+            StartupUniverse::SolarSystem => PresetUniverse::SolarSystem.create(),
+            StartupUniverse::TwinSuns => PresetUniverse::TwinSuns.create(),
+            StartupUniverse::Empty => Universe::default(),
+        }
+    }
+}
+
+/// Builds a fictional binary star system with circumbinary planets.
+///
+/// Bodies can only orbit a single parent, so the stars orbit a body at their
+/// barycenter that holds their combined mass, on opposite sides of it,
+/// which the planets orbit too. The stars' orbits are given the gravitational
+/// parameter of the other star as seen from the barycenter instead of the
+/// combined mass, so they circle each other as fast as a real pair would.
+fn create_twin_suns() -> Universe {
+    const STAR_MASS: f64 = SOLAR_MASS;
+    /// How far apart the stars are, on average.
+    const SEPARATION: f64 = 0.2 * AU;
+    const STAR_ECCENTRICITY: f64 = 0.15;
+
+    let mut universe = Universe::default();
+    let g = universe.get_gravitational_constant();
+    let mu = g * 2.0 * STAR_MASS;
+    // Each star is pulled by the other from twice its distance to the barycenter:
+    // µ = G·M_other³ / (M_a + M_b)², which is G·M/4 for equal masses
+    let star_mu = g * STAR_MASS.powi(3) / (2.0 * STAR_MASS).powi(2);
+
+    let barycenter = Body {
+        name: String::from("Didyma AB"),
+        mass: 2.0 * STAR_MASS,
+        radius: 1.0e3,
+        color: Srgba::new_opaque(90, 90, 90),
+        orbit: None,
+    };
+    let barycenter_id = universe
+        .add_body(barycenter, None)
+        .expect("root bodies don't need a parent");
+
+    // Mirrored orbits, so the stars always stay on opposite sides
+    let stars = [
+        ("Didyma A", 1.0, Srgba::new_opaque(255, 243, 234), 0.0),
+        ("Didyma B", 0.95, Srgba::new_opaque(255, 214, 170), PI),
+    ];
+    for (name, radius, color, arg_pe) in stars {
+        let star = Body {
+            name: String::from(name),
+            mass: STAR_MASS,
+            radius: radius * SOLAR_RADIUS,
+            color,
+            orbit: Some(Orbit::new(
+                STAR_ECCENTRICITY,
+                SEPARATION / 2.0 * (1.0 - STAR_ECCENTRICITY),
+                0.0,
+                arg_pe,
+                0.0,
+                0.0,
+                star_mu,
+            )),
+        };
+        // Added with the barycenter's mass, like any other body, so it's set again after
+        if let Ok(id) = universe.add_body(star, Some(barycenter_id))
+            && let Some(orbit) = universe
+                .get_body_mut(id)
+                .and_then(|w| w.body.orbit.as_mut())
+        {
+            orbit.set_gravitational_parameter(star_mu, MuSetterMode::KeepElements);
+        }
+    }
+
+    // Circumbinary orbits are only stable beyond about three times the separation
+    let planets = [
+        (
+            "Didyma b",
+            8.0 * EARTH_MASS,
+            2.2 * EARTH_RADIUS,
+            Srgba::new_opaque(176, 150, 120),
+            0.75 * AU,
+            0.03,
+            0.4,
+            0.5,
+        ),
+        (
+            "Didyma c",
+            0.4 * JUPITER_MASS,
+            0.85 * JUPITER_RADIUS,
+            Srgba::new_opaque(214, 190, 150),
+            1.4 * AU,
+            0.05,
+            1.9,
+            2.6,
+        ),
+        (
+            "Didyma d",
+            1.3 * EARTH_MASS,
+            1.1 * EARTH_RADIUS,
+            Srgba::new_opaque(140, 170, 210),
+            2.5 * AU,
+            0.08,
+            3.1,
+            4.4,
+        ),
+    ];
+    for (name, mass, radius, color, semi_major_axis, eccentricity, arg_pe, mean_anomaly) in planets
+    {
+        let planet = Body {
+            name: String::from(name),
+            mass,
+            radius,
+            color,
+            orbit: Some(Orbit::new(
+                eccentricity,
+                semi_major_axis * (1.0 - eccentricity),
+                1.5f64.to_radians(),
+                arg_pe,
+                0.3,
+                mean_anomaly,
+                mu,
+            )),
+        };
+        let _ = universe.add_body(planet, Some(barycenter_id));
+    }
+
+    universe.metadata.name = String::from("Didyma");
+    universe.metadata.author = String::from("keplerian_sim demo");
+    universe.metadata.description = String::from(
+        "A fictional pair of Sun-like stars with three circumbinary planets. \
+        The stars orbit their shared barycenter, which also holds their combined mass \
+        for the planets to orbit.",
+    );
+    universe.metadata.default_focus = Some(barycenter_id);
+
+    universe
+}
+
+#[cfg(test)]
+mod tests {
+    use core::f64::consts::TAU;
+
+    use super::*;
+
+    #[test]
+    fn twin_suns_circle_each_other_like_a_real_pair() {
+        let universe = create_twin_suns();
+        let star = universe.get_body_index_with_name("Didyma A").unwrap();
+        let orbit = universe.get_body(star).unwrap().body.orbit.clone().unwrap();
+
+        // The stars are twice as far apart as each is from the barycenter
+        let separation = 2.0 * orbit.get_semi_major_axis();
+        let g = universe.get_gravitational_constant();
+        let expected = TAU * (separation.powi(3) / (g * 2.0 * SOLAR_MASS)).sqrt();
+        let period = TAU / orbit.get_mean_motion();
+        assert!(
+            (period - expected).abs() <= 1e-9 * expected,
+            "{period} != {expected}"
+        );
+    }
+}
@@ -0,0 +1,150 @@
+//! Planned instantaneous burns, executed as the universe's time passes them.
+
+use crate::sim::{
+    orbit_ops::{Kick, kicked_orbit},
+    universe::{Id, Universe},
+};
+
+/// An instantaneous change in a body's velocity at a set time.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ManeuverNode {
+    pub body: Id,
+    /// The universe time the burn happens at.
+    pub time: f64,
+    /// The change in velocity along the direction of motion, in m/s.
+    pub prograde: f64,
+    /// The change in velocity along the orbit normal, in m/s.
+    pub normal: f64,
+    /// The change in velocity away from the parent,
+    /// perpendicular to the direction of motion, in m/s.
+    pub radial: f64,
+}
+
+impl ManeuverNode {
+    /// Creates a node that doesn't change the orbit yet.
+    pub fn new(body: Id, time: f64) -> Self {
+        Self {
+            body,
+            time,
+            prograde: 0.0,
+            normal: 0.0,
+            radial: 0.0,
+        }
+    }
+
+    /// The change in velocity, without when it happens.
+    pub(crate) fn kick(&self) -> Kick {
+        Kick {
+            prograde: self.prograde,
+            normal: self.normal,
+            radial: self.radial,
+        }
+    }
+
+    /// The total change in velocity, in m/s.
+    pub fn delta_v(&self) -> f64 {
+        self.kick().delta_v()
+    }
+}
+
+/// Plans a burn, unless the body is locked or doesn't exist.
+///
+/// Returns: Whether or not the node was added.
+pub(crate) fn add_node(universe: &mut Universe, node: ManeuverNode) -> bool {
+    if universe.get_body(node.body).is_none_or(|w| w.locked) {
+        return false;
+    }
+    universe.maneuver_nodes.push(node);
+    universe.mark_edited();
+    true
+}
+
+/// Applies a burn to a body's orbit.
+///
+/// Returns: Whether or not the body has an orbit to change.
+fn execute(universe: &mut Universe, node: &ManeuverNode) -> bool {
+    let Some(orbit) = universe
+        .get_body_mut(node.body)
+        .and_then(|w| w.body.orbit.as_mut())
+    else {
+        return false;
+    };
+
+    if let Ok(kicked) = kicked_orbit(orbit, node.kick(), node.time) {
+        *orbit = kicked;
+    }
+
+    true
+}
+
+/// Executes and removes every node the universe's time has reached.
+///
+/// Nodes on frozen or locked bodies wait until the body is unfrozen or unlocked.
+pub(crate) fn execute_due(universe: &mut Universe) {
+    let time = universe.time;
+    let is_due = |universe: &Universe, node: &ManeuverNode| {
+        node.time <= time
+            && universe
+                .get_body(node.body)
+                .is_some_and(|w| w.frozen_at.is_none() && !w.locked)
+    };
+
+    if !universe.maneuver_nodes.iter().any(|n| is_due(universe, n)) {
+        return;
+    }
+
+    let (mut due, pending): (Vec<_>, Vec<_>) = universe
+        .maneuver_nodes
+        .iter()
+        .copied()
+        .partition(|n| is_due(universe, n));
+    universe.maneuver_nodes = pending;
+
+    // Burns on the same body have to happen in order
+    due.sort_by(|a, b| a.time.total_cmp(&b.time));
+    for node in due {
+        execute(universe, &node);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use keplerian_sim::{Orbit, OrbitTrait};
+
+    use super::*;
+    use crate::sim::body::Body;
+
+    #[test]
+    fn locked_bodies_get_no_burns() {
+        let mut universe = Universe::default();
+        let sun = Body::new(String::from("Sun"), 2e30, 7e8, None);
+        let sun = universe.add_body(sun, None).unwrap();
+        let orbit = Orbit::new(0.1, 1.5e11, 0.0, 0.0, 0.0, 0.0, 1.0);
+        let planet = Body::new(String::from("Planet"), 6e24, 6e6, Some(orbit));
+        let planet = universe.add_body(planet, Some(sun)).unwrap();
+        let node = ManeuverNode {
+            prograde: 1000.0,
+            ..ManeuverNode::new(planet, 10.0)
+        };
+
+        universe.get_body_mut(planet).unwrap().locked = true;
+        assert!(!add_node(&mut universe, node));
+        assert!(universe.maneuver_nodes.is_empty());
+
+        // Planned before the body was locked
+        universe.maneuver_nodes.push(node);
+        let before = universe.get_body(planet).unwrap().body.orbit.clone();
+        universe.tick(20.0);
+        assert_eq!(universe.get_body(planet).unwrap().body.orbit, before);
+        assert_eq!(universe.maneuver_nodes, [node]);
+
+        universe.get_body_mut(planet).unwrap().locked = false;
+        universe.tick(1.0);
+        let after = universe.get_body(planet).unwrap().body.orbit.clone();
+        assert!(universe.maneuver_nodes.is_empty());
+        assert_ne!(
+            after.map(|o| o.get_eccentricity()),
+            before.map(|o| o.get_eccentricity())
+        );
+    }
+}
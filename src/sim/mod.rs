@@ -1,6 +1,40 @@
+pub(crate) mod altitude_profile;
+#[cfg(not(target_family = "wasm"))]
+pub(crate) mod background;
 pub(crate) mod body;
+pub(crate) mod constraint;
+pub(crate) mod conversion;
+pub(crate) mod ephemeris;
+pub(crate) mod epoch;
+pub(crate) mod fit;
+pub(crate) mod generator;
+pub(crate) mod lambert;
+pub(crate) mod library;
+pub(crate) mod maneuver;
+pub(crate) mod occlusion;
+pub(crate) mod orbit_check;
+pub(crate) mod orbit_ops;
+pub(crate) mod perturbation;
+pub(crate) mod phase;
+pub(crate) mod position_cache;
+pub(crate) mod preset_schema;
 mod presets;
+pub(crate) mod proximity;
+pub(crate) mod relative_path;
+pub(crate) mod rendezvous;
+pub(crate) mod roots;
+pub(crate) mod speed;
+pub(crate) mod stable_id;
+pub(crate) mod startup_options;
 pub(crate) mod universe;
+pub(crate) mod validation;
+pub(crate) mod warp;
+pub(crate) mod zoom_levels;
+
+use three_d::Srgba;
+
+/// The presets file the bundled presets were built from.
+pub(crate) const PRESETS_TOML: &str = include_str!("presets.toml");
 
 macro_rules! declare_universe {
     {
@@ -76,7 +110,7 @@ macro_rules! declare_universe {
 }
 
 pub(crate) fn create_universe() -> universe::Universe {
-    declare_universe! {
+    let mut universe = declare_universe! {
         the_sun {
             mercury,
             venus,
@@ -100,10 +134,15 @@ pub(crate) fn create_universe() -> universe::Universe {
                 mimas,
                 enceladus,
                 tethys,
+                dione,
+                rhea,
                 titan,
                 iapetus,
             },
             uranus {
+                miranda,
+                ariel,
+                umbriel,
                 titania,
                 oberon,
             },
@@ -133,5 +172,57 @@ pub(crate) fn create_universe() -> universe::Universe {
             voyager_2,
             voyager_1,
         }
+    };
+
+    universe.metadata = universe::UniverseMetadata {
+        name: String::from("Solar System"),
+        author: String::from("keplerian_sim demo"),
+        description: String::from(
+            "The Sun and its planets, along with a selection of moons, \
+            dwarf planets, and spacecraft.",
+        ),
+        epoch_label: String::from("Preset epoch"),
+        default_focus: universe.get_body_index_with_name("The Sun"),
+    };
+
+    add_preset_rings(&mut universe);
+
+    universe
+}
+
+/// Gives Saturn and Uranus their rings, in their equatorial planes.
+fn add_preset_rings(universe: &mut universe::Universe) {
+    let rings = [
+        (
+            "Saturn",
+            // From the inner edge of the C ring to the outer edge of the A ring
+            body::Rings {
+                inner_radius: 7.4658e7,
+                outer_radius: 1.36775e8,
+                inclination: 28.05f64.to_radians(),
+                long_asc_node: 169.5f64.to_radians(),
+                color: Srgba::new(210, 190, 150, 170),
+            },
+        ),
+        (
+            "Uranus",
+            // From the 6 ring to the ε ring
+            body::Rings {
+                inner_radius: 4.1837e7,
+                outer_radius: 5.1149e7,
+                inclination: 97.77f64.to_radians(),
+                long_asc_node: 167.6f64.to_radians(),
+                color: Srgba::new(120, 120, 120, 70),
+            },
+        ),
+    ];
+
+    for (name, rings) in rings {
+        let wrapper = universe
+            .get_body_index_with_name(name)
+            .and_then(|id| universe.get_body_mut(id));
+        if let Some(wrapper) = wrapper {
+            wrapper.rings = Some(rings);
+        }
     }
 }
@@ -0,0 +1,227 @@
+//! Line-of-sight tests against the bodies of a universe,
+//! for telling when a body is hidden behind another or in its shadow.
+//!
+//! Bodies are treated as spheres, and a body counts as hidden
+//! once its center is, so partial occlusion isn't detected.
+
+use std::collections::{HashMap, HashSet};
+
+use glam::DVec3;
+
+use crate::sim::universe::{Id, Universe};
+
+/// Whether the segment from `from` to `to`
+/// passes through the sphere, excluding its endpoints.
+pub(crate) fn segment_intersects_sphere(
+    from: DVec3,
+    to: DVec3,
+    center: DVec3,
+    radius: f64,
+) -> bool {
+    let segment = to - from;
+    let length_squared = segment.length_squared();
+
+    if length_squared == 0.0 {
+        return false;
+    }
+
+    // The point on the segment closest to the sphere's center
+    let t = (center - from).dot(segment) / length_squared;
+
+    if t <= 0.0 || t >= 1.0 {
+        return false;
+    }
+
+    let closest = from + segment * t;
+    closest.distance_squared(center) < radius * radius
+}
+
+/// Finds the body closest to `viewer` that blocks its line of sight
+/// to the center of the `target` body.
+///
+/// Bodies the viewer is inside of are ignored.
+pub(crate) fn find_occluder(
+    universe: &Universe,
+    positions: &HashMap<Id, DVec3>,
+    viewer: DVec3,
+    target: Id,
+) -> Option<Id> {
+    let target_pos = *positions.get(&target)?;
+
+    universe
+        .get_bodies()
+        .iter()
+        .filter(|&(&id, _)| id != target)
+        .filter_map(|(&id, wrapper)| {
+            let center = *positions.get(&id)?;
+            let radius = wrapper.body.radius;
+            let distance = viewer.distance(center);
+
+            (distance > radius && segment_intersects_sphere(viewer, target_pos, center, radius))
+                .then_some((id, distance))
+        })
+        .min_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map(|(id, _)| id)
+}
+
+/// Gets the body lighting a body, which is its top-most ancestor.
+///
+/// Returns None for bodies without a parent, which light themselves.
+pub(crate) fn light_source(universe: &Universe, id: Id) -> Option<Id> {
+    let mut current = universe.get_body(id)?.relations.parent?;
+
+    // Bounded by the body count in case of a malformed hierarchy
+    for _ in 0..universe.get_bodies().len() {
+        match universe.get_body(current)?.relations.parent {
+            Some(parent) => current = parent,
+            None => break,
+        }
+    }
+
+    Some(current)
+}
+
+/// Finds the body casting its shadow on the center of a body, if any.
+pub(crate) fn find_eclipser(
+    universe: &Universe,
+    positions: &HashMap<Id, DVec3>,
+    target: Id,
+) -> Option<Id> {
+    let light = light_source(universe, target)?;
+    let light_pos = *positions.get(&light)?;
+
+    find_occluder(universe, positions, light_pos, target).filter(|&id| id != light)
+}
+
+/// Gets the bodies in the focused body's system that are in the shadow
+/// of another body in it.
+///
+/// The system is everything lit by the same body as `focused`. Checking
+/// every body against every other is quadratic, so other systems, which are
+/// usually too far away for their shadows to be seen, are left out,
+/// as are bodies in `skip`, such as ones that aren't drawn.
+pub(crate) fn eclipsed_bodies(
+    universe: &Universe,
+    positions: &HashMap<Id, DVec3>,
+    focused: Id,
+    skip: &HashSet<Id>,
+) -> HashSet<Id> {
+    let light = light_source(universe, focused).unwrap_or(focused);
+    let (Some(&light_pos), Some(system)) = (positions.get(&light), universe.get_descendants(light))
+    else {
+        return HashSet::new();
+    };
+
+    // Each body's position, radius and distance from the light
+    let spheres: Vec<(Id, DVec3, f64, f64)> = system
+        .into_iter()
+        .filter_map(|id| {
+            let position = *positions.get(&id)?;
+            let radius = universe.get_body(id)?.body.radius;
+            Some((id, position, radius, position.distance(light_pos)))
+        })
+        .collect();
+
+    spheres
+        .iter()
+        .filter(|(id, ..)| !skip.contains(id))
+        .filter(|&&(id, position, _, distance)| {
+            spheres
+                .iter()
+                .any(|&(other, center, radius, other_distance)| {
+                    // Only bodies reaching nearer to the light can shade this one
+                    other != id
+                        && other_distance - radius < distance
+                        && other_distance > radius
+                        && segment_intersects_sphere(light_pos, position, center, radius)
+                })
+        })
+        .map(|&(id, ..)| id)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sim::body::Body;
+
+    /// Two stars, each with a planet and a moon in a line behind it.
+    struct Systems {
+        universe: Universe,
+        positions: HashMap<Id, DVec3>,
+        planet: Id,
+        moon: Id,
+        far_planet: Id,
+        far_moon: Id,
+    }
+
+    impl Systems {
+        fn new() -> Self {
+            let mut universe = Universe::default();
+            let mut positions = HashMap::new();
+            let mut add = |parent, position: DVec3| {
+                let body = Body::new(String::from("Body"), 1.0, 1.0, None);
+                let id = universe.add_body(body, parent).unwrap();
+                positions.insert(id, position);
+                id
+            };
+
+            let star = add(None, DVec3::ZERO);
+            let planet = add(Some(star), DVec3::new(10.0, 0.0, 0.0));
+            let moon = add(Some(planet), DVec3::new(15.0, 0.0, 0.0));
+
+            let far_star = add(None, DVec3::new(0.0, 1e9, 0.0));
+            let far_planet = add(Some(far_star), DVec3::new(10.0, 1e9, 0.0));
+            let far_moon = add(Some(far_planet), DVec3::new(15.0, 1e9, 0.0));
+
+            Self {
+                universe,
+                positions,
+                planet,
+                moon,
+                far_planet,
+                far_moon,
+            }
+        }
+    }
+
+    #[test]
+    fn shadows_in_the_focused_system_are_found() {
+        let systems = Systems::new();
+        let eclipsed = eclipsed_bodies(
+            &systems.universe,
+            &systems.positions,
+            systems.planet,
+            &HashSet::new(),
+        );
+        assert_eq!(eclipsed, HashSet::from([systems.moon]));
+        assert_eq!(
+            find_eclipser(&systems.universe, &systems.positions, systems.moon),
+            Some(systems.planet)
+        );
+    }
+
+    #[test]
+    fn other_systems_are_left_out() {
+        let systems = Systems::new();
+        let eclipsed = eclipsed_bodies(
+            &systems.universe,
+            &systems.positions,
+            systems.far_planet,
+            &HashSet::new(),
+        );
+        assert_eq!(eclipsed, HashSet::from([systems.far_moon]));
+    }
+
+    #[test]
+    fn skipped_bodies_are_left_out() {
+        let systems = Systems::new();
+        let eclipsed = eclipsed_bodies(
+            &systems.universe,
+            &systems.positions,
+            systems.planet,
+            &HashSet::from([systems.moon]),
+        );
+        assert!(eclipsed.is_empty());
+    }
+}
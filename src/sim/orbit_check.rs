@@ -0,0 +1,310 @@
+//! Sweeping an orbit through its anomalies to find where the orbit math
+//! gives non-finite or jumping values.
+//!
+//! Near-parabolic orbits in particular can trip up keplerian_sim. The findings
+//! come with the exact elements, so they can be reported upstream as they are.
+
+use core::f64::consts::{PI, TAU};
+
+use keplerian_sim::{Orbit, OrbitTrait};
+use strum::{EnumCount, IntoEnumIterator};
+use strum_macros::{EnumCount, EnumIter};
+
+/// The keplerian_sim version the demo is built against, from build.rs.
+const KEPLERIAN_SIM_VERSION: &str = match option_env!("KEPLERIAN_SIM_VERSION") {
+    Some(v) => v,
+    None => "unknown",
+};
+
+/// How many mean anomalies the sweep checks by default.
+pub(crate) const DEFAULT_SAMPLE_COUNT: usize = 720;
+
+/// How far the sweep goes on either side of periapsis on open orbits, in radians
+/// of mean anomaly. Open orbits don't repeat, so some limit has to be picked.
+const OPEN_MEAN_ANOMALY_RANGE: f64 = 20.0;
+
+/// How many times bigger a step between samples has to be than the steps
+/// on either side of it to count as a jump.
+const JUMP_FACTOR: f64 = 20.0;
+
+/// Steps smaller than this, relative to the values around them,
+/// are never jumps, so rounding noise isn't flagged.
+const JUMP_NOISE: f64 = 1e-9;
+
+/// How far off the mean anomaly may come back after going to the time
+/// at the true anomaly and back, in radians.
+const ROUND_TRIP_TOLERANCE: f64 = 1e-6;
+
+/// A value computed at each sampled mean anomaly, named after the call it comes from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, EnumCount, EnumIter)]
+pub(crate) enum Quantity {
+    EccentricAnomaly,
+    TrueAnomaly,
+    Altitude,
+    Speed,
+    PqwPositionP,
+    PqwPositionQ,
+    PqwVelocityP,
+    PqwVelocityQ,
+    /// How far off the mean anomaly comes back after
+    /// `get_time_at_true_anomaly` and `get_mean_anomaly_at_time`.
+    TimeRoundTrip,
+}
+
+impl Quantity {
+    pub(crate) const fn call(self) -> &'static str {
+        match self {
+            Quantity::EccentricAnomaly => "get_eccentric_anomaly_at_mean_anomaly",
+            Quantity::TrueAnomaly => "get_true_anomaly_at_eccentric_anomaly",
+            Quantity::Altitude => "get_altitude_at_true_anomaly",
+            Quantity::Speed => "get_speed_at_altitude",
+            Quantity::PqwPositionP => "get_pqw_position_at_true_anomaly_unchecked (P)",
+            Quantity::PqwPositionQ => "get_pqw_position_at_true_anomaly_unchecked (Q)",
+            Quantity::PqwVelocityP => "get_pqw_velocity_at_eccentric_anomaly (P)",
+            Quantity::PqwVelocityQ => "get_pqw_velocity_at_eccentric_anomaly (Q)",
+            Quantity::TimeRoundTrip => "get_time_at_true_anomaly → get_mean_anomaly_at_time",
+        }
+    }
+
+    fn index(self) -> usize {
+        Self::iter().position(|q| q == self).unwrap_or(0)
+    }
+
+    /// Whether the quantity is an angle that may wrap around on closed orbits.
+    const fn is_angle(self) -> bool {
+        matches!(self, Quantity::EccentricAnomaly | Quantity::TrueAnomaly)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum IssueKind {
+    /// The value is NaN or infinite.
+    NonFinite,
+    /// The value jumped from `previous` for no reason.
+    Jump { previous: f64 },
+    /// The round trip came back this far off.
+    RoundTrip,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct Issue {
+    pub quantity: Quantity,
+    pub kind: IssueKind,
+    /// Where in the sweep the issue was found.
+    pub mean_anomaly: f64,
+    pub value: f64,
+}
+
+/// The values of each quantity at one mean anomaly.
+struct Sample {
+    mean_anomaly: f64,
+    values: [f64; Quantity::COUNT],
+}
+
+fn sample(orbit: &Orbit, mean_anomaly: f64) -> Sample {
+    let eccentric_anomaly = orbit.get_eccentric_anomaly_at_mean_anomaly(mean_anomaly);
+    let true_anomaly = orbit.get_true_anomaly_at_eccentric_anomaly(eccentric_anomaly);
+    let altitude = orbit.get_altitude_at_true_anomaly(true_anomaly);
+    let speed = orbit.get_speed_at_altitude(altitude);
+    let pqw_position =
+        orbit.get_pqw_position_at_true_anomaly_unchecked(altitude, true_anomaly.sin_cos());
+    let pqw_velocity = orbit.get_pqw_velocity_at_eccentric_anomaly(eccentric_anomaly);
+
+    let time = orbit.get_time_at_true_anomaly(true_anomaly);
+    let round_trip = orbit.get_mean_anomaly_at_time(time) - mean_anomaly;
+    let round_trip = if orbit.is_closed() {
+        wrapped(round_trip)
+    } else {
+        round_trip
+    };
+
+    let mut values = [0.0; Quantity::COUNT];
+    values[Quantity::EccentricAnomaly.index()] = eccentric_anomaly;
+    values[Quantity::TrueAnomaly.index()] = true_anomaly;
+    values[Quantity::Altitude.index()] = altitude;
+    values[Quantity::Speed.index()] = speed;
+    values[Quantity::PqwPositionP.index()] = pqw_position.x;
+    values[Quantity::PqwPositionQ.index()] = pqw_position.y;
+    values[Quantity::PqwVelocityP.index()] = pqw_velocity.x;
+    values[Quantity::PqwVelocityQ.index()] = pqw_velocity.y;
+    values[Quantity::TimeRoundTrip.index()] = round_trip;
+
+    Sample {
+        mean_anomaly,
+        values,
+    }
+}
+
+/// An angle moved into -π..π.
+fn wrapped(angle: f64) -> f64 {
+    (angle + PI).rem_euclid(TAU) - PI
+}
+
+/// The mean anomalies to sample, in order.
+fn mean_anomalies(orbit: &Orbit, sample_count: usize) -> impl Iterator<Item = f64> {
+    let (start, range) = if orbit.is_closed() {
+        (0.0, TAU)
+    } else {
+        (-OPEN_MEAN_ANOMALY_RANGE, 2.0 * OPEN_MEAN_ANOMALY_RANGE)
+    };
+    let step = range / sample_count.max(1) as f64;
+
+    (0..sample_count).map(move |i| start + i as f64 * step)
+}
+
+/// Evaluates `orbit` at `sample_count` evenly spaced mean anomalies,
+/// and returns everything that looks wrong, in sweep order.
+pub(crate) fn sweep(orbit: &Orbit, sample_count: usize) -> Vec<Issue> {
+    let samples: Vec<Sample> = mean_anomalies(orbit, sample_count)
+        .map(|mean_anomaly| sample(orbit, mean_anomaly))
+        .collect();
+    let closed = orbit.is_closed();
+
+    let mut issues = Vec::new();
+    for (i, sample) in samples.iter().enumerate() {
+        for quantity in Quantity::iter() {
+            let value = sample.values[quantity.index()];
+            let issue = |kind| Issue {
+                quantity,
+                kind,
+                mean_anomaly: sample.mean_anomaly,
+                value,
+            };
+
+            if !value.is_finite() {
+                issues.push(issue(IssueKind::NonFinite));
+                continue;
+            }
+            if quantity == Quantity::TimeRoundTrip {
+                if value.abs() > ROUND_TRIP_TOLERANCE {
+                    issues.push(issue(IssueKind::RoundTrip));
+                }
+                continue;
+            }
+
+            let step = |a: usize, b: usize| {
+                let a = samples.get(a)?.values[quantity.index()];
+                let b = samples.get(b)?.values[quantity.index()];
+                let step = b - a;
+                let step = if closed && quantity.is_angle() {
+                    wrapped(step)
+                } else {
+                    step
+                };
+                step.is_finite().then_some(step.abs())
+            };
+
+            // Compared against the steps on either side, so values
+            // that change quickly but smoothly aren't flagged
+            let (Some(before), Some(this), Some(after)) = (
+                i.checked_sub(2).and_then(|j| step(j, j + 1)),
+                step(i.wrapping_sub(1), i),
+                step(i, i + 1),
+            ) else {
+                continue;
+            };
+            let noise = JUMP_NOISE * value.abs().max(1.0);
+            if this > noise && this > JUMP_FACTOR * before.max(after) {
+                let previous = samples[i - 1].values[quantity.index()];
+                issues.push(issue(IssueKind::Jump { previous }));
+            }
+        }
+    }
+    issues
+}
+
+/// Writes the issues up for a bug report, starting with the orbit
+/// as exact Rust code to reproduce it with.
+pub(crate) fn report(orbit: &Orbit, issues: &[Issue]) -> String {
+    let mut report = format!(
+        "keplerian_sim {}\n\
+        let orbit = Orbit::new(\n    \
+            {:?}, // eccentricity\n    \
+            {:?}, // periapsis\n    \
+            {:?}, // inclination\n    \
+            {:?}, // argument of periapsis\n    \
+            {:?}, // longitude of ascending node\n    \
+            {:?}, // mean anomaly at epoch\n    \
+            {:?}, // gravitational parameter\n\
+        );\n",
+        KEPLERIAN_SIM_VERSION,
+        orbit.get_eccentricity(),
+        orbit.get_periapsis(),
+        orbit.get_inclination(),
+        orbit.get_arg_pe(),
+        orbit.get_long_asc_node(),
+        orbit.get_mean_anomaly_at_epoch(),
+        orbit.get_gravitational_parameter(),
+    );
+
+    for issue in issues {
+        let finding = match issue.kind {
+            IssueKind::NonFinite => format!("returned {:?}", issue.value),
+            IssueKind::Jump { previous } => {
+                format!("jumped from {previous:?} to {:?}", issue.value)
+            }
+            IssueKind::RoundTrip => format!("came back {:?} rad off", issue.value),
+        };
+        report.push_str(&format!(
+            "\nAt mean anomaly {:?}: {} {finding}",
+            issue.mean_anomaly,
+            issue.quantity.call(),
+        ));
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MU: f64 = 3.986e14;
+
+    fn orbit(eccentricity: f64) -> Orbit {
+        Orbit::new(eccentricity, 7e6, 0.3, 0.5, 1.0, 0.0, MU)
+    }
+
+    #[test]
+    fn ordinary_orbits_have_no_issues() {
+        for eccentricity in [0.0, 0.3, 0.7, 2.0] {
+            let issues = sweep(&orbit(eccentricity), DEFAULT_SAMPLE_COUNT);
+            assert!(
+                issues.is_empty(),
+                "e = {eccentricity}: {:?}",
+                issues.first()
+            );
+        }
+    }
+
+    #[test]
+    fn closed_sweeps_cover_one_orbit() {
+        let anomalies: Vec<f64> = mean_anomalies(&orbit(0.5), 4).collect();
+        assert_eq!(anomalies, vec![0.0, PI / 2.0, PI, 3.0 * PI / 2.0]);
+
+        let anomalies: Vec<f64> = mean_anomalies(&orbit(2.0), 2).collect();
+        assert_eq!(anomalies, vec![-OPEN_MEAN_ANOMALY_RANGE, 0.0]);
+    }
+
+    #[test]
+    fn angles_wrap_around() {
+        assert!((wrapped(TAU - 0.1) + 0.1).abs() < 1e-12);
+        assert!((wrapped(-TAU + 0.1) - 0.1).abs() < 1e-12);
+        assert_eq!(wrapped(0.5), 0.5);
+    }
+
+    #[test]
+    fn reports_have_the_exact_elements() {
+        let orbit = orbit(0.3);
+        let issue = Issue {
+            quantity: Quantity::Altitude,
+            kind: IssueKind::NonFinite,
+            mean_anomaly: 1.0,
+            value: f64::NAN,
+        };
+        let report = report(&orbit, &[issue]);
+
+        assert!(report.contains(&format!("{:?}, // eccentricity", 0.3)));
+        assert!(report.contains(&format!("{MU:?}, // gravitational parameter")));
+        assert!(report.contains("get_altitude_at_true_anomaly returned NaN"));
+    }
+}
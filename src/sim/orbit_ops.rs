@@ -0,0 +1,171 @@
+//! Changing orbits through their state vectors.
+
+use std::{error::Error, fmt};
+
+use glam::DVec3;
+use keplerian_sim::{Orbit, OrbitTrait, StateVectors};
+
+use crate::sim::universe::{Id, Universe};
+
+/// An instantaneous change in velocity, relative to the direction of motion.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub(crate) struct Kick {
+    /// Along the direction of motion, in m/s.
+    pub prograde: f64,
+    /// Along the orbit normal, in m/s.
+    pub normal: f64,
+    /// Away from the parent, perpendicular to the direction of motion, in m/s.
+    pub radial: f64,
+}
+
+impl Kick {
+    /// The total change in velocity, in m/s.
+    pub(crate) fn delta_v(&self) -> f64 {
+        DVec3::new(self.prograde, self.normal, self.radial).length()
+    }
+
+    /// The velocity after the kick, for a body at `position` moving at `velocity`.
+    pub(crate) fn apply_to(&self, position: DVec3, velocity: DVec3) -> DVec3 {
+        let prograde = velocity.normalize_or_zero();
+        let normal = position.cross(velocity).normalize_or_zero();
+        let radial = prograde.cross(normal);
+
+        velocity + prograde * self.prograde + normal * self.normal + radial * self.radial
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum KickError {
+    BodyNotFound,
+    /// The body isn't orbiting anything, so there's no orbit to change.
+    NoOrbit,
+    /// The kick leaves the body on an orbit that can't be worked out,
+    /// like one that's exactly parabolic.
+    InvalidOrbit,
+}
+
+impl fmt::Display for KickError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KickError::BodyNotFound => write!(f, "the body doesn't exist"),
+            KickError::NoOrbit => write!(f, "the body isn't orbiting anything"),
+            KickError::InvalidOrbit => write!(f, "the resulting orbit isn't valid"),
+        }
+    }
+}
+
+impl Error for KickError {}
+
+/// The orbit after applying `kick` to a body on `orbit` at `time`.
+pub(crate) fn kicked_orbit(orbit: &Orbit, kick: Kick, time: f64) -> Result<Orbit, KickError> {
+    let StateVectors { position, velocity } = orbit.get_state_vectors_at_time(time);
+    let velocity = kick.apply_to(position, velocity);
+
+    let mu = orbit.get_gravitational_parameter();
+    let kicked = StateVectors { position, velocity }.to_cached_orbit(mu, time);
+
+    let valid = kicked.get_eccentricity().is_finite() && kicked.get_periapsis().is_finite();
+    if valid {
+        Ok(kicked)
+    } else {
+        Err(KickError::InvalidOrbit)
+    }
+}
+
+/// Applies `kick` to a body at its current time.
+///
+/// Frozen bodies are kicked where they were frozen.
+pub(crate) fn kick_body(universe: &mut Universe, body: Id, kick: Kick) -> Result<(), KickError> {
+    let time = universe
+        .get_body_time(body)
+        .ok_or(KickError::BodyNotFound)?;
+    let orbit = universe
+        .get_body(body)
+        .ok_or(KickError::BodyNotFound)?
+        .body
+        .orbit
+        .as_ref()
+        .ok_or(KickError::NoOrbit)?;
+
+    let kicked = kicked_orbit(orbit, kick, time)?;
+
+    if let Some(wrapper) = universe.get_body_mut(body) {
+        wrapper.body.orbit = Some(kicked);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn circular_orbit() -> Orbit {
+        // A low Earth orbit
+        Orbit::new(0.0, 7e6, 0.0, 0.0, 0.0, 0.0, 3.986e14)
+    }
+
+    #[test]
+    fn kick_directions_follow_the_motion() {
+        let position = DVec3::new(1.0, 0.0, 0.0);
+        let velocity = DVec3::new(0.0, 2.0, 0.0);
+
+        let prograde = Kick {
+            prograde: 1.0,
+            ..Default::default()
+        };
+        assert_eq!(
+            prograde.apply_to(position, velocity),
+            DVec3::new(0.0, 3.0, 0.0)
+        );
+
+        let normal = Kick {
+            normal: 1.0,
+            ..Default::default()
+        };
+        assert_eq!(
+            normal.apply_to(position, velocity),
+            DVec3::new(0.0, 2.0, 1.0)
+        );
+
+        let radial = Kick {
+            radial: 1.0,
+            ..Default::default()
+        };
+        assert_eq!(
+            radial.apply_to(position, velocity),
+            DVec3::new(1.0, 2.0, 0.0)
+        );
+    }
+
+    #[test]
+    fn empty_kick_keeps_the_orbit() {
+        let orbit = circular_orbit();
+        let kicked = kicked_orbit(&orbit, Kick::default(), 1234.0).unwrap();
+
+        let before = orbit.get_position_at_time(5000.0);
+        let after = kicked.get_position_at_time(5000.0);
+        assert!((before - after).length() < 1e-3);
+    }
+
+    #[test]
+    fn prograde_kick_raises_the_far_side() {
+        let orbit = circular_orbit();
+        let kick = Kick {
+            prograde: 100.0,
+            ..Default::default()
+        };
+        let kicked = kicked_orbit(&orbit, kick, 0.0).unwrap();
+
+        assert!((kicked.get_periapsis() - 7e6).abs() < 1.0);
+        assert!(kicked.get_apoapsis() > 7e6 + 1e5);
+    }
+
+    #[test]
+    fn kicks_missing_bodies_fail() {
+        let mut universe = Universe::default();
+        assert_eq!(
+            kick_body(&mut universe, 0, Kick::default()),
+            Err(KickError::BodyNotFound)
+        );
+    }
+}
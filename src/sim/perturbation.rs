@@ -0,0 +1,357 @@
+//! Corrections applied on top of the Keplerian orbits every tick.
+//!
+//! Each pass nudges orbital elements by how much they would drift over
+//! the tick, so bodies still follow plain Keplerian orbits in between.
+
+use core::f64::consts::{PI, TAU};
+
+use keplerian_sim::{Orbit, OrbitTrait};
+use strum::{EnumCount, IntoEnumIterator};
+use strum_macros::{EnumCount, EnumIter};
+
+use crate::sim::{
+    generator::Rng,
+    universe::{Id, Universe},
+};
+
+/// The speed of light in a vacuum, in m/s.
+const SPEED_OF_LIGHT: f64 = 299_792_458.0;
+
+/// How close jittered eccentricities may get to 1,
+/// since parabolic orbits aren't supported.
+const PARABOLIC_MARGIN: f64 = 1e-6;
+
+/// Perturbation settings shared by the whole universe.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PerturbationSettings {
+    /// A multiplier on the relativistic apsidal precession rate.
+    ///
+    /// At 1, the rate is the real one, about 43″ per century for Mercury.
+    /// Larger values make the precession visible in a reasonable time.
+    pub precession_scale: f64,
+    /// The seed the element jitter's random walks started from.
+    jitter_seed: u64,
+    /// Where the element jitter's random walks are at. Kept with the universe,
+    /// so the same seed gives the same nudges when ticked the same way.
+    jitter_rng: Rng,
+}
+
+impl Default for PerturbationSettings {
+    fn default() -> Self {
+        Self {
+            precession_scale: 1.0,
+            jitter_seed: 0,
+            jitter_rng: Rng::new(0),
+        }
+    }
+}
+
+impl PerturbationSettings {
+    pub(crate) fn jitter_seed(&self) -> u64 {
+        self.jitter_seed
+    }
+
+    /// Restarts the element jitter's random walks from `seed`.
+    pub(crate) fn set_jitter_seed(&mut self, seed: u64) {
+        self.jitter_seed = seed;
+        self.jitter_rng = Rng::new(seed);
+    }
+}
+
+/// Which perturbations a body is affected by.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct BodyPerturbations {
+    /// Whether the body's periapsis slowly rotates forward,
+    /// as predicted by general relativity.
+    pub relativistic_precession: bool,
+    /// Random walks of the body's orbital elements.
+    pub jitter: ElementJitter,
+}
+
+/// An orbital element that can be jittered.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, EnumCount, EnumIter)]
+pub enum JitterElement {
+    Eccentricity,
+    Periapsis,
+    Inclination,
+    ArgPe,
+    LongAscNode,
+    MeanAnomaly,
+}
+
+impl JitterElement {
+    pub(crate) const fn name(self) -> &'static str {
+        match self {
+            JitterElement::Eccentricity => "Eccentricity",
+            JitterElement::Periapsis => "Periapsis",
+            JitterElement::Inclination => "Inclination",
+            JitterElement::ArgPe => "Arg. of Pe.",
+            JitterElement::LongAscNode => "RAAN",
+            JitterElement::MeanAnomaly => "Mean anomaly",
+        }
+    }
+
+    fn index(self) -> usize {
+        Self::iter().position(|e| e == self).unwrap_or(0)
+    }
+}
+
+/// Small random walks of some of a body's orbital elements, to show how
+/// a family of similar orbits spreads out, without simulating n-body gravity.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ElementJitter {
+    /// How far the elements typically wander per orbit: as is for the
+    /// eccentricity, as a fraction of the periapsis for the periapsis,
+    /// and as a fraction of a full turn for angles.
+    pub amplitude: f64,
+    enabled: [bool; JitterElement::COUNT],
+}
+
+impl ElementJitter {
+    pub(crate) fn is_enabled(&self, element: JitterElement) -> bool {
+        self.enabled[element.index()]
+    }
+
+    pub(crate) fn set_enabled(&mut self, element: JitterElement, enabled: bool) {
+        self.enabled[element.index()] = enabled;
+    }
+
+    /// Whether any element wanders at all.
+    pub(crate) fn is_active(&self) -> bool {
+        self.amplitude > 0.0 && self.enabled.contains(&true)
+    }
+}
+
+/// Gets the rate the periapsis of an orbit rotates at
+/// due to general relativity, in radians per second.
+///
+/// Open orbits never come back around, so their rate is 0.
+pub(crate) fn relativistic_precession_rate(orbit: &impl OrbitTrait) -> f64 {
+    let eccentricity = orbit.get_eccentricity();
+
+    if eccentricity >= 1.0 {
+        return 0.0;
+    }
+
+    // Δω = 6πµ / (c²a(1 - e²)) per orbit, and there are
+    // n / 2π = √(µ/a³) / 2π orbits per second
+    let mu = orbit.get_gravitational_parameter();
+    let semi_major_axis = orbit.get_semi_major_axis();
+
+    3.0 * mu.powf(1.5)
+        / (SPEED_OF_LIGHT.powi(2) * semi_major_axis.powf(2.5) * (1.0 - eccentricity.powi(2)))
+}
+
+/// Applies every perturbation pass to the universe over a timestep.
+pub(crate) fn apply(universe: &mut Universe, dt: f64) {
+    relativistic_precession_pass(universe, dt);
+    jitter_pass(universe, dt);
+}
+
+fn relativistic_precession_pass(universe: &mut Universe, dt: f64) {
+    let scale = universe.perturbation_settings.precession_scale;
+
+    if scale == 0.0 {
+        return;
+    }
+
+    // Frozen bodies don't move along their orbit, so their orbit doesn't drift either
+    let ids: Vec<Id> = universe
+        .get_bodies()
+        .iter()
+        .filter(|(_, w)| w.perturbations.relativistic_precession && w.frozen_at.is_none())
+        .map(|(&id, _)| id)
+        .collect();
+
+    for id in ids {
+        let Some(orbit) = universe
+            .get_body_mut(id)
+            .and_then(|w| w.body.orbit.as_mut())
+        else {
+            continue;
+        };
+
+        let rate = relativistic_precession_rate(orbit) * scale;
+        orbit.set_arg_pe((orbit.get_arg_pe() + rate * dt).rem_euclid(TAU));
+    }
+}
+
+fn jitter_pass(universe: &mut Universe, dt: f64) {
+    // Random walks can't be retraced, so they only happen going forwards
+    if dt <= 0.0 {
+        return;
+    }
+
+    // Sorted, so the same seed nudges the same bodies the same way
+    let mut ids: Vec<Id> = universe
+        .get_bodies()
+        .iter()
+        .filter(|(_, w)| w.perturbations.jitter.is_active() && w.frozen_at.is_none())
+        .map(|(&id, _)| id)
+        .collect();
+    if ids.is_empty() {
+        return;
+    }
+    ids.sort_unstable();
+
+    let time = universe.time;
+    let mut rng = universe.perturbation_settings.jitter_rng;
+    for id in ids {
+        let Some(wrapper) = universe.get_body_mut(id) else {
+            continue;
+        };
+        let jitter = wrapper.perturbations.jitter;
+        if let Some(orbit) = wrapper.body.orbit.as_mut() {
+            jitter_orbit(orbit, jitter, time, dt, &mut rng);
+        }
+    }
+    universe.perturbation_settings.jitter_rng = rng;
+}
+
+/// Nudges the orbit's jittered elements by a random step over `dt` seconds.
+///
+/// Steps grow with the square root of time, like any random walk, and are
+/// sized so the elements wander by about the amplitude every orbit.
+/// On open orbits, the time the body takes to sweep one radian
+/// of hyperbolic mean anomaly, times 2π, stands in for the period.
+///
+/// The body keeps its mean anomaly at `time`, so changing the orbit's
+/// size doesn't also change where along it the body is.
+fn jitter_orbit(orbit: &mut Orbit, jitter: ElementJitter, time: f64, dt: f64, rng: &mut Rng) {
+    if !jitter.is_active() {
+        return;
+    }
+    let orbit_time = TAU / orbit.get_mean_motion();
+    let scale = jitter.amplitude * (dt / orbit_time).sqrt();
+    if !scale.is_finite() {
+        return;
+    }
+
+    // Spread evenly over ±√3, which gives a standard deviation of 1
+    let mut step = || scale * rng.range(-3f64.sqrt(), 3f64.sqrt());
+
+    let mut mean_anomaly = orbit.get_mean_anomaly_at_time(time);
+
+    for element in JitterElement::iter().filter(|&e| jitter.is_enabled(e)) {
+        match element {
+            JitterElement::Eccentricity => {
+                let eccentricity = orbit.get_eccentricity();
+                // Kept on the same side of 1, so the orbit doesn't
+                // flip between closed and open
+                let jittered = if eccentricity < 1.0 {
+                    (eccentricity + step()).clamp(0.0, 1.0 - PARABOLIC_MARGIN)
+                } else {
+                    (eccentricity + step()).max(1.0 + PARABOLIC_MARGIN)
+                };
+                orbit.set_eccentricity(jittered);
+            }
+            JitterElement::Periapsis => {
+                // Multiplied, so it never reaches 0
+                orbit.set_periapsis(orbit.get_periapsis() * step().exp());
+            }
+            JitterElement::Inclination => {
+                let inclination = orbit.get_inclination() + TAU * step();
+                orbit.set_inclination(inclination.clamp(0.0, PI));
+            }
+            JitterElement::ArgPe => {
+                orbit.set_arg_pe((orbit.get_arg_pe() + TAU * step()).rem_euclid(TAU));
+            }
+            JitterElement::LongAscNode => {
+                let long_asc_node = orbit.get_long_asc_node() + TAU * step();
+                orbit.set_long_asc_node(long_asc_node.rem_euclid(TAU));
+            }
+            JitterElement::MeanAnomaly => {
+                mean_anomaly += TAU * step();
+            }
+        }
+    }
+
+    // The mean motion changes with the size of the orbit, so the epoch
+    // is moved to keep the body where it was, like unfreezing does
+    let mean_anomaly_at_epoch = mean_anomaly - orbit.get_mean_motion() * time;
+    let mean_anomaly_at_epoch = if orbit.is_closed() {
+        mean_anomaly_at_epoch.rem_euclid(TAU)
+    } else {
+        mean_anomaly_at_epoch
+    };
+    orbit.set_mean_anomaly_at_epoch(mean_anomaly_at_epoch);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MU: f64 = 3.986e14;
+
+    fn jitter(amplitude: f64) -> ElementJitter {
+        let mut jitter = ElementJitter {
+            amplitude,
+            ..Default::default()
+        };
+        JitterElement::iter().for_each(|e| jitter.set_enabled(e, true));
+        jitter
+    }
+
+    fn jittered(eccentricity: f64, amplitude: f64, seed: u64) -> Orbit {
+        let mut orbit = Orbit::new(eccentricity, 7e6, 0.3, 0.5, 1.0, 0.0, MU);
+        let mut rng = Rng::new(seed);
+        let period = TAU / orbit.get_mean_motion();
+        for _ in 0..200 {
+            jitter_orbit(&mut orbit, jitter(amplitude), 0.0, period / 10.0, &mut rng);
+        }
+        orbit
+    }
+
+    #[test]
+    fn same_seed_gives_same_walk() {
+        assert_eq!(jittered(0.3, 0.01, 7), jittered(0.3, 0.01, 7));
+        assert_ne!(jittered(0.3, 0.01, 7), jittered(0.3, 0.01, 8));
+    }
+
+    #[test]
+    fn no_amplitude_changes_nothing() {
+        let orbit = Orbit::new(0.3, 7e6, 0.3, 0.5, 1.0, 0.0, MU);
+        assert_eq!(jittered(0.3, 0.0, 7), orbit);
+        assert!(!jitter(0.0).is_active());
+    }
+
+    #[test]
+    fn orbits_stay_closed_or_open() {
+        for seed in 0..20 {
+            let closed = jittered(0.9, 0.5, seed);
+            assert!(closed.get_eccentricity() < 1.0);
+            assert!(closed.get_periapsis() > 0.0);
+            assert!((0.0..=PI).contains(&closed.get_inclination()));
+
+            let open = jittered(1.1, 0.5, seed);
+            assert!(open.get_eccentricity() > 1.0);
+        }
+    }
+
+    #[test]
+    fn jitter_keeps_the_body_in_place_late_on() {
+        let mut orbit = Orbit::new(0.3, 7e6, 0.3, 0.5, 1.0, 0.0, MU);
+        let period = TAU / orbit.get_mean_motion();
+        // Long enough that a slightly different mean motion
+        // would put the body somewhere else entirely
+        let time = 1e5 * period;
+        let dt = period / 100.0;
+
+        let mut jitter = ElementJitter {
+            amplitude: 1e-3,
+            ..Default::default()
+        };
+        jitter.set_enabled(JitterElement::Periapsis, true);
+        jitter.set_enabled(JitterElement::Eccentricity, true);
+
+        let mut rng = Rng::new(3);
+        for _ in 0..10 {
+            let before = orbit.get_position_at_time(time);
+            jitter_orbit(&mut orbit, jitter, time, dt, &mut rng);
+            let after = orbit.get_position_at_time(time);
+
+            let jump = (after - before).length() / before.length();
+            assert!(jump < 1e-2, "the body jumped by {jump} of its distance");
+        }
+    }
+}
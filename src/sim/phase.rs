@@ -0,0 +1,202 @@
+//! The phase angle between two bodies orbiting the same parent,
+//! and how long until it reaches a given value, e.g. for lining up
+//! a conjunction or the departure of a transfer orbit.
+
+use core::f64::consts::{PI, TAU};
+use std::{error::Error, fmt};
+
+use glam::DVec3;
+use keplerian_sim::{Orbit, OrbitTrait};
+
+use crate::sim::{
+    roots,
+    universe::{Id, Universe},
+};
+
+/// How many steps the search takes per orbit of the faster body.
+/// Phase angles are sampled this often while looking for a crossing.
+const STEPS_PER_ORBIT: f64 = 64.0;
+
+/// The most steps the search takes, however slowly the phase changes.
+const MAX_STEPS: usize = 8192;
+
+/// How many synodic periods ahead to search.
+/// A little over one, so a target that was just passed is still found.
+const SEARCH_SYNODIC_PERIODS: f64 = 1.25;
+
+/// The search stops narrowing down a time once it is
+/// this precise, relative to one step.
+const RELATIVE_TOLERANCE: f64 = 1e-9;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PhaseError {
+    /// The bodies don't orbit the same parent, or one of them isn't orbiting at all.
+    NotSiblings,
+    /// One of the bodies is on an open orbit, so it has no period.
+    OpenOrbit,
+    /// The bodies orbit at the same rate, or are both frozen,
+    /// so their phase angle never changes.
+    Stationary,
+}
+
+impl fmt::Display for PhaseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PhaseError::NotSiblings => write!(f, "the bodies don't orbit the same parent"),
+            PhaseError::OpenOrbit => write!(f, "one of the bodies is on an open orbit"),
+            PhaseError::Stationary => write!(f, "the phase angle between the bodies never changes"),
+        }
+    }
+}
+
+impl Error for PhaseError {}
+
+/// The two orbits, and how fast each body goes around, in orbits per second.
+/// Frozen bodies don't go around at all.
+struct Pair<'a> {
+    from: (&'a Orbit, Option<f64>),
+    to: (&'a Orbit, Option<f64>),
+    from_rate: f64,
+    to_rate: f64,
+}
+
+impl<'a> Pair<'a> {
+    fn new(universe: &'a Universe, from: Id, to: Id) -> Result<Self, PhaseError> {
+        let from = universe.get_body(from).ok_or(PhaseError::NotSiblings)?;
+        let to = universe.get_body(to).ok_or(PhaseError::NotSiblings)?;
+
+        let siblings =
+            from.relations.parent.is_some() && from.relations.parent == to.relations.parent;
+        let (Some(from_orbit), Some(to_orbit), true) = (&from.body.orbit, &to.body.orbit, siblings)
+        else {
+            return Err(PhaseError::NotSiblings);
+        };
+
+        if !from_orbit.is_closed() || !to_orbit.is_closed() {
+            return Err(PhaseError::OpenOrbit);
+        }
+
+        let rate = |orbit: &Orbit, frozen_at: Option<f64>| match frozen_at {
+            Some(_) => 0.0,
+            None => orbit.get_orbital_period().recip(),
+        };
+
+        Ok(Self {
+            from_rate: rate(from_orbit, from.frozen_at),
+            to_rate: rate(to_orbit, to.frozen_at),
+            from: (from_orbit, from.frozen_at),
+            to: (to_orbit, to.frozen_at),
+        })
+    }
+
+    fn synodic_period(&self) -> Result<f64, PhaseError> {
+        let relative_rate = (self.from_rate - self.to_rate).abs();
+
+        if relative_rate == 0.0 {
+            return Err(PhaseError::Stationary);
+        }
+
+        Ok(relative_rate.recip())
+    }
+
+    fn phase_angle(&self, time: f64) -> f64 {
+        let position = |(orbit, frozen_at): (&Orbit, Option<f64>)| {
+            orbit.get_position_at_time(frozen_at.unwrap_or(time))
+        };
+
+        angle_between(
+            position(self.from),
+            position(self.to),
+            orbit_normal(self.from.0),
+        )
+    }
+}
+
+/// The direction of an orbit's angular momentum.
+fn orbit_normal(orbit: &impl OrbitTrait) -> DVec3 {
+    let (sin_inc, cos_inc) = orbit.get_inclination().sin_cos();
+    let (sin_lan, cos_lan) = orbit.get_long_asc_node().sin_cos();
+
+    DVec3::new(sin_lan * sin_inc, -cos_lan * sin_inc, cos_inc)
+}
+
+/// How far `to` is ahead of `from`, going around `normal`,
+/// after projecting `to` into the plane `normal` is perpendicular to.
+///
+/// Returns: The angle, in radians, from 0 to 2π.
+fn angle_between(from: DVec3, to: DVec3, normal: DVec3) -> f64 {
+    let to = to - normal * to.dot(normal);
+    let angle = normal.dot(from.cross(to)).atan2(from.dot(to));
+
+    angle.rem_euclid(TAU)
+}
+
+/// Gets how far `to` is ahead of `from` along `from`'s orbit,
+/// as seen from their shared parent.
+///
+/// 0 means they're lined up on the same side of the parent (a conjunction),
+/// and π means they're on opposite sides (an opposition).
+///
+/// Returns: The phase angle, in radians, from 0 to 2π.
+pub(crate) fn phase_angle(universe: &Universe, from: Id, to: Id) -> Result<f64, PhaseError> {
+    Pair::new(universe, from, to).map(|pair| pair.phase_angle(universe.time))
+}
+
+/// Gets how long it takes for the phase angle between the bodies
+/// to come back around to the same value, in seconds.
+pub(crate) fn synodic_period(universe: &Universe, from: Id, to: Id) -> Result<f64, PhaseError> {
+    Pair::new(universe, from, to)?.synodic_period()
+}
+
+/// Gets how long until `to` is `target` radians ahead of `from`,
+/// in seconds from the universe's current time.
+///
+/// Returns Ok(None) if the phase angle isn't reached in the searched time,
+/// which can happen if it's only reached for an instant between two samples.
+pub(crate) fn time_until_phase(
+    universe: &Universe,
+    from: Id,
+    to: Id,
+    target: f64,
+) -> Result<Option<f64>, PhaseError> {
+    let pair = Pair::new(universe, from, to)?;
+    let synodic_period = pair.synodic_period()?;
+
+    let fastest_period = pair.from_rate.max(pair.to_rate).recip();
+    let horizon = synodic_period * SEARCH_SYNODIC_PERIODS;
+    let steps = (horizon / fastest_period * STEPS_PER_ORBIT)
+        .ceil()
+        .clamp(1.0, MAX_STEPS as f64) as usize;
+    let tolerance = horizon / steps as f64 * RELATIVE_TOLERANCE;
+
+    // The difference wraps from π to -π halfway around from the target,
+    // which find_first_root skips with a max jump of π
+    let now = universe.time;
+    let difference = |dt: f64| (pair.phase_angle(now + dt) - target + PI).rem_euclid(TAU) - PI;
+
+    Ok(roots::find_first_root(
+        difference, 0.0, horizon, steps, tolerance, PI,
+    ))
+}
+
+/// Gets the phase angle `to` needs to be ahead of `from`
+/// to be met by a Hohmann transfer orbit that leaves from `from`'s orbit.
+///
+/// This treats both orbits as circles of their semi-major axes.
+///
+/// Returns: The phase angle, in radians, from 0 to 2π.
+pub(crate) fn hohmann_phase_angle(
+    universe: &Universe,
+    from: Id,
+    to: Id,
+) -> Result<f64, PhaseError> {
+    let pair = Pair::new(universe, from, to)?;
+    let r1 = pair.from.0.get_semi_major_axis();
+    let r2 = pair.to.0.get_semi_major_axis();
+
+    // During the transfer's half orbit, the target moves π((r1 + r2) / 2r2)^1.5,
+    // and has to end up π ahead of where the transfer started
+    let target_travel = PI * ((r1 + r2) / (2.0 * r2)).powf(1.5);
+
+    Ok((PI - target_travel).rem_euclid(TAU))
+}
@@ -0,0 +1,42 @@
+//! Reuses the positions of all bodies between frames while nothing moves,
+//! e.g. when the simulation is paused or every body is frozen.
+
+use std::{collections::HashMap, sync::Arc};
+
+use glam::DVec3;
+
+use crate::sim::universe::{Id, Universe};
+
+#[derive(Clone, Debug, Default)]
+pub(crate) struct PositionCache {
+    /// The universe time (as bits, so it compares exactly)
+    /// and structure version the positions were computed at.
+    key: Option<(u64, u64)>,
+    positions: Arc<HashMap<Id, DVec3>>,
+}
+
+impl PositionCache {
+    /// Gets the positions of all bodies in the universe,
+    /// only recomputing them if the time or the bodies changed.
+    pub(crate) fn positions(&mut self, universe: &Universe) -> Arc<HashMap<Id, DVec3>> {
+        let key = (universe.time.to_bits(), universe.structure_version());
+
+        if self.key != Some(key) {
+            match Arc::get_mut(&mut self.positions) {
+                Some(map) => universe.fill_all_body_positions(map),
+                // Last frame's positions are still being used somewhere
+                None => self.positions = Arc::new(universe.get_all_body_positions()),
+            }
+            self.key = Some(key);
+        }
+
+        Arc::clone(&self.positions)
+    }
+
+    /// Keeps positions worked out elsewhere, like on a worker thread,
+    /// for the universe as it is now.
+    pub(crate) fn insert(&mut self, universe: &Universe, positions: HashMap<Id, DVec3>) {
+        self.key = Some((universe.time.to_bits(), universe.structure_version()));
+        self.positions = Arc::new(positions);
+    }
+}
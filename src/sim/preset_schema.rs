@@ -0,0 +1,601 @@
+//! The format of `presets.toml`.
+//!
+//! This file is shared between the app and `build.rs`,
+//! so that the in-app preset editor accepts exactly what the build does.
+//! It must only depend on `std` and `toml`.
+
+use std::{
+    borrow::Cow,
+    error::Error,
+    fmt::{self, Display},
+};
+
+use toml::{
+    Spanned,
+    de::{DeArray, DeInteger, DeTable, DeValue},
+};
+
+pub(crate) const KEY_NAME: &str = "name";
+pub(crate) const KEY_DOCNAME: &str = "docname";
+pub(crate) const KEY_DESC: &str = "description";
+pub(crate) const KEY_MASS: &str = "mass";
+pub(crate) const KEY_RADIUS: &str = "radius";
+pub(crate) const KEY_APOAPSIS: &str = "apoapsis";
+pub(crate) const KEY_ECCENTRICITY: &str = "eccentricity";
+pub(crate) const KEY_PERIAPSIS: &str = "periapsis";
+pub(crate) const KEY_INCLINATION: &str = "inclination";
+pub(crate) const KEY_ARG_PE: &str = "arg_pe";
+pub(crate) const KEY_LONG_ASC_NODE: &str = "long_asc_node";
+pub(crate) const KEY_MEAN_ANOMALY: &str = "mean_anomaly";
+pub(crate) const KEY_COLOR: &str = "color";
+pub(crate) const KEY_EPOCH: &str = "epoch";
+
+/// The name used in errors about the file as a whole.
+const FILE_ENTRY_NAME: &str = "presets file";
+
+/// How the shape of a preset's orbit is given.
+///
+/// Some sources give the eccentricity, some give the apoapsis;
+/// an entry must pick exactly one.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum EccentricityDefiner {
+    Eccentricity(f64),
+    /// The apoapsis distance, in meters.
+    Apoapsis(f64),
+}
+
+/// One `[table]` of the presets file.
+///
+/// Angles are in degrees, as they are in the file.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct PresetEntry {
+    /// The table name, which becomes the preset's function name.
+    pub key: String,
+    pub name: String,
+    pub docname: Option<String>,
+    pub description: Option<String>,
+    /// In kilograms.
+    pub mass: f64,
+    /// In meters.
+    pub radius: f64,
+    pub ecc_definer: EccentricityDefiner,
+    /// In meters.
+    pub periapsis: f64,
+    pub inclination: f64,
+    pub arg_pe: f64,
+    pub long_asc_node: f64,
+    pub mean_anomaly: f64,
+    /// sRGBA.
+    pub color: [u8; 4],
+    /// The Julian Date the mean anomaly is measured at,
+    /// if it differs from the file's.
+    pub epoch: Option<f64>,
+}
+
+/// The whole presets file.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub(crate) struct PresetCatalog {
+    /// The Julian Date entries' mean anomalies are measured at by default.
+    pub epoch: Option<f64>,
+    pub entries: Vec<PresetEntry>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct PresetError {
+    /// The table name of the entry, or "presets file".
+    pub entry: String,
+    pub cause: PresetErrorCause,
+}
+
+#[non_exhaustive]
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum PresetErrorCause {
+    /// The text isn't valid TOML.
+    Syntax(String),
+    NotATable,
+    InvalidKey,
+    MissingField(&'static str),
+    WrongType {
+        key: &'static str,
+        expected: &'static str,
+    },
+    InvalidFloat {
+        key: &'static str,
+        reason: String,
+    },
+    NoEccentricityDefiner,
+    BothEccentricityDefiners,
+    InvalidColor {
+        key: &'static str,
+        reason: String,
+    },
+}
+
+impl Display for PresetErrorCause {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PresetErrorCause::Syntax(e) => write!(f, "failed to parse as TOML: {e}"),
+            PresetErrorCause::NotATable => write!(f, "expected table"),
+            PresetErrorCause::InvalidKey => write!(
+                f,
+                "table name must be a snake_case identifier, as it becomes a function name"
+            ),
+            PresetErrorCause::MissingField(key) => write!(f, "missing required field {key}"),
+            PresetErrorCause::WrongType { key, expected } => {
+                write!(f, "expected field {key} to be {expected}")
+            }
+            PresetErrorCause::InvalidFloat { key, reason } => {
+                write!(f, "failed to parse field {key} as float: {reason}")
+            }
+            PresetErrorCause::NoEccentricityDefiner => write!(
+                f,
+                "missing either {KEY_APOAPSIS} or {KEY_ECCENTRICITY} (pick one and define)"
+            ),
+            PresetErrorCause::BothEccentricityDefiners => write!(
+                f,
+                "defining both {KEY_APOAPSIS} and {KEY_ECCENTRICITY} is disallowed"
+            ),
+            PresetErrorCause::InvalidColor { key, reason } => write!(f, "{key}: {reason}"),
+        }
+    }
+}
+
+impl Display for PresetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.entry, self.cause)
+    }
+}
+
+impl Error for PresetError {}
+
+impl PresetEntry {
+    /// The eccentricity of the orbit, computing it from the apoapsis if needed.
+    pub(crate) fn eccentricity(&self) -> f64 {
+        match self.ecc_definer {
+            EccentricityDefiner::Eccentricity(e) => e,
+            EccentricityDefiner::Apoapsis(apoapsis) => {
+                (apoapsis - self.periapsis) / (apoapsis + self.periapsis)
+            }
+        }
+    }
+
+    /// Writes the entry as a `presets.toml` table.
+    ///
+    /// Fails if any of its numbers isn't finite, as the file can't hold them.
+    pub(crate) fn to_toml(&self) -> Result<String, PresetError> {
+        let float = |key: &'static str, float: f64| {
+            toml_float(float).ok_or_else(|| PresetError {
+                entry: self.key.clone(),
+                cause: PresetErrorCause::InvalidFloat {
+                    key,
+                    reason: format!("{float} isn't finite"),
+                },
+            })
+        };
+
+        let mut out = format!("[{}]\n", self.key);
+        out += &format!("{KEY_NAME} = {}\n", toml_string(&self.name));
+        if let Some(docname) = &self.docname {
+            out += &format!("{KEY_DOCNAME} = {}\n", toml_string(docname));
+        }
+        if let Some(description) = &self.description {
+            out += &format!("{KEY_DESC} = {}\n", toml_string(description));
+        }
+        out += &format!("{KEY_MASS} = {}\n", float(KEY_MASS, self.mass)?);
+        out += &format!("{KEY_RADIUS} = {}\n", float(KEY_RADIUS, self.radius)?);
+        match self.ecc_definer {
+            EccentricityDefiner::Eccentricity(e) => {
+                out += &format!("{KEY_ECCENTRICITY} = {}\n", float(KEY_ECCENTRICITY, e)?);
+            }
+            EccentricityDefiner::Apoapsis(ap) => {
+                out += &format!("{KEY_APOAPSIS} = {}\n", float(KEY_APOAPSIS, ap)?);
+            }
+        }
+        out += &format!(
+            "{KEY_PERIAPSIS} = {}\n",
+            float(KEY_PERIAPSIS, self.periapsis)?
+        );
+        out += &format!(
+            "{KEY_INCLINATION} = {}\n",
+            float(KEY_INCLINATION, self.inclination)?
+        );
+        out += &format!("{KEY_ARG_PE} = {}\n", float(KEY_ARG_PE, self.arg_pe)?);
+        out += &format!(
+            "{KEY_LONG_ASC_NODE} = {}\n",
+            float(KEY_LONG_ASC_NODE, self.long_asc_node)?
+        );
+        out += &format!(
+            "{KEY_MEAN_ANOMALY} = {}\n",
+            float(KEY_MEAN_ANOMALY, self.mean_anomaly)?
+        );
+        out += &format!("{KEY_COLOR} = {}\n", color_to_toml(self.color));
+        if let Some(epoch) = self.epoch {
+            out += &format!("{KEY_EPOCH} = {}\n", float(KEY_EPOCH, epoch)?);
+        }
+        Ok(out)
+    }
+
+    /// Checks the entry against the same rules the build script uses,
+    /// by writing it out and parsing it back.
+    pub(crate) fn validate(&self) -> Result<(), PresetError> {
+        parse_catalog(&self.to_toml()?).map(|_| ())
+    }
+}
+
+impl PresetCatalog {
+    /// Writes the whole catalog as a `presets.toml` file.
+    ///
+    /// Comments in the original file are not kept.
+    pub(crate) fn to_toml(&self) -> Result<String, PresetError> {
+        let mut out = String::new();
+
+        if let Some(epoch) = self.epoch {
+            let epoch = toml_float(epoch).ok_or_else(|| PresetError {
+                entry: FILE_ENTRY_NAME.to_owned(),
+                cause: PresetErrorCause::InvalidFloat {
+                    key: KEY_EPOCH,
+                    reason: format!("{epoch} isn't finite"),
+                },
+            })?;
+            out.push_str(&format!("{KEY_EPOCH} = {epoch}\n"));
+        }
+
+        for entry in &self.entries {
+            if !out.is_empty() {
+                out.push('\n');
+            }
+            out.push_str(&entry.to_toml()?);
+        }
+
+        Ok(out)
+    }
+}
+
+/// Parses a whole presets file, or a snippet of one.
+pub(crate) fn parse_catalog(text: &str) -> Result<PresetCatalog, PresetError> {
+    let table = DeTable::parse(text).map_err(|e| PresetError {
+        entry: FILE_ENTRY_NAME.to_owned(),
+        cause: PresetErrorCause::Syntax(e.to_string()),
+    })?;
+    let table = table.get_ref();
+
+    let epoch = get_float_optional(table, FILE_ENTRY_NAME, KEY_EPOCH)?;
+    let mut entries = Vec::with_capacity(table.len());
+
+    for (key, value) in table {
+        let key = key.get_ref();
+        if key == KEY_EPOCH {
+            continue;
+        }
+        entries.push(parse_entry(key, value.get_ref())?);
+    }
+
+    Ok(PresetCatalog { epoch, entries })
+}
+
+/// Parses one `[table]` of the presets file.
+pub(crate) fn parse_entry(key: &str, value: &DeValue<'_>) -> Result<PresetEntry, PresetError> {
+    let error = |cause| PresetError {
+        entry: key.to_owned(),
+        cause,
+    };
+
+    let DeValue::Table(map) = value else {
+        return Err(error(PresetErrorCause::NotATable));
+    };
+
+    if !is_valid_key(key) {
+        return Err(error(PresetErrorCause::InvalidKey));
+    }
+
+    let name = get_str_required(map, key, KEY_NAME)?;
+    let docname = get_str_optional(map, key, KEY_DOCNAME)?;
+    let description = get_str_optional(map, key, KEY_DESC)?;
+    let mass = get_float_required(map, key, KEY_MASS)?;
+    let radius = get_float_required(map, key, KEY_RADIUS)?;
+
+    let apoapsis = get_float_optional(map, key, KEY_APOAPSIS)?;
+    let eccentricity = get_float_optional(map, key, KEY_ECCENTRICITY)?;
+
+    let ecc_definer = match (apoapsis, eccentricity) {
+        (None, None) => return Err(error(PresetErrorCause::NoEccentricityDefiner)),
+        (None, Some(e)) => EccentricityDefiner::Eccentricity(e),
+        (Some(ap), None) => EccentricityDefiner::Apoapsis(ap),
+        (Some(_), Some(_)) => return Err(error(PresetErrorCause::BothEccentricityDefiners)),
+    };
+
+    Ok(PresetEntry {
+        key: key.to_owned(),
+        name: name.to_owned(),
+        docname: docname.map(str::to_owned),
+        description: description.map(str::to_owned),
+        mass,
+        radius,
+        ecc_definer,
+        periapsis: get_float_required(map, key, KEY_PERIAPSIS)?,
+        inclination: get_float_required(map, key, KEY_INCLINATION)?,
+        arg_pe: get_float_required(map, key, KEY_ARG_PE)?,
+        long_asc_node: get_float_required(map, key, KEY_LONG_ASC_NODE)?,
+        mean_anomaly: get_float_required(map, key, KEY_MEAN_ANOMALY)?,
+        color: get_srgb_required(map, key, KEY_COLOR)?,
+        epoch: get_float_optional(map, key, KEY_EPOCH)?,
+    })
+}
+
+/// Parses a color written the way `presets.toml` accepts it:
+/// either a `0xRRGGBBAA` integer or a 3–4 element array of u8.
+pub(crate) fn parse_color(text: &str) -> Result<[u8; 4], PresetError> {
+    let document = format!("{KEY_COLOR} = {text}");
+    let table = DeTable::parse(&document).map_err(|e| PresetError {
+        entry: KEY_COLOR.to_owned(),
+        cause: PresetErrorCause::Syntax(e.to_string()),
+    })?;
+
+    get_srgb_required(table.get_ref(), KEY_COLOR, KEY_COLOR)
+}
+
+/// Writes a color in the `0xRRGGBBAA` form.
+pub(crate) fn color_to_toml(color: [u8; 4]) -> String {
+    format!("0x{:08X}", u32::from_be_bytes(color))
+}
+
+/// Table names become function names, so they must be snake_case identifiers.
+fn is_valid_key(key: &str) -> bool {
+    let mut chars = key.chars();
+
+    matches!(chars.next(), Some('a'..='z' | '_'))
+        && chars.all(|c| matches!(c, 'a'..='z' | '0'..='9' | '_'))
+        && key != "_"
+}
+
+fn toml_string(string: &str) -> String {
+    toml::Value::String(string.to_owned()).to_string()
+}
+
+/// Writes a float so TOML reads it back as one, or None if it isn't finite.
+///
+/// TOML has `inf` and `nan`, but a preset made with them would be broken.
+fn toml_float(float: f64) -> Option<String> {
+    // `{:?}` always includes a decimal point or exponent,
+    // so TOML reads it back as a float
+    float.is_finite().then(|| format!("{float:?}"))
+}
+
+fn expect_exists<'a>(
+    map: &'a DeTable<'a>,
+    entry: &str,
+    key: &'static str,
+) -> Result<&'a Spanned<DeValue<'a>>, PresetError> {
+    map.get(key).ok_or_else(|| PresetError {
+        entry: entry.to_owned(),
+        cause: PresetErrorCause::MissingField(key),
+    })
+}
+
+fn get_str_required<'a>(
+    map: &'a DeTable<'a>,
+    entry: &str,
+    key: &'static str,
+) -> Result<&'a str, PresetError> {
+    expect_str(expect_exists(map, entry, key)?, entry, key)
+}
+
+fn get_str_optional<'a>(
+    map: &'a DeTable<'a>,
+    entry: &str,
+    key: &'static str,
+) -> Result<Option<&'a str>, PresetError> {
+    match map.get(key) {
+        Some(val) => expect_str(val, entry, key).map(Some),
+        None => Ok(None),
+    }
+}
+
+fn expect_str<'a>(
+    val: &'a Spanned<DeValue<'a>>,
+    entry: &str,
+    key: &'static str,
+) -> Result<&'a str, PresetError> {
+    val.get_ref().as_str().ok_or_else(|| PresetError {
+        entry: entry.to_owned(),
+        cause: PresetErrorCause::WrongType {
+            key,
+            expected: "string",
+        },
+    })
+}
+
+fn get_float_required(
+    map: &DeTable<'_>,
+    entry: &str,
+    key: &'static str,
+) -> Result<f64, PresetError> {
+    expect_float(expect_exists(map, entry, key)?, entry, key)
+}
+
+fn get_float_optional(
+    map: &DeTable<'_>,
+    entry: &str,
+    key: &'static str,
+) -> Result<Option<f64>, PresetError> {
+    match map.get(key) {
+        Some(val) => expect_float(val, entry, key).map(Some),
+        None => Ok(None),
+    }
+}
+
+fn expect_float(
+    val: &Spanned<DeValue<'_>>,
+    entry: &str,
+    key: &'static str,
+) -> Result<f64, PresetError> {
+    let error = |cause| PresetError {
+        entry: entry.to_owned(),
+        cause,
+    };
+
+    let Some(val) = val.get_ref().as_float() else {
+        return Err(error(PresetErrorCause::WrongType {
+            key,
+            expected: "float",
+        }));
+    };
+
+    let float: f64 = val
+        .as_str()
+        .parse()
+        .map_err(|e: std::num::ParseFloatError| {
+            error(PresetErrorCause::InvalidFloat {
+                key,
+                reason: e.to_string(),
+            })
+        })?;
+
+    if !float.is_finite() {
+        return Err(error(PresetErrorCause::InvalidFloat {
+            key,
+            reason: format!("{float} isn't finite"),
+        }));
+    }
+    Ok(float)
+}
+
+fn get_srgb_required(
+    map: &DeTable<'_>,
+    entry: &str,
+    key: &'static str,
+) -> Result<[u8; 4], PresetError> {
+    let val = expect_exists(map, entry, key)?;
+    let error = |reason: Cow<'static, str>| PresetError {
+        entry: entry.to_owned(),
+        cause: PresetErrorCause::InvalidColor {
+            key,
+            reason: reason.into_owned(),
+        },
+    };
+
+    match val.get_ref() {
+        DeValue::Integer(de_integer) => srgb_from_int(de_integer).map_err(error),
+        DeValue::Array(de_array) => srgb_from_array(de_array).map_err(error),
+        _ => Err(error(Cow::Borrowed(
+            "expected either a 3–4 element integer array or an integer",
+        ))),
+    }
+}
+
+fn srgb_from_int(val: &DeInteger<'_>) -> Result<[u8; 4], Cow<'static, str>> {
+    match u32::from_str_radix(val.as_str(), val.radix()) {
+        Ok(v) => Ok(v.to_be_bytes()),
+        Err(e) => Err(format!("expected integer to fit in 32 bits, got error {e}").into()),
+    }
+}
+
+fn srgb_from_array(val: &DeArray<'_>) -> Result<[u8; 4], Cow<'static, str>> {
+    if val.len() != 3 && val.len() != 4 {
+        return Err(format!("expected array to have 3–4 elements, got {}", val.len()).into());
+    }
+
+    let mut color = [255; 4];
+
+    for (i, channel) in color.iter_mut().enumerate().take(val.len()) {
+        let Some(int) = val.get(i).and_then(|s| s.get_ref().as_integer()) else {
+            return Err(format!("expected element {i} to be u8").into());
+        };
+
+        *channel = match u8::from_str_radix(int.as_str(), int.radix()) {
+            Ok(v) => v,
+            Err(e) => {
+                return Err(format!("expected element {i} to fit in u8, got error {e}").into());
+            }
+        };
+    }
+
+    Ok(color)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(key: &str) -> PresetEntry {
+        PresetEntry {
+            key: key.to_owned(),
+            name: String::from("Test \"Body\""),
+            docname: Some(String::from("test body")),
+            description: None,
+            mass: 5.972e24,
+            radius: 6.371e6,
+            ecc_definer: EccentricityDefiner::Apoapsis(1.521e11),
+            periapsis: 1.471e11,
+            inclination: 0.00005,
+            arg_pe: 114.20783,
+            long_asc_node: -11.26064,
+            mean_anomaly: 358.617,
+            color: [51, 108, 245, 255],
+            epoch: Some(2451545.0),
+        }
+    }
+
+    #[test]
+    fn catalogs_round_trip() {
+        let catalog = PresetCatalog {
+            epoch: Some(2460000.5),
+            entries: vec![
+                entry("first"),
+                PresetEntry {
+                    ecc_definer: EccentricityDefiner::Eccentricity(0.0167),
+                    docname: None,
+                    description: Some(String::from("Two\nlines")),
+                    epoch: None,
+                    ..entry("second")
+                },
+            ],
+        };
+
+        let toml = catalog.to_toml().unwrap();
+        assert_eq!(parse_catalog(&toml), Ok(catalog));
+    }
+
+    #[test]
+    fn the_bundled_presets_round_trip() {
+        let catalog = parse_catalog(crate::sim::PRESETS_TOML).unwrap();
+        assert_eq!(parse_catalog(&catalog.to_toml().unwrap()), Ok(catalog));
+    }
+
+    #[test]
+    fn non_finite_numbers_are_not_written() {
+        let entry = PresetEntry {
+            mass: f64::INFINITY,
+            ..entry("broken")
+        };
+        let error = entry.to_toml().unwrap_err();
+        assert_eq!(error.entry, "broken");
+        assert!(matches!(
+            error.cause,
+            PresetErrorCause::InvalidFloat { key: KEY_MASS, .. }
+        ));
+        assert!(entry.validate().is_err());
+
+        let catalog = PresetCatalog {
+            epoch: Some(f64::NAN),
+            entries: Vec::new(),
+        };
+        assert!(catalog.to_toml().is_err());
+    }
+
+    #[test]
+    fn non_finite_numbers_are_not_read() {
+        let toml = entry("broken").to_toml().unwrap().replace(
+            &format!("{KEY_RADIUS} = 6371000.0"),
+            &format!("{KEY_RADIUS} = inf"),
+        );
+        let error = parse_catalog(&toml).unwrap_err();
+        assert!(matches!(
+            error.cause,
+            PresetErrorCause::InvalidFloat {
+                key: KEY_RADIUS,
+                ..
+            }
+        ));
+    }
+}
@@ -1,9 +1,34 @@
 //! Generated by build.rs::presets
 #![allow(clippy::excessive_precision)]
-use crate::sim::body::Body;
+use crate::sim::{body::Body, epoch::PresetEpoch};
 use keplerian_sim::Orbit;
 use three_d::Srgba;
 
+/// Returns Ariel, the fourth-largest moon of Uranus.
+///
+/// `parent_mu`: The gravitational parameter of the parent body, if any.
+/// If None, the celestial body will not be placed in an orbit.
+pub(crate) fn ariel(parent_mu: Option<f64>) -> Body {
+    let orbit = parent_mu.map(|mu| {
+        Orbit::new(
+            1.19999999999999989488e-3,
+            1.90790776000000000000e8,
+            1.70518667919846000913e0,
+            2.01236462754946199283e0,
+            2.92517182634249639506e0,
+            6.89405054537760131339e-1,
+            mu,
+        )
+    });
+
+    Body {
+        name: String::from("Ariel"),
+        mass: 1.25100000000000000000e21,
+        radius: 5.78900000000000000000e5,
+        orbit,
+        color: Srgba::new(181, 173, 164, 255),
+    }
+}
 /// Returns Callisto, the outermost Galilean moon of Jupiter.
 ///
 /// `parent_mu`: The gravitational parameter of the parent body, if any.
@@ -104,6 +129,31 @@ pub(crate) fn deimos(parent_mu: Option<f64>) -> Body {
         color: Srgba::new(209, 199, 187, 255),
     }
 }
+/// Returns Dione, the fourth-largest moon of Saturn.
+///
+/// `parent_mu`: The gravitational parameter of the parent body, if any.
+/// If None, the celestial body will not be placed in an orbit.
+pub(crate) fn dione(parent_mu: Option<f64>) -> Body {
+    let orbit = parent_mu.map(|mu| {
+        Orbit::new(
+            2.20000000000000013253e-3,
+            3.76565729000000000000e8,
+            4.89564855184409453148e-1,
+            2.94611577736642837877e0,
+            2.95833308213038836954e0,
+            4.96197106341987925759e0,
+            mu,
+        )
+    });
+
+    Body {
+        name: String::from("Dione"),
+        mass: 1.09545200000000000000e21,
+        radius: 5.61400000000000000000e5,
+        orbit,
+        color: Srgba::new(216, 214, 210, 255),
+    }
+}
 /// Returns (136199) Eris I Dysnomia, the moon of the dwarf planet Eris.
 ///
 /// `parent_mu`: The gravitational parameter of the parent body, if any.
@@ -529,6 +579,31 @@ pub(crate) fn mimas(parent_mu: Option<f64>) -> Body {
         color: Srgba::new(230, 230, 230, 255),
     }
 }
+/// Returns Miranda, the smallest of the five major moons of Uranus.
+///
+/// `parent_mu`: The gravitational parameter of the parent body, if any.
+/// If None, the celestial body will not be placed in an orbit.
+pub(crate) fn miranda(parent_mu: Option<f64>) -> Body {
+    let orbit = parent_mu.map(|mu| {
+        Orbit::new(
+            1.29999999999999994033e-3,
+            1.29221793000000000000e8,
+            1.77849050778222195213e0,
+            1.19205987911212707431e0,
+            2.92517182634249639506e0,
+            5.43320996145834822499e0,
+            mu,
+        )
+    });
+
+    Body {
+        name: String::from("Miranda"),
+        mass: 6.40000000000000000000e19,
+        radius: 2.35800000000000000000e5,
+        orbit,
+        color: Srgba::new(169, 167, 163, 255),
+    }
+}
 /// Returns Neptune, the eighth planet from the Sun.
 ///
 /// `parent_mu`: The gravitational parameter of the parent body, if any.
@@ -804,6 +879,31 @@ pub(crate) fn quaoar(parent_mu: Option<f64>) -> Body {
         color: Srgba::new(99, 87, 82, 80),
     }
 }
+/// Returns Rhea, the second-largest moon of Saturn.
+///
+/// `parent_mu`: The gravitational parameter of the parent body, if any.
+/// If None, the celestial body will not be placed in an orbit.
+pub(crate) fn rhea(parent_mu: Option<f64>) -> Body {
+    let orbit = parent_mu.map(|mu| {
+        Orbit::new(
+            1.25800000000000000364e-3,
+            5.26444898100000023842e8,
+            4.93928178314395283088e-1,
+            4.47851486061744985534e0,
+            2.95658775287839414858e0,
+            3.33532420056116363227e0,
+            mu,
+        )
+    });
+
+    Body {
+        name: String::from("Rhea"),
+        mass: 2.30651800000000006554e21,
+        radius: 7.63800000000000000000e5,
+        orbit,
+        color: Srgba::new(203, 199, 193, 255),
+    }
+}
 /// Returns Saturn, the sixth planet from the Sun.
 ///
 /// `parent_mu`: The gravitational parameter of the parent body, if any.
@@ -979,6 +1079,31 @@ pub(crate) fn triton(parent_mu: Option<f64>) -> Body {
         color: Srgba::new(167, 167, 167, 255),
     }
 }
+/// Returns Umbriel, the darkest of the major moons of Uranus.
+///
+/// `parent_mu`: The gravitational parameter of the parent body, if any.
+/// If None, the celestial body will not be placed in an orbit.
+pub(crate) fn umbriel(parent_mu: Option<f64>) -> Body {
+    let orbit = parent_mu.map(|mu| {
+        Orbit::new(
+            3.89999999999999982098e-3,
+            2.64962600000000000000e8,
+            1.70518667919846000913e0,
+            1.47829387643919707429e0,
+            2.92517182634249639506e0,
+            2.18166156499291191695e-1,
+            mu,
+        )
+    });
+
+    Body {
+        name: String::from("Umbriel"),
+        mass: 1.28849999999999986893e21,
+        radius: 5.84700000000000000000e5,
+        orbit,
+        color: Srgba::new(111, 106, 102, 255),
+    }
+}
 /// Returns Uranus, the seventh planet from the Sun.
 ///
 /// `parent_mu`: The gravitational parameter of the parent body, if any.
@@ -1128,4 +1253,258 @@ pub(crate) fn weywot(parent_mu: Option<f64>) -> Body {
         orbit,
         color: Srgba::new(70, 70, 70, 255),
     }
-}
\ No newline at end of file
+}
+
+/// The epochs the preset bodies' mean anomalies are measured at.
+pub(crate) const PRESET_EPOCHS: &[PresetEpoch] = &[
+    PresetEpoch {
+        name: "Ariel",
+        julian_date: 2.46094616666670003906e6,
+        mean_anomaly: 6.89405054537760131339e-1,
+    },
+    PresetEpoch {
+        name: "Callisto",
+        julian_date: 2.46094616666670003906e6,
+        mean_anomaly: 3.20478718554404062857e0,
+    },
+    PresetEpoch {
+        name: "Ceres",
+        julian_date: 2.46094616666670003906e6,
+        mean_anomaly: 3.83811268370889635548e0,
+    },
+    PresetEpoch {
+        name: "Charon",
+        julian_date: 2.46094616666670003906e6,
+        mean_anomaly: 1.78841867088763017968e0,
+    },
+    PresetEpoch {
+        name: "Deimos",
+        julian_date: 2.46094616666670003906e6,
+        mean_anomaly: 1.78483588231838430360e0,
+    },
+    PresetEpoch {
+        name: "Dione",
+        julian_date: 2.46094616666670003906e6,
+        mean_anomaly: 4.96197106341987925759e0,
+    },
+    PresetEpoch {
+        name: "Dysnomia",
+        julian_date: 2.46094616666670003906e6,
+        mean_anomaly: 5.41522005836052677807e0,
+    },
+    PresetEpoch {
+        name: "Earth",
+        julian_date: 2.46094616666670003906e6,
+        mean_anomaly: 4.61837973661737599684e0,
+    },
+    PresetEpoch {
+        name: "Enceladus",
+        julian_date: 2.46094616666670003906e6,
+        mean_anomaly: 1.40782580882600250050e0,
+    },
+    PresetEpoch {
+        name: "Eris",
+        julian_date: 2.46094616666670003906e6,
+        mean_anomaly: 3.68870982740637121111e0,
+    },
+    PresetEpoch {
+        name: "Europa",
+        julian_date: 2.46094616666670003906e6,
+        mean_anomaly: 3.86998727757317784182e0,
+    },
+    PresetEpoch {
+        name: "Ganymede",
+        julian_date: 2.46094616666670003906e6,
+        mean_anomaly: 4.48782673296934486729e0,
+    },
+    PresetEpoch {
+        name: "Geostationary Satellite",
+        julian_date: 2.46094616666670003906e6,
+        mean_anomaly: 0.00000000000000000000e0,
+    },
+    PresetEpoch {
+        name: "Haumea",
+        julian_date: 2.46094616666670003906e6,
+        mean_anomaly: 3.87640542681359523414e0,
+    },
+    PresetEpoch {
+        name: "Iapetus",
+        julian_date: 2.46094616666670003906e6,
+        mean_anomaly: 4.98392695640728078388e-1,
+    },
+    PresetEpoch {
+        name: "Io",
+        julian_date: 2.46094616666670003906e6,
+        mean_anomaly: 1.19377417068254043642e0,
+    },
+    PresetEpoch {
+        name: "Jupiter",
+        julian_date: 2.46094616666670003906e6,
+        mean_anomaly: 1.41979311786106965343e0,
+    },
+    PresetEpoch {
+        name: "Leleākūhonua",
+        julian_date: 2.46094616666670003906e6,
+        mean_anomaly: 6.27691011804525711426e0,
+    },
+    PresetEpoch {
+        name: "Luna",
+        julian_date: 2.46094616666670003906e6,
+        mean_anomaly: 3.42916122080155005847e0,
+    },
+    PresetEpoch {
+        name: "Makemake",
+        julian_date: 2.46094616666670003906e6,
+        mean_anomaly: 2.95258720441105992904e0,
+    },
+    PresetEpoch {
+        name: "Mars",
+        julian_date: 2.46094616666670003906e6,
+        mean_anomaly: 4.63981684470795041619e0,
+    },
+    PresetEpoch {
+        name: "Mercury",
+        julian_date: 2.46094616666670003906e6,
+        mean_anomaly: 2.22553246024303374995e0,
+    },
+    PresetEpoch {
+        name: "Mimas",
+        julian_date: 2.46094616666670003906e6,
+        mean_anomaly: 1.91047141435486178551e0,
+    },
+    PresetEpoch {
+        name: "Miranda",
+        julian_date: 2.46094616666670003906e6,
+        mean_anomaly: 5.43320996145834822499e0,
+    },
+    PresetEpoch {
+        name: "Neptune",
+        julian_date: 2.46094616666670003906e6,
+        mean_anomaly: 5.51317397706606460162e0,
+    },
+    PresetEpoch {
+        name: "Nereid",
+        julian_date: 2.46094616666670003906e6,
+        mean_anomaly: 4.44642592189711205464e0,
+    },
+    PresetEpoch {
+        name: "New Horizons",
+        julian_date: 2.46094616666670003906e6,
+        mean_anomaly: 9.21047022547655913627e0,
+    },
+    PresetEpoch {
+        name: "Oberon",
+        julian_date: 2.46094616666670003906e6,
+        mean_anomaly: 4.19348658600581281064e0,
+    },
+    PresetEpoch {
+        name: "Parker Solar Probe",
+        julian_date: 2.46094616666670003906e6,
+        mean_anomaly: 8.41112960342036974914e-1,
+    },
+    PresetEpoch {
+        name: "Phobos",
+        julian_date: 2.46094616666670003906e6,
+        mean_anomaly: 1.31150356728336703327e0,
+    },
+    PresetEpoch {
+        name: "Pioneer 10",
+        julian_date: 2.46094616666670003906e6,
+        mean_anomaly: 1.78677740419062089927e1,
+    },
+    PresetEpoch {
+        name: "Pioneer 11",
+        julian_date: 2.46094616666670003906e6,
+        mean_anomaly: 1.23966710442169372186e1,
+    },
+    PresetEpoch {
+        name: "Pluto",
+        julian_date: 2.46094616666670003906e6,
+        mean_anomaly: 8.88349436303251249747e-1,
+    },
+    PresetEpoch {
+        name: "Proteus",
+        julian_date: 2.46094616666670003906e6,
+        mean_anomaly: 1.35374072438646830818e0,
+    },
+    PresetEpoch {
+        name: "Quaoar",
+        julian_date: 2.46094616666670003906e6,
+        mean_anomaly: 5.08276432412114154147e0,
+    },
+    PresetEpoch {
+        name: "Rhea",
+        julian_date: 2.46094616666670003906e6,
+        mean_anomaly: 3.33532420056116363227e0,
+    },
+    PresetEpoch {
+        name: "Saturn",
+        julian_date: 2.46094616666670003906e6,
+        mean_anomaly: 4.76558411108678292578e0,
+    },
+    PresetEpoch {
+        name: "Sedna",
+        julian_date: 2.46094616666670003906e6,
+        mean_anomaly: 6.25890811267192148648e0,
+    },
+    PresetEpoch {
+        name: "Tethys",
+        julian_date: 2.46094616666670003906e6,
+        mean_anomaly: 6.23427711029640541085e0,
+    },
+    PresetEpoch {
+        name: "The Sun",
+        julian_date: 2.46094616666670003906e6,
+        mean_anomaly: 0.00000000000000000000e0,
+    },
+    PresetEpoch {
+        name: "Titan",
+        julian_date: 2.46094616666670003906e6,
+        mean_anomaly: 1.87040029330365299362e-3,
+    },
+    PresetEpoch {
+        name: "Titania",
+        julian_date: 2.46094616666670003906e6,
+        mean_anomaly: 4.71483672122259347970e-2,
+    },
+    PresetEpoch {
+        name: "Triton",
+        julian_date: 2.46094616666670003906e6,
+        mean_anomaly: 4.12735531721081849721e-1,
+    },
+    PresetEpoch {
+        name: "Umbriel",
+        julian_date: 2.46094616666670003906e6,
+        mean_anomaly: 2.18166156499291191695e-1,
+    },
+    PresetEpoch {
+        name: "Uranus",
+        julian_date: 2.46094616666670003906e6,
+        mean_anomaly: 4.51290712778646962988e0,
+    },
+    PresetEpoch {
+        name: "Venus",
+        julian_date: 2.46094616666670003906e6,
+        mean_anomaly: 6.15151825197227264397e0,
+    },
+    PresetEpoch {
+        name: "Vesta",
+        julian_date: 2.46094616666670003906e6,
+        mean_anomaly: 2.10187172950634604263e-1,
+    },
+    PresetEpoch {
+        name: "Voyager 1",
+        julian_date: 2.46094616666670003906e6,
+        mean_anomaly: 4.98320618682255300769e1,
+    },
+    PresetEpoch {
+        name: "Voyager 2",
+        julian_date: 2.46094616666670003906e6,
+        mean_anomaly: 3.30399819334499014190e1,
+    },
+    PresetEpoch {
+        name: "Weywot",
+        julian_date: 2.46094616666670003906e6,
+        mean_anomaly: 6.02268083093493000746e0,
+    },
+];
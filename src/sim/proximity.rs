@@ -0,0 +1,137 @@
+//! Which bodies are closest to a given body, for the quick switcher,
+//! and whose sphere of influence a point is in.
+
+use std::collections::HashMap;
+
+use glam::DVec3;
+
+use crate::sim::universe::{Id, Universe};
+
+/// Every body other than `from`, with its distance from `from`, nearest first.
+///
+/// Bodies at the same distance are ordered by id, and bodies whose
+/// distance can't be worked out are left out.
+pub(crate) fn by_distance(position_map: &HashMap<Id, DVec3>, from: Id) -> Vec<(Id, f64)> {
+    let Some(&origin) = position_map.get(&from) else {
+        return Vec::new();
+    };
+
+    let mut bodies: Vec<(Id, f64)> = position_map
+        .iter()
+        .filter(|&(&id, _)| id != from)
+        .map(|(&id, &position)| (id, position.distance(origin)))
+        .filter(|(_, distance)| distance.is_finite())
+        .collect();
+    bodies.sort_by(|a, b| a.1.total_cmp(&b.1).then(a.0.cmp(&b.0)));
+    bodies
+}
+
+/// The body whose sphere of influence `position` is deepest in:
+/// of the bodies whose SOI reaches it, the one with the smallest SOI.
+/// Root bodies' SOIs reach everywhere, so the nearest root is the last resort.
+///
+/// Bodies in `position_map` that are no longer in `universe` are skipped,
+/// so a map from before a deletion can be used.
+pub(crate) fn dominant_body(
+    universe: &Universe,
+    position_map: &HashMap<Id, DVec3>,
+    position: DVec3,
+) -> Option<Id> {
+    position_map
+        .iter()
+        .filter_map(|(&id, &center)| {
+            let soi_radius = universe.get_soi_radius(id)?;
+            let distance = center.distance(position);
+            (distance <= soi_radius).then_some((id, soi_radius, distance))
+        })
+        .min_by(|a, b| {
+            a.1.total_cmp(&b.1)
+                .then(a.2.total_cmp(&b.2))
+                .then(a.0.cmp(&b.0))
+        })
+        .map(|(id, _, _)| id)
+}
+
+#[cfg(test)]
+mod tests {
+    use keplerian_sim::Orbit;
+
+    use super::*;
+    use crate::sim::body::Body;
+
+    #[test]
+    fn nearest_bodies_come_first() {
+        let positions = HashMap::from([
+            (0, DVec3::ZERO),
+            (1, DVec3::new(10.0, 0.0, 0.0)),
+            (2, DVec3::new(0.0, 3.0, 4.0)),
+            (3, DVec3::new(0.0, 0.0, -5.0)),
+        ]);
+
+        assert_eq!(
+            by_distance(&positions, 0),
+            vec![(2, 5.0), (3, 5.0), (1, 10.0)]
+        );
+        assert_eq!(by_distance(&positions, 1)[0], (0, 10.0));
+    }
+
+    #[test]
+    fn unknown_positions_are_skipped() {
+        let positions = HashMap::from([
+            (0, DVec3::ZERO),
+            (1, DVec3::new(f64::NAN, 0.0, 0.0)),
+            (2, DVec3::X),
+        ]);
+
+        assert_eq!(by_distance(&positions, 0), vec![(2, 1.0)]);
+        assert!(by_distance(&positions, 7).is_empty());
+    }
+
+    fn body(mass: f64, orbit_radius: Option<f64>) -> Body {
+        let orbit = orbit_radius.map(|radius| Orbit::new(0.0, radius, 0.0, 0.0, 0.0, 0.0, 1.0));
+        Body::new(String::from("Body"), mass, 1e3, orbit)
+    }
+
+    #[test]
+    fn the_smallest_soi_containing_the_point_wins() {
+        let mut universe = Universe::default();
+        let sun = universe.add_body(body(1e30, None), None).unwrap();
+        let planet = universe
+            .add_body(body(1e24, Some(1.5e11)), Some(sun))
+            .unwrap();
+        let positions = universe.get_all_body_positions();
+        let planet_position = positions[&planet];
+
+        let near_planet = planet_position + DVec3::new(1e6, 0.0, 0.0);
+        assert_eq!(
+            dominant_body(&universe, &positions, near_planet),
+            Some(planet)
+        );
+
+        let far_from_planet = planet_position + DVec3::new(1e10, 0.0, 0.0);
+        assert_eq!(
+            dominant_body(&universe, &positions, far_from_planet),
+            Some(sun)
+        );
+    }
+
+    #[test]
+    fn removed_bodies_are_skipped() {
+        let mut universe = Universe::default();
+        let sun = universe.add_body(body(1e30, None), None).unwrap();
+        let planet = universe
+            .add_body(body(1e24, Some(1.5e11)), Some(sun))
+            .unwrap();
+        let positions = universe.get_all_body_positions();
+        let planet_position = positions[&planet];
+
+        universe.remove_body(planet);
+        assert_eq!(
+            dominant_body(&universe, &positions, planet_position),
+            Some(sun)
+        );
+
+        universe.remove_body(sun);
+        assert_eq!(dominant_body(&universe, &positions, planet_position), None);
+    }
+}
@@ -0,0 +1,111 @@
+//! A body's path as seen from another body, like the retrograde loops
+//! Mars makes in Earth's sky.
+
+use glam::DVec3;
+use keplerian_sim::OrbitTrait;
+
+use crate::sim::universe::{Id, Universe};
+
+/// How many points a relative path is sampled at.
+pub(crate) const RELATIVE_PATH_SAMPLES: usize = 2048;
+
+/// How long it takes for the motion of one body relative to another
+/// to repeat, given each body's orbital period.
+///
+/// For bodies orbiting the same parent, this is their synodic period.
+/// Otherwise, the longer period is used, which covers a full lap of the
+/// slower body and usually several loops of the faster one.
+///
+/// A period is None for a body that isn't going around anything,
+/// like the root body, a frozen body or one on an open orbit.
+pub(crate) fn repeat_span(
+    target_period: Option<f64>,
+    observer_period: Option<f64>,
+    siblings: bool,
+) -> Option<f64> {
+    let span = match (target_period, observer_period) {
+        (Some(target), Some(observer)) if siblings => {
+            let relative_rate = (target.recip() - observer.recip()).abs();
+            if relative_rate > 0.0 {
+                relative_rate.recip()
+            } else {
+                // Going around together, so only the shared orbit repeats
+                target
+            }
+        }
+        (Some(target), Some(observer)) => target.max(observer),
+        (Some(period), None) | (None, Some(period)) => period,
+        (None, None) => return None,
+    };
+
+    (span.is_finite() && span > 0.0).then_some(span)
+}
+
+/// How long the path of `target` as seen from `observer`
+/// takes to repeat, in seconds. See [`repeat_span`].
+pub(crate) fn relative_path_span(universe: &Universe, target: Id, observer: Id) -> Option<f64> {
+    let period = |id: Id| {
+        let wrapper = universe.get_body(id)?;
+        if wrapper.frozen_at.is_some() {
+            return None;
+        }
+        wrapper
+            .body
+            .orbit
+            .as_ref()
+            .filter(|orbit| orbit.is_closed())
+            .map(|orbit| orbit.get_orbital_period())
+    };
+
+    let parent = |id: Id| universe.get_body(id).and_then(|w| w.relations.parent);
+    let siblings = parent(target).is_some() && parent(target) == parent(observer);
+
+    repeat_span(period(target), period(observer), siblings)
+}
+
+/// Samples where `target` is relative to `observer`, in meters,
+/// at `count` evenly spaced times from `start` to `start + span`.
+///
+/// Each orbit is followed as it is now, so maneuver nodes and
+/// perturbations along the way aren't taken into account.
+pub(crate) fn sample_relative_path(
+    universe: &Universe,
+    target: Id,
+    observer: Id,
+    start: f64,
+    span: f64,
+    count: usize,
+) -> Option<Vec<DVec3>> {
+    let step = span / count.saturating_sub(1).max(1) as f64;
+
+    (0..count)
+        .map(|index| {
+            let time = start + index as f64 * step;
+            let target = universe.get_body_position_at_time(target, time)?;
+            let observer = universe.get_body_position_at_time(observer, time)?;
+            Some(target - observer)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn siblings_repeat_after_synodic_period() {
+        // Earth and Mars, in days
+        let span = repeat_span(Some(686.98), Some(365.256), true).unwrap();
+        assert!((span - 779.9).abs() < 0.1, "{span}");
+
+        assert_eq!(repeat_span(Some(10.0), Some(10.0), true), Some(10.0));
+    }
+
+    #[test]
+    fn others_repeat_after_longer_period() {
+        assert_eq!(repeat_span(Some(27.3), Some(687.0), false), Some(687.0));
+        assert_eq!(repeat_span(None, Some(365.0), true), Some(365.0));
+        assert_eq!(repeat_span(None, None, false), None);
+        assert_eq!(repeat_span(Some(f64::INFINITY), None, false), None);
+    }
+}
@@ -0,0 +1,117 @@
+//! The frames a rendezvous camera moves with, to show how one body
+//! drifts relative to another like a docking camera would.
+
+use glam::{DMat3, DVec3};
+use keplerian_sim::{OrbitTrait, StateVectors};
+
+use crate::sim::universe::{Id, Universe};
+
+/// The local frame of a body's orbit around its parent, as the columns
+/// radial (away from the parent), along-track and orbit normal.
+///
+/// Returns None if the body isn't orbiting anything, or isn't moving.
+pub(crate) fn local_frame(universe: &Universe, body: Id) -> Option<DMat3> {
+    let time = universe.get_body_time(body)?;
+    let orbit = universe.get_body(body)?.body.orbit.as_ref()?;
+    let StateVectors { position, velocity } = orbit.get_state_vectors_at_time(time);
+
+    let radial = position.try_normalize()?;
+    let normal = position.cross(velocity).try_normalize()?;
+    let along_track = normal.cross(radial);
+
+    Some(DMat3::from_cols(radial, along_track, normal))
+}
+
+/// The rotation that carries directions fixed in the `from` frame
+/// over to the `to` frame.
+pub(crate) fn frame_rotation(from: DMat3, to: DMat3) -> DMat3 {
+    // Local frames are orthonormal, so the transpose is the inverse
+    to * from.transpose()
+}
+
+/// Where `target` is and how fast it's moving, relative to `observer`.
+pub(crate) fn relative_state(
+    universe: &Universe,
+    observer: Id,
+    target: Id,
+) -> Option<StateVectors> {
+    let position = universe.get_body_position(target)? - universe.get_body_position(observer)?;
+    let velocity = universe.get_body_velocity(target)? - universe.get_body_velocity(observer)?;
+
+    Some(StateVectors { position, velocity })
+}
+
+/// The direction from `observer` to `target`, if they aren't in the same place.
+pub(crate) fn direction_to(universe: &Universe, observer: Id, target: Id) -> Option<DVec3> {
+    relative_state(universe, observer, target)?
+        .position
+        .try_normalize()
+}
+
+#[cfg(test)]
+mod tests {
+    use keplerian_sim::Orbit;
+
+    use super::*;
+    use crate::sim::body::Body;
+
+    fn universe_with_moons() -> (Universe, Id, Id, Id) {
+        let mut universe = Universe::default();
+        let planet = universe
+            .add_body(Body::new("Planet".to_owned(), 6e24, 6.4e6, None), None)
+            .unwrap();
+        let orbit = |mean_anomaly| Orbit::new(0.0, 7e6, 0.3, 0.0, 0.0, mean_anomaly, 3.986e14);
+        let first = universe
+            .add_body(
+                Body::new("First".to_owned(), 1e3, 1.0, Some(orbit(0.0))),
+                Some(planet),
+            )
+            .unwrap();
+        let second = universe
+            .add_body(
+                Body::new("Second".to_owned(), 1e3, 1.0, Some(orbit(0.01))),
+                Some(planet),
+            )
+            .unwrap();
+        (universe, planet, first, second)
+    }
+
+    #[test]
+    fn local_frames_are_orthonormal() {
+        let (mut universe, planet, first, _) = universe_with_moons();
+        assert!(local_frame(&universe, planet).is_none());
+
+        for time in [0.0, 1000.0, 2500.0] {
+            universe.time = time;
+            let frame = local_frame(&universe, first).unwrap();
+            let product = frame.transpose() * frame;
+            assert!(product.abs_diff_eq(DMat3::IDENTITY, 1e-12));
+            assert!((frame.determinant() - 1.0).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn frame_rotation_carries_frames_over() {
+        let (mut universe, _, first, _) = universe_with_moons();
+        let before = local_frame(&universe, first).unwrap();
+        universe.time = 1234.0;
+        let after = local_frame(&universe, first).unwrap();
+
+        let rotation = frame_rotation(before, after);
+        assert!((rotation * before).abs_diff_eq(after, 1e-12));
+        assert!(frame_rotation(after, after).abs_diff_eq(DMat3::IDENTITY, 1e-12));
+    }
+
+    #[test]
+    fn relative_state_is_between_bodies() {
+        let (universe, _, first, second) = universe_with_moons();
+        let relative = relative_state(&universe, first, second).unwrap();
+        let expected = universe.get_body_position(second).unwrap()
+            - universe.get_body_position(first).unwrap();
+        assert_eq!(relative.position, expected);
+
+        let direction = direction_to(&universe, first, second).unwrap();
+        assert!((direction.length() - 1.0).abs() < 1e-12);
+        assert!(direction_to(&universe, first, first).is_none());
+    }
+}
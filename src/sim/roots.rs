@@ -0,0 +1,137 @@
+//! Finding where a function crosses zero, for questions like
+//! "when does this angle reach that value?" that have no closed-form answer.
+
+/// The most bisection steps to take before giving up on the tolerance.
+const MAX_BISECTIONS: usize = 200;
+
+/// Narrows down a root of `f` between `lo` and `hi`
+/// until it is known to within `tolerance`.
+///
+/// Returns None if `f(lo)` and `f(hi)` have the same sign,
+/// or either is NaN.
+pub(crate) fn bisect(
+    mut f: impl FnMut(f64) -> f64,
+    mut lo: f64,
+    mut hi: f64,
+    tolerance: f64,
+) -> Option<f64> {
+    let mut f_lo = f(lo);
+    let f_hi = f(hi);
+
+    if f_lo == 0.0 {
+        return Some(lo);
+    }
+    if f_hi == 0.0 {
+        return Some(hi);
+    }
+    if f_lo.is_nan() || f_hi.is_nan() || f_lo.signum() == f_hi.signum() {
+        return None;
+    }
+
+    for _ in 0..MAX_BISECTIONS {
+        if (hi - lo).abs() <= tolerance {
+            break;
+        }
+
+        let mid = lo + (hi - lo) / 2.0;
+        let f_mid = f(mid);
+
+        if f_mid == 0.0 {
+            return Some(mid);
+        }
+        if f_mid.signum() == f_lo.signum() {
+            lo = mid;
+            f_lo = f_mid;
+        } else {
+            hi = mid;
+        }
+    }
+
+    Some(lo + (hi - lo) / 2.0)
+}
+
+/// Finds the first root of `f` between `start` and `end`.
+///
+/// This steps through the range in `steps` equal steps and bisects
+/// the first step `f` changes sign in, so roots closer together than
+/// a step may be missed.
+///
+/// `max_jump`: Sign changes where `f` changes by more than this
+/// over one step are taken to be discontinuities, not roots,
+/// such as an angle wrapping around from 180° to -180°.
+/// Use infinity for continuous functions.
+pub(crate) fn find_first_root(
+    mut f: impl FnMut(f64) -> f64,
+    start: f64,
+    end: f64,
+    steps: usize,
+    tolerance: f64,
+    max_jump: f64,
+) -> Option<f64> {
+    let steps = steps.max(1);
+    let step = (end - start) / steps as f64;
+
+    let mut prev_x = start;
+    let mut prev_y = f(start);
+    if prev_y == 0.0 {
+        return Some(start);
+    }
+
+    for i in 1..=steps {
+        let x = start + step * i as f64;
+        let y = f(x);
+
+        let crosses = y == 0.0 || (y.signum() != prev_y.signum() && (y - prev_y).abs() <= max_jump);
+        if crosses {
+            return bisect(&mut f, prev_x, x, tolerance);
+        }
+
+        prev_x = x;
+        prev_y = y;
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use core::f64::consts::{FRAC_PI_2, PI, TAU};
+
+    use super::*;
+
+    #[test]
+    fn bisects_square_root() {
+        let root = bisect(|x| x * x - 2.0, 0.0, 2.0, 1e-12).unwrap();
+        assert!((root - 2f64.sqrt()).abs() < 1e-11, "got {root}");
+    }
+
+    #[test]
+    fn bisect_needs_a_bracket() {
+        assert_eq!(bisect(|x| x * x + 1.0, -1.0, 1.0, 1e-9), None);
+        assert_eq!(bisect(|_| f64::NAN, -1.0, 1.0, 1e-9), None);
+    }
+
+    #[test]
+    fn finds_first_of_several_roots() {
+        // sin has roots at every multiple of π
+        let root = find_first_root(f64::sin, 1.0, 10.0, 100, 1e-12, f64::INFINITY).unwrap();
+        assert!((root - PI).abs() < 1e-11, "got {root}");
+    }
+
+    #[test]
+    fn skips_wrap_around() {
+        // An angle growing from 90°, wrapped to (-180°, 180°],
+        // jumps from 180° to -180° before it reaches 0°
+        let wrapped = |x: f64| (x + FRAC_PI_2 + PI).rem_euclid(TAU) - PI;
+        let root = find_first_root(wrapped, 0.0, 2.0 * TAU, 1000, 1e-12, PI).unwrap();
+        assert!((root - 1.5 * PI).abs() < 1e-11, "got {root}");
+    }
+
+    #[test]
+    fn reports_no_root() {
+        assert_eq!(
+            find_first_root(|x| x + 1.0, 0.0, 10.0, 10, 1e-9, f64::INFINITY),
+            None
+        );
+    }
+}
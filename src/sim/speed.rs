@@ -0,0 +1,64 @@
+//! Keeping the simulation speed within limits.
+//!
+//! Holding the time slider multiplies the speed every frame, so without
+//! a ceiling it would eventually overflow to infinity and take the
+//! universe's time with it.
+
+/// The slowest the simulation runs by default, in simulated seconds per second.
+pub(crate) const DEFAULT_MIN_SIM_SPEED: f64 = 1e-6;
+
+/// The fastest the simulation runs by default, in simulated seconds per second.
+///
+/// About 30,000 years per second, which still leaves the universe's time
+/// precise to well under a second for millions of years.
+pub(crate) const DEFAULT_MAX_SIM_SPEED: f64 = 1e12;
+
+/// Keeps a simulation speed's magnitude between `min` and `max`,
+/// without changing which way time runs.
+///
+/// A NaN is replaced by normal speed, and zero by `min`,
+/// since a speed of zero can't be sped back up with the slider.
+pub(crate) fn limit_sim_speed(speed: f64, min: f64, max: f64) -> f64 {
+    if speed.is_nan() {
+        return 1.0_f64.clamp(min, max);
+    }
+
+    let magnitude = speed.abs().clamp(min, max);
+    magnitude.copysign(speed)
+}
+
+/// Whether a simulation speed is as fast as `max` allows.
+pub(crate) fn is_at_max_speed(speed: f64, max: f64) -> bool {
+    speed.abs() >= max
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_speeds_within_limits() {
+        assert_eq!(limit_sim_speed(100.0, 1e-6, 1e12), 100.0);
+        assert_eq!(limit_sim_speed(-100.0, 1e-6, 1e12), -100.0);
+        assert_eq!(limit_sim_speed(1e20, 1e-6, 1e12), 1e12);
+        assert_eq!(limit_sim_speed(-1e20, 1e-6, 1e12), -1e12);
+        assert_eq!(limit_sim_speed(1e-9, 1e-6, 1e12), 1e-6);
+        assert_eq!(limit_sim_speed(-1e-9, 1e-6, 1e12), -1e-6);
+    }
+
+    #[test]
+    fn sanitizes_non_finite_speeds() {
+        assert_eq!(limit_sim_speed(f64::INFINITY, 1e-6, 1e12), 1e12);
+        assert_eq!(limit_sim_speed(f64::NEG_INFINITY, 1e-6, 1e12), -1e12);
+        assert_eq!(limit_sim_speed(f64::NAN, 1e-6, 1e12), 1.0);
+        assert_eq!(limit_sim_speed(f64::NAN, 10.0, 1e12), 10.0);
+        assert_eq!(limit_sim_speed(0.0, 1e-6, 1e12), 1e-6);
+    }
+
+    #[test]
+    fn detects_max_speed() {
+        assert!(is_at_max_speed(1e12, 1e12));
+        assert!(is_at_max_speed(-1e12, 1e12));
+        assert!(!is_at_max_speed(1e11, 1e12));
+    }
+}
@@ -0,0 +1,131 @@
+//! Body identifiers that stay the same across sessions and universes.
+//!
+//! Runtime `Id`s are handed out sequentially by each universe, so the same
+//! `Id` means unrelated bodies in two different universes. Content that moves
+//! between universes (the clipboard, templates, saved files) identifies bodies
+//! by their `StableId` instead, and runtime ids are translated with an
+//! [`IdRemap`] when it is imported.
+
+use std::{
+    collections::HashMap,
+    fmt,
+    sync::{Mutex, PoisonError},
+};
+
+use crate::sim::{
+    constraint::{Constraint, ParameterRef},
+    generator::Rng,
+    maneuver::ManeuverNode,
+    universe::Id,
+};
+
+/// A random (version 4) UUID identifying a body.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct StableId(u128);
+
+static GENERATOR: Mutex<Option<Rng>> = Mutex::new(None);
+
+impl StableId {
+    /// Generates a new random id.
+    pub fn new_random() -> Self {
+        let mut generator = GENERATOR.lock().unwrap_or_else(PoisonError::into_inner);
+        let rng = generator.get_or_insert_with(|| Rng::new(entropy()));
+
+        let bits = (rng.next_u64() as u128) << 64 | rng.next_u64() as u128;
+
+        // Version 4, RFC 4122 variant
+        let bits = bits & !(0xf << 76) | (0x4 << 76);
+        let bits = bits & !(0x3 << 62) | (0x2 << 62);
+
+        Self(bits)
+    }
+}
+
+impl fmt::Display for StableId {
+    /// Formats the id the usual hyphenated way,
+    /// e.g. `67e55044-10b1-426f-9247-bb680e5fe0c8`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let bits = self.0;
+        write!(
+            f,
+            "{:08x}-{:04x}-{:04x}-{:04x}-{:012x}",
+            bits >> 96,
+            (bits >> 80) & 0xffff,
+            (bits >> 64) & 0xffff,
+            (bits >> 48) & 0xffff,
+            bits & 0xffff_ffff_ffff,
+        )
+    }
+}
+
+/// Something different every time the program starts,
+/// to seed the id generator with.
+#[cfg(not(target_family = "wasm"))]
+fn entropy() -> u64 {
+    use std::hash::{BuildHasher, RandomState};
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or_default();
+
+    // RandomState is randomly keyed per process
+    RandomState::new().hash_one(nanos)
+}
+
+/// Something different every time the page loads,
+/// to seed the id generator with.
+#[cfg(target_family = "wasm")]
+fn entropy() -> u64 {
+    web_sys::window()
+        .and_then(|w| w.performance())
+        .map(|p| ((p.time_origin() + p.now()) * 1000.0).to_bits())
+        .unwrap_or_default()
+}
+
+/// Where bodies copied from another universe ended up.
+///
+/// Anything that refers to bodies by runtime id, like constraints
+/// and maneuver nodes, has to go through this when it is imported.
+#[derive(Clone, Debug, Default)]
+pub struct IdRemap {
+    ids: HashMap<Id, Id>,
+}
+
+impl IdRemap {
+    pub fn insert(&mut self, old: Id, new: Id) {
+        self.ids.insert(old, new);
+    }
+
+    /// Gets the new id of an imported body.
+    ///
+    /// Returns: None if the body wasn't imported.
+    pub fn get(&self, old: Id) -> Option<Id> {
+        self.ids.get(&old).copied()
+    }
+
+    /// Translates a constraint, if both of its bodies were imported.
+    pub fn constraint(&self, constraint: &Constraint) -> Option<Constraint> {
+        let remap_ref = |r: ParameterRef| {
+            Some(ParameterRef {
+                body: self.get(r.body)?,
+                ..r
+            })
+        };
+
+        Some(Constraint {
+            target: remap_ref(constraint.target)?,
+            source: remap_ref(constraint.source)?,
+            ..*constraint
+        })
+    }
+
+    /// Translates a maneuver node, if its body was imported.
+    pub fn maneuver_node(&self, node: &ManeuverNode) -> Option<ManeuverNode> {
+        Some(ManeuverNode {
+            body: self.get(node.body)?,
+            ..*node
+        })
+    }
+}
@@ -0,0 +1,329 @@
+//! Options for how the program starts, given as URL query parameters
+//! on the web, like `?preset=twin-suns&focus=didyma-a&speed=1000&paused=1`,
+//! or as command line arguments natively, like `--preset=twin-suns --paused`.
+//!
+//! Names are matched loosely: case, spaces, dashes and underscores
+//! are ignored, so `solar-system` and `Solar System` are the same preset.
+
+use core::fmt;
+use std::error::Error;
+
+use strum::IntoEnumIterator;
+
+use crate::sim::{
+    library::StartupUniverse,
+    universe::{Id, Universe},
+};
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub(crate) struct StartupOptions {
+    /// The universe to start with, instead of the one set in the options.
+    pub preset: Option<StartupUniverse>,
+    /// The name of the body to focus on.
+    pub focus: Option<String>,
+    /// The simulation speed to start at.
+    pub speed: Option<f64>,
+    /// Whether to start paused.
+    pub paused: Option<bool>,
+    /// The names of the windows to open, as listed in the options menu.
+    pub windows: Vec<String>,
+    /// Whether to run the benchmark once loaded.
+    pub benchmark: bool,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum StartupOptionError {
+    UnknownParameter(String),
+    UnknownPreset(String),
+    InvalidSpeed(String),
+    InvalidFlag { key: String, value: String },
+}
+
+impl fmt::Display for StartupOptionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StartupOptionError::UnknownParameter(key) => {
+                write!(f, "\"{key}\" isn't a known startup option")
+            }
+            StartupOptionError::UnknownPreset(name) => write!(f, "there's no preset \"{name}\""),
+            StartupOptionError::InvalidSpeed(value) => {
+                write!(f, "the speed \"{value}\" isn't a finite number")
+            }
+            StartupOptionError::InvalidFlag { key, value } => {
+                write!(f, "\"{key}\" should be 1 or 0, not \"{value}\"")
+            }
+        }
+    }
+}
+
+impl Error for StartupOptionError {}
+
+impl StartupOptions {
+    /// Parses a URL query string, with or without the leading `?`.
+    ///
+    /// Parameters that can't be understood are skipped, and returned
+    /// alongside the options that could.
+    pub(crate) fn parse(query: &str) -> (Self, Vec<StartupOptionError>) {
+        let mut options = Self::default();
+        let mut errors = Vec::new();
+
+        let query = query.strip_prefix('?').unwrap_or(query);
+        for pair in query.split('&').filter(|pair| !pair.is_empty()) {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            options.apply(decode_component(key), decode_component(value), &mut errors);
+        }
+
+        (options, errors)
+    }
+
+    /// Parses command line arguments, like `--preset=twin-suns` or `--benchmark`,
+    /// without the program's name.
+    ///
+    /// Arguments that can't be understood are skipped, and returned
+    /// alongside the options that could.
+    pub(crate) fn from_args(
+        args: impl IntoIterator<Item = String>,
+    ) -> (Self, Vec<StartupOptionError>) {
+        let mut options = Self::default();
+        let mut errors = Vec::new();
+
+        for arg in args {
+            let arg = arg.trim_start_matches('-');
+            let (key, value) = arg.split_once('=').unwrap_or((arg, ""));
+            options.apply(key.to_owned(), value.to_owned(), &mut errors);
+        }
+
+        (options, errors)
+    }
+
+    fn apply(&mut self, key: String, value: String, errors: &mut Vec<StartupOptionError>) {
+        match key.as_str() {
+            "preset" => match find_preset(&value) {
+                Some(preset) => self.preset = Some(preset),
+                None => errors.push(StartupOptionError::UnknownPreset(value)),
+            },
+            "focus" => self.focus = Some(value),
+            "speed" => match value.trim().parse::<f64>() {
+                Ok(speed) if speed.is_finite() => self.speed = Some(speed),
+                _ => errors.push(StartupOptionError::InvalidSpeed(value)),
+            },
+            "paused" => match parse_flag(&value) {
+                Some(paused) => self.paused = Some(paused),
+                None => errors.push(StartupOptionError::InvalidFlag { key, value }),
+            },
+            "windows" | "window" => self.windows.extend(
+                value
+                    .split(',')
+                    .filter(|name| !name.trim().is_empty())
+                    .map(String::from),
+            ),
+            "benchmark" => match parse_flag(&value) {
+                Some(benchmark) => self.benchmark = benchmark,
+                None => errors.push(StartupOptionError::InvalidFlag { key, value }),
+            },
+            _ => errors.push(StartupOptionError::UnknownParameter(key)),
+        }
+    }
+
+    /// Whether no options were given.
+    pub(crate) fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Whether two names are the same, ignoring case and separators.
+pub(crate) fn names_match(a: &str, b: &str) -> bool {
+    let loose = |name: &str| {
+        name.chars()
+            .filter(|c| c.is_alphanumeric())
+            .flat_map(char::to_lowercase)
+            .collect::<String>()
+    };
+    loose(a) == loose(b)
+}
+
+fn find_preset(name: &str) -> Option<StartupUniverse> {
+    StartupUniverse::iter().find(|preset| {
+        // "Empty universe" is just "empty" in links
+        names_match(preset.name(), name)
+            || (*preset == StartupUniverse::Empty && names_match("empty", name))
+    })
+}
+
+/// The body called `name`.
+///
+/// A body named exactly that is preferred over loose matches, so
+/// "Didyma B" and "Didyma b" can both be picked. Among several equally good
+/// matches, the one with the lowest id is picked.
+pub(crate) fn find_body(universe: &Universe, name: &str) -> Option<Id> {
+    let bodies = universe.get_bodies();
+    let matching = |matches: fn(&str, &str) -> bool| {
+        bodies
+            .iter()
+            .filter(|(_, wrapper)| matches(&wrapper.body.name, name))
+            .map(|(&id, _)| id)
+            .min()
+    };
+
+    matching(|a, b| a == b).or_else(|| matching(names_match))
+}
+
+fn parse_flag(value: &str) -> Option<bool> {
+    match value.trim().to_ascii_lowercase().as_str() {
+        // A bare `?paused` turns it on
+        "" | "1" | "true" | "yes" | "on" => Some(true),
+        "0" | "false" | "no" | "off" => Some(false),
+        _ => None,
+    }
+}
+
+/// Decodes `+` as a space and `%XX` escapes as UTF-8 bytes.
+///
+/// Malformed escapes are kept as they are.
+fn decode_component(component: &str) -> String {
+    let bytes = component.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = bytes
+            .get(i + 1..i + 3)
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+
+        match (bytes[i], escaped) {
+            (b'+', _) => decoded.push(b' '),
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                i += 2;
+            }
+            (byte, _) => decoded.push(byte),
+        }
+        i += 1;
+    }
+
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sim::body::Body;
+
+    #[test]
+    fn parses_every_option() {
+        let (options, errors) = StartupOptions::parse(
+            "?preset=twin-suns&focus=Didyma+A&speed=1000&paused=1&windows=edit-celestial-body,log",
+        );
+        assert!(errors.is_empty());
+        assert_eq!(
+            options,
+            StartupOptions {
+                preset: Some(StartupUniverse::TwinSuns),
+                focus: Some(String::from("Didyma A")),
+                speed: Some(1000.0),
+                paused: Some(true),
+                windows: vec![String::from("edit-celestial-body"), String::from("log")],
+                benchmark: false,
+            }
+        );
+    }
+
+    #[test]
+    fn bad_parameters_are_skipped() {
+        let (options, errors) =
+            StartupOptions::parse("preset=jupiter&speed=fast&paused=maybe&zoom=2&focus=io");
+        assert_eq!(
+            errors,
+            vec![
+                StartupOptionError::UnknownPreset(String::from("jupiter")),
+                StartupOptionError::InvalidSpeed(String::from("fast")),
+                StartupOptionError::InvalidFlag {
+                    key: String::from("paused"),
+                    value: String::from("maybe"),
+                },
+                StartupOptionError::UnknownParameter(String::from("zoom")),
+            ]
+        );
+        assert_eq!(options.focus.as_deref(), Some("io"));
+        assert_eq!(options.preset, None);
+    }
+
+    #[test]
+    fn parses_command_line_arguments() {
+        let args = ["--benchmark", "--preset=twin-suns", "--paused", "--zoom=2"];
+        let (options, errors) = StartupOptions::from_args(args.map(String::from));
+        assert_eq!(
+            errors,
+            vec![StartupOptionError::UnknownParameter(String::from("zoom"))]
+        );
+        assert!(options.benchmark);
+        assert_eq!(options.preset, Some(StartupUniverse::TwinSuns));
+        assert_eq!(options.paused, Some(true));
+
+        let (options, errors) = StartupOptions::from_args(Vec::new());
+        assert!(options.is_empty());
+        assert!(errors.is_empty());
+    }
+
+    #[test]
+    fn empty_queries_give_no_options() {
+        for query in ["", "?", "&&"] {
+            let (options, errors) = StartupOptions::parse(query);
+            assert!(options.is_empty());
+            assert!(errors.is_empty());
+        }
+    }
+
+    #[test]
+    fn presets_match_loosely() {
+        for name in [
+            "solar-system",
+            "Solar System",
+            "SOLAR_SYSTEM",
+            "solarsystem",
+        ] {
+            assert_eq!(find_preset(name), Some(StartupUniverse::SolarSystem));
+        }
+        assert_eq!(find_preset("empty"), Some(StartupUniverse::Empty));
+    }
+
+    #[test]
+    fn components_are_decoded() {
+        assert_eq!(decode_component("Io%20%C3%A9+x"), "Io é x");
+        assert_eq!(decode_component("100%"), "100%");
+        assert_eq!(decode_component("%zz"), "%zz");
+    }
+
+    #[test]
+    fn bodies_are_found_by_name() {
+        let mut universe = Universe::default();
+        let jupiter = universe
+            .add_body(
+                Body::new("Jupiter".to_owned(), 1.898e27, 6.99e7, None),
+                None,
+            )
+            .unwrap();
+        assert_eq!(find_body(&universe, "jupiter"), Some(jupiter));
+        assert_eq!(find_body(&universe, "io"), None);
+    }
+
+    #[test]
+    fn exact_names_are_preferred() {
+        let universe = StartupUniverse::TwinSuns.create();
+        let name = |id| universe.get_body(id).map(|w| w.body.name.as_str());
+
+        assert_eq!(
+            name(find_body(&universe, "Didyma B").unwrap()),
+            Some("Didyma B")
+        );
+        assert_eq!(
+            name(find_body(&universe, "Didyma b").unwrap()),
+            Some("Didyma b")
+        );
+        assert_eq!(
+            name(find_body(&universe, "didyma-a").unwrap()),
+            Some("Didyma A")
+        );
+    }
+}
@@ -2,10 +2,16 @@
 
 use std::collections::HashSet;
 use std::f64::INFINITY;
+use std::f64::consts::TAU;
 use std::fmt::{self, Debug, Display};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::{collections::HashMap, error::Error};
 
-use crate::sim::body::Body;
+use crate::sim::body::{Body, Rings};
+use crate::sim::constraint::Constraint;
+use crate::sim::maneuver::{self, ManeuverNode};
+use crate::sim::perturbation::{self, BodyPerturbations, PerturbationSettings};
+use crate::sim::stable_id::{IdRemap, StableId};
 use glam::DVec3;
 use keplerian_sim::{MuSetterMode, OrbitTrait};
 use strum_macros::EnumIter;
@@ -13,6 +19,16 @@ pub type Id = u64;
 
 const GRAVITATIONAL_CONSTANT: f64 = 6.6743e-11;
 
+/// Where structure versions are handed out from.
+///
+/// Shared between all universes, so that two different universes
+/// never have the same version.
+static NEXT_STRUCTURE_VERSION: AtomicU64 = AtomicU64::new(0);
+
+fn next_structure_version() -> u64 {
+    NEXT_STRUCTURE_VERSION.fetch_add(1, Ordering::Relaxed)
+}
+
 /// Struct that represents the simulation of the universe.
 #[derive(Clone, Debug)]
 pub struct Universe {
@@ -27,6 +43,44 @@ pub struct Universe {
 
     /// The gravitational constant, in m^3 kg^-1 s^-2.
     g: f64,
+
+    /// Descriptive information about this universe.
+    pub metadata: UniverseMetadata,
+
+    /// Settings for the corrections applied on top of the Keplerian orbits.
+    pub perturbation_settings: PerturbationSettings,
+
+    /// Links between parameters of the bodies.
+    /// Add new ones with `constraint::add_constraint` so loops are rejected.
+    pub constraints: Vec<Constraint>,
+
+    /// Burns that haven't happened yet, in no particular order.
+    pub maneuver_nodes: Vec<ManeuverNode>,
+
+    /// Changes whenever the bodies may have changed. See `structure_version`.
+    structure_version: u64,
+
+    /// How many times the universe was edited. See `edit_count`.
+    edit_count: u64,
+}
+
+/// Descriptive, non-physical information about a universe.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct UniverseMetadata {
+    /// The display name of the universe.
+    pub name: String,
+
+    /// Who made the universe.
+    pub author: String,
+
+    /// A free-form description of the universe.
+    pub description: String,
+
+    /// A label describing what time = 0 corresponds to, e.g. a calendar date.
+    pub epoch_label: String,
+
+    /// The body the camera focuses on when the universe is loaded.
+    pub default_focus: Option<Id>,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -38,7 +92,75 @@ pub struct BodyRelation {
 #[derive(Clone, Debug)]
 pub struct BodyWrapper {
     pub body: Body,
+    /// Identifies this body outside of this universe.
+    /// Unique within the universe, but kept when copied into another one if possible.
+    pub stable_id: StableId,
     pub relations: BodyRelation,
+    /// The universe time this body was frozen at, if it is frozen.
+    ///
+    /// A frozen body stays where it was at that time
+    /// while the rest of the universe keeps running.
+    pub frozen_at: Option<f64>,
+    /// The perturbations this body is affected by.
+    pub perturbations: BodyPerturbations,
+    /// Multiplies the gravitational constant for this body's satellites
+    /// and everything below them, on top of any multipliers further up.
+    ///
+    /// Use `set_g_multiplier` to change this so orbits are updated.
+    pub g_multiplier: f64,
+    /// The ring system around this body, if it has one.
+    pub rings: Option<Rings>,
+    /// Whether this body and its satellites are drawn even when
+    /// distant subsystems are hidden.
+    pub always_show: bool,
+    /// How much of this body's orbit line is drawn while it's hyperbolic.
+    pub hyperbola_range: HyperbolaRange,
+    /// Whether this body is protected from being edited, moved or deleted
+    /// from the GUI until it's unlocked.
+    pub locked: bool,
+}
+
+/// How much of a hyperbolic orbit's line is drawn.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum HyperbolaRange {
+    /// A stretch centered on the body, which gets shorter as it heads out
+    /// so the line doesn't reach absurd distances.
+    #[default]
+    AroundBody,
+    /// The whole part of the path inside the parent's sphere of influence.
+    WithinParentSoi,
+    /// Out to this true anomaly on both sides of the periapsis, in radians.
+    TrueAnomaly(f64),
+}
+
+impl HyperbolaRange {
+    pub fn name(&self) -> &'static str {
+        match self {
+            HyperbolaRange::AroundBody => "Around body",
+            HyperbolaRange::WithinParentSoi => "Within parent's SOI",
+            HyperbolaRange::TrueAnomaly(_) => "Fixed true anomaly",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            HyperbolaRange::AroundBody => {
+                "Draw a stretch of the path around the body, \
+                which gets shorter the further out the body is."
+            }
+            HyperbolaRange::WithinParentSoi => {
+                "Draw the path from where it enters the parent's sphere of influence \
+                to where it leaves it.\n\
+                Falls back to \"Around body\" if the parent has no sphere of influence, \
+                or the periapsis is outside it."
+            }
+            HyperbolaRange::TrueAnomaly(_) => {
+                "Draw the path out to a fixed angle on both sides of the periapsis.\n\
+                The path never reaches the asymptotes, so angles past them \
+                draw as far as the line can go."
+            }
+        }
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -47,6 +169,18 @@ pub struct BodyAddError {
     body: Box<Body>,
 }
 
+impl BodyAddError {
+    /// Why the body couldn't be added.
+    pub fn cause(&self) -> &BodyAddErrorCause {
+        &self.cause
+    }
+
+    /// Gets back the body that couldn't be added.
+    pub fn into_body(self) -> Body {
+        *self.body
+    }
+}
+
 #[non_exhaustive]
 #[derive(Clone, Debug)]
 pub enum BodyAddErrorCause {
@@ -109,6 +243,12 @@ impl Universe {
             next_id: 0,
             time: 0.0,
             g,
+            metadata: UniverseMetadata::default(),
+            perturbation_settings: PerturbationSettings::default(),
+            constraints: Vec::new(),
+            maneuver_nodes: Vec::new(),
+            structure_version: next_structure_version(),
+            edit_count: 0,
         }
     }
 
@@ -118,12 +258,55 @@ impl Universe {
         id
     }
 
+    /// Gets a number that changes whenever the bodies may have changed,
+    /// other than by time passing.
+    ///
+    /// Anything computed from the bodies and the time can be reused
+    /// for as long as both stay the same.  
+    /// Any mutable access to the bodies counts as a change,
+    /// even if nothing ended up being modified.
+    pub fn structure_version(&self) -> u64 {
+        self.structure_version
+    }
+
+    fn mark_changed(&mut self) {
+        self.structure_version = next_structure_version();
+        self.edit_count = self.edit_count.wrapping_add(1);
+    }
+
+    /// Gets a number that changes whenever the universe is edited,
+    /// but not when it's only ticked or its constraints are solved.
+    ///
+    /// Mutable access to the bodies counts as an edit, so anything that only
+    /// reads them should use `get_body` instead of `get_body_mut`.
+    /// Edits to the public fields, like the metadata, count once
+    /// they're reported with `mark_edited`.
+    pub fn edit_count(&self) -> u64 {
+        self.edit_count
+    }
+
+    /// Counts an edit that didn't go through the bodies,
+    /// like to the metadata, constraints or maneuver nodes.
+    pub fn mark_edited(&mut self) {
+        self.edit_count = self.edit_count.wrapping_add(1);
+    }
+
+    /// Runs `f` without counting what it changes as edits,
+    /// for changes the simulation makes on its own.
+    pub(crate) fn without_edits<R>(&mut self, f: impl FnOnce(&mut Self) -> R) -> R {
+        let edit_count = self.edit_count;
+        let result = f(self);
+        self.edit_count = edit_count;
+        result
+    }
+
     /// Adds a body to the universe.
     ///
     /// `body`: The body to add into the universe.  
     /// `parent_id`: The index of the body that this body is orbiting.  
     /// Returns: The index of the newly-added body.  
     pub fn add_body(&mut self, mut body: Body, parent_id: Option<Id>) -> Result<Id, BodyAddError> {
+        self.mark_changed();
         if let Some(parent_id) = parent_id {
             let parent = match self.bodies.get(&parent_id) {
                 Some(b) => b,
@@ -137,7 +320,7 @@ impl Universe {
 
             if let Some(ref mut o) = body.orbit {
                 o.set_gravitational_parameter(
-                    self.g * parent.body.mass,
+                    self.get_local_gravitational_constant(parent_id) * parent.body.mass,
                     MuSetterMode::KeepElements,
                 );
             }
@@ -149,10 +332,18 @@ impl Universe {
             id,
             BodyWrapper {
                 body,
+                stable_id: StableId::new_random(),
                 relations: BodyRelation {
                     parent: parent_id,
                     satellites: Vec::new(),
                 },
+                frozen_at: None,
+                perturbations: BodyPerturbations::default(),
+                g_multiplier: 1.0,
+                rings: None,
+                always_show: false,
+                hyperbola_range: HyperbolaRange::default(),
+                locked: false,
             },
         );
         if let Some(parent_index) = parent_id {
@@ -188,6 +379,17 @@ impl Universe {
         Some(descendants)
     }
 
+    /// Whether this body or any body below it is locked.
+    /// Deleting the body would delete all of them, so it's kept from being deleted.
+    pub fn is_subtree_locked(&self, id: Id) -> bool {
+        let Some(descendants) = self.get_descendants(id) else {
+            return false;
+        };
+        std::iter::once(id)
+            .chain(descendants)
+            .any(|id| self.bodies.get(&id).is_some_and(|w| w.locked))
+    }
+
     /// Removes a body from the universe.
     ///
     /// `body_index`: The index of the body to remove.
@@ -195,6 +397,7 @@ impl Universe {
     /// Returns: A Vec of all bodies that were removed, including the one specified.  
     /// An empty Vec is returned if the body was not found.
     pub fn remove_body(&mut self, body_index: Id) -> Vec<(Id, Body)> {
+        self.mark_changed();
         let wrapper = match self.bodies.remove(&body_index) {
             Some(wrapper) => wrapper,
             None => return Vec::new(),
@@ -203,6 +406,14 @@ impl Universe {
         let (body, relations) = (wrapper.body, wrapper.relations);
         let mut bodies = vec![(body_index, body)];
 
+        if self.metadata.default_focus == Some(body_index) {
+            self.metadata.default_focus = None;
+        }
+
+        self.constraints
+            .retain(|c| c.source.body != body_index && c.target.body != body_index);
+        self.maneuver_nodes.retain(|n| n.body != body_index);
+
         // Remove the body from its parent's satellites.
         if let Some(parent_index) = relations.parent {
             if let Some(parent_wrapper) = self.bodies.get_mut(&parent_index) {
@@ -228,6 +439,7 @@ impl Universe {
 
     /// Gets a mutable reference to a body in the universe.
     pub fn get_body_mut(&mut self, index: Id) -> Option<&mut BodyWrapper> {
+        self.mark_changed();
         self.bodies.get_mut(&index)
     }
 
@@ -236,6 +448,14 @@ impl Universe {
         self.bodies.get(&index)
     }
 
+    /// Gets the index of the body with a given stable id, if any.
+    pub fn get_body_index_with_stable_id(&self, stable_id: StableId) -> Option<Id> {
+        self.bodies
+            .iter()
+            .find(|(_, w)| w.stable_id == stable_id)
+            .map(|(id, _)| *id)
+    }
+
     /// Gets the first index of a body with a given name, if any.
     pub fn get_body_index_with_name(&self, name: &str) -> Option<Id> {
         self.bodies
@@ -245,7 +465,77 @@ impl Universe {
     }
 
     pub fn tick(&mut self, dt: f64) {
-        self.time += dt;
+        self.without_edits(|universe| {
+            universe.time += dt;
+            // Burns can't be undone, so they only happen going forwards
+            if dt >= 0.0 {
+                maneuver::execute_due(universe);
+            }
+            perturbation::apply(universe, dt);
+        });
+    }
+
+    /// Gets the time a body's orbit is evaluated at.
+    ///
+    /// This is the universe's time, unless the body is frozen,
+    /// in which case it is the time the body was frozen at.
+    pub fn get_body_time(&self, index: Id) -> Option<f64> {
+        let wrapper = self.bodies.get(&index)?;
+        Some(wrapper.frozen_at.unwrap_or(self.time))
+    }
+
+    /// Freezes or unfreezes a body.
+    ///
+    /// Frozen bodies stop advancing along their orbit.
+    /// Their satellites keep orbiting them unless they are frozen too.  
+    /// Unfrozen bodies resume from where they were frozen,
+    /// instead of jumping to where they would have been by now.
+    ///
+    /// `include_satellites`: Whether to also (un)freeze all of the body's descendants.
+    ///
+    /// Returns: Whether or not the body was found.
+    pub fn set_body_frozen(&mut self, index: Id, frozen: bool, include_satellites: bool) -> bool {
+        self.mark_changed();
+        let mut ids = vec![index];
+
+        if include_satellites {
+            match self.get_descendants(index) {
+                Some(descendants) => ids.extend(descendants),
+                None => return false,
+            }
+        }
+
+        let time = self.time;
+        let mut found = false;
+
+        for id in ids {
+            let Some(wrapper) = self.bodies.get_mut(&id) else {
+                continue;
+            };
+            found = true;
+
+            match (frozen, wrapper.frozen_at) {
+                (true, None) => wrapper.frozen_at = Some(time),
+                (false, Some(frozen_at)) => {
+                    wrapper.frozen_at = None;
+
+                    // Shift the epoch back by the time spent frozen
+                    if let Some(orbit) = &mut wrapper.body.orbit {
+                        let mean_anomaly = orbit.get_mean_anomaly_at_epoch()
+                            - orbit.get_mean_motion() * (time - frozen_at);
+                        let mean_anomaly = if orbit.is_closed() {
+                            mean_anomaly.rem_euclid(TAU)
+                        } else {
+                            mean_anomaly
+                        };
+                        orbit.set_mean_anomaly_at_epoch(mean_anomaly);
+                    }
+                }
+                _ => (),
+            }
+        }
+
+        found
     }
 
     /// Gets the absolute position of a body in the universe.
@@ -257,16 +547,26 @@ impl Universe {
     /// Returns: The absolute position of the body.  
     /// The top ancestor of the body (i.e, the body with no parent) is at the origin (0, 0, 0).  
     pub fn get_body_position(&self, index: Id) -> Option<DVec3> {
+        self.get_body_position_at_time(index, self.time)
+    }
+
+    /// Gets the absolute position of a body at any time, following
+    /// each orbit up the hierarchy as it is now.
+    ///
+    /// Frozen bodies stay where they were frozen.
+    /// See [`Self::get_body_position`].
+    pub fn get_body_position_at_time(&self, index: Id, time: f64) -> Option<DVec3> {
         let wrapper = self.bodies.get(&index)?;
         let (orbit, parent) = (&wrapper.body.orbit, wrapper.relations.parent);
+        let orbit_time = wrapper.frozen_at.unwrap_or(time);
 
         let mut position = match orbit {
-            Some(orbit) => orbit.get_position_at_time(self.time),
+            Some(orbit) => orbit.get_position_at_time(orbit_time),
             None => DVec3::ZERO, // If the body is not in orbit, its position is the origin
         };
 
         if let Some(parent) = parent {
-            if let Some(parent_position) = self.get_body_position(parent) {
+            if let Some(parent_position) = self.get_body_position_at_time(parent, time) {
                 position += parent_position;
             }
         }
@@ -310,9 +610,10 @@ impl Universe {
 
         let wrapper = self.bodies.get(&index)?;
         let (orbit, parent) = (&wrapper.body.orbit, wrapper.relations.parent);
+        let time = wrapper.frozen_at.unwrap_or(self.time);
 
         let mut position = match orbit {
-            Some(orbit) => orbit.get_position_at_time(self.time),
+            Some(orbit) => orbit.get_position_at_time(time),
             None => DVec3::ZERO, // If the body is not in orbit, its position is the origin
         };
 
@@ -329,9 +630,114 @@ impl Universe {
 
     pub fn get_all_body_positions(&self) -> HashMap<Id, DVec3> {
         let mut map = HashMap::with_capacity(self.bodies.len());
+        self.fill_all_body_positions(&mut map);
+        map
+    }
+
+    /// Like `get_all_body_positions`, but reuses an existing map's allocation.
+    ///
+    /// Anything already in the map is removed first.
+    pub fn fill_all_body_positions(&self, map: &mut HashMap<Id, DVec3>) {
+        map.clear();
 
         for &index in self.bodies.keys() {
-            self.get_body_position_memoized(index, &mut map);
+            self.get_body_position_memoized(index, map);
+        }
+    }
+
+    fn get_body_velocity_memoized(&self, index: Id, map: &mut HashMap<Id, DVec3>) -> Option<DVec3> {
+        if let Some(&v) = map.get(&index) {
+            return Some(v);
+        }
+
+        let wrapper = self.bodies.get(&index)?;
+        let (orbit, parent) = (&wrapper.body.orbit, wrapper.relations.parent);
+
+        // A frozen body isn't moving relative to its parent
+        let mut velocity = match orbit {
+            Some(_) if wrapper.frozen_at.is_some() => DVec3::ZERO,
+            Some(orbit) => orbit.get_velocity_at_time(self.time),
+            None => DVec3::ZERO, // If the body is not in orbit, it is stationary
+        };
+
+        if let Some(parent) = parent {
+            if let Some(parent_velocity) = self.get_body_velocity_memoized(parent, map) {
+                velocity += parent_velocity;
+            }
+        }
+
+        map.insert(index, velocity);
+
+        Some(velocity)
+    }
+
+    /// Gets the inertial velocity of a body, in meters per second,
+    /// relative to the root bodies.
+    pub fn get_body_velocity(&self, index: Id) -> Option<DVec3> {
+        self.get_body_velocity_memoized(index, &mut HashMap::new())
+    }
+
+    /// Gets the inertial velocity of every body in the universe.
+    ///
+    /// Each coordinate is in meters per second, relative to the root bodies.
+    pub fn get_all_body_velocities(&self) -> HashMap<Id, DVec3> {
+        let mut map = HashMap::with_capacity(self.bodies.len());
+
+        for &index in self.bodies.keys() {
+            self.get_body_velocity_memoized(index, &mut map);
+        }
+
+        map
+    }
+
+    fn get_body_acceleration_memoized(
+        &self,
+        index: Id,
+        map: &mut HashMap<Id, DVec3>,
+    ) -> Option<DVec3> {
+        if let Some(&v) = map.get(&index) {
+            return Some(v);
+        }
+
+        let wrapper = self.bodies.get(&index)?;
+        let (orbit, parent) = (&wrapper.body.orbit, wrapper.relations.parent);
+        let time = wrapper.frozen_at.unwrap_or(self.time);
+
+        // a = -µr / |r|³
+        let mut acceleration = match orbit {
+            Some(orbit) => {
+                let position = orbit.get_position_at_time(time);
+                let distance = position.length();
+                if distance > 0.0 {
+                    -orbit.get_gravitational_parameter() * position / distance.powi(3)
+                } else {
+                    DVec3::ZERO
+                }
+            }
+            None => DVec3::ZERO,
+        };
+
+        if let Some(parent) = parent {
+            if let Some(parent_acceleration) = self.get_body_acceleration_memoized(parent, map) {
+                acceleration += parent_acceleration;
+            }
+        }
+
+        map.insert(index, acceleration);
+
+        Some(acceleration)
+    }
+
+    /// Gets the gravitational acceleration of every body in the universe.
+    ///
+    /// Each coordinate is in meters per second squared.
+    /// Only the pull of each body's parent (and its parent's, and so on)
+    /// is taken into account, as per the patched conics model.
+    pub fn get_all_body_accelerations(&self) -> HashMap<Id, DVec3> {
+        let mut map = HashMap::with_capacity(self.bodies.len());
+
+        for &index in self.bodies.keys() {
+            self.get_body_acceleration_memoized(index, &mut map);
         }
 
         map
@@ -351,10 +757,9 @@ impl Universe {
     }
 
     fn duplicate_body_inner(&mut self, index: Id, parent_index: Option<Id>) -> Id {
-        let wrapper = self.get_body(index).unwrap();
-        let body = wrapper.body.clone();
-        let sats = wrapper.relations.satellites.clone();
-        let new_index = self.add_body(body, parent_index).unwrap();
+        let copy = self.get_body(index).unwrap().clone();
+        let sats = copy.relations.satellites.clone();
+        let new_index = self.add_body_copy(copy, parent_index, false);
 
         for sat_index in sats {
             self.duplicate_body_inner(sat_index, Some(new_index));
@@ -362,18 +767,210 @@ impl Universe {
         new_index
     }
 
+    /// Adds a copy of a body, keeping its per-body state but not its relations.
+    ///
+    /// `keep_stable_id`: Whether to keep the copy's stable id,
+    /// which is only done if no other body here has it already.
+    ///
+    /// Panics if the parent doesn't exist.
+    fn add_body_copy(
+        &mut self,
+        copy: BodyWrapper,
+        parent_index: Option<Id>,
+        keep_stable_id: bool,
+    ) -> Id {
+        let keep_stable_id =
+            keep_stable_id && self.get_body_index_with_stable_id(copy.stable_id).is_none();
+
+        let new_index = self.add_body(copy.body, parent_index).unwrap();
+        if let Some(new_wrapper) = self.bodies.get_mut(&new_index) {
+            if keep_stable_id {
+                new_wrapper.stable_id = copy.stable_id;
+            }
+            new_wrapper.frozen_at = copy.frozen_at;
+            new_wrapper.perturbations = copy.perturbations;
+            new_wrapper.g_multiplier = copy.g_multiplier;
+            new_wrapper.rings = copy.rings;
+            new_wrapper.always_show = copy.always_show;
+            new_wrapper.hyperbola_range = copy.hyperbola_range;
+        }
+        new_index
+    }
+
+    /// Copies a body and all of its satellites into a new universe,
+    /// where the body has no parent.
+    ///
+    /// This is what gets put on the clipboard when copying bodies,
+    /// so they can be pasted into another universe with `paste_bodies`.
+    /// Constraints and maneuver nodes between the copied bodies come along.
+    ///
+    /// Returns: None if the body wasn't found.
+    pub fn copy_bodies(&self, index: Id) -> Option<Universe> {
+        self.get_body(index)?;
+
+        let mut copy = Universe {
+            time: self.time,
+            g: self.g,
+            ..Default::default()
+        };
+        let mut remap = IdRemap::default();
+        copy.paste_bodies_inner(self, index, None, &mut remap);
+        copy.import_links(self, &remap);
+        Some(copy)
+    }
+
+    /// Adds copies of every top-level body in `source`, along with
+    /// their satellites, as satellites of `parent_index`.
+    ///
+    /// The bodies keep their stable ids unless they are already taken here,
+    /// e.g. when pasting the same bodies twice. Constraints and maneuver nodes
+    /// in `source` are carried over to the new bodies.
+    ///
+    /// Returns: The new indices of the top-level bodies.
+    pub fn paste_bodies(
+        &mut self,
+        source: &Universe,
+        parent_index: Id,
+    ) -> Result<Vec<Id>, BodyAddErrorCause> {
+        if !self.bodies.contains_key(&parent_index) {
+            return Err(BodyAddErrorCause::ParentNotFound {
+                parent_id: parent_index,
+            });
+        }
+
+        let mut roots: Vec<Id> = source
+            .bodies
+            .iter()
+            .filter(|(_, w)| w.relations.parent.is_none())
+            .map(|(&id, _)| id)
+            .collect();
+        roots.sort_unstable();
+
+        let mut remap = IdRemap::default();
+        let new_roots = roots
+            .into_iter()
+            .map(|id| self.paste_bodies_inner(source, id, Some(parent_index), &mut remap))
+            .collect();
+
+        self.import_links(source, &remap);
+
+        Ok(new_roots)
+    }
+
+    fn paste_bodies_inner(
+        &mut self,
+        source: &Universe,
+        index: Id,
+        parent_index: Option<Id>,
+        remap: &mut IdRemap,
+    ) -> Id {
+        let wrapper = source.get_body(index).unwrap();
+        let new_index = self.add_body_copy(wrapper.clone(), parent_index, true);
+        remap.insert(index, new_index);
+
+        for &sat_index in &wrapper.relations.satellites {
+            self.paste_bodies_inner(source, sat_index, Some(new_index), remap);
+        }
+        new_index
+    }
+
+    /// Copies the constraints and maneuver nodes of `source`
+    /// that only involve bodies imported from it.
+    fn import_links(&mut self, source: &Universe, remap: &IdRemap) {
+        self.constraints.extend(
+            source
+                .constraints
+                .iter()
+                .filter_map(|c| remap.constraint(c)),
+        );
+        self.maneuver_nodes.extend(
+            source
+                .maneuver_nodes
+                .iter()
+                .filter_map(|n| remap.maneuver_node(n)),
+        );
+    }
+
     #[inline]
     pub fn get_gravitational_constant(&self) -> f64 {
         self.g
     }
 
     pub fn set_gravitational_constant(&mut self, new_g: f64, mode: BulkMuSetterMode) {
+        self.mark_changed();
         self.g = new_g;
         self.update_all_gravitational_parameters(mode);
     }
 
+    /// Gets the gravitational constant that the satellites of a body feel,
+    /// which is the universe's, times the G multipliers of the body and its ancestors.
+    ///
+    /// Returns the universe's gravitational constant if the body isn't found.
+    pub fn get_local_gravitational_constant(&self, parent_id: Id) -> f64 {
+        let mut g = self.g;
+        let mut cur = Some(parent_id);
+
+        // Bounded in case of a loop
+        for _ in 0..=self.bodies.len() {
+            let Some(wrapper) = cur.and_then(|id| self.bodies.get(&id)) else {
+                break;
+            };
+            g *= wrapper.g_multiplier;
+            cur = wrapper.relations.parent;
+        }
+
+        g
+    }
+
+    /// Gets the gravitational parameter (µ) of a body, in m^3 s^-2,
+    /// taking G multipliers into account.
+    pub fn get_body_gravitational_parameter(&self, index: Id) -> Option<f64> {
+        let wrapper = self.bodies.get(&index)?;
+        Some(wrapper.body.mass * self.get_local_gravitational_constant(index))
+    }
+
+    /// Sets the G multiplier of a body, and updates the orbits below it.
+    ///
+    /// Returns: Whether or not the body was found.
+    pub fn set_g_multiplier(&mut self, index: Id, multiplier: f64, mode: BulkMuSetterMode) -> bool {
+        self.mark_changed();
+        let Some(wrapper) = self.bodies.get_mut(&index) else {
+            return false;
+        };
+        wrapper.g_multiplier = multiplier;
+        self.update_descendant_gravitational_parameters(index, mode);
+        true
+    }
+
+    /// Resynchronizes the gravitational parameters of every body below a body,
+    /// e.g. after the G multipliers above them changed.
+    fn update_descendant_gravitational_parameters(&mut self, index: Id, mode: BulkMuSetterMode) {
+        let mode = mode.to_mu_setter(self.time);
+
+        let Some(descendants) = self.get_descendants(index) else {
+            return;
+        };
+
+        let changes: Box<[(Id, f64)]> = descendants
+            .into_iter()
+            .filter_map(|id| {
+                let parent_id = self.bodies.get(&id)?.relations.parent?;
+                Some((id, self.get_body_gravitational_parameter(parent_id)?))
+            })
+            .collect();
+
+        for (id, mu) in changes {
+            if let Some(orbit) = self.bodies.get_mut(&id).and_then(|w| w.body.orbit.as_mut())
+                && orbit.get_gravitational_parameter() != mu
+            {
+                orbit.set_gravitational_parameter(mu, mode);
+            }
+        }
+    }
+
     /// Resynchronizes bodies' gravitational parameters to a calculated value.
     pub fn update_all_gravitational_parameters(&mut self, mode: BulkMuSetterMode) {
+        self.mark_changed();
         let mode = mode.to_mu_setter(self.time);
 
         struct MuChange {
@@ -381,14 +978,12 @@ impl Universe {
             new_mu: f64,
         }
 
-        let g = self.g;
         self.bodies
             .iter()
             .filter_map(|(&id, wrapper)| {
                 let parent_id = wrapper.relations.parent?;
-                let parent_mass = self.bodies.get(&parent_id)?.body.mass;
                 let old_mu = wrapper.body.orbit.as_ref()?.get_gravitational_parameter();
-                let new_mu = g * parent_mass;
+                let new_mu = self.get_body_gravitational_parameter(parent_id)?;
                 (old_mu != new_mu).then(|| MuChange {
                     body_id: id,
                     new_mu,
@@ -414,11 +1009,12 @@ impl Universe {
         parent_id: Id,
         mode: BulkMuSetterMode,
     ) -> Result<(), ()> {
+        self.mark_changed();
         let mode = mode.to_mu_setter(self.time);
 
         let parent = self.bodies.get(&parent_id).ok_or(())?;
 
-        let mu = parent.body.mass * self.g;
+        let mu = parent.body.mass * self.get_local_gravitational_constant(parent_id);
 
         parent
             .relations
@@ -442,12 +1038,63 @@ impl Universe {
         Ok(())
     }
 
+    /// Whether moving `body_id` to orbit `new_parent_id` would make
+    /// a body orbit itself, either directly or through its satellites.
+    pub fn would_create_loop(&self, body_id: Id, new_parent_id: Id) -> bool {
+        let mut cur = Some(new_parent_id);
+        // Bounded, in case the relations already contain a loop
+        for _ in 0..=self.bodies.len() {
+            let Some(id) = cur else {
+                return false;
+            };
+            if id == body_id {
+                return true;
+            }
+            cur = self.bodies.get(&id).and_then(|w| w.relations.parent);
+        }
+        true
+    }
+
+    /// Gets the body as it would be after moving it to orbit `new_parent_id`,
+    /// without moving it.
+    ///
+    /// The orbit's gravitational parameter is changed the same way
+    /// [`Universe::move_body`] would change it.
+    pub fn preview_move(
+        &self,
+        body_id: Id,
+        new_parent_id: Id,
+        mode: BulkMuSetterMode,
+    ) -> Result<Body, BodyMoveError> {
+        if self.would_create_loop(body_id, new_parent_id) {
+            return Err(BodyMoveError::NewConfigCreatesLoop);
+        }
+        let wrapper = self
+            .bodies
+            .get(&body_id)
+            .ok_or(BodyMoveError::BodyNotFound)?;
+        let new_parent = self
+            .bodies
+            .get(&new_parent_id)
+            .ok_or(BodyMoveError::NewParentNotFound)?;
+
+        let mut body = wrapper.body.clone();
+        if let Some(orbit) = &mut body.orbit {
+            let gravitational_parameter =
+                self.get_local_gravitational_constant(new_parent_id) * new_parent.body.mass;
+            orbit
+                .set_gravitational_parameter(gravitational_parameter, mode.to_mu_setter(self.time));
+        }
+        Ok(body)
+    }
+
     pub fn move_body(
         &mut self,
         body_id: Id,
         new_parent_id: Option<Id>,
         mode: BulkMuSetterMode,
     ) -> Result<(), BodyMoveError> {
+        self.mark_changed();
         let new_parent_id = match new_parent_id {
             Some(id) => id,
             None => {
@@ -455,25 +1102,22 @@ impl Universe {
                     .bodies
                     .get_mut(&body_id)
                     .ok_or(BodyMoveError::BodyNotFound)?;
-                wrapper.relations.parent = None;
+                let old_parent_id = wrapper.relations.parent.take();
                 wrapper.body.orbit = None;
+                if let Some(old_parent_id) = old_parent_id
+                    && let Some(old_parent) = self.bodies.get_mut(&old_parent_id)
+                {
+                    old_parent.relations.satellites.retain(|&id| id != body_id);
+                }
+                self.update_descendant_gravitational_parameters(body_id, mode);
                 return Ok(());
             }
         };
 
-        if body_id == new_parent_id {
+        if self.would_create_loop(body_id, new_parent_id) {
             return Err(BodyMoveError::NewConfigCreatesLoop);
         }
 
-        // Check for loop
-        let mut cur = Some(new_parent_id);
-        while let Some(id) = cur {
-            if id == body_id {
-                return Err(BodyMoveError::NewConfigCreatesLoop);
-            }
-            cur = self.bodies.get(&id).and_then(|w| w.relations.parent);
-        }
-
         let mut old_parent_id = self.bodies.get(&body_id).and_then(|w| w.relations.parent);
 
         if old_parent_id == Some(new_parent_id) {
@@ -497,6 +1141,8 @@ impl Universe {
             }
         }
 
+        let local_g = self.get_local_gravitational_constant(new_parent_id);
+
         let [body, old_parent, new_parent] = match old_parent_id {
             Some(old_parent_id) => {
                 self.bodies
@@ -525,11 +1171,14 @@ impl Universe {
         body.relations.parent = Some(new_parent_id);
 
         if let Some(orbit) = &mut body.body.orbit {
-            let gravitational_parameter = self.g * new_parent.body.mass;
+            let gravitational_parameter = local_g * new_parent.body.mass;
             orbit
                 .set_gravitational_parameter(gravitational_parameter, mode.to_mu_setter(self.time));
         }
 
+        // The G multipliers above the satellites may have changed too
+        self.update_descendant_gravitational_parameters(body_id, mode);
+
         Ok(())
     }
 }
@@ -542,6 +1191,12 @@ impl Default for Universe {
             time: 0.0,
             g: GRAVITATIONAL_CONSTANT,
             next_id: 0,
+            metadata: UniverseMetadata::default(),
+            perturbation_settings: PerturbationSettings::default(),
+            constraints: Vec::new(),
+            maneuver_nodes: Vec::new(),
+            structure_version: next_structure_version(),
+            edit_count: 0,
         }
     }
 }
@@ -612,3 +1267,380 @@ impl Display for BulkMuSetterMode {
         write!(f, "{}", self.name())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use keplerian_sim::Orbit;
+
+    use super::*;
+
+    /// A small deterministic random number generator (xorshift64*),
+    /// so failures can be replayed from the seed.
+    struct Rng(u64);
+
+    impl Rng {
+        fn next(&mut self) -> u64 {
+            self.0 ^= self.0 >> 12;
+            self.0 ^= self.0 << 25;
+            self.0 ^= self.0 >> 27;
+            self.0.wrapping_mul(0x2545_F491_4F6C_DD1D)
+        }
+
+        fn below(&mut self, n: usize) -> usize {
+            (self.next() % n as u64) as usize
+        }
+
+        /// A random body in the universe, if it has any.
+        fn body(&mut self, universe: &Universe) -> Option<Id> {
+            let mut ids: Vec<Id> = universe.bodies.keys().copied().collect();
+            ids.sort_unstable();
+            (!ids.is_empty()).then(|| ids[self.below(ids.len())])
+        }
+    }
+
+    fn body(name: &str) -> Body {
+        Body::new(name.to_owned(), 1e20, 1e3, None)
+    }
+
+    /// Checks that parents and satellites agree with each other,
+    /// that they all exist, and that no body is its own ancestor.
+    fn check_relations(universe: &Universe) -> Result<(), String> {
+        for (&id, wrapper) in &universe.bodies {
+            if let Some(parent_id) = wrapper.relations.parent {
+                let parent = universe
+                    .bodies
+                    .get(&parent_id)
+                    .ok_or_else(|| format!("{id}'s parent {parent_id} doesn't exist"))?;
+                let listed = parent
+                    .relations
+                    .satellites
+                    .iter()
+                    .filter(|&&sat| sat == id)
+                    .count();
+                if listed != 1 {
+                    return Err(format!("{id} is listed {listed} times by its parent"));
+                }
+            }
+
+            for &sat in &wrapper.relations.satellites {
+                let satellite = universe
+                    .bodies
+                    .get(&sat)
+                    .ok_or_else(|| format!("{id}'s satellite {sat} doesn't exist"))?;
+                if satellite.relations.parent != Some(id) {
+                    return Err(format!("{id} lists {sat}, which orbits something else"));
+                }
+            }
+
+            let mut ancestor = wrapper.relations.parent;
+            for _ in 0..universe.bodies.len() {
+                match ancestor {
+                    Some(a) if a == id => return Err(format!("{id} is its own ancestor")),
+                    Some(a) => ancestor = universe.bodies[&a].relations.parent,
+                    None => break,
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// The names in a body's subtree, in satellite order.
+    fn shape(universe: &Universe, id: Id) -> (String, Vec<(String, usize)>) {
+        let wrapper = &universe.bodies[&id];
+        let mut below = Vec::new();
+        let mut stack: Vec<(Id, usize)> = wrapper
+            .relations
+            .satellites
+            .iter()
+            .rev()
+            .map(|&sat| (sat, 1))
+            .collect();
+        while let Some((cur, depth)) = stack.pop() {
+            let cur_wrapper = &universe.bodies[&cur];
+            below.push((cur_wrapper.body.name.clone(), depth));
+            stack.extend(
+                cur_wrapper
+                    .relations
+                    .satellites
+                    .iter()
+                    .rev()
+                    .map(|&sat| (sat, depth + 1)),
+            );
+        }
+        (wrapper.body.name.clone(), below)
+    }
+
+    #[test]
+    fn random_operations_keep_relations_consistent() {
+        for seed in 1..=20 {
+            let mut rng = Rng(seed * 0x9E37_79B9_7F4A_7C15);
+            let mut universe = Universe::default();
+
+            for step in 0..500 {
+                let op = rng.below(10);
+                let target = rng.body(&universe);
+                let other = rng.body(&universe);
+                match op {
+                    0..=3 => {
+                        let parent = if rng.below(4) == 0 { None } else { other };
+                        universe.add_body(body(&format!("{step}")), parent).unwrap();
+                    }
+                    4 => {
+                        if let Some(target) = target {
+                            universe.remove_body(target);
+                        }
+                    }
+                    5..=7 => {
+                        if let Some(target) = target {
+                            let new_parent = if rng.below(5) == 0 { None } else { other };
+                            let _ = universe.move_body(
+                                target,
+                                new_parent,
+                                BulkMuSetterMode::KeepElements,
+                            );
+                        }
+                    }
+                    _ => {
+                        if let Some(target) = target
+                            && universe.bodies.len() < 200
+                        {
+                            let _ = universe.duplicate_body(target);
+                        }
+                    }
+                }
+
+                if let Err(e) = check_relations(&universe) {
+                    panic!("seed {seed}, step {step}, op {op}: {e}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn remove_body_removes_exactly_the_subtree() {
+        let mut universe = Universe::default();
+        let sun = universe.add_body(body("Sun"), None).unwrap();
+        let earth = universe.add_body(body("Earth"), Some(sun)).unwrap();
+        let moon = universe.add_body(body("Moon"), Some(earth)).unwrap();
+        let probe = universe.add_body(body("Probe"), Some(moon)).unwrap();
+        let mars = universe.add_body(body("Mars"), Some(sun)).unwrap();
+        let comet = universe.add_body(body("Comet"), None).unwrap();
+
+        let mut removed: Vec<Id> = universe
+            .remove_body(earth)
+            .into_iter()
+            .map(|(id, _)| id)
+            .collect();
+        removed.sort_unstable();
+        assert_eq!(removed, [earth, moon, probe]);
+
+        let mut left: Vec<Id> = universe.bodies.keys().copied().collect();
+        left.sort_unstable();
+        assert_eq!(left, [sun, mars, comet]);
+        assert_eq!(universe.bodies[&sun].relations.satellites, [mars]);
+        check_relations(&universe).unwrap();
+
+        assert!(universe.remove_body(earth).is_empty());
+    }
+
+    #[test]
+    fn locks_cover_the_whole_subtree() {
+        let mut universe = Universe::default();
+        let sun = universe.add_body(body("Sun"), None).unwrap();
+        let earth = universe.add_body(body("Earth"), Some(sun)).unwrap();
+        let moon = universe.add_body(body("Moon"), Some(earth)).unwrap();
+        let mars = universe.add_body(body("Mars"), Some(sun)).unwrap();
+
+        assert!(!universe.is_subtree_locked(sun));
+
+        universe.get_body_mut(moon).unwrap().locked = true;
+        assert!(universe.is_subtree_locked(moon));
+        assert!(universe.is_subtree_locked(earth));
+        assert!(universe.is_subtree_locked(sun));
+        assert!(!universe.is_subtree_locked(mars));
+        assert!(!universe.is_subtree_locked(Id::MAX));
+    }
+
+    #[test]
+    fn duplicate_preserves_structure() {
+        let mut universe = Universe::default();
+        let sun = universe.add_body(body("Sun"), None).unwrap();
+        let jupiter = universe.add_body(body("Jupiter"), Some(sun)).unwrap();
+        let io = universe.add_body(body("Io"), Some(jupiter)).unwrap();
+        universe.add_body(body("Europa"), Some(jupiter)).unwrap();
+        universe.add_body(body("Probe"), Some(io)).unwrap();
+
+        let copy = universe.duplicate_body(jupiter).unwrap();
+
+        assert_ne!(copy, jupiter);
+        assert_eq!(shape(&universe, copy), shape(&universe, jupiter));
+        assert_eq!(universe.bodies[&copy].relations.parent, Some(sun));
+        assert_ne!(
+            universe.bodies[&copy].stable_id,
+            universe.bodies[&jupiter].stable_id
+        );
+        check_relations(&universe).unwrap();
+
+        assert!(universe.duplicate_body(sun).is_err());
+    }
+
+    #[test]
+    fn move_body_rejects_loops() {
+        let mut universe = Universe::default();
+        let sun = universe.add_body(body("Sun"), None).unwrap();
+        let earth = universe.add_body(body("Earth"), Some(sun)).unwrap();
+        let moon = universe.add_body(body("Moon"), Some(earth)).unwrap();
+
+        let mode = BulkMuSetterMode::KeepElements;
+        assert!(matches!(
+            universe.move_body(earth, Some(earth), mode),
+            Err(BodyMoveError::NewConfigCreatesLoop)
+        ));
+        assert!(matches!(
+            universe.move_body(sun, Some(moon), mode),
+            Err(BodyMoveError::NewConfigCreatesLoop)
+        ));
+        check_relations(&universe).unwrap();
+
+        universe.move_body(moon, None, mode).unwrap();
+        assert!(universe.bodies[&earth].relations.satellites.is_empty());
+        check_relations(&universe).unwrap();
+    }
+
+    #[test]
+    fn detects_loops_before_moving() {
+        let mut universe = Universe::default();
+        let sun = universe.add_body(body("Sun"), None).unwrap();
+        let earth = universe.add_body(body("Earth"), Some(sun)).unwrap();
+        let moon = universe.add_body(body("Moon"), Some(earth)).unwrap();
+        let mars = universe.add_body(body("Mars"), Some(sun)).unwrap();
+
+        assert!(universe.would_create_loop(earth, earth));
+        assert!(universe.would_create_loop(earth, moon));
+        assert!(universe.would_create_loop(sun, moon));
+        assert!(!universe.would_create_loop(moon, mars));
+        assert!(!universe.would_create_loop(mars, moon));
+
+        let mode = BulkMuSetterMode::KeepElements;
+        assert!(matches!(
+            universe.preview_move(earth, moon, mode),
+            Err(BodyMoveError::NewConfigCreatesLoop)
+        ));
+        let preview = universe.preview_move(moon, mars, mode).unwrap();
+        assert_eq!(preview.name, "Moon");
+        // Previewing leaves the universe as it was
+        assert_eq!(universe.bodies[&moon].relations.parent, Some(earth));
+        check_relations(&universe).unwrap();
+    }
+
+    #[test]
+    fn move_body_recovers_from_self_parenting() {
+        let mut universe = Universe::default();
+        let sun = universe.add_body(body("Sun"), None).unwrap();
+        let rogue = universe.add_body(body("Rogue"), None).unwrap();
+
+        let relations = &mut universe.get_body_mut(rogue).unwrap().relations;
+        relations.parent = Some(rogue);
+        relations.satellites.push(rogue);
+        assert!(check_relations(&universe).is_err());
+
+        universe
+            .move_body(rogue, Some(sun), BulkMuSetterMode::KeepElements)
+            .unwrap();
+        check_relations(&universe).unwrap();
+    }
+
+    #[test]
+    fn only_edits_are_counted_as_edits() {
+        let mut universe = Universe::default();
+        let sun = universe.add_body(body("Sun"), None).unwrap();
+        let orbit = Orbit::new(0.2, 1e9, 0.0, 0.0, 0.0, 0.0, 1.0);
+        let planet = Body::new("Planet".to_owned(), 1e20, 1e3, Some(orbit));
+        let planet = universe.add_body(planet, Some(sun)).unwrap();
+        universe
+            .get_body_mut(planet)
+            .unwrap()
+            .perturbations
+            .relativistic_precession = true;
+        universe.perturbation_settings.precession_scale = 1e20;
+        let arg_pe = |universe: &Universe| {
+            universe
+                .get_body(planet)
+                .unwrap()
+                .body
+                .orbit
+                .as_ref()
+                .unwrap()
+                .get_arg_pe()
+        };
+        let old_arg_pe = arg_pe(&universe);
+        let edit_count = universe.edit_count();
+
+        // Precession rewrites the orbit every tick
+        universe.tick(1e3);
+        assert_ne!(arg_pe(&universe), old_arg_pe);
+        assert_eq!(universe.edit_count(), edit_count);
+
+        universe.mark_edited();
+        assert_ne!(universe.edit_count(), edit_count);
+        let edit_count = universe.edit_count();
+        universe.get_body_mut(planet).unwrap().body.radius = 2e3;
+        assert_ne!(universe.edit_count(), edit_count);
+    }
+
+    /// A star with a planet, and a moon around the planet.
+    fn star_planet_moon() -> (Universe, Id, Id) {
+        let mut universe = Universe::default();
+        let star = Body::new("Star".to_owned(), 2e30, 7e8, None);
+        let star = universe.add_body(star, None).unwrap();
+        let orbit = Orbit::new(0.1, 1.5e11, 0.1, 0.2, 0.3, 0.4, 1.327e20);
+        let planet = Body::new("Planet".to_owned(), 6e24, 6.4e6, Some(orbit));
+        let planet = universe.add_body(planet, Some(star)).unwrap();
+        let orbit = Orbit::new(0.05, 3.8e8, 0.3, 0.1, 0.2, 1.0, 3.98e14);
+        let moon = Body::new("Moon".to_owned(), 7e22, 1.7e6, Some(orbit));
+        let moon = universe.add_body(moon, Some(planet)).unwrap();
+        (universe, planet, moon)
+    }
+
+    fn assert_same_position(universe: &Universe, id: Id, before: DVec3) {
+        let after = universe.get_body_position(id).unwrap();
+        assert!(
+            (after - before).length() <= 1e-9 * before.length(),
+            "{before} jumped to {after}"
+        );
+    }
+
+    #[test]
+    fn unfreezing_continues_from_where_the_body_stopped() {
+        let (mut universe, planet, _) = star_planet_moon();
+        universe.tick(1e6);
+        universe.set_body_frozen(planet, true, false);
+        let frozen_at = universe.get_body_position(planet).unwrap();
+
+        universe.tick(5e6);
+        assert_same_position(&universe, planet, frozen_at);
+
+        universe.set_body_frozen(planet, false, false);
+        assert_same_position(&universe, planet, frozen_at);
+
+        universe.tick(1e3);
+        assert_ne!(universe.get_body_position(planet), Some(frozen_at));
+    }
+
+    #[test]
+    fn unfreezing_with_satellites_continues_them_all() {
+        let (mut universe, planet, moon) = star_planet_moon();
+        universe.tick(1e6);
+        assert!(universe.set_body_frozen(planet, true, true));
+        let positions = [planet, moon].map(|id| universe.get_body_position(id).unwrap());
+
+        universe.tick(5e6);
+        assert!(universe.get_body(moon).unwrap().frozen_at.is_some());
+        assert!(universe.set_body_frozen(planet, false, true));
+
+        for (id, before) in [planet, moon].into_iter().zip(positions) {
+            assert_eq!(universe.get_body(id).unwrap().frozen_at, None);
+            assert_same_position(&universe, id, before);
+        }
+    }
+}
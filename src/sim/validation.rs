@@ -0,0 +1,141 @@
+use crate::sim::universe::{Id, Universe};
+
+/// The default mass ratio (satellite mass / parent mass)
+/// above which a satellite is considered too heavy.
+///
+/// Pluto and Charon (~0.12) are above this; Earth and the Moon (~0.012) are not.
+pub(crate) const DEFAULT_MAX_MASS_RATIO: f64 = 0.05;
+
+/// The lowest mass ratio that can be warned above.
+/// Any lower, and every body with some mass would be warned about.
+pub(crate) const MIN_MAX_MASS_RATIO: f64 = 1e-4;
+pub(crate) const MAX_MAX_MASS_RATIO: f64 = 10.0;
+
+/// Keeps a mass ratio threshold, like one read back from storage,
+/// within the range the settings allow.
+pub(crate) fn clamp_max_mass_ratio(ratio: f64) -> f64 {
+    if ratio.is_nan() {
+        DEFAULT_MAX_MASS_RATIO
+    } else {
+        ratio.clamp(MIN_MAX_MASS_RATIO, MAX_MAX_MASS_RATIO)
+    }
+}
+
+/// How far above its parent's surface [`min_safe_periapsis`] keeps a body,
+/// as a fraction of the parent's radius.
+pub(crate) const SURFACE_MARGIN: f64 = 0.05;
+
+/// The lowest periapsis, from the parent's center, that keeps a body
+/// of `radius` from touching its parent's surface, with some margin.
+pub(crate) fn min_safe_periapsis(parent_radius: f64, radius: f64) -> f64 {
+    parent_radius * (1.0 + SURFACE_MARGIN) + radius
+}
+
+/// A reason a body's simulation may not be accurate.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum Warning {
+    /// The body is heavy enough compared to its parent that both would
+    /// noticeably orbit their shared barycenter, which Keplerian orbits
+    /// around the parent's center can't represent.
+    HeavySatellite {
+        parent_id: Id,
+        /// The body's mass divided by its parent's mass.
+        mass_ratio: f64,
+    },
+}
+
+/// Checks a body for conditions that break the simulation's assumptions.
+///
+/// `max_mass_ratio`: The satellite-to-parent mass ratio to warn above.
+pub(crate) fn validate_body(universe: &Universe, id: Id, max_mass_ratio: f64) -> Vec<Warning> {
+    let mut warnings = Vec::new();
+
+    let Some(wrapper) = universe.get_body(id) else {
+        return warnings;
+    };
+
+    if let Some(parent_id) = wrapper.relations.parent
+        && let Some(parent) = universe.get_body(parent_id)
+        && parent.body.mass > 0.0
+    {
+        let mass_ratio = wrapper.body.mass / parent.body.mass;
+
+        if mass_ratio > max_mass_ratio {
+            warnings.push(Warning::HeavySatellite {
+                parent_id,
+                mass_ratio,
+            });
+        }
+    }
+
+    warnings
+}
+
+#[cfg(test)]
+mod tests {
+    use keplerian_sim::Orbit;
+
+    use super::*;
+    use crate::sim::body::Body;
+
+    /// A parent of `parent_mass` with a satellite of `mass`.
+    fn pair(parent_mass: f64, mass: f64) -> (Universe, Id, Id) {
+        let mut universe = Universe::default();
+        let parent = Body::new("Parent".to_owned(), parent_mass, 6e6, None);
+        let parent = universe.add_body(parent, None).unwrap();
+        let orbit = Orbit::new(0.0, 4e8, 0.0, 0.0, 0.0, 0.0, 1.0);
+        let satellite = Body::new("Satellite".to_owned(), mass, 1e6, Some(orbit));
+        let satellite = universe.add_body(satellite, Some(parent)).unwrap();
+        (universe, parent, satellite)
+    }
+
+    #[test]
+    fn heavy_satellites_are_warned_about() {
+        let (universe, parent, satellite) = pair(1e24, 1e23);
+        let warnings = validate_body(&universe, satellite, DEFAULT_MAX_MASS_RATIO);
+        assert_eq!(warnings.len(), 1);
+        let Warning::HeavySatellite {
+            parent_id,
+            mass_ratio,
+        } = warnings[0];
+        assert_eq!(parent_id, parent);
+        assert!((mass_ratio - 0.1).abs() < 1e-12);
+
+        // The parent itself has nothing above it to be compared to
+        assert!(validate_body(&universe, parent, DEFAULT_MAX_MASS_RATIO).is_empty());
+    }
+
+    #[test]
+    fn light_satellites_are_not_warned_about() {
+        let (universe, _, satellite) = pair(1e24, 1e22);
+        assert!(validate_body(&universe, satellite, DEFAULT_MAX_MASS_RATIO).is_empty());
+        assert_eq!(validate_body(&universe, satellite, 0.001).len(), 1);
+    }
+
+    #[test]
+    fn massless_parents_are_skipped() {
+        let (universe, _, satellite) = pair(0.0, 1e22);
+        assert!(validate_body(&universe, satellite, DEFAULT_MAX_MASS_RATIO).is_empty());
+    }
+
+    #[test]
+    fn mass_ratio_thresholds_are_kept_in_range() {
+        assert_eq!(clamp_max_mass_ratio(0.0), MIN_MAX_MASS_RATIO);
+        assert_eq!(clamp_max_mass_ratio(-1.0), MIN_MAX_MASS_RATIO);
+        assert_eq!(clamp_max_mass_ratio(f64::INFINITY), MAX_MAX_MASS_RATIO);
+        assert_eq!(clamp_max_mass_ratio(f64::NAN), DEFAULT_MAX_MASS_RATIO);
+        assert_eq!(clamp_max_mass_ratio(0.2), 0.2);
+    }
+
+    #[test]
+    fn safe_periapsis_clears_the_surface() {
+        // Low Earth orbit for a 10 m station: 5% of Earth's radius, plus the station
+        let earth_radius = 6.371e6;
+        let min = min_safe_periapsis(earth_radius, 10.0);
+        assert!((min - (earth_radius * 1.05 + 10.0)).abs() < 1e-6);
+
+        // A body as big as its parent has to stay a whole radius further out
+        assert!(min_safe_periapsis(1.0, 1.0) > 2.0);
+        assert_eq!(min_safe_periapsis(0.0, 0.0), 0.0);
+    }
+}
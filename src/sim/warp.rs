@@ -0,0 +1,385 @@
+//! Warping ahead to upcoming events, like a body's next periapsis,
+//! one after another.
+//!
+//! The warp speeds time up towards each event and slows it back down
+//! on the approach, so the event itself plays out at a watchable speed.
+
+use std::{collections::VecDeque, error::Error, fmt};
+
+use keplerian_sim::OrbitTrait;
+
+use crate::sim::universe::{Id, Universe};
+
+/// How long, in real seconds, the warp aims to take to reach its event
+/// from wherever it is.
+///
+/// Since this is worked out again every frame, the speed falls off
+/// smoothly as the event gets closer.
+const APPROACH_TIME_S: f64 = 0.5;
+
+/// Events closer to the current time than this fraction of an orbit
+/// count as just passed, so warping to the next periapsis
+/// right at a periapsis goes to the one after.
+const JUST_PASSED_FRACTION: f64 = 1e-9;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum WarpEvent {
+    /// The body's next closest approach to its parent.
+    Periapsis(Id),
+    /// The body's next farthest point from its parent.
+    Apoapsis(Id),
+    /// The body leaving its parent's sphere of influence.
+    SoiExit(Id),
+    /// A universe time, in seconds.
+    Date(f64),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum WarpEventError {
+    BodyNotFound,
+    /// The body is frozen, so it never gets anywhere.
+    Frozen,
+    /// The body isn't orbiting anything.
+    NoOrbit,
+    /// The body is on an open orbit, which has no apoapsis.
+    OpenOrbit,
+    /// The event only happens once, and that's already in the past.
+    AlreadyPassed,
+    /// The body's orbit never takes it out of its parent's sphere of influence.
+    StaysInSoi,
+}
+
+impl fmt::Display for WarpEventError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WarpEventError::BodyNotFound => write!(f, "the body doesn't exist anymore"),
+            WarpEventError::Frozen => write!(f, "the body is frozen"),
+            WarpEventError::NoOrbit => write!(f, "the body isn't orbiting anything"),
+            WarpEventError::OpenOrbit => write!(f, "open orbits have no apoapsis"),
+            WarpEventError::AlreadyPassed => write!(f, "that's already in the past"),
+            WarpEventError::StaysInSoi => {
+                write!(f, "the body never leaves its parent's sphere of influence")
+            }
+        }
+    }
+}
+
+impl Error for WarpEventError {}
+
+impl WarpEvent {
+    /// The body the event happens to, if any.
+    pub(crate) fn body(self) -> Option<Id> {
+        match self {
+            WarpEvent::Periapsis(id) | WarpEvent::Apoapsis(id) | WarpEvent::SoiExit(id) => Some(id),
+            WarpEvent::Date(_) => None,
+        }
+    }
+
+    /// What kind of event this is, like "Periapsis".
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            WarpEvent::Periapsis(_) => "Periapsis",
+            WarpEvent::Apoapsis(_) => "Apoapsis",
+            WarpEvent::SoiExit(_) => "SOI exit",
+            WarpEvent::Date(_) => "Date",
+        }
+    }
+}
+
+/// When `event` next happens after the universe's current time, in seconds.
+pub(crate) fn next_event_time(
+    universe: &Universe,
+    event: WarpEvent,
+) -> Result<f64, WarpEventError> {
+    let now = universe.time;
+
+    let Some(id) = event.body() else {
+        let WarpEvent::Date(time) = event else {
+            unreachable!("only dates have no body");
+        };
+        return next_occurrence(time, now, None);
+    };
+
+    let wrapper = universe.get_body(id).ok_or(WarpEventError::BodyNotFound)?;
+    if wrapper.frozen_at.is_some() {
+        return Err(WarpEventError::Frozen);
+    }
+    let orbit = wrapper.body.orbit.as_ref().ok_or(WarpEventError::NoOrbit)?;
+
+    let time = match event {
+        WarpEvent::Periapsis(_) => orbit.get_time_of_periapsis(),
+        WarpEvent::Apoapsis(_) => {
+            if !orbit.is_closed() {
+                return Err(WarpEventError::OpenOrbit);
+            }
+            orbit.get_time_of_apoapsis()
+        }
+        WarpEvent::SoiExit(_) => {
+            let soi_radius = wrapper
+                .relations
+                .parent
+                .and_then(|parent| universe.get_soi_radius(parent))
+                .filter(|radius| radius.is_finite())
+                .ok_or(WarpEventError::StaysInSoi)?;
+            if orbit.is_closed() && orbit.get_apoapsis() <= soi_radius {
+                return Err(WarpEventError::StaysInSoi);
+            }
+
+            let true_anomaly = orbit.get_true_anomaly_at_altitude(soi_radius);
+            if !true_anomaly.is_finite() {
+                return Err(WarpEventError::StaysInSoi);
+            }
+            orbit.get_time_at_true_anomaly(true_anomaly)
+        }
+        WarpEvent::Date(_) => unreachable!("dates were handled above"),
+    };
+
+    let period = orbit.is_closed().then(|| orbit.get_orbital_period());
+    next_occurrence(time, now, period)
+}
+
+/// The first time after `now` that an event at `time` happens,
+/// repeating every `period` if it's given.
+fn next_occurrence(time: f64, now: f64, period: Option<f64>) -> Result<f64, WarpEventError> {
+    let next = match period {
+        Some(period) if period.is_finite() && period > 0.0 => {
+            let wait = (time - now).rem_euclid(period);
+            if wait <= period * JUST_PASSED_FRACTION {
+                now + wait + period
+            } else {
+                now + wait
+            }
+        }
+        _ => time,
+    };
+
+    if next > now && next.is_finite() {
+        Ok(next)
+    } else {
+        Err(WarpEventError::AlreadyPassed)
+    }
+}
+
+/// The event being warped to.
+#[derive(Clone, Copy, Debug)]
+struct ActiveWarp {
+    /// When the event happens, in seconds.
+    target: f64,
+    /// The simulation speed before warping, to go back to afterwards.
+    speed_before: f64,
+    /// Whether this frame's step was set to land right on the event.
+    landing: bool,
+}
+
+/// What happened when the warp queue was updated.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum WarpUpdate {
+    /// There's nothing to warp to.
+    Idle,
+    /// On the way to the first event in the queue.
+    Warping,
+    /// The first event was reached, and the speed was put back.
+    Reached(WarpEvent),
+    /// The first event won't happen, so it was dropped.
+    Failed(WarpEvent, WarpEventError),
+}
+
+/// Events waiting to be warped to, in the order they're warped to.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct WarpQueue {
+    entries: VecDeque<WarpEvent>,
+    /// The first entry, once warping to it has started.
+    active: Option<ActiveWarp>,
+}
+
+impl WarpQueue {
+    pub(crate) fn push(&mut self, event: WarpEvent) {
+        self.entries.push_back(event);
+    }
+
+    pub(crate) fn entries(&self) -> impl ExactSizeIterator<Item = WarpEvent> {
+        self.entries.iter().copied()
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// When the event being warped to happens, once warping to it has started.
+    pub(crate) fn target_time(&self) -> Option<f64> {
+        self.active.map(|active| active.target)
+    }
+
+    /// Takes an event out of the queue.
+    ///
+    /// Returns the speed to go back to if it was being warped to.
+    pub(crate) fn cancel(&mut self, index: usize) -> Option<f64> {
+        self.entries.remove(index)?;
+        if index == 0 {
+            self.active.take().map(|active| active.speed_before)
+        } else {
+            None
+        }
+    }
+
+    /// Takes every event out of the queue.
+    ///
+    /// Returns the speed to go back to if one was being warped to.
+    pub(crate) fn clear(&mut self) -> Option<f64> {
+        self.entries.clear();
+        self.active.take().map(|active| active.speed_before)
+    }
+
+    /// Sets the simulation speed for a frame lasting `elapsed_s` real seconds,
+    /// heading towards the first event in the queue.
+    ///
+    /// The speed never goes above `max_speed`, and the frame is cut short
+    /// to land exactly on the event instead of overshooting it.
+    pub(crate) fn update(
+        &mut self,
+        universe: &Universe,
+        sim_speed: &mut f64,
+        elapsed_s: f64,
+        max_speed: f64,
+    ) -> WarpUpdate {
+        let Some(&event) = self.entries.front() else {
+            return WarpUpdate::Idle;
+        };
+
+        let active = match self.active {
+            Some(active) => active,
+            None => match next_event_time(universe, event) {
+                Ok(target) => ActiveWarp {
+                    target,
+                    speed_before: *sim_speed,
+                    landing: false,
+                },
+                Err(e) => {
+                    self.entries.pop_front();
+                    return WarpUpdate::Failed(event, e);
+                }
+            },
+        };
+
+        let remaining = active.target - universe.time;
+        if active.landing || remaining <= 0.0 {
+            self.entries.pop_front();
+            self.active = None;
+            *sim_speed = active.speed_before;
+            return WarpUpdate::Reached(event);
+        }
+
+        if elapsed_s <= 0.0 {
+            self.active = Some(active);
+            return WarpUpdate::Warping;
+        }
+
+        // Never slower than before warping, and always forwards
+        let slowest = active.speed_before.abs().min(max_speed);
+        let speed = (remaining / APPROACH_TIME_S).clamp(slowest, max_speed);
+
+        let landing = speed * elapsed_s >= remaining;
+        *sim_speed = if landing {
+            remaining / elapsed_s
+        } else {
+            speed
+        };
+
+        self.active = Some(ActiveWarp { landing, ..active });
+        WarpUpdate::Warping
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_next_occurrence() {
+        assert_eq!(next_occurrence(5.0, 2.0, None), Ok(5.0));
+        assert_eq!(
+            next_occurrence(1.0, 2.0, None),
+            Err(WarpEventError::AlreadyPassed)
+        );
+        assert_eq!(next_occurrence(1.0, 2.0, Some(10.0)), Ok(11.0));
+        assert_eq!(next_occurrence(35.0, 2.0, Some(10.0)), Ok(5.0));
+        // Right at the event, so the next one
+        assert_eq!(next_occurrence(2.0, 2.0, Some(10.0)), Ok(12.0));
+    }
+
+    #[test]
+    fn dates_in_the_past_fail() {
+        let mut universe = Universe::default();
+        universe.time = 100.0;
+
+        assert_eq!(
+            next_event_time(&universe, WarpEvent::Date(200.0)),
+            Ok(200.0)
+        );
+        assert_eq!(
+            next_event_time(&universe, WarpEvent::Date(50.0)),
+            Err(WarpEventError::AlreadyPassed)
+        );
+        assert_eq!(
+            next_event_time(&universe, WarpEvent::Periapsis(0)),
+            Err(WarpEventError::BodyNotFound)
+        );
+    }
+
+    #[test]
+    fn warps_onto_events_in_order() {
+        let mut universe = Universe::default();
+        let mut queue = WarpQueue::default();
+        queue.push(WarpEvent::Date(1e6));
+        queue.push(WarpEvent::Date(5e5));
+        queue.push(WarpEvent::Date(2e6));
+
+        let mut speed = 2.0;
+        let mut reached = Vec::new();
+        let mut failed = Vec::new();
+
+        for _ in 0..10_000 {
+            match queue.update(&universe, &mut speed, 1.0 / 60.0, 1e12) {
+                WarpUpdate::Idle => break,
+                WarpUpdate::Warping => assert!(speed <= 1e12),
+                WarpUpdate::Reached(event) => {
+                    assert_eq!(speed, 2.0);
+                    reached.push((event, universe.time));
+                }
+                WarpUpdate::Failed(event, e) => failed.push((event, e)),
+            }
+            universe.tick(speed / 60.0);
+        }
+
+        assert!(queue.is_empty());
+        assert_eq!(reached.len(), 2);
+        for (event, time) in reached {
+            let WarpEvent::Date(target) = event else {
+                panic!("only dates were queued");
+            };
+            assert!((time - target).abs() < 1e-6 * target, "{time} vs {target}");
+        }
+        // Already passed by the time it came up
+        assert_eq!(
+            failed,
+            [(WarpEvent::Date(5e5), WarpEventError::AlreadyPassed)]
+        );
+    }
+
+    #[test]
+    fn cancelling_restores_speed() {
+        let universe = Universe::default();
+        let mut queue = WarpQueue::default();
+        queue.push(WarpEvent::Date(1e6));
+        queue.push(WarpEvent::Date(2e6));
+
+        let mut speed = 3.0;
+        queue.update(&universe, &mut speed, 1.0 / 60.0, 1e12);
+        assert!(speed > 3.0);
+
+        assert_eq!(queue.cancel(1), None);
+        assert_eq!(queue.cancel(0), Some(3.0));
+        assert!(queue.is_empty());
+        assert_eq!(queue.cancel(0), None);
+    }
+}
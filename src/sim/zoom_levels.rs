@@ -0,0 +1,185 @@
+//! Camera distances that show a body at meaningful scales,
+//! instead of zooming there a wheel step at a time.
+
+use keplerian_sim::OrbitTrait;
+use strum_macros::EnumIter;
+
+use crate::sim::universe::{Id, Universe};
+
+/// How far out a close-up is, in radii of the body.
+///
+/// Matches the closest the camera may get.
+const SURFACE_DISTANCE_RADII: f64 = 1.5;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, EnumIter)]
+pub(crate) enum ZoomLevel {
+    /// Just above the surface.
+    Surface,
+    /// Three radii from the body's center.
+    ThreeRadii,
+    /// The whole sphere of influence in view.
+    Soi,
+    /// The body's whole orbit around its parent in view.
+    ParentSystem,
+}
+
+impl ZoomLevel {
+    pub(crate) const fn name(self) -> &'static str {
+        match self {
+            ZoomLevel::Surface => "Surface",
+            ZoomLevel::ThreeRadii => "3 radii",
+            ZoomLevel::Soi => "SOI",
+            ZoomLevel::ParentSystem => "Parent system",
+        }
+    }
+
+    pub(crate) const fn description(self) -> &'static str {
+        match self {
+            ZoomLevel::Surface => "Zoom in as close to the surface as the camera goes.",
+            ZoomLevel::ThreeRadii => "Zoom to three radii from the body's center.",
+            ZoomLevel::Soi => {
+                "Zoom out until the body's whole sphere of influence is in view, \
+                where its satellites can orbit."
+            }
+            ZoomLevel::ParentSystem => {
+                "Zoom out until the body's whole orbit around its parent is in view."
+            }
+        }
+    }
+
+    /// The key that zooms to this level.
+    pub(crate) const fn key(self) -> char {
+        match self {
+            ZoomLevel::Surface => '1',
+            ZoomLevel::ThreeRadii => '2',
+            ZoomLevel::Soi => '3',
+            ZoomLevel::ParentSystem => '4',
+        }
+    }
+
+    pub(crate) fn from_key(key: char) -> Option<Self> {
+        match key {
+            '1' => Some(ZoomLevel::Surface),
+            '2' => Some(ZoomLevel::ThreeRadii),
+            '3' => Some(ZoomLevel::Soi),
+            '4' => Some(ZoomLevel::ParentSystem),
+            _ => None,
+        }
+    }
+}
+
+/// Where to put the camera for a zoom level, relative to the body's center.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum ZoomTarget {
+    /// This far from the center, in meters.
+    Distance(f64),
+    /// Far enough out that a sphere of this radius, in meters, fits in view.
+    Sphere(f64),
+}
+
+/// Where to put the camera to see `body` at `level`.
+///
+/// Returns None if the body doesn't exist, or the level doesn't apply to it,
+/// like the SOI of a root body, which is infinite.
+pub(crate) fn zoom_target(universe: &Universe, body: Id, level: ZoomLevel) -> Option<ZoomTarget> {
+    let wrapper = universe.get_body(body)?;
+    let radius = wrapper.body.radius;
+
+    let target = match level {
+        ZoomLevel::Surface => ZoomTarget::Distance(SURFACE_DISTANCE_RADII * radius),
+        ZoomLevel::ThreeRadii => ZoomTarget::Distance(3.0 * radius),
+        ZoomLevel::Soi => ZoomTarget::Sphere(universe.get_soi_radius(body)?),
+        ZoomLevel::ParentSystem => {
+            wrapper.relations.parent?;
+            let orbit = wrapper.body.orbit.as_ref()?;
+            let time = universe.get_body_time(body)?;
+            let distance = orbit.get_position_at_time(time).length();
+            // The camera stays on the body, so the far side of the orbit
+            // is its distance to the parent plus the apoapsis away
+            let far_side = if orbit.is_closed() {
+                orbit.get_apoapsis()
+            } else {
+                distance
+            };
+            ZoomTarget::Sphere(distance + far_side)
+        }
+    };
+
+    let meters = match target {
+        ZoomTarget::Distance(meters) | ZoomTarget::Sphere(meters) => meters,
+    };
+    (meters.is_finite() && meters > 0.0).then_some(target)
+}
+
+#[cfg(test)]
+mod tests {
+    use keplerian_sim::Orbit;
+    use strum::IntoEnumIterator;
+
+    use super::*;
+    use crate::sim::body::Body;
+
+    fn earth_and_moon() -> (Universe, Id, Id) {
+        let mut universe = Universe::default();
+        let earth = universe
+            .add_body(Body::new("Earth".to_owned(), 5.972e24, 6.371e6, None), None)
+            .unwrap();
+        let orbit = Orbit::new(0.0, 3.844e8, 0.0, 0.0, 0.0, 0.0, 3.986e14);
+        let moon = universe
+            .add_body(
+                Body::new("Moon".to_owned(), 7.342e22, 1.737e6, Some(orbit)),
+                Some(earth),
+            )
+            .unwrap();
+        (universe, earth, moon)
+    }
+
+    #[test]
+    fn radius_levels_scale_with_the_body() {
+        let (universe, _, moon) = earth_and_moon();
+        assert_eq!(
+            zoom_target(&universe, moon, ZoomLevel::ThreeRadii),
+            Some(ZoomTarget::Distance(3.0 * 1.737e6))
+        );
+        assert_eq!(
+            zoom_target(&universe, moon, ZoomLevel::Surface),
+            Some(ZoomTarget::Distance(1.5 * 1.737e6))
+        );
+    }
+
+    #[test]
+    fn soi_level_fits_the_soi() {
+        let (universe, _, moon) = earth_and_moon();
+        let soi = universe.get_soi_radius(moon).unwrap();
+        assert_eq!(
+            zoom_target(&universe, moon, ZoomLevel::Soi),
+            Some(ZoomTarget::Sphere(soi))
+        );
+    }
+
+    #[test]
+    fn parent_system_level_fits_the_orbit() {
+        let (universe, _, moon) = earth_and_moon();
+        let Some(ZoomTarget::Sphere(radius)) =
+            zoom_target(&universe, moon, ZoomLevel::ParentSystem)
+        else {
+            panic!("expected a sphere to fit");
+        };
+        assert!((radius - 2.0 * 3.844e8).abs() < 1.0);
+    }
+
+    #[test]
+    fn root_bodies_only_have_radius_levels() {
+        let (universe, earth, _) = earth_and_moon();
+        assert_eq!(zoom_target(&universe, earth, ZoomLevel::Soi), None);
+        assert_eq!(zoom_target(&universe, earth, ZoomLevel::ParentSystem), None);
+        assert!(zoom_target(&universe, earth, ZoomLevel::ThreeRadii).is_some());
+    }
+
+    #[test]
+    fn keys_round_trip() {
+        for level in ZoomLevel::iter() {
+            assert_eq!(ZoomLevel::from_key(level.key()), Some(level));
+        }
+    }
+}
@@ -4,6 +4,7 @@ use strum::IntoEnumIterator;
 
 pub(crate) mod length;
 pub(crate) mod mass;
+pub(crate) mod scientific;
 pub(crate) mod time;
 
 pub(crate) trait UnitEnum: Copy + Display + Eq + Ord + IntoEnumIterator + FromStr {
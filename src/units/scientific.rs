@@ -0,0 +1,211 @@
+//! Reading and writing numbers in scientific notation, for fields where
+//! an exact value like 5.972e24 matters more than dragging to it.
+
+use std::{error::Error, fmt};
+
+use crate::units::UnitEnum;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum NumberParseError {
+    Empty,
+    NotANumber,
+    /// The number was followed by something that isn't one of the units.
+    UnknownUnit(String),
+    /// The number is too large to store.
+    NotFinite,
+}
+
+impl fmt::Display for NumberParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NumberParseError::Empty => write!(f, "enter a number"),
+            NumberParseError::NotANumber => {
+                write!(f, "not a number; try something like 5.972e24")
+            }
+            NumberParseError::UnknownUnit(unit) => write!(f, "unknown unit \"{unit}\""),
+            NumberParseError::NotFinite => write!(f, "the number is too large"),
+        }
+    }
+}
+
+impl Error for NumberParseError {}
+
+/// Reads a number like "5.972e24", "5.972×10^24" or "1 000 000".
+///
+/// Spaces and underscores between digits are ignored.
+pub(crate) fn parse_scientific(text: &str) -> Result<f64, NumberParseError> {
+    let mut number: String = text
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != '_')
+        .map(|c| if c == '−' { '-' } else { c })
+        .collect();
+
+    if number.is_empty() {
+        return Err(NumberParseError::Empty);
+    }
+
+    for times_ten in ["×10^", "x10^", "X10^", "*10^", "·10^"] {
+        number = number.replace(times_ten, "e");
+    }
+
+    let value: f64 = number.parse().map_err(|_| NumberParseError::NotANumber)?;
+    if value.is_finite() {
+        Ok(value)
+    } else if value.is_nan() {
+        Err(NumberParseError::NotANumber)
+    } else {
+        Err(NumberParseError::NotFinite)
+    }
+}
+
+/// Reads a number like [`parse_scientific`] does,
+/// optionally followed by one of the unit's names, like "1.5e8 km".
+///
+/// Returns the number as written, along with the unit if one was given.
+/// Unit names are matched regardless of case.
+pub(crate) fn parse_scientific_with_unit<U: UnitEnum>(
+    text: &str,
+) -> Result<(f64, Option<U>), NumberParseError> {
+    let text = text.trim();
+
+    // Longest name first, so "mm" isn't read as "m"
+    let mut units: Vec<(U, String)> = U::iter().map(|u| (u, u.to_string())).collect();
+    units.sort_by_key(|(_, name)| std::cmp::Reverse(name.len()));
+
+    for (unit, name) in units {
+        let Some(split) = text.len().checked_sub(name.len()) else {
+            continue;
+        };
+        let Some((number, suffix)) = text.split_at_checked(split) else {
+            continue;
+        };
+        if suffix.eq_ignore_ascii_case(&name)
+            && let Ok(value) = parse_scientific(number)
+        {
+            return Ok((value, Some(unit)));
+        }
+    }
+
+    match parse_scientific(text) {
+        Ok(value) => Ok((value, None)),
+        Err(NumberParseError::NotANumber) => {
+            // Name what looks like a unit, if it follows a valid number
+            let word = text
+                .rsplit(|c: char| c.is_whitespace() || c.is_ascii_digit())
+                .next()
+                .unwrap_or_default();
+            let number = &text[..text.len() - word.len()];
+            let is_unit = !word.is_empty()
+                && word.chars().all(char::is_alphabetic)
+                && parse_scientific(number).is_ok();
+            if is_unit {
+                Err(NumberParseError::UnknownUnit(word.to_owned()))
+            } else {
+                Err(NumberParseError::NotANumber)
+            }
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// Writes a number in scientific notation, with as many digits
+/// as it takes to read back exactly the same number.
+pub(crate) fn format_scientific(value: f64) -> String {
+    format!("{value:e}")
+}
+
+/// Multiplies a number by a power of ten by changing its decimal exponent,
+/// so 5.972e24 becomes exactly 5.972e25 rather than 5.972e24 × 10.
+///
+/// Returns the number unchanged if the result would be too large.
+pub(crate) fn shift_exponent(value: f64, by: i32) -> f64 {
+    let text = format_scientific(value);
+    let Some((mantissa, exponent)) = text.split_once('e') else {
+        return value;
+    };
+    let Ok(exponent) = exponent.parse::<i32>() else {
+        return value;
+    };
+
+    format!("{mantissa}e{}", exponent.saturating_add(by))
+        .parse::<f64>()
+        .ok()
+        .filter(|shifted| shifted.is_finite())
+        .unwrap_or(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::units::{
+        length::LengthUnit,
+        mass::MassUnit,
+        scientific::{
+            NumberParseError, format_scientific, parse_scientific, parse_scientific_with_unit,
+            shift_exponent,
+        },
+    };
+
+    #[test]
+    fn test_parse_scientific() {
+        assert_eq!(parse_scientific("5.972e24"), Ok(5.972e24));
+        assert_eq!(parse_scientific("5.972E24"), Ok(5.972e24));
+        assert_eq!(parse_scientific("5.972×10^24"), Ok(5.972e24));
+        assert_eq!(parse_scientific("5.972 x 10^24"), Ok(5.972e24));
+        assert_eq!(parse_scientific("6.674e-11"), Ok(6.674e-11));
+        assert_eq!(parse_scientific("−3"), Ok(-3.0));
+        assert_eq!(parse_scientific("1 000 000"), Ok(1e6));
+        assert_eq!(parse_scientific("1_000"), Ok(1e3));
+
+        assert_eq!(parse_scientific("  "), Err(NumberParseError::Empty));
+        assert_eq!(parse_scientific("abc"), Err(NumberParseError::NotANumber));
+        assert_eq!(parse_scientific("NaN"), Err(NumberParseError::NotANumber));
+        assert_eq!(parse_scientific("1e400"), Err(NumberParseError::NotFinite));
+    }
+
+    #[test]
+    fn test_parse_scientific_with_unit() {
+        assert_eq!(
+            parse_scientific_with_unit::<LengthUnit>("1.5e8 km"),
+            Ok((1.5e8, Some(LengthUnit::Kilometers)))
+        );
+        assert_eq!(
+            parse_scientific_with_unit::<LengthUnit>("3mm"),
+            Ok((3.0, Some(LengthUnit::Millimeters)))
+        );
+        assert_eq!(
+            parse_scientific_with_unit::<LengthUnit>("3 Meter"),
+            Ok((3.0, Some(LengthUnit::Meters)))
+        );
+        assert_eq!(
+            parse_scientific_with_unit::<MassUnit>("1 earth"),
+            Ok((1.0, Some(MassUnit::EarthMasses)))
+        );
+        assert_eq!(
+            parse_scientific_with_unit::<MassUnit>("5.972e24"),
+            Ok((5.972e24, None))
+        );
+        assert_eq!(
+            parse_scientific_with_unit::<MassUnit>("5 lbs"),
+            Err(NumberParseError::UnknownUnit("lbs".to_owned()))
+        );
+        assert_eq!(
+            parse_scientific_with_unit::<MassUnit>("kg"),
+            Err(NumberParseError::NotANumber)
+        );
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for value in [5.972e24, 6.6743e-11, 1.0, 0.1 + 0.2, 1.988416e30] {
+            assert_eq!(parse_scientific(&format_scientific(value)), Ok(value));
+        }
+    }
+
+    #[test]
+    fn test_shift_exponent() {
+        assert_eq!(shift_exponent(5.972e24, 1), 5.972e25);
+        assert_eq!(shift_exponent(5.972e24, -3), 5.972e21);
+        assert_eq!(shift_exponent(0.3, 2), 30.0);
+        assert_eq!(shift_exponent(1e308, 10), 1e308);
+    }
+}
@@ -116,6 +116,113 @@ impl UnitEnum for TimeUnit {
     }
 }
 
+impl TimeUnit {
+    /// Reads a unit from its symbol or its name, like "d", "day" or "days".
+    pub(crate) fn from_name(name: &str) -> Option<Self> {
+        if let Ok(unit) = Self::from_str(name) {
+            return Some(unit);
+        }
+
+        match name.to_lowercase().as_str() {
+            "ns" | "nanosecond" | "nanoseconds" => Some(TimeUnit::Nanos),
+            "us" | "µs" | "microsecond" | "microseconds" => Some(TimeUnit::Micros),
+            "ms" | "millisecond" | "milliseconds" => Some(TimeUnit::Millis),
+            "s" | "sec" | "secs" | "second" | "seconds" => Some(TimeUnit::Seconds),
+            "min" | "mins" | "minute" | "minutes" => Some(TimeUnit::Minutes),
+            "h" | "hr" | "hrs" | "hour" | "hours" => Some(TimeUnit::Hours),
+            "d" | "day" | "days" => Some(TimeUnit::Days),
+            "y" | "yr" | "yrs" | "year" | "years" => Some(TimeUnit::Years),
+            _ => None,
+        }
+    }
+}
+
+/// Reads a duration made of one or more amounts with units, like
+/// "3.2 years" or "100 d 4 h", into seconds.
+///
+/// The amounts can be separated with commas, so the multi-unit display
+/// can be pasted back in. A lone number without a unit is in seconds.
+/// A leading minus makes the whole duration negative.
+pub(crate) fn parse_duration(text: &str) -> Option<f64> {
+    let text = text.trim();
+    let (sign, text) = match text.strip_prefix(['-', '−']) {
+        Some(rest) => (-1.0, rest.trim_start()),
+        None => (1.0, text),
+    };
+
+    if let Ok(seconds) = text.parse::<f64>() {
+        return seconds.is_finite().then_some(sign * seconds);
+    }
+
+    let mut rest = text;
+    let mut total = 0.0;
+    let mut parts = 0;
+
+    while !rest.is_empty() {
+        let number_end = rest
+            .find(|c: char| !(c.is_ascii_digit() || matches!(c, '.' | 'e' | 'E')))
+            .unwrap_or(rest.len());
+        let (number, after) = rest.split_at(number_end);
+        let amount: f64 = number.parse().ok()?;
+
+        let after = after.trim_start();
+        let unit_end = after
+            .find(|c: char| c.is_whitespace() || c == ',' || c.is_ascii_digit())
+            .unwrap_or(after.len());
+        let (unit, after) = after.split_at(unit_end);
+        let unit = TimeUnit::from_name(unit)?;
+
+        total += amount * unit.get_value();
+        parts += 1;
+        rest = after.trim_start().trim_start_matches(',').trim_start();
+    }
+
+    (parts > 0 && total.is_finite()).then_some(sign * total)
+}
+
+/// What was typed into the time field.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum TimeEntry {
+    /// Jump to this many seconds.
+    At(f64),
+    /// Move by this many seconds, like "+30 d".
+    By(f64),
+    /// Move by this many orbits of the focused body, like "+1 orbit".
+    Orbits(f64),
+}
+
+/// Reads what was typed into the time field.
+///
+/// A leading `+` or `-` makes it relative to the current time, and then
+/// the unit can also be "orbit" or "orbits". Negative absolute times are
+/// written with a `−` (U+2212), like the time display shows them.
+pub(crate) fn parse_time_entry(text: &str) -> Option<TimeEntry> {
+    let text = text.trim();
+    let (sign, delta) = if let Some(rest) = text.strip_prefix('+') {
+        (1.0, rest)
+    } else if let Some(rest) = text.strip_prefix('-') {
+        (-1.0, rest)
+    } else {
+        return parse_duration(text).map(TimeEntry::At);
+    };
+
+    let delta = delta.trim();
+    if let Some(amount) = delta
+        .strip_suffix("orbits")
+        .or_else(|| delta.strip_suffix("orbit"))
+    {
+        let amount = amount.trim();
+        let amount: f64 = if amount.is_empty() {
+            1.0
+        } else {
+            amount.parse().ok()?
+        };
+        return Some(TimeEntry::Orbits(sign * amount));
+    }
+
+    parse_duration(delta).map(|seconds| TimeEntry::By(sign * seconds))
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, EnumCount, EnumIter)]
 pub(crate) enum TimeDisplayMode {
     /// e.g. `1755069111.3 s`,
@@ -224,7 +331,9 @@ impl Display for TimeDisplayMode {
 
 #[cfg(test)]
 mod tests {
-    use crate::units::time::TimeDisplayMode;
+    use crate::units::time::{
+        DAY, HOUR, TimeDisplayMode, TimeEntry, YEAR, parse_duration, parse_time_entry,
+    };
     use std::collections::HashSet;
 
     const TIME_DISPLAY_ENUM_VARIANTS: usize = 3;
@@ -246,4 +355,39 @@ mod tests {
             assert_eq!(variant, next_prev);
         }
     }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("42"), Some(42.0));
+        assert_eq!(parse_duration("3.2 years"), Some(3.2 * YEAR));
+        assert_eq!(parse_duration("100 d 4 h"), Some(100.0 * DAY + 4.0 * HOUR));
+        assert_eq!(parse_duration("100d4h"), Some(100.0 * DAY + 4.0 * HOUR));
+        assert_eq!(
+            parse_duration("1 y, 2 d, 3 h"),
+            Some(YEAR + 2.0 * DAY + 3.0 * HOUR)
+        );
+        assert_eq!(parse_duration("−2 Days"), Some(-2.0 * DAY));
+        assert_eq!(parse_duration("1.5e3 s"), Some(1500.0));
+
+        assert_eq!(parse_duration(""), None);
+        assert_eq!(parse_duration("3 fortnights"), None);
+        assert_eq!(parse_duration("d 4"), None);
+    }
+
+    #[test]
+    fn test_parse_time_entry() {
+        assert_eq!(parse_time_entry("2 d"), Some(TimeEntry::At(2.0 * DAY)));
+        assert_eq!(parse_time_entry("−2 d"), Some(TimeEntry::At(-2.0 * DAY)));
+        assert_eq!(parse_time_entry("+30 d"), Some(TimeEntry::By(30.0 * DAY)));
+        assert_eq!(parse_time_entry("- 4 h"), Some(TimeEntry::By(-4.0 * HOUR)));
+        assert_eq!(parse_time_entry("+1 orbit"), Some(TimeEntry::Orbits(1.0)));
+        assert_eq!(
+            parse_time_entry("-2.5 orbits"),
+            Some(TimeEntry::Orbits(-2.5))
+        );
+        assert_eq!(parse_time_entry("+orbit"), Some(TimeEntry::Orbits(1.0)));
+
+        assert_eq!(parse_time_entry("+"), None);
+        assert_eq!(parse_time_entry("3 orbits"), None);
+    }
 }
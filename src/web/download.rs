@@ -0,0 +1,31 @@
+//! Saving files from the page, through the browser's downloads.
+
+use wasm_bindgen::{JsCast, JsValue};
+use web_sys::{Blob, BlobPropertyBag, HtmlAnchorElement, Url, js_sys::Array};
+
+/// Offers `contents` to the user as a file download.
+pub(crate) fn download(file_name: &str, mime_type: &str, contents: &str) -> Result<(), String> {
+    start_download(file_name, mime_type, contents).map_err(|e| {
+        e.as_string()
+            .unwrap_or_else(|| String::from("unknown error"))
+    })
+}
+
+fn start_download(file_name: &str, mime_type: &str, contents: &str) -> Result<(), JsValue> {
+    let document = web_sys::window()
+        .and_then(|window| window.document())
+        .ok_or_else(|| JsValue::from_str("no document"))?;
+
+    let parts = Array::of1(&JsValue::from_str(contents));
+    let options = BlobPropertyBag::new();
+    options.set_type(mime_type);
+    let blob = Blob::new_with_str_sequence_and_options(&parts, &options)?;
+    let url = Url::create_object_url_with_blob(&blob)?;
+
+    let anchor: HtmlAnchorElement = document.create_element("a")?.dyn_into()?;
+    anchor.set_href(&url);
+    anchor.set_download(file_name);
+    anchor.click();
+
+    Url::revoke_object_url(&url)
+}
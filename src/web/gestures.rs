@@ -0,0 +1,95 @@
+//! Keeps trackpad pinches on the canvas from zooming the whole page.
+//!
+//! Chromium and Firefox report a pinch as wheel events with Ctrl held,
+//! which reach the camera control like any other wheel event.
+//! Safari sends its own gesture events instead, whose zoom is collected
+//! here for the camera control to pick up with `take_pinch_zoom`.
+
+use std::sync::Mutex;
+
+use wasm_bindgen::{JsCast, closure::Closure, prelude::wasm_bindgen};
+use web_sys::{AddEventListenerOptions, Event, HtmlCanvasElement, WheelEvent};
+
+#[wasm_bindgen]
+extern "C" {
+    /// Safari's pinch event, which web-sys has no bindings for.
+    #[wasm_bindgen(extends = Event)]
+    type GestureEvent;
+
+    /// How far the fingers spread since the gesture started,
+    /// relative to where they started.
+    #[wasm_bindgen(method, getter)]
+    fn scale(this: &GestureEvent) -> f64;
+}
+
+/// Zoom from gesture events since it was last taken,
+/// as the natural log of how much the camera distance should grow.
+static PENDING_ZOOM: Mutex<f64> = Mutex::new(0.0);
+
+/// The scale of the gesture in progress, as of its last event.
+static GESTURE_SCALE: Mutex<f64> = Mutex::new(1.0);
+
+/// Takes the zoom from gesture events since the last call,
+/// as the natural log of how much the camera distance should grow.
+pub(crate) fn take_pinch_zoom() -> f64 {
+    PENDING_ZOOM
+        .try_lock()
+        .map(|mut zoom| core::mem::take(&mut *zoom))
+        .unwrap_or(0.0)
+}
+
+/// Starts catching pinches on the canvas.
+pub(crate) fn capture_gestures(canvas: &HtmlCanvasElement) {
+    // Passive listeners can't prevent the page from zooming
+    let options = AddEventListenerOptions::new();
+    options.set_passive(false);
+
+    let on_wheel = Closure::<dyn FnMut(WheelEvent)>::new(|event: WheelEvent| {
+        if event.ctrl_key() {
+            event.prevent_default();
+        }
+    });
+
+    let on_gesture_start = Closure::<dyn FnMut(GestureEvent)>::new(|event: GestureEvent| {
+        event.prevent_default();
+        if let Ok(mut scale) = GESTURE_SCALE.try_lock() {
+            *scale = 1.0;
+        }
+    });
+
+    let on_gesture_change = Closure::<dyn FnMut(GestureEvent)>::new(|event: GestureEvent| {
+        event.prevent_default();
+
+        let scale = event.scale();
+        let valid = scale > 0.0;
+        if !valid {
+            return;
+        }
+
+        let Ok(mut last_scale) = GESTURE_SCALE.try_lock() else {
+            return;
+        };
+        if let Ok(mut zoom) = PENDING_ZOOM.try_lock() {
+            // Spreading the fingers zooms in, bringing the camera closer
+            *zoom -= (scale / *last_scale).ln();
+        }
+        *last_scale = scale;
+    });
+
+    for (event_name, callback) in [
+        ("wheel", on_wheel.as_ref()),
+        ("gesturestart", on_gesture_start.as_ref()),
+        ("gesturechange", on_gesture_change.as_ref()),
+    ] {
+        let _ = canvas.add_event_listener_with_callback_and_add_event_listener_options(
+            event_name,
+            callback.unchecked_ref(),
+            &options,
+        );
+    }
+
+    // The listeners stay for as long as the page does
+    on_wheel.forget();
+    on_gesture_start.forget();
+    on_gesture_change.forget();
+}
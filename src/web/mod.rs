@@ -1,2 +1,6 @@
+pub(super) mod download;
+pub(super) mod gestures;
 pub(super) mod heartbeat;
 pub(super) mod panic_handler;
+pub(super) mod query;
+pub(super) mod shortcuts;
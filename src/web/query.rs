@@ -0,0 +1,9 @@
+//! The query part of the page's URL, which startup options are read from.
+
+/// The page's URL query, including the leading `?`,
+/// or an empty string if there isn't one.
+pub(crate) fn search() -> String {
+    web_sys::window()
+        .and_then(|window| window.location().search().ok())
+        .unwrap_or_default()
+}
@@ -0,0 +1,48 @@
+//! Keeps the browser's own zoom shortcuts from zooming the whole page,
+//! and hands them to the UI scale instead.
+//!
+//! The browser doesn't pass these keys on to the canvas as typed text
+//! once their default action is prevented, so they're collected here
+//! for the keybinds to pick up with `take_zoom_keys`.
+
+use std::sync::Mutex;
+
+use wasm_bindgen::{JsCast, closure::Closure};
+use web_sys::{KeyboardEvent, Window};
+
+/// Zoom shortcut characters typed since they were last taken.
+static PENDING_KEYS: Mutex<Vec<char>> = Mutex::new(Vec::new());
+
+/// Takes the zoom shortcut characters typed since the last call.
+pub(crate) fn take_zoom_keys() -> Vec<char> {
+    PENDING_KEYS
+        .try_lock()
+        .map(|mut keys| core::mem::take(&mut *keys))
+        .unwrap_or_default()
+}
+
+/// Starts catching Ctrl/Cmd with `+`, `=`, `-` and `0`.
+pub(crate) fn capture_zoom_keys(window: &Window) {
+    let on_key_down = Closure::<dyn FnMut(KeyboardEvent)>::new(|event: KeyboardEvent| {
+        if !(event.ctrl_key() || event.meta_key()) {
+            return;
+        }
+
+        let key = event.key();
+        let Some(char @ ('+' | '=' | '-' | '0')) = key.chars().next().filter(|_| key.len() == 1)
+        else {
+            return;
+        };
+
+        event.prevent_default();
+        if let Ok(mut keys) = PENDING_KEYS.try_lock() {
+            keys.push(char);
+        }
+    });
+
+    let _ = window
+        .add_event_listener_with_callback("keydown", on_key_down.as_ref().unchecked_ref());
+
+    // The listener stays for as long as the page does
+    on_key_down.forget();
+}